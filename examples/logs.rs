@@ -16,7 +16,7 @@ fn main() {
     // As usual, create the Cursive root
     let mut siv = Cursive::default();
 
-    let cb_sink = siv.cb_sink().clone();
+    let refresh = siv.refresh_sink();
 
     // We want to refresh the page even when no input is given.
     siv.add_global_callback('q', |s| s.quit());
@@ -26,7 +26,7 @@ fn main() {
 
     // Generate data in a separate thread.
     thread::spawn(move || {
-        generate_logs(&tx, cb_sink);
+        generate_logs(&tx, &refresh);
     });
 
     // And sets the view to read from the other end of the channel.
@@ -37,7 +37,7 @@ fn main() {
 
 // We will only simulate log generation here.
 // In real life, this may come from a running task, a separate process, ...
-fn generate_logs(tx: &mpsc::Sender<String>, cb_sink: cursive::CbSink) {
+fn generate_logs(tx: &mpsc::Sender<String>, refresh: &cursive::RefreshSink) {
     let mut i = 1;
     loop {
         let line = format!("Interesting log line {}", i);
@@ -47,7 +47,7 @@ fn generate_logs(tx: &mpsc::Sender<String>, cb_sink: cursive::CbSink) {
         if tx.send(line).is_err() {
             return;
         }
-        cb_sink.send(Box::new(Cursive::noop)).unwrap();
+        refresh.request_refresh();
         thread::sleep(Duration::from_millis(30));
     }
 }
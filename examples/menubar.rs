@@ -87,7 +87,8 @@ fn main() {
 
     // siv.set_autohide_menu(false);
 
-    siv.add_global_callback(Key::Esc, |s| s.select_menubar());
+    siv.menubar().set_reveal_event(Some(Key::Esc));
+    siv.menubar().set_reveal_on_mouse(true);
 
     siv.add_layer(Dialog::text("Hit <Esc> to show the menu!"));
 
@@ -43,7 +43,7 @@ fn phase_1(s: &mut Cursive) {
     s.add_layer(Dialog::around(
         ProgressBar::new()
             // We need to know how many ticks represent a full bar.
-            .range(0, n_max)
+            .range(0, n_max as u64)
             .with_task(move |counter| {
                 // This closure will be called in a separate thread.
                 fake_load(n_max, &counter);
@@ -86,7 +86,9 @@ fn phase_2(s: &mut Cursive) {
     // Let's prepare the progress bars...
     let mut linear = LinearLayout::vertical();
     for c in &counters {
-        linear.add_child(ProgressBar::new().max(n_max).with_value(c.clone()));
+        linear.add_child(
+            ProgressBar::new().max(n_max as u64).with_value(c.clone()),
+        );
     }
 
     s.pop_layer();
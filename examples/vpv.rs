@@ -120,7 +120,7 @@ fn main() {
                     if let Some(len) = len {
                         l.add_child(
                             ProgressBar::new()
-                                .max(len as usize)
+                                .max(len)
                                 .with_value(counter.clone()),
                         );
                     }
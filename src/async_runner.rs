@@ -0,0 +1,410 @@
+//! Run the event loop alongside an async runtime.
+//!
+//! Needs the `async-runner` feature to be enabled.
+//!
+//! This module only relies on the standard `Future` trait, so it works
+//! with any executor (tokio, async-std, ...):
+//!
+//! * [`run`] returns a future driving the event loop. It never blocks the
+//!   executor thread: when nothing happens, it yields to other tasks.
+//! * [`CursiveHandle`] can be cloned and sent to other tasks. Its methods
+//!   return futures, resolving once the UI ran the given closure.
+//! * [`EventStream`] gives the backend input one event at a time, for
+//!   applications handling events themselves.
+//!
+//! `Cursive` cannot be sent to other threads, so the future returned by `run`
+//! must be polled on the thread that created it, for example with tokio's
+//! `LocalSet` or a `block_on` in `main`.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! # use cursive::Cursive;
+//! # use cursive::traits::Identifiable;
+//! # use cursive::views::TextView;
+//! use cursive::async_runner::{self, CursiveHandle};
+//!
+//! let mut siv = Cursive::dummy();
+//! siv.add_layer(TextView::new("Loading...").with_id("status"));
+//! let handle = CursiveHandle::new(&siv);
+//!
+//! // From any task, on any thread:
+//! let task = async move {
+//!     handle
+//!         .call(|s| {
+//!             s.call_on_id("status", |view: &mut TextView| {
+//!                 view.set_content("Done!");
+//!             });
+//!         })
+//!         .await;
+//!     handle.quit().await;
+//! };
+//!
+//! // On the main thread, with the executor of your choice:
+//! // block_on(join(async_runner::run(&mut siv), task));
+//! # let _ = (task, async_runner::run(&mut siv));
+//! ```
+//!
+//! [`run`]: fn.run.html
+//! [`CursiveHandle`]: struct.CursiveHandle.html
+//! [`EventStream`]: struct.EventStream.html
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+use crossbeam_channel::{Receiver, Sender, TrySendError};
+
+use crate::cursive::Waiter;
+use crate::event::Event;
+use crate::{CbFunc, CbSink, Cursive};
+
+/// Returns a future running the event loop until `Cursive::quit` is called.
+///
+/// Each poll performs one step of the event loop. When nothing happens, the
+/// future waits without blocking the executor: a background thread wakes it
+/// up once there is something to do, like `Cursive::step` would.
+pub fn run(siv: &mut Cursive) -> Run<'_> {
+    Run {
+        siv,
+        started: false,
+        ticker: Ticker::new(),
+    }
+}
+
+/// Future returned by [`run`](fn.run.html).
+pub struct Run<'a> {
+    siv: &'a mut Cursive,
+    started: bool,
+    ticker: Ticker,
+}
+
+impl<'a> Future for Run<'a> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = &mut *self;
+
+        if !this.started {
+            this.started = true;
+            this.siv.set_running(true);
+            this.siv.refresh();
+        }
+
+        if !this.siv.is_running() {
+            this.siv.run_on_exit();
+            return Poll::Ready(());
+        }
+
+        if this.siv.try_step() {
+            // Something happened: there may be more to do right away, but
+            // let other tasks run first.
+            cx.waker().wake_by_ref();
+        } else {
+            this.ticker.wake_when_ready(this.siv.waiter(None), cx.waker());
+        }
+
+        if this.siv.is_running() {
+            Poll::Pending
+        } else {
+            this.siv.run_on_exit();
+            Poll::Ready(())
+        }
+    }
+}
+
+/// Handle to a running `Cursive`, usable from any task or thread.
+///
+/// Closures given to a handle run on the UI thread, during the event loop.
+#[derive(Clone)]
+pub struct CursiveHandle {
+    sink: CbSink,
+}
+
+impl CursiveHandle {
+    /// Creates a handle sending closures to `siv`.
+    pub fn new(siv: &Cursive) -> Self {
+        CursiveHandle {
+            sink: siv.cb_sink().clone(),
+        }
+    }
+
+    /// Runs `f` with the `Cursive` root, and returns its result.
+    ///
+    /// The future resolves to `None` if `f` is dropped without running.
+    /// This happens when the `Cursive` root is dropped first: closures still
+    /// queued are then dropped along with the last handle.
+    pub fn call<F, R>(&self, f: F) -> Call<R>
+    where
+        F: FnOnce(&mut Cursive) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let shared = Arc::new(Mutex::new(CallState {
+            result: None,
+            done: false,
+            waker: None,
+        }));
+
+        let guard = CallGuard(Arc::clone(&shared));
+        let cb: Box<dyn CbFunc> = Box::new(move |s: &mut Cursive| {
+            let result = f(s);
+            guard.finish(Some(result));
+        });
+
+        let mut call = Call {
+            shared,
+            pending: Some((self.sink.clone(), cb)),
+        };
+        call.try_send();
+        call
+    }
+
+    /// Stops the event loop.
+    pub fn quit(&self) -> Call<()> {
+        self.call(Cursive::quit)
+    }
+}
+
+/// Future returned by [`CursiveHandle::call`].
+///
+/// [`CursiveHandle::call`]: struct.CursiveHandle.html#method.call
+pub struct Call<R> {
+    shared: Arc<Mutex<CallState<R>>>,
+    // The callback, until it fits in the channel. The sink is dropped along
+    // with it, so queued callbacks don't outlive the root.
+    pending: Option<(CbSink, Box<dyn CbFunc>)>,
+}
+
+impl<R> Call<R> {
+    // Sends the pending callback, if the channel has room for it.
+    //
+    // The task calling this may share its thread with the event loop, so
+    // it must never block on a full channel: it retries on the next poll.
+    // If the root is gone, the callback and its guard are dropped right
+    // away, ending the call.
+    fn try_send(&mut self) {
+        if let Some((sink, cb)) = self.pending.take() {
+            if let Err(TrySendError::Full(cb)) = sink.try_send(cb) {
+                self.pending = Some((sink, cb));
+            }
+        }
+    }
+}
+
+struct CallState<R> {
+    result: Option<R>,
+    done: bool,
+    waker: Option<Waker>,
+}
+
+// Marks the call as done when the callback runs, or when it is dropped
+// without running.
+struct CallGuard<R>(Arc<Mutex<CallState<R>>>);
+
+impl<R> CallGuard<R> {
+    fn finish(&self, result: Option<R>) {
+        let mut state = self.0.lock().unwrap();
+        state.result = result;
+        state.done = true;
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<R> Drop for CallGuard<R> {
+    fn drop(&mut self) {
+        let done = self.0.lock().unwrap().done;
+        if !done {
+            self.finish(None);
+        }
+    }
+}
+
+impl<R> Future for Call<R> {
+    type Output = Option<R>;
+
+    fn poll(
+        mut self: Pin<&mut Self>, cx: &mut Context<'_>,
+    ) -> Poll<Option<R>> {
+        let this = &mut *self;
+        this.try_send();
+
+        let mut state = this.shared.lock().unwrap();
+        if state.done {
+            Poll::Ready(state.result.take())
+        } else {
+            if this.pending.is_some() {
+                // The channel is full: try again once other tasks had a
+                // chance to run, including the event loop.
+                cx.waker().wake_by_ref();
+            }
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+/// Backend input, one event at a time.
+///
+/// Events read from this stream are not given to the views: call
+/// `Cursive::on_event` as needed, followed by `Cursive::refresh`. Closures
+/// sent through a `CursiveHandle` are run while waiting for input.
+pub struct EventStream<'a> {
+    siv: &'a mut Cursive,
+    ticker: Ticker,
+}
+
+impl<'a> EventStream<'a> {
+    /// Creates a stream of events from the backend of `siv`.
+    pub fn new(siv: &'a mut Cursive) -> Self {
+        EventStream {
+            siv,
+            ticker: Ticker::new(),
+        }
+    }
+
+    /// Returns the `Cursive` root.
+    pub fn cursive(&mut self) -> &mut Cursive {
+        self.siv
+    }
+
+    /// Waits for the next event.
+    ///
+    /// Resolves to `None` once `Cursive::quit` was called.
+    pub fn next_event(&mut self) -> NextEvent<'_, 'a> {
+        NextEvent { stream: self }
+    }
+}
+
+/// Future returned by [`EventStream::next_event`].
+///
+/// [`EventStream::next_event`]: struct.EventStream.html#method.next_event
+pub struct NextEvent<'b, 'a> {
+    stream: &'b mut EventStream<'a>,
+}
+
+impl<'b, 'a> Future for NextEvent<'b, 'a> {
+    type Output = Option<Event>;
+
+    fn poll(
+        mut self: Pin<&mut Self>, cx: &mut Context<'_>,
+    ) -> Poll<Option<Event>> {
+        let stream = &mut *self.stream;
+
+        if !stream.siv.is_running() {
+            return Poll::Ready(None);
+        }
+
+        if let Some(event) = stream.siv.poll_input() {
+            return Poll::Ready(Some(event));
+        }
+
+        if stream.siv.run_callbacks() {
+            stream.siv.refresh();
+        }
+
+        if !stream.siv.is_running() {
+            return Poll::Ready(None);
+        }
+
+        stream
+            .ticker
+            .wake_when_ready(stream.siv.waiter(None), cx.waker());
+        Poll::Pending
+    }
+}
+
+// Wakes a task once the event loop may have something to do, from a
+// background thread. The thread stops with the ticker.
+struct Ticker {
+    requests: Option<Sender<(Waiter, Waker)>>,
+}
+
+impl Ticker {
+    fn new() -> Self {
+        Ticker { requests: None }
+    }
+
+    fn wake_when_ready(&mut self, waiter: Waiter, waker: &Waker) {
+        let requests = self.requests.get_or_insert_with(|| {
+            let (sender, receiver) = crossbeam_channel::unbounded();
+            thread::spawn(move || wait_for_requests(&receiver));
+            sender
+        });
+        let _ = requests.send((waiter, waker.clone()));
+    }
+}
+
+fn wait_for_requests(requests: &Receiver<(Waiter, Waker)>) {
+    let mut next = requests.recv().ok();
+    while let Some((waiter, waker)) = next {
+        // A newer request, from a later poll, replaces this one.
+        if !waiter.wait_or(Some(requests)) {
+            waker.wake();
+        }
+        next = requests.recv().ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::task::{RawWaker, RawWakerVTable};
+
+    fn noop_waker() -> Waker {
+        fn clone(_: *const ()) -> RawWaker {
+            RawWaker::new(std::ptr::null(), &VTABLE)
+        }
+        fn noop(_: *const ()) {}
+        static VTABLE: RawWakerVTable =
+            RawWakerVTable::new(clone, noop, noop, noop);
+
+        unsafe { Waker::from_raw(clone(std::ptr::null())) }
+    }
+
+    #[test]
+    fn call_and_quit() {
+        let mut siv = Cursive::dummy();
+        let handle = CursiveHandle::new(&siv);
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        let mut call = handle.call(|s| s.is_running());
+        assert!(Pin::new(&mut call).poll(&mut cx).is_pending());
+        assert!(siv.run_callbacks());
+        assert_eq!(Pin::new(&mut call).poll(&mut cx), Poll::Ready(Some(true)));
+
+        // The dummy backend asks to exit right away.
+        let mut quit = handle.quit();
+        assert_eq!(Pin::new(&mut run(&mut siv)).poll(&mut cx), Poll::Ready(()));
+        assert!(Pin::new(&mut quit).poll(&mut cx).is_pending());
+
+        // Once the root is gone, queued calls never run.
+        drop(siv);
+        drop(handle);
+        assert_eq!(Pin::new(&mut quit).poll(&mut cx), Poll::Ready(None));
+    }
+
+    #[test]
+    fn call_on_full_channel() {
+        let mut siv = Cursive::dummy();
+        assert!(siv.set_cb_sink_capacity(Some(1)));
+        let handle = CursiveHandle::new(&siv);
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        // The second call waits for room instead of blocking this thread.
+        let mut first = handle.call(|_| 1);
+        let mut second = handle.call(|_| 2);
+        assert!(Pin::new(&mut second).poll(&mut cx).is_pending());
+
+        assert!(siv.run_callbacks());
+        assert_eq!(Pin::new(&mut first).poll(&mut cx), Poll::Ready(Some(1)));
+        assert!(Pin::new(&mut second).poll(&mut cx).is_pending());
+        assert!(siv.run_callbacks());
+        assert_eq!(Pin::new(&mut second).poll(&mut cx), Poll::Ready(Some(2)));
+    }
+}
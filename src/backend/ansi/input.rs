@@ -0,0 +1,342 @@
+//! Parsing of the input sent by terminals.
+
+// Termion does its own parsing, and only needs the extended keys.
+#![allow(dead_code)]
+
+use super::{char_event, key_event};
+use crate::event::{Event, Key, MouseButton, MouseEvent};
+use crate::vec::Vec2;
+
+/// Enables mouse reports with button motion, in the SGR format, bracketed
+/// paste, and extended key reporting.
+pub const ENABLE_INPUT: &str = "\x1B[?1002h\x1B[?1006h\x1B[?2004h\x1B[>1u";
+pub const DISABLE_INPUT: &str = "\x1B[<u\x1B[?2004l\x1B[?1006l\x1B[?1002l";
+
+/// Asks the terminal to report keys with the kitty keyboard protocol.
+///
+/// Keys are then sent as `CSI code ; modifiers u`, so combinations like
+/// `Ctrl-Shift-A` or `Ctrl-Enter` can be told apart. Terminals without
+/// this protocol ignore it.
+pub const ENABLE_KEYBOARD: &str = "\x1B[>1u";
+pub const DISABLE_KEYBOARD: &str = "\x1B[<u";
+
+const PASTE_START: &[u8] = b"\x1B[200~";
+const PASTE_END: &[u8] = b"\x1B[201~";
+
+/// Turns the bytes sent by a terminal into events.
+#[derive(Default)]
+pub struct Parser {
+    // Start of a sequence cut between two reads.
+    pending: Vec<u8>,
+    // Text received since the start of a bracketed paste.
+    paste: Option<Vec<u8>>,
+}
+
+impl Parser {
+    pub fn new() -> Self {
+        Parser::default()
+    }
+
+    /// Parses the given input, and appends the events to `events`.
+    pub fn parse(&mut self, input: &[u8], events: &mut Vec<Event>) {
+        self.pending.extend_from_slice(input);
+        let bytes = std::mem::take(&mut self.pending);
+
+        let mut i = 0;
+        while i < bytes.len() {
+            let rest = &bytes[i..];
+
+            if let Some(ref mut paste) = self.paste {
+                match find(rest, PASTE_END) {
+                    Some(end) => {
+                        paste.extend_from_slice(&rest[..end]);
+                        let text = String::from_utf8_lossy(paste).into_owned();
+                        events.push(Event::Paste(text));
+                        self.paste = None;
+                        i += end + PASTE_END.len();
+                        continue;
+                    }
+                    None => {
+                        // Keeps what may be the start of the end marker.
+                        let keep = rest.len().min(PASTE_END.len() - 1);
+                        paste.extend_from_slice(&rest[..rest.len() - keep]);
+                        self.pending = rest[rest.len() - keep..].to_vec();
+                        return;
+                    }
+                }
+            }
+
+            if rest.starts_with(PASTE_START) {
+                self.paste = Some(Vec::new());
+                i += PASTE_START.len();
+                continue;
+            }
+
+            match next_event(rest) {
+                Some((event, len)) => {
+                    events.push(event);
+                    i += len;
+                }
+                None => {
+                    self.pending = rest.to_vec();
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Parses a single escape sequence.
+///
+/// Returns `Event::Unknown` if it is not supported, or is not complete.
+pub fn parse_sequence(bytes: &[u8]) -> Event {
+    match next_event(bytes) {
+        Some((event, len)) if len == bytes.len() => event,
+        _ => Event::Unknown(bytes.to_vec()),
+    }
+}
+
+fn find(bytes: &[u8], needle: &[u8]) -> Option<usize> {
+    bytes.windows(needle.len()).position(|w| w == needle)
+}
+
+// Parses the first event, and returns it with the number of bytes used.
+//
+// Returns `None` if the sequence is not complete yet.
+fn next_event(bytes: &[u8]) -> Option<(Event, usize)> {
+    let event = match bytes[0] {
+        0x1B => return escape(bytes),
+        b'\r' | b'\n' => Event::Key(Key::Enter),
+        b'\t' => Event::Key(Key::Tab),
+        0x7F | 0x08 => Event::Key(Key::Backspace),
+        0x03 => Event::Exit,
+        b @ 1..=26 => Event::CtrlChar((b'a' + b - 1) as char),
+        b @ 0..=31 => Event::Unknown(vec![b]),
+        _ => {
+            let (c, len) = decode_char(bytes)?;
+            return Some((Event::Char(c), len));
+        }
+    };
+    Some((event, 1))
+}
+
+// Decodes the first UTF-8 character.
+fn decode_char(bytes: &[u8]) -> Option<(char, usize)> {
+    let len = match bytes[0] {
+        0xF0..=0xFF => 4,
+        0xE0..=0xEF => 3,
+        0xC0..=0xDF => 2,
+        _ => 1,
+    };
+    if bytes.len() < len {
+        return None;
+    }
+    let c = std::str::from_utf8(&bytes[..len])
+        .ok()
+        .and_then(|s| s.chars().next())
+        .unwrap_or(std::char::REPLACEMENT_CHARACTER);
+    Some((c, len))
+}
+
+fn escape(bytes: &[u8]) -> Option<(Event, usize)> {
+    match bytes.get(1) {
+        // A lone escape is the key itself.
+        None | Some(0x1B) => Some((Event::Key(Key::Esc), 1)),
+        Some(b'[') => csi(bytes),
+        Some(b'O') => {
+            let key = match *bytes.get(2)? {
+                b'P' => Key::F1,
+                b'Q' => Key::F2,
+                b'R' => Key::F3,
+                b'S' => Key::F4,
+                b'A' => Key::Up,
+                b'B' => Key::Down,
+                b'C' => Key::Right,
+                b'D' => Key::Left,
+                b'H' => Key::Home,
+                b'F' => Key::End,
+                b => return Some((Event::Unknown(vec![0x1B, b'O', b]), 3)),
+            };
+            Some((Event::Key(key), 3))
+        }
+        Some(_) => {
+            let (c, len) = decode_char(&bytes[1..])?;
+            Some((Event::AltChar(c), len + 1))
+        }
+    }
+}
+
+// Parses a control sequence: `ESC [`, parameters, and a final byte.
+fn csi(bytes: &[u8]) -> Option<(Event, usize)> {
+    let is_final = |b: &u8| (0x40..=0x7E).contains(b);
+    let end = 2 + bytes[2..].iter().position(is_final)?;
+    let len = end + 1;
+    let params = std::str::from_utf8(&bytes[2..end]).unwrap_or("");
+    let unknown = || Event::Unknown(bytes[..len].to_vec());
+
+    if let Some(params) = params.strip_prefix('<') {
+        let event = mouse(params, bytes[end]).unwrap_or_else(unknown);
+        return Some((event, len));
+    }
+
+    // Extended key reports may add sub-parameters, after a `:`.
+    let numbers: Vec<u32> = params
+        .split(';')
+        .map(|n| n.split(':').next().unwrap_or("").parse().unwrap_or(1))
+        .collect();
+    // Modifiers are sent as `1 + bits`, with shift, alt and ctrl bits.
+    let modifiers = || {
+        let bits = numbers.get(1).map_or(0, |&m| m.saturating_sub(1));
+        (bits & 4 != 0, bits & 2 != 0, bits & 1 != 0)
+    };
+    let modified = |key: Key| {
+        let (ctrl, alt, shift) = modifiers();
+        key_event(key, ctrl, alt, shift)
+    };
+    // A key given by its unicode code.
+    let code = |code: u32| {
+        let (ctrl, alt, shift) = modifiers();
+        let key = match code {
+            13 => Key::Enter,
+            9 => Key::Tab,
+            127 | 8 => Key::Backspace,
+            27 => Key::Esc,
+            code => {
+                return match std::char::from_u32(code) {
+                    // Like the raw byte, Ctrl-C quits.
+                    Some('c') if ctrl && !alt && !shift => Event::Exit,
+                    Some(c) if !c.is_control() => {
+                        char_event(c, ctrl, alt, shift)
+                    }
+                    _ => unknown(),
+                };
+            }
+        };
+        key_event(key, ctrl, alt, shift)
+    };
+
+    let event = match bytes[end] {
+        b'A' => modified(Key::Up),
+        b'B' => modified(Key::Down),
+        b'C' => modified(Key::Right),
+        b'D' => modified(Key::Left),
+        b'H' => modified(Key::Home),
+        b'F' => modified(Key::End),
+        b'P' => modified(Key::F1),
+        b'Q' => modified(Key::F2),
+        b'R' => modified(Key::F3),
+        b'S' => modified(Key::F4),
+        b'Z' => Event::Shift(Key::Tab),
+        // The kitty keyboard protocol.
+        b'u' => code(numbers[0]),
+        b'~' => match numbers[0] {
+            1 | 7 => modified(Key::Home),
+            2 => modified(Key::Ins),
+            3 => modified(Key::Del),
+            4 | 8 => modified(Key::End),
+            5 => modified(Key::PageUp),
+            6 => modified(Key::PageDown),
+            n @ 11..=15 => modified(Key::from_f((n - 10) as u8)),
+            n @ 17..=21 => modified(Key::from_f((n - 11) as u8)),
+            n @ 23..=24 => modified(Key::from_f((n - 12) as u8)),
+            // Sent by xterm with `modifyOtherKeys`.
+            27 if numbers.len() == 3 => code(numbers[2]),
+            _ => unknown(),
+        },
+        _ => unknown(),
+    };
+    Some((event, len))
+}
+
+// Parses a SGR mouse report: `button;x;y`, followed by `M` or `m`.
+fn mouse(params: &str, last: u8) -> Option<Event> {
+    let mut numbers = params.split(';').map(|n| n.parse::<usize>().ok());
+    let code = numbers.next()??;
+    let x = numbers.next()??;
+    let y = numbers.next()??;
+
+    let button = match code & 3 {
+        0 => MouseButton::Left,
+        1 => MouseButton::Middle,
+        _ => MouseButton::Right,
+    };
+    let event = match (code & !28, last) {
+        (64, _) => MouseEvent::WheelUp,
+        (65, _) => MouseEvent::WheelDown,
+        (c, b'M') if c & 32 != 0 && c & 3 != 3 => MouseEvent::Hold(button),
+        (c, b'M') if c & 32 == 0 => MouseEvent::Press(button),
+        (c, b'm') if c & 32 == 0 => MouseEvent::Release(button),
+        _ => return None,
+    };
+
+    Some(Event::Mouse {
+        offset: Vec2::zero(),
+        position: Vec2::new(x.saturating_sub(1), y.saturating_sub(1)),
+        event,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_input() {
+        let mut parser = Parser::new();
+        let mut events = Vec::new();
+        parser.parse(b"a\xC3\xA9\x1B[A\x1B[1;5C\x1B[3~\x01", &mut events);
+        // Sequences can be cut between two reads.
+        parser.parse(b"\x1B[<0;3;", &mut events);
+        parser.parse(b"2M\x1B[200~x\ny\x1B[20", &mut events);
+        parser.parse(b"1~\x1B[97;6u\x1B[13;5u\x1B[27;3;97~", &mut events);
+        parser.parse(b"\x1B[99;5u\x1B[27u\x1B", &mut events);
+
+        let press = Event::Mouse {
+            offset: Vec2::zero(),
+            position: Vec2::new(2, 1),
+            event: MouseEvent::Press(MouseButton::Left),
+        };
+        assert_eq!(
+            events,
+            vec![
+                Event::Char('a'),
+                Event::Char('é'),
+                Event::Key(Key::Up),
+                Event::Ctrl(Key::Right),
+                Event::Key(Key::Del),
+                Event::CtrlChar('a'),
+                press,
+                Event::Paste("x\ny".to_string()),
+                Event::CtrlShiftChar('a'),
+                Event::Ctrl(Key::Enter),
+                Event::AltChar('a'),
+                Event::Exit,
+                Event::Key(Key::Esc),
+                Event::Key(Key::Esc),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_mouse_moves() {
+        let hold = |button, x, y| Event::Mouse {
+            offset: Vec2::zero(),
+            position: Vec2::new(x, y),
+            event: MouseEvent::Hold(button),
+        };
+        assert_eq!(
+            parse_sequence(b"\x1B[<33;4;2M"),
+            hold(MouseButton::Middle, 3, 1)
+        );
+        // With Shift.
+        assert_eq!(
+            parse_sequence(b"\x1B[<38;1;5M"),
+            hold(MouseButton::Right, 0, 4)
+        );
+        // Moves without a button are not drags.
+        assert_eq!(
+            parse_sequence(b"\x1B[<35;2;2M"),
+            Event::Unknown(b"\x1B[<35;2;2M".to_vec())
+        );
+    }
+}
@@ -0,0 +1,84 @@
+//! Escape sequences, for the backends talking to a terminal themselves.
+
+#![cfg(any(
+    feature = "web-backend",
+    feature = "telnet-backend",
+    feature = "termion",
+    all(windows, feature = "wincon-backend")
+))]
+
+use crate::event::{Event, Key};
+use crate::theme::{Color, ColorDepth, Effect};
+
+// Input is only parsed by the backends reading from a terminal.
+#[cfg(any(feature = "telnet-backend", feature = "termion"))]
+mod input;
+mod screen;
+
+#[cfg(any(feature = "telnet-backend", feature = "termion"))]
+#[allow(unused_imports)]
+pub use self::input::{
+    parse_sequence, Parser, DISABLE_INPUT, DISABLE_KEYBOARD, ENABLE_INPUT,
+    ENABLE_KEYBOARD,
+};
+pub use self::screen::Screen;
+
+/// Returns the SGR parameters for the closest color `depth` can display.
+pub fn color_code(
+    color: Color, background: bool, depth: ColorDepth,
+) -> String {
+    let base = if background { 40 } else { 30 };
+    match color.quantize(depth) {
+        Color::TerminalDefault => (base + 9).to_string(),
+        Color::Dark(color) => (base + color as u8).to_string(),
+        Color::Light(color) => (base + 60 + color as u8).to_string(),
+        Color::Rgb(r, g, b) if depth == ColorDepth::TrueColor => {
+            format!("{};2;{};{};{}", base + 8, r, g, b)
+        }
+        color => format!("{};5;{}", base + 8, color.to_256colors().unwrap()),
+    }
+}
+
+/// Returns the sequence enabling or disabling the given effect.
+pub fn effect_code(effect: Effect, enabled: bool) -> &'static str {
+    match (effect, enabled) {
+        (Effect::Simple, _) => "",
+        (Effect::Reverse, true) => "\x1B[7m",
+        (Effect::Reverse, false) => "\x1B[27m",
+        (Effect::Bold, true) => "\x1B[1m",
+        (Effect::Bold, false) => "\x1B[22m",
+        (Effect::Italic, true) => "\x1B[3m",
+        (Effect::Italic, false) => "\x1B[23m",
+        (Effect::Underline, true) => "\x1B[4m",
+        (Effect::Underline, false) => "\x1B[24m",
+    }
+}
+
+/// Applies the modifiers to a key.
+pub fn key_event(key: Key, ctrl: bool, alt: bool, shift: bool) -> Event {
+    match (ctrl, alt, shift) {
+        (false, false, false) => Event::Key(key),
+        (false, false, true) => Event::Shift(key),
+        (false, true, false) => Event::Alt(key),
+        (false, true, true) => Event::AltShift(key),
+        (true, false, false) => Event::Ctrl(key),
+        (true, false, true) => Event::CtrlShift(key),
+        (true, true, _) => Event::CtrlAlt(key),
+    }
+}
+
+/// Applies the modifiers to a character.
+///
+/// `c` is the character without Shift, like `a` for `Ctrl-Shift-A`.
+pub fn char_event(c: char, ctrl: bool, alt: bool, shift: bool) -> Event {
+    let upper = || c.to_uppercase().next().unwrap_or(c);
+    match (ctrl, alt, shift) {
+        (false, false, false) => Event::Char(c),
+        (false, false, true) => Event::Char(upper()),
+        (false, true, false) => Event::AltChar(c),
+        (false, true, true) => Event::AltChar(upper()),
+        (true, false, false) => Event::CtrlChar(c),
+        (true, false, true) => Event::CtrlShiftChar(c),
+        (true, true, _) => Event::CtrlAltChar(c),
+    }
+}
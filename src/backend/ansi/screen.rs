@@ -0,0 +1,189 @@
+//! Double-buffered output, only writing the cells that changed.
+
+use enumset::EnumSet;
+
+use super::{color_code, effect_code};
+use crate::screen_buffer::{ScreenBuffer, ScreenCell};
+use crate::theme::{Color, ColorDepth, ColorPair, Effect};
+use crate::utils::width::{grapheme_width, is_ambiguous};
+use crate::vec::Vec2;
+
+type Style = (ColorPair, EnumSet<Effect>);
+
+/// Content of the terminal, and what views drew since the last frame.
+///
+/// Backends print into it, and write the result of `render` on refresh:
+/// only the cells that changed are sent, which matters on slow links.
+pub struct Screen {
+    // What the views drew.
+    next: ScreenBuffer,
+    // What the terminal shows, if known.
+    shown: Option<ScreenBuffer>,
+    colors: ColorPair,
+    effects: EnumSet<Effect>,
+    depth: ColorDepth,
+}
+
+impl Screen {
+    /// Creates a blank screen, for a terminal with the given color depth.
+    pub fn new(size: Vec2, depth: ColorDepth) -> Self {
+        Screen {
+            next: ScreenBuffer::new(size, terminal_default()),
+            shown: None,
+            colors: terminal_default(),
+            effects: EnumSet::new(),
+            depth,
+        }
+    }
+
+    /// Changes the size of the screen.
+    ///
+    /// The whole screen is sent on the next frame.
+    pub fn resize(&mut self, size: Vec2) {
+        if self.next.size() != size {
+            self.next = ScreenBuffer::new(size, self.next.background());
+            self.shown = None;
+        }
+    }
+
+    /// Fills the screen with the given color.
+    pub fn clear(&mut self, color: Color) {
+        let colors = ColorPair {
+            front: color,
+            back: color,
+        };
+        self.next = ScreenBuffer::new(self.next.size(), colors);
+    }
+
+    /// Prints `text` with the current style.
+    pub fn print(&mut self, pos: Vec2, text: &str) {
+        self.next.print(pos, text, self.colors, self.effects);
+    }
+
+    /// Changes the colors used to print, and returns the previous ones.
+    pub fn set_colors(&mut self, colors: ColorPair) -> ColorPair {
+        std::mem::replace(&mut self.colors, colors)
+    }
+
+    /// Enables an effect for the next prints.
+    pub fn set_effect(&mut self, effect: Effect) {
+        self.effects |= effect;
+    }
+
+    /// Disables an effect for the next prints.
+    pub fn unset_effect(&mut self, effect: Effect) {
+        self.effects -= effect;
+    }
+
+    /// Forgets what the terminal shows, so the next frame is sent whole.
+    ///
+    /// Used when something else wrote to the terminal.
+    #[cfg_attr(
+        not(any(feature = "termion", all(windows, feature = "wincon-backend"))),
+        allow(dead_code)
+    )]
+    pub fn invalidate(&mut self) {
+        self.shown = None;
+    }
+
+    /// Returns the sequence updating the terminal to the new content.
+    pub fn render(&mut self) -> String {
+        let size = self.next.size();
+        let mut out = String::new();
+
+        let blank;
+        let (shown, mut pen) = match self.shown {
+            Some(ref shown) if shown.size() == size => (shown, None),
+            _ => {
+                out.push_str("\x1B[0m\x1B[2J");
+                blank = ScreenBuffer::new(size, terminal_default());
+                (&blank, Some((terminal_default(), EnumSet::new())))
+            }
+        };
+
+        // Where the terminal cursor is, if known.
+        let mut cursor = None;
+        for y in 0..size.y {
+            for x in 0..size.x {
+                let pos = Vec2::new(x, y);
+                let cell = self.next.cell(pos).unwrap();
+                // Empty cells are covered by a wide character.
+                if shown.cell(pos) == Some(cell) || cell.text.is_empty() {
+                    continue;
+                }
+
+                let style = (cell.colors, cell.effects);
+                if pen != Some(style) {
+                    self.write_style(&mut out, pen, style);
+                    pen = Some(style);
+                }
+                match cursor {
+                    Some(c) if c == pos => (),
+                    Some(Vec2 { x: cx, y: cy }) if cy == y && cx < x => {
+                        out.push_str(&format!("\x1B[{}C", x - cx));
+                    }
+                    _ => out.push_str(&format!("\x1B[{};{}H", y + 1, x + 1)),
+                }
+                cursor = next_cursor(pos, cell);
+                out.push_str(&cell.text);
+            }
+        }
+
+        self.shown = Some(self.next.clone());
+        out
+    }
+
+    fn write_style(&self, out: &mut String, pen: Option<Style>, style: Style) {
+        if pen.map(|(_, effects)| effects) != Some(style.1) {
+            out.push_str("\x1B[0m");
+            for effect in style.1.iter() {
+                out.push_str(effect_code(effect, true));
+            }
+        }
+        let (colors, _) = style;
+        out.push_str(&format!(
+            "\x1B[{};{}m",
+            color_code(colors.front, false, self.depth),
+            color_code(colors.back, true, self.depth)
+        ));
+    }
+}
+
+// Terminals may not agree on the width of some graphemes: the cursor is
+// then moved explicitly.
+fn next_cursor(pos: Vec2, cell: &ScreenCell) -> Option<Vec2> {
+    if is_ambiguous(&cell.text) {
+        None
+    } else {
+        Some(pos + (grapheme_width(&cell.text), 0))
+    }
+}
+
+fn terminal_default() -> ColorPair {
+    ColorPair {
+        front: Color::TerminalDefault,
+        back: Color::TerminalDefault,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_changes() {
+        let mut screen = Screen::new(Vec2::new(5, 2), ColorDepth::TrueColor);
+        screen.print(Vec2::new(0, 0), "Hello");
+        screen.print(Vec2::new(0, 1), "World");
+        let first = screen.render();
+        assert!(first.starts_with("\x1B[0m\x1B[2J"));
+        assert!(first.contains("\x1B[1;1HHello\x1B[2;1HWorld"));
+
+        screen.print(Vec2::new(0, 1), "Wired");
+        assert_eq!(screen.render(), "\x1B[0m\x1B[39;49m\x1B[2;2Hi\x1B[1Ce");
+        assert_eq!(screen.render(), "");
+
+        screen.resize(Vec2::new(4, 1));
+        assert!(screen.render().starts_with("\x1B[0m\x1B[2J"));
+    }
+}
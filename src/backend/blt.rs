@@ -60,7 +60,9 @@ impl Backend {
         terminal::read_event().map(|ev| {
             match ev {
                 BltEvent::Close => Event::Exit,
-                BltEvent::Resize { .. } => Event::WindowResize,
+                BltEvent::Resize { width, height } => Event::WindowResize(
+                    Vec2::new(width as usize, height as usize),
+                ),
                 // TODO: mouse support
                 BltEvent::MouseMove { x, y } => {
                     self.mouse_position = Vec2::new(x as usize, y as usize);
@@ -241,6 +243,10 @@ impl backend::Backend for Backend {
         "bear-lib-terminal"
     }
 
+    fn uses_terminal(&self) -> bool {
+        false
+    }
+
     fn finish(&mut self) {
         terminal::close();
     }
@@ -1,11 +1,22 @@
 //! Backend using the pure-rust crossplatform crossterm library.
 //!
 //! Requires the `crossterm-backend` feature.
+//!
+//! It works on Windows too, without any C dependency. Crossterm does not
+//! report terminal resizes: the size is checked on each input poll.
+//!
+//! True colors are used if the terminal supports them, according to
+//! `ColorDepth::detect`; other terminals get the closest colors they have.
+//! Use `Backend::init_with_color_depth` when the guess is wrong.
+//!
+//! On unix, pasted text is sent as a single `Event::Paste`. Crossterm does
+//! not know the sequences around it, and reports each of them as an unknown
+//! event: the paste is the text in between.
 
 #![cfg(feature = "crossterm")]
 
 use crate::vec::Vec2;
-use crate::{backend, theme};
+use crate::{backend, clipboard, theme};
 use crossterm::{
     cursor, input, terminal, AlternateScreen, AsyncReader, Attribute,
     ClearType, Color, Colored, InputEvent as CInputEvent,
@@ -16,14 +27,23 @@ use crossterm::{
 use crate::event::{Event, Key, MouseButton, MouseEvent};
 use std::cell::{Cell, RefCell};
 use std::io::{self, Stdout, Write};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// How long to wait for the rest of a paste, once the text stops coming.
+const PASTE_TIMEOUT: Duration = Duration::from_millis(100);
 
 /// Backend using crossterm
 pub struct Backend {
     current_style: Cell<theme::ColorPair>,
+    color_depth: theme::ColorDepth,
     last_button: Option<MouseButton>,
+    // Crossterm does not report resizes: poll the size instead.
+    last_size: Vec2,
     // reader to read user input async.
     async_reader: AsyncReader,
-    _alternate_screen: AlternateScreen,
+    // Dropped while suspended, to leave the alternate screen and raw mode.
+    alternate_screen: Option<AlternateScreen>,
     stdout: RefCell<Stdout>,
     cursor: TerminalCursor,
     terminal: Terminal,
@@ -31,32 +51,88 @@ pub struct Backend {
 
 impl Backend {
     /// Creates a new crossterm backend.
+    ///
+    /// The color depth is given by `ColorDepth::detect`.
     pub fn init() -> std::io::Result<Box<dyn backend::Backend>>
     where
         Self: Sized,
     {
-        let _alternate_screen = AlternateScreen::to_alternate(true)?;
+        Self::init_with_color_depth(theme::ColorDepth::detect())
+    }
+
+    /// Creates a new crossterm backend, using the given color depth.
+    pub fn init_with_color_depth(
+        color_depth: theme::ColorDepth,
+    ) -> std::io::Result<Box<dyn backend::Backend>> {
+        let alternate_screen = AlternateScreen::to_alternate(true)?;
 
         let input = input();
         let async_reader = input.read_async();
-        input.enable_mouse_mode().unwrap();
+        input.enable_mouse_mode()?;
 
         cursor().hide()?;
 
-        Ok(Box::new(Backend {
+        let terminal = terminal();
+        let last_size = Vec2::from(terminal.terminal_size()) + (1, 1);
+
+        let backend = Backend {
             current_style: Cell::new(theme::ColorPair::from_256colors(0, 0)),
+            color_depth,
             last_button: None,
+            last_size,
             async_reader,
-            _alternate_screen,
+            alternate_screen: Some(alternate_screen),
             stdout: RefCell::new(io::stdout()),
-            terminal: terminal(),
+            terminal,
             cursor: cursor(),
-        }))
+        };
+        backend.set_bracketed_paste(true);
+
+        Ok(Box::new(backend))
+    }
+
+    // Bracketed paste sequences are only parsed on unix.
+    fn set_bracketed_paste(&self, enabled: bool) {
+        if cfg!(unix) {
+            self.write(if enabled { "\x1B[?2004h" } else { "\x1B[?2004l" });
+            let _ = self.stdout.borrow_mut().flush();
+        }
+    }
+
+    // Reads pasted text, until the unknown event ending the paste.
+    fn read_paste(&mut self) -> Event {
+        let mut text = String::new();
+        let mut last_input = Instant::now();
+
+        // Don't wait forever if the end never comes.
+        while last_input.elapsed() < PASTE_TIMEOUT {
+            let event = match self.async_reader.next() {
+                Some(event) => event,
+                None => {
+                    thread::sleep(Duration::from_millis(5));
+                    continue;
+                }
+            };
+            last_input = Instant::now();
+
+            match event {
+                CInputEvent::Unknown => break,
+                CInputEvent::Keyboard(CKeyEvent::Char(c)) => text.push(c),
+                _ => (),
+            }
+        }
+
+        if text.is_empty() {
+            // Only an unknown sequence, not a paste.
+            return Event::Unknown(vec![]);
+        }
+        Event::Paste(text)
     }
 
     fn apply_colors(&self, colors: theme::ColorPair) {
-        with_color(colors.front, |c| self.write(Colored::Fg(*c)));
-        with_color(colors.back, |c| self.write(Colored::Bg(*c)));
+        let depth = self.color_depth;
+        with_color(colors.front, depth, |c| self.write(Colored::Fg(*c)));
+        with_color(colors.back, depth, |c| self.write(Colored::Bg(*c)));
     }
 
     fn write<T>(&self, content: T)
@@ -81,6 +157,15 @@ impl Backend {
                 CKeyEvent::PageDown => Event::Key(Key::PageDown),
                 CKeyEvent::Delete => Event::Key(Key::Del),
                 CKeyEvent::Insert => Event::Key(Key::Ins),
+                CKeyEvent::BackTab => Event::Shift(Key::Tab),
+                CKeyEvent::CtrlUp => Event::Ctrl(Key::Up),
+                CKeyEvent::CtrlDown => Event::Ctrl(Key::Down),
+                CKeyEvent::CtrlLeft => Event::Ctrl(Key::Left),
+                CKeyEvent::CtrlRight => Event::Ctrl(Key::Right),
+                CKeyEvent::ShiftUp => Event::Shift(Key::Up),
+                CKeyEvent::ShiftDown => Event::Shift(Key::Down),
+                CKeyEvent::ShiftLeft => Event::Shift(Key::Left),
+                CKeyEvent::ShiftRight => Event::Shift(Key::Right),
                 CKeyEvent::F(n) => Event::Key(Key::from_f(n)),
                 CKeyEvent::Char('\n') => Event::Key(Key::Enter),
                 CKeyEvent::Char('\t') => Event::Key(Key::Tab),
@@ -160,17 +245,49 @@ impl backend::Backend for Backend {
     }
 
     fn poll_event(&mut self) -> Option<Event> {
-        self.async_reader.next().map(|event| self.map_key(event))
+        let size = self.screen_size();
+        if size != self.last_size {
+            self.last_size = size;
+            return Some(Event::WindowResize(size));
+        }
+
+        match self.async_reader.next()? {
+            CInputEvent::Unknown if cfg!(unix) => Some(self.read_paste()),
+            event => Some(self.map_key(event)),
+        }
     }
 
     fn finish(&mut self) {
         self.cursor.goto(1, 1).unwrap();
         self.terminal.clear(ClearType::All).unwrap();
         self.write(Attribute::Reset);
+        self.set_bracketed_paste(false);
         input().disable_mouse_mode().unwrap();
         cursor().show().unwrap();
     }
 
+    fn suspend(&mut self) {
+        self.set_bracketed_paste(false);
+        input().disable_mouse_mode().unwrap();
+        cursor().show().unwrap();
+        self.stdout.borrow_mut().flush().unwrap();
+        self.alternate_screen = None;
+    }
+
+    fn resume(&mut self) {
+        self.alternate_screen = AlternateScreen::to_alternate(true).ok();
+        input().enable_mouse_mode().unwrap();
+        self.set_bracketed_paste(true);
+        cursor().hide().unwrap();
+    }
+
+    fn set_clipboard(&self, text: &str) -> bool {
+        let mut stdout = self.stdout.borrow_mut();
+        write!(stdout, "{}", clipboard::osc52(text))
+            .and_then(|_| stdout.flush())
+            .is_ok()
+    }
+
     fn refresh(&mut self) {
         self.stdout.borrow_mut().flush().unwrap();
     }
@@ -241,19 +358,19 @@ impl backend::Backend for Backend {
     fn unset_effect(&self, effect: theme::Effect) {
         match effect {
             theme::Effect::Simple => (),
-            theme::Effect::Reverse => self.write(Attribute::Reverse),
-            theme::Effect::Bold => self.write(Attribute::NoBold),
+            theme::Effect::Reverse => self.write(Attribute::NoInverse),
+            theme::Effect::Bold => self.write(Attribute::NormalIntensity),
             theme::Effect::Italic => self.write(Attribute::NoItalic),
-            theme::Effect::Underline => self.write(Attribute::Underlined),
+            theme::Effect::Underline => self.write(Attribute::NoUnderline),
         }
     }
 }
 
-fn with_color<F, R>(clr: theme::Color, f: F) -> R
+fn with_color<F, R>(clr: theme::Color, depth: theme::ColorDepth, f: F) -> R
 where
     F: FnOnce(&Color) -> R,
 {
-    match clr {
+    match clr.quantize(depth) {
         theme::Color::Dark(theme::BaseColor::Black) => f(&Color::Black),
         theme::Color::Dark(theme::BaseColor::Red) => f(&Color::DarkRed),
         theme::Color::Dark(theme::BaseColor::Green) => f(&Color::DarkGreen),
@@ -274,7 +391,15 @@ where
         theme::Color::Light(theme::BaseColor::Cyan) => f(&Color::Cyan),
         theme::Color::Light(theme::BaseColor::White) => f(&Color::White),
 
-        theme::Color::Rgb(r, g, b) => f(&Color::Rgb { r, g, b }),
+        theme::Color::Rgb(r, g, b)
+            if depth == theme::ColorDepth::TrueColor =>
+        {
+            f(&Color::Rgb { r, g, b })
+        }
+        // A grayscale value from the 256 colors list.
+        clr @ theme::Color::Rgb(..) => {
+            f(&Color::AnsiValue(clr.to_256colors().unwrap()))
+        }
         theme::Color::RgbLowRes(r, g, b) => {
             debug_assert!(r <= 5,
                           "Red color fragment (r = {}) is out of bound. Make sure r ≤ 5.",
@@ -289,10 +414,6 @@ where
             f(&Color::AnsiValue(16 + 36 * r + 6 * g + b))
         }
 
-        theme::Color::TerminalDefault => {
-            unimplemented!(
-                "I have to take a look at how reset has to work out"
-            );
-        }
+        theme::Color::TerminalDefault => f(&Color::Reset),
     }
 }
@@ -89,37 +89,11 @@ fn find_closest(color: Color, max_colors: i16) -> i16 {
         Color::Light(BaseColor::Magenta) => 13 % max_colors,
         Color::Light(BaseColor::Cyan) => 14 % max_colors,
         Color::Light(BaseColor::White) => 15 % max_colors,
-        Color::Rgb(r, g, b) if max_colors >= 256 => {
-            // If r = g = b, it may be a grayscale value!
-            if r == g && g == b && r != 0 && r < 250 {
-                // Grayscale
-                // (r = g = b) = 8 + 10 * n
-                // (r - 8) / 10 = n
-                //
-                let n = (r - 8) / 10;
-                i16::from(232 + n)
-            } else {
-                // Generic RGB
-                let r = 6 * u16::from(r) / 256;
-                let g = 6 * u16::from(g) / 256;
-                let b = 6 * u16::from(b) / 256;
-                (16 + 36 * r + 6 * g + b) as i16
-            }
+        Color::Rgb(..) | Color::RgbLowRes(..) if max_colors >= 256 => {
+            i16::from(color.to_256colors().unwrap())
         }
-        Color::Rgb(r, g, b) => {
-            let r = if r > 127 { 1 } else { 0 };
-            let g = if g > 127 { 1 } else { 0 };
-            let b = if b > 127 { 1 } else { 0 };
-            (r + 2 * g + 4 * b) as i16
-        }
-        Color::RgbLowRes(r, g, b) if max_colors >= 256 => {
-            i16::from(16 + 36 * r + 6 * g + b)
-        }
-        Color::RgbLowRes(r, g, b) => {
-            let r = if r > 2 { 1 } else { 0 };
-            let g = if g > 2 { 1 } else { 0 };
-            let b = if b > 2 { 1 } else { 0 };
-            (r + 2 * g + 4 * b) as i16
+        Color::Rgb(..) | Color::RgbLowRes(..) => {
+            find_closest(color.to_16colors(), max_colors)
         }
     }
 }
@@ -12,6 +12,7 @@ use std::io::Write;
 use libc;
 
 use crate::backend;
+use crate::clipboard;
 use crate::event::{Event, Key, MouseButton, MouseEvent};
 use crate::theme::{Color, ColorPair, Effect};
 use crate::utf8;
@@ -20,6 +21,15 @@ use crate::vec::Vec2;
 use self::super::split_i32;
 use self::ncurses::mmask_t;
 
+// Codes given to the sequences around pasted text, in bracketed paste mode.
+const KEY_PASTE_START: i32 = 0x7F00;
+const KEY_PASTE_END: i32 = 0x7F01;
+
+extern "C" {
+    // Not exposed by the `ncurses` crate.
+    fn define_key(definition: *const libc::c_char, keycode: i32) -> i32;
+}
+
 /// Backend using ncurses.
 pub struct Backend {
     current_style: Cell<ColorPair>,
@@ -37,6 +47,9 @@ pub struct Backend {
     //
     // So remember the one we didn't return.
     input_buffer: Option<Event>,
+
+    // Set while the terminal is in program mode, until `finish`.
+    active: bool,
 }
 
 fn find_closest_pair(pair: ColorPair) -> (i16, i16) {
@@ -48,8 +61,7 @@ fn find_closest_pair(pair: ColorPair) -> (i16, i16) {
 /// Since this is not going to be used often, we can afford to re-open the
 /// file every time.
 fn write_to_tty(bytes: &[u8]) -> io::Result<()> {
-    let mut tty_output =
-        File::create("/dev/tty").expect("cursive can only run with a tty");
+    let mut tty_output = File::create("/dev/tty")?;
     tty_output.write_all(bytes)?;
     // tty_output will be flushed automatically at the end of the function.
     Ok(())
@@ -83,10 +95,20 @@ impl Backend {
         let tty_path = CString::new("/dev/tty").unwrap();
         let mode = CString::new("r+").unwrap();
         let tty = unsafe { libc::fopen(tty_path.as_ptr(), mode.as_ptr()) };
+        if tty.is_null() {
+            return Err(io::Error::last_os_error());
+        }
         ncurses::newterm(None, tty, tty);
         // Enable keypad (like arrows)
         ncurses::keypad(ncurses::stdscr(), true);
 
+        let paste_start = CString::new("\x1B[200~").unwrap();
+        let paste_end = CString::new("\x1B[201~").unwrap();
+        unsafe {
+            define_key(paste_start.as_ptr(), KEY_PASTE_START);
+            define_key(paste_end.as_ptr(), KEY_PASTE_END);
+        }
+
         // This disables mouse click detection,
         // and provides 0-delay access to mouse presses.
         ncurses::mouseinterval(0);
@@ -113,7 +135,8 @@ impl Backend {
         // This asks the terminal to provide us with mouse drag events
         // (Mouse move when a button is pressed).
         // Replacing 1002 with 1003 would give us ANY mouse move.
-        write_to_tty(b"\x1B[?1002h")?;
+        // 2004 enables bracketed paste, to receive pasted text at once.
+        write_to_tty(b"\x1B[?1002h\x1B[?2004h")?;
 
         let c = Backend {
             current_style: Cell::new(ColorPair::from_256colors(0, 0)),
@@ -121,6 +144,7 @@ impl Backend {
             key_codes: initialize_keymap(),
             last_mouse_button: None,
             input_buffer: None,
+            active: true,
         };
 
         Ok(Box::new(c))
@@ -182,6 +206,10 @@ impl Backend {
             return None;
         }
 
+        if ch == KEY_PASTE_START {
+            return Some(self.read_paste());
+        }
+
         // Is it a UTF-8 starting point?
         let event = if 32 <= ch && ch <= 255 && ch != 127 {
             utf8::read_char(ch as u8, || Some(ncurses::getch() as u8))
@@ -197,10 +225,31 @@ impl Backend {
         Some(event)
     }
 
+    // Reads pasted text, until the end of the paste.
+    fn read_paste(&mut self) -> Event {
+        let mut bytes = Vec::new();
+
+        // Wait for the rest of the text, but not forever.
+        ncurses::timeout(1000);
+        loop {
+            match ncurses::getch() {
+                -1 | KEY_PASTE_END => break,
+                13 => bytes.push(b'\n'),
+                ch @ 0..=255 => bytes.push(ch as u8),
+                _ => (),
+            }
+        }
+        ncurses::timeout(0);
+
+        Event::Paste(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
     fn parse_ncurses_char(&mut self, ch: i32) -> Event {
         // eprintln!("Found {:?}", ncurses::keyname(ch));
         if ch == ncurses::KEY_MOUSE {
             self.parse_mouse_event()
+        } else if ch == ncurses::KEY_RESIZE {
+            Event::WindowResize(backend::Backend::screen_size(self))
         } else {
             self.key_codes
                 .get(&ch)
@@ -323,8 +372,24 @@ impl backend::Backend for Backend {
     }
 
     fn finish(&mut self) {
-        write_to_tty(b"\x1B[?1002l").unwrap();
+        write_to_tty(b"\x1B[?1002l\x1B[?2004l").unwrap();
         ncurses::endwin();
+        self.active = false;
+    }
+
+    fn suspend(&mut self) {
+        self.finish();
+    }
+
+    fn resume(&mut self) {
+        // Refreshing after `endwin` restores the program mode.
+        ncurses::refresh();
+        write_to_tty(b"\x1B[?1002h\x1B[?2004h").unwrap();
+        self.active = true;
+    }
+
+    fn set_clipboard(&self, text: &str) -> bool {
+        write_to_tty(clipboard::osc52(text).as_bytes()).is_ok()
     }
 
     fn set_color(&self, colors: ColorPair) -> ColorPair {
@@ -389,6 +454,16 @@ impl backend::Backend for Backend {
     }
 }
 
+impl Drop for Backend {
+    fn drop(&mut self) {
+        // Restores the terminal if `finish` was not called, for example
+        // while unwinding from a panic.
+        if self.active {
+            backend::Backend::finish(self);
+        }
+    }
+}
+
 /// Returns the Key enum corresponding to the given ncurses event.
 fn get_mouse_button(bare_event: i32) -> MouseButton {
     match bare_event {
@@ -477,8 +552,6 @@ fn initialize_keymap() -> HashMap<i32, Event> {
     map.insert(127, Event::Key(Key::Backspace));
     map.insert(ncurses::KEY_BACKSPACE, Event::Key(Key::Backspace));
 
-    map.insert(410, Event::WindowResize);
-
     map.insert(ncurses::KEY_B2, Event::Key(Key::NumpadCenter));
     map.insert(ncurses::KEY_DC, Event::Key(Key::Del));
     map.insert(ncurses::KEY_IC, Event::Key(Key::Ins));
@@ -7,6 +7,7 @@ use std::cell::{Cell, RefCell};
 use std::io::{stdout, Write};
 
 use crate::backend;
+use crate::clipboard;
 use crate::event::{Event, Key, MouseButton, MouseEvent};
 use crate::theme::{Color, ColorPair, Effect};
 use crate::vec::Vec2;
@@ -14,6 +15,10 @@ use crate::vec::Vec2;
 use self::pancurses::mmask_t;
 use super::split_i32;
 
+// Sequences around pasted text, in bracketed paste mode.
+const PASTE_START: &str = "\x1B[200~";
+const PASTE_END: &str = "\x1B[201~";
+
 /// Backend using pancurses.
 pub struct Backend {
     // Used
@@ -26,6 +31,9 @@ pub struct Backend {
     key_codes: HashMap<i32, Event>,
     last_mouse_button: Option<MouseButton>,
     input_buffer: Option<Event>,
+
+    // Set while the terminal is in program mode, until `finish`.
+    active: bool,
 }
 
 fn find_closest_pair(pair: ColorPair) -> (i16, i16) {
@@ -70,7 +78,8 @@ impl Backend {
         // This asks the terminal to provide us with mouse drag events
         // (Mouse move when a button is pressed).
         // Replacing 1002 with 1003 would give us ANY mouse move.
-        print!("\x1B[?1002h");
+        // 2004 enables bracketed paste, to receive pasted text at once.
+        print!("\x1B[?1002h\x1B[?2004h");
         stdout().flush()?;
 
         let c = Backend {
@@ -79,6 +88,7 @@ impl Backend {
             key_codes: initialize_keymap(),
             last_mouse_button: None,
             input_buffer: None,
+            active: true,
             window,
         };
 
@@ -128,6 +138,56 @@ impl Backend {
         let style = pancurses::COLOR_PAIR(i as pancurses::chtype);
         self.window.attron(style);
     }
+    // Reads what follows an escape, in case it starts a paste.
+    //
+    // pancurses does not know the paste sequences: they come one character
+    // at a time.
+    fn parse_escape(&mut self) -> Event {
+        let mut read = Vec::new();
+        for expected in PASTE_START.chars().skip(1) {
+            match self.window.getch() {
+                Some(pancurses::Input::Character(c)) if c == expected => {
+                    read.push(c)
+                }
+                next => {
+                    // Not a paste: give back what we read, in order.
+                    if let Some(ref input) = next {
+                        self.window.ungetch(input);
+                    }
+                    for &c in read.iter().rev() {
+                        self.window.ungetch(&pancurses::Input::Character(c));
+                    }
+                    return Event::Key(Key::Esc);
+                }
+            }
+        }
+
+        self.read_paste()
+    }
+
+    // Reads pasted text, until the end of the paste.
+    fn read_paste(&mut self) -> Event {
+        let mut text = String::new();
+
+        // Wait for the rest of the text, but not forever.
+        self.window.timeout(1000);
+        while !text.ends_with(PASTE_END) {
+            match self.window.getch() {
+                None => break,
+                Some(pancurses::Input::Character('\r'))
+                | Some(pancurses::Input::KeyEnter) => text.push('\n'),
+                Some(pancurses::Input::Character(c)) => text.push(c),
+                Some(_) => (),
+            }
+        }
+        self.window.timeout(0);
+
+        if text.ends_with(PASTE_END) {
+            text.truncate(text.len() - PASTE_END.len());
+        }
+        Event::Paste(text)
+    }
+
     fn parse_next(&mut self) -> Option<Event> {
         if let Some(event) = self.input_buffer.take() {
             return Some(event);
@@ -143,7 +203,7 @@ impl Backend {
                     Event::Key(Key::Backspace)
                 }
                 pancurses::Input::Character('\u{9}') => Event::Key(Key::Tab),
-                pancurses::Input::Character('\u{1b}') => Event::Key(Key::Esc),
+                pancurses::Input::Character('\u{1b}') => self.parse_escape(),
                 // Ctrl+C
                 // TODO: Do not sent Exit here, but register it as a default callback
                 pancurses::Input::Character('\u{3}') => Event::Exit,
@@ -273,7 +333,7 @@ impl Backend {
                     if cfg!(target_os = "windows") {
                         pancurses::resize_term(0, 0);
                     }
-                    Event::WindowResize
+                    Event::WindowResize(backend::Backend::screen_size(self))
                 }
                 pancurses::Input::KeyEvent => Event::Refresh,
                 // TODO: mouse support
@@ -366,9 +426,27 @@ impl backend::Backend for Backend {
     }
 
     fn finish(&mut self) {
-        print!("\x1B[?1002l");
+        print!("\x1B[?1002l\x1B[?2004l");
         stdout().flush().expect("could not flush stdout");
         pancurses::endwin();
+        self.active = false;
+    }
+
+    fn suspend(&mut self) {
+        self.finish();
+    }
+
+    fn resume(&mut self) {
+        // Refreshing after `endwin` restores the program mode.
+        self.window.refresh();
+        print!("\x1B[?1002h\x1B[?2004h");
+        stdout().flush().expect("could not flush stdout");
+        self.active = true;
+    }
+
+    fn set_clipboard(&self, text: &str) -> bool {
+        print!("{}", clipboard::osc52(text));
+        stdout().flush().is_ok()
     }
 
     fn set_color(&self, colors: ColorPair) -> ColorPair {
@@ -436,6 +514,16 @@ impl backend::Backend for Backend {
     }
 }
 
+impl Drop for Backend {
+    fn drop(&mut self) {
+        // Restores the terminal if `finish` was not called, for example
+        // while unwinding from a panic.
+        if self.active {
+            backend::Backend::finish(self);
+        }
+    }
+}
+
 /// Parse the given code into one or more event.
 ///
 /// If the given event code should expend into multiple events
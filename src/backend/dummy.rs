@@ -25,6 +25,10 @@ impl backend::Backend for Backend {
         "dummy"
     }
 
+    fn uses_terminal(&self) -> bool {
+        false
+    }
+
     fn finish(&mut self) {}
 
     fn refresh(&mut self) {}
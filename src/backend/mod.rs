@@ -7,20 +7,27 @@
 //! using some common libraries. Each of those included backends needs a
 //! corresonding feature to be enabled.
 
+use crossbeam_channel::Sender;
+
 use crate::event::Event;
 use crate::theme;
+use crate::utils::width::TextWidth;
 use crate::vec::Vec2;
-use unicode_width::UnicodeWidthStr;
 
+mod ansi;
 #[cfg(unix)]
 mod resize;
 
 pub mod dummy;
+pub mod puppet;
 
 pub mod blt;
 pub mod crossterm;
 pub mod curses;
+pub mod telnet;
 pub mod termion;
+pub mod web;
+pub mod wincon;
 
 /// Trait defining the required methods to be a backend.
 ///
@@ -35,7 +42,10 @@ pub mod termion;
 /// provide a backend initializer - usually their `init()` function.
 ///
 /// Backends are responsible for handling input and converting it to `Event`. Input must be
-/// non-blocking, it will be polled regularly.
+/// non-blocking, it will be polled regularly (unless the backend uses a
+/// [`Waker`]).
+///
+/// [`Waker`]: struct.Waker.html
 pub trait Backend {
     /// Polls the backend for any input.
     ///
@@ -66,6 +76,16 @@ pub trait Backend {
     /// Main method used for printing
     fn print_at(&self, pos: Vec2, text: &str);
 
+    /// Prints a single grapheme, expected to take `width` cells.
+    ///
+    /// Used for graphemes terminals may not agree on, like emoji sequences.
+    /// The default implementation first blanks the cells, in case the
+    /// terminal draws it narrower.
+    fn print_grapheme_at(&self, pos: Vec2, grapheme: &str, width: usize) {
+        self.print_at_rep(pos, width, " ");
+        self.print_at(pos, grapheme);
+    }
+
     /// First positions the cursor, similar to `print_at`, and then prints the given number of
     /// `repetitions` of `text`.
     fn print_at_rep(&self, pos: Vec2, repetitions: usize, text: &str) {
@@ -108,4 +128,71 @@ pub trait Backend {
     fn name(&self) -> &str {
         "unknown"
     }
+
+    /// Gives the terminal back, for example to run another program.
+    ///
+    /// The terminal should be left as it was before the backend started,
+    /// until `resume` is called.
+    ///
+    /// Default implementation does nothing.
+    fn suspend(&mut self) {}
+
+    /// Takes the terminal again after `suspend`.
+    ///
+    /// The screen will be entirely redrawn afterward.
+    ///
+    /// Default implementation does nothing.
+    fn resume(&mut self) {}
+
+    /// Sets the terminal clipboard to `text`, if supported.
+    ///
+    /// Returns `false` if this backend cannot reach the terminal clipboard.
+    ///
+    /// Default implementation returns `false`.
+    fn set_clipboard(&self, _text: &str) -> bool {
+        false
+    }
+
+    /// Returns `true` if this backend draws on the local terminal.
+    ///
+    /// Panic messages are only delayed while such a backend runs, see the
+    /// `panic_hook` module.
+    ///
+    /// Default implementation returns `true`.
+    fn uses_terminal(&self) -> bool {
+        true
+    }
+
+    /// Gives the backend a way to wake up the event loop.
+    ///
+    /// Backends reading input on another thread should keep `waker`, call
+    /// `Waker::wake` after each event they queue, and return `true`. The
+    /// idle event loop then sleeps until something happens, instead of
+    /// polling the backend every few milliseconds.
+    ///
+    /// Default implementation returns `false`.
+    fn set_waker(&mut self, _waker: Waker) -> bool {
+        false
+    }
+}
+
+/// Wakes up the event loop of a `Cursive` root, from any thread.
+///
+/// Given to `Backend::set_waker`.
+#[derive(Clone, Debug)]
+pub struct Waker {
+    sender: Sender<()>,
+}
+
+impl Waker {
+    pub(crate) fn new(sender: Sender<()>) -> Self {
+        Waker { sender }
+    }
+
+    /// Wakes up the event loop if it is waiting, so it polls the backend.
+    ///
+    /// Calls made before the event loop wakes up are merged.
+    pub fn wake(&self) {
+        let _ = self.sender.try_send(());
+    }
 }
@@ -0,0 +1,245 @@
+//! Headless backend, driven by scripted input.
+//!
+//! Useful for integration tests: events are queued through a [`Puppet`], and
+//! the [`ScreenBuffer`] drawn by the views can be inspected after each step.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use cursive::Cursive;
+//! # use cursive::backend::puppet;
+//! # use cursive::event::Key;
+//! # use cursive::views::{Dialog, EditView};
+//! let (backend, puppet) = puppet::Backend::init((30, 10));
+//! let mut siv = Cursive::new(move || backend);
+//! siv.add_layer(Dialog::around(EditView::new()).title("Name"));
+//!
+//! puppet.type_str("Ferris");
+//! puppet.send(Key::Enter);
+//! while siv.step() {}
+//!
+//! let screen = puppet.screen();
+//! assert!(screen.contains("Name"));
+//! assert!(screen.contains("Ferris"));
+//! ```
+//!
+//! [`Puppet`]: struct.Puppet.html
+//! [`ScreenBuffer`]: ../../screen_buffer/struct.ScreenBuffer.html
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use enumset::EnumSet;
+use crate::backend;
+use crate::event::Event;
+use crate::screen_buffer::ScreenBuffer;
+use crate::theme::{Color, ColorPair, Effect};
+use crate::vec::Vec2;
+
+struct State {
+    input: VecDeque<Event>,
+    // Screen being drawn, and the last refreshed one.
+    buffer: ScreenBuffer,
+    screen: ScreenBuffer,
+    frames: usize,
+    clipboard: Option<String>,
+}
+
+/// Headless backend, printing to an in-memory grid of cells.
+///
+/// Created along with its [`Puppet`](struct.Puppet.html).
+pub struct Backend {
+    state: Rc<RefCell<State>>,
+    colors: Cell<ColorPair>,
+    effects: Cell<EnumSet<Effect>>,
+}
+
+/// Handle to a puppet backend, to send events and read the screen.
+#[derive(Clone)]
+pub struct Puppet {
+    state: Rc<RefCell<State>>,
+}
+
+impl Backend {
+    /// Creates a new puppet backend with the given screen size.
+    ///
+    /// Returns the backend, to give to `Cursive::new`, and a handle to
+    /// drive it.
+    pub fn init<S: Into<Vec2>>(size: S) -> (Box<dyn backend::Backend>, Puppet) {
+        let size = size.into();
+        let colors = ColorPair {
+            front: Color::TerminalDefault,
+            back: Color::TerminalDefault,
+        };
+        let screen = ScreenBuffer::new(size, colors);
+        let state = Rc::new(RefCell::new(State {
+            input: VecDeque::new(),
+            buffer: screen.clone(),
+            screen,
+            frames: 0,
+            clipboard: None,
+        }));
+
+        let backend = Backend {
+            state: Rc::clone(&state),
+            colors: Cell::new(colors),
+            effects: Cell::new(EnumSet::new()),
+        };
+        (Box::new(backend), Puppet { state })
+    }
+}
+
+impl Puppet {
+    /// Queues an event, to be received on the next step.
+    pub fn send<E: Into<Event>>(&self, event: E) {
+        self.state.borrow_mut().input.push_back(event.into());
+    }
+
+    /// Queues a sequence of events.
+    pub fn send_all<I, E>(&self, events: I)
+    where
+        I: IntoIterator<Item = E>,
+        E: Into<Event>,
+    {
+        for event in events {
+            self.send(event);
+        }
+    }
+
+    /// Queues a key press for each character of `text`.
+    pub fn type_str(&self, text: &str) {
+        self.send_all(text.chars());
+    }
+
+    /// Changes the screen size, and queues an `Event::WindowResize`.
+    pub fn resize<S: Into<Vec2>>(&self, size: S) {
+        let size = size.into();
+        let mut state = self.state.borrow_mut();
+        state.buffer = ScreenBuffer::new(size, state.buffer.background());
+        state.input.push_back(Event::WindowResize(size));
+    }
+
+    /// Returns the number of events not received yet.
+    pub fn pending(&self) -> usize {
+        self.state.borrow().input.len()
+    }
+
+    /// Returns the screen, as of the last refresh.
+    pub fn screen(&self) -> ScreenBuffer {
+        self.state.borrow().screen.clone()
+    }
+
+    /// Returns the number of times the screen was refreshed.
+    pub fn frame_count(&self) -> usize {
+        self.state.borrow().frames
+    }
+
+    /// Returns the text last sent to the terminal clipboard.
+    pub fn clipboard(&self) -> Option<String> {
+        self.state.borrow().clipboard.clone()
+    }
+}
+
+impl backend::Backend for Backend {
+    fn name(&self) -> &str {
+        "puppet"
+    }
+
+    fn uses_terminal(&self) -> bool {
+        false
+    }
+
+    fn poll_event(&mut self) -> Option<Event> {
+        self.state.borrow_mut().input.pop_front()
+    }
+
+    fn finish(&mut self) {}
+
+    fn refresh(&mut self) {
+        let mut state = self.state.borrow_mut();
+        state.screen = state.buffer.clone();
+        state.frames += 1;
+    }
+
+    fn has_colors(&self) -> bool {
+        true
+    }
+
+    fn screen_size(&self) -> Vec2 {
+        self.state.borrow().buffer.size()
+    }
+
+    fn print_at(&self, pos: Vec2, text: &str) {
+        let colors = self.colors.get();
+        let effects = self.effects.get();
+        self.state
+            .borrow_mut()
+            .buffer
+            .print(pos, text, colors, effects);
+    }
+
+    fn clear(&self, color: Color) {
+        let mut state = self.state.borrow_mut();
+        let size = state.buffer.size();
+        let colors = ColorPair {
+            front: color,
+            back: color,
+        };
+        state.buffer = ScreenBuffer::new(size, colors);
+    }
+
+    fn set_color(&self, colors: ColorPair) -> ColorPair {
+        self.colors.replace(colors)
+    }
+
+    fn set_effect(&self, effect: Effect) {
+        self.effects.set(self.effects.get() | effect);
+    }
+
+    fn unset_effect(&self, effect: Effect) {
+        self.effects.set(self.effects.get() - effect);
+    }
+
+    fn set_clipboard(&self, text: &str) -> bool {
+        self.state.borrow_mut().clipboard = Some(text.to_string());
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::Key;
+    use crate::views::{Checkbox, LinearLayout, TextView};
+    use crate::Cursive;
+
+    #[test]
+    fn scripted_input() {
+        let (backend, puppet) = Backend::init((20, 4));
+        let mut siv = Cursive::new(move || backend);
+        siv.add_fullscreen_layer(
+            LinearLayout::vertical()
+                .child(TextView::new("Hello 世界"))
+                .child(Checkbox::new()),
+        );
+        siv.refresh();
+
+        let screen = puppet.screen();
+        assert_eq!(screen.find("世界"), Some(Vec2::new(6, 0)));
+        assert_eq!(screen.cell((7, 0)).unwrap().text, "");
+        assert_eq!(screen.row(1).unwrap().trim_end(), "[ ]");
+        // The focused checkbox is highlighted.
+        let colors = |pos: (usize, usize)| screen.cell(pos).unwrap().colors;
+        assert_ne!(colors((1, 1)), colors((5, 1)));
+
+        puppet.send(Key::Down);
+        puppet.send(' ');
+        assert_eq!(puppet.pending(), 2);
+        while siv.step() {}
+
+        assert_eq!(puppet.pending(), 0);
+        assert!(puppet.screen().contains("[X]"));
+        assert!(puppet.frame_count() > 1);
+    }
+}
@@ -1,14 +1,19 @@
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::thread;
 
 use crossbeam_channel::Sender;
 use signal_hook::iterator::Signals;
 
+use crate::backend::Waker;
+
 /// This starts a new thread to listen for SIGWINCH signals
+///
+/// The event loop is woken up after each resize, once `waker` is set.
 #[allow(unused)]
 pub fn start_resize_thread(
     resize_sender: Sender<()>, resize_running: Arc<AtomicBool>,
+    waker: Arc<Mutex<Option<Waker>>>,
 ) {
     let signals = Signals::new(&[libc::SIGWINCH]).unwrap();
     thread::spawn(move || {
@@ -17,6 +22,9 @@ pub fn start_resize_thread(
             // We know it will only contain SIGWINCH signals, so no need to check.
             if signals.wait().count() > 0 {
                 resize_sender.send(()).unwrap();
+                if let Some(ref waker) = *waker.lock().unwrap() {
+                    waker.wake();
+                }
             }
         }
     });
@@ -0,0 +1,409 @@
+//! Backend serving the interface over telnet.
+//!
+//! Requires the `telnet-backend` feature.
+//!
+//! [`serve`] listens for connections, and runs a separate `Cursive` root
+//! for each of them, on its own thread. Clients only need a telnet client
+//! in a terminal: the remote terminal size is followed, and mouse and paste
+//! are supported when the terminal does.
+//!
+//! Only the cells that changed are sent on each frame, so slow links stay
+//! usable.
+//!
+//! Telnet is not encrypted: keep it on trusted networks. For SSH, a server
+//! like `sshd` can instead start the application for each session, with a
+//! regular backend.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! # use cursive::backend::telnet;
+//! # use cursive::views::Dialog;
+//! telnet::serve("0.0.0.0:2323", |siv| {
+//!     siv.add_layer(Dialog::text("Welcome!").button("Quit", |s| s.quit()));
+//! })
+//! .unwrap();
+//! ```
+//!
+//! [`serve`]: fn.serve.html
+
+#![cfg(feature = "telnet-backend")]
+
+use std::cell::RefCell;
+use std::io::{self, BufWriter, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crossbeam_channel::{Receiver, Sender};
+
+use crate::backend::{self, ansi};
+use crate::event::Event;
+use crate::theme::{Color, ColorDepth, ColorPair, Effect};
+use crate::vec::Vec2;
+use crate::Cursive;
+
+// Telnet commands and options.
+const IAC: u8 = 255;
+const DONT: u8 = 254;
+const DO: u8 = 253;
+const WILL: u8 = 251;
+const SB: u8 = 250;
+const SE: u8 = 240;
+const ECHO: u8 = 1;
+const SUPPRESS_GO_AHEAD: u8 = 3;
+const NAWS: u8 = 31;
+const LINEMODE: u8 = 34;
+
+// Size used until the client tells its own.
+const DEFAULT_SIZE: (usize, usize) = (80, 24);
+
+/// Listens on `addr`, and runs a `Cursive` root for each connection.
+///
+/// `setup` is called on each new root, before its event loop starts. The
+/// connection is closed once the root quits.
+///
+/// This only returns if the listener cannot be created.
+pub fn serve<A, F>(addr: A, setup: F) -> io::Result<()>
+where
+    A: ToSocketAddrs,
+    F: Fn(&mut Cursive) + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind(addr)?;
+    let setup = Arc::new(setup);
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(err) => {
+                log::warn!("Could not accept connection: {}", err);
+                continue;
+            }
+        };
+
+        let setup = Arc::clone(&setup);
+        thread::spawn(move || {
+            match Cursive::try_new(move || Backend::init(stream)) {
+                Ok(mut siv) => {
+                    // Stopping the process would stop every session.
+                    siv.set_suspend_on_ctrl_z(false);
+                    setup(&mut siv);
+                    siv.run();
+                }
+                Err(err) => log::warn!("Could not start session: {}", err),
+            }
+        });
+    }
+    Ok(())
+}
+
+/// Backend using a telnet connection.
+pub struct Backend {
+    stream: TcpStream,
+    writer: RefCell<BufWriter<TcpStream>>,
+    events: Receiver<Event>,
+    size: Arc<Mutex<Vec2>>,
+    screen: RefCell<ansi::Screen>,
+    // Shared with the input thread, once set.
+    waker: Arc<Mutex<Option<backend::Waker>>>,
+}
+
+impl Backend {
+    /// Creates a new backend for a connected client.
+    ///
+    /// This asks the client to send its window size, and to give each key
+    /// right away instead of lines.
+    pub fn init(stream: TcpStream) -> io::Result<Box<dyn backend::Backend>> {
+        let mut writer = BufWriter::new(stream.try_clone()?);
+        writer.write_all(&[
+            IAC,
+            WILL,
+            ECHO,
+            IAC,
+            WILL,
+            SUPPRESS_GO_AHEAD,
+            IAC,
+            DONT,
+            LINEMODE,
+            IAC,
+            DO,
+            NAWS,
+        ])?;
+        // Alternate screen, hidden cursor.
+        write!(writer, "\x1B[?1049h\x1B[?25l{}", ansi::ENABLE_INPUT)?;
+        writer.flush()?;
+
+        let size = Vec2::from(DEFAULT_SIZE);
+        let screen = ansi::Screen::new(size, ColorDepth::TrueColor);
+        let size = Arc::new(Mutex::new(size));
+        let (sender, events) = crossbeam_channel::unbounded();
+        let reader = stream.try_clone()?;
+        let shared_size = Arc::clone(&size);
+        let waker = Arc::new(Mutex::new(None));
+        let input_waker = Arc::clone(&waker);
+        thread::spawn(move || {
+            read_input(reader, &sender, &shared_size, &input_waker)
+        });
+
+        Ok(Box::new(Backend {
+            stream,
+            writer: RefCell::new(writer),
+            events,
+            size,
+            screen: RefCell::new(screen),
+            waker,
+        }))
+    }
+
+    fn write(&self, text: &str) {
+        // Errors mean the client left: the input thread then sends an
+        // `Event::Exit`.
+        let _ = self.writer.borrow_mut().write_all(text.as_bytes());
+    }
+}
+
+impl backend::Backend for Backend {
+    fn name(&self) -> &str {
+        "telnet"
+    }
+
+    fn uses_terminal(&self) -> bool {
+        false
+    }
+
+    fn set_waker(&mut self, waker: backend::Waker) -> bool {
+        *self.waker.lock().unwrap() = Some(waker);
+        true
+    }
+
+    fn poll_event(&mut self) -> Option<Event> {
+        self.events.try_recv().ok()
+    }
+
+    fn finish(&mut self) {
+        self.write(ansi::DISABLE_INPUT);
+        self.write("\x1B[0m\x1B[2J\x1B[?25h\x1B[?1049l");
+        let _ = self.writer.borrow_mut().flush();
+        // Also stops the input thread.
+        let _ = self.stream.shutdown(Shutdown::Both);
+    }
+
+    fn refresh(&mut self) {
+        let output = self.screen.get_mut().render();
+        self.write(&output);
+        let _ = self.writer.borrow_mut().flush();
+    }
+
+    fn has_colors(&self) -> bool {
+        true
+    }
+
+    fn screen_size(&self) -> Vec2 {
+        *self.size.lock().unwrap()
+    }
+
+    fn print_at(&self, pos: Vec2, text: &str) {
+        self.screen.borrow_mut().print(pos, text);
+    }
+
+    fn clear(&self, color: Color) {
+        let mut screen = self.screen.borrow_mut();
+        screen.resize(self.screen_size());
+        screen.clear(color);
+    }
+
+    fn set_color(&self, colors: ColorPair) -> ColorPair {
+        self.screen.borrow_mut().set_colors(colors)
+    }
+
+    fn set_effect(&self, effect: Effect) {
+        self.screen.borrow_mut().set_effect(effect);
+    }
+
+    fn unset_effect(&self, effect: Effect) {
+        self.screen.borrow_mut().unset_effect(effect);
+    }
+}
+
+// Reads the client input until the connection is closed.
+fn read_input(
+    mut stream: TcpStream, sender: &Sender<Event>, size: &Mutex<Vec2>,
+    waker: &Mutex<Option<backend::Waker>>,
+) {
+    let wake = || {
+        if let Some(ref waker) = *waker.lock().unwrap() {
+            waker.wake();
+        }
+    };
+
+    let mut decoder = Decoder::default();
+    let mut parser = ansi::Parser::new();
+    let mut buffer = [0; 1024];
+    let mut data = Vec::new();
+    let mut events = Vec::new();
+
+    loop {
+        let n = match stream.read(&mut buffer) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => n,
+        };
+
+        let resized = decoder.decode(&buffer[..n], &mut data);
+        if let Some(new_size) = resized {
+            *size.lock().unwrap() = new_size;
+            events.push(Event::WindowResize(new_size));
+        }
+        parser.parse(&data, &mut events);
+        data.clear();
+
+        for event in events.drain(..) {
+            if sender.send(event).is_err() {
+                // The backend is gone.
+                return;
+            }
+        }
+        wake();
+    }
+
+    let _ = sender.send(Event::Exit);
+    wake();
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DecoderState {
+    Data,
+    // After a carriage return, which may be followed by `\n` or `\0`.
+    CarriageReturn,
+    Iac,
+    // After a negotiation command, expecting its option.
+    Negotiation,
+    Subnegotiation,
+    SubnegotiationIac,
+}
+
+// Separates the terminal input from the telnet commands.
+struct Decoder {
+    state: DecoderState,
+    subnegotiation: Vec<u8>,
+}
+
+impl Default for Decoder {
+    fn default() -> Self {
+        Decoder {
+            state: DecoderState::Data,
+            subnegotiation: Vec::new(),
+        }
+    }
+}
+
+impl Decoder {
+    // Appends the terminal input to `data`, and returns the new window
+    // size, if the client sent one.
+    fn decode(&mut self, bytes: &[u8], data: &mut Vec<u8>) -> Option<Vec2> {
+        use self::DecoderState::*;
+
+        let mut size = None;
+        for &byte in bytes {
+            self.state = match (self.state, byte) {
+                (Data, IAC) | (CarriageReturn, IAC) => Iac,
+                (CarriageReturn, b'\n') | (CarriageReturn, 0) => Data,
+                (Data, b'\r') | (CarriageReturn, b'\r') => {
+                    data.push(b'\r');
+                    CarriageReturn
+                }
+                (Data, byte) | (CarriageReturn, byte) => {
+                    data.push(byte);
+                    Data
+                }
+                (Iac, IAC) => {
+                    // An escaped 255 byte.
+                    data.push(IAC);
+                    Data
+                }
+                (Iac, SB) => {
+                    self.subnegotiation.clear();
+                    Subnegotiation
+                }
+                (Iac, byte) if byte >= WILL => Negotiation,
+                (Iac, _) | (Negotiation, _) => Data,
+                (Subnegotiation, IAC) => SubnegotiationIac,
+                (Subnegotiation, byte) | (SubnegotiationIac, byte @ IAC) => {
+                    self.subnegotiation.push(byte);
+                    Subnegotiation
+                }
+                (SubnegotiationIac, SE) => {
+                    size = self.window_size().or(size);
+                    Data
+                }
+                (SubnegotiationIac, _) => Data,
+            };
+        }
+        size
+    }
+
+    // Parses a `NAWS` subnegotiation: two 16-bit numbers.
+    fn window_size(&self) -> Option<Vec2> {
+        match self.subnegotiation[..] {
+            [NAWS, w1, w0, h1, h0] => {
+                let width = usize::from(w1) << 8 | usize::from(w0);
+                let height = usize::from(h1) << 8 | usize::from(h0);
+                Some(Vec2::new(width, height))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::Key;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn telnet_session() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client =
+            TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (server, _) = listener.accept().unwrap();
+        let mut backend = Backend::init(server).unwrap();
+        assert_eq!(backend.screen_size(), Vec2::new(80, 24));
+
+        // Window size, then `a`, Enter as sent by telnet clients, and Up.
+        client
+            .write_all(&[IAC, SB, NAWS, 0, 100, 0, 30, IAC, SE])
+            .unwrap();
+        client.write_all(b"a\r\0\x1B[A").unwrap();
+
+        let mut events = Vec::new();
+        let start = Instant::now();
+        while events.len() < 4 {
+            assert!(start.elapsed() < Duration::from_secs(5));
+            match backend.poll_event() {
+                Some(event) => events.push(event),
+                None => thread::sleep(Duration::from_millis(5)),
+            }
+        }
+        let size = Vec2::new(100, 30);
+        assert_eq!(
+            events,
+            vec![
+                Event::WindowResize(size),
+                Event::Char('a'),
+                Event::Key(Key::Enter),
+                Event::Key(Key::Up),
+            ]
+        );
+        assert_eq!(backend.screen_size(), size);
+
+        backend.clear(Color::TerminalDefault);
+        backend.print_at(Vec2::new(2, 1), "Hi");
+        backend.refresh();
+        backend.finish();
+        let mut output = Vec::new();
+        client.read_to_end(&mut output).unwrap();
+        assert!(output.starts_with(&[IAC, WILL, ECHO]));
+        let text = String::from_utf8_lossy(&output);
+        assert!(text.contains("\x1B[2;3HHi"));
+    }
+}
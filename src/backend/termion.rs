@@ -1,49 +1,89 @@
 //! Backend using the pure-rust termion library.
 //!
 //! Requires the `termion-backend` feature.
+//!
+//! True colors are used if the terminal supports them, according to
+//! `ColorDepth::detect`; other terminals get the closest colors they have.
+//! Use `Backend::init_with_color_depth` when the guess is wrong.
+//! Only the cells that changed are written on each frame.
 #![cfg(feature = "termion")]
 
 use termion;
 
-use self::termion::color as tcolor;
 use self::termion::event::Event as TEvent;
 use self::termion::event::Key as TKey;
 use self::termion::event::MouseButton as TMouseButton;
 use self::termion::event::MouseEvent as TMouseEvent;
-use self::termion::input::{MouseTerminal, TermRead};
+use self::termion::input::{MouseTerminal, TermReadEventsAndRaw};
 use self::termion::raw::{IntoRawMode, RawTerminal};
 use self::termion::screen::AlternateScreen;
-use self::termion::style as tstyle;
 use crossbeam_channel::{self, select, Receiver};
 
-use crate::backend;
+use crate::backend::{self, ansi};
+use crate::clipboard;
 use crate::event::{Event, Key, MouseButton, MouseEvent};
 use crate::theme;
 use crate::vec::Vec2;
 
-use std::cell::{Cell, RefCell};
+use std::cell::RefCell;
 use std::fs::File;
 use std::io::{BufWriter, Write};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::thread;
+use std::time::Duration;
+
+// Sequences around pasted text, in bracketed paste mode.
+const PASTE_START: &[u8] = b"\x1B[200~";
+const PASTE_END: &[u8] = b"\x1B[201~";
+// Mouse modes of `MouseTerminal`, with all moves (1003).
+const ENABLE_MOUSE: &str =
+    "\x1B[?1000h\x1B[?1002h\x1B[?1003h\x1B[?1015h\x1B[?1006h";
+const DISABLE_MOUSE: &str =
+    "\x1B[?1003l\x1B[?1006l\x1B[?1015l\x1B[?1002l\x1B[?1000l";
+// Start of SGR mouse reports, which tell the button of each move.
+const SGR_MOUSE: &[u8] = b"\x1B[<";
 
 /// Backend using termion
 pub struct Backend {
     terminal:
         RefCell<AlternateScreen<MouseTerminal<RawTerminal<BufWriter<File>>>>>,
-    current_style: Cell<theme::ColorPair>,
+    screen: RefCell<ansi::Screen>,
 
     // Inner state required to parse input
     last_button: Option<MouseButton>,
 
-    input_receiver: Receiver<TEvent>,
+    // Events come with their bytes, to parse mouse reports ourselves.
+    input_receiver: Receiver<(TEvent, Vec<u8>)>,
     resize_receiver: Receiver<()>,
+
+    // Shared with the input and resize threads, once set.
+    waker: Arc<Mutex<Option<backend::Waker>>>,
 }
 
 impl Backend {
     /// Creates a new termion-based backend.
+    ///
+    /// The color depth is given by `ColorDepth::detect`.
     pub fn init() -> std::io::Result<Box<dyn backend::Backend>> {
+        Self::init_with_color_depth(theme::ColorDepth::detect())
+    }
+
+    /// Creates a new termion-based backend, using the given color depth.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cursive::Cursive;
+    /// # use cursive::backend::termion::Backend;
+    /// # use cursive::theme::ColorDepth;
+    /// let siv = Cursive::try_new(|| {
+    ///     Backend::init_with_color_depth(ColorDepth::Palette256)
+    /// });
+    /// ```
+    pub fn init_with_color_depth(
+        depth: theme::ColorDepth,
+    ) -> std::io::Result<Box<dyn backend::Backend>> {
         // Use a ~8MB buffer
         // Should be enough for a single screen most of the time.
         let terminal =
@@ -52,24 +92,34 @@ impl Backend {
                     .into_raw_mode()?,
             )));
 
-        write!(terminal.borrow_mut(), "{}", termion::cursor::Hide)?;
+        // Enable bracketed paste, to receive pasted text at once, all mouse
+        // moves, and extended key reporting.
+        write!(
+            terminal.borrow_mut(),
+            "{}\x1B[?2004h\x1B[?1003h{}",
+            termion::cursor::Hide,
+            ansi::ENABLE_KEYBOARD
+        )?;
 
         let (input_sender, input_receiver) = crossbeam_channel::unbounded();
-        let (resize_sender, resize_receiver) = crossbeam_channel::bounded(0);
+        let (resize_sender, resize_receiver) = crossbeam_channel::bounded(1);
 
         let running = Arc::new(AtomicBool::new(true));
+        let waker = Arc::new(Mutex::new(None::<backend::Waker>));
 
         #[cfg(unix)]
         backend::resize::start_resize_thread(
             resize_sender,
             Arc::clone(&running),
+            Arc::clone(&waker),
         );
 
         // We want nonblocking input, but termion is blocking by default
         // Read input from a separate thread
+        let input = std::fs::File::open("/dev/tty")?;
+        let input_waker = Arc::clone(&waker);
         thread::spawn(move || {
-            let input = std::fs::File::open("/dev/tty").unwrap();
-            let mut events = input.events();
+            let mut events = input.events_and_raw();
 
             // Take all the events we can
             while let Some(Ok(event)) = events.next() {
@@ -78,6 +128,9 @@ impl Backend {
                 if input_sender.send(event).is_err() {
                     break;
                 }
+                if let Some(ref waker) = *input_waker.lock().unwrap() {
+                    waker.wake();
+                }
             }
 
             running.store(false, Ordering::Relaxed);
@@ -85,24 +138,41 @@ impl Backend {
 
         let c = Backend {
             terminal,
-            current_style: Cell::new(theme::ColorPair::from_256colors(0, 0)),
+            screen: RefCell::new(ansi::Screen::new(terminal_size(), depth)),
 
             last_button: None,
             input_receiver,
             resize_receiver,
+            waker,
         };
 
         Ok(Box::new(c))
     }
 
-    fn apply_colors(&self, colors: theme::ColorPair) {
-        with_color(colors.front, |c| self.write(tcolor::Fg(c)));
-        with_color(colors.back, |c| self.write(tcolor::Bg(c)));
+    // Reads pasted text, until the end of the paste.
+    fn read_paste(&mut self) -> Event {
+        let mut text = String::new();
+        // Don't wait forever if the end never comes.
+        let timeout = Duration::from_secs(1);
+        while let Ok((event, _)) = self.input_receiver.recv_timeout(timeout) {
+            match event {
+                TEvent::Unsupported(ref bytes) if bytes[..] == *PASTE_END => {
+                    break
+                }
+                TEvent::Key(TKey::Char(c)) => text.push(c),
+                _ => (),
+            }
+        }
+        Event::Paste(text)
     }
 
-    fn map_key(&mut self, event: TEvent) -> Event {
+    fn map_key(&mut self, event: TEvent, bytes: Vec<u8>) -> Event {
         match event {
-            TEvent::Unsupported(bytes) => Event::Unknown(bytes),
+            TEvent::Unsupported(ref bytes) if bytes[..] == *PASTE_START => {
+                self.read_paste()
+            }
+            // Termion does not know modified keys.
+            TEvent::Unsupported(bytes) => ansi::parse_sequence(&bytes),
             TEvent::Key(TKey::Esc) => Event::Key(Key::Esc),
             TEvent::Key(TKey::Backspace) => Event::Key(Key::Backspace),
             TEvent::Key(TKey::Left) => Event::Key(Key::Left),
@@ -123,6 +193,10 @@ impl Backend {
             TEvent::Key(TKey::Ctrl('c')) => Event::Exit,
             TEvent::Key(TKey::Ctrl(c)) => Event::CtrlChar(c),
             TEvent::Key(TKey::Alt(c)) => Event::AltChar(c),
+            // Termion forgets which button is held during a drag.
+            TEvent::Mouse(_) if bytes.starts_with(SGR_MOUSE) => {
+                ansi::parse_sequence(&bytes)
+            }
             TEvent::Mouse(TMouseEvent::Press(btn, x, y)) => {
                 let position = (x - 1, y - 1).into();
 
@@ -151,7 +225,8 @@ impl Backend {
             TEvent::Mouse(TMouseEvent::Release(x, y))
                 if self.last_button.is_some() =>
             {
-                let event = MouseEvent::Release(self.last_button.unwrap());
+                let button = self.last_button.take().unwrap();
+                let event = MouseEvent::Release(button);
                 let position = (x - 1, y - 1).into();
                 Event::Mouse {
                     event,
@@ -174,12 +249,6 @@ impl Backend {
         }
     }
 
-    fn write<T>(&self, content: T)
-    where
-        T: std::fmt::Display,
-    {
-        write!(self.terminal.borrow_mut(), "{}", content).unwrap();
-    }
 }
 
 impl backend::Backend for Backend {
@@ -187,10 +256,16 @@ impl backend::Backend for Backend {
         "termion"
     }
 
+    fn set_waker(&mut self, waker: backend::Waker) -> bool {
+        *self.waker.lock().unwrap() = Some(waker);
+        true
+    }
+
     fn finish(&mut self) {
         write!(
             self.terminal.get_mut(),
-            "{}{}",
+            "{}\x1B[?1003l\x1B[?2004l{}{}",
+            ansi::DISABLE_KEYBOARD,
             termion::cursor::Show,
             termion::cursor::Goto(1, 1)
         )
@@ -206,35 +281,56 @@ impl backend::Backend for Backend {
         .unwrap();
     }
 
-    fn set_color(&self, color: theme::ColorPair) -> theme::ColorPair {
-        let current_style = self.current_style.get();
+    fn suspend(&mut self) {
+        let terminal = self.terminal.get_mut();
+        // Same sequence as when the `MouseTerminal` is dropped.
+        write!(
+            terminal,
+            "{}{}{}{}\x1B[?2004l",
+            ansi::DISABLE_KEYBOARD,
+            termion::screen::ToMainScreen,
+            termion::cursor::Show,
+            DISABLE_MOUSE
+        )
+        .unwrap();
+        terminal.flush().unwrap();
+        terminal.suspend_raw_mode().unwrap();
+    }
 
-        if current_style != color {
-            self.apply_colors(color);
-            self.current_style.set(color);
-        }
+    fn resume(&mut self) {
+        let terminal = self.terminal.get_mut();
+        terminal.activate_raw_mode().unwrap();
+        write!(
+            terminal,
+            "{}{}{}\x1B[?2004h{}",
+            termion::screen::ToAlternateScreen,
+            termion::cursor::Hide,
+            ENABLE_MOUSE,
+            ansi::ENABLE_KEYBOARD
+        )
+        .unwrap();
+        terminal.flush().unwrap();
+        // The other programs may have written anything.
+        self.screen.get_mut().invalidate();
+    }
 
-        current_style
+    fn set_clipboard(&self, text: &str) -> bool {
+        let mut terminal = self.terminal.borrow_mut();
+        write!(terminal, "{}", clipboard::osc52(text))
+            .and_then(|_| terminal.flush())
+            .is_ok()
+    }
+
+    fn set_color(&self, color: theme::ColorPair) -> theme::ColorPair {
+        self.screen.borrow_mut().set_colors(color)
     }
 
     fn set_effect(&self, effect: theme::Effect) {
-        match effect {
-            theme::Effect::Simple => (),
-            theme::Effect::Reverse => self.write(tstyle::Invert),
-            theme::Effect::Bold => self.write(tstyle::Bold),
-            theme::Effect::Italic => self.write(tstyle::Italic),
-            theme::Effect::Underline => self.write(tstyle::Underline),
-        }
+        self.screen.borrow_mut().set_effect(effect);
     }
 
     fn unset_effect(&self, effect: theme::Effect) {
-        match effect {
-            theme::Effect::Simple => (),
-            theme::Effect::Reverse => self.write(tstyle::NoInvert),
-            theme::Effect::Bold => self.write(tstyle::NoBold),
-            theme::Effect::Italic => self.write(tstyle::NoItalic),
-            theme::Effect::Underline => self.write(tstyle::NoUnderline),
-        }
+        self.screen.borrow_mut().unset_effect(effect);
     }
 
     fn has_colors(&self) -> bool {
@@ -243,95 +339,49 @@ impl backend::Backend for Backend {
     }
 
     fn screen_size(&self) -> Vec2 {
-        // TODO: termion::terminal_size currently requires stdout.
-        // When available, we should try to use /dev/tty instead.
-        let (x, y) = termion::terminal_size().unwrap_or((1, 1));
-        (x, y).into()
+        terminal_size()
     }
 
     fn clear(&self, color: theme::Color) {
-        self.apply_colors(theme::ColorPair {
-            front: color,
-            back: color,
-        });
-
-        self.write(termion::clear::All);
+        let mut screen = self.screen.borrow_mut();
+        screen.resize(self.screen_size());
+        screen.clear(color);
     }
 
     fn refresh(&mut self) {
-        self.terminal.get_mut().flush().unwrap();
+        let output = self.screen.get_mut().render();
+        let terminal = self.terminal.get_mut();
+        write!(terminal, "{}", output).unwrap();
+        terminal.flush().unwrap();
     }
 
     fn print_at(&self, pos: Vec2, text: &str) {
-        write!(
-            self.terminal.borrow_mut(),
-            "{}{}",
-            termion::cursor::Goto(1 + pos.x as u16, 1 + pos.y as u16),
-            text
-        )
-        .unwrap();
+        self.screen.borrow_mut().print(pos, text);
     }
 
-    fn print_at_rep(&self, pos: Vec2, repetitions: usize, text: &str) {
-        if repetitions > 0 {
-            let mut out = self.terminal.borrow_mut();
-            write!(
-                out,
-                "{}{}",
-                termion::cursor::Goto(1 + pos.x as u16, 1 + pos.y as u16),
-                text
-            )
-            .unwrap();
-
-            let mut dupes_left = repetitions - 1;
-            while dupes_left > 0 {
-                write!(out, "{}", text).unwrap();
-                dupes_left -= 1;
+    fn poll_event(&mut self) -> Option<Event> {
+        loop {
+            let (event, bytes) = select! {
+                recv(self.input_receiver) -> event => event.ok()?,
+                recv(self.resize_receiver) -> _ => {
+                    return Some(Event::WindowResize(self.screen_size()))
+                }
+                default => return None,
+            };
+            match self.map_key(event, bytes) {
+                // Moves without any button pressed: views do not use them.
+                Event::Unknown(ref bytes) if bytes.starts_with(SGR_MOUSE) => {
+                    continue
+                }
+                event => return Some(event),
             }
         }
     }
-
-    fn poll_event(&mut self) -> Option<Event> {
-        let event = select! {
-            recv(self.input_receiver) -> event => event.ok(),
-            recv(self.resize_receiver) -> _ => return Some(Event::WindowResize),
-            default => return None,
-        };
-        event.map(|event| self.map_key(event))
-    }
 }
 
-fn with_color<F, R>(clr: theme::Color, f: F) -> R
-where
-    F: FnOnce(&dyn tcolor::Color) -> R,
-{
-    match clr {
-        theme::Color::TerminalDefault => f(&tcolor::Reset),
-        theme::Color::Dark(theme::BaseColor::Black) => f(&tcolor::Black),
-        theme::Color::Dark(theme::BaseColor::Red) => f(&tcolor::Red),
-        theme::Color::Dark(theme::BaseColor::Green) => f(&tcolor::Green),
-        theme::Color::Dark(theme::BaseColor::Yellow) => f(&tcolor::Yellow),
-        theme::Color::Dark(theme::BaseColor::Blue) => f(&tcolor::Blue),
-        theme::Color::Dark(theme::BaseColor::Magenta) => f(&tcolor::Magenta),
-        theme::Color::Dark(theme::BaseColor::Cyan) => f(&tcolor::Cyan),
-        theme::Color::Dark(theme::BaseColor::White) => f(&tcolor::White),
-
-        theme::Color::Light(theme::BaseColor::Black) => f(&tcolor::LightBlack),
-        theme::Color::Light(theme::BaseColor::Red) => f(&tcolor::LightRed),
-        theme::Color::Light(theme::BaseColor::Green) => f(&tcolor::LightGreen),
-        theme::Color::Light(theme::BaseColor::Yellow) => {
-            f(&tcolor::LightYellow)
-        }
-        theme::Color::Light(theme::BaseColor::Blue) => f(&tcolor::LightBlue),
-        theme::Color::Light(theme::BaseColor::Magenta) => {
-            f(&tcolor::LightMagenta)
-        }
-        theme::Color::Light(theme::BaseColor::Cyan) => f(&tcolor::LightCyan),
-        theme::Color::Light(theme::BaseColor::White) => f(&tcolor::LightWhite),
-
-        theme::Color::Rgb(r, g, b) => f(&tcolor::Rgb(r, g, b)),
-        theme::Color::RgbLowRes(r, g, b) => {
-            f(&tcolor::AnsiValue::rgb(r, g, b))
-        }
-    }
+fn terminal_size() -> Vec2 {
+    // TODO: termion::terminal_size currently requires stdout.
+    // When available, we should try to use /dev/tty instead.
+    let (x, y) = termion::terminal_size().unwrap_or((1, 1));
+    (x, y).into()
 }
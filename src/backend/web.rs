@@ -0,0 +1,359 @@
+//! Backend for web pages, rendering to an xterm.js terminal.
+//!
+//! Requires the `web-backend` feature.
+//!
+//! This backend has no dependency: it only produces the escape sequences to
+//! give to the terminal emulator, and receives the browser events from the
+//! page. The glue with the page, usually written with `wasm-bindgen`, goes
+//! through the [`Frontend`] returned along with the backend:
+//!
+//! * DOM keyboard and mouse events are given to `Frontend::key_down`,
+//!   `Frontend::mouse_down`, ... which queue the matching cursive events.
+//! * The output is taken with `Frontend::take_output`, and written to the
+//!   terminal with xterm.js' `Terminal.write`.
+//!
+//! Only the cells that changed are written on each frame.
+//!
+//! Browsers cannot block the page, so `Cursive::run` cannot be used.
+//! Instead, call `Cursive::process_events` and `Cursive::draw_if_needed`
+//! after each browser event, and from a timer to run cursive timers.
+//!
+//! On `wasm32-unknown-unknown`, the standard library has no clock: give the
+//! time to `Frontend::set_time` before each step, for timers, animations
+//! and double clicks to work.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use cursive::Cursive;
+//! # use cursive::backend::web;
+//! # use cursive::views::TextView;
+//! let (backend, frontend) = web::Backend::init((80, 24));
+//! let mut siv = Cursive::new(move || backend);
+//! siv.add_layer(TextView::new("Hello from the browser!"));
+//!
+//! // From the `onKey` callback of xterm.js:
+//! frontend.key_down("ArrowDown", false, false, false);
+//!
+//! // Then, after each event:
+//! frontend.set_time(1250.0); // performance.now()
+//! siv.process_events();
+//! siv.draw_if_needed();
+//! let output = frontend.take_output();
+//! // term.write(output);
+//! # assert!(output.contains("Hello from the browser!"));
+//! ```
+//!
+//! [`Frontend`]: struct.Frontend.html
+
+#![cfg(feature = "web-backend")]
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::backend::{self, ansi};
+use crate::event::{Event, Key, MouseButton, MouseEvent};
+use crate::theme::{Color, ColorDepth, ColorPair, Effect};
+use crate::utils::time;
+use crate::vec::Vec2;
+
+struct State {
+    input: VecDeque<Event>,
+    output: String,
+    size: Vec2,
+    // Button currently pressed, to report drags.
+    pressed: Option<MouseButton>,
+}
+
+/// Backend writing escape sequences for xterm.js.
+///
+/// Created along with its [`Frontend`](struct.Frontend.html).
+pub struct Backend {
+    state: Rc<RefCell<State>>,
+    screen: RefCell<ansi::Screen>,
+}
+
+/// Handle to a web backend, used by the page.
+#[derive(Clone)]
+pub struct Frontend {
+    state: Rc<RefCell<State>>,
+}
+
+impl Backend {
+    /// Creates a new web backend, for a terminal of the given size.
+    ///
+    /// Returns the backend, to give to `Cursive::new`, and a handle for the
+    /// page.
+    pub fn init<S: Into<Vec2>>(
+        size: S,
+    ) -> (Box<dyn backend::Backend>, Frontend) {
+        let size = size.into();
+        let state = Rc::new(RefCell::new(State {
+            input: VecDeque::new(),
+            // Hides the cursor.
+            output: String::from("\x1B[?25l"),
+            size,
+            pressed: None,
+        }));
+
+        let screen = ansi::Screen::new(size, ColorDepth::TrueColor);
+        let backend = Backend {
+            state: Rc::clone(&state),
+            screen: RefCell::new(screen),
+        };
+        (Box::new(backend), Frontend { state })
+    }
+
+    fn write(&self, text: &str) {
+        self.state.borrow_mut().output.push_str(text);
+    }
+}
+
+impl Frontend {
+    /// Queues an event, to be received on the next step.
+    pub fn send<E: Into<Event>>(&self, event: E) {
+        self.state.borrow_mut().input.push_back(event.into());
+    }
+
+    /// Queues a key press, from a DOM `KeyboardEvent`.
+    ///
+    /// `key` is the `key` attribute of the event, like `"a"`, `"Enter"` or
+    /// `"ArrowUp"`. Returns `false` if it is not supported, for example for
+    /// modifier keys: the page can then leave the event to the browser.
+    pub fn key_down(
+        &self, key: &str, ctrl: bool, alt: bool, shift: bool,
+    ) -> bool {
+        let mut chars = key.chars();
+        let event = match (chars.next(), chars.next()) {
+            (Some(c), None) if ctrl => {
+                ansi::char_event(c.to_ascii_lowercase(), ctrl, alt, shift)
+            }
+            (Some(c), None) if alt => Event::AltChar(c),
+            (Some(c), None) => Event::Char(c),
+            _ => match parse_key(key) {
+                Some(key) => ansi::key_event(key, ctrl, alt, shift),
+                None => return false,
+            },
+        };
+        self.send(event);
+        true
+    }
+
+    /// Queues a mouse press, at the given cell.
+    ///
+    /// `button` is the `button` attribute of the DOM `MouseEvent`.
+    pub fn mouse_down(&self, button: u16, x: usize, y: usize) {
+        if let Some(button) = parse_button(button) {
+            self.state.borrow_mut().pressed = Some(button);
+            self.send_mouse(MouseEvent::Press(button), x, y);
+        }
+    }
+
+    /// Queues a mouse release, at the given cell.
+    pub fn mouse_up(&self, button: u16, x: usize, y: usize) {
+        if let Some(button) = parse_button(button) {
+            self.state.borrow_mut().pressed = None;
+            self.send_mouse(MouseEvent::Release(button), x, y);
+        }
+    }
+
+    /// Queues a mouse move, at the given cell.
+    ///
+    /// Moves are only reported while a button is pressed.
+    pub fn mouse_move(&self, x: usize, y: usize) {
+        let pressed = self.state.borrow().pressed;
+        if let Some(button) = pressed {
+            self.send_mouse(MouseEvent::Hold(button), x, y);
+        }
+    }
+
+    /// Queues a wheel step, at the given cell.
+    ///
+    /// `delta_y` is the `deltaY` attribute of the DOM `WheelEvent`.
+    pub fn wheel(&self, delta_y: f64, x: usize, y: usize) {
+        let event = if delta_y < 0.0 {
+            MouseEvent::WheelUp
+        } else {
+            MouseEvent::WheelDown
+        };
+        self.send_mouse(event, x, y);
+    }
+
+    /// Queues text pasted in the page.
+    pub fn paste(&self, text: &str) {
+        self.send(Event::Paste(text.to_string()));
+    }
+
+    /// Changes the size of the terminal, and queues an
+    /// `Event::WindowResize`.
+    pub fn resize(&self, cols: usize, rows: usize) {
+        let size = Vec2::new(cols, rows);
+        self.state.borrow_mut().size = size;
+        self.send(Event::WindowResize(size));
+    }
+
+    /// Sets the current time, in milliseconds, as given by the DOM
+    /// `performance.now()`.
+    ///
+    /// Only needed on `wasm32-unknown-unknown`, where cursive has no other
+    /// clock; elsewhere, this does nothing.
+    pub fn set_time(&self, milliseconds: f64) {
+        if milliseconds.is_finite() && milliseconds >= 0.0 {
+            time::set_now(Duration::from_secs_f64(milliseconds / 1000.0));
+        }
+    }
+
+    /// Returns the text written since the last call, to give to xterm.js.
+    pub fn take_output(&self) -> String {
+        std::mem::take(&mut self.state.borrow_mut().output)
+    }
+
+    fn send_mouse(&self, event: MouseEvent, x: usize, y: usize) {
+        self.send(Event::Mouse {
+            offset: Vec2::zero(),
+            position: Vec2::new(x, y),
+            event,
+        });
+    }
+}
+
+impl backend::Backend for Backend {
+    fn name(&self) -> &str {
+        "web"
+    }
+
+    fn uses_terminal(&self) -> bool {
+        false
+    }
+
+    fn poll_event(&mut self) -> Option<Event> {
+        self.state.borrow_mut().input.pop_front()
+    }
+
+    fn finish(&mut self) {
+        self.write("\x1B[0m\x1B[2J\x1B[?25h");
+    }
+
+    fn refresh(&mut self) {
+        let output = self.screen.get_mut().render();
+        self.write(&output);
+    }
+
+    fn has_colors(&self) -> bool {
+        true
+    }
+
+    fn screen_size(&self) -> Vec2 {
+        self.state.borrow().size
+    }
+
+    fn print_at(&self, pos: Vec2, text: &str) {
+        self.screen.borrow_mut().print(pos, text);
+    }
+
+    fn clear(&self, color: Color) {
+        let mut screen = self.screen.borrow_mut();
+        screen.resize(self.screen_size());
+        screen.clear(color);
+    }
+
+    fn set_color(&self, colors: ColorPair) -> ColorPair {
+        self.screen.borrow_mut().set_colors(colors)
+    }
+
+    fn set_effect(&self, effect: Effect) {
+        self.screen.borrow_mut().set_effect(effect);
+    }
+
+    fn unset_effect(&self, effect: Effect) {
+        self.screen.borrow_mut().unset_effect(effect);
+    }
+}
+
+// Parses the name of a non-printable DOM key.
+fn parse_key(key: &str) -> Option<Key> {
+    let key = match key {
+        "Enter" => Key::Enter,
+        "Tab" => Key::Tab,
+        "Backspace" => Key::Backspace,
+        "Escape" => Key::Esc,
+        "ArrowLeft" => Key::Left,
+        "ArrowRight" => Key::Right,
+        "ArrowUp" => Key::Up,
+        "ArrowDown" => Key::Down,
+        "Insert" => Key::Ins,
+        "Delete" => Key::Del,
+        "Home" => Key::Home,
+        "End" => Key::End,
+        "PageUp" => Key::PageUp,
+        "PageDown" => Key::PageDown,
+        "Pause" => Key::PauseBreak,
+        _ => match key.get(1..).map(str::parse) {
+            Some(Ok(n)) if key.starts_with('F') && (1..=12).contains(&n) => {
+                Key::from_f(n)
+            }
+            _ => return None,
+        },
+    };
+    Some(key)
+}
+
+fn parse_button(button: u16) -> Option<MouseButton> {
+    match button {
+        0 => Some(MouseButton::Left),
+        1 => Some(MouseButton::Middle),
+        2 => Some(MouseButton::Right),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::views::{Checkbox, LinearLayout, TextView};
+    use crate::Cursive;
+
+    #[test]
+    fn browser_input() {
+        let (backend, frontend) = Backend::init((20, 4));
+        let mut siv = Cursive::new(move || backend);
+        siv.add_fullscreen_layer(
+            LinearLayout::vertical()
+                .child(TextView::new("Hello"))
+                .child(Checkbox::new()),
+        );
+        siv.refresh();
+        let output = frontend.take_output();
+        assert!(output.contains("\x1B[1;1HHello"));
+        assert!(output.contains("[ ]"));
+
+        assert!(!frontend.key_down("Shift", false, false, true));
+        assert!(frontend.key_down("ArrowDown", false, false, false));
+        assert!(frontend.key_down(" ", false, false, false));
+        siv.process_events();
+        siv.draw_if_needed();
+        assert!(frontend.take_output().contains("X"));
+
+        frontend.key_down("q", true, false, false);
+        frontend.key_down("K", true, false, true);
+        frontend.key_down("F5", false, false, true);
+        frontend.mouse_down(0, 3, 1);
+        frontend.mouse_move(4, 1);
+        let state = frontend.state.borrow();
+        let events: Vec<_> = state.input.iter().take(3).cloned().collect();
+        assert_eq!(
+            events,
+            vec![
+                Event::CtrlChar('q'),
+                Event::CtrlShiftChar('k'),
+                Event::Shift(Key::F5)
+            ]
+        );
+        assert_eq!(
+            state.input.back().and_then(Event::mouse_position),
+            Some(Vec2::new(4, 1))
+        );
+    }
+}
@@ -0,0 +1,527 @@
+//! Backend using the Windows console API.
+//!
+//! Requires the `wincon-backend` feature, and only builds on Windows.
+//!
+//! Input is read with `ReadConsoleInputW`, which gives each key with its
+//! modifiers, the mouse buttons and moves, and the window resizes. It is
+//! only read when the console has pending input, so polling never blocks
+//! and no thread outlives the backend. The console has no bracketed paste:
+//! a long run of text in a single read, more than anyone types between two
+//! polls, is sent as one `Event::Paste` instead. Held keys never are.
+//!
+//! Output goes through the virtual terminal sequences of the console, with
+//! true colors: this needs Windows 10 (version 1703) or later, in `cmd`,
+//! PowerShell or Windows Terminal. Only the cells that changed are written
+//! on each frame.
+
+#![cfg(all(windows, feature = "wincon-backend"))]
+
+use std::cell::RefCell;
+use std::char;
+use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::mem;
+
+use winapi::shared::minwindef::{DWORD, WORD};
+use winapi::um::consoleapi::{
+    GetConsoleMode, GetNumberOfConsoleInputEvents, ReadConsoleInputW,
+    SetConsoleMode,
+};
+use winapi::um::handleapi::INVALID_HANDLE_VALUE;
+use winapi::um::processenv::GetStdHandle;
+use winapi::um::winbase::{STD_INPUT_HANDLE, STD_OUTPUT_HANDLE};
+use winapi::um::wincon::{
+    GetConsoleScreenBufferInfo, CONSOLE_SCREEN_BUFFER_INFO,
+    ENABLE_EXTENDED_FLAGS, ENABLE_MOUSE_INPUT,
+    ENABLE_VIRTUAL_TERMINAL_PROCESSING, ENABLE_WINDOW_INPUT,
+    FROM_LEFT_1ST_BUTTON_PRESSED, FROM_LEFT_2ND_BUTTON_PRESSED, INPUT_RECORD,
+    KEY_EVENT, KEY_EVENT_RECORD, LEFT_ALT_PRESSED, LEFT_CTRL_PRESSED,
+    MOUSE_EVENT, MOUSE_EVENT_RECORD, MOUSE_MOVED, MOUSE_WHEELED,
+    RIGHTMOST_BUTTON_PRESSED, RIGHT_ALT_PRESSED, RIGHT_CTRL_PRESSED,
+    SHIFT_PRESSED, WINDOW_BUFFER_SIZE_EVENT,
+};
+use winapi::um::winnt::HANDLE;
+use winapi::um::winuser::{
+    VK_BACK, VK_DELETE, VK_DOWN, VK_END, VK_ESCAPE, VK_F1, VK_F12, VK_HOME,
+    VK_INSERT, VK_LEFT, VK_NEXT, VK_PAUSE, VK_PRIOR, VK_RETURN, VK_RIGHT,
+    VK_TAB, VK_UP,
+};
+
+use crate::backend::{self, ansi};
+use crate::event::{Event, Key, MouseButton, MouseEvent};
+use crate::theme::{Color, ColorDepth, ColorPair, Effect};
+use crate::vec::Vec2;
+
+const BUTTONS: DWORD = FROM_LEFT_1ST_BUTTON_PRESSED
+    | FROM_LEFT_2ND_BUTTON_PRESSED
+    | RIGHTMOST_BUTTON_PRESSED;
+
+/// Backend using the Windows console.
+pub struct Backend {
+    input: HANDLE,
+    output: HANDLE,
+    // Modes to restore when leaving.
+    input_mode: DWORD,
+    output_mode: DWORD,
+    input_state: InputState,
+    // Events read but not polled yet.
+    events: VecDeque<Event>,
+    screen: RefCell<ansi::Screen>,
+}
+
+impl Backend {
+    /// Creates a new backend, using the console of the process.
+    ///
+    /// Fails if the standard input or output is not a console, or if the
+    /// console does not support virtual terminal sequences.
+    pub fn init() -> io::Result<Box<dyn backend::Backend>> {
+        let input = std_handle(STD_INPUT_HANDLE)?;
+        let output = std_handle(STD_OUTPUT_HANDLE)?;
+        let input_mode = console_mode(input)?;
+        let output_mode = console_mode(output)?;
+
+        let backend = Backend {
+            input,
+            output,
+            input_mode,
+            output_mode,
+            input_state: InputState::default(),
+            events: VecDeque::new(),
+            screen: RefCell::new(ansi::Screen::new(
+                window_size(output),
+                ColorDepth::TrueColor,
+            )),
+        };
+        backend.enter()?;
+        Ok(Box::new(backend))
+    }
+
+    // Sets the console modes, and switches to the alternate screen.
+    fn enter(&self) -> io::Result<()> {
+        // Keys are given one by one, including `Ctrl-C`, and the mouse is
+        // not used to select text.
+        set_console_mode(
+            self.input,
+            ENABLE_MOUSE_INPUT | ENABLE_WINDOW_INPUT | ENABLE_EXTENDED_FLAGS,
+        )?;
+        let output_mode =
+            self.output_mode | ENABLE_VIRTUAL_TERMINAL_PROCESSING;
+        if let Err(err) = set_console_mode(self.output, output_mode) {
+            let _ = set_console_mode(self.input, self.input_mode);
+            return Err(err);
+        }
+
+        // Alternate screen, hidden cursor.
+        self.write("\x1B[?1049h\x1B[?25l");
+        Ok(())
+    }
+
+    // Gives the console back as it was.
+    fn leave(&self) {
+        self.write("\x1B[0m\x1B[2J\x1B[?25h\x1B[?1049l");
+        let _ = set_console_mode(self.input, self.input_mode);
+        let _ = set_console_mode(self.output, self.output_mode);
+    }
+
+    fn write(&self, text: &str) {
+        let mut stdout = io::stdout();
+        let _ = stdout.write_all(text.as_bytes());
+        let _ = stdout.flush();
+    }
+
+    // Reads all the pending console input, without blocking.
+    fn read_input(&mut self) {
+        let mut events = Vec::new();
+        self.input_state.repeated = false;
+        while self.read_records(&mut events) {}
+        let repeated = self.input_state.repeated;
+        self.events.extend(merge_paste(events, repeated));
+    }
+
+    // Reads some of the pending console input.
+    //
+    // Returns `false` once there is nothing left to read.
+    fn read_records(&mut self, events: &mut Vec<Event>) -> bool {
+        let mut pending = 0;
+        let ok =
+            unsafe { GetNumberOfConsoleInputEvents(self.input, &mut pending) };
+        if ok == 0 || pending == 0 {
+            return false;
+        }
+
+        let mut records: [INPUT_RECORD; 64] = unsafe { mem::zeroed() };
+        let len = pending.min(records.len() as DWORD);
+        let mut read = 0;
+        let ok = unsafe {
+            ReadConsoleInputW(self.input, records.as_mut_ptr(), len, &mut read)
+        };
+        if ok == 0 {
+            return false;
+        }
+
+        for record in &records[..read as usize] {
+            match record.EventType {
+                KEY_EVENT => self
+                    .input_state
+                    .key(unsafe { record.Event.KeyEvent() }, events),
+                MOUSE_EVENT => {
+                    let mouse = unsafe { record.Event.MouseEvent() };
+                    events.extend(self.input_state.mouse(mouse));
+                }
+                WINDOW_BUFFER_SIZE_EVENT => {
+                    events.push(Event::WindowResize(window_size(self.output)))
+                }
+                _ => (),
+            }
+        }
+        read > 0
+    }
+}
+
+// Number of characters read at once above which they are a paste.
+//
+// Keys typed while the application was busy stay well below this.
+const PASTE_MIN_LEN: usize = 16;
+
+// Merges the characters read at once into a paste.
+//
+// Only when everything read is text, and more than anyone could type
+// between two polls: other keys are still sent one by one. Held keys,
+// which the console repeats, are never a paste.
+fn merge_paste(events: Vec<Event>, repeated: bool) -> Vec<Event> {
+    if repeated || events.len() < PASTE_MIN_LEN {
+        return events;
+    }
+
+    let mut text = String::new();
+    for event in &events {
+        match *event {
+            Event::Char(c) => text.push(c),
+            Event::Key(Key::Enter) => text.push('\n'),
+            Event::Key(Key::Tab) => text.push('\t'),
+            _ => return events,
+        }
+    }
+    vec![Event::Paste(text)]
+}
+
+impl backend::Backend for Backend {
+    fn name(&self) -> &str {
+        "wincon"
+    }
+
+    fn poll_event(&mut self) -> Option<Event> {
+        if self.events.is_empty() {
+            self.read_input();
+        }
+        self.events.pop_front()
+    }
+
+    fn finish(&mut self) {
+        self.leave();
+    }
+
+    fn suspend(&mut self) {
+        self.leave();
+    }
+
+    fn resume(&mut self) {
+        if self.enter().is_err() {
+            log::warn!("Could not set the console mode");
+        }
+        // The other programs may have written anything.
+        self.screen.get_mut().invalidate();
+    }
+
+    fn refresh(&mut self) {
+        let output = self.screen.get_mut().render();
+        self.write(&output);
+    }
+
+    fn has_colors(&self) -> bool {
+        true
+    }
+
+    fn screen_size(&self) -> Vec2 {
+        window_size(self.output)
+    }
+
+    fn print_at(&self, pos: Vec2, text: &str) {
+        self.screen.borrow_mut().print(pos, text);
+    }
+
+    fn clear(&self, color: Color) {
+        let mut screen = self.screen.borrow_mut();
+        screen.resize(self.screen_size());
+        screen.clear(color);
+    }
+
+    fn set_color(&self, colors: ColorPair) -> ColorPair {
+        self.screen.borrow_mut().set_colors(colors)
+    }
+
+    fn set_effect(&self, effect: Effect) {
+        self.screen.borrow_mut().set_effect(effect);
+    }
+
+    fn unset_effect(&self, effect: Effect) {
+        self.screen.borrow_mut().unset_effect(effect);
+    }
+}
+
+fn std_handle(which: DWORD) -> io::Result<HANDLE> {
+    let handle = unsafe { GetStdHandle(which) };
+    if handle == INVALID_HANDLE_VALUE || handle.is_null() {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(handle)
+    }
+}
+
+fn console_mode(handle: HANDLE) -> io::Result<DWORD> {
+    let mut mode = 0;
+    if unsafe { GetConsoleMode(handle, &mut mode) } == 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(mode)
+    }
+}
+
+fn set_console_mode(handle: HANDLE, mode: DWORD) -> io::Result<()> {
+    if unsafe { SetConsoleMode(handle, mode) } == 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+// Size of the visible part of the console, not of its whole buffer.
+fn window_size(output: HANDLE) -> Vec2 {
+    let mut info: CONSOLE_SCREEN_BUFFER_INFO = unsafe { mem::zeroed() };
+    if unsafe { GetConsoleScreenBufferInfo(output, &mut info) } == 0 {
+        return Vec2::new(80, 24);
+    }
+    let window = info.srWindow;
+    Vec2::new(
+        (window.Right - window.Left + 1).max(0) as usize,
+        (window.Bottom - window.Top + 1).max(0) as usize,
+    )
+}
+
+#[derive(Default)]
+struct InputState {
+    // Buttons pressed in the last mouse event.
+    buttons: DWORD,
+    // First half of a character outside the basic plane.
+    surrogate: Option<u16>,
+    // `true` if a key record of the current read was repeated, when a key
+    // is held: this is not a paste.
+    repeated: bool,
+}
+
+impl InputState {
+    fn key(&mut self, key: &KEY_EVENT_RECORD, events: &mut Vec<Event>) {
+        if key.bKeyDown == 0 {
+            return;
+        }
+
+        let unit = unsafe { *key.uChar.UnicodeChar() };
+        let c = match (self.surrogate.take(), unit) {
+            (_, 0xD800..=0xDBFF) => {
+                self.surrogate = Some(unit);
+                return;
+            }
+            (Some(high), 0xDC00..=0xDFFF) => {
+                let mut chars = char::decode_utf16(vec![high, unit]);
+                chars.next().and_then(Result::ok)
+            }
+            (_, unit) => char::from_u32(u32::from(unit)),
+        };
+
+        let state = key.dwControlKeyState;
+        if let Some(event) = key_event(key.wVirtualKeyCode, c, state) {
+            self.repeated |= key.wRepeatCount > 1;
+            for _ in 0..key.wRepeatCount.max(1) {
+                events.push(event.clone());
+            }
+        }
+    }
+
+    fn mouse(&mut self, mouse: &MOUSE_EVENT_RECORD) -> Option<Event> {
+        let pressed = mouse.dwButtonState & BUTTONS;
+        let previous = mem::replace(&mut self.buttons, pressed);
+
+        let event = if mouse.dwEventFlags & MOUSE_WHEELED != 0 {
+            // The high word is the signed distance, positive upward.
+            if (mouse.dwButtonState as i32) >> 16 > 0 {
+                MouseEvent::WheelUp
+            } else {
+                MouseEvent::WheelDown
+            }
+        } else if let Some(button) = mouse_button(pressed & !previous) {
+            MouseEvent::Press(button)
+        } else if let Some(button) = mouse_button(previous & !pressed) {
+            MouseEvent::Release(button)
+        } else if mouse.dwEventFlags & MOUSE_MOVED != 0 {
+            // Moves without a button are not reported.
+            MouseEvent::Hold(mouse_button(pressed)?)
+        } else {
+            return None;
+        };
+
+        let position = mouse.dwMousePosition;
+        let x = position.X.max(0) as usize;
+        let y = position.Y.max(0) as usize;
+        Some(Event::Mouse {
+            offset: Vec2::zero(),
+            position: Vec2::new(x, y),
+            event,
+        })
+    }
+}
+
+// Translates a key press, given its virtual key code, the character it
+// typed, and the state of the modifiers.
+fn key_event(vk: WORD, c: Option<char>, state: DWORD) -> Option<Event> {
+    let ctrl = state & (LEFT_CTRL_PRESSED | RIGHT_CTRL_PRESSED) != 0;
+    let alt = state & (LEFT_ALT_PRESSED | RIGHT_ALT_PRESSED) != 0;
+    let shift = state & SHIFT_PRESSED != 0;
+
+    if let Some(key) = special_key(vk) {
+        return Some(ansi::key_event(key, ctrl, alt, shift));
+    }
+
+    match c.filter(|c| !c.is_control()) {
+        // AltGr is reported as Ctrl-Alt, with the composed character.
+        Some(c) if ctrl && alt => Some(Event::Char(c)),
+        Some(c) if alt => Some(Event::AltChar(c)),
+        Some(c) if !ctrl => Some(Event::Char(c)),
+        // With Ctrl, the console gives a control code: use the key instead.
+        _ if ctrl => match (vk as u8 as char).to_ascii_lowercase() {
+            'c' if !alt && !shift => Some(Event::Exit),
+            c @ 'a'..='z' | c @ '0'..='9' => {
+                Some(ansi::char_event(c, ctrl, alt, shift))
+            }
+            _ => None,
+        },
+        // Modifier keys alone.
+        _ => None,
+    }
+}
+
+fn special_key(vk: WORD) -> Option<Key> {
+    let key = match i32::from(vk) {
+        VK_RETURN => Key::Enter,
+        VK_TAB => Key::Tab,
+        VK_BACK => Key::Backspace,
+        VK_ESCAPE => Key::Esc,
+        VK_LEFT => Key::Left,
+        VK_RIGHT => Key::Right,
+        VK_UP => Key::Up,
+        VK_DOWN => Key::Down,
+        VK_INSERT => Key::Ins,
+        VK_DELETE => Key::Del,
+        VK_HOME => Key::Home,
+        VK_END => Key::End,
+        VK_PRIOR => Key::PageUp,
+        VK_NEXT => Key::PageDown,
+        VK_PAUSE => Key::PauseBreak,
+        vk @ VK_F1..=VK_F12 => Key::from_f((vk - VK_F1 + 1) as u8),
+        _ => return None,
+    };
+    Some(key)
+}
+
+fn mouse_button(buttons: DWORD) -> Option<MouseButton> {
+    if buttons & FROM_LEFT_1ST_BUTTON_PRESSED != 0 {
+        Some(MouseButton::Left)
+    } else if buttons & RIGHTMOST_BUTTON_PRESSED != 0 {
+        Some(MouseButton::Right)
+    } else if buttons & FROM_LEFT_2ND_BUTTON_PRESSED != 0 {
+        Some(MouseButton::Middle)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn translate_keys() {
+        let ctrl = LEFT_CTRL_PRESSED;
+        let alt_gr = LEFT_CTRL_PRESSED | RIGHT_ALT_PRESSED;
+        let vk = |c: u8| WORD::from(c);
+
+        assert_eq!(key_event(vk(b'A'), Some('a'), 0), Some(Event::Char('a')));
+        assert_eq!(
+            key_event(vk(b'A'), Some('\u{1}'), ctrl),
+            Some(Event::CtrlChar('a'))
+        );
+        assert_eq!(
+            key_event(vk(b'A'), Some('\u{1}'), ctrl | SHIFT_PRESSED),
+            Some(Event::CtrlShiftChar('a'))
+        );
+        assert_eq!(
+            key_event(vk(b'C'), Some('\u{3}'), ctrl),
+            Some(Event::Exit)
+        );
+        assert_eq!(
+            key_event(vk(b'Q'), Some('@'), alt_gr),
+            Some(Event::Char('@'))
+        );
+        assert_eq!(
+            key_event(VK_TAB as WORD, Some('\t'), SHIFT_PRESSED),
+            Some(Event::Shift(Key::Tab))
+        );
+        assert_eq!(
+            key_event(VK_F1 as WORD + 4, None, LEFT_ALT_PRESSED),
+            Some(Event::Alt(Key::F5))
+        );
+        // Shift alone.
+        assert_eq!(key_event(0x10, None, SHIFT_PRESSED), None);
+    }
+
+    #[test]
+    fn paste() {
+        // Fast typing is not a paste.
+        let typed = vec![Event::Char('q'), Event::Char('q')];
+        assert_eq!(merge_paste(typed.clone(), false), typed);
+
+        let mut pasted: Vec<Event> = "pasted text\nwith lines"
+            .chars()
+            .map(|c| match c {
+                '\n' => Event::Key(Key::Enter),
+                c => Event::Char(c),
+            })
+            .collect();
+        assert_eq!(
+            merge_paste(pasted.clone(), false),
+            vec![Event::Paste("pasted text\nwith lines".into())]
+        );
+
+        pasted.push(Event::CtrlChar('s'));
+        assert_eq!(merge_paste(pasted.clone(), false), pasted);
+    }
+
+    #[test]
+    fn repeated_key() {
+        // Enter, held long enough to be repeated 20 times.
+        let mut record: KEY_EVENT_RECORD = unsafe { mem::zeroed() };
+        record.bKeyDown = 1;
+        record.wRepeatCount = 20;
+        record.wVirtualKeyCode = VK_RETURN as WORD;
+        unsafe { *record.uChar.UnicodeChar_mut() = u16::from(b'\r') };
+
+        let mut state = InputState::default();
+        let mut events = Vec::new();
+        state.key(&record, &mut events);
+        assert!(state.repeated);
+
+        // Even above the paste length, each press stays a key.
+        let keys = vec![Event::Key(Key::Enter); 20];
+        assert_eq!(events, keys);
+        assert_eq!(merge_paste(events, state.repeated), keys);
+    }
+}
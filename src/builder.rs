@@ -0,0 +1,622 @@
+//! Build a view tree from a toml document.
+//!
+//! A [`Builder`] reads a description of the views: each view is a table
+//! with its `type`, and attributes depending on this type. This lets the
+//! layout change without recompiling the application, while callbacks stay
+//! in the code: they are registered by name, and referenced from the
+//! document.
+//!
+//! ```toml
+//! type = "Dialog"
+//! title = "Login"
+//! buttons = [
+//!     { label = "Ok", callback = "login" },
+//!     { label = "Quit", callback = "quit" },
+//! ]
+//!
+//! [content]
+//! type = "ListView"
+//! children = [
+//!     { label = "Name", type = "EditView", id = "name", min_width = 20 },
+//!     { label = "Password", type = "EditView", secret = true },
+//! ]
+//! ```
+//!
+//! Any view accepts these attributes:
+//!
+//! * `id`: wraps the view in an `IdView`, to find it from the code.
+//! * `width`, `height`: fixed size.
+//! * `min_width`, `min_height`, `max_width`, `max_height`: bounds on the
+//!   size.
+//! * `full_width`, `full_height`: takes all the available space.
+//! * `scrollable`: wraps the view in a `ScrollView`.
+//!
+//! The following view types are available, with their own attributes:
+//!
+//! * `TextView`: `content`.
+//! * `EditView`: `content`, `secret`, `max_content_width`, `on_submit`.
+//! * `TextArea`: `content`.
+//! * `Button`: `label`, `callback`.
+//! * `Checkbox`: `checked`.
+//! * `SelectView`: `items` (a list of strings), `on_submit`.
+//! * `DummyView`.
+//! * `LinearLayout`: `orientation` (`"horizontal"` or `"vertical"`),
+//!   `children`.
+//! * `ListView`: `children`, each with a `label`.
+//! * `Dialog`: `title`, `content`, `buttons` (each with a `label` and a
+//!   `callback`).
+//! * `Panel`: `title`, `content`.
+//! * `ScrollView`: `content`.
+//!
+//! Other attributes are rejected with `Error::UnknownAttribute`, to catch
+//! typos. Other types can be added with [`Builder::add_view_type`].
+//!
+//! Documents in other formats, like yaml or json, can be converted to a
+//! `toml::Value` and given to `Builder::build`.
+//!
+//! [`Builder`]: struct.Builder.html
+//! [`Builder::add_view_type`]: struct.Builder.html#method.add_view_type
+
+use std::error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+use std::rc::Rc;
+
+use hashbrown::HashMap;
+use toml::value::{Table, Value};
+
+use crate::direction::Orientation;
+use crate::event::Callback;
+use crate::view::{Scrollable, SizeConstraint, View};
+use crate::views::{self, ViewBox};
+use crate::Cursive;
+
+/// Possible error returned when building views.
+#[derive(Debug)]
+pub enum Error {
+    /// An error occured when reading the file.
+    Io(io::Error),
+    /// An error occured when parsing the toml content.
+    Parse(toml::de::Error),
+    /// A view is not a table, or has no `type`.
+    InvalidView,
+    /// A view type is not known.
+    UnknownViewType(String),
+    /// A required attribute is missing.
+    MissingAttribute(String),
+    /// An attribute is not known for this view type.
+    UnknownAttribute(String),
+    /// An attribute does not have the expected type or value.
+    InvalidAttribute(String),
+    /// A callback name was not registered.
+    UnknownCallback(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Error::Io(ref err) => write!(f, "{}", err),
+            Error::Parse(ref err) => write!(f, "{}", err),
+            Error::InvalidView => {
+                write!(f, "a view is not a table with a `type`")
+            }
+            Error::UnknownViewType(ref name) => {
+                write!(f, "unknown view type `{}`", name)
+            }
+            Error::MissingAttribute(ref key) => {
+                write!(f, "missing attribute `{}`", key)
+            }
+            Error::UnknownAttribute(ref key) => {
+                write!(f, "unknown attribute `{}`", key)
+            }
+            Error::InvalidAttribute(ref key) => {
+                write!(f, "invalid value for attribute `{}`", key)
+            }
+            Error::UnknownCallback(ref name) => {
+                write!(f, "unknown callback `{}`", name)
+            }
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Io(ref err) => Some(err),
+            Error::Parse(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(err: toml::de::Error) -> Self {
+        Error::Parse(err)
+    }
+}
+
+type ViewConstructor = dyn Fn(&Table, &Builder) -> Result<ViewBox, Error>;
+
+// Attributes any view accepts, applied by `Builder::wrap`.
+const COMMON_ATTRIBUTES: [&str; 11] = [
+    "type",
+    "id",
+    "width",
+    "height",
+    "min_width",
+    "min_height",
+    "max_width",
+    "max_height",
+    "full_width",
+    "full_height",
+    "scrollable",
+];
+
+// Attributes of each standard view type, besides the common ones.
+//
+// Returns `None` if `view_type` is not a standard type.
+fn standard_attributes(view_type: &str) -> Option<&'static [&'static str]> {
+    Some(match view_type {
+        "TextView" | "TextArea" => &["content"],
+        "EditView" => &["content", "secret", "max_content_width", "on_submit"],
+        "Button" => &["label", "callback"],
+        "Checkbox" => &["checked"],
+        "SelectView" => &["items", "on_submit"],
+        "DummyView" => &[],
+        "LinearLayout" => &["orientation", "children"],
+        "ListView" => &["children"],
+        "Dialog" => &["title", "content", "buttons"],
+        "Panel" => &["title", "content"],
+        "ScrollView" => &["content"],
+        _ => return None,
+    })
+}
+
+/// Builds views from a toml description.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::builder::Builder;
+/// # use cursive::Cursive;
+/// # use cursive::views::EditView;
+/// let builder = Builder::new().callback("quit", Cursive::quit);
+/// let view = builder.load_toml(r#"
+///     type = "Dialog"
+///     title = "Hello"
+///     buttons = [{ label = "Quit", callback = "quit" }]
+///
+///     [content]
+///     type = "EditView"
+///     id = "name"
+///     width = 20
+/// "#).unwrap();
+///
+/// let mut siv = Cursive::dummy();
+/// siv.add_layer(view);
+/// assert!(siv.find_id::<EditView>("name").is_some());
+/// ```
+#[derive(Clone, Default)]
+pub struct Builder {
+    callbacks: HashMap<String, Callback>,
+    view_types: HashMap<String, Rc<ViewConstructor>>,
+}
+
+impl Builder {
+    /// Creates a new builder, with only the standard view types.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a callback, to be referenced by `name` in the document.
+    pub fn add_callback<S, F>(&mut self, name: S, callback: F)
+    where
+        S: Into<String>,
+        F: Fn(&mut Cursive) + 'static,
+    {
+        self.callbacks
+            .insert(name.into(), Callback::from_fn(callback));
+    }
+
+    /// Registers a callback, to be referenced by `name` in the document.
+    ///
+    /// Chainable variant.
+    pub fn callback<S, F>(mut self, name: S, callback: F) -> Self
+    where
+        S: Into<String>,
+        F: Fn(&mut Cursive) + 'static,
+    {
+        self.add_callback(name, callback);
+        self
+    }
+
+    /// Registers a view type, or replaces a standard one.
+    ///
+    /// `constructor` is given the attributes of the view, and the builder to
+    /// build children or find callbacks. It should end with `Builder::wrap`,
+    /// to apply the common attributes like `id` or `width`, and can call
+    /// `Builder::check_attributes` to reject unknown attributes.
+    pub fn add_view_type<S, F>(&mut self, name: S, constructor: F)
+    where
+        S: Into<String>,
+        F: Fn(&Table, &Builder) -> Result<ViewBox, Error> + 'static,
+    {
+        self.view_types.insert(name.into(), Rc::new(constructor));
+    }
+
+    /// Registers a view type, or replaces a standard one.
+    ///
+    /// Chainable variant.
+    pub fn view_type<S, F>(mut self, name: S, constructor: F) -> Self
+    where
+        S: Into<String>,
+        F: Fn(&Table, &Builder) -> Result<ViewBox, Error> + 'static,
+    {
+        self.add_view_type(name, constructor);
+        self
+    }
+
+    /// Builds the view described in the given toml file.
+    pub fn load_file<P: AsRef<Path>>(
+        &self, filename: P,
+    ) -> Result<ViewBox, Error> {
+        let mut content = String::new();
+        File::open(filename)?.read_to_string(&mut content)?;
+
+        self.load_toml(&content)
+    }
+
+    /// Builds the view described in the given toml content.
+    pub fn load_toml(&self, content: &str) -> Result<ViewBox, Error> {
+        let value: Value = toml::de::from_str(content)?;
+        self.build(&value)
+    }
+
+    /// Builds the view described by `value`.
+    pub fn build(&self, value: &Value) -> Result<ViewBox, Error> {
+        let table = value.as_table().ok_or(Error::InvalidView)?;
+        let view_type = table
+            .get("type")
+            .and_then(Value::as_str)
+            .ok_or(Error::InvalidView)?;
+
+        match self.view_types.get(view_type) {
+            Some(constructor) => constructor(table, self),
+            None => self.build_standard(view_type, table),
+        }
+    }
+
+    /// Returns the callback registered with the given name.
+    pub fn get_callback(&self, name: &str) -> Result<Callback, Error> {
+        self.callbacks
+            .get(name)
+            .cloned()
+            .ok_or_else(|| Error::UnknownCallback(name.to_string()))
+    }
+
+    /// Returns `Error::UnknownAttribute` if `table` has an attribute which
+    /// is neither in `known`, nor one of the common attributes.
+    pub fn check_attributes(
+        &self, table: &Table, known: &[&str],
+    ) -> Result<(), Error> {
+        let is_known = |key: &str| {
+            COMMON_ATTRIBUTES.contains(&key) || known.contains(&key)
+        };
+        match table.keys().find(|key| !is_known(key)) {
+            Some(key) => Err(Error::UnknownAttribute(key.clone())),
+            None => Ok(()),
+        }
+    }
+
+    // Returns the callback referenced by the given attribute, if any.
+    fn callback_attr(
+        &self, table: &Table, key: &str,
+    ) -> Result<Option<Callback>, Error> {
+        match str_attr(table, key)? {
+            Some(name) => self.get_callback(name).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    // Builds the view in the given attribute.
+    fn child(&self, table: &Table, key: &str) -> Result<ViewBox, Error> {
+        let value = table
+            .get(key)
+            .ok_or_else(|| Error::MissingAttribute(key.to_string()))?;
+        self.build(value)
+    }
+
+    fn build_standard(
+        &self, view_type: &str, table: &Table,
+    ) -> Result<ViewBox, Error> {
+        let unknown_type = || Error::UnknownViewType(view_type.to_string());
+        let known = standard_attributes(view_type).ok_or_else(unknown_type)?;
+        self.check_attributes(table, known)?;
+
+        // Text content, for views which have one.
+        let text =
+            || str_attr(table, "content").map(Option::unwrap_or_default);
+
+        match view_type {
+            "TextView" => self.wrap(views::TextView::new(text()?), table),
+            "EditView" => {
+                let mut view = views::EditView::new().content(text()?);
+                if bool_attr(table, "secret")? {
+                    view.set_secret(true);
+                }
+                if let Some(width) = usize_attr(table, "max_content_width")? {
+                    view.set_max_content_width(Some(width));
+                }
+                if let Some(cb) = self.callback_attr(table, "on_submit")? {
+                    view.set_on_submit(move |s, _| cb(s));
+                }
+                self.wrap(view, table)
+            }
+            "TextArea" => {
+                self.wrap(views::TextArea::new().content(text()?), table)
+            }
+            "Button" => {
+                let label = str_attr(table, "label")?.unwrap_or("");
+                let cb = self
+                    .callback_attr(table, "callback")?
+                    .unwrap_or_else(Callback::dummy);
+                self.wrap(views::Button::new(label, move |s| cb(s)), table)
+            }
+            "Checkbox" => {
+                let mut view = views::Checkbox::new();
+                view.set_checked(bool_attr(table, "checked")?);
+                self.wrap(view, table)
+            }
+            "SelectView" => {
+                let mut view = views::SelectView::<String>::new();
+                for item in array_attr(table, "items")? {
+                    let item = item.as_str().ok_or_else(|| {
+                        Error::InvalidAttribute(String::from("items"))
+                    })?;
+                    view.add_item_str(item);
+                }
+                if let Some(cb) = self.callback_attr(table, "on_submit")? {
+                    view.set_on_submit(move |s, _: &String| cb(s));
+                }
+                self.wrap(view, table)
+            }
+            "DummyView" => self.wrap(views::DummyView, table),
+            "LinearLayout" => {
+                let orientation = match str_attr(table, "orientation")? {
+                    None | Some("vertical") => Orientation::Vertical,
+                    Some("horizontal") => Orientation::Horizontal,
+                    Some(_) => {
+                        return Err(Error::InvalidAttribute(String::from(
+                            "orientation",
+                        )));
+                    }
+                };
+                let mut view = views::LinearLayout::new(orientation);
+                for child in array_attr(table, "children")? {
+                    view.add_child(self.build(child)?);
+                }
+                self.wrap(view, table)
+            }
+            "ListView" => {
+                let mut view = views::ListView::new();
+                for child in array_attr(table, "children")? {
+                    // The label belongs to the list, not to the child.
+                    let mut child = child.clone();
+                    let label = match child.as_table_mut() {
+                        Some(child) => match child.remove("label") {
+                            Some(Value::String(label)) => label,
+                            Some(_) => {
+                                return Err(Error::InvalidAttribute(
+                                    String::from("label"),
+                                ));
+                            }
+                            None => String::new(),
+                        },
+                        None => String::new(),
+                    };
+                    view.add_child(&label, self.build(&child)?);
+                }
+                self.wrap(view, table)
+            }
+            "Dialog" => {
+                let mut view = match table.get("content") {
+                    Some(content) => {
+                        views::Dialog::around(self.build(content)?)
+                    }
+                    None => views::Dialog::new(),
+                };
+                if let Some(title) = str_attr(table, "title")? {
+                    view.set_title(title);
+                }
+                for button in array_attr(table, "buttons")? {
+                    let button = button.as_table().ok_or_else(|| {
+                        Error::InvalidAttribute(String::from("buttons"))
+                    })?;
+                    let label = str_attr(button, "label")?.unwrap_or("");
+                    let cb = self
+                        .callback_attr(button, "callback")?
+                        .unwrap_or_else(Callback::dummy);
+                    view.add_button(label, move |s| cb(s));
+                }
+                self.wrap(view, table)
+            }
+            "Panel" => {
+                let content = self.child(table, "content")?;
+                let mut view = views::Panel::new(content);
+                if let Some(title) = str_attr(table, "title")? {
+                    view.set_title(title);
+                }
+                self.wrap(view, table)
+            }
+            "ScrollView" => {
+                let content = self.child(table, "content")?;
+                self.wrap(views::ScrollView::new(content), table)
+            }
+            _ => Err(unknown_type()),
+        }
+    }
+
+    /// Applies the attributes common to all views, like `id` or `width`.
+    ///
+    /// Custom view constructors should call this on the view they build,
+    /// so `find_id` can look for this view type.
+    pub fn wrap<V: View>(
+        &self, view: V, table: &Table,
+    ) -> Result<ViewBox, Error> {
+        let mut view = match str_attr(table, "id")? {
+            Some(id) => ViewBox::boxed(views::IdView::new(id, view)),
+            None => ViewBox::boxed(view),
+        };
+
+        let width = size_constraint(table, "width")?;
+        let height = size_constraint(table, "height")?;
+        if width.is_some() || height.is_some() {
+            let width = width.unwrap_or(SizeConstraint::Free);
+            let height = height.unwrap_or(SizeConstraint::Free);
+            view = ViewBox::boxed(views::BoxView::new(width, height, view));
+        }
+
+        if bool_attr(table, "scrollable")? {
+            view = ViewBox::boxed(view.scrollable());
+        }
+        Ok(view)
+    }
+}
+
+// Reads the size constraint for `dimension` ("width" or "height").
+//
+// Returns `None` if the size is free.
+fn size_constraint(
+    table: &Table, dimension: &str,
+) -> Result<Option<SizeConstraint>, Error> {
+    if bool_attr(table, &format!("full_{}", dimension))? {
+        return Ok(Some(SizeConstraint::Full));
+    }
+    if let Some(size) = usize_attr(table, dimension)? {
+        return Ok(Some(SizeConstraint::Fixed(size)));
+    }
+
+    let min = usize_attr(table, &format!("min_{}", dimension))?;
+    let max = usize_attr(table, &format!("max_{}", dimension))?;
+    Ok(match (min, max) {
+        (None, None) => None,
+        (Some(min), None) => Some(SizeConstraint::AtLeast(min)),
+        (None, Some(max)) => Some(SizeConstraint::AtMost(max)),
+        (Some(_), Some(_)) => {
+            // A box view can only apply one of them.
+            return Err(Error::InvalidAttribute(format!("min_{}", dimension)));
+        }
+    })
+}
+
+fn str_attr<'a>(
+    table: &'a Table, key: &str,
+) -> Result<Option<&'a str>, Error> {
+    match table.get(key) {
+        None => Ok(None),
+        Some(value) => value
+            .as_str()
+            .map(Some)
+            .ok_or_else(|| Error::InvalidAttribute(key.to_string())),
+    }
+}
+
+fn bool_attr(table: &Table, key: &str) -> Result<bool, Error> {
+    match table.get(key) {
+        None => Ok(false),
+        Some(value) => value
+            .as_bool()
+            .ok_or_else(|| Error::InvalidAttribute(key.to_string())),
+    }
+}
+
+fn usize_attr(table: &Table, key: &str) -> Result<Option<usize>, Error> {
+    match table.get(key) {
+        None => Ok(None),
+        Some(value) => match value.as_integer() {
+            Some(n) if n >= 0 => Ok(Some(n as usize)),
+            _ => Err(Error::InvalidAttribute(key.to_string())),
+        },
+    }
+}
+
+fn array_attr<'a>(table: &'a Table, key: &str) -> Result<&'a [Value], Error> {
+    match table.get(key) {
+        None => Ok(&[]),
+        Some(value) => value
+            .as_array()
+            .map(Vec::as_slice)
+            .ok_or_else(|| Error::InvalidAttribute(key.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::views::{Checkbox, TextView};
+
+    #[test]
+    fn build_tree() {
+        let builder = Builder::new()
+            .callback("quit", Cursive::quit)
+            .view_type("Greeting", |table, builder| {
+                let name = str_attr(table, "name")?.unwrap_or("world");
+                builder.wrap(TextView::new(format!("Hello {}", name)), table)
+            });
+        let view = builder
+            .load_toml(
+                r#"
+                type = "LinearLayout"
+                orientation = "horizontal"
+                children = [
+                    { type = "Greeting", name = "you", id = "greeting" },
+                    { type = "Checkbox", checked = true, id = "check" },
+                    { type = "Button", label = "Quit", callback = "quit" },
+                ]
+                "#,
+            )
+            .unwrap();
+
+        let mut siv = Cursive::dummy();
+        siv.add_layer(view);
+        let greeting = siv.find_id::<TextView>("greeting").unwrap();
+        assert_eq!(greeting.get_content().source(), "Hello you");
+        assert!(siv.find_id::<Checkbox>("check").unwrap().is_checked());
+
+        let missing = r#"
+            type = "Button"
+            callback = "save"
+        "#;
+        match builder.load_toml(missing) {
+            Err(Error::UnknownCallback(name)) => assert_eq!(name, "save"),
+            _ => panic!("the callback should be missing"),
+        }
+        let unknown = builder.load_toml("type = \"Spinner\"");
+        assert!(unknown.is_err());
+
+        // Typos in attributes are reported, even in nested views.
+        let typo = r#"
+            type = "ListView"
+            children = [
+                { label = "Name", type = "EditView", secert = true },
+            ]
+        "#;
+        match builder.load_toml(typo) {
+            Err(Error::UnknownAttribute(key)) => assert_eq!(key, "secert"),
+            _ => panic!("the attribute should be unknown"),
+        }
+        let err = builder.load_toml("type = \"Button\"\nlable = \"Ok\"");
+        assert_eq!(
+            err.err().unwrap().to_string(),
+            "unknown attribute `lable`"
+        );
+    }
+}
@@ -0,0 +1,306 @@
+//! Clipboard shared by the views.
+//!
+//! Text copied with [`Cursive::clipboard`] is sent to the terminal with an
+//! OSC 52 escape sequence, when the backend supports it. This works through
+//! ssh and tmux, but terminals do not let applications read it back.
+//!
+//! With the `system-clipboard` feature, the platform clipboard is also used,
+//! through the usual command-line tools (`pbcopy`, `wl-copy`, `xclip`,
+//! `xsel` or `clip`). When it is available, pasting reads from it: the
+//! tool which worked last is tried first, and the interface only waits for
+//! it a short while before falling back to the application memory.
+//!
+//! In all cases, the text is also kept in memory, so copy and paste always
+//! work within the application.
+//!
+//! Views read the clipboard with [`get_text`]. Setting it needs the backend,
+//! so views do it from a callback: `TextView` and `TextArea` copy their
+//! selection on [`DEFAULT_COPY_EVENT`] unless told otherwise, while
+//! `EditView` and `TextArea` paste on `Ctrl-V`.
+//!
+//! [`Cursive::clipboard`]: ../struct.Cursive.html#method.clipboard
+//! [`get_text`]: fn.get_text.html
+//! [`DEFAULT_COPY_EVENT`]: constant.DEFAULT_COPY_EVENT.html
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::backend::Backend;
+use crate::event::{Event, EventResult};
+use crate::Cursive;
+
+thread_local! {
+    static CONTENT: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Access to the clipboard, through the backend.
+///
+/// Obtained with `Cursive::clipboard`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::Cursive;
+/// let mut siv = Cursive::dummy();
+/// siv.clipboard().set_text("Hello");
+/// assert_eq!(siv.clipboard().get_text(), Some(String::from("Hello")));
+/// ```
+pub struct Clipboard<'a> {
+    backend: &'a dyn Backend,
+}
+
+impl<'a> Clipboard<'a> {
+    pub(crate) fn new(backend: &'a dyn Backend) -> Self {
+        Clipboard { backend }
+    }
+
+    /// Copies `text` to the clipboard.
+    ///
+    /// Returns `true` if it reached the terminal or the system clipboard,
+    /// and not just the application memory.
+    pub fn set_text(&mut self, text: &str) -> bool {
+        CONTENT.with(|content| *content.borrow_mut() = Some(text.to_string()));
+
+        let terminal = self.backend.set_clipboard(text);
+        let system = system::set_text(text);
+        terminal || system
+    }
+
+    /// Returns the text in the clipboard, if any.
+    ///
+    /// Same as [`get_text`](fn.get_text.html).
+    pub fn get_text(&self) -> Option<String> {
+        get_text()
+    }
+}
+
+/// Returns the text in the clipboard, if any.
+///
+/// The system clipboard is used when available and quick to answer;
+/// otherwise, this returns the last text copied in the application.
+pub fn get_text() -> Option<String> {
+    system::get_text()
+        .or_else(|| CONTENT.with(|content| content.borrow().clone()))
+}
+
+/// Event copying the selection of the views, unless they are told otherwise.
+///
+/// This is `Alt-W`, as in Emacs: the backends turn `Ctrl-C` into
+/// `Event::Exit`, so it never reaches the views.
+pub const DEFAULT_COPY_EVENT: Event = Event::AltChar('w');
+
+/// Callback given the text copied from a view, instead of the clipboard.
+pub(crate) type OnCopy = dyn Fn(&mut Cursive, &str);
+
+/// Copies the selection of a view, on its copy event.
+///
+/// The text goes to `on_copy` if set, and to the clipboard otherwise.
+/// Without a selection, the event is ignored.
+pub(crate) fn copy(
+    on_copy: Option<Rc<OnCopy>>, text: Option<String>,
+) -> EventResult {
+    match (on_copy, text) {
+        (Some(cb), Some(text)) => EventResult::with_cb(move |s| cb(s, &text)),
+        (None, Some(text)) => EventResult::with_cb(move |s| {
+            s.clipboard().set_text(&text);
+        }),
+        (_, None) => EventResult::Ignored,
+    }
+}
+
+/// Returns the OSC 52 sequence setting the terminal clipboard to `text`.
+pub(crate) fn osc52(text: &str) -> String {
+    format!("\x1B]52;c;{}\x07", base64(text.as_bytes()))
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] =
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut result = String::new();
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).cloned().unwrap_or(0),
+            chunk.get(2).cloned().unwrap_or(0),
+        ];
+        let n = u32::from(b[0]) << 16 | u32::from(b[1]) << 8 | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let index = (n >> (18 - 6 * i)) & 0x3F;
+                result.push(char::from(ALPHABET[index as usize]));
+            } else {
+                result.push('=');
+            }
+        }
+    }
+    result
+}
+
+#[cfg(feature = "system-clipboard")]
+mod system {
+    use std::io::Write;
+    use std::iter;
+    use std::process::{Command, Stdio};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::Duration;
+
+    // How long pasting waits for the system clipboard.
+    const PASTE_TIMEOUT: Duration = Duration::from_millis(200);
+
+    // Values of `PASTE_TOOL` which are not an index in `PASTE`.
+    const UNKNOWN: usize = usize::MAX;
+    const UNAVAILABLE: usize = usize::MAX - 1;
+
+    // Index of the paste tool which worked last.
+    static PASTE_TOOL: AtomicUsize = AtomicUsize::new(UNKNOWN);
+
+    // Commands to try, in order, as (program, arguments).
+    type Tools = &'static [(&'static str, &'static [&'static str])];
+
+    #[cfg(target_os = "macos")]
+    const COPY: Tools = &[("pbcopy", &[])];
+    #[cfg(target_os = "macos")]
+    const PASTE: Tools = &[("pbpaste", &[])];
+
+    #[cfg(windows)]
+    const COPY: Tools = &[("clip", &[])];
+    #[cfg(windows)]
+    const PASTE: Tools =
+        &[("powershell", &["-NoProfile", "-Command", "Get-Clipboard"])];
+
+    #[cfg(not(any(target_os = "macos", windows)))]
+    const COPY: Tools = &[
+        ("wl-copy", &[]),
+        ("xclip", &["-selection", "clipboard"]),
+        ("xsel", &["--clipboard", "--input"]),
+    ];
+    #[cfg(not(any(target_os = "macos", windows)))]
+    const PASTE: Tools = &[
+        ("wl-paste", &["--no-newline"]),
+        ("xclip", &["-selection", "clipboard", "-out"]),
+        ("xsel", &["--clipboard", "--output"]),
+    ];
+
+    pub fn set_text(text: &str) -> bool {
+        COPY.iter().any(|&(program, args)| {
+            let child = Command::new(program)
+                .args(args)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn();
+            let mut child = match child {
+                Ok(child) => child,
+                Err(_) => return false,
+            };
+            let written = child
+                .stdin
+                .take()
+                .map(|mut stdin| stdin.write_all(text.as_bytes()).is_ok())
+                .unwrap_or(false);
+            let status = child.wait().map(|status| status.success());
+            written && status.unwrap_or(false)
+        })
+    }
+
+    pub fn get_text() -> Option<String> {
+        if PASTE_TOOL.load(Ordering::Relaxed) == UNAVAILABLE {
+            return None;
+        }
+
+        // A tool waiting for a display server would freeze the interface:
+        // it is left to finish in the background instead.
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = sender.send(read());
+        });
+        receiver.recv_timeout(PASTE_TIMEOUT).ok()?
+    }
+
+    fn read() -> Option<String> {
+        let last = match PASTE_TOOL.load(Ordering::Relaxed) {
+            UNKNOWN => 0,
+            last => last,
+        };
+        let others = (0..PASTE.len()).filter(|&i| i != last);
+
+        let mut found = false;
+        for i in iter::once(last).chain(others) {
+            let (program, args) = PASTE[i];
+            let output = Command::new(program)
+                .args(args)
+                .stdin(Stdio::null())
+                .stderr(Stdio::null())
+                .output();
+            let output = match output {
+                Ok(output) => output,
+                Err(_) => continue,
+            };
+            found = true;
+            if !output.status.success() {
+                continue;
+            }
+            if let Ok(text) = String::from_utf8(output.stdout) {
+                PASTE_TOOL.store(i, Ordering::Relaxed);
+                return Some(text);
+            }
+        }
+
+        // None of the tools is installed: don't look for them again.
+        if !found {
+            PASTE_TOOL.store(UNAVAILABLE, Ordering::Relaxed);
+        }
+        None
+    }
+}
+
+#[cfg(not(feature = "system-clipboard"))]
+mod system {
+    pub fn set_text(_: &str) -> bool {
+        false
+    }
+
+    pub fn get_text() -> Option<String> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::puppet;
+    use crate::event::Event;
+    use crate::traits::Identifiable;
+    use crate::views::{EditView, LinearLayout, TextArea};
+    use crate::Cursive;
+
+    #[test]
+    fn copy_paste() {
+        assert_eq!(osc52("Hi!"), "\x1B]52;c;SGkh\x07");
+        assert_eq!(base64(b"Hello"), "SGVsbG8=");
+
+        let (backend, puppet) = puppet::Backend::init((20, 4));
+        let mut siv = Cursive::new(move || backend);
+        siv.add_fullscreen_layer(
+            LinearLayout::vertical()
+                .child(TextArea::new().content("one\ntwo"))
+                .child(EditView::new().with_id("edit")),
+        );
+        siv.refresh();
+
+        puppet.send_all(vec![Event::CtrlChar('a'), DEFAULT_COPY_EVENT]);
+        while siv.step() {}
+        assert_eq!(puppet.clipboard(), Some(String::from("one\ntwo")));
+
+        siv.focus_id("edit").unwrap();
+        puppet.send(Event::CtrlChar('v'));
+        while siv.step() {}
+        let content = siv.call_on_id("edit", |view: &mut EditView| {
+            view.get_content()
+        });
+        assert_eq!(content.unwrap().as_str(), "one two");
+    }
+}
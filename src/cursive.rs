@@ -1,16 +1,38 @@
 use hashbrown::HashMap;
-use std::any::Any;
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::marker::PhantomData;
+use std::mem;
 use std::num::NonZeroU32;
 use std::path::Path;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 
-use crossbeam_channel::{self, Receiver, Sender};
+use crossbeam_channel::{self, Receiver, Sender, TrySendError};
+use log::warn;
 
 use crate::backend;
+use crate::clipboard;
 use crate::direction;
-use crate::event::{Callback, Event, EventResult};
+use crate::drag;
+use crate::error::Error;
+use crate::event::{
+    Callback, Event, EventResult, Key, MouseButton, MouseEvent,
+};
+use crate::keymap;
+use crate::panic_hook;
 use crate::printer::Printer;
+use crate::profiler;
+use crate::recording;
+use crate::screen_buffer;
+#[cfg(unix)]
+use crate::signals;
+use crate::state::UiState;
 use crate::theme;
+use crate::utils::time::Instant;
 use crate::vec::Vec2;
 use crate::view::{self, Finder, IntoBoxedView, Position, View};
 use crate::views::{self, LayerPosition};
@@ -20,6 +42,9 @@ static DEBUG_VIEW_ID: &'static str = "_cursive_debug_view";
 // How long we wait between two empty input polls
 const INPUT_POLL_DELAY_MS: u64 = 30;
 
+// Maximum delay between the presses of a double click.
+const DEFAULT_CLICK_INTERVAL_MS: u64 = 500;
+
 /// Central part of the cursive library.
 ///
 /// It initializes ncurses on creation and cleans up on drop.
@@ -28,37 +53,476 @@ const INPUT_POLL_DELAY_MS: u64 = 30;
 ///
 /// It uses a list of screen, with one screen active at a time.
 pub struct Cursive {
-    theme: theme::Theme,
-    screens: Vec<views::StackView>,
-    global_callbacks: HashMap<Event, Vec<Callback>>,
-    menubar: views::Menubar,
+    // Views, callbacks and settings, moved as a unit by `dump`.
+    state: RootState,
+
+    // Explicit tab order position, as the last one focused through it.
 
     // Last layer sizes of the stack view.
     // If it changed, clear the screen.
     last_sizes: Vec<Vec2>,
 
-    active_screen: ScreenId,
+    // View receiving all mouse events until the next release.
+    mouse_capture: Option<(String, Rc<MouseHandler>)>,
+
+    // Last press, to detect double and triple clicks.
+    last_click: Option<Click>,
 
     running: bool,
 
     backend: Box<dyn backend::Backend>,
 
+    // Wakes up the idle event loop. Unless the backend uses it, input is
+    // polled every `INPUT_POLL_DELAY_MS`.
+    waker: backend::Waker,
+    wake_source: Receiver<()>,
+    input_wakes: bool,
+
+    // Last tick of the refresh rate.
+    last_tick: Instant,
+
+    last_frame: Option<Instant>,
+    // The screen needs a redraw, possibly postponed by `max_fps`.
+    redraw_pending: bool,
+
+    profiler: Option<profiler::Profiler>,
+    profiling_overlay: bool,
+    draw_observer: Option<Rc<dyn profiler::DrawObserver>>,
+
+    recorder: Option<recording::Recorder>,
+    player: Option<recording::Player>,
+
+    #[cfg(unix)]
+    signal_handlers: Vec<SignalHandler>,
+}
+
+// The part of a `Cursive` root which does not depend on its backend.
+struct RootState {
+    theme: theme::Theme,
+    screens: Vec<views::StackView>,
+    global_callbacks: HashMap<Event, Vec<Callback>>,
+
+    // Run on every event before dispatching it, in order.
+    event_filters: Vec<Rc<EventFilter>>,
+
+    // Global actions, bound to `global_callbacks` through the keymap.
+    actions: Vec<Action>,
+    keymap: keymap::Keymap,
+
+    // Explicit tab order, as view ids.
+    tab_order: Vec<String>,
+
+    menubar: views::Menubar,
+
+    // Screen size when the last resize event was handled.
+    last_screen_size: Vec2,
+    on_resize: Option<Rc<ResizeCallback>>,
+
+    click_interval: Duration,
+    click_tolerance: usize,
+
+    on_pre_quit: Option<Rc<PreQuitCallback>>,
+    on_exit: Option<Callback>,
+
+    active_screen: ScreenId,
+    screen_names: HashMap<String, ScreenId>,
+
+    // Global callbacks only used when a given screen is active.
+    screen_callbacks: HashMap<(ScreenId, Event), Vec<Callback>>,
+
     cb_source: Receiver<Box<dyn CbFunc>>,
     cb_sink: Sender<Box<dyn CbFunc>>,
+    cb_capacity: Option<usize>,
 
-    // User-provided data.
-    user_data: Box<Any>,
+    // Set from other threads to ask for a redraw.
+    refresh_requested: Arc<AtomicBool>,
+
+    // User-provided data, by type and by name.
+    user_data: HashMap<TypeId, Box<dyn Any>>,
+    named_data: HashMap<String, Box<dyn Any>>,
 
     fps: Option<NonZeroU32>,
-    boring_frame_count: u32,
+    render_mode: RenderMode,
+    suspend_on_ctrl_z: bool,
+    max_fps: Option<NonZeroU32>,
+
+    timers: Vec<Timer>,
+}
+
+impl RootState {
+    fn new(screen_size: Vec2, cb_capacity: Option<usize>) -> Self {
+        let (cb_sink, cb_source) = cb_channel(cb_capacity);
+        RootState {
+            theme: theme::load_default(),
+            screens: vec![views::StackView::new()],
+            global_callbacks: HashMap::new(),
+            event_filters: Vec::new(),
+            actions: Vec::new(),
+            keymap: keymap::Keymap::new(),
+            tab_order: Vec::new(),
+            menubar: views::Menubar::new(),
+            last_screen_size: screen_size,
+            on_resize: None,
+            click_interval: Duration::from_millis(DEFAULT_CLICK_INTERVAL_MS),
+            click_tolerance: 1,
+            on_pre_quit: None,
+            on_exit: None,
+            active_screen: 0,
+            screen_names: HashMap::new(),
+            screen_callbacks: HashMap::new(),
+            cb_source,
+            cb_sink,
+            cb_capacity,
+            refresh_requested: Arc::new(AtomicBool::new(false)),
+            user_data: HashMap::new(),
+            named_data: HashMap::new(),
+            fps: None,
+            render_mode: RenderMode::Continuous,
+            suspend_on_ctrl_z: cfg!(unix),
+            max_fps: None,
+            timers: Vec::new(),
+        }
+    }
+}
+
+// Creates the callback channel, bounded if `capacity` is set.
+fn cb_channel(capacity: Option<usize>) -> (CbSink, Receiver<Box<dyn CbFunc>>) {
+    match capacity {
+        Some(capacity) => crossbeam_channel::bounded(capacity),
+        None => crossbeam_channel::unbounded(),
+    }
+}
+
+// A callback for a signal, with the flag set when the signal is received.
+#[cfg(unix)]
+struct SignalHandler {
+    hook: signals::Hook,
+    received: Arc<AtomicBool>,
+    callback: Callback,
+}
+
+type RepeatCallback = dyn Fn(&mut Cursive);
+
+enum TimerCallback {
+    Once(Box<dyn FnOnce(&mut Cursive)>),
+    Repeat(Rc<RepeatCallback>, Duration),
+}
+
+// Callback scheduled with `set_timeout` or `set_interval`.
+struct Timer {
+    deadline: Instant,
+    callback: TimerCallback,
+    handle: TimerHandle,
+}
+
+type ViewTimer = (Duration, Box<dyn FnOnce(&mut Cursive)>);
+
+thread_local! {
+    // Timers requested by the views of the root being laid out.
+    static VIEW_TIMERS: RefCell<Option<Vec<ViewTimer>>> = RefCell::new(None);
+}
+
+/// Calls `cb` after `delay`, from a view being laid out.
+///
+/// Views have no access to the root during the layout: the root laying
+/// them out adds the timer afterwards. Returns `false`, without scheduling
+/// anything, if the view is not laid out by a `Cursive` root.
+pub(crate) fn set_view_timeout<F>(delay: Duration, cb: F) -> bool
+where
+    F: FnOnce(&mut Cursive) + 'static,
+{
+    VIEW_TIMERS.with(|timers| match *timers.borrow_mut() {
+        Some(ref mut timers) => {
+            timers.push((delay, Box::new(cb)));
+            true
+        }
+        None => false,
+    })
+}
+
+// Waits until the event loop may have something to do.
+//
+// It can be sent to another thread, for the async runner.
+pub(crate) struct Waiter {
+    callbacks: Receiver<Box<dyn CbFunc>>,
+    wakes: Receiver<()>,
+    timeout: Option<Duration>,
+}
+
+impl Waiter {
+    // Blocks until a callback or input may be ready, or the timeout expires.
+    pub(crate) fn wait(&self) {
+        self.wait_or::<()>(None);
+    }
+
+    // Like `wait`, but also returns early, with `true`, once `other` is
+    // ready.
+    pub(crate) fn wait_or<T>(&self, other: Option<&Receiver<T>>) -> bool {
+        let mut select = crossbeam_channel::Select::new();
+        select.recv(&self.callbacks);
+        select.recv(&self.wakes);
+        let other = other.map(|other| select.recv(other));
+
+        let ready = match self.timeout {
+            Some(timeout) => select.ready_timeout(timeout).ok(),
+            None => Some(select.ready()),
+        };
+        ready.is_some() && ready == other
+    }
+}
+
+// A mouse press, followed by `count - 1` presses at the same place.
+struct Click {
+    time: Instant,
+    position: Vec2,
+    button: MouseButton,
+    count: usize,
+}
+
+/// The views and callbacks of a `Cursive` root, without its backend.
+///
+/// Returned by [`Cursive::dump`], and given back to [`Cursive::restore`].
+///
+/// [`Cursive::dump`]: struct.Cursive.html#method.dump
+/// [`Cursive::restore`]: struct.Cursive.html#method.restore
+pub struct CursiveDump {
+    state: RootState,
+}
+
+/// Handle to a timer, used to cancel it.
+///
+/// Returned by `Cursive::set_timeout` and `Cursive::set_interval`. It can
+/// be cloned and sent to other threads; dropping it does not cancel the
+/// timer.
+#[derive(Clone, Debug)]
+pub struct TimerHandle {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl TimerHandle {
+    fn new() -> Self {
+        TimerHandle {
+            cancelled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Cancels the timer.
+    ///
+    /// Its callback will not be called anymore. This has no effect if the
+    /// timer already fired.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if `cancel` was called.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// Decides when the event loop redraws the screen.
+///
+/// See [`Cursive::set_render_mode`].
+///
+/// [`Cursive::set_render_mode`]: struct.Cursive.html#method.set_render_mode
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RenderMode {
+    /// Redraw after each input event or callback, and at each tick of the
+    /// refresh rate set with `Cursive::set_fps`.
+    ///
+    /// This is the default.
+    Continuous,
+    /// Only redraw when something may have changed.
+    ///
+    /// This is after an input event, a callback or a call to
+    /// `RefreshSink::request_refresh`. At each tick of the refresh rate,
+    /// the screen is only redrawn if a view needs a new layout (for example
+    /// a `ProgressBar` whose value changed).
+    OnDemand,
+}
+
+type ResizeCallback = dyn Fn(&mut Cursive, Vec2, Vec2);
+type PreQuitCallback = dyn Fn(&mut Cursive) -> bool;
+type MouseHandler = dyn Fn(&mut Cursive, Event) -> Option<EventResult>;
+type EventFilter = dyn Fn(&mut Cursive, Event) -> Option<Event>;
+
+// A global callback, with the name used to rebind it.
+struct Action {
+    name: Option<String>,
+    default: Event,
+    callback: Callback,
+}
+
+impl Action {
+    fn events(&self, keymap: &keymap::Keymap) -> Vec<Event> {
+        keymap.resolve(self.name.as_deref(), &self.default)
+    }
 }
 
 /// Identifies a screen in the cursive root.
 pub type ScreenId = usize;
 
 /// Convenient alias to the result of `Cursive::cb_sink`.
+///
+/// The channel is unbounded, unless `Cursive::set_cb_sink_capacity` was
+/// called: `send` then blocks while it is full, and `try_send` or
+/// `send_timeout` can be used to avoid blocking a producer.
 pub type CbSink = Sender<Box<dyn CbFunc>>;
 
+/// Asks a `Cursive` root to redraw the screen, from any thread.
+///
+/// Requests made before the next event cycle are merged into a single
+/// redraw, and never fill the callback channel. This replaces sending
+/// `Cursive::noop` callbacks through the `CbSink`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::Cursive;
+/// # use std::thread;
+/// let siv = Cursive::dummy();
+/// let refresh = siv.refresh_sink();
+///
+/// thread::spawn(move || {
+///     for _ in 0..1000 {
+///         // Update some shared state...
+///         refresh.request_refresh();
+///     }
+/// });
+/// ```
+#[derive(Clone, Debug)]
+pub struct RefreshSink {
+    requested: Arc<AtomicBool>,
+    waker: backend::Waker,
+}
+
+impl RefreshSink {
+    /// Asks for the screen to be redrawn during the next event cycle.
+    pub fn request_refresh(&self) {
+        self.requested.store(true, Ordering::Relaxed);
+        self.waker.wake();
+    }
+}
+
+/// Handle to send events to a `Cursive` root from other threads.
+///
+/// Events are processed during the next event cycle, like input events.
+///
+/// Obtained with `Cursive::event_sink`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::Cursive;
+/// # use cursive::backend::puppet;
+/// # use cursive::event::CustomEvent;
+/// # use std::thread;
+/// let (backend, _) = puppet::Backend::init((20, 4));
+/// let mut siv = Cursive::new(move || backend);
+/// siv.add_global_callback(CustomEvent::new("new-message"), |s| s.quit());
+///
+/// let events = siv.event_sink();
+/// thread::spawn(move || {
+///     events.send(CustomEvent::with_data("new-message", 42));
+/// })
+/// .join()
+/// .unwrap();
+///
+/// siv.step();
+/// assert!(!siv.is_running());
+/// ```
+#[derive(Clone)]
+pub struct EventSink {
+    cb_sink: CbSink,
+}
+
+impl EventSink {
+    /// Sends an event, to be processed during the next event cycle.
+    ///
+    /// Blocks while the callback queue is full. Returns `false` if the
+    /// `Cursive` root was dropped.
+    pub fn send<E: Into<Event>>(&self, event: E) -> bool {
+        let event = event.into();
+        self.cb_sink
+            .send(Box::new(move |s: &mut Cursive| s.on_event(event)))
+            .is_ok()
+    }
+}
+
+/// Handle to a view with an id, usable from other threads.
+///
+/// Unlike a `WeakViewRef`, it is `Send`: callbacks are sent to the `Cursive`
+/// root, and run on the view during the next event cycle.
+///
+/// Obtained with `Cursive::view_sink`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::Cursive;
+/// # use cursive::backend::puppet;
+/// # use cursive::traits::Identifiable;
+/// # use cursive::views::TextView;
+/// # use std::thread;
+/// let (backend, _) = puppet::Backend::init((20, 4));
+/// let mut siv = Cursive::new(move || backend);
+/// siv.add_layer(TextView::new("Ready").with_id("status"));
+///
+/// let status = siv.view_sink::<TextView>("status").unwrap();
+/// thread::spawn(move || {
+///     status.with(|view| view.set_content("Done"));
+/// })
+/// .join()
+/// .unwrap();
+///
+/// siv.step();
+/// let content = siv
+///     .call_on_id("status", |view: &mut TextView| {
+///         view.get_content().source().to_string()
+///     })
+///     .unwrap();
+/// assert_eq!(content, "Done");
+/// ```
+pub struct ViewSink<V> {
+    id: String,
+    cb_sink: CbSink,
+    _view: PhantomData<fn() -> V>,
+}
+
+impl<V> Clone for ViewSink<V> {
+    fn clone(&self) -> Self {
+        ViewSink {
+            id: self.id.clone(),
+            cb_sink: self.cb_sink.clone(),
+            _view: PhantomData,
+        }
+    }
+}
+
+impl<V: View + Any> ViewSink<V> {
+    /// Returns the id of the view.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Runs `f` on the view during the next event cycle.
+    ///
+    /// `f` is not run if the view was removed by then. Blocks while the
+    /// callback queue is full. Returns `false` if the `Cursive` root was
+    /// dropped.
+    pub fn with<F>(&self, f: F) -> bool
+    where
+        F: FnOnce(&mut V) + Send + 'static,
+    {
+        let id = self.id.clone();
+        self.cb_sink
+            .send(Box::new(move |s: &mut Cursive| {
+                s.call_on_id(&id, f);
+            }))
+            .is_ok()
+    }
+}
+
 /// Asynchronous callback function trait.
 ///
 /// Every `FnOnce(&mut Cursive) -> () + Send` automatically
@@ -96,6 +560,12 @@ cfg_if::cfg_if! {
                 Self::crossterm().unwrap()
             }
        }
+    } else if #[cfg(all(windows, feature = "wincon-backend"))] {
+        impl Default for Cursive {
+            fn default() -> Self {
+                Self::wincon().unwrap()
+            }
+        }
     } else if #[cfg(feature = "pancurses-backend")] {
         impl Default for Cursive {
             fn default() -> Self {
@@ -121,7 +591,7 @@ impl Cursive {
     where
         F: FnOnce() -> Box<dyn backend::Backend>,
     {
-        Self::try_new::<_, ()>(|| Ok(backend_init())).unwrap()
+        Self::try_new(|| Ok::<_, Error>(backend_init())).unwrap()
     }
 
     /// Creates a new Cursive root, and initialize the back-end.
@@ -132,6 +602,7 @@ impl Cursive {
     ///   * `Cursive::pancurses()` if the `pancurses-backend` feature is enabled.
     ///   * `Cursive::termion()` if the `termion-backend` feature is enabled.
     ///   * `Cursive::crossterm()` if the `crossterm-backend` feature is enabled.
+    ///   * `Cursive::wincon()` on Windows, if the `wincon-backend` feature is enabled.
     ///   * `Cursive::blt()` if the `blt-backend` feature is enabled.
     ///   * `Cursive::dummy()` for a dummy backend, mostly useful for tests.
     /// * If you want to use a third-party backend, then `Cursive::new` is indeed the way to go:
@@ -143,55 +614,109 @@ impl Cursive {
     /// # use cursive::{Cursive, backend};
     /// let siv = Cursive::new(backend::dummy::Backend::init); // equivalent to Cursive::dummy()
     /// ```
-    pub fn try_new<F, E>(backend_init: F) -> Result<Self, E>
+    ///
+    /// Errors from `backend_init` are converted to an [`Error`], which can
+    /// be used to fall back to a non-interactive mode:
+    ///
+    /// ```rust,no_run
+    /// # use cursive::{Cursive, backend};
+    /// let siv = Cursive::try_new(backend::curses::n::Backend::init);
+    /// if siv.is_err() {
+    ///     println!("Not a terminal, running in batch mode.");
+    /// }
+    /// ```
+    ///
+    /// [`Error`]: enum.Error.html
+    pub fn try_new<F, E>(backend_init: F) -> Result<Self, Error>
     where
         F: FnOnce() -> Result<Box<dyn backend::Backend>, E>,
+        E: Into<Error>,
     {
-        let theme = theme::load_default();
-
-        let (cb_sink, cb_source) = crossbeam_channel::unbounded();
-
-        backend_init().map(|backend| Cursive {
-            theme,
-            screens: vec![views::StackView::new()],
-            last_sizes: Vec::new(),
-            global_callbacks: HashMap::new(),
-            menubar: views::Menubar::new(),
-            active_screen: 0,
-            running: true,
-            cb_source,
-            cb_sink,
-            backend,
-            fps: None,
-            boring_frame_count: 0,
-            user_data: Box::new(()),
+        backend_init().map_err(Into::into).map(|mut backend| {
+            if backend.uses_terminal() {
+                panic_hook::enter();
+            }
+            let (waker, wake_source) = crossbeam_channel::bounded(1);
+            let waker = backend::Waker::new(waker);
+            let input_wakes = backend.set_waker(waker.clone());
+
+            Cursive {
+                state: RootState::new(backend.screen_size(), None),
+                last_sizes: Vec::new(),
+                mouse_capture: None,
+                last_click: None,
+                running: true,
+                backend,
+                waker,
+                wake_source,
+                input_wakes,
+                last_tick: Instant::now(),
+                last_frame: None,
+                // The first frame is not drawn yet.
+                redraw_pending: true,
+                profiler: None,
+                profiling_overlay: false,
+                draw_observer: None,
+                recorder: None,
+                player: None,
+                #[cfg(unix)]
+                signal_handlers: Vec::new(),
+            }
         })
     }
 
     /// Creates a new Cursive root using a ncurses backend.
+    ///
+    /// Returns `Error::NotATty` if no terminal is attached.
     #[cfg(feature = "ncurses-backend")]
-    pub fn ncurses() -> std::io::Result<Self> {
+    pub fn ncurses() -> Result<Self, Error> {
+        #[cfg(unix)]
+        crate::error::check_tty()?;
+
         Self::try_new(backend::curses::n::Backend::init)
     }
 
     /// Creates a new Cursive root using a pancurses backend.
+    ///
+    /// Returns `Error::NotATty` if no terminal is attached.
     #[cfg(feature = "pancurses-backend")]
-    pub fn pancurses() -> std::io::Result<Self> {
+    pub fn pancurses() -> Result<Self, Error> {
+        #[cfg(unix)]
+        crate::error::check_stdout()?;
+
         Self::try_new(backend::curses::pan::Backend::init)
     }
 
     /// Creates a new Cursive root using a termion backend.
+    ///
+    /// Returns `Error::NotATty` if no terminal is attached.
     #[cfg(feature = "termion-backend")]
-    pub fn termion() -> std::io::Result<Self> {
+    pub fn termion() -> Result<Self, Error> {
+        #[cfg(unix)]
+        crate::error::check_tty()?;
+
         Self::try_new(backend::termion::Backend::init)
     }
 
     /// Creates a new Cursive root using a crossterm backend.
+    ///
+    /// Returns `Error::NotATty` if no terminal is attached.
     #[cfg(feature = "crossterm-backend")]
-    pub fn crossterm() -> std::io::Result<Self> {
+    pub fn crossterm() -> Result<Self, Error> {
+        #[cfg(unix)]
+        crate::error::check_stdout()?;
+
         Self::try_new(backend::crossterm::Backend::init)
     }
 
+    /// Creates a new Cursive root using the Windows console backend.
+    ///
+    /// Returns an `Error::Io` if the process has no console.
+    #[cfg(all(windows, feature = "wincon-backend"))]
+    pub fn wincon() -> Result<Self, Error> {
+        Self::try_new(backend::wincon::Backend::init)
+    }
+
     /// Creates a new Cursive root using a bear-lib-terminal backend.
     #[cfg(feature = "blt-backend")]
     pub fn blt() -> Self {
@@ -208,8 +733,28 @@ impl Cursive {
     /// Sets some data to be stored in Cursive.
     ///
     /// It can later on be accessed with `Cursive::user_data()`
+    ///
+    /// One value is stored for each type: this replaces any data previously
+    /// set with the same type, but keeps data of other types.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// struct Settings { dark: bool }
+    /// struct Count(usize);
+    ///
+    /// let mut siv = Cursive::dummy();
+    /// siv.set_user_data(Settings { dark: true });
+    /// siv.set_user_data(Count(0));
+    ///
+    /// siv.user_data_mut::<Count>().unwrap().0 += 1;
+    /// assert!(siv.get_user_data::<Settings>().unwrap().dark);
+    /// assert_eq!(siv.take_user_data::<Count>().unwrap().0, 1);
+    /// assert!(siv.get_user_data::<Count>().is_none());
+    /// ```
     pub fn set_user_data<T: Any>(&mut self, user_data: T) {
-        self.user_data = Box::new(user_data);
+        self.state.user_data.insert(TypeId::of::<T>(), Box::new(user_data));
     }
 
     /// Attempts to access the user-provided data.
@@ -217,7 +762,88 @@ impl Cursive {
     /// If some data was set previously with the same type, returns a reference to it.
     /// If nothing was set or if the type is different, returns `None`.
     pub fn user_data<T: Any>(&mut self) -> Option<&mut T> {
-        self.user_data.downcast_mut()
+        self.user_data_mut()
+    }
+
+    /// Returns a mutable reference to the data of type `T`, if any.
+    ///
+    /// Same as `Cursive::user_data()`.
+    pub fn user_data_mut<T: Any>(&mut self) -> Option<&mut T> {
+        self.state.user_data
+            .get_mut(&TypeId::of::<T>())
+            .and_then(|data| data.downcast_mut())
+    }
+
+    /// Returns a reference to the data of type `T`, if any.
+    pub fn get_user_data<T: Any>(&self) -> Option<&T> {
+        self.state.user_data
+            .get(&TypeId::of::<T>())
+            .and_then(|data| data.downcast_ref())
+    }
+
+    /// Removes the data of type `T`, and returns it.
+    pub fn take_user_data<T: Any>(&mut self) -> Option<T> {
+        self.state.user_data
+            .remove(&TypeId::of::<T>())
+            .and_then(|data| data.downcast().ok())
+            .map(|data| *data)
+    }
+
+    /// Stores some data under the given name.
+    ///
+    /// Unlike `set_user_data`, several values of the same type can be
+    /// stored, each with its own name. This replaces any data previously
+    /// stored with this name.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// let mut siv = Cursive::dummy();
+    /// siv.set_named_data("input", String::from("foo.txt"));
+    /// siv.set_named_data("output", String::from("bar.txt"));
+    ///
+    /// siv.named_data::<String>("output").unwrap().push_str(".bak");
+    /// assert_eq!(siv.get_named_data::<String>("input").unwrap(), "foo.txt");
+    /// assert!(siv.get_named_data::<usize>("input").is_none());
+    /// ```
+    pub fn set_named_data<S, T>(&mut self, name: S, data: T)
+    where
+        S: Into<String>,
+        T: Any,
+    {
+        self.state.named_data.insert(name.into(), Box::new(data));
+    }
+
+    /// Returns a mutable reference to the data stored under `name`.
+    ///
+    /// Returns `None` if nothing was stored with this name, or if it is not
+    /// of type `T`.
+    pub fn named_data<T: Any>(&mut self, name: &str) -> Option<&mut T> {
+        self.state.named_data
+            .get_mut(name)
+            .and_then(|data| data.downcast_mut())
+    }
+
+    /// Returns a reference to the data stored under `name`.
+    ///
+    /// Returns `None` if nothing was stored with this name, or if it is not
+    /// of type `T`.
+    pub fn get_named_data<T: Any>(&self, name: &str) -> Option<&T> {
+        self.state.named_data.get(name).and_then(|data| data.downcast_ref())
+    }
+
+    /// Removes the data stored under `name`, and returns it.
+    ///
+    /// Data of another type than `T` is left untouched.
+    pub fn take_named_data<T: Any>(&mut self, name: &str) -> Option<T> {
+        if !self.state.named_data.get(name)?.is::<T>() {
+            return None;
+        }
+        self.state.named_data
+            .remove(name)
+            .and_then(|data| data.downcast().ok())
+            .map(|data| *data)
     }
 
     /// Runs the given closure on the stored user data, if any.
@@ -293,20 +919,106 @@ impl Cursive {
     /// # }
     /// ```
     pub fn cb_sink(&self) -> &CbSink {
-        &self.cb_sink
+        &self.state.cb_sink
+    }
+
+    /// Sets how many callbacks can wait in the channel.
+    ///
+    /// Once the channel is full, `CbSink::send` blocks until the event loop
+    /// catches up, which keeps a fast producer from using unbounded memory.
+    /// `None` removes the limit, which is the default.
+    ///
+    /// The event loop is the only consumer: code running on the UI thread,
+    /// such as callbacks or tasks sharing its thread, must use `try_send`
+    /// on a bounded channel, as a blocking `send` would never return.
+    ///
+    /// This creates a new channel: call it before cloning `cb_sink`, as
+    /// previous sinks get disconnected. Waiting callbacks are moved to the
+    /// new channel. If more of them are waiting than `capacity` allows,
+    /// nothing changes and this returns `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// let mut siv = Cursive::dummy();
+    /// assert!(siv.set_cb_sink_capacity(Some(1)));
+    ///
+    /// let sink = siv.cb_sink().clone();
+    /// assert!(sink.try_send(Box::new(Cursive::noop)).is_ok());
+    /// // The channel is full until the event loop runs the first callback.
+    /// assert!(sink.try_send(Box::new(Cursive::noop)).is_err());
+    /// ```
+    pub fn set_cb_sink_capacity(&mut self, capacity: Option<usize>) -> bool {
+        let waiting = self.state.cb_source.len();
+        match capacity {
+            Some(capacity) if waiting > capacity => return false,
+            _ => (),
+        }
+
+        let (cb_sink, cb_source) = cb_channel(capacity);
+        let cb_source = mem::replace(&mut self.state.cb_source, cb_source);
+        self.state.cb_sink = cb_sink;
+        self.state.cb_capacity = capacity;
+
+        // Other threads may have sent more callbacks since `len` was read:
+        // those which do not fit are run right away rather than lost.
+        let overflow: Vec<_> = cb_source
+            .try_iter()
+            .filter_map(|cb| self.state.cb_sink.try_send(cb).err())
+            .map(TrySendError::into_inner)
+            .collect();
+        for cb in overflow {
+            cb.call_box(self);
+        }
+        true
+    }
+
+    /// Returns a handle to send events from other threads.
+    ///
+    /// Like the `cb_sink`, it is invalidated by `set_cb_sink_capacity`.
+    pub fn event_sink(&self) -> EventSink {
+        EventSink {
+            cb_sink: self.state.cb_sink.clone(),
+        }
+    }
+
+    /// Returns a handle to request a redraw from other threads.
+    pub fn refresh_sink(&self) -> RefreshSink {
+        RefreshSink {
+            requested: Arc::clone(&self.state.refresh_requested),
+            waker: self.waker.clone(),
+        }
     }
 
     /// Selects the menubar.
     pub fn select_menubar(&mut self) {
-        self.menubar.take_focus(direction::Direction::none());
+        self.state.menubar.take_focus(direction::Direction::none());
     }
 
     /// Sets the menubar autohide feature.
     ///
     /// * When enabled (default), the menu is only visible when selected.
     /// * When disabled, the menu is always visible and reserves the top row.
+    ///
+    /// Use [`Menubar::set_reveal_event`] and [`Menubar::set_reveal_on_mouse`]
+    /// to let users reveal a hidden menu.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// # use cursive::event::Key;
+    /// let mut siv = Cursive::dummy();
+    /// siv.set_autohide_menu(true);
+    /// siv.menubar().set_reveal_event(Some(Key::Esc));
+    /// siv.menubar().set_reveal_on_mouse(true);
+    /// ```
+    ///
+    /// [`Menubar::set_reveal_event`]: views/struct.Menubar.html#method.set_reveal_event
+    /// [`Menubar::set_reveal_on_mouse`]: views/struct.Menubar.html#method.set_reveal_on_mouse
     pub fn set_autohide_menu(&mut self, autohide: bool) {
-        self.menubar.autohide = autohide;
+        self.state.menubar.autohide = autohide;
     }
 
     /// Access the menu tree used by the menubar.
@@ -360,17 +1072,18 @@ impl Cursive {
     /// # }
     /// ```
     pub fn menubar(&mut self) -> &mut views::Menubar {
-        &mut self.menubar
+        &mut self.state.menubar
     }
 
     /// Returns the currently used theme.
     pub fn current_theme(&self) -> &theme::Theme {
-        &self.theme
+        &self.state.theme
     }
 
     /// Sets the current theme.
     pub fn set_theme(&mut self, theme: theme::Theme) {
-        self.theme = theme;
+        self.state.theme = theme;
+        self.mark_dirty();
         self.clear();
     }
 
@@ -379,7 +1092,7 @@ impl Cursive {
     /// Users rarely have to call this directly.
     pub fn clear(&mut self) {
         self.backend
-            .clear(self.theme.palette[theme::PaletteColor::Background]);
+            .clear(self.state.theme.palette[theme::PaletteColor::Background]);
     }
 
     /// Loads a theme from the given file.
@@ -403,40 +1116,127 @@ impl Cursive {
     ///
     /// Note that the actual frequency is not guaranteed.
     ///
+    /// Unless the render mode is `RenderMode::OnDemand`, the screen is
+    /// redrawn at each tick.
+    ///
     /// Between 0 and 30. Call with `fps = 0` to disable (default value).
     pub fn set_fps(&mut self, fps: u32) {
-        self.fps = NonZeroU32::new(fps);
+        self.state.fps = NonZeroU32::new(fps);
     }
 
-    /// Enables or disables automatic refresh of the screen.
+    /// Sets when the screen should be redrawn.
     ///
-    /// This is a shortcut to call `set_fps` with `30` or `0` depending on
-    /// `autorefresh`.
-    pub fn set_autorefresh(&mut self, autorefresh: bool) {
-        self.set_fps(if autorefresh { 30 } else { 0 });
-    }
-
-    /// Returns a reference to the currently active screen.
-    pub fn screen(&self) -> &views::StackView {
-        let id = self.active_screen;
-        &self.screens[id]
+    /// With `RenderMode::OnDemand`, an idle application does not redraw
+    /// anything, even with a refresh rate: views updating themselves must
+    /// return `true` from `View::needs_relayout` to be redrawn. Other
+    /// threads can use `Cursive::refresh_sink`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::{Cursive, RenderMode};
+    /// let mut siv = Cursive::dummy();
+    /// siv.set_render_mode(RenderMode::OnDemand);
+    /// // Check for updates 10 times per second, but redraw at most 5 times.
+    /// siv.set_fps(10);
+    /// siv.set_max_fps(5);
+    /// ```
+    pub fn set_render_mode(&mut self, mode: RenderMode) {
+        self.state.render_mode = mode;
+    }
+
+    /// Returns the current render mode.
+    pub fn get_render_mode(&self) -> RenderMode {
+        self.state.render_mode
+    }
+
+    /// Limits how often the screen is redrawn, in frames per second.
+    ///
+    /// Changes happening faster are combined into a single redraw.
+    ///
+    /// Call with `fps = 0` to remove the limit (default value).
+    pub fn set_max_fps(&mut self, fps: u32) {
+        self.state.max_fps = NonZeroU32::new(fps);
+    }
+
+    /// Starts recording frame timings, keeping the last `capacity` frames.
+    ///
+    /// They can then be queried with `Cursive::profiler`.
+    pub fn enable_profiling(&mut self, capacity: usize) {
+        self.profiler = Some(profiler::Profiler::new(capacity));
+    }
+
+    /// Stops recording frame timings, and forgets the recorded frames.
+    pub fn disable_profiling(&mut self) {
+        self.profiler = None;
+    }
+
+    /// Returns the frame timings, if profiling is enabled.
+    pub fn profiler(&self) -> Option<&profiler::Profiler> {
+        self.profiler.as_ref()
+    }
+
+    /// Returns the frame timings, if profiling is enabled.
+    pub fn profiler_mut(&mut self) -> Option<&mut profiler::Profiler> {
+        self.profiler.as_mut()
+    }
+
+    /// Sets whether the timings of the last frame are shown.
+    ///
+    /// When profiling is enabled, they are printed in the top-right corner
+    /// of the screen.
+    pub fn set_profiling_overlay(&mut self, enabled: bool) {
+        self.profiling_overlay = enabled;
+    }
+
+    /// Sets an observer, told about each view with an id being laid out or
+    /// drawn, and how long it took.
+    ///
+    /// See [`DrawObserver`](profiler/trait.DrawObserver.html).
+    ///
+    /// The observer only applies to the views of this root, when it lays
+    /// them out or draws them.
+    pub fn set_draw_observer<O>(&mut self, observer: O)
+    where
+        O: profiler::DrawObserver + 'static,
+    {
+        self.draw_observer = Some(Rc::new(observer));
+    }
+
+    /// Removes the draw observer.
+    pub fn clear_draw_observer(&mut self) {
+        self.draw_observer = None;
+    }
+
+    /// Enables or disables automatic refresh of the screen.
+    ///
+    /// This is a shortcut to call `set_fps` with `30` or `0` depending on
+    /// `autorefresh`.
+    pub fn set_autorefresh(&mut self, autorefresh: bool) {
+        self.set_fps(if autorefresh { 30 } else { 0 });
+    }
+
+    /// Returns a reference to the currently active screen.
+    pub fn screen(&self) -> &views::StackView {
+        let id = self.state.active_screen;
+        &self.state.screens[id]
     }
 
     /// Returns a mutable reference to the currently active screen.
     pub fn screen_mut(&mut self) -> &mut views::StackView {
-        let id = self.active_screen;
-        &mut self.screens[id]
+        let id = self.state.active_screen;
+        &mut self.state.screens[id]
     }
 
     /// Returns the id of the currently active screen.
     pub fn active_screen(&self) -> ScreenId {
-        self.active_screen
+        self.state.active_screen
     }
 
     /// Adds a new screen, and returns its ID.
     pub fn add_screen(&mut self) -> ScreenId {
-        let res = self.screens.len();
-        self.screens.push(views::StackView::new());
+        let res = self.state.screens.len();
+        self.state.screens.push(views::StackView::new());
         res
     }
 
@@ -447,17 +1247,99 @@ impl Cursive {
         res
     }
 
+    /// Adds a new screen with the given name, and returns its ID.
+    ///
+    /// If another screen already has this name, the name will now refer to
+    /// the new screen.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// # use cursive::views::TextView;
+    /// let mut siv = Cursive::dummy();
+    /// siv.add_layer(TextView::new("List"));
+    ///
+    /// siv.add_screen_named("settings");
+    /// assert!(siv.switch_to_screen("settings"));
+    /// siv.add_layer(TextView::new("Settings"));
+    /// siv.add_screen_callback(siv.active_screen(), 'q', |s| {
+    ///     s.set_screen(0);
+    /// });
+    ///
+    /// assert_eq!(siv.screen_id("settings"), Some(1));
+    /// assert_eq!(siv.screen_name(1), Some("settings"));
+    /// ```
+    pub fn add_screen_named<S: Into<String>>(&mut self, name: S) -> ScreenId {
+        let id = self.add_screen();
+        self.state.screen_names.insert(name.into(), id);
+        id
+    }
+
+    /// Returns the ID of the screen with the given name.
+    pub fn screen_id(&self, name: &str) -> Option<ScreenId> {
+        self.state.screen_names.get(name).cloned()
+    }
+
+    /// Returns the name of the given screen, if it has one.
+    pub fn screen_name(&self, screen_id: ScreenId) -> Option<&str> {
+        self.state.screen_names
+            .iter()
+            .find(|&(_, &id)| id == screen_id)
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Sets the active screen from its name.
+    ///
+    /// Layers of the previous screen are kept, ready for when it becomes
+    /// active again.
+    ///
+    /// Returns `false` if no screen has this name.
+    pub fn switch_to_screen(&mut self, name: &str) -> bool {
+        match self.screen_id(name) {
+            Some(id) => {
+                self.set_screen(id);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Adds a global callback only used when the given screen is active.
+    ///
+    /// When the screen is active, its callbacks for an event run instead of
+    /// the ones added with `add_global_callback`.
+    pub fn add_screen_callback<F, E>(
+        &mut self, screen_id: ScreenId, event: E, cb: F,
+    ) where
+        F: FnMut(&mut Cursive) + 'static,
+        E: Into<Event>,
+    {
+        self.state.screen_callbacks
+            .entry((screen_id, event.into()))
+            .or_insert_with(Vec::new)
+            .push(Callback::from_fn_mut(cb));
+    }
+
+    /// Removes the callbacks for `event` specific to the given screen.
+    pub fn clear_screen_callbacks<E>(&mut self, screen_id: ScreenId, event: E)
+    where
+        E: Into<Event>,
+    {
+        self.state.screen_callbacks.remove(&(screen_id, event.into()));
+    }
+
     /// Sets the active screen. Panics if no such screen exist.
     pub fn set_screen(&mut self, screen_id: ScreenId) {
-        if screen_id >= self.screens.len() {
+        if screen_id >= self.state.screens.len() {
             panic!(
                 "Tried to set an invalid screen ID: {}, but only {} \
                  screens present.",
                 screen_id,
-                self.screens.len()
+                self.state.screens.len()
             );
         }
-        self.active_screen = screen_id;
+        self.state.active_screen = screen_id;
     }
 
     /// Tries to find the view pointed to by the given selector.
@@ -535,6 +1417,64 @@ impl Cursive {
         self.call_on(&view::Selector::Id(id), callback)
     }
 
+    /// Runs a callback on every view matching `sel`.
+    ///
+    /// Views that are not of the asked type are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::{Cursive, view};
+    /// # use cursive::views::{LinearLayout, TextView};
+    /// # use cursive::traits::*;
+    /// let mut siv = Cursive::dummy();
+    ///
+    /// siv.add_layer(
+    ///     LinearLayout::vertical()
+    ///         .child(TextView::new("").with_id("status-left"))
+    ///         .child(TextView::new("").with_id("status-right")),
+    /// );
+    ///
+    /// siv.call_on_selected(
+    ///     &view::Selector::IdPrefix("status-"),
+    ///     |view: &mut TextView| view.set_content("Ready"),
+    /// );
+    /// ```
+    pub fn call_on_selected<V, F>(
+        &mut self,
+        sel: &view::Selector<'_>,
+        callback: F,
+    ) where
+        V: View + Any,
+        F: FnMut(&mut V),
+    {
+        self.screen_mut().call_on_selected(sel, callback)
+    }
+
+    /// Runs a callback on every view of type `V`, in the current screen.
+    ///
+    /// Convenient method to use `call_on_selected` with a
+    /// `view::Selector::All`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// # use cursive::views::TextView;
+    /// let mut siv = Cursive::dummy();
+    ///
+    /// siv.add_layer(TextView::new("Loading..."));
+    ///
+    /// siv.call_on_all(|view: &mut TextView| view.set_content("Done"));
+    /// ```
+    pub fn call_on_all<V, F>(&mut self, callback: F)
+    where
+        V: View + Any,
+        F: FnMut(&mut V),
+    {
+        self.call_on_selected(&view::Selector::All, callback)
+    }
+
     /// Convenient method to find a view wrapped in [`IdView`].
     ///
     /// This looks for a `IdView<V>` with the given ID, and return
@@ -587,209 +1527,1539 @@ impl Cursive {
         self.call_on_id(id, views::IdView::<V>::get_mut)
     }
 
-    /// Moves the focus to the view identified by `id`.
-    ///
-    /// Convenient method to call `focus` with a `view::Selector::Id`.
-    pub fn focus_id(&mut self, id: &str) -> Result<(), ()> {
-        self.focus(&view::Selector::Id(id))
-    }
-
-    /// Moves the focus to the view identified by `sel`.
-    pub fn focus(&mut self, sel: &view::Selector<'_>) -> Result<(), ()> {
-        self.screen_mut().focus_view(sel)
-    }
-
-    /// Adds a global callback.
+    /// Returns a weak handle to the view with the given id.
     ///
-    /// Will be triggered on the given key press when no view catches it.
+    /// Same as `find_id`, but the handle does not keep the view alive or
+    /// borrowed: it can be stored, and upgraded later if the view still
+    /// exists.
     ///
     /// # Examples
     ///
     /// ```rust
-    /// # extern crate cursive;
-    /// # use cursive::*;
-    /// # fn main() {
+    /// # use cursive::Cursive;
+    /// # use cursive::views::TextView;
+    /// use cursive::traits::Identifiable;
+    ///
     /// let mut siv = Cursive::dummy();
+    /// siv.add_layer(TextView::new("Ready").with_id("status"));
     ///
-    /// siv.add_global_callback('q', |s| s.quit());
-    /// # }
+    /// let status = siv.find_weak_id::<TextView>("status").unwrap();
+    /// siv.add_global_callback('b', move |_| {
+    ///     status.with(|view| view.set_content("Busy"));
+    /// });
     /// ```
-    pub fn add_global_callback<F, E: Into<Event>>(&mut self, event: E, cb: F)
+    pub fn find_weak_id<V>(
+        &mut self, id: &str,
+    ) -> Option<views::WeakViewRef<V>>
     where
-        F: FnMut(&mut Cursive) + 'static,
+        V: View + Any,
     {
-        self.global_callbacks
-            .entry(event.into())
-            .or_insert_with(Vec::new)
-            .push(Callback::from_fn_mut(cb));
+        self.call_on_id(id, |view: &mut views::IdView<V>| view.downgrade())
     }
 
-    /// Removes any callback tied to the given event.
+    /// Returns a handle to the view with the given id, usable from other
+    /// threads.
     ///
-    /// # Examples
+    /// Returns `None` if no view of type `V` has this id. See [`ViewSink`].
     ///
-    /// ```rust
-    /// # extern crate cursive;
-    /// # use cursive::*;
-    /// # fn main() {
-    /// let mut siv = Cursive::dummy();
+    /// Like the `cb_sink`, it is invalidated by `set_cb_sink_capacity`.
     ///
-    /// siv.add_global_callback('q', |s| s.quit());
-    /// siv.clear_global_callbacks('q');
-    /// # }
-    /// ```
-    pub fn clear_global_callbacks<E>(&mut self, event: E)
+    /// [`ViewSink`]: struct.ViewSink.html
+    pub fn view_sink<V>(&mut self, id: &str) -> Option<ViewSink<V>>
     where
-        E: Into<Event>,
+        V: View + Any,
     {
-        let event = event.into();
-        self.global_callbacks.remove(&event);
+        self.call_on_id(id, |_: &mut V| ())?;
+        Some(ViewSink {
+            id: id.to_string(),
+            cb_sink: self.state.cb_sink.clone(),
+            _view: PhantomData,
+        })
     }
 
-    /// Add a layer to the current screen.
+    /// Returns the ids starting with `prefix`, in alphabetical order.
+    ///
+    /// This lists the ids of the [`IdView`]s in all the screens.
     ///
     /// # Examples
     ///
     /// ```rust
-    /// # extern crate cursive;
-    /// # use cursive::*;
-    /// # fn main() {
-    /// let mut siv = Cursive::dummy();
+    /// # use cursive::Cursive;
+    /// # use cursive::views::{LinearLayout, TextView};
+    /// use cursive::traits::Identifiable;
     ///
-    /// siv.add_layer(views::TextView::new("Hello world!"));
-    /// # }
+    /// let mut siv = Cursive::dummy();
+    /// siv.add_layer(
+    ///     LinearLayout::vertical()
+    ///         .child(TextView::new("Ready").with_id("status-left"))
+    ///         .child(TextView::new("0%").with_id("status-right")),
+    /// );
+    ///
+    /// let ids = siv.find_ids_with_prefix("status-");
+    /// assert_eq!(ids, vec!["status-left", "status-right"]);
     /// ```
-    pub fn add_layer<T>(&mut self, view: T)
-    where
-        T: IntoBoxedView,
-    {
-        self.screen_mut().add_layer(view);
-    }
-
-    /// Adds a new full-screen layer to the current screen.
     ///
-    /// Fullscreen layers have no shadow.
-    pub fn add_fullscreen_layer<T>(&mut self, view: T)
-    where
-        T: IntoBoxedView,
-    {
-        self.screen_mut().add_fullscreen_layer(view);
+    /// [`IdView`]: views/struct.IdView.html
+    pub fn find_ids_with_prefix(&mut self, prefix: &str) -> Vec<String> {
+        let mut ids: Vec<String> = self
+            .find_id_views(prefix)
+            .iter()
+            .map(views::AnyIdView::get_id)
+            .collect();
+        ids.sort();
+        ids.dedup();
+        ids
     }
 
-    /// Convenient method to remove a layer from the current screen.
-    pub fn pop_layer(&mut self) -> Option<Box<dyn View>> {
-        self.screen_mut().pop_layer()
+    // Returns the `IdView`s with an id starting with `prefix`, in all the
+    // screens.
+    fn find_id_views(&mut self, prefix: &str) -> Vec<views::AnyIdView> {
+        self.state.screens
+            .iter_mut()
+            .flat_map(|screen| views::find_id_views(screen, prefix))
+            .collect()
     }
 
-    /// Convenient stub forwarding layer repositioning.
-    pub fn reposition_layer(
-        &mut self,
-        layer: LayerPosition,
-        position: Position,
-    ) {
-        self.screen_mut().reposition_layer(layer, position);
-    }
+    // Returns an error if `view` uses an id already in the screens, or
+    // uses the same id twice.
+    fn check_ids(
+        &mut self, view: &mut dyn View,
+    ) -> Result<(), views::IdError> {
+        // Most layers have no id: the screens are only walked for the
+        // others.
+        let mut id_views = views::find_id_views(view, "");
+        if id_views.is_empty() {
+            return Ok(());
+        }
 
-    // Handles a key event when it was ignored by the current view
-    fn on_ignored_event(&mut self, event: Event) {
-        let cb_list = match self.global_callbacks.get(&event) {
-            None => return,
-            Some(cb_list) => cb_list.clone(),
-        };
-        // Not from a view, so no viewpath here
-        for cb in cb_list {
-            cb(self);
+        id_views.extend(self.find_id_views(""));
+        match views::find_duplicate(&id_views) {
+            Some(id) => Err(views::IdError::Duplicate(id)),
+            None => Ok(()),
         }
     }
 
-    /// Processes an event.
-    ///
-    /// * If the menubar is active, it will be handled the event.
-    /// * The view tree will be handled the event.
-    /// * If ignored, global_callbacks will be checked for this event.
-    pub fn on_event(&mut self, event: Event) {
-        if event == Event::Exit {
-            self.quit();
+    // Logs a warning if `view` brings a duplicate id.
+    //
+    // This walks every view of the screens, so it only runs in debug builds.
+    fn warn_duplicate_ids(&mut self, view: &mut dyn View) {
+        if !cfg!(debug_assertions) {
+            return;
         }
 
-        if event == Event::WindowResize {
-            self.clear();
+        if let Err(views::IdError::Duplicate(id)) = self.check_ids(view) {
+            warn!("Duplicate view id: {:?}", id);
         }
+    }
 
-        if let Event::Mouse {
-            event, position, ..
-        } = event
-        {
-            if event.grabs_focus()
-                && !self.menubar.autohide
-                && !self.menubar.has_submenu()
-                && position.y == 0
-            {
-                self.select_menubar();
-            }
+    /// Renames the view identified by `old` to `new`.
+    ///
+    /// Returns an error if no view has the id `old`, or if a view already
+    /// has the id `new`.
+    pub fn rename_id(
+        &mut self, old: &str, new: &str,
+    ) -> Result<(), views::IdError> {
+        let id_views = self.find_id_views("");
+        if old != new && id_views.iter().any(|view| view.get_id() == new) {
+            return Err(views::IdError::Duplicate(new.to_string()));
         }
 
-        // Event dispatch order:
-        // * Focused element:
-        //     * Menubar (if active)
-        //     * Current screen (top layer)
-        // * Global callbacks
-        if self.menubar.receive_events() {
-            self.menubar.on_event(event).process(self);
+        let mut found = false;
+        for mut view in id_views.into_iter().filter(|v| v.get_id() == old) {
+            view.set_id(new);
+            found = true;
+        }
+        if found {
+            Ok(())
         } else {
-            let offset = if self.menubar.autohide { 0 } else { 1 };
-            match self.screen_mut().on_event(event.relativized((0, offset))) {
-                // If the event was ignored,
-                // it is our turn to play with it.
-                EventResult::Ignored => self.on_ignored_event(event),
-                EventResult::Consumed(None) => (),
-                EventResult::Consumed(Some(cb)) => cb(self),
-            }
+            Err(views::IdError::NotFound(old.to_string()))
         }
     }
 
-    /// Returns the size of the screen, in characters.
-    pub fn screen_size(&self) -> Vec2 {
-        self.backend.screen_size()
+    /// Returns the state of the views with an id.
+    ///
+    /// This includes scroll offsets, selected items and edited text. See
+    /// the [`state`](state/index.html) module for the supported views.
+    ///
+    /// Returns an error if two views have the same id, or if a view is
+    /// already borrowed (for example when called from its own callback).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// # use cursive::state::ViewState;
+    /// # use cursive::traits::Identifiable;
+    /// # use cursive::views::EditView;
+    /// let mut siv = Cursive::dummy();
+    /// siv.add_layer(EditView::new().content("draft").with_id("message"));
+    ///
+    /// let state = siv.save_ui_state().unwrap();
+    /// assert_eq!(
+    ///     state.get("message"),
+    ///     Some(&ViewState::Content("draft".into()))
+    /// );
+    ///
+    /// // Later, maybe after a restart.
+    /// siv.pop_layer();
+    /// siv.add_layer(EditView::new().with_id("message"));
+    /// siv.restore_ui_state(&state).unwrap();
+    /// ```
+    pub fn save_ui_state(&mut self) -> Result<UiState, views::IdError> {
+        let mut state = UiState::new();
+        for view in self.find_unique_id_views()? {
+            let id = view.get_id();
+            let view_state = view
+                .with_view(|v| v.save_state())
+                .ok_or_else(|| views::IdError::Borrowed(id.clone()))?;
+            if let Some(view_state) = view_state {
+                state.insert(id, view_state);
+            }
+        }
+        Ok(state)
     }
 
-    fn layout(&mut self) {
-        let size = self.screen_size();
-        let offset = if self.menubar.autohide { 0 } else { 1 };
-        let size = size.saturating_sub((0, offset));
-        self.screen_mut().layout(size);
+    /// Restores the state returned by `save_ui_state`.
+    ///
+    /// Views are matched by id, and the ones missing from `state` are left
+    /// unchanged. The views are laid out first, so scroll offsets can be
+    /// restored.
+    ///
+    /// Returns an error if two views have the same id, or if a view is
+    /// already borrowed. Nothing is restored on a duplicate id; a borrowed
+    /// view stops the restoration there.
+    pub fn restore_ui_state(
+        &mut self, state: &UiState,
+    ) -> Result<(), views::IdError> {
+        self.layout();
+        for view in self.find_unique_id_views()? {
+            let id = view.get_id();
+            if let Some(view_state) = state.get(&id) {
+                view.with_view(|v| v.restore_state(view_state))
+                    .ok_or(views::IdError::Borrowed(id))?;
+            }
+        }
+        Ok(())
     }
 
-    fn draw(&mut self) {
-        let sizes = self.screen().layer_sizes();
-        if self.last_sizes != sizes {
-            self.clear();
-            self.last_sizes = sizes;
+    // Returns all the `IdView`s, or an error if two have the same id.
+    fn find_unique_id_views(
+        &mut self,
+    ) -> Result<Vec<views::AnyIdView>, views::IdError> {
+        let id_views = self.find_id_views("");
+        match views::find_duplicate(&id_views) {
+            Some(id) => Err(views::IdError::Duplicate(id)),
+            None => Ok(id_views),
         }
+    }
 
-        let printer =
-            Printer::new(self.screen_size(), &self.theme, &*self.backend);
+    /// Sets a callback to run when the terminal is resized.
+    ///
+    /// It is given the old and the new size of the screen, before the views
+    /// receive the `Event::WindowResize` event.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// # use cursive::views::TextView;
+    /// let mut siv = Cursive::dummy();
+    /// siv.set_on_resize(|s, old, new| {
+    ///     if old.x >= 80 && new.x < 80 {
+    ///         // Switch to a compact layout.
+    ///         s.pop_layer();
+    ///         s.add_layer(TextView::new("Compact"));
+    ///     }
+    /// });
+    /// ```
+    pub fn set_on_resize<F>(&mut self, cb: F)
+    where
+        F: Fn(&mut Cursive, Vec2, Vec2) + 'static,
+    {
+        self.state.on_resize = Some(Rc::new(cb));
+    }
+
+    /// Removes the callback set by `set_on_resize`.
+    pub fn clear_on_resize(&mut self) {
+        self.state.on_resize = None;
+    }
+
+    /// Sends all mouse events to the view with the given id, until the next
+    /// button release.
+    ///
+    /// The view receives them wherever the mouse is, even outside of its
+    /// area, and whatever the focus. Events are relative to the view, as
+    /// usual.
+    ///
+    /// Views consuming a mouse press already receive the following drag in
+    /// most containers. This makes sure of it, for example from the callback
+    /// of a view which does not take the focus, like a divider.
+    ///
+    /// Moves with a pressed button arrive as `MouseEvent::Hold`. Most
+    /// backends report them, but not the dummy one.
+    ///
+    /// # Errors
+    ///
+    /// Fails if no view of type `V` has this id in the current screen, or if
+    /// it is already borrowed. The mouse is then not captured.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// # use cursive::backend::puppet;
+    /// # use cursive::event::{Event, EventResult, MouseButton, MouseEvent};
+    /// # use cursive::traits::Identifiable;
+    /// # use cursive::views::Canvas;
+    /// let (backend, puppet) = puppet::Backend::init((20, 5));
+    /// let mut siv = Cursive::new(move || backend);
+    ///
+    /// // Remembers the last position of the mouse, once pressed.
+    /// let handle = Canvas::new(None).with_on_event(|pos, event| match event {
+    ///     Event::Mouse { event: MouseEvent::Press(_), .. } => {
+    ///         EventResult::with_cb(|s| {
+    ///             s.capture_mouse::<Canvas<Option<usize>>>("handle")
+    ///                 .unwrap();
+    ///         })
+    ///     }
+    ///     Event::Mouse { event: MouseEvent::Hold(_), position, .. } => {
+    ///         *pos = Some(position.x);
+    ///         EventResult::Consumed(None)
+    ///     }
+    ///     _ => EventResult::Ignored,
+    /// });
+    /// siv.add_fullscreen_layer(handle.with_id("handle"));
+    /// siv.refresh();
+    ///
+    /// let mouse = |x, event| Event::Mouse {
+    ///     offset: (0, 0).into(),
+    ///     position: (x, 0).into(),
+    ///     event,
+    /// };
+    /// puppet.send(mouse(0, MouseEvent::Press(MouseButton::Left)));
+    /// puppet.send(mouse(15, MouseEvent::Hold(MouseButton::Left)));
+    /// while siv.step() {}
+    /// assert_eq!(siv.get_mouse_capture(), Some("handle"));
+    ///
+    /// puppet.send(mouse(15, MouseEvent::Release(MouseButton::Left)));
+    /// while siv.step() {}
+    /// assert_eq!(siv.get_mouse_capture(), None);
+    ///
+    /// let pos = siv.call_on_id("handle", |view: &mut Canvas<Option<usize>>| {
+    ///     *view.state_mut()
+    /// });
+    /// assert_eq!(pos, Some(Some(15)));
+    /// ```
+    pub fn capture_mouse<V>(&mut self, id: &str) -> Result<(), views::IdError>
+    where
+        V: View + Any,
+    {
+        if self.call_on_id(id, |_: &mut V| ()).is_none() {
+            let view = views::find_id_views(self.screen_mut(), id)
+                .into_iter()
+                .find(|view| view.get_id() == id);
+            return Err(match view {
+                None => views::IdError::NotFound(id.to_string()),
+                Some(ref view) if view.with_view(|_| ()).is_none() => {
+                    views::IdError::Borrowed(id.to_string())
+                }
+                Some(_) => views::IdError::WrongType(id.to_string()),
+            });
+        }
+
+        let handler_id = id.to_string();
+        let handler = move |s: &mut Cursive, event: Event| {
+            s.call_on_id(&handler_id, |view: &mut V| view.on_event(event))
+        };
+        self.mouse_capture = Some((id.to_string(), Rc::new(handler)));
+        Ok(())
+    }
+
+    /// Stops sending all mouse events to the view set by `capture_mouse`.
+    pub fn release_mouse(&mut self) {
+        self.mouse_capture = None;
+    }
+
+    /// Returns the id of the view capturing the mouse, if any.
+    pub fn get_mouse_capture(&self) -> Option<&str> {
+        self.mouse_capture.as_ref().map(|(id, _)| id.as_str())
+    }
+
+    /// Sets the maximum delay between the presses of a double click.
+    ///
+    /// After a press, another press with the same button within this delay
+    /// and close enough (see `set_click_tolerance`) is followed by a
+    /// `MouseEvent::DoubleClick` event. A third one is followed by a
+    /// `MouseEvent::TripleClick` event.
+    ///
+    /// Defaults to 500ms.
+    pub fn set_click_interval(&mut self, interval: Duration) {
+        self.state.click_interval = interval;
+    }
+
+    /// Returns the maximum delay between the presses of a double click.
+    pub fn get_click_interval(&self) -> Duration {
+        self.state.click_interval
+    }
+
+    /// Sets how many cells the mouse can move between the presses of a
+    /// double click.
+    ///
+    /// Defaults to 1.
+    pub fn set_click_tolerance(&mut self, tolerance: usize) {
+        self.state.click_tolerance = tolerance;
+    }
+
+    /// Returns how many cells the mouse can move during a double click.
+    pub fn get_click_tolerance(&self) -> usize {
+        self.state.click_tolerance
+    }
+
+    // Returns the double or triple click completed by `event`, if any.
+    fn repeated_click(&mut self, event: &Event) -> Option<Event> {
+        let (position, button) = match *event {
+            Event::Mouse {
+                position,
+                event: MouseEvent::Press(button),
+                ..
+            } => (position, button),
+            _ => return None,
+        };
+
+        let now = Instant::now();
+        let distance = |a: usize, b: usize| a.max(b) - a.min(b);
+        let count = match self.last_click {
+            Some(ref click)
+                if click.button == button
+                    && click.count < 3
+                    && now.duration_since(click.time)
+                        <= self.state.click_interval
+                    && distance(click.position.x, position.x)
+                        <= self.state.click_tolerance
+                    && distance(click.position.y, position.y)
+                        <= self.state.click_tolerance =>
+            {
+                click.count + 1
+            }
+            _ => 1,
+        };
+        self.last_click = Some(Click {
+            time: now,
+            position,
+            button,
+            count,
+        });
+
+        let event = match count {
+            2 => MouseEvent::DoubleClick(button),
+            3 => MouseEvent::TripleClick(button),
+            _ => return None,
+        };
+        Some(Event::Mouse {
+            offset: Vec2::zero(),
+            position,
+            event,
+        })
+    }
+
+    /// Moves the focus to the view identified by `id`.
+    ///
+    /// Convenient method to call `focus` with a `view::Selector::Id`.
+    pub fn focus_id(&mut self, id: &str) -> Result<(), view::ViewNotFound> {
+        self.focus(&view::Selector::Id(id))?;
+
+        // Until the next draw tells which views have the focus.
+        if self.state.tab_order.iter().any(|tab| tab == id) {
+            for view in self.tab_order_views() {
+                view.set_focused(view.get_id() == id);
+            }
+        }
+        Ok(())
+    }
+
+    /// Moves the focus to the next focusable view of the current screen.
+    ///
+    /// Views are visited in layout order, going through nested containers,
+    /// and coming back to the first view after the last one. If a tab order
+    /// was set, it is followed instead.
+    ///
+    /// This is what happens when `Tab` is not used by the views.
+    ///
+    /// Returns `false` if no view could take the focus.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// # use cursive::traits::Identifiable;
+    /// # use cursive::views::{EditView, LinearLayout};
+    /// let mut siv = Cursive::dummy();
+    /// siv.add_layer(
+    ///     LinearLayout::vertical()
+    ///         .child(EditView::new().with_id("name"))
+    ///         .child(EditView::new().with_id("email")),
+    /// );
+    ///
+    /// assert!(siv.focus_next());
+    /// // From the last view, the focus comes back to the first one.
+    /// assert!(siv.focus_next());
+    /// ```
+    pub fn focus_next(&mut self) -> bool {
+        self.move_focus(direction::Direction::front())
+    }
+
+    /// Moves the focus to the previous focusable view of the current screen.
+    ///
+    /// This is what happens when `Shift+Tab` is not used by the views.
+    ///
+    /// Returns `false` if no view could take the focus.
+    pub fn focus_prev(&mut self) -> bool {
+        self.move_focus(direction::Direction::back())
+    }
+
+    fn move_focus(&mut self, source: direction::Direction) -> bool {
+        let forward = source == direction::Direction::front();
+
+        if !self.state.tab_order.is_empty() {
+            return self.move_tab_order(forward);
+        }
+
+        let event = if forward {
+            Event::Key(Key::Tab)
+        } else {
+            Event::Shift(Key::Tab)
+        };
+        match self.screen_mut().on_event(event) {
+            EventResult::Consumed(None) => true,
+            EventResult::Consumed(Some(cb)) => {
+                cb(self);
+                true
+            }
+            // The focus reached the edge: start again from the other side.
+            EventResult::Ignored => self.screen_mut().take_focus(source),
+        }
+    }
+
+    // Returns the `IdView`s of the current screen in the tab order.
+    fn tab_order_views(&mut self) -> Vec<views::AnyIdView> {
+        let tab_order = &self.state.tab_order;
+        let screen = &mut self.state.screens[self.state.active_screen];
+        views::find_id_views(screen, "")
+            .into_iter()
+            .filter(|view| tab_order.contains(&view.get_id()))
+            .collect()
+    }
+
+    // Returns the focused view of the tab order, and its position.
+    //
+    // Views are found from the outside in: the last focused one is the
+    // innermost.
+    fn focused_tab(&mut self) -> Option<(usize, views::AnyIdView)> {
+        let view = self
+            .tab_order_views()
+            .into_iter()
+            .filter(views::AnyIdView::is_focused)
+            .last()?;
+        let id = view.get_id();
+        let i = self.state.tab_order.iter().position(|tab| *tab == id)?;
+        Some((i, view))
+    }
+
+    // Gives `Tab` to the focused view of the tab order, but not to the
+    // containers around it, which would move the focus themselves.
+    fn on_tab_order_event(&mut self, event: Event) -> bool {
+        let result = self
+            .focused_tab()
+            .and_then(|(_, view)| {
+                view.with_view(|view| view.on_event(event.clone()))
+            })
+            .unwrap_or(EventResult::Ignored);
+
+        match result {
+            EventResult::Ignored => self.on_ignored_event(event),
+            EventResult::Consumed(None) => true,
+            EventResult::Consumed(Some(cb)) => {
+                cb(self);
+                true
+            }
+        }
+    }
+
+    fn move_tab_order(&mut self, forward: bool) -> bool {
+        let len = self.state.tab_order.len();
+        let position = self.focused_tab().map(|(i, _)| i);
+        for step in 1..=len {
+            let i = match (position, forward) {
+                (None, true) => step - 1,
+                (None, false) => len - step,
+                (Some(i), true) => (i + step) % len,
+                (Some(i), false) => (i + len - step) % len,
+            };
+            let id = self.state.tab_order[i].clone();
+            if self.focus_id(&id).is_ok() {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Sets an explicit tab order, as a list of view ids.
+    ///
+    /// `Tab` and `Shift+Tab` then go through these views in this order,
+    /// instead of the layout order. Ids that cannot be found in the current
+    /// screen are skipped.
+    ///
+    /// The order goes on from the view which has the focus, however it got
+    /// it. This view still gets `Tab` first and can use it, like an
+    /// `EditView` with a completer, but its containers do not.
+    ///
+    /// An empty list restores the layout order.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// let mut siv = Cursive::dummy();
+    /// siv.set_tab_order(vec!["email", "name", "ok"]);
+    /// assert_eq!(siv.get_tab_order()[0], "email");
+    /// ```
+    pub fn set_tab_order<I, S>(&mut self, ids: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.state.tab_order = ids.into_iter().map(Into::into).collect();
+    }
+
+    /// Returns the explicit tab order.
+    ///
+    /// It is empty if the layout order is used.
+    pub fn get_tab_order(&self) -> &[String] {
+        &self.state.tab_order
+    }
+
+    /// Moves the focus to the view identified by `sel`.
+    ///
+    /// Returns an error if no view of the current screen matches `sel`, or
+    /// if it cannot take the focus.
+    pub fn focus(
+        &mut self, sel: &view::Selector<'_>,
+    ) -> Result<(), view::ViewNotFound> {
+        self.screen_mut()
+            .focus_view(sel)
+            .map_err(|()| view::ViewNotFound)
+    }
+
+    /// Adds a global callback.
+    ///
+    /// Will be triggered on the given key press when no view catches it.
+    ///
+    /// The keymap can bind it to other keys, using the name of `event` as
+    /// the action name (see the [`keymap`] module).
+    ///
+    /// [`keymap`]: keymap/index.html
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # extern crate cursive;
+    /// # use cursive::*;
+    /// # fn main() {
+    /// let mut siv = Cursive::dummy();
+    ///
+    /// siv.add_global_callback('q', |s| s.quit());
+    /// # }
+    /// ```
+    pub fn add_global_callback<F, E: Into<Event>>(&mut self, event: E, cb: F)
+    where
+        F: FnMut(&mut Cursive) + 'static,
+    {
+        let event = event.into();
+        self.push_action(Action {
+            name: keymap::event_name(&event),
+            default: event,
+            callback: Callback::from_fn_mut(cb),
+        });
+    }
+
+    /// Adds a named global action, triggered by `default` unless the keymap
+    /// binds it to other keys.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// # use cursive::event::Event;
+    /// # use cursive::keymap::Keymap;
+    /// let mut siv = Cursive::dummy();
+    /// siv.add_action("quit", 'q', Cursive::quit);
+    ///
+    /// // Users can then pick another key, for example from a file.
+    /// siv.set_keymap(Keymap::new().binding("quit", vec!['x']));
+    /// ```
+    pub fn add_action<S, E, F>(&mut self, name: S, default: E, cb: F)
+    where
+        S: Into<String>,
+        E: Into<Event>,
+        F: FnMut(&mut Cursive) + 'static,
+    {
+        self.push_action(Action {
+            name: Some(name.into()),
+            default: default.into(),
+            callback: Callback::from_fn_mut(cb),
+        });
+    }
+
+    fn push_action(&mut self, action: Action) {
+        for event in action.events(&self.state.keymap) {
+            self.state.global_callbacks
+                .entry(event)
+                .or_insert_with(Vec::new)
+                .push(action.callback.clone());
+        }
+        self.state.actions.push(action);
+    }
+
+    /// Sets the keymap, binding global actions to new keys.
+    ///
+    /// This can be called again at any time, for example to reload the
+    /// keymap after the user edited it.
+    pub fn set_keymap(&mut self, keymap: keymap::Keymap) {
+        self.state.keymap = keymap;
+        self.state.global_callbacks.clear();
+        for action in std::mem::take(&mut self.state.actions) {
+            self.push_action(action);
+        }
+    }
+
+    /// Returns the current keymap.
+    pub fn get_keymap(&self) -> &keymap::Keymap {
+        &self.state.keymap
+    }
+
+    /// Loads a keymap from the given toml file, and sets it.
+    pub fn load_keymap_file<P: AsRef<Path>>(
+        &mut self, filename: P,
+    ) -> Result<(), keymap::Error> {
+        keymap::Keymap::load_file(filename).map(|k| self.set_keymap(k))
+    }
+
+    /// Removes any callback tied to the given event.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # extern crate cursive;
+    /// # use cursive::*;
+    /// # fn main() {
+    /// let mut siv = Cursive::dummy();
+    ///
+    /// siv.add_global_callback('q', |s| s.quit());
+    /// siv.clear_global_callbacks('q');
+    /// # }
+    /// ```
+    pub fn clear_global_callbacks<E>(&mut self, event: E)
+    where
+        E: Into<Event>,
+    {
+        let event = event.into();
+        self.state.global_callbacks.remove(&event);
+
+        let keymap = &self.state.keymap;
+        self.state.actions
+            .retain(|action| !action.events(keymap).contains(&event));
+    }
+
+    /// Adds a filter, run on every event before it is dispatched.
+    ///
+    /// The filter receives the event, and returns the event to dispatch
+    /// instead, or `None` to drop it. Filters run in the order they were
+    /// added, each receiving the event returned by the previous one. This
+    /// can remap keys for the whole application, log events, or disable
+    /// some of them.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// # use cursive::event::{Event, Key};
+    /// let mut siv = Cursive::dummy();
+    ///
+    /// // Vim-like navigation everywhere.
+    /// siv.add_event_filter(|_, event| match event {
+    ///     Event::Char('j') => Some(Event::Key(Key::Down)),
+    ///     Event::Char('k') => Some(Event::Key(Key::Up)),
+    ///     event => Some(event),
+    /// });
+    ///
+    /// // Kiosk mode: Ctrl-C does nothing.
+    /// siv.add_event_filter(|_, event| match event {
+    ///     Event::CtrlChar('c') => None,
+    ///     event => Some(event),
+    /// });
+    /// ```
+    pub fn add_event_filter<F>(&mut self, filter: F)
+    where
+        F: Fn(&mut Cursive, Event) -> Option<Event> + 'static,
+    {
+        self.state.event_filters.push(Rc::new(filter));
+    }
+
+    /// Removes all event filters.
+    pub fn clear_event_filters(&mut self) {
+        self.state.event_filters.clear();
+    }
+
+    // Runs the event filters, returning the event to dispatch, if any.
+    fn filter_event(&mut self, event: Event) -> Option<Event> {
+        // Filters may add other filters.
+        let filters = self.state.event_filters.clone();
+        filters
+            .iter()
+            .try_fold(event, |event, filter| filter(self, event))
+    }
+
+    /// Add a layer to the current screen.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # extern crate cursive;
+    /// # use cursive::*;
+    /// # fn main() {
+    /// let mut siv = Cursive::dummy();
+    ///
+    /// siv.add_layer(views::TextView::new("Hello world!"));
+    /// # }
+    /// ```
+    ///
+    /// In debug builds, logs a warning if the view uses an id already in the
+    /// screens.
+    pub fn add_layer<T>(&mut self, view: T)
+    where
+        T: IntoBoxedView,
+    {
+        let mut view = view.as_boxed_view();
+        self.warn_duplicate_ids(&mut *view);
+        self.screen_mut().add_layer(view);
+    }
+
+    /// Adds a layer to the current screen, unless it brings a duplicate id.
+    ///
+    /// Returns an error if the view uses an id already in the screens, or
+    /// uses the same id twice. The view is not added then.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// # use cursive::traits::Identifiable;
+    /// # use cursive::views::{IdError, TextView};
+    /// let mut siv = Cursive::dummy();
+    ///
+    /// let ready = TextView::new("Ready").with_id("status");
+    /// assert!(siv.try_add_layer(ready).is_ok());
+    ///
+    /// let busy = TextView::new("Busy").with_id("status");
+    /// let error = IdError::Duplicate("status".into());
+    /// assert_eq!(siv.try_add_layer(busy), Err(error));
+    /// ```
+    pub fn try_add_layer<T>(&mut self, view: T) -> Result<(), views::IdError>
+    where
+        T: IntoBoxedView,
+    {
+        let mut view = view.as_boxed_view();
+        self.check_ids(&mut *view)?;
+        self.screen_mut().add_layer(view);
+        Ok(())
+    }
+
+    /// Adds a new non-modal layer to the current screen.
+    ///
+    /// See `StackView::add_non_modal_layer`.
+    pub fn add_non_modal_layer<T>(&mut self, view: T)
+    where
+        T: IntoBoxedView,
+    {
+        let mut view = view.as_boxed_view();
+        self.warn_duplicate_ids(&mut *view);
+        self.screen_mut().add_non_modal_layer(view);
+    }
+
+    /// Adds a new full-screen layer to the current screen.
+    ///
+    /// Fullscreen layers have no shadow.
+    pub fn add_fullscreen_layer<T>(&mut self, view: T)
+    where
+        T: IntoBoxedView,
+    {
+        let mut view = view.as_boxed_view();
+        self.warn_duplicate_ids(&mut *view);
+        self.screen_mut().add_fullscreen_layer(view);
+    }
+
+    /// Convenient method to remove a layer from the current screen.
+    pub fn pop_layer(&mut self) -> Option<Box<dyn View>> {
+        self.screen_mut().pop_layer()
+    }
+
+    /// Convenient method to close the top layer of the current screen.
+    ///
+    /// Unlike `pop_layer`, this plays the layer's transition, if any.
+    ///
+    /// Returns `false` if there was no layer to close.
+    pub fn close_layer(&mut self) -> bool {
+        self.screen_mut().close_layer()
+    }
+
+    /// Convenient stub forwarding layer repositioning.
+    pub fn reposition_layer(
+        &mut self,
+        layer: LayerPosition,
+        position: Position,
+    ) {
+        self.screen_mut().reposition_layer(layer, position);
+    }
+
+    // Handles a key event when it was ignored by the current view.
+    //
+    // Returns `true` if anything used the event.
+    fn on_ignored_event(&mut self, event: Event) -> bool {
+        if self.state.menubar.reveals_on(&event) {
+            self.select_menubar();
+            return true;
+        }
+
+        let key = (self.state.active_screen, event.clone());
+        let cb_list = match self
+            .state
+            .screen_callbacks
+            .get(&key)
+            .or_else(|| self.state.global_callbacks.get(&event))
+        {
+            None => {
+                return if event == Event::Key(Key::Tab) {
+                    self.focus_next()
+                } else if event == Event::Shift(Key::Tab) {
+                    self.focus_prev()
+                } else if event == Event::CtrlChar('z')
+                    && self.state.suspend_on_ctrl_z
+                {
+                    self.stop_process();
+                    true
+                } else {
+                    false
+                };
+            }
+            Some(cb_list) => cb_list.clone(),
+        };
+        // Not from a view, so no viewpath here
+        for cb in cb_list {
+            cb(self);
+        }
+        true
+    }
+
+    /// Processes an event.
+    ///
+    /// * Event filters can change or drop the event first.
+    /// * If the menubar is active, it will be handled the event.
+    /// * The view tree will be handled the event.
+    /// * If ignored, global_callbacks will be checked for this event.
+    /// * If still ignored, `Tab` and `Shift+Tab` move the focus around.
+    pub fn on_event(&mut self, event: Event) {
+        let event = match self.filter_event(event) {
+            Some(event) => event,
+            None => return,
+        };
+        let repeated_click = self.repeated_click(&event);
+        self.dispatch_event(event);
+
+        // The press itself comes first.
+        let repeated_click =
+            repeated_click.and_then(|event| self.filter_event(event));
+        if let Some(event) = repeated_click {
+            self.dispatch_event(event);
+        }
+    }
+
+    // Processes an event, and returns `true` if anything used it.
+    fn dispatch_event(&mut self, mut event: Event) -> bool {
+        if event == Event::Exit {
+            self.quit();
+        }
+
+        if let Some(consumed) = self.dispatch_drag_event(&event) {
+            return consumed;
+        }
+        if let Some(consumed) = self.dispatch_captured_mouse(&event) {
+            return consumed;
+        }
+        // A press may start a drag: it starts under the mouse.
+        let mouse_position = event.mouse_position();
+
+        if let Event::WindowResize(_) = event {
+            self.clear();
+
+            let size = self.screen_size();
+            event = Event::WindowResize(size);
+            let old_size =
+                std::mem::replace(&mut self.state.last_screen_size, size);
+            if old_size != size {
+                if let Some(cb) = self.state.on_resize.clone() {
+                    cb(self, old_size, size);
+                }
+            }
+        }
+
+        if let Event::Mouse {
+            event, position, ..
+        } = event
+        {
+            if event.grabs_focus()
+                && !self.state.menubar.autohide
+                && !self.state.menubar.has_submenu()
+                && position.y == 0
+            {
+                self.select_menubar();
+            }
+        }
+
+        // Event dispatch order:
+        // * Focused element:
+        //     * Menubar (if active)
+        //     * Current screen (top layer)
+        // * Global callbacks
+        let consumed = if self.state.menubar.receive_events() {
+            let result = self.state.menubar.on_event(event);
+            let consumed = result.is_consumed();
+            result.process(self);
+            consumed
+        } else if !self.state.tab_order.is_empty()
+            && (event == Event::Key(Key::Tab)
+                || event == Event::Shift(Key::Tab))
+        {
+            // An explicit tab order overrides the containers.
+            self.on_tab_order_event(event)
+        } else {
+            let offset = if self.state.menubar.autohide { 0 } else { 1 };
+            match self.screen_mut().on_event(event.relativized((0, offset))) {
+                // If the event was ignored,
+                // it is our turn to play with it.
+                EventResult::Ignored => self.on_ignored_event(event),
+                EventResult::Consumed(None) => true,
+                EventResult::Consumed(Some(cb)) => {
+                    cb(self);
+                    true
+                }
+            }
+        };
+
+        if let Some(position) = mouse_position {
+            drag::move_to(position);
+        }
+        consumed
+    }
+
+    // Turns mouse moves into drag events while a drag is active.
+    //
+    // Returns `None` if the event should be dispatched as usual.
+    fn dispatch_drag_event(&mut self, event: &Event) -> Option<bool> {
+        if !drag::is_active() {
+            return None;
+        }
+
+        let (position, drag_event) = match *event {
+            Event::Key(Key::Esc) => {
+                self.finish_drag(false);
+                return Some(true);
+            }
+            Event::Mouse {
+                position,
+                event: MouseEvent::Hold(_),
+                ..
+            } => (position, MouseEvent::DragOver),
+            Event::Mouse {
+                position,
+                event: MouseEvent::Release(_),
+                ..
+            } => (position, MouseEvent::Drop),
+            _ => return None,
+        };
+
+        // The ghost leaves a trail behind it.
+        if drag::move_to(position) {
+            self.screen().invalidate_background();
+        }
+
+        let event = Event::Mouse {
+            offset: Vec2::zero(),
+            position,
+            event: drag_event,
+        };
+        let offset = if self.state.menubar.autohide { 0 } else { 1 };
+        let event = event.relativized((0, offset));
+        let result = self.screen_mut().on_event(event);
+        let consumed = result.is_consumed();
+        result.process(self);
+
+        if drag_event == MouseEvent::Drop {
+            self.finish_drag(consumed);
+        }
+        Some(true)
+    }
+
+    // Sends mouse events to the view capturing the mouse, if any.
+    //
+    // Returns `None` if the event should be dispatched as usual.
+    fn dispatch_captured_mouse(&mut self, event: &Event) -> Option<bool> {
+        let (id, handler) = self.mouse_capture.clone()?;
+        let (position, mouse) = match *event {
+            Event::Mouse {
+                position, event, ..
+            } => (position, event),
+            _ => return None,
+        };
+        if let MouseEvent::Release(_) = mouse {
+            self.release_mouse();
+        }
+
+        let offset = if self.state.menubar.autohide { 0 } else { 1 };
+        let size = self.screen_size().saturating_sub((0, offset));
+        let area = self.screen().locate(&view::Selector::Id(&id), size);
+        let area = match area {
+            Some(area) => area,
+            None => {
+                // The view is gone.
+                self.release_mouse();
+                return None;
+            }
+        };
+
+        let event = Event::Mouse {
+            offset: area.top_left() + (0, offset),
+            position,
+            event: mouse,
+        };
+        let result = handler(self, event)?;
+        let consumed = result.is_consumed();
+        result.process(self);
+        Some(consumed)
+    }
+
+    fn finish_drag(&mut self, dropped: bool) {
+        self.screen().invalidate_background();
+        if let Some(cb) = drag::finish() {
+            cb(self, dropped);
+        }
+    }
+
+    /// Returns the clipboard, shared by the views.
+    ///
+    /// See the [`clipboard`](clipboard/index.html) module.
+    pub fn clipboard(&self) -> clipboard::Clipboard<'_> {
+        clipboard::Clipboard::new(&*self.backend)
+    }
+
+    /// Returns the size of the screen, in characters.
+    pub fn screen_size(&self) -> Vec2 {
+        self.backend.screen_size()
+    }
+
+    // Size available to the layers, below the menubar.
+    fn layout_size(&self) -> Vec2 {
+        let offset = if self.state.menubar.autohide { 0 } else { 1 };
+        self.screen_size().saturating_sub((0, offset))
+    }
+
+    fn layout(&mut self) {
+        let size = self.layout_size();
+        let observer = self.draw_observer.clone();
+
+        let previous =
+            VIEW_TIMERS.with(|timers| timers.replace(Some(Vec::new())));
+        profiler::with_observer(observer, || self.screen_mut().layout(size));
+        let timers = VIEW_TIMERS.with(|timers| timers.replace(previous));
+
+        for (delay, cb) in timers.unwrap_or_default() {
+            self.add_timer(delay, TimerCallback::Once(cb));
+        }
+    }
+
+    /// Asks every view of the current screen to be laid out again, and the
+    /// screen to be redrawn.
+    ///
+    /// On each refresh, views are only laid out again if the screen was
+    /// resized or if `View::needs_relayout` returns `true`. This forces it,
+    /// after a change the views cannot notice themselves.
+    pub fn mark_dirty(&mut self) {
+        self.screen_mut().mark_dirty();
+        self.redraw_pending = true;
+    }
+
+    fn draw(&mut self) {
+        let sizes = self.screen().layer_sizes();
+        if self.last_sizes != sizes {
+            self.clear();
+            self.last_sizes = sizes;
+        }
+
+        let observer = self.draw_observer.clone();
+        profiler::with_observer(observer, || self.draw_to(&*self.backend));
+    }
+
+    /// Draws the screen as it currently is, and returns the drawn cells.
+    ///
+    /// This does not refresh the actual screen. The layout from the last
+    /// refresh is used.
+    pub fn screenshot(&self) -> screen_buffer::ScreenBuffer {
+        let (mut capture, puppet) =
+            backend::puppet::Backend::init(self.screen_size());
+        capture
+            .clear(self.state.theme.palette[theme::PaletteColor::Background]);
+        self.draw_to(&*capture);
+        capture.refresh();
+
+        puppet.screen()
+    }
+
+    // Draws the menubar and the active screen with the given backend.
+    fn draw_to(&self, backend: &dyn backend::Backend) {
+        let printer =
+            Printer::new(self.screen_size(), &self.state.theme, backend);
+
+        let selected = self.state.menubar.receive_events();
+
+        // Print the stackview background before the menubar
+        let offset = if self.state.menubar.autohide { 0 } else { 1 };
+        let id = self.state.active_screen;
+        let sv_printer = printer.offset((0, offset)).focused(!selected);
+
+        self.state.screens[id].draw_bg(&sv_printer);
+
+        // Draw the currently active screen
+        // If the menubar is active, nothing else can be.
+        // Draw the menubar?
+        if self.state.menubar.visible() {
+            let printer = printer.focused(self.state.menubar.receive_events());
+            self.state.menubar.draw(&printer);
+        }
+
+        // finally draw stackview layers
+        // using variables from above
+        self.state.screens[id].draw_fg(&sv_printer);
+
+        drag::with_current(|drag| {
+            if let Some(position) = drag.position() {
+                printer.print_styled(position, drag.get_ghost().into());
+            }
+        });
+    }
+
+    /// Starts recording input events to `writer`.
+    ///
+    /// Each event is written as soon as it is received, in the format
+    /// described in the [`recording`] module. This replaces the current
+    /// recording, if any.
+    ///
+    /// [`recording`]: recording/index.html
+    pub fn record_to<W: Write + 'static>(&mut self, writer: W) {
+        self.recorder = Some(recording::Recorder::new(Box::new(writer)));
+    }
+
+    /// Starts recording input events to the given file.
+    ///
+    /// The file is created, or truncated if it exists.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cursive::Cursive;
+    /// let mut siv = Cursive::default();
+    /// if std::env::var("RECORD_EVENTS").is_ok() {
+    ///     siv.record_to_file("events.txt").unwrap();
+    /// }
+    /// siv.run();
+    /// ```
+    pub fn record_to_file<P: AsRef<Path>>(
+        &mut self, filename: P,
+    ) -> std::io::Result<()> {
+        let file = std::fs::File::create(filename)?;
+        self.record_to(file);
+        Ok(())
+    }
+
+    /// Stops recording input events.
+    pub fn stop_recording(&mut self) {
+        self.recorder = None;
+    }
+
+    /// Returns `true` if input events are being recorded.
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    /// Plays recorded events, as if they came from the backend.
+    ///
+    /// With `realtime`, events are played at their original speed, from
+    /// now on. Otherwise, they are all played during the next step. Input
+    /// from the backend is still received while playing.
+    ///
+    /// This replaces the current playback, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cursive::Cursive;
+    /// # use cursive::recording::Recording;
+    /// let mut siv = Cursive::default();
+    /// let recording = Recording::load_file("events.txt").unwrap();
+    /// siv.play_recording(recording, true);
+    /// siv.run();
+    /// ```
+    pub fn play_recording(
+        &mut self, recording: recording::Recording, realtime: bool,
+    ) {
+        self.player = Some(recording::Player::new(recording, realtime));
+    }
+
+    /// Stops playing recorded events.
+    pub fn stop_playing(&mut self) {
+        self.player = None;
+    }
+
+    /// Returns `true` if recorded events remain to be played.
+    pub fn is_playing(&self) -> bool {
+        self.player.is_some()
+    }
+
+    // Returns the next input event, from the playback or the backend.
+    fn next_input(&mut self) -> Option<Event> {
+        // We are awake now: wake-ups until here are for the input we poll.
+        while self.wake_source.try_recv().is_ok() {}
+
+        if let Some(ref mut player) = self.player {
+            let event = player.next_event();
+            if player.is_done() {
+                self.player = None;
+            }
+            if event.is_some() {
+                return event;
+            }
+        }
+
+        let event = self.backend.poll_event()?;
+        if let Some(ref mut recorder) = self.recorder {
+            recorder.record(&event);
+        }
+        Some(event)
+    }
+
+    /// Calls `cb` once, after `delay`.
+    ///
+    /// The callback is run by the event loop, so its precision depends on
+    /// how often it runs: a few tens of milliseconds when idle.
+    ///
+    /// Returns a handle to cancel the timer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::time::Duration;
+    /// # use cursive::Cursive;
+    /// # use cursive::backend::puppet;
+    /// let (backend, _) = puppet::Backend::init((20, 4));
+    /// let mut siv = Cursive::new(move || backend);
+    ///
+    /// let quit = siv.set_timeout(Duration::from_millis(10), |s| s.quit());
+    /// let cancelled = siv.set_timeout(Duration::from_millis(0), |_| {
+    ///     panic!("Cancelled timers never fire");
+    /// });
+    /// cancelled.cancel();
+    ///
+    /// while siv.is_running() {
+    ///     siv.step();
+    /// }
+    /// assert!(!quit.is_cancelled());
+    /// ```
+    pub fn set_timeout<F>(&mut self, delay: Duration, cb: F) -> TimerHandle
+    where
+        F: FnOnce(&mut Cursive) + 'static,
+    {
+        self.add_timer(delay, TimerCallback::Once(Box::new(cb)))
+    }
+
+    /// Calls `cb` every `interval`, until the returned handle is cancelled.
+    ///
+    /// The first call happens after `interval`. If the event loop falls
+    /// behind, missed calls are skipped rather than run in a burst.
+    ///
+    /// # Panics
+    ///
+    /// If `interval` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::time::Duration;
+    /// # use cursive::Cursive;
+    /// # use cursive::views::TextView;
+    /// # use cursive::traits::Identifiable;
+    /// let mut siv = Cursive::dummy();
+    /// siv.add_layer(TextView::new("0").with_id("counter"));
+    ///
+    /// siv.set_interval(Duration::from_secs(1), |s| {
+    ///     s.call_on_id("counter", |view: &mut TextView| {
+    ///         let count: u32 = view.get_content().source().parse().unwrap();
+    ///         view.set_content((count + 1).to_string());
+    ///     });
+    /// });
+    /// ```
+    pub fn set_interval<F>(&mut self, interval: Duration, cb: F) -> TimerHandle
+    where
+        F: Fn(&mut Cursive) + 'static,
+    {
+        assert!(interval > Duration::from_secs(0), "interval cannot be zero");
+
+        let callback = TimerCallback::Repeat(Rc::new(cb), interval);
+        self.add_timer(interval, callback)
+    }
+
+    fn add_timer(
+        &mut self, delay: Duration, callback: TimerCallback,
+    ) -> TimerHandle {
+        let handle = TimerHandle::new();
+        self.state.timers.push(Timer {
+            deadline: Instant::now() + delay,
+            callback,
+            handle: handle.clone(),
+        });
+        handle
+    }
+
+    /// Adds a callback, run when the process receives `signal`.
+    ///
+    /// The signal handler only takes note of the signal: the callback runs
+    /// later, from the event loop, where it can safely use the interface.
+    /// This replaces the default action of the signal, so a callback for
+    /// `SIGTERM` can save the work in progress before calling `quit`.
+    ///
+    /// Returns an error for the signals which cannot be handled, like
+    /// `SIGKILL`.
+    ///
+    /// Only available on unix.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// # use cursive::backend::puppet;
+    /// # let (backend, _) = puppet::Backend::init((20, 4));
+    /// let mut siv = Cursive::new(move || backend);
+    /// siv.add_signal_callback(libc::SIGUSR1, |s| s.set_user_data("dump"))
+    ///     .unwrap();
+    ///
+    /// unsafe { libc::raise(libc::SIGUSR1) };
+    /// siv.step();
+    /// assert_eq!(siv.user_data::<&str>(), Some(&mut "dump"));
+    /// ```
+    #[cfg(unix)]
+    pub fn add_signal_callback<F>(
+        &mut self, signal: libc::c_int, cb: F,
+    ) -> io::Result<()>
+    where
+        F: FnMut(&mut Cursive) + 'static,
+    {
+        let received = Arc::new(AtomicBool::new(false));
+        let hook = signals::register(signal, Arc::clone(&received))?;
+        self.signal_handlers.push(SignalHandler {
+            hook,
+            received,
+            callback: Callback::from_fn_mut(cb),
+        });
+        Ok(())
+    }
 
-        let selected = self.menubar.receive_events();
+    /// Removes the callbacks for `signal`, restoring its default action.
+    ///
+    /// Only available on unix.
+    #[cfg(unix)]
+    pub fn clear_signal_callbacks(&mut self, signal: libc::c_int) {
+        self.signal_handlers.retain(|handler| {
+            if handler.hook.signal == signal {
+                signals::unregister(&handler.hook);
+            }
+            handler.hook.signal != signal
+        });
+    }
 
-        // Print the stackview background before the menubar
-        let offset = if self.menubar.autohide { 0 } else { 1 };
-        let id = self.active_screen;
-        let sv_printer = printer.offset((0, offset)).focused(!selected);
+    // Runs the callbacks for the signals received since the last call.
+    //
+    // Returns `true` if any was run.
+    fn run_signal_callbacks(&mut self) -> bool {
+        #[cfg(unix)]
+        {
+            let callbacks: Vec<Callback> = self
+                .signal_handlers
+                .iter()
+                .filter(|handler| {
+                    handler.received.swap(false, Ordering::SeqCst)
+                })
+                .map(|handler| handler.callback.clone())
+                .collect();
+            for cb in &callbacks {
+                cb(self);
+            }
+            !callbacks.is_empty()
+        }
 
-        self.screens[id].draw_bg(&sv_printer);
+        #[cfg(not(unix))]
+        false
+    }
 
-        // Draw the currently active screen
-        // If the menubar is active, nothing else can be.
-        // Draw the menubar?
-        if self.menubar.visible() {
-            let printer = printer.focused(self.menubar.receive_events());
-            self.menubar.draw(&printer);
-        }
+    // Runs the timers which are due. Returns `true` if any was run.
+    fn run_timers(&mut self) -> bool {
+        self.state.timers.retain(|timer| !timer.handle.is_cancelled());
+
+        let now = Instant::now();
+        let (due, pending) = std::mem::take(&mut self.state.timers)
+            .into_iter()
+            .partition::<Vec<_>, _>(|timer| timer.deadline <= now);
+        self.state.timers = pending;
+
+        let any = !due.is_empty();
+        let mut due = due.into_iter();
+        while let Some(timer) = due.next() {
+            // A previous callback may have cancelled this timer.
+            if timer.handle.is_cancelled() {
+                continue;
+            }
 
-        // finally draw stackview layers
-        // using variables from above
-        self.screens[id].draw_fg(&sv_printer);
+            match timer.callback {
+                TimerCallback::Once(cb) => cb(self),
+                TimerCallback::Repeat(cb, interval) => {
+                    let mut deadline = timer.deadline + interval;
+                    if deadline <= now {
+                        deadline = now + interval;
+                    }
+                    let callback =
+                        TimerCallback::Repeat(Rc::clone(&cb), interval);
+                    self.state.timers.push(Timer {
+                        deadline,
+                        callback,
+                        handle: timer.handle,
+                    });
+                    cb(self);
+                }
+            }
+
+            if !self.running {
+                // The others are still due if the event loop runs again.
+                self.state.timers.extend(due);
+                break;
+            }
+        }
+        any
     }
 
     /// Returns `true` until [`quit(&mut self)`] is called.
@@ -809,6 +3079,8 @@ impl Cursive {
     /// After this function returns, you can call
     /// it again and it will start a new loop.
     ///
+    /// The callback set with `set_on_exit` is called before returning.
+    ///
     /// [`step(&mut self)`]: #method.step
     /// [`quit(&mut self)`]: #method.quit
     pub fn run(&mut self) {
@@ -820,6 +3092,15 @@ impl Cursive {
         while self.running {
             self.step();
         }
+
+        self.run_on_exit();
+    }
+
+    /// Runs the callback set with `set_on_exit`.
+    pub(crate) fn run_on_exit(&mut self) {
+        if let Some(cb) = self.state.on_exit.clone() {
+            cb(self);
+        }
     }
 
     /// Performs a single step from the event loop.
@@ -827,16 +3108,202 @@ impl Cursive {
     /// Useful if you need tighter control on the event loop.
     /// Otherwise, [`run(&mut self)`] might be more convenient.
     ///
+    /// When nothing happened, this then waits until something may: an
+    /// input event, a callback, a redraw request, a timer or the next tick
+    /// of the refresh rate. Backends which cannot wake up the event loop
+    /// are polled every few milliseconds instead.
+    ///
     /// Returns `true` if an input event or callback was received
     /// during this step, and `false` otherwise.
     ///
     /// [`run(&mut self)`]: #method.run
     pub fn step(&mut self) -> bool {
+        let busy = self.try_step();
+
+        if !busy && self.running {
+            self.waiter(None).wait();
+        }
+
+        busy
+    }
+
+    /// Performs a single step from the event loop, waiting at most
+    /// `timeout` for something to happen.
+    ///
+    /// This processes events and redraws the screen like [`step`], but
+    /// returns as soon as an input event or callback was received, or
+    /// when `timeout` expired. This is convenient when Cursive runs inside
+    /// another event loop which needs the thread back regularly.
+    ///
+    /// Returns `true` if an input event or callback was received.
+    ///
+    /// [`step`]: #method.step
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::time::Duration;
+    /// # use cursive::Cursive;
+    /// # use cursive::backend::puppet;
+    /// # use cursive::traits::Identifiable;
+    /// # use cursive::views::EditView;
+    /// let (backend, puppet) = puppet::Backend::init((20, 4));
+    /// let mut siv = Cursive::new(move || backend);
+    /// siv.add_layer(EditView::new().with_id("name"));
+    /// siv.refresh();
+    ///
+    /// let timeout = Duration::from_millis(20);
+    /// assert!(!siv.step_with_timeout(timeout));
+    ///
+    /// puppet.type_str("Hi");
+    /// assert!(siv.step_with_timeout(timeout));
+    /// let content = siv.call_on_id("name", |view: &mut EditView| {
+    ///     view.get_content()
+    /// });
+    /// assert_eq!(content.unwrap().as_str(), "Hi");
+    /// ```
+    pub fn step_with_timeout(&mut self, timeout: Duration) -> bool {
+        let start = Instant::now();
+
+        loop {
+            if self.try_step() {
+                return true;
+            }
+
+            let elapsed = start.elapsed();
+            if elapsed >= timeout || !self.running {
+                return false;
+            }
+            self.waiter(Some(timeout - elapsed)).wait();
+        }
+    }
+
+    /// Returns what the idle event loop waits for, at most for `limit`.
+    pub(crate) fn waiter(&self, limit: Option<Duration>) -> Waiter {
+        let timeout = match (self.next_wakeup(), limit) {
+            (Some(timeout), Some(limit)) => Some(timeout.min(limit)),
+            (timeout, limit) => timeout.or(limit),
+        };
+        Waiter {
+            callbacks: self.state.cb_source.clone(),
+            wakes: self.wake_source.clone(),
+            timeout,
+        }
+    }
+
+    // Returns how long the event loop can sleep, if not until woken up.
+    fn next_wakeup(&self) -> Option<Duration> {
+        let poll_delay = Duration::from_millis(INPUT_POLL_DELAY_MS);
+
+        // What we can't be woken up for is polled.
+        let polled = !self.input_wakes
+            || self.player.is_some()
+            || self.screen().is_animating();
+        #[cfg(unix)]
+        let polled = polled || !self.signal_handlers.is_empty();
+
+        let now = Instant::now();
+        let redraw = if self.state.refresh_requested.load(Ordering::Relaxed) {
+            Some(now)
+        } else if self.redraw_pending {
+            // Postponed by `max_fps`.
+            self.state
+                .max_fps
+                .map(|fps| Duration::from_secs(1) / fps.get())
+                .and_then(|delay| Some(self.last_frame? + delay))
+        } else {
+            None
+        };
+        let tick = self
+            .state
+            .fps
+            .map(|fps| self.last_tick + Duration::from_secs(1) / fps.get());
+        let timer = self.state.timers.iter().map(|timer| timer.deadline).min();
+        let poll = if polled { Some(now + poll_delay) } else { None };
+
+        [redraw, tick, timer, poll]
+            .iter()
+            .filter_map(|&deadline| deadline)
+            .min()
+            .map(|deadline| deadline.saturating_duration_since(now))
+    }
+
+    /// Sets whether the event loop should keep running.
+    #[cfg(feature = "async-runner")]
+    pub(crate) fn set_running(&mut self, running: bool) {
+        self.running = running;
+    }
+
+    /// Returns the next input event from the backend, if any.
+    #[cfg(feature = "async-runner")]
+    pub(crate) fn poll_input(&mut self) -> Option<Event> {
+        self.next_input()
+    }
+
+    /// Runs the callbacks received so far.
+    ///
+    /// Returns `true` if any callback was run.
+    #[cfg(feature = "async-runner")]
+    pub(crate) fn run_callbacks(&mut self) -> bool {
+        let mut any = false;
+        while let Ok(cb) = self.state.cb_source.try_recv() {
+            any = true;
+            cb.call_box(self);
+
+            if !self.running {
+                break;
+            }
+        }
+        any
+    }
+
+    /// Performs a single step from the event loop, without waiting for
+    /// input when nothing happens.
+    ///
+    /// Returns `true` if an input event or callback was received.
+    pub(crate) fn try_step(&mut self) -> bool {
+        let busy = self.process_events();
+
+        if self.running {
+            self.draw_if_needed();
+        }
+
+        busy
+    }
+
+    /// Handles all pending input events and callbacks, without drawing.
+    ///
+    /// This is the first half of [`step`], for applications running their
+    /// own loop: it never blocks, and should be followed by
+    /// [`draw_if_needed`] to update the screen.
+    ///
+    /// Returns `true` if an input event or callback was received.
+    ///
+    /// [`step`]: #method.step
+    /// [`draw_if_needed`]: #method.draw_if_needed
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// # use cursive::backend::puppet;
+    /// # use cursive::views::TextView;
+    /// let (backend, puppet) = puppet::Backend::init((20, 4));
+    /// let mut siv = Cursive::new(move || backend);
+    /// siv.add_layer(TextView::new("Hello"));
+    ///
+    /// // In the host application loop:
+    /// siv.process_events();
+    /// siv.draw_if_needed();
+    /// assert!(puppet.screen().contains("Hello"));
+    /// ```
+    pub fn process_events(&mut self) -> bool {
         // Things are boring if nothing significant happened.
         let mut boring = true;
+        let events_start = Instant::now();
 
         // First, handle all available input
-        while let Some(event) = self.backend.poll_event() {
+        while let Some(event) = self.next_input() {
             boring = false;
             self.on_event(event);
 
@@ -846,7 +3313,7 @@ impl Cursive {
         }
 
         // Then, handle any available callback
-        while let Ok(cb) = self.cb_source.try_recv() {
+        while let Ok(cb) = self.state.cb_source.try_recv() {
             boring = false;
             cb.call_box(self);
 
@@ -855,48 +3322,429 @@ impl Cursive {
             }
         }
 
-        // How many times should we try if it's still boring?
-        // Total duration will be INPUT_POLL_DELAY_MS * repeats
-        // So effectively fps = 1000 / INPUT_POLL_DELAY_MS / repeats
-        if !boring
-            || self
-                .fps
-                .map(|fps| 1000 / INPUT_POLL_DELAY_MS as u32 / fps.get())
-                .map(|repeats| self.boring_frame_count >= repeats)
-                .unwrap_or(false)
-        {
-            // We deserve to draw something!
-            self.refresh();
+        // And the signals received.
+        if self.run_signal_callbacks() {
+            boring = false;
+
+            if !self.running {
+                return true;
+            }
+        }
+
+        // And the timers which are due
+        if self.run_timers() {
+            boring = false;
+
+            if !self.running {
+                return true;
+            }
+        }
+
+        if let Some(ref mut profiler) = self.profiler {
+            profiler.add_event_time(events_start.elapsed());
+        }
+
+        // Redraw requests all end up in a single refresh.
+        if self.state.refresh_requested.swap(false, Ordering::Relaxed) {
+            boring = false;
         }
 
-        if boring {
-            std::thread::sleep(Duration::from_millis(INPUT_POLL_DELAY_MS));
-            self.boring_frame_count += 1;
+        // Ticks of the refresh rate only count the time spent idle.
+        let tick = self
+            .state
+            .fps
+            .map(|fps| Duration::from_secs(1) / fps.get())
+            .map(|period| self.last_tick.elapsed() >= period)
+            .unwrap_or(false);
+
+        let mut redraw = !boring || self.screen().is_animating();
+
+        if boring && tick {
+            // Nothing happened, but views changing on their own, like
+            // animations, ask for a new layout.
+            self.last_tick = Instant::now();
+            let changed = self.screen().needs_layout(self.layout_size());
+            redraw |=
+                changed || self.state.render_mode == RenderMode::Continuous;
         }
 
+        self.redraw_pending |= redraw;
+
         !boring
     }
 
+    /// Redraws the screen if something changed since the last frame.
+    ///
+    /// This is the second half of [`step`], after [`process_events`]. With
+    /// `set_max_fps`, the redraw may be delayed to a later call.
+    ///
+    /// Returns `true` if the screen was redrawn.
+    ///
+    /// [`step`]: #method.step
+    /// [`process_events`]: #method.process_events
+    pub fn draw_if_needed(&mut self) -> bool {
+        if !self.redraw_pending || !self.frame_allowed() {
+            return false;
+        }
+
+        // We deserve to draw something!
+        self.refresh();
+        true
+    }
+
+    // Keeps open menus in sync with the menubar, if it was modified.
+    fn update_menu_popups(&mut self) {
+        if !self.state.menubar.take_changed()
+            || !self.state.menubar.has_submenu()
+        {
+            return;
+        }
+
+        // Open menus are the top-most layers of the current screen.
+        let is_popup = |view: &dyn View| {
+            view.as_any().is::<views::OnEventView<views::MenuPopup>>()
+        };
+        let popups = (0..self.screen().len())
+            .take_while(|&i| {
+                self.screen()
+                    .get(LayerPosition::FromFront(i))
+                    .filter(|&view| is_popup(view))
+                    .is_some()
+            })
+            .count();
+
+        if popups == 0 {
+            return;
+        }
+
+        // Nested menus may not exist anymore: close them.
+        for _ in 1..popups {
+            self.pop_layer();
+        }
+
+        let menu = self.state.menubar.focused_subtree();
+        let popup = self
+            .screen_mut()
+            .get_mut(LayerPosition::FromFront(0))
+            .and_then(|view| {
+                view.as_any_mut()
+                    .downcast_mut::<views::OnEventView<views::MenuPopup>>()
+            })
+            .map(views::OnEventView::get_inner_mut);
+
+        match (menu, popup) {
+            (Some(menu), Some(popup)) => popup.set_menu(menu),
+            _ => {
+                // The open menu was removed.
+                self.pop_layer();
+                self.state.menubar.hide();
+            }
+        }
+    }
+
+    // Stops the process until the shell continues it.
+    fn stop_process(&mut self) {
+        #[cfg(unix)]
+        self.suspend(|| unsafe {
+            libc::raise(libc::SIGTSTP);
+        });
+    }
+
+    // Returns `false` if the last frame is too recent for `max_fps`.
+    fn frame_allowed(&self) -> bool {
+        match (self.state.max_fps, self.last_frame) {
+            (Some(fps), Some(last_frame)) => {
+                last_frame.elapsed() >= Duration::from_secs(1) / fps.get()
+            }
+            _ => true,
+        }
+    }
+
     /// Refresh the screen with the current view tree state.
     pub fn refresh(&mut self) {
-        self.boring_frame_count = 0;
+        self.last_tick = Instant::now();
+        self.redraw_pending = false;
+        self.last_frame = Some(Instant::now());
 
         // Do we need to redraw everytime?
         // Probably, actually.
-        // TODO: Do we need to re-layout everytime?
-        self.layout();
+        // Menus may have changed while they were open.
+        self.update_menu_popups();
+
+        let start = Instant::now();
+        let mut views = Vec::new();
+
+        // Views which did not change keep the layout from the last frame.
+        if self.screen().needs_layout(self.layout_size()) {
+            self.profile_views(&mut views, Cursive::layout);
+        }
+        let layout = start.elapsed();
 
         // TODO: Do we need to redraw every view every time?
         // (Is this getting repetitive? :p)
-        self.draw();
+        self.profile_views(&mut views, Cursive::draw);
+        let draw = start.elapsed() - layout;
+
+        let frame = self.profiler.as_mut().map(|profiler| {
+            profiler::FrameTimings {
+                events: profiler.take_event_time(),
+                layout,
+                draw,
+                flush: Duration::default(),
+                views,
+            }
+        });
+        if let Some(ref frame) = frame {
+            self.draw_profiling_overlay(frame);
+        }
+
+        let flush_start = Instant::now();
         self.backend.refresh();
+
+        if let (Some(profiler), Some(mut frame)) = (&mut self.profiler, frame)
+        {
+            frame.flush = flush_start.elapsed();
+            profiler.push(frame);
+        }
+    }
+
+    // Runs `f`, recording the time spent on each view if profiling.
+    fn profile_views<F>(
+        &mut self, views: &mut Vec<profiler::ViewTimings>, f: F,
+    ) where
+        F: FnOnce(&mut Self),
+    {
+        if self.profiler.is_some() {
+            profiler::record_views(views, || f(self));
+        } else {
+            f(self);
+        }
+    }
+
+    // Prints the timings of the current frame in the top-right corner.
+    //
+    // The backend is flushed after this is drawn: the flush time shown is
+    // the one of the previous frame.
+    fn draw_profiling_overlay(&self, frame: &profiler::FrameTimings) {
+        if !self.profiling_overlay {
+            return;
+        }
+
+        let ms = |time: Duration| time.as_secs_f64() * 1000.0;
+        let flush = self
+            .profiler
+            .as_ref()
+            .and_then(|profiler| profiler.last())
+            .map_or(Duration::default(), |last| last.flush);
+        let mut text = format!(
+            " {:.1}ms (events {:.1}, layout {:.1}, draw {:.1}, flush {:.1}) ",
+            ms(frame.total() + flush),
+            ms(frame.events),
+            ms(frame.layout),
+            ms(frame.draw),
+            ms(flush)
+        );
+        if let Some(view) = frame.slowest_view() {
+            text = format!(
+                "{}slowest: {} {:.1}ms ",
+                text,
+                view.id,
+                ms(view.total())
+            );
+        }
+
+        let size = self.screen_size();
+        let printer = Printer::new(size, &self.state.theme, &*self.backend);
+        let x = size.x.saturating_sub(text.len());
+        printer.with_color(theme::ColorStyle::highlight(), |printer| {
+            printer.print((x, 0), &text);
+        });
+    }
+
+    /// Gives the terminal back while running `f`, and returns its result.
+    ///
+    /// This can be used to run an external program, like a text editor or a
+    /// shell. The screen is entirely redrawn afterward.
+    ///
+    /// Input is not received while `f` runs. The termion backend is an
+    /// exception: it keeps reading input in the background, so it may take
+    /// some keys from the external program.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cursive::Cursive;
+    /// # use std::process::Command;
+    /// let mut siv = Cursive::default();
+    /// siv.add_global_callback('e', |s| {
+    ///     let editor = || Command::new("vi").arg("notes.txt").status();
+    ///     let status = s.suspend(editor);
+    ///     if status.is_err() {
+    ///         s.quit();
+    ///     }
+    /// });
+    /// ```
+    pub fn suspend<F, R>(&mut self, f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        self.backend.suspend();
+        let result = f();
+        self.backend.resume();
+
+        // The terminal may have been resized in the meantime.
+        self.on_event(Event::WindowResize(self.screen_size()));
+        self.refresh();
+
+        result
+    }
+
+    /// Takes the views and callbacks out of this root, leaving it empty.
+    ///
+    /// This includes the screens and their layers, the menubar, the theme,
+    /// the callbacks, timers, user data and settings like the click
+    /// interval or the callback channel capacity. Give it to [`restore`] on
+    /// another root, to come back where the application was: for example
+    /// after dropping this root to fork the process, or to switch to another
+    /// terminal or backend.
+    ///
+    /// The callback channel is kept as well, so `cb_sink` clones stay valid.
+    /// Callbacks for unix signals, the recording and profiling stay with
+    /// this root.
+    ///
+    /// [`restore`]: #method.restore
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// # use cursive::views::TextView;
+    /// let mut siv = Cursive::dummy();
+    /// siv.add_layer(TextView::new("Still here"));
+    /// siv.set_user_data(42);
+    ///
+    /// let dump = siv.dump();
+    /// drop(siv);
+    ///
+    /// let mut siv = Cursive::dummy();
+    /// siv.restore(dump);
+    /// assert_eq!(siv.screen().len(), 1);
+    /// assert_eq!(siv.user_data::<i32>(), Some(&mut 42));
+    /// ```
+    pub fn dump(&mut self) -> CursiveDump {
+        let state = RootState::new(
+            self.backend.screen_size(),
+            self.state.cb_capacity,
+        );
+
+        self.last_sizes.clear();
+        self.mouse_capture = None;
+        self.last_click = None;
+        self.redraw_pending = true;
+
+        CursiveDump {
+            state: mem::replace(&mut self.state, state),
+        }
+    }
+
+    /// Restores the views and callbacks taken by [`dump`].
+    ///
+    /// They replace the ones of this root. If the screen size changed since
+    /// the dump, the `set_on_resize` callback is called, and the views
+    /// receive an `Event::WindowResize`.
+    ///
+    /// [`dump`]: #method.dump
+    pub fn restore(&mut self, dump: CursiveDump) {
+        self.state = dump.state;
+
+        self.last_sizes.clear();
+        self.mouse_capture = None;
+        self.last_click = None;
+
+        // The new backend may have another size.
+        self.on_event(Event::WindowResize(self.screen_size()));
+        self.mark_dirty();
+    }
+
+    /// Sets whether `Ctrl-Z` suspends the application.
+    ///
+    /// When enabled, and no view or global callback uses `Ctrl-Z`, the
+    /// terminal is restored and the process is stopped with `SIGTSTP`, like
+    /// other terminal programs. It resumes when continued by the shell (for
+    /// example with `fg`).
+    ///
+    /// Enabled by default on unix, does nothing on other platforms.
+    pub fn set_suspend_on_ctrl_z(&mut self, enabled: bool) {
+        self.state.suspend_on_ctrl_z = enabled;
     }
 
     /// Stops the event loop.
+    ///
+    /// If a callback was set with `set_on_pre_quit`, it is asked first, and
+    /// can cancel it.
     pub fn quit(&mut self) {
+        if let Some(cb) = self.state.on_pre_quit.clone() {
+            if !cb(self) {
+                return;
+            }
+        }
+        self.force_quit();
+    }
+
+    /// Stops the event loop, without asking the `set_on_pre_quit` callback.
+    pub fn force_quit(&mut self) {
         self.running = false;
     }
 
+    /// Sets a callback asked before quitting.
+    ///
+    /// It is called by `Cursive::quit`, including when the backend asks to
+    /// exit (for example when the window is closed). If it returns `false`,
+    /// the application keeps running.
+    ///
+    /// To quit after asking the user, use `Cursive::force_quit`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::Cursive;
+    /// # use cursive::views::Dialog;
+    /// let mut siv = Cursive::dummy();
+    /// siv.set_on_pre_quit(|s| {
+    ///     s.add_layer(
+    ///         Dialog::text("Quit without saving?")
+    ///             .button("Quit", Cursive::force_quit)
+    ///             .dismiss_button("Cancel"),
+    ///     );
+    ///     false
+    /// });
+    ///
+    /// siv.quit();
+    /// assert!(siv.is_running());
+    /// ```
+    pub fn set_on_pre_quit<F>(&mut self, cb: F)
+    where
+        F: Fn(&mut Cursive) -> bool + 'static,
+    {
+        self.state.on_pre_quit = Some(Rc::new(cb));
+    }
+
+    /// Removes the callback set by `set_on_pre_quit`.
+    pub fn clear_on_pre_quit(&mut self) {
+        self.state.on_pre_quit = None;
+    }
+
+    /// Sets a callback to run when the event loop ends.
+    ///
+    /// It is called when `Cursive::run` returns, for example to save some
+    /// state. The views are still available.
+    pub fn set_on_exit<F>(&mut self, cb: F)
+    where
+        F: FnMut(&mut Cursive) + 'static,
+    {
+        self.state.on_exit = Some(Callback::from_fn_mut(cb));
+    }
+
     /// Does not do anything.
     pub fn noop(&mut self) {
         // foo
@@ -913,5 +3761,140 @@ impl Cursive {
 impl Drop for Cursive {
     fn drop(&mut self) {
         self.backend.finish();
+        if self.backend.uses_terminal() {
+            panic_hook::leave();
+        }
+
+        // Signals get their default action back.
+        #[cfg(unix)]
+        for handler in &self.signal_handlers {
+            signals::unregister(&handler.hook);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Identifiable;
+
+    #[test]
+    fn capture_mouse_errors() {
+        let mut siv = Cursive::dummy();
+        siv.add_layer(views::TextView::new("Drag me").with_id("text"));
+
+        let result = siv.capture_mouse::<views::TextView>("missing");
+        assert_eq!(result, Err(views::IdError::NotFound("missing".into())));
+        let result = siv.capture_mouse::<views::EditView>("text");
+        assert_eq!(result, Err(views::IdError::WrongType("text".into())));
+        assert_eq!(siv.get_mouse_capture(), None);
+
+        assert_eq!(siv.capture_mouse::<views::TextView>("text"), Ok(()));
+        assert_eq!(siv.get_mouse_capture(), Some("text"));
+    }
+
+    #[test]
+    fn tab_order() {
+        fn content(siv: &mut Cursive, id: &str) -> String {
+            siv.call_on_id(id, |view: &mut views::EditView| {
+                view.get_content().to_string()
+            })
+            .unwrap()
+        }
+
+        let (backend, _) = backend::puppet::Backend::init((20, 10));
+        let mut siv = Cursive::new(move || backend);
+        siv.add_layer(
+            views::LinearLayout::vertical()
+                .child(views::EditView::new().with_id("a"))
+                .child(views::EditView::new().with_id("b"))
+                .child(
+                    views::EditView::new()
+                        .completer(|_| vec![String::from("done")])
+                        .with_id("c"),
+                ),
+        );
+        siv.set_tab_order(vec!["b", "c", "a"]);
+        siv.refresh();
+
+        // From "a", the focus wraps around to "b".
+        siv.on_event(Event::Key(Key::Tab));
+        siv.on_event(Event::Char('x'));
+        assert_eq!(content(&mut siv, "b"), "x");
+
+        // The layout moves the focus to "c": the order goes on from there.
+        siv.on_event(Event::Key(Key::Down));
+        siv.refresh();
+        siv.on_event(Event::Shift(Key::Tab));
+        siv.on_event(Event::Char('y'));
+        assert_eq!(content(&mut siv, "b"), "xy");
+
+        // "c" uses `Tab` for its completion.
+        siv.on_event(Event::Key(Key::Tab));
+        siv.on_event(Event::Key(Key::Tab));
+        assert_eq!(content(&mut siv, "c"), "done");
+        siv.on_event(Event::Shift(Key::Tab));
+        siv.on_event(Event::Char('z'));
+        assert_eq!(content(&mut siv, "b"), "xyz");
+    }
+
+    #[test]
+    fn dump_and_restore() {
+        let mut siv = Cursive::dummy();
+        siv.set_click_interval(Duration::from_millis(200));
+        siv.set_click_tolerance(3);
+        siv.set_suspend_on_ctrl_z(false);
+        assert!(siv.set_cb_sink_capacity(Some(2)));
+        siv.add_layer(views::TextView::new("Still here"));
+
+        let dump = siv.dump();
+        // The emptied root gets a new channel with the same capacity.
+        assert_eq!(siv.screen().len(), 0);
+        assert_eq!(siv.cb_sink().capacity(), Some(2));
+
+        let mut siv = Cursive::dummy();
+        siv.restore(dump);
+        assert_eq!(siv.screen().len(), 1);
+        assert_eq!(siv.state.click_interval, Duration::from_millis(200));
+        assert_eq!(siv.state.click_tolerance, 3);
+        assert!(!siv.state.suspend_on_ctrl_z);
+        assert_eq!(siv.state.cb_capacity, Some(2));
+    }
+
+    #[test]
+    fn timers_survive_quit() {
+        let mut siv = Cursive::dummy();
+        siv.set_timeout(Duration::from_secs(0), Cursive::quit);
+        siv.set_timeout(Duration::from_secs(0), |s| s.set_user_data(1));
+
+        assert!(siv.run_timers());
+        assert_eq!(siv.state.timers.len(), 1);
+        assert_eq!(siv.user_data::<i32>(), None);
+
+        siv.running = true;
+        assert!(siv.run_timers());
+        assert_eq!(siv.user_data::<i32>(), Some(&mut 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "interval cannot be zero")]
+    fn zero_interval() {
+        Cursive::dummy().set_interval(Duration::from_secs(0), |_| ());
+    }
+
+    #[test]
+    fn cb_sink_capacity() {
+        let mut siv = Cursive::dummy();
+        assert_eq!(siv.cb_sink().capacity(), None);
+        for _ in 0..3 {
+            siv.cb_sink().send(Box::new(Cursive::quit)).unwrap();
+        }
+
+        // Waiting callbacks are kept, with the old channel if they don't fit.
+        assert!(!siv.set_cb_sink_capacity(Some(2)));
+        assert_eq!(siv.cb_sink().capacity(), None);
+        assert!(siv.set_cb_sink_capacity(Some(3)));
+        assert_eq!(siv.cb_sink().capacity(), Some(3));
+        assert_eq!(siv.state.cb_source.len(), 3);
     }
 }
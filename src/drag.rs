@@ -0,0 +1,289 @@
+//! Drag and drop between views.
+//!
+//! A view starts a drag from its `on_event` method, usually when receiving a
+//! mouse press, by calling [`start`] with a [`Drag`]. The drag holds a
+//! payload, and a ghost drawn under the mouse while dragging.
+//!
+//! While a drag is active, the `Cursive` root turns mouse moves into
+//! `MouseEvent::DragOver` events, and the final release into a
+//! `MouseEvent::Drop` event. Like presses, these are sent to the view under
+//! the mouse; only the drop gives it the focus. A view accepts the drop by
+//! consuming the event, and can get the payload with [`take_payload`] or
+//! [`with_payload`].
+//! Pressing `Esc` cancels the drag.
+//!
+//! [`start`]: fn.start.html
+//! [`Drag`]: struct.Drag.html
+//! [`take_payload`]: fn.take_payload.html
+//! [`with_payload`]: fn.with_payload.html
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use cursive::drag::{self, Drag};
+//! # use cursive::event::{Event, EventResult, MouseEvent};
+//! # use cursive::views::Canvas;
+//! // A view which can be dragged around.
+//! let source = Canvas::new("apple")
+//!     .with_draw(|text: &&str, printer| printer.print((0, 0), text))
+//!     .with_on_event(|text, event| match event {
+//!         Event::Mouse { event: MouseEvent::Press(_), .. } => {
+//!             drag::start(Drag::new(text.to_string()).ghost(*text));
+//!             EventResult::Consumed(None)
+//!         }
+//!         _ => EventResult::Ignored,
+//!     });
+//!
+//! // A view accepting the drop.
+//! let basket = Canvas::new(Vec::<String>::new())
+//!     .with_on_event(|items, event| match event {
+//!         Event::Mouse { event: MouseEvent::Drop, .. } => {
+//!             match drag::take_payload::<String>() {
+//!                 Some(item) => {
+//!                     items.push(item);
+//!                     EventResult::Consumed(None)
+//!                 }
+//!                 None => EventResult::Ignored,
+//!             }
+//!         }
+//!         _ => EventResult::Ignored,
+//!     });
+//! # let _ = (source, basket);
+//! ```
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::utils::markup::StyledString;
+use crate::vec::Vec2;
+use crate::Cursive;
+
+type FinishCallback = dyn Fn(&mut Cursive, bool);
+
+/// Payload being dragged, with its ghost representation.
+pub struct Drag {
+    payload: Option<Box<dyn Any>>,
+    ghost: StyledString,
+    on_finish: Option<Rc<FinishCallback>>,
+    position: Option<Vec2>,
+}
+
+impl Drag {
+    /// Creates a new drag, carrying the given payload.
+    ///
+    /// It has no ghost by default.
+    pub fn new<T: Any>(payload: T) -> Self {
+        Drag {
+            payload: Some(Box::new(payload)),
+            ghost: StyledString::new(),
+            on_finish: None,
+            position: None,
+        }
+    }
+
+    /// Sets the ghost, drawn under the mouse while dragging.
+    pub fn set_ghost<S: Into<StyledString>>(&mut self, ghost: S) {
+        self.ghost = ghost.into();
+    }
+
+    /// Sets the ghost, drawn under the mouse while dragging.
+    ///
+    /// Chainable variant.
+    pub fn ghost<S: Into<StyledString>>(mut self, ghost: S) -> Self {
+        self.set_ghost(ghost);
+        self
+    }
+
+    /// Sets a callback to run when the drag ends.
+    ///
+    /// It is given `true` if the payload was dropped on a view accepting it,
+    /// and `false` if the drag was cancelled. The source view can use it to
+    /// remove a moved item.
+    pub fn set_on_finish<F>(&mut self, f: F)
+    where
+        F: Fn(&mut Cursive, bool) + 'static,
+    {
+        self.on_finish = Some(Rc::new(f));
+    }
+
+    /// Sets a callback to run when the drag ends.
+    ///
+    /// Chainable variant.
+    pub fn on_finish<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&mut Cursive, bool) + 'static,
+    {
+        self.set_on_finish(f);
+        self
+    }
+
+    /// Returns the ghost drawn while dragging.
+    pub fn get_ghost(&self) -> &StyledString {
+        &self.ghost
+    }
+
+    /// Returns the payload, if it is of type `T` and was not taken yet.
+    pub fn payload<T: Any>(&self) -> Option<&T> {
+        self.payload.as_ref()?.downcast_ref()
+    }
+
+    /// Returns the last known position of the mouse.
+    pub fn position(&self) -> Option<Vec2> {
+        self.position
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Drag>> = const { RefCell::new(None) };
+}
+
+/// Starts a drag, replacing the current one if any.
+///
+/// The replaced drag is dropped without running its `on_finish` callback.
+pub fn start(drag: Drag) {
+    CURRENT.with(|current| *current.borrow_mut() = Some(drag));
+}
+
+/// Returns `true` if a drag is active.
+pub fn is_active() -> bool {
+    CURRENT.with(|current| current.borrow().is_some())
+}
+
+/// Runs `f` on the payload of the current drag, if it is of type `T`.
+///
+/// Returns `None` if there is no drag, or if its payload has another type.
+pub fn with_payload<T, F, R>(f: F) -> Option<R>
+where
+    T: Any,
+    F: FnOnce(&T) -> R,
+{
+    CURRENT.with(|current| current.borrow().as_ref()?.payload().map(f))
+}
+
+/// Takes the payload of the current drag, if it is of type `T`.
+///
+/// This is usually called when receiving a `MouseEvent::Drop`.
+pub fn take_payload<T: Any>() -> Option<T> {
+    CURRENT.with(|current| {
+        let mut current = current.borrow_mut();
+        let payload = current.as_mut()?.payload.take()?;
+        match payload.downcast() {
+            Ok(payload) => Some(*payload),
+            Err(payload) => {
+                // Leave it for another view.
+                current.as_mut().unwrap().payload = Some(payload);
+                None
+            }
+        }
+    })
+}
+
+/// Updates the position of the current drag.
+///
+/// Returns `true` if a drag with a ghost moved.
+pub(crate) fn move_to(position: Vec2) -> bool {
+    CURRENT.with(|current| match *current.borrow_mut() {
+        Some(ref mut drag) => {
+            let old = drag.position.replace(position);
+            old != Some(position) && !drag.ghost.is_empty()
+        }
+        None => false,
+    })
+}
+
+/// Runs `f` on the current drag, if any.
+pub(crate) fn with_current<F, R>(f: F) -> Option<R>
+where
+    F: FnOnce(&Drag) -> R,
+{
+    CURRENT.with(|current| current.borrow().as_ref().map(f))
+}
+
+/// Ends the current drag, and returns its `on_finish` callback.
+pub(crate) fn finish() -> Option<Rc<FinishCallback>> {
+    CURRENT.with(|current| current.borrow_mut().take()?.on_finish)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::puppet;
+    use crate::event::{Event, EventResult, Key, MouseButton, MouseEvent};
+    use crate::views::{Canvas, IdView, LinearLayout};
+
+    fn mouse(x: usize, event: MouseEvent) -> Event {
+        Event::Mouse {
+            offset: Vec2::zero(),
+            position: Vec2::new(x, 0),
+            event,
+        }
+    }
+
+    #[test]
+    fn drag_and_drop() {
+        let (backend, puppet) = puppet::Backend::init((10, 1));
+        let mut siv = Cursive::new(move || backend);
+        siv.set_user_data(Vec::<bool>::new());
+
+        let source = Canvas::new(())
+            .with_required_size(|_, _| Vec2::new(5, 1))
+            .with_take_focus(|_, _| true)
+            .with_on_event(|_, event| match event {
+                Event::Mouse {
+                    event: MouseEvent::Press(_),
+                    ..
+                } => {
+                    let drag = Drag::new(42).ghost("ab").on_finish(|s, ok| {
+                        s.with_user_data(|done: &mut Vec<bool>| done.push(ok));
+                    });
+                    start(drag);
+                    EventResult::Consumed(None)
+                }
+                _ => EventResult::Ignored,
+            });
+        let target = Canvas::new(0)
+            .with_required_size(|_, _| Vec2::new(5, 1))
+            .with_take_focus(|_, _| true)
+            .with_on_event(|value, event| match event {
+                Event::Mouse {
+                    event: MouseEvent::Drop,
+                    ..
+                } => match take_payload::<i32>() {
+                    Some(payload) => {
+                        *value = payload;
+                        EventResult::Consumed(None)
+                    }
+                    None => EventResult::Ignored,
+                },
+                _ => EventResult::Ignored,
+            });
+        siv.add_fullscreen_layer(
+            LinearLayout::horizontal()
+                .child(source)
+                .child(IdView::new("target", target)),
+        );
+
+        let left = MouseButton::Left;
+        puppet.send(mouse(1, MouseEvent::Press(left)));
+        puppet.send(mouse(7, MouseEvent::Hold(left)));
+        while siv.step() {}
+        assert!(is_active());
+        assert_eq!(puppet.screen().find("ab"), Some(Vec2::new(7, 0)));
+
+        puppet.send(mouse(7, MouseEvent::Release(left)));
+        while siv.step() {}
+        assert!(!is_active());
+        let value = siv.call_on_id("target", |view: &mut Canvas<i32>| {
+            *view.state_mut()
+        });
+        assert_eq!(value, Some(42));
+
+        // Cancelled drags are not dropped.
+        puppet.send(mouse(1, MouseEvent::Press(left)));
+        puppet.send(Key::Esc);
+        while siv.step() {}
+        assert!(!is_active());
+        assert_eq!(siv.user_data::<Vec<bool>>(), Some(&mut vec![true, false]));
+    }
+}
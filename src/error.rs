@@ -0,0 +1,87 @@
+use std::error;
+use std::fmt;
+use std::io;
+
+/// Possible error returned when initializing Cursive.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cursive::Cursive;
+/// match Cursive::ncurses() {
+///     Ok(mut siv) => siv.run(),
+///     Err(err) => eprintln!("Cannot start the interface: {}", err),
+/// }
+/// ```
+#[derive(Debug)]
+pub enum Error {
+    /// The application is not attached to a terminal.
+    NotATty,
+    /// The logger could not be set, because `log::set_logger` was already
+    /// called.
+    Logger(log::SetLoggerError),
+    /// An error occured when initializing the backend.
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Error::NotATty => write!(f, "not attached to a terminal"),
+            Error::Logger(ref err) => write!(f, "cannot set logger: {}", err),
+            Error::Io(ref err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Io(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<log::SetLoggerError> for Error {
+    fn from(err: log::SetLoggerError) -> Self {
+        Error::Logger(err)
+    }
+}
+
+/// Returns `Error::NotATty` if the controlling terminal cannot be opened.
+///
+/// Used by the backends writing to `/dev/tty`.
+#[cfg(all(
+    unix,
+    any(feature = "ncurses-backend", feature = "termion-backend")
+))]
+pub(crate) fn check_tty() -> Result<(), Error> {
+    std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .map(drop)
+        .map_err(|_| Error::NotATty)
+}
+
+/// Returns `Error::NotATty` if the standard output is not a terminal.
+///
+/// Used by the backends writing to the standard output.
+#[cfg(all(
+    unix,
+    any(feature = "pancurses-backend", feature = "crossterm-backend")
+))]
+pub(crate) fn check_stdout() -> Result<(), Error> {
+    if unsafe { libc::isatty(libc::STDOUT_FILENO) } == 1 {
+        Ok(())
+    } else {
+        Err(Error::NotATty)
+    }
+}
@@ -17,8 +17,11 @@ use crate::vec::Vec2;
 use crate::Cursive;
 use std::any::Any;
 use std::cell::RefCell;
+use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::ops::Deref;
 use std::rc::Rc;
+use std::sync::Arc;
 
 /// Callback is a function that can be triggered by an event.
 /// It has a mutable access to the cursive root.
@@ -27,7 +30,7 @@ pub struct Callback(Rc<Box<dyn Fn(&mut Cursive)>>);
 // TODO: remove the Box when Box<T: Sized> -> Rc<T> is possible
 
 /// A boxed callback that can be run on `&mut Any`.
-pub type AnyCb<'a> = Box<FnMut(&mut dyn Any) + 'a>;
+pub type AnyCb<'a> = Box<dyn FnMut(&mut dyn Any) + 'a>;
 
 /// A trigger that only selects some types of events.
 pub struct EventTrigger(Box<dyn Fn(&Event) -> bool>);
@@ -315,6 +318,21 @@ pub enum Key {
 }
 
 impl Key {
+    /// Returns `true` if this key moves the cursor of text views.
+    pub(crate) fn is_movement(self) -> bool {
+        [
+            Key::Left,
+            Key::Right,
+            Key::Up,
+            Key::Down,
+            Key::Home,
+            Key::End,
+            Key::PageUp,
+            Key::PageDown,
+        ]
+        .contains(&self)
+    }
+
     /// Returns the function key corresponding to the given number
     ///
     /// 1 -> F1, etc...
@@ -375,6 +393,22 @@ pub enum MouseEvent {
     WheelUp,
     /// The wheel was moved down.
     WheelDown,
+    /// A button was pressed twice in quick succession.
+    ///
+    /// Sent after the second `Press`. See `Cursive::set_click_interval`.
+    DoubleClick(MouseButton),
+    /// A button was pressed three times in quick succession.
+    ///
+    /// Sent after the third `Press`.
+    TripleClick(MouseButton),
+    /// Something is being dragged over this position.
+    ///
+    /// See the [`drag`](../drag/index.html) module.
+    DragOver,
+    /// Something was dropped at this position.
+    ///
+    /// See the [`drag`](../drag/index.html) module.
+    Drop,
 }
 
 impl MouseEvent {
@@ -385,29 +419,54 @@ impl MouseEvent {
         match self {
             MouseEvent::Press(btn)
             | MouseEvent::Release(btn)
-            | MouseEvent::Hold(btn) => Some(btn),
+            | MouseEvent::Hold(btn)
+            | MouseEvent::DoubleClick(btn)
+            | MouseEvent::TripleClick(btn) => Some(btn),
             _ => None,
         }
     }
 
     /// Returns `true` if `self` is an event that can grab focus.
     ///
-    /// This includes `Press`, `WheelUp` and `WheelDown`.
+    /// This includes `Press`, `DoubleClick`, `TripleClick`, `WheelUp`,
+    /// `WheelDown` and `Drop`.
     pub fn grabs_focus(self) -> bool {
         match self {
             MouseEvent::Press(_)
+            | MouseEvent::DoubleClick(_)
+            | MouseEvent::TripleClick(_)
             | MouseEvent::WheelUp
-            | MouseEvent::WheelDown => true,
+            | MouseEvent::WheelDown
+            | MouseEvent::Drop => true,
             _ => false,
         }
     }
+
+    /// Returns `true` if `self` goes to the view under the mouse.
+    ///
+    /// This includes the events grabbing the focus, and `DragOver`, which
+    /// leaves the focus where it is.
+    pub fn targets_hovered(self) -> bool {
+        self.grabs_focus() || self == MouseEvent::DragOver
+    }
+
+    /// Returns `true` if `self` continues a press: `Hold` or `Release`.
+    ///
+    /// Containers send these events to the child which received the press,
+    /// even when the mouse moved outside of it.
+    pub fn is_drag(self) -> bool {
+        self.button().is_some() && !self.grabs_focus()
+    }
 }
 
 /// Represents an event as seen by the application.
 #[derive(PartialEq, Eq, Clone, Hash, Debug)]
 pub enum Event {
     /// Event fired when the window is resized.
-    WindowResize,
+    ///
+    /// Holds the new size of the screen. Backends may not know it: the
+    /// `Cursive` root sets it before views receive the event.
+    WindowResize(Vec2),
 
     /// Event fired regularly when a auto-refresh is set.
     Refresh,
@@ -419,6 +478,14 @@ pub enum Event {
     CtrlChar(char),
     /// A character was entered with the Alt key pressed.
     AltChar(char),
+    /// A character was entered with the Ctrl and Shift keys pressed.
+    ///
+    /// Only sent by terminals supporting extended key reporting.
+    CtrlShiftChar(char),
+    /// A character was entered with the Ctrl and Alt keys pressed.
+    ///
+    /// Only sent by terminals supporting extended key reporting.
+    CtrlAltChar(char),
 
     /// A non-character key was pressed.
     Key(Key),
@@ -445,12 +512,22 @@ pub enum Event {
         event: MouseEvent,
     },
 
+    /// Some text was pasted in the terminal.
+    ///
+    /// Backends supporting bracketed paste send the whole text at once,
+    /// instead of one event per character. New lines are not `Enter` key
+    /// presses: a form is not submitted halfway through a paste.
+    Paste(String),
+
+    /// A user-defined event was sent.
+    ///
+    /// It is routed like a key press, to the focused view first.
+    Custom(CustomEvent),
+
     // TODO: use a backend-dependent type for the unknown values?
     /// An unknown event was received.
     Unknown(Vec<u8>),
 
-    // Maybe add a `Custom(Rc<Any>)` ?
-
     // Having a doc-hidden event prevents people from having exhaustive
     // matches, allowing us to add events in the future.
     #[doc(hidden)]
@@ -458,6 +535,97 @@ pub enum Event {
     Exit,
 }
 
+/// A user-defined event, carried by `Event::Custom`.
+///
+/// Custom events are identified by their name: events with the same name
+/// are equal whatever their data, so a global callback registered for a
+/// name receives all events with this name.
+///
+/// They can be sent from other threads with an
+/// [`EventSink`](../struct.EventSink.html).
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::event::{CustomEvent, Event, EventResult};
+/// # use cursive::views::Canvas;
+/// let inbox = Canvas::new(Vec::<String>::new()).with_on_event(
+///     |messages, event| match event {
+///         Event::Custom(ref event) if event.name() == "new-message" => {
+///             if let Some(text) = event.data::<String>() {
+///                 messages.push(text.clone());
+///             }
+///             EventResult::Consumed(None)
+///         }
+///         _ => EventResult::Ignored,
+///     },
+/// );
+///
+/// let event = CustomEvent::with_data("new-message", String::from("Hi!"));
+/// assert_eq!(event, CustomEvent::new("new-message"));
+/// # let _ = inbox;
+/// ```
+#[derive(Clone)]
+pub struct CustomEvent {
+    name: String,
+    data: Option<Arc<dyn Any + Send + Sync>>,
+}
+
+impl CustomEvent {
+    /// Creates a new custom event with the given name, without data.
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        CustomEvent {
+            name: name.into(),
+            data: None,
+        }
+    }
+
+    /// Creates a new custom event with the given name, carrying `data`.
+    pub fn with_data<S, T>(name: S, data: T) -> Self
+    where
+        S: Into<String>,
+        T: Any + Send + Sync,
+    {
+        CustomEvent {
+            name: name.into(),
+            data: Some(Arc::new(data)),
+        }
+    }
+
+    /// Returns the name of this event.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the data of this event, if it is of type `T`.
+    pub fn data<T: Any>(&self) -> Option<&T> {
+        self.data.as_ref()?.downcast_ref()
+    }
+}
+
+impl PartialEq for CustomEvent {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Eq for CustomEvent {}
+
+impl Hash for CustomEvent {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+    }
+}
+
+impl fmt::Debug for CustomEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomEvent")
+            .field("name", &self.name)
+            .field("has_data", &self.data.is_some())
+            .finish()
+    }
+}
+
 impl Event {
     /// Returns the position of the mouse, if `self` is a mouse event.
     pub fn mouse_position(&self) -> Option<Vec2> {
@@ -520,3 +688,9 @@ impl From<Key> for Event {
         Event::Key(k)
     }
 }
+
+impl From<CustomEvent> for Event {
+    fn from(event: CustomEvent) -> Event {
+        Event::Custom(event)
+    }
+}
@@ -0,0 +1,302 @@
+//! Key bindings for global actions, loaded from a file.
+//!
+//! Global callbacks are actions, bound to keys. Named actions are added with
+//! [`Cursive::add_action`]; callbacks added with
+//! [`Cursive::add_global_callback`] are named after their default key.
+//!
+//! A [`Keymap`] binds actions to other keys, so end users can change them
+//! without recompiling the application. It can be loaded from a toml file,
+//! mapping each action to a key or a list of keys:
+//!
+//! ```toml
+//! # Named actions
+//! quit = "Ctrl-q"
+//! save = ["Ctrl-s", "F2"]
+//! # The callback added for `q` now uses `x` instead
+//! "q" = "x"
+//! # An empty list disables an action
+//! help = []
+//! ```
+//!
+//! Keys are written as a single character, or a key name like `Enter`,
+//! `Tab`, `PageUp` or `F5`, with optional `Ctrl-`, `Alt-` and `Shift-`
+//! modifiers.
+//!
+//! [`Cursive::add_action`]: ../struct.Cursive.html#method.add_action
+//! [`Cursive::add_global_callback`]: ../struct.Cursive.html#method.add_global_callback
+//! [`Keymap`]: struct.Keymap.html
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+use crate::event::{Event, Key};
+
+/// Possible error returned when loading a keymap.
+#[derive(Debug)]
+pub enum Error {
+    /// An error occured when reading the file.
+    Io(io::Error),
+    /// An error occured when parsing the toml content.
+    Parse(toml::de::Error),
+    /// A binding is neither a key nor a list of keys.
+    InvalidBinding(String),
+    /// A key name is not recognized.
+    InvalidKey(String),
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(err: toml::de::Error) -> Self {
+        Error::Parse(err)
+    }
+}
+
+/// Keys bound to actions, overriding their default keys.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::event::{Event, Key};
+/// # use cursive::keymap::Keymap;
+/// let keymap = Keymap::load_toml(r#"
+///     quit = "Ctrl-q"
+///     save = ["Ctrl-s", "F2"]
+/// "#).unwrap();
+///
+/// assert_eq!(keymap.get("quit"), Some(&[Event::CtrlChar('q')][..]));
+/// assert_eq!(keymap.get("save").unwrap()[1], Event::Key(Key::F2));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Keymap {
+    bindings: HashMap<String, Vec<Event>>,
+}
+
+impl Keymap {
+    /// Creates an empty keymap, where actions use their default keys.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads a keymap from the given toml file.
+    pub fn load_file<P: AsRef<Path>>(filename: P) -> Result<Self, Error> {
+        let mut content = String::new();
+        File::open(filename)?.read_to_string(&mut content)?;
+
+        Self::load_toml(&content)
+    }
+
+    /// Loads a keymap from the given toml content.
+    pub fn load_toml(content: &str) -> Result<Self, Error> {
+        let table: toml::value::Table = toml::de::from_str(content)?;
+
+        let mut keymap = Keymap::new();
+        for (action, value) in table {
+            let names = match value {
+                toml::Value::String(name) => vec![name],
+                toml::Value::Array(values) => values
+                    .into_iter()
+                    .map(|value| match value {
+                        toml::Value::String(name) => Ok(name),
+                        _ => Err(Error::InvalidBinding(action.clone())),
+                    })
+                    .collect::<Result<_, _>>()?,
+                _ => return Err(Error::InvalidBinding(action)),
+            };
+
+            let events = names
+                .iter()
+                .map(|name| {
+                    parse_event(name)
+                        .ok_or_else(|| Error::InvalidKey(name.clone()))
+                })
+                .collect::<Result<_, _>>()?;
+            keymap.bindings.insert(action, events);
+        }
+
+        Ok(keymap)
+    }
+
+    /// Binds `action` to the given events, instead of its default keys.
+    pub fn bind<S, I, E>(&mut self, action: S, events: I)
+    where
+        S: Into<String>,
+        I: IntoIterator<Item = E>,
+        E: Into<Event>,
+    {
+        let events = events.into_iter().map(Into::into).collect();
+        self.bindings.insert(action.into(), events);
+    }
+
+    /// Binds `action` to the given events, instead of its default keys.
+    ///
+    /// Chainable variant.
+    pub fn binding<S, I, E>(mut self, action: S, events: I) -> Self
+    where
+        S: Into<String>,
+        I: IntoIterator<Item = E>,
+        E: Into<Event>,
+    {
+        self.bind(action, events);
+        self
+    }
+
+    /// Removes the binding for `action`, which will use its default keys.
+    pub fn unbind(&mut self, action: &str) -> Option<Vec<Event>> {
+        self.bindings.remove(action)
+    }
+
+    /// Returns the events bound to `action`, if it was rebound.
+    pub fn get(&self, action: &str) -> Option<&[Event]> {
+        self.bindings.get(action).map(Vec::as_slice)
+    }
+
+    /// Returns the events for `action`, or `default` if it was not rebound.
+    pub(crate) fn resolve(
+        &self, action: Option<&str>, default: &Event,
+    ) -> Vec<Event> {
+        match action.and_then(|action| self.get(action)) {
+            Some(events) => events.to_vec(),
+            None => vec![default.clone()],
+        }
+    }
+}
+
+const KEYS: [Key; 29] = [
+    Key::Enter,
+    Key::Tab,
+    Key::Backspace,
+    Key::Esc,
+    Key::Left,
+    Key::Right,
+    Key::Up,
+    Key::Down,
+    Key::Ins,
+    Key::Del,
+    Key::Home,
+    Key::End,
+    Key::PageUp,
+    Key::PageDown,
+    Key::PauseBreak,
+    Key::NumpadCenter,
+    Key::F0,
+    Key::F1,
+    Key::F2,
+    Key::F3,
+    Key::F4,
+    Key::F5,
+    Key::F6,
+    Key::F7,
+    Key::F8,
+    Key::F9,
+    Key::F10,
+    Key::F11,
+    Key::F12,
+];
+
+/// Parses a key description like `"q"`, `"Ctrl-s"` or `"Shift-Tab"`.
+///
+/// Returns `None` if the description is not a valid key.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::event::{Event, Key};
+/// # use cursive::keymap::parse_event;
+/// assert_eq!(parse_event("Ctrl-s"), Some(Event::CtrlChar('s')));
+/// assert_eq!(parse_event("Ctrl-Shift-Up"), Some(Event::CtrlShift(Key::Up)));
+/// assert_eq!(parse_event("Hyper-x"), None);
+/// ```
+pub fn parse_event(description: &str) -> Option<Event> {
+    let (mut ctrl, mut alt, mut shift) = (false, false, false);
+    let mut rest = description;
+
+    // A single character is never a modifier, even `-`.
+    while rest.chars().count() > 1 {
+        if rest.starts_with("Ctrl-") {
+            ctrl = true;
+            rest = &rest[5..];
+        } else if rest.starts_with("Alt-") {
+            alt = true;
+            rest = &rest[4..];
+        } else if rest.starts_with("Shift-") {
+            shift = true;
+            rest = &rest[6..];
+        } else {
+            break;
+        }
+    }
+
+    let mut chars = rest.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return match (ctrl, alt, shift) {
+            (false, false, false) => Some(Event::Char(c)),
+            (true, false, false) => Some(Event::CtrlChar(c)),
+            (false, true, false) => Some(Event::AltChar(c)),
+            (true, false, true) => Some(Event::CtrlShiftChar(c)),
+            (true, true, false) => Some(Event::CtrlAltChar(c)),
+            _ => None,
+        };
+    }
+
+    let key = *KEYS.iter().find(|key| format!("{:?}", key) == rest)?;
+    match (ctrl, alt, shift) {
+        (false, false, false) => Some(Event::Key(key)),
+        (false, false, true) => Some(Event::Shift(key)),
+        (false, true, false) => Some(Event::Alt(key)),
+        (false, true, true) => Some(Event::AltShift(key)),
+        (true, false, false) => Some(Event::Ctrl(key)),
+        (true, false, true) => Some(Event::CtrlShift(key)),
+        (true, true, false) => Some(Event::CtrlAlt(key)),
+        (true, true, true) => None,
+    }
+}
+
+/// Returns the description of a key event, as parsed by `parse_event`.
+///
+/// Returns `None` for events other than key presses.
+pub fn event_name(event: &Event) -> Option<String> {
+    let (modifiers, key) = match *event {
+        Event::Char(c) => return Some(c.to_string()),
+        Event::CtrlChar(c) => return Some(format!("Ctrl-{}", c)),
+        Event::AltChar(c) => return Some(format!("Alt-{}", c)),
+        Event::CtrlShiftChar(c) => return Some(format!("Ctrl-Shift-{}", c)),
+        Event::CtrlAltChar(c) => return Some(format!("Ctrl-Alt-{}", c)),
+        Event::Key(key) => ("", key),
+        Event::Shift(key) => ("Shift-", key),
+        Event::Alt(key) => ("Alt-", key),
+        Event::AltShift(key) => ("Alt-Shift-", key),
+        Event::Ctrl(key) => ("Ctrl-", key),
+        Event::CtrlShift(key) => ("Ctrl-Shift-", key),
+        Event::CtrlAlt(key) => ("Ctrl-Alt-", key),
+        _ => return None,
+    };
+    Some(format!("{}{:?}", modifiers, key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_names() {
+        for &key in &KEYS {
+            for &event in &[Event::Key, Event::Shift, Event::CtrlAlt] {
+                let event = event(key);
+                let name = event_name(&event).unwrap();
+                assert_eq!(parse_event(&name), Some(event));
+            }
+        }
+        assert_eq!(parse_event("-"), Some(Event::Char('-')));
+        assert_eq!(parse_event("Alt--"), Some(Event::AltChar('-')));
+        let event = Event::CtrlShiftChar('k');
+        assert_eq!(parse_event(&event_name(&event).unwrap()), Some(event));
+        assert_eq!(event_name(&Event::Refresh), None);
+    }
+}
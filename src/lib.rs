@@ -84,12 +84,22 @@ pub mod view;
 pub mod utils;
 
 pub mod align;
+pub mod builder;
+pub mod clipboard;
 pub mod direction;
+pub mod drag;
+pub mod keymap;
 pub mod logger;
 pub mod menu;
+pub mod panic_hook;
+pub mod profiler;
+pub mod recording;
+pub mod screen_buffer;
+pub mod state;
 pub mod theme;
 pub mod vec;
 pub mod views;
+pub mod workers;
 
 // This probably doesn't need to be public?
 mod cursive;
@@ -99,11 +109,21 @@ mod with;
 mod xy;
 
 mod div;
+mod error;
+#[cfg(unix)]
+mod signals;
 mod utf8;
 
 pub mod backend;
 
-pub use self::cursive::{CbFunc, CbSink, Cursive, ScreenId};
+#[cfg(feature = "async-runner")]
+pub mod async_runner;
+
+pub use self::cursive::{
+    CbFunc, CbSink, Cursive, CursiveDump, EventSink, RefreshSink, RenderMode,
+    ScreenId, TimerHandle, ViewSink,
+};
+pub use self::error::Error;
 pub use self::printer::Printer;
 pub use self::rect::Rect;
 pub use self::vec::Vec2;
@@ -4,6 +4,8 @@ use lazy_static::lazy_static;
 use std::collections::VecDeque;
 use std::sync::Mutex;
 
+use crate::Error;
+
 /// Saves all log records in a global deque.
 ///
 /// Uses a `DebugView` to access it.
@@ -97,24 +99,57 @@ impl log::Log for CursiveLogger {
 ///
 /// Use a [`DebugView`](crate::views::DebugView) to see the logs, or use
 /// [`Cursive::toggle_debug_console()`](crate::Cursive::toggle_debug_console()).
+///
+/// # Panics
+///
+/// If `log::set_logger` was already called. See [`try_init`](fn.try_init.html)
+/// for a non-panicking version.
 pub fn init() {
+    try_init().unwrap();
+}
+
+/// Initialize the Cursive logger.
+///
+/// Returns `Error::Logger` if `log::set_logger` was already called, for
+/// example by another logger.
+///
+/// # Examples
+///
+/// ```rust
+/// assert!(cursive::logger::try_init().is_ok());
+/// assert!(cursive::logger::try_init().is_err());
+/// ```
+pub fn try_init() -> Result<(), Error> {
+    log::set_logger(&LOGGER)?;
+
     // TODO: Configure the deque size?
     LOGS.lock().unwrap().reserve(1_000);
 
-    // This will panic if `set_logger` was already called.
-    log::set_logger(&LOGGER).unwrap();
-
     // TODO: read the level from env variable? From argument?
     log::set_max_level(log::LevelFilter::Trace);
+    Ok(())
 }
 
 /// Initialise the Cursive logger, adding the ability to filter debug logs by module
+///
+/// # Panics
+///
+/// If `log::set_logger` was already called.
 pub fn init_for_module(module: &str) {
+    try_init_for_module(module).unwrap();
+}
+
+/// Initialise the Cursive logger, adding the ability to filter debug logs by module
+///
+/// Returns `Error::Logger` if `log::set_logger` was already called.
+pub fn try_init_for_module(module: &str) -> Result<(), Error> {
+    // Another logger may be installed: leave the filter untouched then.
+    try_init()?;
+
     let mut custom_module = MODULE.lock().unwrap();
     *custom_module = Some(module.to_string());
 
     // TODO: Configure the deque size?
     MODULE_LOGS.lock().unwrap().reserve(1_000);
-
-    init();
+    Ok(())
 }
@@ -5,6 +5,9 @@
 //! A menu can be seen as a `MenuTree`. It has a list of children:
 //!
 //! * Leaf nodes are made of a label and a callback
+//! * Toggles are leaves with a check mark.
+//! * Radio items are leaves with a check mark, where only one item of a
+//!   given group can be checked.
 //! * Sub-trees are made of a label, and another `MenuTree`.
 //! * Delimiters are just there to separate groups of related children.
 //!
@@ -15,6 +18,7 @@
 use crate::event::Callback;
 use crate::Cursive;
 use crate::With;
+use std::cell::Cell;
 use std::rc::Rc;
 
 /// Root of a menu tree.
@@ -29,6 +33,15 @@ pub struct MenuTree {
 pub enum MenuItem {
     /// Actionnable button with a label.
     Leaf(String, Callback),
+    /// Button with a label and a check mark.
+    ///
+    /// The state is shared by every clone of this item.
+    Toggle(String, Rc<Cell<bool>>, Callback),
+    /// Button with a label and a check mark, part of a group.
+    ///
+    /// The second string is the name of the group. At most one item of a
+    /// group is checked at any given time.
+    Radio(String, String, Rc<Cell<bool>>, Callback),
     /// Sub-menu with a label.
     Subtree(String, Rc<MenuTree>),
     /// Delimiter without a label.
@@ -42,8 +55,24 @@ impl MenuItem {
     pub fn label(&self) -> &str {
         match *self {
             MenuItem::Delimiter => "│",
-            MenuItem::Leaf(ref label, _) | MenuItem::Subtree(ref label, _) => {
-                label
+            MenuItem::Leaf(ref label, _)
+            | MenuItem::Toggle(ref label, _, _)
+            | MenuItem::Radio(ref label, _, _, _)
+            | MenuItem::Subtree(ref label, _) => label,
+        }
+    }
+
+    /// Changes the label for this item.
+    ///
+    /// Does nothing if `self` is a delimiter.
+    pub fn set_label<S: Into<String>>(&mut self, new_label: S) {
+        match *self {
+            MenuItem::Delimiter => (),
+            MenuItem::Leaf(ref mut label, _)
+            | MenuItem::Toggle(ref mut label, _, _)
+            | MenuItem::Radio(ref mut label, _, _, _)
+            | MenuItem::Subtree(ref mut label, _) => {
+                *label = new_label.into();
             }
         }
     }
@@ -57,13 +86,62 @@ impl MenuItem {
     }
 
     /// Returns `true` if `self` is a leaf node.
+    ///
+    /// Toggles and radio items are leaves too.
     pub fn is_leaf(&self) -> bool {
         match *self {
-            MenuItem::Leaf(_, _) => true,
+            MenuItem::Leaf(_, _)
+            | MenuItem::Toggle(_, _, _)
+            | MenuItem::Radio(_, _, _, _) => true,
             _ => false,
         }
     }
 
+    /// Returns `true` if `self` is a toggle or a radio item.
+    pub fn is_checkable(&self) -> bool {
+        self.check_state().is_some()
+    }
+
+    /// Returns `true` if `self` is a checked toggle or radio item.
+    pub fn is_checked(&self) -> bool {
+        self.check_state().map(|state| state.get()).unwrap_or(false)
+    }
+
+    /// Checks or unchecks this item.
+    ///
+    /// Does nothing if `self` is not a toggle or a radio item.
+    ///
+    /// This does not uncheck the other items of a radio group; use
+    /// `MenuTree::select_radio` for that.
+    pub fn set_checked(&mut self, checked: bool) {
+        if let Some(state) = self.check_state() {
+            state.set(checked);
+        }
+    }
+
+    /// Returns the group of this item, if it is a radio item.
+    pub fn radio_group(&self) -> Option<&str> {
+        match *self {
+            MenuItem::Radio(_, ref group, _, _) => Some(group),
+            _ => None,
+        }
+    }
+
+    // Unchecks this item, without needing a mutable reference.
+    fn set_unchecked(&self) {
+        if let Some(state) = self.check_state() {
+            state.set(false);
+        }
+    }
+
+    fn check_state(&self) -> Option<&Rc<Cell<bool>>> {
+        match *self {
+            MenuItem::Toggle(_, ref state, _)
+            | MenuItem::Radio(_, _, ref state, _) => Some(state),
+            _ => None,
+        }
+    }
+
     /// Returns `true` if `self` is a subtree.
     pub fn is_subtree(&self) -> bool {
         match *self {
@@ -144,6 +222,177 @@ impl MenuTree {
         self.with(|menu| menu.add_leaf(title, cb))
     }
 
+    /// Inserts a toggle at the given position.
+    ///
+    /// `cb` is given the new state of the toggle when it is activated.
+    pub fn insert_toggle<S, F>(
+        &mut self, i: usize, title: S, checked: bool, cb: F,
+    ) where
+        S: Into<String>,
+        F: 'static + Fn(&mut Cursive, bool),
+    {
+        let state = Rc::new(Cell::new(checked));
+        let cb = {
+            let state = Rc::clone(&state);
+            Callback::from_fn(move |s| cb(s, state.get()))
+        };
+        self.insert(i, MenuItem::Toggle(title.into(), state, cb));
+    }
+
+    /// Adds a toggle to the end of this tree.
+    ///
+    /// `cb` is given the new state of the toggle when it is activated.
+    pub fn add_toggle<S, F>(&mut self, title: S, checked: bool, cb: F)
+    where
+        S: Into<String>,
+        F: 'static + Fn(&mut Cursive, bool),
+    {
+        let i = self.children.len();
+        self.insert_toggle(i, title, checked, cb);
+    }
+
+    /// Adds a toggle to the end of this tree - chainable variant.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::menu::MenuTree;
+    /// let mut tree = MenuTree::new()
+    ///     .toggle("Word wrap", true, |_, wrap| { /* ... */ });
+    ///
+    /// assert!(tree.find_item("Word wrap").unwrap().is_checked());
+    /// ```
+    pub fn toggle<S, F>(self, title: S, checked: bool, cb: F) -> Self
+    where
+        S: Into<String>,
+        F: 'static + Fn(&mut Cursive, bool),
+    {
+        self.with(|menu| menu.add_toggle(title, checked, cb))
+    }
+
+    /// Inserts a radio item at the given position.
+    ///
+    /// Only one item of `group` can be checked: if `checked` is `true`,
+    /// other items from this group are unchecked.
+    pub fn insert_radio<G, S, F>(
+        &mut self, i: usize, group: G, title: S, checked: bool, cb: F,
+    ) where
+        G: Into<String>,
+        S: Into<String>,
+        F: 'static + Fn(&mut Cursive),
+    {
+        let group = group.into();
+        if checked {
+            self.uncheck_group(&group);
+        }
+        let state = Rc::new(Cell::new(checked));
+        let item = MenuItem::Radio(
+            title.into(),
+            group,
+            state,
+            Callback::from_fn(cb),
+        );
+        self.insert(i, item);
+    }
+
+    /// Adds a radio item to the end of this tree.
+    ///
+    /// Only one item of `group` can be checked: if `checked` is `true`,
+    /// other items from this group are unchecked.
+    pub fn add_radio<G, S, F>(
+        &mut self, group: G, title: S, checked: bool, cb: F,
+    ) where
+        G: Into<String>,
+        S: Into<String>,
+        F: 'static + Fn(&mut Cursive),
+    {
+        let i = self.children.len();
+        self.insert_radio(i, group, title, checked, cb);
+    }
+
+    /// Adds a radio item to the end of this tree - chainable variant.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::menu::MenuTree;
+    /// let tree = MenuTree::new()
+    ///     .radio("theme", "Light", true, |_| ())
+    ///     .radio("theme", "Dark", false, |_| ());
+    ///
+    /// assert_eq!(tree.checked_radio("theme"), Some("Light"));
+    /// ```
+    pub fn radio<G, S, F>(
+        self, group: G, title: S, checked: bool, cb: F,
+    ) -> Self
+    where
+        G: Into<String>,
+        S: Into<String>,
+        F: 'static + Fn(&mut Cursive),
+    {
+        self.with(|menu| menu.add_radio(group, title, checked, cb))
+    }
+
+    /// Returns the label of the checked item in the given radio group.
+    ///
+    /// Returns `None` if no item from this group is checked.
+    pub fn checked_radio(&self, group: &str) -> Option<&str> {
+        self.children
+            .iter()
+            .find(|child| {
+                child.radio_group() == Some(group) && child.is_checked()
+            })
+            .map(MenuItem::label)
+    }
+
+    /// Checks the radio item with the given label.
+    ///
+    /// Other items from the same group are unchecked.
+    ///
+    /// Returns `false` if no such radio item was found.
+    pub fn select_radio(&mut self, title: &str) -> bool {
+        let group = match self.find_item(title).and_then(|item| {
+            item.radio_group().map(String::from)
+        }) {
+            Some(group) => group,
+            None => return false,
+        };
+
+        self.uncheck_group(&group);
+        if let Some(item) = self.find_item(title) {
+            item.set_checked(true);
+        }
+        true
+    }
+
+    fn uncheck_group(&self, group: &str) {
+        for child in &self.children {
+            if child.radio_group() == Some(group) {
+                child.set_unchecked();
+            }
+        }
+    }
+
+    /// Activates the leaf at the given position.
+    ///
+    /// Updates the state of toggles and radio items, and returns the
+    /// callback to run.
+    pub(crate) fn activate(&self, i: usize) -> Option<Callback> {
+        match self.children[i] {
+            MenuItem::Leaf(_, ref cb) => Some(cb.clone()),
+            MenuItem::Toggle(_, ref state, ref cb) => {
+                state.set(!state.get());
+                Some(cb.clone())
+            }
+            MenuItem::Radio(_, ref group, ref state, ref cb) => {
+                self.uncheck_group(group);
+                state.set(true);
+                Some(cb.clone())
+            }
+            _ => None,
+        }
+    }
+
     /// Inserts a subtree at the given position.
     pub fn insert_subtree<S>(&mut self, i: usize, title: S, tree: MenuTree)
     where
@@ -203,6 +452,54 @@ impl MenuTree {
             .next()
     }
 
+    /// Looks for an item, following the given path of labels.
+    ///
+    /// All but the last labels must identify subtrees.
+    ///
+    /// Returns `None` if no such item was found.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::menu::MenuTree;
+    /// let mut tree = MenuTree::new()
+    ///     .subtree("File", MenuTree::new().leaf("Open", |_| ()));
+    ///
+    /// assert!(tree.find_path(&["File", "Open"]).is_some());
+    /// assert!(tree.find_path(&["File", "Save"]).is_none());
+    /// ```
+    pub fn find_path(&mut self, path: &[&str]) -> Option<&mut MenuItem> {
+        let (last, parents) = path.split_last()?;
+
+        let mut tree = self;
+        for title in parents {
+            tree = tree.find_subtree(title)?;
+        }
+        tree.find_item(last)
+    }
+
+    /// Removes the child with the given label, and returns it.
+    ///
+    /// Returns `None` if no such label was found.
+    pub fn remove_item(&mut self, title: &str) -> Option<MenuItem> {
+        self.find_position(title).map(|i| self.children.remove(i))
+    }
+
+    /// Renames the child with the given label.
+    ///
+    /// Returns `false` if no such label was found.
+    pub fn rename_item<S: Into<String>>(
+        &mut self, title: &str, new_title: S,
+    ) -> bool {
+        match self.find_item(title) {
+            Some(item) => {
+                item.set_label(new_title);
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Returns the position of a child with the given label.
     ///
     /// Returns `None` if no such label was found.
@@ -230,3 +527,25 @@ impl MenuTree {
         self.children.is_empty()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checkable_items() {
+        let tree = MenuTree::new()
+            .toggle("Wrap", false, |_, _| ())
+            .radio("size", "Small", true, |_| ())
+            .radio("size", "Large", false, |_| ());
+
+        tree.activate(0);
+        assert!(tree.children[0].is_checked());
+        tree.activate(0);
+        assert!(!tree.children[0].is_checked());
+
+        tree.activate(2);
+        assert_eq!(tree.checked_radio("size"), Some("Large"));
+        assert!(!tree.children[1].is_checked());
+    }
+}
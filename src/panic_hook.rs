@@ -0,0 +1,159 @@
+//! Panic hook keeping panic messages readable.
+//!
+//! When the application panics, the default hook prints the message right
+//! away, while the terminal is still in raw mode and on the alternate
+//! screen: the message is garbled, then erased when the backend restores
+//! the terminal.
+//!
+//! The hook installed by [`install`] instead keeps the message while a
+//! `Cursive` root using the terminal is alive on the panicking thread.
+//! Unwinding drops the root, which restores the terminal, and the message is
+//! then printed to the standard error. Panics from other threads are printed
+//! right away.
+//!
+//! Messages are only delayed if the panic unwinds: with `panic = "abort"`,
+//! the terminal cannot be restored.
+//!
+//! [`install`]: fn.install.html
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! # use cursive::Cursive;
+//! cursive::panic_hook::install();
+//!
+//! let mut siv = Cursive::default();
+//! siv.add_global_callback('p', |_| panic!("Oops"));
+//! siv.run();
+//! ```
+
+use std::any::Any;
+use std::panic::{self, Location};
+use std::sync::{Mutex, Once};
+use std::thread::{self, ThreadId};
+
+// Threads running `Cursive` roots using the terminal, once per root.
+static ACTIVE: Mutex<Vec<ThreadId>> = Mutex::new(Vec::new());
+
+static INSTALL: Once = Once::new();
+
+// Delayed messages, with the thread they come from.
+static PENDING: Mutex<Vec<(ThreadId, String)>> = Mutex::new(Vec::new());
+
+/// Installs the panic hook.
+///
+/// Panics happening on a thread without a `Cursive` root are given to the
+/// previous hook. Calling this more than once has no effect.
+pub fn install() {
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let id = thread::current().id();
+            if !is_active(id) {
+                previous(info);
+            } else if let Ok(mut pending) = PENDING.lock() {
+                let message = message(info.payload(), info.location());
+                pending.push((id, message));
+            }
+        }));
+    });
+}
+
+// Returns `true` if a `Cursive` root using the terminal runs on `thread`.
+fn is_active(thread: ThreadId) -> bool {
+    ACTIVE
+        .lock()
+        .map(|active| active.contains(&thread))
+        .unwrap_or(false)
+}
+
+/// Returns `true` if [`install`](fn.install.html) was called.
+pub fn is_installed() -> bool {
+    INSTALL.is_completed()
+}
+
+/// Registers a `Cursive` root on the current thread, delaying its panic
+/// messages until the root is dropped.
+pub(crate) fn enter() {
+    if let Ok(mut active) = ACTIVE.lock() {
+        active.push(thread::current().id());
+    }
+}
+
+/// Unregisters a `Cursive` root, after it restored the terminal.
+///
+/// Once the last one of this thread is dropped, its delayed messages are
+/// printed.
+pub(crate) fn leave() {
+    let id = thread::current().id();
+    if let Ok(mut active) = ACTIVE.lock() {
+        if let Some(i) = active.iter().position(|&other| other == id) {
+            active.remove(i);
+        }
+        if active.contains(&id) {
+            return;
+        }
+    }
+
+    let pending: Vec<_> = match PENDING.lock() {
+        Ok(mut pending) => {
+            let (mine, others) =
+                pending.drain(..).partition(|&(thread, _)| thread == id);
+            *pending = others;
+            mine
+        }
+        Err(_) => return,
+    };
+    for (_, message) in pending {
+        eprintln!("{}", message);
+    }
+}
+
+// Formats the panic like the default hook.
+fn message(payload: &dyn Any, location: Option<&Location<'_>>) -> String {
+    let thread = thread::current();
+    let name = thread.name().unwrap_or("<unnamed>");
+
+    let text = payload
+        .downcast_ref::<&str>()
+        .cloned()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("Box<dyn Any>");
+
+    match location {
+        Some(location) => {
+            format!("thread '{}' panicked at {}:\n{}", name, location, text)
+        }
+        None => format!("thread '{}' panicked:\n{}", name, text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delayed_message() {
+        install();
+        assert!(is_installed());
+
+        enter();
+        let result = panic::catch_unwind(|| panic!("Oops"));
+        assert!(result.is_err());
+
+        // Panics from other threads are not delayed.
+        let other = thread::spawn(|| panic!("Elsewhere")).join();
+        assert!(other.is_err());
+
+        let id = thread::current().id();
+        let pending = PENDING.lock().unwrap().clone();
+        let delayed = |text: &str| {
+            pending.iter().any(|(thread, message)| {
+                *thread == id && message.ends_with(text)
+            })
+        };
+        assert!(delayed("Oops"));
+        assert!(!delayed("Elsewhere"));
+        leave();
+    }
+}
@@ -6,12 +6,12 @@ use crate::theme::{
     BorderStyle, ColorStyle, Effect, PaletteColor, Style, Theme,
 };
 use crate::utils::lines::simple::{prefix, suffix};
+use crate::utils::width::{grapheme_width, is_ambiguous, TextWidth};
 use crate::vec::Vec2;
 use crate::with::With;
 use enumset::EnumSet;
 use std::cmp::min;
 use unicode_segmentation::UnicodeSegmentation;
-use unicode_width::UnicodeWidthStr;
 
 /// Convenient interface to draw on a subset of the screen.
 ///
@@ -111,12 +111,12 @@ impl<'a, 'b> Printer<'a, 'b> {
     // We don't want people to start calling prints in parallel?
     /// Prints some text at the given position
     pub fn print<S: Into<Vec2>>(&self, start: S, text: &str) {
-        self.print_with_width(start, text, UnicodeWidthStr::width);
+        self.print_with_width(start, text, TextWidth::width);
     }
 
     /// Prints some text, using the given callback to compute width.
     ///
-    /// Mostly used with `UnicodeWidthStr::width`.
+    /// Mostly used with `TextWidth::width`.
     /// If you already know the width, you can give it as a constant instead.
     fn print_with_width<S, F>(&self, start: S, text: &str, width: F)
     where
@@ -150,6 +150,8 @@ impl<'a, 'b> Printer<'a, 'b> {
 
         let mut text = text;
         let mut start = start;
+        // Cells left blank when a wide character is cut on the left.
+        let mut padding = 0;
 
         if hidden_part.x > 0 {
             // We have to drop hidden_part.x width from the start of the string.
@@ -172,6 +174,7 @@ impl<'a, 'b> Printer<'a, 'b> {
             text = &text[skipped_len..];
             start = start + (skipped_width, 0);
             text_width -= skipped_width;
+            padding = skipped_width - hidden_part.x;
         }
 
         assert!(start.fits(self.content_offset));
@@ -186,13 +189,44 @@ impl<'a, 'b> Printer<'a, 'b> {
             // Drop the end of the text if it's too long
             // We want the number of CHARACTERS, not bytes.
             // (Actually we want the "width" of the string, see unicode-width)
-            let prefix_len = prefix(text.graphemes(true), room, "").length;
-            text = &text[..prefix_len];
-            assert!(text.width() <= room);
+            let prefix = prefix(text.graphemes(true), room, "");
+            text = &text[..prefix.length];
+            assert!(prefix.width <= room);
+
+            // Blanks the cell where a wide character was cut.
+            let end = start + self.offset + (prefix.width, 0);
+            self.backend.print_at_rep(end, room - prefix.width, " ");
         }
 
         let start = start + self.offset;
-        self.backend.print_at(start, text);
+        self.backend.print_at_rep(start - (padding, 0), padding, " ");
+        self.print_graphemes(start, text);
+    }
+
+    // Prints each ambiguous grapheme on its own, with its expected width: a
+    // terminal measuring it differently will not shift the rest of the row.
+    fn print_graphemes(&self, start: Vec2, text: &str) {
+        if text.is_ascii() {
+            self.backend.print_at(start, text);
+            return;
+        }
+
+        let mut run = (0, start);
+        let mut pos = start;
+        for (i, grapheme) in text.grapheme_indices(true) {
+            let width = grapheme_width(grapheme);
+            if is_ambiguous(grapheme) {
+                if run.0 < i {
+                    self.backend.print_at(run.1, &text[run.0..i]);
+                }
+                self.backend.print_grapheme_at(pos, grapheme, width);
+                run = (i + grapheme.len(), pos + (width, 0));
+            }
+            pos = pos + (width, 0);
+        }
+        if run.0 < text.len() {
+            self.backend.print_at(run.1, &text[run.0..]);
+        }
     }
 
     /// Prints a vertical line using the given character.
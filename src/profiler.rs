@@ -0,0 +1,486 @@
+//! Frame timings, to find out what slows down the event loop.
+//!
+//! When profiling is enabled (see [`Cursive::enable_profiling`]), each frame
+//! records the time spent handling events, computing the layout, drawing the
+//! views and flushing the backend, as well as the time spent on each view
+//! with an id. The last frames are kept in a [`Profiler`], which can be
+//! queried at any time.
+//!
+//! For a finer view, a [`DrawObserver`] set with
+//! [`Cursive::set_draw_observer`] is told about each view with an id being
+//! laid out or drawn.
+//!
+//! [`Cursive::enable_profiling`]: ../struct.Cursive.html#method.enable_profiling
+//! [`Cursive::set_draw_observer`]: ../struct.Cursive.html#method.set_draw_observer
+//! [`Profiler`]: struct.Profiler.html
+//! [`DrawObserver`]: trait.DrawObserver.html
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::mem;
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::utils::time::Instant;
+
+/// Time spent on a view with an id during a frame.
+///
+/// This includes the time spent on its children.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ViewTimings {
+    /// Id of the view.
+    pub id: String,
+    /// Time spent computing its layout.
+    pub layout: Duration,
+    /// Time spent drawing it.
+    pub draw: Duration,
+}
+
+impl ViewTimings {
+    /// Returns the total time spent on this view.
+    pub fn total(&self) -> Duration {
+        self.layout + self.draw
+    }
+}
+
+/// Time spent in each phase of a frame.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FrameTimings {
+    /// Time spent handling input events and callbacks since the last frame.
+    pub events: Duration,
+    /// Time spent computing the layout.
+    pub layout: Duration,
+    /// Time spent drawing the views.
+    pub draw: Duration,
+    /// Time spent flushing the backend.
+    pub flush: Duration,
+    /// Time spent on each view with an id, in the order they were first
+    /// laid out or drawn.
+    pub views: Vec<ViewTimings>,
+}
+
+impl FrameTimings {
+    /// Returns the total time spent on this frame.
+    pub fn total(&self) -> Duration {
+        self.events + self.layout + self.draw + self.flush
+    }
+
+    /// Returns the time spent on the view `id`, if it was laid out or drawn.
+    pub fn view(&self, id: &str) -> Option<&ViewTimings> {
+        self.views.iter().find(|view| view.id == id)
+    }
+
+    /// Returns the view with an id which took the longest.
+    ///
+    /// Parents include the time spent on their children: the slowest view
+    /// may only be slow because of one of its children.
+    pub fn slowest_view(&self) -> Option<&ViewTimings> {
+        self.views.iter().max_by_key(|view| view.total())
+    }
+}
+
+/// Timings of the last frames.
+///
+/// Only the last `capacity` frames are kept.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::Cursive;
+/// let mut siv = Cursive::dummy();
+/// siv.enable_profiling(100);
+/// siv.refresh();
+///
+/// let profiler = siv.profiler().unwrap();
+/// assert_eq!(profiler.len(), 1);
+/// println!("Average frame: {:?}", profiler.average().total());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Profiler {
+    frames: VecDeque<FrameTimings>,
+    capacity: usize,
+
+    // Time spent on events, waiting for the next frame.
+    pending_events: Duration,
+}
+
+impl Profiler {
+    /// Creates a new profiler, keeping the last `capacity` frames.
+    pub fn new(capacity: usize) -> Self {
+        Profiler {
+            frames: VecDeque::with_capacity(capacity),
+            capacity,
+            pending_events: Duration::default(),
+        }
+    }
+
+    /// Returns the maximum number of frames kept.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of frames recorded.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns `true` if no frame was recorded.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Iterates on the recorded frames, from the oldest to the newest.
+    pub fn frames(&self) -> impl Iterator<Item = &FrameTimings> {
+        self.frames.iter()
+    }
+
+    /// Returns the timings of the last frame.
+    pub fn last(&self) -> Option<&FrameTimings> {
+        self.frames.back()
+    }
+
+    /// Returns the average timings of the recorded frames.
+    pub fn average(&self) -> FrameTimings {
+        let mut sum = FrameTimings::default();
+        for frame in &self.frames {
+            sum.events += frame.events;
+            sum.layout += frame.layout;
+            sum.draw += frame.draw;
+            sum.flush += frame.flush;
+        }
+
+        let n = self.frames.len().max(1) as u32;
+        FrameTimings {
+            events: sum.events / n,
+            layout: sum.layout / n,
+            draw: sum.draw / n,
+            flush: sum.flush / n,
+            views: Vec::new(),
+        }
+    }
+
+    /// Returns the average time spent on the view `id`.
+    ///
+    /// Only the frames where the view was laid out or drawn are counted.
+    pub fn average_view(&self, id: &str) -> Option<ViewTimings> {
+        let mut sum = ViewTimings {
+            id: id.to_string(),
+            ..ViewTimings::default()
+        };
+        let mut n = 0;
+        for view in self.frames.iter().filter_map(|frame| frame.view(id)) {
+            sum.layout += view.layout;
+            sum.draw += view.draw;
+            n += 1;
+        }
+
+        if n == 0 {
+            return None;
+        }
+        sum.layout /= n;
+        sum.draw /= n;
+        Some(sum)
+    }
+
+    /// Returns the slowest recorded frame.
+    pub fn slowest(&self) -> Option<&FrameTimings> {
+        self.frames.iter().max_by_key(|frame| frame.total())
+    }
+
+    /// Returns the frames which took longer than `budget`.
+    pub fn over_budget(
+        &self, budget: Duration,
+    ) -> impl Iterator<Item = &FrameTimings> {
+        self.frames.iter().filter(move |frame| frame.total() > budget)
+    }
+
+    /// Removes all recorded frames.
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+
+    /// Adds time spent on events, counted in the next frame.
+    pub(crate) fn add_event_time(&mut self, time: Duration) {
+        self.pending_events += time;
+    }
+
+    /// Returns the time spent on events since the last frame.
+    pub(crate) fn take_event_time(&mut self) -> Duration {
+        mem::take(&mut self.pending_events)
+    }
+
+    /// Records a frame, forgetting the oldest one if needed.
+    pub(crate) fn push(&mut self, mut frame: FrameTimings) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.frames.len() == self.capacity {
+            self.frames.pop_front();
+        }
+
+        frame.events += self.pending_events;
+        self.pending_events = Duration::default();
+        self.frames.push_back(frame);
+    }
+}
+
+/// Part of a frame observed by a `DrawObserver`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Phase {
+    /// The view is laid out.
+    Layout,
+    /// The view is drawn.
+    Draw,
+}
+
+/// Observes the views with an id while they are laid out and drawn.
+///
+/// Calls are nested like the views: the time given to a view includes the
+/// time spent on its children.
+///
+/// Closures taking the id, the phase and the elapsed time implement this
+/// trait, with only the `after` method.
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::time::Duration;
+/// # use cursive::Cursive;
+/// # use cursive::profiler::Phase;
+/// let mut siv = Cursive::dummy();
+/// siv.set_draw_observer(|id: &str, phase, elapsed: Duration| {
+///     if phase == Phase::Draw && elapsed > Duration::from_millis(5) {
+///         log::warn!("{} is slow to draw: {:?}", id, elapsed);
+///     }
+/// });
+/// ```
+pub trait DrawObserver {
+    /// Called before the view `id` goes through `phase`.
+    ///
+    /// Default implementation does nothing.
+    fn before(&self, id: &str, phase: Phase) {
+        let _ = (id, phase);
+    }
+
+    /// Called after the view `id` went through `phase`, which took
+    /// `elapsed`.
+    fn after(&self, id: &str, phase: Phase, elapsed: Duration);
+}
+
+impl<F> DrawObserver for F
+where
+    F: Fn(&str, Phase, Duration),
+{
+    fn after(&self, id: &str, phase: Phase, elapsed: Duration) {
+        self(id, phase, elapsed);
+    }
+}
+
+// Adds up the time spent on each view during a frame.
+#[derive(Default)]
+struct ViewRecorder {
+    views: RefCell<Vec<ViewTimings>>,
+}
+
+impl DrawObserver for ViewRecorder {
+    // Parents are added before their children.
+    fn before(&self, id: &str, _: Phase) {
+        let mut views = self.views.borrow_mut();
+        if views.iter().all(|view| view.id != id) {
+            views.push(ViewTimings {
+                id: id.to_string(),
+                ..ViewTimings::default()
+            });
+        }
+    }
+
+    fn after(&self, id: &str, phase: Phase, elapsed: Duration) {
+        let mut views = self.views.borrow_mut();
+        if let Some(view) = views.iter_mut().find(|view| view.id == id) {
+            match phase {
+                Phase::Layout => view.layout += elapsed,
+                Phase::Draw => view.draw += elapsed,
+            }
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+struct Observers {
+    observer: Option<Rc<dyn DrawObserver>>,
+    recorder: Option<Rc<ViewRecorder>>,
+}
+
+thread_local! {
+    static OBSERVERS: RefCell<Observers> = RefCell::default();
+}
+
+/// Runs `f`, telling `observer` about the views laid out or drawn.
+///
+/// Each root sets its own observer while its views are laid out or drawn.
+pub(crate) fn with_observer<F, R>(
+    observer: Option<Rc<dyn DrawObserver>>, f: F,
+) -> R
+where
+    F: FnOnce() -> R,
+{
+    let previous = OBSERVERS.with(|current| {
+        mem::replace(&mut current.borrow_mut().observer, observer)
+    });
+
+    let result = f();
+
+    OBSERVERS.with(|current| current.borrow_mut().observer = previous);
+    result
+}
+
+/// Runs `f`, adding the time spent on each view with an id to `views`.
+pub(crate) fn record_views<F, R>(views: &mut Vec<ViewTimings>, f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    let recorder = Rc::new(ViewRecorder {
+        views: RefCell::new(mem::take(views)),
+    });
+    let previous = OBSERVERS.with(|current| {
+        current.borrow_mut().recorder.replace(Rc::clone(&recorder))
+    });
+
+    let result = f();
+
+    OBSERVERS.with(|current| current.borrow_mut().recorder = previous);
+    *views = recorder.views.replace(Vec::new());
+    result
+}
+
+/// Runs `f` for the view `id`, telling the observers if there are any.
+///
+/// The id is only copied when there is an observer.
+pub(crate) fn observe<F, R>(id: &RefCell<String>, phase: Phase, f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    // Cloned, so nested views can use them too.
+    let observers = OBSERVERS.with(|current| current.borrow().clone());
+    if observers.observer.is_none() && observers.recorder.is_none() {
+        return f();
+    }
+
+    // The view could be renamed while it is drawn.
+    let id = id.borrow().clone();
+    let id = id.as_str();
+
+    if let Some(ref observer) = observers.observer {
+        observer.before(id, phase);
+    }
+    if let Some(ref recorder) = observers.recorder {
+        recorder.before(id, phase);
+    }
+    let start = Instant::now();
+    let result = f();
+    let elapsed = start.elapsed();
+
+    if let Some(ref observer) = observers.observer {
+        observer.after(id, phase, elapsed);
+    }
+    if let Some(ref recorder) = observers.recorder {
+        recorder.after(id, phase, elapsed);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(ms: u64) -> FrameTimings {
+        FrameTimings {
+            draw: Duration::from_millis(ms),
+            ..FrameTimings::default()
+        }
+    }
+
+    #[test]
+    fn draw_observer() {
+        use crate::backend::puppet;
+        use crate::traits::Identifiable;
+        use crate::views::TextView;
+        use crate::Cursive;
+
+        let (backend, _) = puppet::Backend::init((10, 4));
+        let mut siv = Cursive::new(move || backend);
+        siv.add_layer(TextView::new("Hi").with_id("text"));
+
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let observed = Rc::clone(&calls);
+        siv.set_draw_observer(move |id: &str, phase, _| {
+            observed.borrow_mut().push((id.to_string(), phase));
+        });
+        siv.refresh();
+        siv.clear_draw_observer();
+        siv.refresh();
+
+        // Other roots on the same thread are not observed.
+        let (backend, _) = puppet::Backend::init((10, 4));
+        let mut other = Cursive::new(move || backend);
+        other.add_layer(TextView::new("Hi").with_id("other"));
+        let observed = Rc::clone(&calls);
+        siv.set_draw_observer(move |id: &str, phase, _| {
+            observed.borrow_mut().push((id.to_string(), phase));
+        });
+        other.refresh();
+
+        let text = String::from("text");
+        assert_eq!(
+            *calls.borrow(),
+            vec![(text.clone(), Phase::Layout), (text, Phase::Draw)]
+        );
+    }
+
+    #[test]
+    fn view_timings() {
+        use crate::backend::puppet;
+        use crate::traits::Identifiable;
+        use crate::views::{LinearLayout, TextView};
+        use crate::Cursive;
+
+        let (backend, _) = puppet::Backend::init((10, 4));
+        let mut siv = Cursive::new(move || backend);
+        siv.enable_profiling(10);
+        siv.add_layer(
+            LinearLayout::vertical()
+                .child(TextView::new("Hi").with_id("text"))
+                .with_id("layout"),
+        );
+        siv.refresh();
+
+        let profiler = siv.profiler().unwrap();
+        let frame = profiler.last().unwrap();
+        let ids: Vec<&str> =
+            frame.views.iter().map(|view| view.id.as_str()).collect();
+        assert_eq!(ids, vec!["layout", "text"]);
+
+        // Parents include the time spent on their children.
+        let layout = frame.view("layout").unwrap();
+        assert!(layout.total() >= frame.view("text").unwrap().total());
+        assert!(profiler.average_view("text").is_some());
+        assert!(profiler.average_view("missing").is_none());
+    }
+
+    #[test]
+    fn ring_buffer() {
+        let mut profiler = Profiler::new(2);
+        profiler.add_event_time(Duration::from_millis(1));
+        profiler.push(frame(10));
+        profiler.push(frame(20));
+        profiler.push(frame(40));
+
+        assert_eq!(profiler.len(), 2);
+        assert_eq!(profiler.frames().next(), Some(&frame(20)));
+        assert_eq!(profiler.average().draw, Duration::from_millis(30));
+        assert_eq!(profiler.slowest(), Some(&frame(40)));
+
+        let budget = Duration::from_millis(30);
+        assert_eq!(profiler.over_budget(budget).count(), 1);
+    }
+}
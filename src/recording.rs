@@ -0,0 +1,483 @@
+//! Recording and playback of input events.
+//!
+//! [`Cursive::record_to_file`] saves every input event received from the
+//! backend, with the time it arrived. [`Cursive::play_recording`] later
+//! feeds these events back to the application, either all at once or at
+//! their original speed. This helps reproducing bugs reported by users, or
+//! automating demos.
+//!
+//! Recordings are text files, with one event per line. Each line has the
+//! time in milliseconds since the start of the recording, the kind of
+//! event and its parameters, separated by tabs (shown as spaces here):
+//!
+//! ```text
+//! 0       key     H
+//! 150     key     Ctrl-s
+//! 400     mouse   press-left      12      3
+//! 480     mouse   release-left    12      3
+//! 900     resize  80      24
+//! 1200    paste   first line\nsecond line
+//! 1500    custom  new-message
+//! ```
+//!
+//! Keys are written like in [keymaps](../keymap/index.html). In keys, pasted
+//! text and custom event names, backslashes, tabs and line breaks are
+//! escaped as `\\`, `\t`, `\n` and `\r`. Custom events are recorded by
+//! name: their data is lost. Lines starting with `#` are ignored.
+//!
+//! [`Cursive::record_to_file`]: ../struct.Cursive.html#method.record_to_file
+//! [`Cursive::play_recording`]: ../struct.Cursive.html#method.play_recording
+
+use std::collections::VecDeque;
+use std::error;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::event::{CustomEvent, Event, MouseButton, MouseEvent};
+use crate::keymap;
+use crate::utils::time::Instant;
+use crate::vec::Vec2;
+
+/// Possible error returned when loading or saving a recording.
+#[derive(Debug)]
+pub enum Error {
+    /// An error occured when reading or writing the file.
+    Io(io::Error),
+    /// A line could not be parsed. Holds the line number, starting at 1.
+    Parse(usize),
+    /// An event cannot be recorded.
+    Unsupported(Event),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Error::Io(ref err) => write!(f, "{}", err),
+            Error::Parse(line) => write!(f, "invalid event on line {}", line),
+            Error::Unsupported(ref event) => {
+                write!(f, "cannot record event {:?}", event)
+            }
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Io(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+/// A list of input events, with the time they were received.
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::time::Duration;
+/// # use cursive::event::{Event, Key};
+/// # use cursive::recording::Recording;
+/// let mut recording = Recording::new();
+/// recording.push(Duration::from_millis(0), Event::Char('a'));
+/// recording.push(Duration::from_millis(250), Event::Key(Key::Enter));
+///
+/// let text = recording.to_text().unwrap();
+/// assert_eq!(text, "0\tkey\ta\n250\tkey\tEnter\n");
+/// assert_eq!(Recording::parse(&text).unwrap(), recording);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Recording {
+    events: Vec<(Duration, Event)>,
+}
+
+impl Recording {
+    /// Creates an empty recording.
+    pub fn new() -> Self {
+        Recording::default()
+    }
+
+    /// Parses a recording, in the format described in the module.
+    pub fn parse(content: &str) -> Result<Self, Error> {
+        let mut recording = Recording::new();
+        for (i, line) in content.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (time, event) = parse_line(line).ok_or(Error::Parse(i + 1))?;
+            recording.push(time, event);
+        }
+        Ok(recording)
+    }
+
+    /// Loads a recording from a file.
+    pub fn load_file<P: AsRef<Path>>(filename: P) -> Result<Self, Error> {
+        let content = fs::read_to_string(filename)?;
+        Recording::parse(&content)
+    }
+
+    /// Returns this recording, in the format described in the module.
+    ///
+    /// Returns `Error::Unsupported` if an event cannot be recorded.
+    pub fn to_text(&self) -> Result<String, Error> {
+        let mut text = String::new();
+        for &(time, ref event) in &self.events {
+            text.push_str(&format_line(time, event)?);
+            text.push('\n');
+        }
+        Ok(text)
+    }
+
+    /// Saves this recording to a file.
+    ///
+    /// Returns `Error::Unsupported`, without creating the file, if an event
+    /// cannot be recorded.
+    pub fn save_file<P: AsRef<Path>>(&self, filename: P) -> Result<(), Error> {
+        let text = self.to_text()?;
+        fs::write(filename, text)?;
+        Ok(())
+    }
+
+    /// Adds an event, received `time` after the start of the recording.
+    pub fn push(&mut self, time: Duration, event: Event) {
+        self.events.push((time, event));
+    }
+
+    /// Returns the recorded events, with the time they were received.
+    pub fn events(&self) -> &[(Duration, Event)] {
+        &self.events
+    }
+
+    /// Returns the number of recorded events.
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Returns `true` if this recording has no event.
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+/// Writes input events as they are received.
+pub(crate) struct Recorder {
+    writer: Box<dyn Write>,
+    start: Instant,
+}
+
+impl Recorder {
+    pub fn new(writer: Box<dyn Write>) -> Self {
+        Recorder {
+            writer,
+            start: Instant::now(),
+        }
+    }
+
+    pub fn record(&mut self, event: &Event) {
+        // The recording keeps a comment where an event is missing.
+        let line = format_line(self.start.elapsed(), event).unwrap_or_else(
+            |err| {
+                log::error!("Could not record event: {}", err);
+                format!("# {}", err)
+            },
+        );
+
+        // Flush each event, so the file is complete even after a crash.
+        let result = writeln!(self.writer, "{}", line)
+            .and_then(|_| self.writer.flush());
+        if let Err(err) = result {
+            log::warn!("Could not record event: {}", err);
+        }
+    }
+}
+
+/// Feeds recorded events back.
+pub(crate) struct Player {
+    events: VecDeque<(Duration, Event)>,
+    start: Instant,
+    realtime: bool,
+}
+
+impl Player {
+    pub fn new(recording: Recording, realtime: bool) -> Self {
+        Player {
+            events: recording.events.into_iter().collect(),
+            start: Instant::now(),
+            realtime,
+        }
+    }
+
+    /// Returns the next event, if it is time to play it.
+    pub fn next_event(&mut self) -> Option<Event> {
+        let time = self.events.front()?.0;
+        if self.realtime && self.start.elapsed() < time {
+            return None;
+        }
+        self.events.pop_front().map(|(_, event)| event)
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+fn format_line(time: Duration, event: &Event) -> Result<String, Error> {
+    let millis = time.as_secs() * 1000 + u64::from(time.subsec_millis());
+    let unsupported = || Error::Unsupported(event.clone());
+    let description = match *event {
+        Event::WindowResize(size) => {
+            format!("resize\t{}\t{}", size.x, size.y)
+        }
+        Event::Refresh => "refresh".to_string(),
+        Event::Mouse {
+            position,
+            event: mouse_event,
+            ..
+        } => format!(
+            "mouse\t{}\t{}\t{}",
+            mouse_name(mouse_event).ok_or_else(unsupported)?,
+            position.x,
+            position.y
+        ),
+        Event::Paste(ref text) => format!("paste\t{}", escape(text)),
+        Event::Custom(ref custom) => {
+            format!("custom\t{}", escape(custom.name()))
+        }
+        Event::Unknown(ref bytes) => {
+            let hex: Vec<String> =
+                bytes.iter().map(|b| format!("{:02x}", b)).collect();
+            format!("unknown\t{}", hex.join(" "))
+        }
+        ref event => {
+            let name = keymap::event_name(event).ok_or_else(unsupported)?;
+            format!("key\t{}", escape(&name))
+        }
+    };
+    Ok(format!("{}\t{}", millis, description))
+}
+
+fn parse_line(line: &str) -> Option<(Duration, Event)> {
+    let fields: Vec<&str> = line.split('\t').collect();
+    let time = Duration::from_millis(fields[0].parse().ok()?);
+    let number = |i: usize| fields.get(i)?.parse::<usize>().ok();
+
+    let event = match (fields.get(1).cloned()?, fields.len()) {
+        ("key", 3) => keymap::parse_event(&unescape(fields[2])?)?,
+        ("mouse", 5) => Event::Mouse {
+            offset: Vec2::zero(),
+            position: Vec2::new(number(3)?, number(4)?),
+            event: parse_mouse(fields[2])?,
+        },
+        ("paste", 3) => Event::Paste(unescape(fields[2])?),
+        ("custom", 3) => Event::Custom(CustomEvent::new(unescape(fields[2])?)),
+        ("unknown", 3) => Event::Unknown(
+            fields[2]
+                .split_whitespace()
+                .map(|b| u8::from_str_radix(b, 16).ok())
+                .collect::<Option<_>>()?,
+        ),
+        ("resize", 4) => {
+            Event::WindowResize(Vec2::new(number(2)?, number(3)?))
+        }
+        ("refresh", 2) => Event::Refresh,
+        _ => return None,
+    };
+    Some((time, event))
+}
+
+// Escapes the characters which would break the line format.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape(text: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        unescaped.push(match chars.next()? {
+            '\\' => '\\',
+            't' => '\t',
+            'n' => '\n',
+            'r' => '\r',
+            _ => return None,
+        });
+    }
+    Some(unescaped)
+}
+
+const BUTTONS: [(MouseButton, &str); 5] = [
+    (MouseButton::Left, "left"),
+    (MouseButton::Middle, "middle"),
+    (MouseButton::Right, "right"),
+    (MouseButton::Button4, "button4"),
+    (MouseButton::Button5, "button5"),
+];
+
+fn mouse_name(event: MouseEvent) -> Option<String> {
+    let (action, button) = match event {
+        MouseEvent::WheelUp => return Some(String::from("wheel-up")),
+        MouseEvent::WheelDown => return Some(String::from("wheel-down")),
+        MouseEvent::Press(button) => ("press", button),
+        MouseEvent::Release(button) => ("release", button),
+        MouseEvent::Hold(button) => ("hold", button),
+        MouseEvent::DoubleClick(button) => ("double", button),
+        MouseEvent::TripleClick(button) => ("triple", button),
+        MouseEvent::DragOver => return Some(String::from("drag-over")),
+        MouseEvent::Drop => return Some(String::from("drop")),
+    };
+    let &(_, name) = BUTTONS.iter().find(|&&(b, _)| b == button)?;
+    Some(format!("{}-{}", action, name))
+}
+
+fn parse_mouse(name: &str) -> Option<MouseEvent> {
+    match name {
+        "wheel-up" => return Some(MouseEvent::WheelUp),
+        "wheel-down" => return Some(MouseEvent::WheelDown),
+        "drag-over" => return Some(MouseEvent::DragOver),
+        "drop" => return Some(MouseEvent::Drop),
+        _ => (),
+    }
+
+    let i = name.find('-')?;
+    let (action, button) = (&name[..i], &name[i + 1..]);
+    let &(button, _) = BUTTONS.iter().find(|&&(_, n)| n == button)?;
+    match action {
+        "press" => Some(MouseEvent::Press(button)),
+        "release" => Some(MouseEvent::Release(button)),
+        "hold" => Some(MouseEvent::Hold(button)),
+        "double" => Some(MouseEvent::DoubleClick(button)),
+        "triple" => Some(MouseEvent::TripleClick(button)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::puppet;
+    use crate::event::Key;
+    use crate::traits::Identifiable;
+    use crate::views::EditView;
+    use crate::Cursive;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    // Writer shared with the test.
+    #[derive(Clone, Default)]
+    struct Buffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn content(siv: &mut Cursive) -> String {
+        siv.call_on_id("edit", |view: &mut EditView| view.get_content())
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn record_and_play() {
+        let (backend, puppet) = puppet::Backend::init((20, 4));
+        let mut siv = Cursive::new(move || backend);
+        siv.add_layer(EditView::new().with_id("edit"));
+        siv.refresh();
+
+        let buffer = Buffer::default();
+        siv.record_to(buffer.clone());
+        puppet.type_str("Hi");
+        puppet.send(Event::Mouse {
+            offset: Vec2::zero(),
+            position: Vec2::new(3, 1),
+            event: MouseEvent::Press(MouseButton::Left),
+        });
+        puppet.send(Key::Left);
+        while siv.step() {}
+        siv.stop_recording();
+
+        let text = String::from_utf8(buffer.0.borrow().clone()).unwrap();
+        let recording = Recording::parse(&text).unwrap();
+        assert_eq!(recording.len(), 4);
+        assert_eq!(
+            recording.events()[2].1.mouse_position(),
+            Some(Vec2::new(3, 1))
+        );
+        assert!(text.ends_with("\tkey\tLeft\n"));
+
+        // Plays the recording on a fresh view.
+        siv.pop_layer();
+        siv.add_layer(EditView::new().with_id("edit"));
+        siv.play_recording(recording, false);
+        while siv.step() {}
+        assert!(!siv.is_playing());
+        assert_eq!(content(&mut siv), "Hi");
+
+        assert!(Recording::parse("0\tkey\tHyper-x").is_err());
+    }
+
+    #[test]
+    fn all_events() {
+        let mouse = |event| Event::Mouse {
+            offset: Vec2::zero(),
+            position: Vec2::new(2, 5),
+            event,
+        };
+        let events = vec![
+            Event::Char('\t'),
+            Event::Char('\\'),
+            Event::Paste("a\tb\\n\nc\r".into()),
+            Event::Custom(CustomEvent::new("new\tmessage")),
+            Event::Unknown(vec![0x1b, 0xff]),
+            Event::Refresh,
+            Event::WindowResize(Vec2::new(80, 24)),
+            mouse(MouseEvent::DoubleClick(MouseButton::Left)),
+            mouse(MouseEvent::TripleClick(MouseButton::Right)),
+            mouse(MouseEvent::DragOver),
+            mouse(MouseEvent::Drop),
+        ];
+
+        let mut recording = Recording::new();
+        for event in events {
+            recording.push(Duration::from_millis(10), event);
+        }
+        let text = recording.to_text().unwrap();
+        assert_eq!(text.lines().count(), recording.len());
+        assert_eq!(Recording::parse(&text).unwrap(), recording);
+
+        // Events which cannot be recorded are errors, not skipped.
+        recording.push(Duration::from_millis(20), Event::Exit);
+        match recording.to_text() {
+            Err(Error::Unsupported(Event::Exit)) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,243 @@
+//! Grid of cells, as drawn on the screen.
+//!
+//! [`Cursive::screenshot`] draws the views into a [`ScreenBuffer`], where the
+//! text and style of each cell can be inspected, saved as plain text, or
+//! compared against a stored snapshot.
+//!
+//! [`Cursive::screenshot`]: ../struct.Cursive.html#method.screenshot
+//! [`ScreenBuffer`]: struct.ScreenBuffer.html
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use enumset::EnumSet;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::theme::{Color, ColorPair, Effect};
+use crate::utils::width::TextWidth;
+use crate::vec::Vec2;
+
+/// A cell of the screen, as printed by the views.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScreenCell {
+    /// Grapheme printed in this cell.
+    ///
+    /// Empty for cells covered by a wide character on their left.
+    pub text: String,
+    /// Colors used to print this cell.
+    pub colors: ColorPair,
+    /// Effects used to print this cell.
+    pub effects: EnumSet<Effect>,
+}
+
+/// Content of the screen, as a grid of cells.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::Cursive;
+/// # use cursive::backend::puppet;
+/// # use cursive::views::TextView;
+/// let (backend, _) = puppet::Backend::init((20, 5));
+/// let mut siv = Cursive::new(move || backend);
+/// siv.add_fullscreen_layer(TextView::new("Hello"));
+/// siv.refresh();
+///
+/// let screen = siv.screenshot();
+/// assert_eq!(screen.to_text(), "Hello");
+/// assert_eq!(screen.size(), (20, 5).into());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScreenBuffer {
+    size: Vec2,
+    cells: Vec<ScreenCell>,
+}
+
+impl ScreenBuffer {
+    /// Creates a blank screen of the given size.
+    pub fn new(size: Vec2, colors: ColorPair) -> Self {
+        let cell = ScreenCell {
+            text: String::from(" "),
+            colors,
+            effects: EnumSet::new(),
+        };
+        ScreenBuffer {
+            size,
+            cells: vec![cell; size.x * size.y],
+        }
+    }
+
+    /// Returns the size of the screen.
+    pub fn size(&self) -> Vec2 {
+        self.size
+    }
+
+    /// Returns the cell at the given position.
+    pub fn cell<P: Into<Vec2>>(&self, pos: P) -> Option<&ScreenCell> {
+        let pos = pos.into();
+        if pos.x >= self.size.x || pos.y >= self.size.y {
+            return None;
+        }
+        self.cells.get(pos.y * self.size.x + pos.x)
+    }
+
+    fn cell_mut(&mut self, pos: Vec2) -> Option<&mut ScreenCell> {
+        if pos.x >= self.size.x || pos.y >= self.size.y {
+            return None;
+        }
+        self.cells.get_mut(pos.y * self.size.x + pos.x)
+    }
+
+    /// Returns the colors of the first cell, used to clear the screen.
+    pub(crate) fn background(&self) -> ColorPair {
+        self.cells
+            .first()
+            .map(|cell| cell.colors)
+            .unwrap_or_else(terminal_default)
+    }
+
+    /// Prints `text` at the given position, clipped to the screen.
+    pub(crate) fn print(
+        &mut self, pos: Vec2, text: &str, colors: ColorPair,
+        effects: EnumSet<Effect>,
+    ) {
+        let mut x = pos.x;
+        for grapheme in text.graphemes(true) {
+            let width = grapheme.width();
+            if x + width > self.size.x {
+                // Wide characters are not cut in half.
+                break;
+            }
+            for i in 0..width {
+                let cell = match self.cell_mut(Vec2::new(x + i, pos.y)) {
+                    Some(cell) => cell,
+                    None => break,
+                };
+                cell.text = if i == 0 {
+                    grapheme.to_string()
+                } else {
+                    String::new()
+                };
+                cell.colors = colors;
+                cell.effects = effects;
+            }
+            x += width;
+        }
+    }
+
+    /// Returns the text of the given row, if it exists.
+    pub fn row(&self, y: usize) -> Option<String> {
+        if y >= self.size.y {
+            return None;
+        }
+        let start = y * self.size.x;
+        let cells = &self.cells[start..start + self.size.x];
+        Some(cells.iter().map(|cell| cell.text.as_str()).collect())
+    }
+
+    /// Returns the text of all rows.
+    pub fn rows(&self) -> Vec<String> {
+        (0..self.size.y).filter_map(|y| self.row(y)).collect()
+    }
+
+    /// Returns `true` if `text` is printed somewhere on a single row.
+    pub fn contains(&self, text: &str) -> bool {
+        self.find(text).is_some()
+    }
+
+    /// Returns the position of the first occurrence of `text`.
+    pub fn find(&self, text: &str) -> Option<Vec2> {
+        self.rows().iter().enumerate().find_map(|(y, row)| {
+            // Empty cells after wide characters have no width either.
+            row.find(text).map(|i| Vec2::new(row[..i].width(), y))
+        })
+    }
+
+    /// Returns the content as plain text.
+    ///
+    /// Trailing spaces and empty rows at the end are removed.
+    pub fn to_text(&self) -> String {
+        let rows: Vec<_> = self
+            .rows()
+            .iter()
+            .map(|row| row.trim_end().to_string())
+            .collect();
+        rows.join("\n").trim_end().to_string()
+    }
+
+    /// Saves the content as plain text, as returned by `to_text`.
+    pub fn save_text<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_text() + "\n")
+    }
+
+    /// Returns the rows which differ from the given plain text.
+    ///
+    /// Trailing spaces are ignored, as with `to_text`.
+    pub fn mismatched_rows(&self, expected: &str) -> Vec<usize> {
+        let actual = self.to_text();
+        let actual: Vec<_> = actual.lines().collect();
+        let expected: Vec<_> = expected.trim_end().lines().collect();
+
+        (0..actual.len().max(expected.len()))
+            .filter(|&y| {
+                let actual = actual.get(y).map(|row| row.trim_end());
+                let expected = expected.get(y).map(|row| row.trim_end());
+                actual != expected
+            })
+            .collect()
+    }
+
+    /// Compares the content with the snapshot saved at `path`.
+    ///
+    /// If the file does not exist, the snapshot is created, and this
+    /// returns `true`.
+    pub fn matches_snapshot<P: AsRef<Path>>(
+        &self, path: P,
+    ) -> io::Result<bool> {
+        let path = path.as_ref();
+        match fs::read_to_string(path) {
+            Ok(expected) => Ok(self.mismatched_rows(&expected).is_empty()),
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => {
+                self.save_text(path)?;
+                Ok(true)
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
+
+fn terminal_default() -> ColorPair {
+    ColorPair {
+        front: Color::TerminalDefault,
+        back: Color::TerminalDefault,
+    }
+}
+
+impl fmt::Display for ScreenBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            writeln!(f, "{}", row)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot() {
+        let colors = terminal_default();
+        let mut screen = ScreenBuffer::new(Vec2::new(6, 3), colors);
+        screen.print(Vec2::new(0, 0), "ab", colors, EnumSet::new());
+        screen.print(Vec2::new(1, 1), "日本語", colors, EnumSet::new());
+
+        assert_eq!(screen.to_text(), "ab\n 日本");
+        assert_eq!(screen.find("本"), Some(Vec2::new(3, 1)));
+        assert!(screen.mismatched_rows("ab  \n 日本\n").is_empty());
+        assert_eq!(screen.mismatched_rows("ab\n 日\nc"), vec![1, 2]);
+    }
+}
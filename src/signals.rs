@@ -0,0 +1,144 @@
+//! Hooks for unix signals, giving signals their default action back.
+//!
+//! `signal-hook` cannot uninstall its own signal handler: once the last hook
+//! for a signal is unregistered, the signal is silently ignored. To restore
+//! signals which had their default action, we register an action doing
+//! what the default would have done instead.
+
+use std::io;
+use std::mem;
+use std::ptr;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
+
+use hashbrown::HashMap;
+use lazy_static::lazy_static;
+use libc::c_int;
+use signal_hook::SigId;
+
+/// A registered hook, setting a flag when its signal is received.
+pub(crate) struct Hook {
+    pub signal: c_int,
+    id: SigId,
+}
+
+// What we know about a signal we registered hooks for.
+#[derive(Default)]
+struct Signal {
+    // Number of hooks currently registered.
+    hooks: usize,
+
+    // `true` if the signal had its default action before the first hook.
+    was_default: bool,
+
+    // Action emulating the default one, once all hooks are gone.
+    default_action: Option<SigId>,
+}
+
+lazy_static! {
+    static ref SIGNALS: Mutex<HashMap<c_int, Signal>> =
+        Mutex::new(HashMap::new());
+}
+
+/// Sets `received` whenever `signal` is received.
+pub(crate) fn register(
+    signal: c_int, received: Arc<AtomicBool>,
+) -> io::Result<Hook> {
+    let mut signals = SIGNALS.lock().unwrap();
+    let state = signals.entry(signal).or_default();
+
+    if state.hooks == 0 {
+        state.was_default =
+            state.default_action.is_some() || has_default_action(signal);
+        if let Some(id) = state.default_action.take() {
+            signal_hook::unregister(id);
+        }
+    }
+
+    match signal_hook::flag::register(signal, received) {
+        Ok(id) => {
+            state.hooks += 1;
+            Ok(Hook { signal, id })
+        }
+        Err(err) => {
+            if state.hooks == 0 && state.was_default {
+                state.default_action = register_default_action(signal);
+            }
+            Err(err)
+        }
+    }
+}
+
+/// Removes the hook, restoring the default action of its signal if it had
+/// it before the first hook.
+pub(crate) fn unregister(hook: &Hook) {
+    signal_hook::unregister(hook.id);
+
+    let mut signals = SIGNALS.lock().unwrap();
+    let state = match signals.get_mut(&hook.signal) {
+        Some(state) => state,
+        None => return,
+    };
+    state.hooks -= 1;
+    if state.hooks == 0 && state.was_default {
+        state.default_action = register_default_action(hook.signal);
+    }
+}
+
+// Returns `true` if nothing handles `signal` yet.
+fn has_default_action(signal: c_int) -> bool {
+    unsafe {
+        let mut action: libc::sigaction = mem::zeroed();
+        libc::sigaction(signal, ptr::null(), &mut action) == 0
+            && action.sa_sigaction == libc::SIG_DFL
+    }
+}
+
+fn register_default_action(signal: c_int) -> Option<SigId> {
+    unsafe { signal_hook::register(signal, move || emulate_default(signal)) }
+        .ok()
+}
+
+// Does what the default action of `signal` does.
+//
+// Runs in the signal handler, so only calls async-signal-safe functions.
+fn emulate_default(signal: c_int) {
+    match signal {
+        libc::SIGCHLD | libc::SIGCONT | libc::SIGURG | libc::SIGWINCH => (),
+        libc::SIGTSTP | libc::SIGTTIN | libc::SIGTTOU => unsafe {
+            libc::raise(libc::SIGSTOP);
+        },
+        // The signal is blocked while its handler runs: it is delivered
+        // again, with the default action, once we return.
+        _ => unsafe {
+            libc::signal(signal, libc::SIG_DFL);
+            libc::raise(signal);
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::Ordering;
+
+    #[test]
+    fn default_action_comes_back() {
+        // The default action for `SIGURG` is to ignore it.
+        let signal = libc::SIGURG;
+        let received = Arc::new(AtomicBool::new(false));
+        let hook = register(signal, Arc::clone(&received)).unwrap();
+
+        unsafe { libc::raise(signal) };
+        assert!(received.load(Ordering::SeqCst));
+
+        unregister(&hook);
+        assert!(SIGNALS.lock().unwrap()[&signal].default_action.is_some());
+        unsafe { libc::raise(signal) };
+
+        // A new hook replaces the default action again.
+        let hook = register(signal, received).unwrap();
+        assert!(SIGNALS.lock().unwrap()[&signal].default_action.is_none());
+        unregister(&hook);
+    }
+}
@@ -0,0 +1,394 @@
+//! Saving and restoring the state of views.
+//!
+//! [`Cursive::save_ui_state`] collects the state of every view with an id:
+//! scroll offsets, selected items, edited text, ... This [`UiState`] can be
+//! saved to a file, and given back to
+//! [`Cursive::restore_ui_state`][restore] on the next run, so the interface
+//! looks like the user left it.
+//!
+//! Views provide their state with `View::save_state`, and take it back with
+//! `View::restore_state`. The supported views are:
+//!
+//! * `ScrollView`: the scroll offset.
+//! * `SelectView`, `SliderView` and `LinearLayout`: the selected item, the
+//!   value, and the focused child.
+//! * `EditView` and `TextArea`: the content.
+//! * `Checkbox`: whether it is checked.
+//!
+//! Files use the toml format, with a table for each view:
+//!
+//! ```toml
+//! [files]
+//! selected = 3
+//!
+//! [preview]
+//! offset = [0, 42]
+//!
+//! [search]
+//! content = "needle"
+//! ```
+//!
+//! With the `serde` feature, `UiState` and `ViewState` also implement
+//! `Serialize` and `Deserialize`, with the same layout.
+//!
+//! [`Cursive::save_ui_state`]: ../struct.Cursive.html#method.save_ui_state
+//! [restore]: ../struct.Cursive.html#method.restore_ui_state
+//! [`UiState`]: struct.UiState.html
+
+use std::collections::BTreeMap;
+use std::error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::vec::Vec2;
+
+/// Possible error returned when loading a state.
+#[derive(Debug)]
+pub enum Error {
+    /// An error occured when reading the file.
+    Io(io::Error),
+    /// An error occured when parsing the toml content.
+    Parse(toml::de::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Error::Io(ref err) => write!(f, "{}", err),
+            Error::Parse(ref err) => write!(f, "invalid UI state: {}", err),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Io(ref err) => Some(err),
+            Error::Parse(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(err: toml::de::Error) -> Self {
+        Error::Parse(err)
+    }
+}
+
+/// State of a single view.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ViewState {
+    /// The scroll offset of a scrollable view.
+    Offset(Vec2),
+    /// The index of the selected item or focused child, or a value.
+    Selected(usize),
+    /// Text edited by the user.
+    Content(String),
+    /// Whether the view is checked.
+    Checked(bool),
+}
+
+impl ViewState {
+    fn to_toml(&self) -> (&'static str, toml::Value) {
+        let number = |n: usize| toml::Value::Integer(n as i64);
+        match *self {
+            ViewState::Offset(offset) => (
+                "offset",
+                toml::Value::Array(vec![number(offset.x), number(offset.y)]),
+            ),
+            ViewState::Selected(i) => ("selected", number(i)),
+            ViewState::Content(ref content) => {
+                ("content", toml::Value::String(content.clone()))
+            }
+            ViewState::Checked(checked) => {
+                ("checked", toml::Value::Boolean(checked))
+            }
+        }
+    }
+
+    // Returns the first known state in `table`, skipping unknown keys.
+    fn from_toml(table: &toml::value::Table) -> Option<Self> {
+        table
+            .iter()
+            .find_map(|(key, value)| ViewState::from_entry(key, value))
+    }
+
+    fn from_entry(key: &str, value: &toml::Value) -> Option<Self> {
+        let number = |value: &toml::Value| match *value {
+            toml::Value::Integer(n) if n >= 0 => Some(n as usize),
+            _ => None,
+        };
+
+        let state = match (key, value) {
+            ("offset", toml::Value::Array(offset)) if offset.len() == 2 => {
+                ViewState::Offset(Vec2::new(
+                    number(&offset[0])?,
+                    number(&offset[1])?,
+                ))
+            }
+            ("selected", value) => ViewState::Selected(number(value)?),
+            ("content", toml::Value::String(content)) => {
+                ViewState::Content(content.clone())
+            }
+            ("checked", toml::Value::Boolean(checked)) => {
+                ViewState::Checked(*checked)
+            }
+            _ => return None,
+        };
+        Some(state)
+    }
+}
+
+/// The state of the views with an id.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::state::{UiState, ViewState};
+/// let mut state = UiState::new();
+/// state.insert("files", ViewState::Selected(3));
+/// state.insert("search", ViewState::Content("needle".into()));
+///
+/// let text = state.to_string();
+/// assert_eq!(UiState::parse(&text).unwrap(), state);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct UiState {
+    views: BTreeMap<String, ViewState>,
+}
+
+impl UiState {
+    /// Creates an empty state.
+    pub fn new() -> Self {
+        UiState::default()
+    }
+
+    /// Parses a state, in the format described in the module.
+    ///
+    /// Unknown keys, and views without a known state, are skipped.
+    pub fn parse(content: &str) -> Result<Self, Error> {
+        let table: toml::value::Table = toml::de::from_str(content)?;
+        Ok(UiState::from_toml(&table))
+    }
+
+    fn from_toml(table: &toml::value::Table) -> Self {
+        let mut state = UiState::new();
+        for (id, value) in table {
+            let view = match *value {
+                toml::Value::Table(ref table) => ViewState::from_toml(table),
+                _ => None,
+            };
+            if let Some(view) = view {
+                state.insert(id.clone(), view);
+            }
+        }
+        state
+    }
+
+    fn to_toml(&self) -> toml::value::Table {
+        let mut table = toml::value::Table::new();
+        for (id, state) in &self.views {
+            let (key, value) = state.to_toml();
+            let mut view = toml::value::Table::new();
+            view.insert(key.to_string(), value);
+            table.insert(id.clone(), toml::Value::Table(view));
+        }
+        table
+    }
+
+    /// Loads a state from a file.
+    pub fn load_file<P: AsRef<Path>>(filename: P) -> Result<Self, Error> {
+        let content = fs::read_to_string(filename)?;
+        UiState::parse(&content)
+    }
+
+    /// Saves this state to a file.
+    pub fn save_file<P: AsRef<Path>>(&self, filename: P) -> io::Result<()> {
+        fs::write(filename, self.to_string())
+    }
+
+    /// Sets the state of the view with the given id.
+    pub fn insert<S: Into<String>>(&mut self, id: S, state: ViewState) {
+        self.views.insert(id.into(), state);
+    }
+
+    /// Returns the state of the view with the given id.
+    pub fn get(&self, id: &str) -> Option<&ViewState> {
+        self.views.get(id)
+    }
+
+    /// Removes the state of the view with the given id.
+    pub fn remove(&mut self, id: &str) -> Option<ViewState> {
+        self.views.remove(id)
+    }
+
+    /// Iterates on the views, sorted by id.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &ViewState)> {
+        self.views.iter().map(|(id, state)| (id.as_str(), state))
+    }
+
+    /// Returns the number of views.
+    pub fn len(&self) -> usize {
+        self.views.len()
+    }
+
+    /// Returns `true` if this state has no view.
+    pub fn is_empty(&self) -> bool {
+        self.views.is_empty()
+    }
+}
+
+impl fmt::Display for UiState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", toml::Value::Table(self.to_toml()))
+    }
+}
+
+// With serde, the state is a map of views, each one a single-entry map like
+// in the toml files.
+#[cfg(feature = "serde")]
+impl serde::Serialize for UiState {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serde::Serialize::serialize(&self.to_toml(), serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for UiState {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let table: toml::value::Table =
+            serde::Deserialize::deserialize(deserializer)?;
+        Ok(UiState::from_toml(&table))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for ViewState {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let (key, value) = self.to_toml();
+        let mut table = toml::value::Table::new();
+        table.insert(key.to_string(), value);
+        serde::Serialize::serialize(&table, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ViewState {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let table: toml::value::Table =
+            serde::Deserialize::deserialize(deserializer)?;
+        ViewState::from_toml(&table)
+            .ok_or_else(|| serde::de::Error::custom("unknown view state"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::puppet;
+    use crate::traits::{Boxable, Identifiable};
+    use crate::views::{
+        Checkbox, IdError, IdView, LinearLayout, ScrollView, SelectView,
+    };
+    use crate::Cursive;
+
+    fn build(siv: &mut Cursive) {
+        let list = SelectView::new()
+            .with_all_str((0..20).map(|i| i.to_string()))
+            .with_id("list");
+        siv.add_fullscreen_layer(
+            LinearLayout::vertical()
+                .child(Checkbox::new().with_id("check"))
+                .child(ScrollView::new(list).with_id("scroll"))
+                .full_screen(),
+        );
+    }
+
+    #[test]
+    fn save_and_restore() {
+        let (backend, _) = puppet::Backend::init((10, 6));
+        let mut siv = Cursive::new(move || backend);
+        build(&mut siv);
+        siv.call_on_id("list", |view: &mut SelectView| view.set_selection(12));
+        siv.call_on_id("check", |view: &mut Checkbox| view.check());
+        siv.refresh();
+        siv.call_on_id("scroll", |view: &mut ScrollView<IdView<SelectView>>| {
+            view.set_offset((0, 8))
+        });
+        siv.refresh();
+
+        let state = siv.save_ui_state().unwrap();
+        let text = state.to_string();
+        assert_eq!(UiState::parse(&text).unwrap(), state);
+
+        siv.pop_layer();
+        build(&mut siv);
+        siv.restore_ui_state(&UiState::parse(&text).unwrap()).unwrap();
+        assert_eq!(siv.save_ui_state().unwrap(), state);
+        assert_eq!(state.get("list"), Some(&ViewState::Selected(12)));
+        assert_eq!(state.get("check"), Some(&ViewState::Checked(true)));
+        let offset = ViewState::Offset(Vec2::new(0, 8));
+        assert_eq!(state.get("scroll"), Some(&offset));
+    }
+
+    #[test]
+    fn errors() {
+        let mut siv = Cursive::dummy();
+        build(&mut siv);
+
+        let view = siv.find_id::<Checkbox>("check").unwrap();
+        let borrowed = Err(IdError::Borrowed("check".into()));
+        assert_eq!(siv.save_ui_state(), borrowed);
+        assert_eq!(siv.restore_ui_state(&UiState::new()), Ok(()));
+        drop(view);
+
+        siv.add_layer(Checkbox::new().with_id("check"));
+        let duplicate = IdError::Duplicate("check".into());
+        assert_eq!(siv.save_ui_state(), Err(duplicate.clone()));
+        assert_eq!(siv.restore_ui_state(&UiState::new()), Err(duplicate));
+    }
+
+    #[test]
+    fn unknown_keys() {
+        let text = "[list]\ncolor = \"red\"\nselected = 4\n\n[other]\nx = 1";
+        let state = UiState::parse(text).unwrap();
+        assert_eq!(state.get("list"), Some(&ViewState::Selected(4)));
+        assert_eq!(state.len(), 1);
+
+        let err = UiState::parse("[list").unwrap_err();
+        assert!(err.to_string().starts_with("invalid UI state"));
+        assert!(error::Error::source(&err).is_some());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let mut state = UiState::new();
+        state.insert("files", ViewState::Offset(Vec2::new(0, 42)));
+        state.insert("search", ViewState::Content("needle".into()));
+
+        let text = toml::to_string(&state).unwrap();
+        assert_eq!(UiState::parse(&text).unwrap(), state);
+        assert_eq!(toml::from_str::<UiState>(&text).unwrap(), state);
+    }
+}
@@ -63,6 +63,73 @@ impl From<u8> for BaseColor {
     }
 }
 
+/// Number of colors a terminal can display.
+///
+/// Backends use it to convert colors the terminal does not support to the
+/// closest one it does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ColorDepth {
+    /// 24-bit colors: every `Color` is displayed as is.
+    TrueColor,
+    /// The 256 colors palette.
+    Palette256,
+    /// Only the 16 base colors.
+    Palette16,
+}
+
+impl ColorDepth {
+    /// Guesses the color depth of the current terminal.
+    ///
+    /// Uses the `COLORTERM` and `TERM` environment variables. Terminals
+    /// calling themselves `xterm` get 256 colors, unless `TERM` names
+    /// another number of colors, like `xterm-16color`.
+    pub fn detect() -> Self {
+        let colorterm = std::env::var("COLORTERM").ok();
+        let term = std::env::var("TERM").ok();
+        ColorDepth::from_env(colorterm.as_deref(), term.as_deref())
+    }
+
+    fn from_env(colorterm: Option<&str>, term: Option<&str>) -> Self {
+        match (colorterm, term) {
+            (Some("truecolor"), _) | (Some("24bit"), _) => {
+                ColorDepth::TrueColor
+            }
+            (_, Some(term)) if term.contains("256color") => {
+                ColorDepth::Palette256
+            }
+            (_, Some(term))
+                if term.starts_with("xterm") && !term.contains("color") =>
+            {
+                ColorDepth::Palette256
+            }
+            _ => ColorDepth::Palette16,
+        }
+    }
+}
+
+// Values of the 6 levels of `RgbLowRes`.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+// Default values of the 16 base colors, as used by xterm.
+const BASE_COLORS: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
 /// Represents a color used by the theme.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Color {
@@ -126,6 +193,95 @@ impl Color {
         }
     }
 
+    /// Returns the red, green and blue values of this color.
+    ///
+    /// Base colors use the default values of xterm, as their actual value
+    /// depends on the terminal. Returns `None` for `TerminalDefault`.
+    pub fn to_rgb(self) -> Option<(u8, u8, u8)> {
+        match self {
+            Color::TerminalDefault => None,
+            Color::Dark(color) => Some(BASE_COLORS[color as usize]),
+            Color::Light(color) => Some(BASE_COLORS[8 + color as usize]),
+            Color::Rgb(r, g, b) => Some((r, g, b)),
+            Color::RgbLowRes(r, g, b) => Some((
+                CUBE_LEVELS[r as usize],
+                CUBE_LEVELS[g as usize],
+                CUBE_LEVELS[b as usize],
+            )),
+        }
+    }
+
+    /// Returns the ID of the closest color in the 256 colors list.
+    ///
+    /// This is the opposite of `Color::from_256colors`. Returns `None` for
+    /// `TerminalDefault`.
+    pub fn to_256colors(self) -> Option<u8> {
+        match self {
+            Color::TerminalDefault => None,
+            Color::Dark(color) => Some(color as u8),
+            Color::Light(color) => Some(8 + color as u8),
+            Color::RgbLowRes(r, g, b) => Some(16 + 36 * r + 6 * g + b),
+            Color::Rgb(r, g, b) => {
+                // Closest color in the 6x6x6 cube...
+                let level = |v: u8| match v {
+                    0..=47 => 0,
+                    48..=114 => 1,
+                    v => (v - 35) / 40,
+                };
+                let cube = Color::RgbLowRes(level(r), level(g), level(b));
+
+                // ... and closest grayscale value.
+                let mean = (u16::from(r) + u16::from(g) + u16::from(b)) / 3;
+                let n = (mean.clamp(3, 238) - 3) / 10;
+                let gray = (8 + 10 * n) as u8;
+
+                let rgb = (r, g, b);
+                if distance(rgb, (gray, gray, gray))
+                    < distance(rgb, cube.to_rgb().unwrap())
+                {
+                    Some(232 + n as u8)
+                } else {
+                    cube.to_256colors()
+                }
+            }
+        }
+    }
+
+    /// Returns the closest of the 16 base colors.
+    ///
+    /// `TerminalDefault` and base colors are returned unchanged.
+    pub fn to_16colors(self) -> Color {
+        let rgb = match self {
+            Color::Rgb(r, g, b) => (r, g, b),
+            Color::RgbLowRes(..) => self.to_rgb().unwrap(),
+            other => return other,
+        };
+
+        let n = (0..16)
+            .min_by_key(|&n| distance(rgb, BASE_COLORS[n]))
+            .unwrap();
+        if n < 8 {
+            Color::Dark(BaseColor::from(n as u8))
+        } else {
+            Color::Light(BaseColor::from(n as u8))
+        }
+    }
+
+    /// Returns the closest color that can be displayed with `depth`.
+    ///
+    /// With `ColorDepth::Palette256`, `Rgb` colors are kept if they are part
+    /// of the list: use `Color::to_256colors` to get their ID.
+    pub fn quantize(self, depth: ColorDepth) -> Color {
+        match depth {
+            ColorDepth::TrueColor => self,
+            ColorDepth::Palette256 => match self.to_256colors() {
+                Some(n) => Color::from_256colors(n),
+                None => self,
+            },
+            ColorDepth::Palette16 => self.to_16colors(),
+        }
+    }
+
     /// Parse a string into a color.
     ///
     /// Examples:
@@ -192,6 +348,12 @@ impl Color {
     }
 }
 
+// Squared distance between two colors.
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |a: u8, b: u8| (i32::from(a) - i32::from(b)).pow(2) as u32;
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
 /// Loads a hexadecimal code
 fn load_hex(s: &str) -> u16 {
     let mut sum = 0;
@@ -221,4 +383,42 @@ mod tests {
             Color::from_256colors(i as u8);
         }
     }
+
+    #[test]
+    fn test_quantize() {
+        use super::{BaseColor, Color, ColorDepth};
+
+        for i in 0..=255u8 {
+            assert_eq!(Color::from_256colors(i).to_256colors(), Some(i));
+        }
+
+        let orange = Color::Rgb(255, 135, 0);
+        assert_eq!(orange.quantize(ColorDepth::TrueColor), orange);
+        assert_eq!(orange.to_256colors(), Some(208));
+        assert_eq!(
+            orange.quantize(ColorDepth::Palette256),
+            Color::RgbLowRes(5, 2, 0)
+        );
+        assert_eq!(
+            orange.quantize(ColorDepth::Palette16),
+            Color::Dark(BaseColor::Yellow)
+        );
+        assert_eq!(Color::Rgb(70, 72, 69).to_256colors(), Some(238));
+        assert_eq!(
+            Color::TerminalDefault.quantize(ColorDepth::Palette16),
+            Color::TerminalDefault
+        );
+
+        let detect = ColorDepth::from_env;
+        assert_eq!(detect(Some("truecolor"), None), ColorDepth::TrueColor);
+        let term = Some("xterm-256color");
+        assert_eq!(detect(None, term), ColorDepth::Palette256);
+        assert_eq!(detect(None, Some("xterm")), ColorDepth::Palette256);
+        let term = Some("xterm-kitty");
+        assert_eq!(detect(None, term), ColorDepth::Palette256);
+        let term = Some("xterm-16color");
+        assert_eq!(detect(None, term), ColorDepth::Palette16);
+        assert_eq!(detect(None, Some("linux")), ColorDepth::Palette16);
+        assert_eq!(detect(None, None), ColorDepth::Palette16);
+    }
 }
@@ -0,0 +1,209 @@
+//! Compose characters from several key presses.
+//!
+//! Some keyboard layouts use "dead keys": pressing `^` then `e` should give
+//! `ê`. Terminals usually do this themselves, but some only send the
+//! accent, followed by the letter.
+//!
+//! Input methods can also show some text before it is committed (for
+//! example while a word is being converted).
+//!
+//! [`Composer`] keeps track of such an in-progress composition. It is used by
+//! `EditView` and `TextArea`, and can be used by custom text input views.
+//!
+//! [`Composer`]: struct.Composer.html
+
+// For each dead key, the letters it can modify and the result.
+const DEAD_KEYS: &[(char, &str, &str)] = &[
+    ('`', "aeiouAEIOU", "àèìòùÀÈÌÒÙ"),
+    ('´', "aeiouyAEIOUY", "áéíóúýÁÉÍÓÚÝ"),
+    ('^', "aeiouAEIOU", "âêîôûÂÊÎÔÛ"),
+    ('¨', "aeiouyAEIOU", "äëïöüÿÄËÏÖÜ"),
+    ('~', "anoANO", "ãñõÃÑÕ"),
+];
+
+/// Returns `true` if `c` can start a dead key composition.
+pub fn is_dead_key(c: char) -> bool {
+    DEAD_KEYS.iter().any(|&(dead, _, _)| dead == c)
+}
+
+/// Returns the result of typing `base` after the dead key `dead`.
+///
+/// Returns `None` if they cannot be composed.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::utils::compose;
+/// assert_eq!(compose::compose('^', 'e'), Some('ê'));
+/// assert_eq!(compose::compose('^', 'x'), None);
+/// ```
+pub fn compose(dead: char, base: char) -> Option<char> {
+    let &(_, bases, results) =
+        DEAD_KEYS.iter().find(|&&(d, _, _)| d == dead)?;
+    let i = bases.chars().position(|c| c == base)?;
+    results.chars().nth(i)
+}
+
+/// Text being composed, not yet part of the content.
+#[derive(Clone, Debug, Default)]
+pub struct Composer {
+    dead_keys: bool,
+    text: String,
+    // `true` if `text` comes from an input method, rather than a dead key.
+    from_input_method: bool,
+}
+
+impl Composer {
+    /// Creates a new, empty composer.
+    ///
+    /// Dead keys are disabled by default.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enables or disables dead key composition.
+    pub fn set_dead_keys(&mut self, enabled: bool) {
+        self.dead_keys = enabled;
+    }
+
+    /// Returns `true` if dead key composition is enabled.
+    pub fn get_dead_keys(&self) -> bool {
+        self.dead_keys
+    }
+
+    /// Replaces the text being composed.
+    ///
+    /// This can be used by an input method to show its in-progress text.
+    /// Input methods then commit it as typed characters, often one event
+    /// per character: `feed` takes each of them from the start of the
+    /// composition, instead of inserting them after it.
+    pub fn set_text<S: Into<String>>(&mut self, text: S) {
+        self.text = text.into();
+        self.from_input_method = true;
+    }
+
+    /// Returns the text being composed.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns `true` if some text is being composed.
+    pub fn is_composing(&self) -> bool {
+        !self.text.is_empty()
+    }
+
+    /// Drops the text being composed.
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.from_input_method = false;
+    }
+
+    /// Returns the text being composed, and clears it.
+    pub fn take(&mut self) -> String {
+        self.from_input_method = false;
+        std::mem::take(&mut self.text)
+    }
+
+    /// Feeds a typed character.
+    ///
+    /// Returns the text to insert in the content. It may be empty if the
+    /// composition is still going on.
+    pub fn feed(&mut self, ch: char) -> String {
+        let mut result = String::new();
+
+        // The input method commits its text, maybe in several chunks.
+        if self.from_input_method && self.text.starts_with(ch) {
+            self.text.drain(..ch.len_utf8());
+            self.from_input_method = self.is_composing();
+            result.push(ch);
+            return result;
+        }
+
+        if self.is_composing() {
+            let from_input_method = self.from_input_method;
+            let text = self.take();
+            let mut chars = text.chars();
+            match (chars.next(), chars.next()) {
+                (Some(dead), None)
+                    if !from_input_method
+                        && self.dead_keys
+                        && is_dead_key(dead) =>
+                {
+                    // A space gives the accent itself.
+                    if ch == ' ' {
+                        result.push(dead);
+                        return result;
+                    }
+                    if let Some(c) = compose(dead, ch) {
+                        result.push(c);
+                        return result;
+                    }
+                    result.push(dead);
+                }
+                // Anything else is committed as-is.
+                _ => result.push_str(&text),
+            }
+        }
+
+        if self.dead_keys && is_dead_key(ch) {
+            self.text.push(ch);
+        } else {
+            result.push(ch);
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dead_keys() {
+        let mut composer = Composer::new();
+        assert_eq!(composer.feed('^'), "^");
+
+        composer.set_dead_keys(true);
+        assert_eq!(composer.feed('^'), "");
+        assert_eq!(composer.text(), "^");
+        assert_eq!(composer.feed('e'), "ê");
+
+        composer.feed('~');
+        assert_eq!(composer.feed(' '), "~");
+
+        composer.feed('¨');
+        assert_eq!(composer.feed('x'), "¨x");
+
+        composer.feed('`');
+        assert_eq!(composer.feed('^'), "`");
+        assert_eq!(composer.feed('o'), "ô");
+
+        composer.set_text("漢字");
+        assert_eq!(composer.feed('!'), "漢字!");
+        assert!(!composer.is_composing());
+    }
+
+    #[test]
+    fn input_method_chunks() {
+        let mut composer = Composer::new();
+        composer.set_dead_keys(true);
+
+        // The committed text comes one character at a time.
+        composer.set_text("漢字");
+        assert_eq!(composer.feed('漢'), "漢");
+        assert_eq!(composer.text(), "字");
+        assert_eq!(composer.feed('字'), "字");
+        assert!(!composer.is_composing());
+
+        // Other characters commit what is left first.
+        composer.set_text("漢字");
+        assert_eq!(composer.feed('漢'), "漢");
+        assert_eq!(composer.feed('!'), "字!");
+
+        // Dead keys still work afterwards.
+        assert_eq!(composer.feed('^'), "");
+        assert_eq!(composer.feed('^'), "^");
+        assert_eq!(composer.text(), "^");
+    }
+}
@@ -1,6 +1,12 @@
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
+#[cfg(target_has_atomic = "64")]
+use std::sync::atomic::AtomicU64;
+
+#[cfg(not(target_has_atomic = "64"))]
+use self::locked::AtomicU64;
+
 /// Atomic counter used by [`ProgressBar`].
 ///
 /// [`ProgressBar`]: ../views/struct.ProgressBar.html
@@ -28,3 +34,146 @@ impl Counter {
         self.0.fetch_add(ticks, Ordering::Relaxed);
     }
 }
+
+/// Atomic 64-bit counter.
+///
+/// Like [`Counter`], but doesn't overflow on platforms where `usize` is 32
+/// bits, for instance when counting bytes of a large transfer.
+///
+/// On targets without 64-bit atomics, the value is behind a lock instead.
+///
+/// [`Counter`]: struct.Counter.html
+#[derive(Clone, Debug)]
+pub struct Counter64(Arc<AtomicU64>);
+
+impl Counter64 {
+    /// Creates a new `Counter64` starting with the given value.
+    pub fn new(value: u64) -> Self {
+        Counter64(Arc::new(AtomicU64::new(value)))
+    }
+
+    /// Retrieves the current progress value.
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Sets the current progress value.
+    pub fn set(&self, value: u64) {
+        self.0.store(value, Ordering::Relaxed);
+    }
+
+    /// Increase the current progress by `ticks`.
+    pub fn tick(&self, ticks: u64) {
+        self.0.fetch_add(ticks, Ordering::Relaxed);
+    }
+}
+
+/// Atomic floating-point counter.
+///
+/// Useful when the progress comes in fractions, like `0.25` of a step.
+#[derive(Clone, Debug)]
+pub struct FloatCounter(Arc<AtomicU64>);
+
+impl FloatCounter {
+    /// Creates a new `FloatCounter` starting with the given value.
+    pub fn new(value: f64) -> Self {
+        FloatCounter(Arc::new(AtomicU64::new(value.to_bits())))
+    }
+
+    /// Retrieves the current progress value.
+    pub fn get(&self) -> f64 {
+        f64::from_bits(self.0.load(Ordering::Relaxed))
+    }
+
+    /// Sets the current progress value.
+    pub fn set(&self, value: f64) {
+        self.0.store(value.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Increase the current progress by `ticks`.
+    pub fn tick(&self, ticks: f64) {
+        let mut current = self.0.load(Ordering::Relaxed);
+        loop {
+            let next = (f64::from_bits(current) + ticks).to_bits();
+            match self.0.compare_exchange_weak(
+                current,
+                next,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return,
+                Err(actual) => current = actual,
+            }
+        }
+    }
+}
+
+// Replaces `AtomicU64` on targets without 64-bit atomics, like some 32-bit
+// ARM, MIPS or PowerPC.
+#[cfg(any(test, not(target_has_atomic = "64")))]
+#[cfg_attr(test, allow(dead_code))]
+mod locked {
+    use std::sync::atomic::Ordering;
+    use std::sync::Mutex;
+
+    #[derive(Debug, Default)]
+    pub struct AtomicU64(Mutex<u64>);
+
+    impl AtomicU64 {
+        pub fn new(value: u64) -> Self {
+            AtomicU64(Mutex::new(value))
+        }
+
+        pub fn load(&self, _: Ordering) -> u64 {
+            *self.0.lock().unwrap()
+        }
+
+        pub fn store(&self, value: u64, _: Ordering) {
+            *self.0.lock().unwrap() = value;
+        }
+
+        pub fn fetch_add(&self, value: u64, _: Ordering) -> u64 {
+            let mut current = self.0.lock().unwrap();
+            let previous = *current;
+            *current = previous.wrapping_add(value);
+            previous
+        }
+
+        pub fn compare_exchange_weak(
+            &self, expected: u64, new: u64, _: Ordering, _: Ordering,
+        ) -> Result<u64, u64> {
+            let mut current = self.0.lock().unwrap();
+            if *current == expected {
+                *current = new;
+                Ok(expected)
+            } else {
+                Err(*current)
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn locked_atomic() {
+            let value = AtomicU64::new(u64::MAX - 1);
+            assert_eq!(value.fetch_add(1, Ordering::Relaxed), u64::MAX - 1);
+            assert_eq!(value.load(Ordering::Relaxed), u64::MAX);
+
+            let swap = |expected, new| {
+                value.compare_exchange_weak(
+                    expected,
+                    new,
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                )
+            };
+            assert_eq!(swap(3, 4), Err(u64::MAX));
+            assert_eq!(swap(u64::MAX, 4), Ok(u64::MAX));
+            value.store(7, Ordering::Relaxed);
+            assert_eq!(value.load(Ordering::Relaxed), 7);
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::utils::time::Instant;
+use crate::{Cursive, TimerHandle};
+
+/// Wraps `cb` so it only runs once calls stop for `delay`.
+///
+/// Each call to the returned callback cancels the previous pending one,
+/// and schedules `cb` with the latest value after `delay`, using
+/// `Cursive::set_timeout`. This suits live-search fields, which should
+/// only send a query once the user stops typing.
+///
+/// The value must be owned: callbacks receiving a `&str`, like
+/// `EditView::on_edit`, can convert it first.
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::time::Duration;
+/// # use cursive::Cursive;
+/// # use cursive::utils::debounce;
+/// # use cursive::views::EditView;
+/// let search = debounce(Duration::from_millis(300), |s, query: String| {
+///     // Only runs 300ms after the last keystroke.
+///     s.set_user_data(query);
+/// });
+///
+/// let mut siv = Cursive::dummy();
+/// siv.add_layer(EditView::new().on_edit(move |s, text, _| {
+///     search(s, text.to_string())
+/// }));
+/// ```
+pub fn debounce<T, F>(delay: Duration, cb: F) -> impl Fn(&mut Cursive, T)
+where
+    T: 'static,
+    F: Fn(&mut Cursive, T) + 'static,
+{
+    let cb = Rc::new(cb);
+    let pending: Rc<RefCell<Option<TimerHandle>>> = Rc::default();
+
+    move |s, value| {
+        if let Some(timer) = pending.borrow_mut().take() {
+            timer.cancel();
+        }
+
+        let cb = Rc::clone(&cb);
+        let timer = s.set_timeout(delay, move |s| cb(s, value));
+        *pending.borrow_mut() = Some(timer);
+    }
+}
+
+struct Throttle<T> {
+    last_call: Option<Instant>,
+    // Latest value received too early, with the timer which will use it.
+    pending: Option<T>,
+    timer: Option<TimerHandle>,
+}
+
+/// Wraps `cb` so it runs at most once every `interval`.
+///
+/// The first call runs `cb` right away. Calls received during the
+/// following `interval` are merged: once it ends, `cb` runs with the
+/// latest value, using `Cursive::set_timeout`. Unlike [`debounce`], this
+/// keeps reacting while calls keep coming.
+///
+/// [`debounce`]: fn.debounce.html
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::time::Duration;
+/// # use cursive::Cursive;
+/// # use cursive::utils::throttle;
+/// let update = throttle(Duration::from_millis(100), |s, count: usize| {
+///     s.set_user_data(count);
+/// });
+///
+/// let mut siv = Cursive::dummy();
+/// update(&mut siv, 1);
+/// update(&mut siv, 2);
+/// // Only the first call ran so far: the second one is delayed.
+/// assert_eq!(siv.user_data::<usize>(), Some(&mut 1));
+/// ```
+pub fn throttle<T, F>(interval: Duration, cb: F) -> impl Fn(&mut Cursive, T)
+where
+    T: 'static,
+    F: Fn(&mut Cursive, T) + 'static,
+{
+    let cb = Rc::new(cb);
+    let state = Rc::new(RefCell::new(Throttle {
+        last_call: None,
+        pending: None,
+        timer: None,
+    }));
+
+    move |s, value| {
+        let now = Instant::now();
+        let remaining = {
+            let mut state = state.borrow_mut();
+            let last_call = state.last_call;
+            match last_call {
+                Some(last) if now < last + interval => {
+                    state.pending = Some(value);
+                    if state.timer.is_some() {
+                        // The pending call will use the new value.
+                        return;
+                    }
+                    last + interval - now
+                }
+                _ => {
+                    state.last_call = Some(now);
+                    drop(state);
+                    cb(s, value);
+                    return;
+                }
+            }
+        };
+
+        let cb = Rc::clone(&cb);
+        let delayed = Rc::clone(&state);
+        let timer = s.set_timeout(remaining, move |s| {
+            let value = {
+                let mut state = delayed.borrow_mut();
+                state.timer = None;
+                state.last_call = Some(Instant::now());
+                state.pending.take()
+            };
+            if let Some(value) = value {
+                cb(s, value);
+            }
+        });
+        state.borrow_mut().timer = Some(timer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::puppet;
+
+    #[test]
+    fn debounced_calls() {
+        let (backend, _) = puppet::Backend::init((10, 2));
+        let mut siv = Cursive::new(move || backend);
+        siv.set_user_data(Vec::<u32>::new());
+
+        let push = |s: &mut Cursive, value: u32| {
+            s.with_user_data(|values: &mut Vec<u32>| values.push(value));
+        };
+        // The calls below take much less than these delays, even on a busy
+        // machine: only the last debounced call and the first and last
+        // throttled ones run.
+        let debounced = debounce(Duration::from_millis(200), push);
+        let throttled = throttle(Duration::from_millis(200), push);
+
+        for i in 0..3 {
+            debounced(&mut siv, i);
+        }
+        for i in 10..13 {
+            throttled(&mut siv, i);
+        }
+
+        let timeout = Duration::from_millis(600);
+        let start = Instant::now();
+        while start.elapsed() < timeout {
+            siv.step_with_timeout(timeout);
+        }
+        let mut values = siv.take_user_data::<Vec<u32>>().unwrap();
+        values.sort();
+        assert_eq!(values, vec![2, 10, 12]);
+    }
+}
@@ -9,8 +9,8 @@ mod row;
 
 pub use self::lines_iterator::LinesIterator;
 pub use self::row::Row;
+use crate::utils::width::TextWidth;
 use unicode_segmentation::UnicodeSegmentation;
-use unicode_width::UnicodeWidthStr;
 
 /// The length and width of a part of a string.
 pub struct Span {
@@ -1,8 +1,8 @@
 use super::chunk::Chunk;
 use super::segment::Segment;
 use crate::utils::span::SpannedText;
+use crate::utils::width::TextWidth;
 use std::rc::Rc;
-use unicode_width::UnicodeWidthStr;
 use xi_unicode::LineBreakLeafIter;
 
 /// Iterator that returns non-breakable chunks of text.
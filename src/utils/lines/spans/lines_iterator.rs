@@ -5,10 +5,10 @@ use super::row::Row;
 use super::segment::Segment;
 use super::segment_merge_iterator::SegmentMergeIterator;
 use crate::utils::span::SpannedText;
+use crate::utils::width::TextWidth;
 use std::iter::Peekable;
 use std::rc::Rc;
 use unicode_segmentation::UnicodeSegmentation;
-use unicode_width::UnicodeWidthStr;
 
 /// Generates rows of text in constrainted width.
 ///
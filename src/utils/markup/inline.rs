@@ -0,0 +1,275 @@
+//! Parse a simple inline markup.
+//!
+//! This is a small subset of markdown and BBCode, meant for short strings:
+//!
+//! * `**bold**`, `*italic*` or `_italic_`
+//! * `[b]bold[/b]`, `[i]italic[/i]`, `[u]underline[/u]`
+//! * `[red]colored[/red]`, using any color name accepted by
+//!   [`Color::parse`], like `[light blue]` or `[#ff8800]`.
+//!   `[/]` closes the last opened tag.
+//! * `[text](url)` shows `text` underlined, without the url.
+//!
+//! Use `\` to escape special characters. Anything else is kept as-is.
+//!
+//! [`Color::parse`]: ../../../theme/enum.Color.html#method.parse
+
+use crate::theme::{Color, Effect, Style};
+use crate::utils::markup::StyledString;
+
+/// Parses the given string as inline markup.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::utils::markup::inline;
+/// let text = inline::parse("**error**: [red]failed[/red]");
+/// assert_eq!(text.source(), "error: failed");
+/// ```
+pub fn parse<S>(input: S) -> StyledString
+where
+    S: Into<String>,
+{
+    let input = input.into();
+
+    let mut parser = Parser {
+        result: StyledString::new(),
+        stack: Vec::new(),
+        current: String::new(),
+    };
+    parser.parse(&input);
+    parser.result
+}
+
+// What opened a style, to know what can close it.
+#[derive(PartialEq)]
+enum Tag {
+    // `**`
+    Stars,
+    // `*` or `_`
+    Emphasis(char),
+    // `[name]`
+    Bracket(String),
+}
+
+struct Parser {
+    result: StyledString,
+    // Currently opened tags.
+    stack: Vec<(Tag, Style)>,
+    // Text not yet added to `result`, using the current style.
+    current: String,
+}
+
+impl Parser {
+    fn parse(&mut self, input: &str) {
+        let mut rest = input;
+        let mut previous = None;
+
+        while let Some(c) = rest.chars().next() {
+            let next = rest[c.len_utf8()..].chars().next();
+
+            let consumed = match c {
+                '\\' if next.is_some() => {
+                    self.current.extend(next);
+                    1 + next.unwrap().len_utf8()
+                }
+                '*' if next == Some('*') => {
+                    let after = rest[2..].chars().next();
+                    if !self.toggle(Tag::Stars, Effect::Bold, previous, after)
+                    {
+                        self.current.push_str("**");
+                    }
+                    2
+                }
+                '*' | '_' => {
+                    // Don't break snake_case words.
+                    let inside_word =
+                        c == '_' && is_word(previous) && is_word(next);
+                    if inside_word
+                        || !self.toggle(
+                            Tag::Emphasis(c),
+                            Effect::Italic,
+                            previous,
+                            next,
+                        )
+                    {
+                        self.current.push(c);
+                    }
+                    1
+                }
+                '[' => self.bracket(rest).unwrap_or_else(|| {
+                    self.current.push('[');
+                    1
+                }),
+                c => {
+                    self.current.push(c);
+                    c.len_utf8()
+                }
+            };
+
+            previous = rest[..consumed].chars().last();
+            rest = &rest[consumed..];
+        }
+
+        self.flush();
+    }
+
+    // Opens or closes a style marker.
+    //
+    // Returns `false` if the marker could not be used.
+    fn toggle(
+        &mut self, tag: Tag, effect: Effect, previous: Option<char>,
+        next: Option<char>,
+    ) -> bool {
+        if let Some(i) = self.stack.iter().position(|(t, _)| *t == tag) {
+            // Closing markers must follow some text.
+            if is_space(previous) {
+                return false;
+            }
+            self.flush();
+            self.stack.remove(i);
+        } else {
+            // Opening markers must precede some text.
+            if is_space(next) {
+                return false;
+            }
+            self.flush();
+            self.stack.push((tag, Style::from(effect)));
+        }
+
+        true
+    }
+
+    // Handles a `[`, at the start of `text`.
+    //
+    // Returns the number of bytes consumed, or `None` if this is not markup.
+    fn bracket(&mut self, text: &str) -> Option<usize> {
+        let end = text.find(']')?;
+        let name = &text[1..end];
+        let after = &text[end + 1..];
+
+        // A link: `[text](url)`
+        if after.starts_with('(') {
+            if let Some(close) = after.find(')') {
+                self.flush();
+                let style = self.style().combine(Effect::Underline);
+                self.result.append_styled(name, style);
+                return Some(end + 1 + close + 1);
+            }
+        }
+
+        if let Some(name) = name.strip_prefix('/') {
+            let name = name.trim().to_lowercase();
+            let i = self.stack.iter().rposition(|(tag, _)| match *tag {
+                Tag::Bracket(ref tag) => name.is_empty() || *tag == name,
+                _ => false,
+            })?;
+            self.flush();
+            self.stack.remove(i);
+        } else {
+            let name = name.trim().to_lowercase();
+            let style = tag_style(&name)?;
+            self.flush();
+            self.stack.push((Tag::Bracket(name), style));
+        }
+
+        Some(end + 1)
+    }
+
+    // Returns the style from all opened tags.
+    fn style(&self) -> Style {
+        let styles: Vec<Style> =
+            self.stack.iter().map(|&(_, style)| style).collect();
+        Style::merge(&styles)
+    }
+
+    // Adds the pending text to the result, with the current style.
+    fn flush(&mut self) {
+        if self.current.is_empty() {
+            return;
+        }
+
+        let text = std::mem::take(&mut self.current);
+        let style = self.style();
+        self.result.append_styled(text, style);
+    }
+}
+
+fn is_word(c: Option<char>) -> bool {
+    c.filter(|c| c.is_alphanumeric()).is_some()
+}
+
+fn is_space(c: Option<char>) -> bool {
+    c.filter(|c| !c.is_whitespace()).is_none()
+}
+
+// Returns the style for a bracket tag, if it is a known one.
+fn tag_style(name: &str) -> Option<Style> {
+    Some(match name {
+        "b" | "bold" => Effect::Bold.into(),
+        "i" | "italic" => Effect::Italic.into(),
+        "u" | "underline" => Effect::Underline.into(),
+        "reverse" => Effect::Reverse.into(),
+        _ => parse_color(name)?.into(),
+    })
+}
+
+// `Color::parse` panics on some malformed colors, so check them first.
+fn parse_color(name: &str) -> Option<Color> {
+    if !name.is_ascii() {
+        return None;
+    }
+
+    if let Some(hex) = name.strip_prefix('#') {
+        let valid = (hex.len() == 3 || hex.len() == 6)
+            && hex.chars().all(|c| c.is_ascii_hexdigit());
+        if !valid {
+            return None;
+        }
+    }
+
+    Color::parse(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theme::BaseColor;
+    use crate::utils::span::Span;
+
+    #[test]
+    fn inline_markup() {
+        let parsed = parse("**error**: [red]failed[/red] in snake_case");
+        let spans: Vec<_> = parsed.spans().collect();
+        assert_eq!(
+            spans,
+            vec![
+                Span {
+                    content: "error",
+                    width: 5,
+                    attr: &Style::from(Effect::Bold),
+                },
+                Span {
+                    content: ": ",
+                    width: 2,
+                    attr: &Style::none(),
+                },
+                Span {
+                    content: "failed",
+                    width: 6,
+                    attr: &Style::from(Color::Dark(BaseColor::Red)),
+                },
+                Span {
+                    content: " in snake_case",
+                    width: 14,
+                    attr: &Style::none(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn inline_markup_literals() {
+        let parsed = parse("a * b, [not a tag], \\*c\\*, [link](url)");
+        assert_eq!(parsed.source(), "a * b, [not a tag], *c*, link");
+    }
+}
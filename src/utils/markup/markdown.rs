@@ -5,11 +5,11 @@
 use std::borrow::Cow;
 
 use crate::theme::{Effect, Style};
-use crate::utils::markup::{StyledIndexedSpan, StyledString};
+use crate::utils::markup::{Link, StyledIndexedSpan, StyledString};
 use crate::utils::span::IndexedCow;
+use crate::utils::width::TextWidth;
 
 use pulldown_cmark::{self, CowStr, Event, Tag};
-use unicode_width::UnicodeWidthStr;
 
 /// Parses the given string as markdown text.
 pub fn parse<S>(input: S) -> StyledString
@@ -23,12 +23,47 @@ where
     StyledString::with_spans(input, spans)
 }
 
+/// Parses the given string as markdown text, keeping the link targets.
+///
+/// Unlike [`parse`], links are not written out as `[text](target)`: only
+/// their text is kept, and the returned links tell where they are.
+///
+/// [`parse`]: fn.parse.html
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::utils::markup::markdown;
+/// let (text, links) = markdown::parse_with_links("See [the docs](docs.rs).");
+/// assert_eq!(links[0].target, "docs.rs");
+/// ```
+pub fn parse_with_links<S>(input: S) -> (StyledString, Vec<Link>)
+where
+    S: Into<String>,
+{
+    let input = input.into();
+
+    let mut parser = Parser::new(&input).with_links();
+    let spans: Vec<_> = parser.by_ref().collect();
+    let links = parser.take_links();
+
+    (StyledString::with_spans(input, spans), links)
+}
+
 /// Iterator that parse a markdown text and outputs styled spans.
 pub struct Parser<'a> {
     first: bool,
     stack: Vec<Style>,
     input: &'a str,
     parser: pulldown_cmark::Parser<'a>,
+
+    // Bytes of text produced so far.
+    offset: usize,
+
+    // Links found so far, with the start of the link being parsed.
+    //
+    // `None` to write the links out as markdown instead.
+    links: Option<(Vec<Link>, Vec<usize>)>,
 }
 
 impl<'a> Parser<'a> {
@@ -39,15 +74,35 @@ impl<'a> Parser<'a> {
             first: true,
             parser: pulldown_cmark::Parser::new(input),
             stack: Vec::new(),
+            offset: 0,
+            links: None,
+        }
+    }
+
+    /// Keeps the link targets instead of writing links out as markdown.
+    ///
+    /// The links found so far are available with `take_links`.
+    pub fn with_links(mut self) -> Self {
+        self.links = Some((Vec::new(), Vec::new()));
+        self
+    }
+
+    /// Returns the links found so far, if `with_links` was used.
+    pub fn take_links(&mut self) -> Vec<Link> {
+        match self.links {
+            Some((ref mut links, _)) => std::mem::take(links),
+            None => Vec::new(),
         }
     }
 
     /// Creates a new span with the given value
-    fn literal<S>(&self, text: S) -> StyledIndexedSpan
+    fn literal<S>(&mut self, text: S) -> StyledIndexedSpan
     where
         S: Into<String>,
     {
-        StyledIndexedSpan::simple_owned(text.into(), Style::merge(&self.stack))
+        let text = text.into();
+        self.offset += text.len();
+        StyledIndexedSpan::simple_owned(text, Style::merge(&self.stack))
     }
 }
 
@@ -81,7 +136,10 @@ impl<'a> Iterator for Parser<'a> {
                     }
                     Tag::Rule => return Some(self.literal("---")),
                     Tag::BlockQuote => return Some(self.literal("> ")),
-                    Tag::Link(_, _, _) => return Some(self.literal("[")),
+                    Tag::Link(_, _, _) => match self.links {
+                        Some((_, ref mut starts)) => starts.push(self.offset),
+                        None => return Some(self.literal("[")),
+                    },
                     Tag::Code => return Some(self.literal("```")),
                     Tag::Strong => self.stack.push(Style::from(Effect::Bold)),
                     Tag::Paragraph if !self.first => {
@@ -93,9 +151,16 @@ impl<'a> Iterator for Parser<'a> {
                     // Remove from stack!
                     Tag::Paragraph if self.first => self.first = false,
                     Tag::Header(_) => return Some(self.literal("\n\n")),
-                    Tag::Link(_, link, _) => {
-                        return Some(self.literal(format!("]({})", link)))
-                    }
+                    Tag::Link(_, link, _) => match self.links {
+                        Some((ref mut links, ref mut starts)) => {
+                            let start = starts.pop().unwrap();
+                            let range = start..self.offset;
+                            links.push(Link::new(range, link.to_string()));
+                        }
+                        None => {
+                            return Some(self.literal(format!("]({})", link)))
+                        }
+                    },
                     Tag::Code => return Some(self.literal("```")),
                     Tag::Emphasis | Tag::Strong => {
                         self.stack.pop().unwrap();
@@ -115,6 +180,7 @@ impl<'a> Iterator for Parser<'a> {
                         CowStr::Inlined(text) => Cow::Owned(text.to_string()),
                     };
                     let width = text.width();
+                    self.offset += text.len();
                     // Return something!
                     return Some(StyledIndexedSpan {
                         content: IndexedCow::from_cow(text, self.input),
@@ -200,4 +266,26 @@ I *really* love __Cursive__!";
             ]
         );
     }
+    #[test]
+    fn links() {
+        let input = "See [the *docs*](https://docs.rs) and [this](#x).";
+        let (text, links) = parse_with_links(input);
+
+        assert_eq!(text.source(), input);
+        let content: String =
+            text.spans().map(|span| span.content).collect();
+        assert_eq!(content, "See the docs and this.");
+        assert_eq!(
+            links,
+            vec![
+                Link::new(4..12, "https://docs.rs"),
+                Link::new(17..21, "#x"),
+            ]
+        );
+
+        // Without `with_links`, links are written out.
+        let content: String =
+            parse(input).spans().map(|span| span.content).collect();
+        assert_eq!(content, "See [the docs](https://docs.rs) and [this](#x).");
+    }
 }
@@ -2,12 +2,15 @@
 //!
 //! Each module is optional and relies on a feature.
 
+pub mod inline;
 #[cfg(feature = "markdown")]
 pub mod markdown;
 
 use crate::theme::Style;
 use crate::utils::span::{IndexedSpan, Span, SpannedString};
 
+use std::ops::Range;
+
 /// A parsed string with markup style.
 ///
 /// Contains both the source string, and parsed information indicating the
@@ -25,6 +28,33 @@ pub type StyledIndexedSpan = IndexedSpan<Style>;
 /// A resolved styled span borrowing its source string.
 pub type StyledSpan<'a> = Span<'a, Style>;
 
+/// A hyperlink in a `StyledString`.
+///
+/// Links are kept next to the text rather than in its style: `range` is a
+/// byte range of the text as displayed, that is, of the concatenated content
+/// of its spans.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Link {
+    /// Byte range of the text of the link.
+    pub range: Range<usize>,
+
+    /// Target of the link: an URL, or any id understood by the application.
+    pub target: String,
+}
+
+impl Link {
+    /// Creates a new link to `target` over the given byte range.
+    pub fn new<S>(range: Range<usize>, target: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Link {
+            range,
+            target: target.into(),
+        }
+    }
+}
+
 impl SpannedString<Style> {
     /// Returns a plain StyledString without any style.
     ///
@@ -48,6 +78,26 @@ impl SpannedString<Style> {
         Self::single_span(content, style)
     }
 
+    /// Parses the given inline markup into a `StyledString`.
+    ///
+    /// See the [`inline`] module for the supported syntax.
+    ///
+    /// [`inline`]: inline/index.html
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::utils::markup::StyledString;
+    /// let text = StyledString::from_markup("**error**: [red]failed[/red]");
+    /// assert_eq!(text.source(), "error: failed");
+    /// ```
+    pub fn from_markup<S>(input: S) -> Self
+    where
+        S: Into<String>,
+    {
+        inline::parse(input)
+    }
+
     /// Appends the given plain text to `self`.
     pub fn append_plain<S>(&mut self, text: S)
     where
@@ -1,12 +1,18 @@
 //! Toolbox to make text layout easier.
 
+pub mod compose;
 mod counter;
+mod debounce;
 #[macro_use]
 mod immutify;
 pub mod lines;
 pub mod markup;
 mod reader;
+pub mod search;
 pub mod span;
+pub(crate) mod time;
+pub mod width;
 
-pub use self::counter::Counter;
+pub use self::counter::{Counter, Counter64, FloatCounter};
+pub use self::debounce::{debounce, throttle};
 pub use self::reader::ProgressReader;
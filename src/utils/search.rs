@@ -0,0 +1,553 @@
+//! Search for patterns in text.
+//!
+//! [`Pattern`] finds either a literal string, or a small subset of regular
+//! expressions:
+//!
+//! * `.` matches any character, `[abc]`, `[a-z]` or `[^abc]` a set of them.
+//! * `\d`, `\w` and `\s` match digits, word characters and whitespace
+//!   (`\D`, `\W` and `\S` match the opposite).
+//! * `*`, `+` and `?` repeat the previous item.
+//! * `(...)` groups items, and `|` separates alternatives.
+//! * `^` and `$` match the start and end of a line.
+//!
+//! Use `\` to escape special characters.
+//!
+//! Matching takes time proportional to the length of the text times the
+//! length of the expression, whatever the expression.
+//!
+//! [`Pattern`]: struct.Pattern.html
+
+use std::ops::Range;
+
+/// Error returned when a regular expression cannot be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// A `(` or `[` was not closed.
+    Unclosed(usize),
+    /// A `)` was not opened.
+    Unopened(usize),
+    /// A repetition (`*`, `+`, `?`) does not follow anything.
+    NothingToRepeat(usize),
+    /// The expression ends with a single `\`.
+    TrailingEscape,
+}
+
+// A piece of a regular expression.
+#[derive(Debug, Clone)]
+enum Node {
+    Char(char),
+    Any,
+    // Ranges of characters, and whether the set is negated.
+    Class(Vec<(char, char)>, bool),
+    LineStart,
+    LineEnd,
+    // Alternatives, each as a sequence of nodes.
+    Group(Vec<Vec<Node>>),
+    // Node, minimum and maximum number of repetitions.
+    Repeat(Box<Node>, usize, Option<usize>),
+}
+
+/// A pattern to look for in some text.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::utils::search::Pattern;
+/// let pattern = Pattern::regex(r"\d+", true).unwrap();
+/// assert_eq!(pattern.find_all("a1 b22 c333"), vec![1..2, 4..6, 8..11]);
+///
+/// let pattern = Pattern::literal("ab", false);
+/// assert_eq!(pattern.find("xxAByy", 0), Some(2..4));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    alternatives: Vec<Vec<Node>>,
+    program: Vec<Inst>,
+    case_sensitive: bool,
+}
+
+impl Pattern {
+    /// Creates a pattern matching the given text exactly.
+    pub fn literal(text: &str, case_sensitive: bool) -> Self {
+        let alternatives = vec![text.chars().map(Node::Char).collect()];
+        Pattern {
+            program: Compiler::compile(&alternatives),
+            alternatives,
+            case_sensitive,
+        }
+    }
+
+    /// Parses a regular expression.
+    pub fn regex(
+        expression: &str,
+        case_sensitive: bool,
+    ) -> Result<Self, Error> {
+        let chars: Vec<char> = expression.chars().collect();
+        let mut parser = Parser {
+            chars: &chars,
+            i: 0,
+        };
+        let alternatives = parser.alternatives()?;
+
+        if parser.i < chars.len() {
+            return Err(Error::Unopened(parser.i));
+        }
+
+        Ok(Pattern {
+            program: Compiler::compile(&alternatives),
+            alternatives,
+            case_sensitive,
+        })
+    }
+
+    /// Returns `true` if this pattern can only match empty strings.
+    pub fn is_empty(&self) -> bool {
+        self.alternatives.iter().all(Vec::is_empty)
+    }
+
+    /// Returns the first match starting at or after the byte offset `start`.
+    ///
+    /// Empty matches are ignored.
+    pub fn find(&self, text: &str, start: usize) -> Option<Range<usize>> {
+        let chars: Vec<(usize, char)> = text.char_indices().collect();
+        let first = chars.iter().position(|&(b, _)| b >= start)?;
+        self.find_chars(&chars, first)
+            .map(|range| byte_range(&chars, text, range))
+    }
+
+    /// Returns all non-overlapping matches in `text`.
+    pub fn find_all(&self, text: &str) -> Vec<Range<usize>> {
+        let chars: Vec<(usize, char)> = text.char_indices().collect();
+        let mut result = Vec::new();
+        let mut start = 0;
+
+        while let Some(range) = self.find_chars(&chars, start) {
+            start = range.end;
+            result.push(byte_range(&chars, text, range));
+        }
+
+        result
+    }
+
+    // Returns the first match starting at or after the char `start`,
+    // as a range of chars.
+    fn find_chars(
+        &self, chars: &[(usize, char)], start: usize,
+    ) -> Option<Range<usize>> {
+        let matcher = Matcher {
+            program: &self.program,
+            chars,
+            case_sensitive: self.case_sensitive,
+        };
+        matcher.find(start)
+    }
+}
+
+// Converts a range of chars into a range of bytes.
+fn byte_range(
+    chars: &[(usize, char)], text: &str, range: Range<usize>,
+) -> Range<usize> {
+    let byte = |i: usize| chars.get(i).map_or(text.len(), |&(b, _)| b);
+    byte(range.start)..byte(range.end)
+}
+
+struct Parser<'a> {
+    chars: &'a [char],
+    i: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.i).cloned()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.i += 1;
+        c
+    }
+
+    // Parses alternatives, until the end or a `)`.
+    fn alternatives(&mut self) -> Result<Vec<Vec<Node>>, Error> {
+        let mut alternatives = vec![self.sequence()?];
+        while self.peek() == Some('|') {
+            self.i += 1;
+            alternatives.push(self.sequence()?);
+        }
+        Ok(alternatives)
+    }
+
+    fn sequence(&mut self) -> Result<Vec<Node>, Error> {
+        let mut nodes = Vec::new();
+
+        while let Some(c) = self.peek() {
+            let node = match c {
+                '|' | ')' => break,
+                '*' | '+' | '?' => {
+                    let node =
+                        nodes.pop().ok_or(Error::NothingToRepeat(self.i))?;
+                    self.i += 1;
+                    let (min, max) = match c {
+                        '*' => (0, None),
+                        '+' => (1, None),
+                        _ => (0, Some(1)),
+                    };
+                    Node::Repeat(Box::new(node), min, max)
+                }
+                _ => self.atom()?,
+            };
+            nodes.push(node);
+        }
+
+        Ok(nodes)
+    }
+
+    fn atom(&mut self) -> Result<Node, Error> {
+        let start = self.i;
+        Ok(match self.next().unwrap() {
+            '.' => Node::Any,
+            '^' => Node::LineStart,
+            '$' => Node::LineEnd,
+            '(' => {
+                let alternatives = self.alternatives()?;
+                if self.next() != Some(')') {
+                    return Err(Error::Unclosed(start));
+                }
+                Node::Group(alternatives)
+            }
+            '[' => self.class(start)?,
+            '\\' => self.escape()?,
+            c => Node::Char(c),
+        })
+    }
+
+    fn escape(&mut self) -> Result<Node, Error> {
+        let c = self.next().ok_or(Error::TrailingEscape)?;
+        Ok(match c {
+            'd' | 'D' => Node::Class(vec![('0', '9')], c == 'D'),
+            'w' | 'W' => Node::Class(word_ranges(), c == 'W'),
+            's' | 'S' => Node::Class(space_ranges(), c == 'S'),
+            'n' => Node::Char('\n'),
+            't' => Node::Char('\t'),
+            c => Node::Char(c),
+        })
+    }
+
+    fn class(&mut self, start: usize) -> Result<Node, Error> {
+        let negated = self.peek() == Some('^');
+        if negated {
+            self.i += 1;
+        }
+
+        let mut ranges = Vec::new();
+        loop {
+            let c = match self.next() {
+                None => return Err(Error::Unclosed(start)),
+                // A `]` right at the start is a literal.
+                Some(']') if !ranges.is_empty() => break,
+                Some('\\') => self.next().ok_or(Error::TrailingEscape)?,
+                Some(c) => c,
+            };
+
+            if self.peek() == Some('-')
+                && self.chars.get(self.i + 1).filter(|&&c| c != ']').is_some()
+            {
+                self.i += 1;
+                let end = self.next().unwrap();
+                ranges.push((c, end));
+            } else {
+                ranges.push((c, c));
+            }
+        }
+
+        Ok(Node::Class(ranges, negated))
+    }
+}
+
+fn word_ranges() -> Vec<(char, char)> {
+    vec![('a', 'z'), ('A', 'Z'), ('0', '9'), ('_', '_')]
+}
+
+fn space_ranges() -> Vec<(char, char)> {
+    vec![(' ', ' '), ('\t', '\r')]
+}
+
+// An instruction of a compiled pattern.
+#[derive(Debug, Clone)]
+enum Inst {
+    // Consumes a character matching a `Char`, `Any` or `Class` node.
+    Consume(Node),
+    LineStart,
+    LineEnd,
+    // Continues at both places, preferring the first one.
+    Split(usize, usize),
+    Jump(usize),
+    Match,
+}
+
+// Turns the nodes into a program, run by `Matcher`.
+#[derive(Default)]
+struct Compiler {
+    program: Vec<Inst>,
+}
+
+impl Compiler {
+    fn compile(alternatives: &[Vec<Node>]) -> Vec<Inst> {
+        let mut compiler = Compiler::default();
+        compiler.alternatives(alternatives);
+        compiler.push(Inst::Match);
+        compiler.program
+    }
+
+    fn push(&mut self, inst: Inst) -> usize {
+        self.program.push(inst);
+        self.program.len() - 1
+    }
+
+    fn alternatives(&mut self, alternatives: &[Vec<Node>]) {
+        let mut jumps = Vec::new();
+        for (n, alternative) in alternatives.iter().enumerate() {
+            if n + 1 == alternatives.len() {
+                self.sequence(alternative);
+                break;
+            }
+            let split = self.push(Inst::Split(0, 0));
+            self.sequence(alternative);
+            jumps.push(self.push(Inst::Jump(0)));
+            self.program[split] = Inst::Split(split + 1, self.program.len());
+        }
+
+        let end = self.program.len();
+        for jump in jumps {
+            self.program[jump] = Inst::Jump(end);
+        }
+    }
+
+    fn sequence(&mut self, nodes: &[Node]) {
+        for node in nodes {
+            self.node(node);
+        }
+    }
+
+    fn node(&mut self, node: &Node) {
+        match *node {
+            Node::Group(ref alternatives) => self.alternatives(alternatives),
+            Node::LineStart => {
+                self.push(Inst::LineStart);
+            }
+            Node::LineEnd => {
+                self.push(Inst::LineEnd);
+            }
+            Node::Repeat(ref node, min, max) => {
+                for _ in 0..min {
+                    self.node(node);
+                }
+                if let Some(max) = max {
+                    for _ in min..max {
+                        self.optional(node);
+                    }
+                } else {
+                    let split = self.optional(node);
+                    self.push(Inst::Jump(split));
+                    self.program[split] =
+                        Inst::Split(split + 1, self.program.len());
+                }
+            }
+            ref node => {
+                self.push(Inst::Consume(node.clone()));
+            }
+        }
+    }
+
+    // Compiles `node` as optional, and returns the position of the split.
+    fn optional(&mut self, node: &Node) -> usize {
+        let split = self.push(Inst::Split(0, 0));
+        self.node(node);
+        self.program[split] = Inst::Split(split + 1, self.program.len());
+        split
+    }
+}
+
+// Threads of the matcher: positions in the program, with the start of
+// their match.
+struct Threads {
+    list: Vec<(usize, usize)>,
+    seen: Vec<bool>,
+    marked: Vec<usize>,
+}
+
+impl Threads {
+    fn new(len: usize) -> Self {
+        Threads {
+            list: Vec::new(),
+            seen: vec![false; len],
+            marked: Vec::new(),
+        }
+    }
+
+    // Returns `false` if `pc` was already visited in this step.
+    fn visit(&mut self, pc: usize) -> bool {
+        if self.seen[pc] {
+            return false;
+        }
+        self.seen[pc] = true;
+        self.marked.push(pc);
+        true
+    }
+
+    fn clear(&mut self) {
+        for pc in self.marked.drain(..) {
+            self.seen[pc] = false;
+        }
+        self.list.clear();
+    }
+}
+
+// Runs the program on all threads at once, in time proportional to the
+// length of the text times the length of the program.
+struct Matcher<'a> {
+    program: &'a [Inst],
+    chars: &'a [(usize, char)],
+    case_sensitive: bool,
+}
+
+impl<'a> Matcher<'a> {
+    // Returns the leftmost, then longest, non-empty match starting at or
+    // after `start`.
+    fn find(&self, start: usize) -> Option<Range<usize>> {
+        let mut current = Threads::new(self.program.len());
+        let mut next = Threads::new(self.program.len());
+        let mut best: Option<Range<usize>> = None;
+
+        for i in start..=self.chars.len() {
+            // Later matches cannot beat the one already found.
+            if best.is_none() {
+                self.add(&mut current, 0, i, i);
+            } else if current.list.is_empty() {
+                break;
+            }
+
+            for &(pc, from) in &current.list {
+                if best.as_ref().filter(|best| best.start < from).is_some() {
+                    continue;
+                }
+                match self.program[pc] {
+                    Inst::Match if i > from => {
+                        let better = match best {
+                            Some(ref b) => from < b.start || i > b.end,
+                            None => true,
+                        };
+                        if better {
+                            best = Some(from..i);
+                        }
+                    }
+                    Inst::Consume(ref node)
+                        if i < self.chars.len()
+                            && self.matches(node, self.chars[i].1) =>
+                    {
+                        self.add(&mut next, pc + 1, i + 1, from);
+                    }
+                    _ => (),
+                }
+            }
+
+            std::mem::swap(&mut current, &mut next);
+            next.clear();
+        }
+
+        best
+    }
+
+    // Adds a thread at `pc`, following jumps and assertions at `i`.
+    fn add(&self, threads: &mut Threads, pc: usize, i: usize, from: usize) {
+        if !threads.visit(pc) {
+            // Threads added first started earlier, and win.
+            return;
+        }
+
+        match self.program[pc] {
+            Inst::Jump(to) => self.add(threads, to, i, from),
+            Inst::Split(first, second) => {
+                self.add(threads, first, i, from);
+                self.add(threads, second, i, from);
+            }
+            Inst::LineStart => {
+                if i == 0 || self.chars[i - 1].1 == '\n' {
+                    self.add(threads, pc + 1, i, from);
+                }
+            }
+            Inst::LineEnd => {
+                if i == self.chars.len() || self.chars[i].1 == '\n' {
+                    self.add(threads, pc + 1, i, from);
+                }
+            }
+            Inst::Consume(_) | Inst::Match => threads.list.push((pc, from)),
+        }
+    }
+
+    fn matches(&self, node: &Node, c: char) -> bool {
+        match *node {
+            Node::Char(expected) => {
+                c == expected
+                    || (!self.case_sensitive && same_letter(c, expected))
+            }
+            Node::Any => c != '\n',
+            Node::Class(ref ranges, negated) => {
+                let in_ranges = |c: char| {
+                    ranges.iter().any(|&(start, end)| start <= c && c <= end)
+                };
+                let found = in_ranges(c)
+                    || (!self.case_sensitive
+                        && (c.to_lowercase().any(in_ranges)
+                            || c.to_uppercase().any(in_ranges)));
+                found != negated
+            }
+            _ => false,
+        }
+    }
+}
+
+fn same_letter(a: char, b: char) -> bool {
+    a.to_lowercase().eq(b.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn regex() {
+        let find = |expression: &str, text: &str| {
+            Pattern::regex(expression, true).unwrap().find_all(text)
+        };
+
+        assert_eq!(find("a.c", "abc a-c ac"), vec![0..3, 4..7]);
+        assert_eq!(find("colou?r", "color colour"), vec![0..5, 6..12]);
+        assert_eq!(find("(cat|dog)s", "cats dogs"), vec![0..4, 5..9]);
+        assert_eq!(find("^[a-c]+$", "abc\nabd\ncab"), vec![0..3, 8..11]);
+        assert_eq!(find(r"\w+@\w+", "me@home, you"), vec![0..7]);
+        assert_eq!(find("x*", "aaa"), vec![]);
+        assert_eq!(find("b|abcd", "abcd"), vec![0..4]);
+        assert_eq!(find("a(b|bc)d?", "abcd abd"), vec![0..4, 5..8]);
+
+        let pattern = Pattern::regex("[a-z]+", false).unwrap();
+        assert_eq!(pattern.find_all("Hé HO"), vec![0..1, 4..6]);
+
+        assert_eq!(Pattern::regex("(a", true).err(), Some(Error::Unclosed(0)));
+        assert_eq!(Pattern::regex("a)", true).err(), Some(Error::Unopened(1)));
+        assert_eq!(
+            Pattern::regex("*a", true).err(),
+            Some(Error::NothingToRepeat(0))
+        );
+    }
+
+    #[test]
+    fn no_backtracking() {
+        // Each position is only tried once per state.
+        let text = "a".repeat(5000);
+        let pattern = Pattern::regex("(a|a)*b", true).unwrap();
+        assert_eq!(pattern.find_all(&text), vec![]);
+        let pattern = Pattern::regex("(a*)*$", true).unwrap();
+        assert_eq!(pattern.find_all(&text), vec![0..5000]);
+    }
+}
@@ -2,8 +2,8 @@
 //!
 //! This module defines various structs describing a span of text from a
 //! larger string.
+use crate::utils::width::TextWidth;
 use std::borrow::Cow;
-use unicode_width::UnicodeWidthStr;
 
 /// A string with associated spans.
 ///
@@ -0,0 +1,133 @@
+//! Clock used by the event loop and the views.
+//!
+//! `std::time::Instant` panics on `wasm32-unknown-unknown`, where the
+//! standard library has no clock. On this target, the page gives the time
+//! instead, with `web::Frontend::set_time`; until then, time stands still.
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub(crate) use std::time::Instant;
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+pub(crate) use self::manual::Instant;
+
+/// Sets the current time, as a duration since an arbitrary origin.
+///
+/// Only used on targets without a clock: this does nothing elsewhere.
+#[cfg(feature = "web-backend")]
+#[allow(unused_variables)]
+pub(crate) fn set_now(now: std::time::Duration) {
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    manual::set_now(now);
+}
+
+#[cfg(any(test, all(target_arch = "wasm32", target_os = "unknown")))]
+#[cfg_attr(test, allow(dead_code))]
+mod manual {
+    use std::ops::{Add, AddAssign, Sub, SubAssign};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::Duration;
+
+    // Nanoseconds since the origin chosen by the page.
+    static NOW: AtomicU64 = AtomicU64::new(0);
+
+    // Time never goes back, even if the page gives an earlier time.
+    #[cfg(any(test, feature = "web-backend"))]
+    pub fn set_now(now: Duration) {
+        let nanos = now.as_nanos().min(u128::from(u64::MAX)) as u64;
+        NOW.fetch_max(nanos, Ordering::Relaxed);
+    }
+
+    /// Replaces `std::time::Instant`, with the methods cursive uses.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct Instant(Duration);
+
+    impl Instant {
+        pub fn now() -> Self {
+            Instant(Duration::from_nanos(NOW.load(Ordering::Relaxed)))
+        }
+
+        pub fn duration_since(&self, earlier: Instant) -> Duration {
+            self.saturating_duration_since(earlier)
+        }
+
+        pub fn checked_duration_since(
+            &self, earlier: Instant,
+        ) -> Option<Duration> {
+            self.0.checked_sub(earlier.0)
+        }
+
+        pub fn saturating_duration_since(&self, earlier: Instant) -> Duration {
+            self.checked_duration_since(earlier).unwrap_or_default()
+        }
+
+        pub fn elapsed(&self) -> Duration {
+            Instant::now().duration_since(*self)
+        }
+    }
+
+    impl Add<Duration> for Instant {
+        type Output = Instant;
+
+        fn add(self, duration: Duration) -> Instant {
+            Instant(self.0 + duration)
+        }
+    }
+
+    impl AddAssign<Duration> for Instant {
+        fn add_assign(&mut self, duration: Duration) {
+            self.0 += duration;
+        }
+    }
+
+    // The origin is arbitrary: going before it saturates, instead of
+    // panicking like `std::time::Instant` would.
+    impl Sub<Duration> for Instant {
+        type Output = Instant;
+
+        fn sub(self, duration: Duration) -> Instant {
+            Instant(self.0.checked_sub(duration).unwrap_or_default())
+        }
+    }
+
+    impl SubAssign<Duration> for Instant {
+        fn sub_assign(&mut self, duration: Duration) {
+            *self = *self - duration;
+        }
+    }
+
+    impl Sub<Instant> for Instant {
+        type Output = Duration;
+
+        fn sub(self, earlier: Instant) -> Duration {
+            self.duration_since(earlier)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn manual_clock() {
+            set_now(Duration::from_millis(1500));
+            let start = Instant::now();
+            assert_eq!(start.elapsed(), Duration::from_secs(0));
+
+            set_now(Duration::from_millis(2000));
+            assert_eq!(start.elapsed(), Duration::from_millis(500));
+            assert_eq!(Instant::now() - start, Duration::from_millis(500));
+            assert_eq!(start - Instant::now(), Duration::from_secs(0));
+
+            // Going back in time is ignored.
+            set_now(Duration::from_millis(1000));
+            assert_eq!(start.elapsed(), Duration::from_millis(500));
+
+            let later = start + Duration::from_secs(1);
+            assert!(later > Instant::now());
+            let remaining = later.checked_duration_since(Instant::now());
+            assert_eq!(remaining, Some(Duration::from_millis(500)));
+            let origin = Instant(Duration::from_secs(0));
+            assert_eq!(start - Duration::from_secs(10), origin);
+        }
+    }
+}
@@ -0,0 +1,124 @@
+//! Width of text on the screen.
+//!
+//! Terminals draw each grapheme cluster (what a user sees as one character)
+//! in one or two cells. Adding the width of each `char` is wrong for most
+//! emoji: `"❤\u{FE0F}"` (a heart and a presentation selector) takes two
+//! cells, and `"👨\u{200D}👩\u{200D}👧"` (three faces joined with zero-width
+//! joiners) takes two cells instead of six.
+//!
+//! Cursive measures all text with [`TextWidth::width`], which uses the
+//! grapheme clusters.
+//!
+//! [`TextWidth::width`]: trait.TextWidth.html#tymethod.width
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthChar;
+
+// Asks for the emoji presentation of the previous character.
+const EMOJI_PRESENTATION: char = '\u{FE0F}';
+
+/// Returns the number of cells taken by a single grapheme cluster.
+///
+/// Emoji sequences take two cells, and combining characters none.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::utils::width::grapheme_width;
+/// assert_eq!(grapheme_width("a"), 1);
+/// assert_eq!(grapheme_width("e\u{301}"), 1);
+/// assert_eq!(grapheme_width("❤\u{FE0F}"), 2);
+/// assert_eq!(grapheme_width("🇫🇷"), 2);
+/// ```
+pub fn grapheme_width(grapheme: &str) -> usize {
+    let mut chars = grapheme.chars();
+    let first = match chars.next() {
+        Some(c) => c,
+        None => return 0,
+    };
+    let width = first.width().unwrap_or(0);
+    if chars.as_str().is_empty() {
+        return width;
+    }
+
+    // The following characters are joined to the first one.
+    if grapheme.contains(EMOJI_PRESENTATION) || is_regional_indicator(first)
+    {
+        2
+    } else {
+        width
+    }
+}
+
+/// Returns `true` if terminals may not agree on the width of `grapheme`.
+///
+/// Some terminals add the width of each `char` instead of using the whole
+/// cluster: printing these graphemes alone keeps the rest of the row in
+/// place.
+pub(crate) fn is_ambiguous(grapheme: &str) -> bool {
+    let char_widths: usize =
+        grapheme.chars().map(|c| c.width().unwrap_or(0)).sum();
+    !grapheme.is_ascii() && char_widths != grapheme_width(grapheme)
+}
+
+// Two of these make a flag.
+fn is_regional_indicator(c: char) -> bool {
+    ('\u{1F1E6}'..='\u{1F1FF}').contains(&c)
+}
+
+/// Measures the width of text on the screen.
+pub trait TextWidth {
+    /// Returns the number of cells taken by this text.
+    fn width(&self) -> usize;
+}
+
+impl TextWidth for str {
+    fn width(&self) -> usize {
+        if self.is_ascii() {
+            return self.bytes().filter(|b| !b.is_ascii_control()).count();
+        }
+        self.graphemes(true).map(grapheme_width).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_ambiguous, TextWidth};
+
+    #[test]
+    fn emoji_width() {
+        assert_eq!("Hello".width(), 5);
+        assert_eq!("a\tb".width(), 2);
+        assert_eq!("日本".width(), 4);
+        assert_eq!("👨\u{200D}👩\u{200D}👧 ok".width(), 5);
+        assert_eq!("cafe\u{301}".width(), 4);
+
+        assert!(is_ambiguous("❤\u{FE0F}"));
+        assert!(is_ambiguous("👨\u{200D}👩\u{200D}👧"));
+        assert!(!is_ambiguous("e\u{301}"));
+        assert!(!is_ambiguous("日"));
+    }
+
+    #[test]
+    fn print_wide_graphemes() {
+        use crate::backend::puppet;
+        use crate::theme::Theme;
+        use crate::Printer;
+
+        let (mut backend, puppet) = puppet::Backend::init((5, 2));
+        let theme = Theme::default();
+        let printer = Printer::new((5, 2), &theme, &*backend);
+        printer.print((0, 0), "xxxxx");
+        printer.print((0, 1), "xxxxx");
+
+        // The last wide character does not fit, and is replaced by a space.
+        printer.print((0, 0), "a❤\u{FE0F}b日");
+        // The first one is cut on the left.
+        printer.content_offset((1, 0)).print((0, 1), "日👨\u{200D}👧c");
+        backend.refresh();
+
+        let screen = puppet.screen();
+        assert_eq!(screen.row(0).unwrap(), "a❤\u{FE0F}b ");
+        assert_eq!(screen.row(1).unwrap(), " 👨\u{200D}👧cx");
+    }
+}
@@ -28,6 +28,16 @@ pub trait Boxable: View + Sized {
         BoxView::with_fixed_height(height, self)
     }
 
+    /// Wraps `self` into a `BoxView` taking a percentage of the width.
+    fn percent_width(self, percent: f32) -> BoxView<Self> {
+        BoxView::with_percent_width(percent, self)
+    }
+
+    /// Wraps `self` into a `BoxView` taking a percentage of the height.
+    fn percent_height(self, percent: f32) -> BoxView<Self> {
+        BoxView::with_percent_height(percent, self)
+    }
+
     /// Wraps `self` into a full-screen `BoxView`.
     fn full_screen(self) -> BoxView<Self> {
         BoxView::with_full_screen(self)
@@ -1,6 +1,8 @@
 use crate::view::{View, ViewPath, ViewWrapper};
 use crate::views::{IdView, ViewRef};
 use std::any::Any;
+use std::error;
+use std::fmt;
 
 /// Provides `call_on<V: View>` to views.
 ///
@@ -41,6 +43,40 @@ pub trait Finder {
     {
         self.call_on_id(id, IdView::<V>::get_mut)
     }
+
+    /// Runs a callback on every view matching `sel`.
+    ///
+    /// Views that are not of the asked type are skipped. This includes
+    /// views wrapped in an `IdView<V>`.
+    fn call_on_selected<V, F>(&mut self, sel: &Selector<'_>, callback: F)
+    where
+        V: View + Any,
+        F: FnMut(&mut V);
+
+    /// Runs a callback on every view of type `V`.
+    ///
+    /// Convenient method to use `call_on_selected` with a
+    /// `view::Selector::All`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::views::{LinearLayout, TextView};
+    /// use cursive::view::Finder;
+    ///
+    /// let mut layout = LinearLayout::vertical()
+    ///     .child(TextView::new("foo"))
+    ///     .child(TextView::new("bar"));
+    ///
+    /// layout.call_on_all(|view: &mut TextView| view.set_content("baz"));
+    /// ```
+    fn call_on_all<V, F>(&mut self, callback: F)
+    where
+        V: View + Any,
+        F: FnMut(&mut V),
+    {
+        self.call_on_selected(&Selector::All, callback)
+    }
 }
 
 impl<T: View> Finder for T {
@@ -72,12 +108,110 @@ impl<T: View> Finder for T {
         }
         result
     }
+
+    fn call_on_selected<V, F>(&mut self, sel: &Selector<'_>, mut callback: F)
+    where
+        V: View + Any,
+        F: FnMut(&mut V),
+    {
+        let callback = |v: &mut dyn Any| {
+            if let Some(v) = v.downcast_mut::<V>() {
+                callback(v);
+            } else if let Selector::All = *sel {
+                // The view inside an `IdView` will be visited on its own.
+            } else if let Some(v) = v.downcast_mut::<IdView<V>>() {
+                v.with_view_mut(&mut callback);
+            }
+        };
+        self.call_on_any(sel, Box::new(callback));
+    }
 }
 
-/// Selects a single view (if any) in the tree.
+/// Selects views in the tree.
+///
+/// Most selectors match a single view (if any). `IdPrefix`, `AnyIdPrefix`
+/// and `All` can match many, see [`Finder::call_on_selected`].
+///
+/// [`Finder::call_on_selected`]: trait.Finder.html#method.call_on_selected
 pub enum Selector<'a> {
     /// Selects a view from its ID.
     Id(&'a str),
     /// Selects a view from its path.
     Path(&'a ViewPath),
+    /// Selects every view with an ID starting with the given prefix.
+    IdPrefix(&'a str),
+    /// Selects every `IdView` with an ID starting with the given prefix,
+    /// whatever the type of its view.
+    ///
+    /// The callback receives an [`AnyIdView`].
+    ///
+    /// [`AnyIdView`]: ../views/struct.AnyIdView.html
+    AnyIdPrefix(&'a str),
+    /// Selects every view in the tree.
+    All,
+}
+
+impl Selector<'_> {
+    /// Runs `callback` on `view` if this is `Selector::All`.
+    ///
+    /// Views implementing `View::call_on_any` use this to run the callback
+    /// on themselves, before forwarding it to their children.
+    pub fn call_on_self(
+        &self, view: &mut dyn Any, callback: &mut dyn FnMut(&mut dyn Any),
+    ) {
+        if let Selector::All = *self {
+            callback(view);
+        }
+    }
+}
+
+/// Error returned when no view matches a selector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ViewNotFound;
+
+impl fmt::Display for ViewNotFound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no view matches the selector")
+    }
+}
+
+impl error::Error for ViewNotFound {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view::Identifiable;
+    use crate::views::{LinearLayout, Panel, TextView};
+
+    #[test]
+    fn call_on_selected() {
+        let mut layout = LinearLayout::vertical()
+            .child(TextView::new("a").with_id("status-a"))
+            .child(Panel::new(TextView::new("b").with_id("status-b")))
+            .child(TextView::new("c").with_id("other"));
+
+        let mut count = 0;
+        layout.call_on_all(|_: &mut TextView| count += 1);
+        assert_eq!(count, 3);
+
+        let mut count = 0;
+        layout.call_on_all(|_: &mut Panel<IdView<TextView>>| count += 1);
+        assert_eq!(count, 1);
+
+        let mut ids = 0;
+        layout.call_on_selected(
+            &Selector::IdPrefix("status-"),
+            |view: &mut TextView| {
+                view.set_content("ok");
+                ids += 1;
+            },
+        );
+        assert_eq!(ids, 2);
+        assert_eq!(
+            layout.call_on_id("other", |v: &mut TextView| {
+                v.get_content().source().to_string()
+            }),
+            Some("c".to_string())
+        );
+    }
 }
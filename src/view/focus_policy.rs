@@ -0,0 +1,68 @@
+use crate::direction::Direction;
+use crate::event::{Event, EventResult, Key};
+use crate::view::View;
+
+/// Defines what happens when focus reaches the edge of a container.
+///
+/// When the last child of a container is focused and `Tab` is pressed (or
+/// the first one with `Shift+Tab`), the container can let its parent move
+/// the focus elsewhere, bring the focus back to the other side, or keep it
+/// where it is.
+///
+/// Views report their policy through [`View::focus_policy`]. Containers
+/// like `LinearLayout`, `ListView` and `Dialog` apply the policy of their
+/// focused child when it ignores `Tab` or `Shift+Tab`, before moving the
+/// focus to another child.
+///
+/// [`View::focus_policy`]: trait.View.html#method.focus_policy
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FocusPolicy {
+    /// The container ignores the event, and the parent can move the focus.
+    ///
+    /// This is the default.
+    Escape,
+    /// The focus comes back to the other side of the container.
+    Wrap,
+    /// The focus stays on the current child.
+    Stop,
+}
+
+impl FocusPolicy {
+    /// Applies this policy to `view`, when the focus would leave it.
+    ///
+    /// `source` is where the focus comes from when it wraps around.
+    pub(crate) fn apply<V>(
+        self, view: &mut V, source: Direction,
+    ) -> EventResult
+    where
+        V: View + ?Sized,
+    {
+        match self {
+            FocusPolicy::Escape => EventResult::Ignored,
+            FocusPolicy::Stop => EventResult::Consumed(None),
+            FocusPolicy::Wrap => {
+                if view.take_focus(source) {
+                    EventResult::Consumed(None)
+                } else {
+                    EventResult::Ignored
+                }
+            }
+        }
+    }
+}
+
+/// Applies the policy of `child`, which ignored `event`.
+///
+/// Arrow keys depend on the orientation of the child, so only `Tab` and
+/// `Shift+Tab` are handled here.
+pub(crate) fn on_child_edge<V>(child: &mut V, event: &Event) -> EventResult
+where
+    V: View + ?Sized,
+{
+    let source = match *event {
+        Event::Key(Key::Tab) => Direction::front(),
+        Event::Shift(Key::Tab) => Direction::back(),
+        _ => return EventResult::Ignored,
+    };
+    child.focus_policy().apply(child, source)
+}
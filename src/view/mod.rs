@@ -37,10 +37,13 @@
 
 #[macro_use]
 mod view_wrapper;
+#[macro_use]
+mod ui_macro;
 
 // Essentials components
 mod any;
 mod finder;
+mod focus_policy;
 mod margins;
 mod position;
 mod size_cache;
@@ -65,12 +68,17 @@ mod into_boxed_view;
 
 pub use self::any::AnyView;
 pub use self::boxable::Boxable;
-pub use self::finder::{Finder, Selector};
+pub use self::finder::{Finder, Selector, ViewNotFound};
+pub use self::focus_policy::FocusPolicy;
+pub(crate) use self::focus_policy::on_child_edge;
 pub use self::identifiable::Identifiable;
 pub use self::into_boxed_view::IntoBoxedView;
 pub use self::margins::Margins;
 pub use self::position::{Offset, Position};
-pub use self::scroll::ScrollStrategy;
+pub use self::scroll::{
+    ScrollAction, ScrollKeys, ScrollStrategy, ScrollbarPolicy,
+    ScrollbarPosition, ScrollbarStyle,
+};
 pub use self::scroll_base::ScrollBase;
 pub use self::scrollable::Scrollable;
 pub use self::size_cache::SizeCache;
@@ -1,12 +1,20 @@
-use std::cmp::min;
+use std::cmp::{max, min};
+use std::rc::Rc;
+use std::time::Duration;
 
 use crate::direction::Orientation;
-use crate::event::{AnyCb, Event, EventResult, Key, MouseButton, MouseEvent};
+use crate::div::div_up;
+use crate::event::{AnyCb, Event, EventResult, MouseButton, MouseEvent};
 use crate::printer::Printer;
 use crate::rect::Rect;
 use crate::theme::ColorStyle;
+use crate::utils::time::Instant;
 use crate::vec::Vec2;
-use crate::view::{ScrollStrategy, Selector, SizeCache};
+use crate::view::scroll::{ScrollAction, ScrollKeys};
+use crate::view::{
+    ScrollStrategy, ScrollbarPolicy, ScrollbarPosition, ScrollbarStyle,
+    Selector, SizeCache,
+};
 use crate::with::With;
 use crate::XY;
 
@@ -45,14 +53,29 @@ pub struct Core {
     /// Are we scrollable in each direction?
     enabled: XY<bool>,
 
-    /// Should we show scrollbars?
+    /// When should we show scrollbars?
     ///
-    /// Even if this is true, no scrollbar will be printed if we don't need to
-    /// scroll.
+    /// `scrollbar_policy.x` applies to the horizontal scrollbar.
+    scrollbar_policy: XY<ScrollbarPolicy>,
+
+    /// On which side of the content should we draw scrollbars?
+    ///
+    /// `scrollbar_position.x` applies to the horizontal scrollbar.
+    scrollbar_position: XY<ScrollbarPosition>,
+
+    /// If set, scrollbars are hidden after this long without scrolling.
     ///
-    /// TODO: have an option to always show the scrollbar.
-    /// TODO: have an option to show scrollbar on top/left.
-    show_scrollbars: bool,
+    /// Their space is still reserved, so the content doesn't move around.
+    auto_hide: Option<Duration>,
+
+    /// When we last scrolled.
+    last_scroll: Option<Instant>,
+
+    /// Should large jumps be animated over a few frames?
+    smooth_scrolling: bool,
+
+    /// Offset we are moving towards, when animating.
+    scroll_target: Option<Vec2>,
 
     /// How much padding should be between content and scrollbar?
     ///
@@ -67,6 +90,21 @@ pub struct Core {
 
     /// Defines how to update the offset when the view size changes.
     scroll_strategy: ScrollStrategy,
+
+    /// If `true`, stay at the bottom when the content grows.
+    follow_bottom: bool,
+
+    /// Whether the viewport was at the bottom before the last layout.
+    was_at_bottom: bool,
+
+    /// `true` while the content handles a mouse drag.
+    content_grab: bool,
+
+    /// Glyphs, colors and width of the scrollbars.
+    scrollbar_style: ScrollbarStyle,
+
+    /// Keys used to scroll.
+    scroll_keys: Rc<ScrollKeys>,
 }
 
 impl Default for Core {
@@ -83,11 +121,21 @@ impl Core {
             offset: Vec2::zero(),
             last_size: Vec2::zero(),
             enabled: XY::new(false, true),
-            show_scrollbars: true,
+            scrollbar_policy: XY::both_from(ScrollbarPolicy::Auto),
+            scrollbar_position: XY::both_from(ScrollbarPosition::End),
+            auto_hide: None,
+            last_scroll: None,
+            smooth_scrolling: false,
+            scroll_target: None,
             scrollbar_padding: Vec2::new(1, 0),
             thumb_grab: None,
             size_cache: None,
             scroll_strategy: ScrollStrategy::KeepRow,
+            follow_bottom: false,
+            was_at_bottom: true,
+            content_grab: false,
+            scrollbar_style: ScrollbarStyle::default(),
+            scroll_keys: ScrollKeys::get_default(),
         }
     }
 
@@ -96,60 +144,93 @@ impl Core {
         &self, printer: &Printer<'a, 'b>,
     ) -> Printer<'a, 'b> {
         // Draw scrollbar?
-        let scrolling = self.is_scrolling();
+        let scrolling = self.scrollbars_shown();
+        let visible = if self.scrollbars_hidden() {
+            XY::new(false, false)
+        } else {
+            scrolling
+        };
 
         let lengths = self.scrollbar_thumb_lengths();
         let offsets = self.scrollbar_thumb_offsets(lengths);
 
-        let line_c = XY::new("-", "|");
-
-        let color = if printer.focused {
+        let style = &self.scrollbar_style;
+        let color = style.thumb_color.unwrap_or(if printer.focused {
             ColorStyle::highlight()
         } else {
             ColorStyle::highlight_inactive()
-        };
+        });
 
         let size = self.available_size();
+        let origin = self.content_origin();
+        let location = self.scrollbar_location();
 
         // Draw the scrollbars
-        XY::zip5(lengths, offsets, size, line_c, Orientation::pair()).run_if(
-            scrolling,
-            |(length, offset, size, c, orientation)| {
-                let start = printer
-                    .size
-                    .saturating_sub((1, 1))
-                    .with_axis(orientation, 0);
-                let offset = orientation.make_vec(offset, 0);
-
-                printer.print_line(orientation, start, size, c);
-
-                let thumb_c = if self
+        XY::zip4(lengths, offsets, size, Orientation::pair()).run_if(
+            visible,
+            |(length, offset, size, orientation)| {
+                let track = style.track.get(orientation);
+                let thumb = if self
                     .thumb_grab
                     .map(|(o, _)| o == orientation)
                     .unwrap_or(false)
                 {
-                    " "
+                    style.grabbed_thumb.get(orientation)
                 } else {
-                    "▒"
+                    style.thumb.get(orientation)
                 };
-                printer.with_color(color, |printer| {
-                    printer.print_line(
-                        orientation,
-                        start + offset,
-                        length,
-                        thumb_c,
+
+                for i in 0..style.width {
+                    // The scrollbar runs along the content, at the location
+                    // row (or column).
+                    let start = origin.with_axis(
+                        orientation.swap(),
+                        location.get(orientation.swap()) + i,
                     );
-                });
+                    let offset = orientation.make_vec(offset, 0);
+
+                    match style.track_color {
+                        Some(track_color) => {
+                            printer.with_color(track_color, |printer| {
+                                printer.print_line(
+                                    orientation,
+                                    start,
+                                    size,
+                                    track,
+                                );
+                            })
+                        }
+                        None => {
+                            printer.print_line(orientation, start, size, track)
+                        }
+                    }
+
+                    printer.with_color(color, |printer| {
+                        printer.print_line(
+                            orientation,
+                            start + offset,
+                            length,
+                            thumb,
+                        );
+                    });
+                }
             },
         );
 
         // Draw the X between the two scrollbars.
-        if scrolling.both() {
-            printer.print(printer.size.saturating_sub((1, 1)), "╳");
+        if visible.both() {
+            for y in 0..style.width {
+                printer.print_hline(
+                    location + (0, y),
+                    style.width,
+                    &style.corner,
+                );
+            }
         }
 
         // Draw content
         printer
+            .offset(origin)
             .cropped(size)
             .content_offset(self.offset)
             .inner_size(self.inner_size)
@@ -158,35 +239,77 @@ impl Core {
     /// Returns `true` if `event` should be processed by the content.
     ///
     /// This also updates `event` so that it is relative to the content.
+    ///
+    /// While the content handles a mouse drag, `Hold` and `Release` events
+    /// are always given to the content, even outside of the viewport.
     pub fn is_event_inside(&self, event: &mut Event) -> bool {
         if let Event::Mouse {
             ref mut position,
-            ref offset,
-            ..
+            ref mut offset,
+            event,
         } = event
         {
+            // The content may not start at our top-left corner.
+            *offset = *offset + self.content_origin();
+
             // For mouse events, check if it falls inside the available area
             let inside = position
-                .checked_sub(offset)
+                .checked_sub(*offset)
                 .map(|p| p.fits_in(self.available_size()))
                 .unwrap_or(false);
             *position = *position + self.offset;
-            inside
+            inside || (self.content_grab && event.is_drag())
         } else {
             // For key events, assume it's inside by default.
             true
         }
     }
 
+    /// Returns `true` if the content is handling a mouse drag.
+    ///
+    /// This starts when the content consumes a mouse press, and ends with
+    /// the next release.
+    pub fn is_content_grabbed(&self) -> bool {
+        self.content_grab
+    }
+
     /// Handle an event after processing by the content.
     pub fn on_inner_event(
         &mut self, event: Event, inner_result: EventResult,
         important_area: Rect,
     ) -> EventResult {
+        if let Event::Mouse {
+            event: mouse,
+            position,
+            offset,
+        } = event
+        {
+            match mouse {
+                MouseEvent::Press(_) => {
+                    self.content_grab = inner_result.is_consumed();
+                }
+                MouseEvent::Hold(_) if self.content_grab => {
+                    self.follow_drag(position, offset);
+                }
+                MouseEvent::Release(_) => self.content_grab = false,
+                _ => (),
+            }
+        }
+
         match inner_result {
             EventResult::Ignored => {
                 // The view ignored the event, so we're free to use it.
 
+                // If we were in the middle of an animation, large jumps
+                // continue from where we were going.
+                // Anything else cancels the animation.
+                let target = self.scroll_target.take();
+                let base = target.unwrap_or(self.offset);
+                let action = self.scroll_keys.get(&event);
+                let max_offset =
+                    self.inner_size.saturating_sub(self.available_size());
+                let half_page = max(self.available_size().y / 2, 1);
+
                 // If it's an arrow, try to scroll in the given direction.
                 // If it's a mouse scroll, try to scroll as well.
                 // Also allow Ctrl+arrow to move the view,
@@ -216,7 +339,7 @@ impl Core {
                         event: MouseEvent::Press(MouseButton::Left),
                         position,
                         offset,
-                    } if self.show_scrollbars
+                    } if self.get_show_scrollbars()
                         && position
                             .checked_sub(offset)
                             .map(|position| self.start_drag(position))
@@ -228,7 +351,7 @@ impl Core {
                         event: MouseEvent::Hold(MouseButton::Left),
                         position,
                         offset,
-                    } if self.show_scrollbars => {
+                    } if self.get_show_scrollbars() => {
                         let position = position.saturating_sub(offset);
                         self.drag(position);
                     }
@@ -238,58 +361,79 @@ impl Core {
                     } => {
                         self.release_grab();
                     }
-                    Event::Key(Key::Home) if self.enabled.any() => {
-                        self.offset =
-                            self.enabled.select_or(Vec2::zero(), self.offset);
+                    _ if action == Some(ScrollAction::Top)
+                        && self.enabled.any() =>
+                    {
+                        self.jump_to(
+                            self.enabled.select_or(Vec2::zero(), base),
+                        );
                     }
-                    Event::Key(Key::End) if self.enabled.any() => {
-                        let max_offset = self
-                            .inner_size
-                            .saturating_sub(self.available_size());
-                        self.offset =
-                            self.enabled.select_or(max_offset, self.offset);
+                    _ if action == Some(ScrollAction::Bottom)
+                        && self.enabled.any() =>
+                    {
+                        self.jump_to(self.enabled.select_or(max_offset, base));
                     }
-                    Event::Ctrl(Key::Up) | Event::Key(Key::Up)
-                        if self.enabled.y && self.offset.y > 0 =>
+                    _ if action == Some(ScrollAction::LineUp)
+                        && self.enabled.y
+                        && self.offset.y > 0 =>
                     {
                         self.offset.y -= 1;
                     }
-                    Event::Key(Key::PageUp)
-                        if self.enabled.y && self.offset.y > 0 =>
+                    _ if action == Some(ScrollAction::PageUp)
+                        && self.enabled.y
+                        && base.y > 0 =>
+                    {
+                        self.jump_to(base.map_y(|y| y.saturating_sub(5)));
+                    }
+                    _ if action == Some(ScrollAction::HalfPageUp)
+                        && self.enabled.y
+                        && base.y > 0 =>
                     {
-                        self.offset.y = self.offset.y.saturating_sub(5);
+                        self.jump_to(
+                            base.map_y(|y| y.saturating_sub(half_page)),
+                        );
                     }
-                    Event::Key(Key::PageDown)
-                        if self.enabled.y
-                            && (self.offset.y + self.available_size().y
-                                < self.inner_size.y) =>
+                    _ if action == Some(ScrollAction::PageDown)
+                        && self.enabled.y
+                        && base.y < max_offset.y =>
                     {
-                        self.offset.y += 5;
+                        self.jump_to(base.map_y(|y| y + 5));
                     }
-                    Event::Ctrl(Key::Down) | Event::Key(Key::Down)
-                        if self.enabled.y
-                            && (self.offset.y + self.available_size().y
-                                < self.inner_size.y) =>
+                    _ if action == Some(ScrollAction::HalfPageDown)
+                        && self.enabled.y
+                        && base.y < max_offset.y =>
+                    {
+                        self.jump_to(
+                            base.map_y(|y| min(y + half_page, max_offset.y)),
+                        );
+                    }
+                    _ if action == Some(ScrollAction::LineDown)
+                        && self.enabled.y
+                        && self.offset.y < max_offset.y =>
                     {
                         self.offset.y += 1;
                     }
-                    Event::Ctrl(Key::Left) | Event::Key(Key::Left)
-                        if self.enabled.x && self.offset.x > 0 =>
+                    _ if action == Some(ScrollAction::ColumnLeft)
+                        && self.enabled.x
+                        && self.offset.x > 0 =>
                     {
                         self.offset.x -= 1;
                     }
-                    Event::Ctrl(Key::Right) | Event::Key(Key::Right)
-                        if self.enabled.x
-                            && (self.offset.x + self.available_size().x
-                                < self.inner_size.x) =>
+                    _ if action == Some(ScrollAction::ColumnRight)
+                        && self.enabled.x
+                        && self.offset.x < max_offset.x =>
                     {
                         self.offset.x += 1;
                     }
-                    _ => return EventResult::Ignored,
+                    _ => {
+                        self.scroll_target = target;
+                        return EventResult::Ignored;
+                    }
                 };
 
                 // We just scrolled manually, so reset the scroll strategy.
                 self.scroll_strategy = ScrollStrategy::KeepRow;
+                self.last_scroll = Some(Instant::now());
                 // TODO: return callback on_scroll?
                 EventResult::Consumed(None)
             }
@@ -310,8 +454,12 @@ impl Core {
                 let offset_min = Vec2::min(top_left, bottom_right);
                 let offset_max = Vec2::max(top_left, bottom_right);
 
-                self.offset =
-                    self.offset.or_max(offset_min).or_min(offset_max);
+                let base = self.scroll_target.unwrap_or(self.offset);
+                let offset = base.or_max(offset_min).or_min(offset_max);
+                if offset != base {
+                    self.jump_to(offset);
+                    self.last_scroll = Some(Instant::now());
+                }
 
                 other
             }
@@ -320,6 +468,8 @@ impl Core {
 
     /// Specifies the size given in a layout phase.
     pub(crate) fn set_last_size(&mut self, last_size: Vec2) {
+        // Remember where we were before the content changes.
+        self.was_at_bottom = self.is_at_bottom();
         self.last_size = last_size;
     }
 
@@ -340,20 +490,29 @@ impl Core {
 
     /// Makes sure the viewport is within the content.
     pub(crate) fn update_offset(&mut self) {
+        // Move one step further in the current animation, if any.
+        self.animate();
+
         // Keep the offset in the valid range.
-        self.offset = self
-            .offset
-            .or_min(self.inner_size.saturating_sub(self.available_size()));
+        let max_offset = self.inner_size.saturating_sub(self.available_size());
+        self.offset = self.offset.or_min(max_offset);
+        self.scroll_target = self.scroll_target.map(|t| t.or_min(max_offset));
 
         // Possibly update the offset if we're following a specific strategy.
         self.adjust_scroll();
+
+        if self.follow_bottom && self.was_at_bottom {
+            self.offset.y = max_offset.y;
+            self.scroll_target = None;
+        }
     }
 
     /// Returns `true` if we should relayout, no matter the content.
     ///
     /// Even if this returns `false`, the content itself might still needs to relayout.
     pub fn needs_relayout(&self) -> bool {
-        self.size_cache.is_none()
+        // Animations progress during the layout phase.
+        self.size_cache.is_none() || self.scroll_target.is_some()
     }
 
     /// Performs `View::call_on_any()`
@@ -400,6 +559,32 @@ impl Core {
         self.with(|s| s.set_scroll_strategy(strategy))
     }
 
+    /// Keeps the viewport at the bottom when the content grows.
+    ///
+    /// Unlike `ScrollStrategy::StickToBottom`, this is not reset when the
+    /// user scrolls: following stops while the user scrolls up, and resumes
+    /// when they scroll back to the bottom.
+    pub fn set_follow_bottom(&mut self, follow: bool) {
+        self.follow_bottom = follow;
+    }
+
+    /// Keeps the viewport at the bottom when the content grows.
+    ///
+    /// Chainable variant.
+    pub fn follow_bottom(self, follow: bool) -> Self {
+        self.with(|s| s.set_follow_bottom(follow))
+    }
+
+    /// Returns `true` if the viewport follows the bottom of the content.
+    pub fn get_follow_bottom(&self) -> bool {
+        self.follow_bottom
+    }
+
+    /// Returns `true` if the viewport shows the bottom of the content.
+    pub fn is_at_bottom(&self) -> bool {
+        self.current_target().y + self.available_size().y >= self.inner_size.y
+    }
+
     /// Sets the padding between content and scrollbar.
     pub fn set_scrollbar_padding<V: Into<Vec2>>(
         &mut self, scrollbar_padding: V,
@@ -432,8 +617,17 @@ impl Core {
     /// Control whether scroll bars are visibile.
     ///
     /// Defaults to `true`.
+    ///
+    /// This sets the policy for both scrollbars to either
+    /// `ScrollbarPolicy::Auto` or `ScrollbarPolicy::Never`.
     pub fn set_show_scrollbars(&mut self, show_scrollbars: bool) {
-        self.show_scrollbars = show_scrollbars;
+        let policy = if show_scrollbars {
+            ScrollbarPolicy::Auto
+        } else {
+            ScrollbarPolicy::Never
+        };
+        self.scrollbar_policy = XY::both_from(policy);
+        self.invalidate_cache();
     }
 
     /// Control whether scroll bars are visibile.
@@ -443,11 +637,174 @@ impl Core {
         self.with(|s| s.set_show_scrollbars(show_scrollbars))
     }
 
-    /// Returns `true` if we will show scrollbars when needed.
-    ///
-    /// Scrollbars are always hidden when not needed.
+    /// Returns `true` if we may show scrollbars on any axis.
     pub fn get_show_scrollbars(&self) -> bool {
-        self.show_scrollbars
+        self.scrollbar_policy
+            .map(|policy| policy != ScrollbarPolicy::Never)
+            .any()
+    }
+
+    /// Sets when to show the scrollbar for the given orientation.
+    ///
+    /// `Orientation::Horizontal` refers to the horizontal scrollbar, used to
+    /// scroll along the x axis.
+    ///
+    /// Defaults to `ScrollbarPolicy::Auto`.
+    pub fn set_scrollbar_policy(
+        &mut self, orientation: Orientation, policy: ScrollbarPolicy,
+    ) {
+        *self.scrollbar_policy.get_mut(orientation) = policy;
+        self.invalidate_cache();
+    }
+
+    /// Sets when to show the scrollbar for the given orientation.
+    ///
+    /// Chainable variant.
+    pub fn scrollbar_policy(
+        self, orientation: Orientation, policy: ScrollbarPolicy,
+    ) -> Self {
+        self.with(|s| s.set_scrollbar_policy(orientation, policy))
+    }
+
+    /// Returns when the scrollbar for the given orientation is shown.
+    pub fn get_scrollbar_policy(
+        &self, orientation: Orientation,
+    ) -> ScrollbarPolicy {
+        *self.scrollbar_policy.get(orientation)
+    }
+
+    /// Sets on which side to draw the scrollbar for the given orientation.
+    ///
+    /// Defaults to `ScrollbarPosition::End` (right or bottom).
+    pub fn set_scrollbar_position(
+        &mut self, orientation: Orientation, position: ScrollbarPosition,
+    ) {
+        *self.scrollbar_position.get_mut(orientation) = position;
+    }
+
+    /// Sets on which side to draw the scrollbar for the given orientation.
+    ///
+    /// Chainable variant.
+    pub fn scrollbar_position(
+        self, orientation: Orientation, position: ScrollbarPosition,
+    ) -> Self {
+        self.with(|s| s.set_scrollbar_position(orientation, position))
+    }
+
+    /// Returns on which side the scrollbar for the given orientation is drawn.
+    pub fn get_scrollbar_position(
+        &self, orientation: Orientation,
+    ) -> ScrollbarPosition {
+        *self.scrollbar_position.get(orientation)
+    }
+
+    /// Sets the glyphs, colors and width of the scrollbars.
+    pub fn set_scrollbar_style(&mut self, style: ScrollbarStyle) {
+        self.scrollbar_style = style;
+        self.invalidate_cache();
+    }
+
+    /// Sets the glyphs, colors and width of the scrollbars.
+    ///
+    /// Chainable variant.
+    pub fn scrollbar_style(self, style: ScrollbarStyle) -> Self {
+        self.with(|s| s.set_scrollbar_style(style))
+    }
+
+    /// Returns the glyphs, colors and width of the scrollbars.
+    pub fn get_scrollbar_style(&self) -> &ScrollbarStyle {
+        &self.scrollbar_style
+    }
+
+    /// Sets the keys used to scroll.
+    ///
+    /// Defaults to the bindings set with `ScrollKeys::set_default`.
+    pub fn set_scroll_keys(&mut self, keys: ScrollKeys) {
+        self.scroll_keys = Rc::new(keys);
+    }
+
+    /// Sets the keys used to scroll.
+    ///
+    /// Chainable variant.
+    pub fn scroll_keys(self, keys: ScrollKeys) -> Self {
+        self.with(|s| s.set_scroll_keys(keys))
+    }
+
+    /// Returns the keys used to scroll.
+    pub fn get_scroll_keys(&self) -> &ScrollKeys {
+        &self.scroll_keys
+    }
+
+    /// Hides the scrollbars after the given time without scrolling.
+    ///
+    /// Their space is still reserved, so the content doesn't move when they
+    /// appear or disappear. Since nothing else triggers a redraw, you may
+    /// want to use `Cursive::set_autorefresh` alongside this.
+    ///
+    /// Give `None` to always show them (this is the default).
+    pub fn set_scrollbar_auto_hide(&mut self, timeout: Option<Duration>) {
+        self.auto_hide = timeout;
+    }
+
+    /// Hides the scrollbars after the given time without scrolling.
+    ///
+    /// Chainable variant.
+    pub fn scrollbar_auto_hide(self, timeout: Duration) -> Self {
+        self.with(|s| s.set_scrollbar_auto_hide(Some(timeout)))
+    }
+
+    /// Returns `true` if the auto-hide timeout has expired since we last
+    /// scrolled.
+    fn scrollbars_hidden(&self) -> bool {
+        match (self.auto_hide, self.last_scroll) {
+            (None, _) => false,
+            (Some(_), None) => true,
+            (Some(timeout), Some(last)) => last.elapsed() >= timeout,
+        }
+    }
+
+    /// Returns for each axis if a scrollbar is required, given whether we
+    /// are scrolling on this axis.
+    pub fn scrollbars_for(&self, scrolling: XY<bool>) -> XY<bool> {
+        self.scrollbar_policy.zip_map(scrolling, |policy, scrolling| {
+            match policy {
+                ScrollbarPolicy::Always => true,
+                ScrollbarPolicy::Auto => scrolling,
+                ScrollbarPolicy::Never => false,
+            }
+        })
+    }
+
+    /// Returns for each axis if we are showing a scrollbar.
+    ///
+    /// This ignores auto-hiding: the space is taken anyway.
+    pub fn scrollbars_shown(&self) -> XY<bool> {
+        self.scrollbars_for(self.is_scrolling())
+    }
+
+    /// Returns the top-left corner of the content area.
+    ///
+    /// This is not zero when scrollbars are drawn on the left or top sides.
+    pub(crate) fn content_origin(&self) -> Vec2 {
+        let before = self
+            .scrollbar_position
+            .map(|position| position == ScrollbarPosition::Start)
+            .swap();
+
+        before.select_or(self.scrollbar_size(), Vec2::zero())
+    }
+
+    /// Returns the column of the vertical scrollbar (as `x`), and the row of
+    /// the horizontal scrollbar (as `y`).
+    fn scrollbar_location(&self) -> Vec2 {
+        let width = self.scrollbar_style.width;
+        let end = self.last_size.saturating_sub((width, width));
+        self.scrollbar_position
+            .swap()
+            .zip_map(end, |position, end| match position {
+                ScrollbarPosition::Start => 0,
+                ScrollbarPosition::End => end,
+            })
     }
 
     /// Returns the size given to the content on the last layout phase.
@@ -456,12 +813,85 @@ impl Core {
     }
 
     /// Sets the scroll offset to the given value
+    ///
+    /// This is never animated, and cancels any ongoing animation.
     pub fn set_offset<S>(&mut self, offset: S)
     where
         S: Into<Vec2>,
     {
         let max_offset = self.inner_size.saturating_sub(self.available_size());
         self.offset = offset.into().or_min(max_offset);
+        self.scroll_target = None;
+    }
+
+    /// Controls whether large jumps are animated.
+    ///
+    /// When enabled, paging, `Home`/`End` and programmatic scrolling
+    /// (`scroll_to`, `scroll_to_bottom`, ...) move the viewport over a few
+    /// frames instead of all at once.
+    ///
+    /// The animation progresses with each layout phase, so it needs a
+    /// non-zero refresh rate (see `Cursive::set_fps`) to run on its own.
+    ///
+    /// Defaults to `false`.
+    pub fn set_smooth_scrolling(&mut self, smooth_scrolling: bool) {
+        self.smooth_scrolling = smooth_scrolling;
+        if !smooth_scrolling {
+            // Finish any ongoing animation right away.
+            if let Some(target) = self.scroll_target.take() {
+                self.offset = target;
+            }
+        }
+    }
+
+    /// Controls whether large jumps are animated.
+    ///
+    /// Chainable variant.
+    pub fn smooth_scrolling(self, smooth_scrolling: bool) -> Self {
+        self.with(|s| s.set_smooth_scrolling(smooth_scrolling))
+    }
+
+    /// Returns `true` if large jumps are animated.
+    pub fn get_smooth_scrolling(&self) -> bool {
+        self.smooth_scrolling
+    }
+
+    /// Returns `true` if we are currently animating a scroll.
+    pub fn is_animating(&self) -> bool {
+        self.scroll_target.is_some()
+    }
+
+    /// Moves the viewport to the given offset.
+    ///
+    /// This will be animated if smooth scrolling is enabled.
+    fn jump_to(&mut self, offset: Vec2) {
+        let max_offset = self.inner_size.saturating_sub(self.available_size());
+        let offset = offset.or_min(max_offset);
+
+        if self.smooth_scrolling && offset != self.offset {
+            self.scroll_target = Some(offset);
+        } else {
+            self.offset = offset;
+            self.scroll_target = None;
+        }
+    }
+
+    /// Moves the offset one step closer to the animation target.
+    fn animate(&mut self) {
+        if let Some(target) = self.scroll_target {
+            // Cover half the remaining distance at every step.
+            self.offset = self.offset.zip_map(target, |offset, target| {
+                if offset < target {
+                    offset + div_up(target - offset, 2)
+                } else {
+                    offset - div_up(offset - target, 2)
+                }
+            });
+
+            if self.offset == target {
+                self.scroll_target = None;
+            }
+        }
     }
 
     /// Controls whether this view can scroll vertically.
@@ -504,7 +934,24 @@ impl Core {
         let max = rect.top_left();
         let (min, max) = (Vec2::min(min, max), Vec2::max(min, max));
 
-        self.offset = self.offset.or_min(max).or_max(min);
+        let offset = self.current_target().or_min(max).or_max(min);
+        self.jump_to(offset);
+    }
+
+    /// Scroll as little as possible to show the given area.
+    ///
+    /// If the area is larger than the viewport, its top-left corner is
+    /// shown.
+    pub fn scroll_to_rect(&mut self, rect: Rect) {
+        // The furthest top-left we can go
+        let top_left = (rect.bottom_right() + (1, 1))
+            .saturating_sub(self.available_size());
+        // The furthest bottom-right we can go
+        let bottom_right = rect.top_left();
+
+        let offset =
+            self.current_target().or_max(top_left).or_min(bottom_right);
+        self.jump_to(offset);
     }
 
     /// Scroll until the given point is visible.
@@ -514,55 +961,65 @@ impl Core {
         // How far to the bottom-right we can go
         let max = pos;
 
-        self.offset = self.offset.or_min(max).or_max(min);
+        let offset = self.current_target().or_min(max).or_max(min);
+        self.jump_to(offset);
     }
 
     /// Scroll until the given column is visible.
     pub fn scroll_to_x(&mut self, x: usize) {
-        if x >= self.offset.x + self.last_size.x {
-            self.offset.x = 1 + x - self.last_size.x;
-        } else if x < self.offset.x {
-            self.offset.x = x;
+        let mut offset = self.current_target();
+        if x >= offset.x + self.last_size.x {
+            offset.x = 1 + x - self.last_size.x;
+        } else if x < offset.x {
+            offset.x = x;
         }
+        self.jump_to(offset);
     }
 
     /// Scroll until the given row is visible.
     pub fn scroll_to_y(&mut self, y: usize) {
-        if y >= self.offset.y + self.last_size.y {
-            self.offset.y = 1 + y - self.last_size.y;
-        } else if y < self.offset.y {
-            self.offset.y = y;
+        let mut offset = self.current_target();
+        if y >= offset.y + self.last_size.y {
+            offset.y = 1 + y - self.last_size.y;
+        } else if y < offset.y {
+            offset.y = y;
         }
+        self.jump_to(offset);
     }
 
     /// Programmatically scroll to the top of the view.
     pub fn scroll_to_top(&mut self) {
-        let curr_x = self.offset.x;
-        self.set_offset((curr_x, 0));
+        let curr_x = self.current_target().x;
+        self.jump_to(Vec2::new(curr_x, 0));
     }
 
     /// Programmatically scroll to the bottom of the view.
     pub fn scroll_to_bottom(&mut self) {
         let max_y = self.inner_size.saturating_sub(self.available_size()).y;
-        let curr_x = self.offset.x;
-        self.set_offset((curr_x, max_y));
+        let curr_x = self.current_target().x;
+        self.jump_to(Vec2::new(curr_x, max_y));
     }
 
     /// Programmatically scroll to the leftmost side of the view.
     pub fn scroll_to_left(&mut self) {
-        let curr_y = self.offset.y;
-        self.set_offset((0, curr_y));
+        let curr_y = self.current_target().y;
+        self.jump_to(Vec2::new(0, curr_y));
     }
 
     /// Programmatically scroll to the rightmost side of the view.
     pub fn scroll_to_right(&mut self) {
         let max_x = self.inner_size.saturating_sub(self.available_size()).x;
-        let curr_y = self.offset.y;
-        self.set_offset((max_x, curr_y));
+        let curr_y = self.current_target().y;
+        self.jump_to(Vec2::new(max_x, curr_y));
     }
 
-    /// Clears the cache.
-    fn invalidate_cache(&mut self) {
+    /// Returns where the viewport will end up once any animation is done.
+    fn current_target(&self) -> Vec2 {
+        self.scroll_target.unwrap_or(self.offset)
+    }
+
+    /// Clears the cache, so the next layout is done from scratch.
+    pub fn invalidate_cache(&mut self) {
         self.size_cache = None;
     }
 
@@ -571,6 +1028,33 @@ impl Core {
         self.inner_size.zip_map(self.last_size, |i, s| i > s)
     }
 
+    /// Scrolls one step towards `position`, if it is outside the viewport.
+    fn follow_drag(&mut self, position: Vec2, offset: Vec2) {
+        let start = offset + self.content_origin();
+        let end = start + self.available_size();
+        let max_offset = self.inner_size.saturating_sub(self.available_size());
+
+        let offset = XY::zip5(position, start, end, self.offset, max_offset)
+            .zip(self.enabled)
+            .map(|((position, start, end, current, max), enabled)| {
+                if !enabled {
+                    current
+                } else if position < start {
+                    current.saturating_sub(1)
+                } else if position >= end {
+                    min(current + 1, max)
+                } else {
+                    current
+                }
+            });
+
+        if offset != self.offset {
+            self.scroll_target = None;
+            self.offset = offset;
+            self.last_scroll = Some(Instant::now());
+        }
+    }
+
     /// Stops grabbing the scrollbar.
     fn release_grab(&mut self) {
         self.thumb_grab = None;
@@ -582,18 +1066,18 @@ impl Core {
     ///
     /// The scrollbar_size().x will be the horizontal space taken by the vertical scrollbar.
     pub fn scrollbar_size(&self) -> Vec2 {
-        self.is_scrolling()
+        self.scrollbars_shown()
             .swap()
-            .select_or(self.scrollbar_padding + (1, 1), Vec2::zero())
+            .select_or(
+                self.scrollbar_padding
+                    + Vec2::both_from(self.scrollbar_style.width),
+                Vec2::zero(),
+            )
     }
 
     /// Returns the size available for the child view.
     fn available_size(&self) -> Vec2 {
-        if self.show_scrollbars {
-            self.last_size.saturating_sub(self.scrollbar_size())
-        } else {
-            self.last_size
-        }
+        self.last_size.saturating_sub(self.scrollbar_size())
     }
 
     /// Starts scrolling from the cursor position.
@@ -601,19 +1085,25 @@ impl Core {
     /// Returns `true` if the event was consumed.
     fn start_drag(&mut self, position: Vec2) -> bool {
         // For each scrollbar, how far it is.
-        let scrollbar_pos = self.last_size.saturating_sub((1, 1));
+        let scrollbar_pos = self.scrollbar_location();
         let lengths = self.scrollbar_thumb_lengths();
         let offsets = self.scrollbar_thumb_offsets(lengths);
         let available = self.available_size();
+        let origin = self.content_origin();
 
         // This is true for Y if we grabbed the vertical scrollbar
         // More specifically, we need both (for instance for the vertical bar):
-        // * To be in the right column: X == scrollbar_pos
-        // * To be in the right range: Y < available
+        // * To be in the right column: X in the scrollbar width
+        // * To be in the right range: origin <= Y < origin + available
+        let width = self.scrollbar_style.width;
         let grabbed = position
-            .zip_map(scrollbar_pos, |p, s| p == s)
+            .zip_map(scrollbar_pos, |p, s| p >= s && p < s + width)
             .swap()
-            .and(position.zip_map(available, |p, a| p < a));
+            .and(position.zip_map(origin, |p, o| p >= o))
+            .and(position.zip_map(origin + available, |p, a| p < a));
+
+        // From now on, we only care about the position along the scrollbar.
+        let position = position.saturating_sub(origin);
 
         // Iterate on axises, and keep the one we grabbed.
         if let Some((orientation, pos, length, offset)) =
@@ -630,7 +1120,7 @@ impl Core {
                 // We hit the scrollbar, outside of the thumb.
                 // Let's move the middle there.
                 self.thumb_grab = Some((orientation, (length - 1) / 2));
-                self.drag(position);
+                self.drag(position + origin);
             }
 
             return true;
@@ -643,6 +1133,8 @@ impl Core {
     fn drag(&mut self, position: Vec2) {
         // Only do something if we grabbed something before.
         if let Some((orientation, grab)) = self.thumb_grab {
+            let position = position.saturating_sub(self.content_origin());
+            self.last_scroll = Some(Instant::now());
             self.scroll_to_thumb(
                 orientation,
                 position.get(orientation).saturating_sub(grab),
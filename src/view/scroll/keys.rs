@@ -0,0 +1,141 @@
+use crate::event::{Event, Key};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Movement triggered by a key in a scrolling view.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ScrollAction {
+    /// Scroll up by one row.
+    LineUp,
+    /// Scroll down by one row.
+    LineDown,
+    /// Scroll left by one column.
+    ColumnLeft,
+    /// Scroll right by one column.
+    ColumnRight,
+    /// Scroll up by a few rows.
+    PageUp,
+    /// Scroll down by a few rows.
+    PageDown,
+    /// Scroll up by half the visible height.
+    HalfPageUp,
+    /// Scroll down by half the visible height.
+    HalfPageDown,
+    /// Jump to the top-left corner.
+    Top,
+    /// Jump to the bottom-right corner.
+    Bottom,
+}
+
+/// Key bindings used to scroll a view.
+///
+/// New scrolling views use the default bindings, which can be changed for
+/// the current thread with [`ScrollKeys::set_default`]. Each view can then
+/// be given its own bindings, with [`ScrollView::set_scroll_keys`].
+///
+/// [`ScrollKeys::set_default`]: #method.set_default
+/// [`ScrollView::set_scroll_keys`]: ../views/struct.ScrollView.html#method.set_scroll_keys
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::view::{ScrollAction, ScrollKeys};
+/// # use cursive::views::{ScrollView, TextView};
+/// let keys = ScrollKeys::vim().binding(' ', ScrollAction::PageDown);
+/// let view = ScrollView::new(TextView::new("Lorem ipsum..."))
+///     .scroll_keys(keys);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ScrollKeys {
+    bindings: HashMap<Event, ScrollAction>,
+}
+
+thread_local! {
+    static DEFAULT_KEYS: RefCell<Rc<ScrollKeys>> =
+        RefCell::new(Rc::new(ScrollKeys::arrows()));
+}
+
+impl ScrollKeys {
+    /// Creates an empty key map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Arrows, `PageUp`, `PageDown`, `Home` and `End`.
+    ///
+    /// `Ctrl` with an arrow also scrolls, so the content can use the bare
+    /// arrows for itself.
+    pub fn arrows() -> Self {
+        use self::ScrollAction::*;
+
+        ScrollKeys::new()
+            .binding(Key::Up, LineUp)
+            .binding(Event::Ctrl(Key::Up), LineUp)
+            .binding(Key::Down, LineDown)
+            .binding(Event::Ctrl(Key::Down), LineDown)
+            .binding(Key::Left, ColumnLeft)
+            .binding(Event::Ctrl(Key::Left), ColumnLeft)
+            .binding(Key::Right, ColumnRight)
+            .binding(Event::Ctrl(Key::Right), ColumnRight)
+            .binding(Key::PageUp, PageUp)
+            .binding(Key::PageDown, PageDown)
+            .binding(Key::Home, Top)
+            .binding(Key::End, Bottom)
+    }
+
+    /// The arrow bindings, plus vim-like keys.
+    ///
+    /// `j`, `k`, `h` and `l` move by one row or column, `Ctrl-d` and
+    /// `Ctrl-u` by half a page, `g` and `G` go to the top and bottom.
+    pub fn vim() -> Self {
+        use self::ScrollAction::*;
+
+        ScrollKeys::arrows()
+            .binding('j', LineDown)
+            .binding('k', LineUp)
+            .binding('h', ColumnLeft)
+            .binding('l', ColumnRight)
+            .binding(Event::CtrlChar('d'), HalfPageDown)
+            .binding(Event::CtrlChar('u'), HalfPageUp)
+            .binding('g', Top)
+            .binding('G', Bottom)
+    }
+
+    /// Binds `event` to `action`, replacing any previous binding.
+    pub fn bind<E: Into<Event>>(&mut self, event: E, action: ScrollAction) {
+        self.bindings.insert(event.into(), action);
+    }
+
+    /// Binds `event` to `action`, replacing any previous binding.
+    ///
+    /// Chainable variant.
+    pub fn binding<E: Into<Event>>(
+        mut self, event: E, action: ScrollAction,
+    ) -> Self {
+        self.bind(event, action);
+        self
+    }
+
+    /// Removes the binding for `event`, and returns its action.
+    pub fn unbind<E: Into<Event>>(&mut self, event: E) -> Option<ScrollAction> {
+        self.bindings.remove(&event.into())
+    }
+
+    /// Returns the action bound to `event`, if any.
+    pub fn get(&self, event: &Event) -> Option<ScrollAction> {
+        self.bindings.get(event).cloned()
+    }
+
+    /// Sets the bindings given to new scrolling views on this thread.
+    ///
+    /// Views created before this call keep their bindings.
+    pub fn set_default(keys: ScrollKeys) {
+        DEFAULT_KEYS.with(|default| *default.borrow_mut() = Rc::new(keys));
+    }
+
+    /// Returns the bindings given to new scrolling views on this thread.
+    pub(crate) fn get_default() -> Rc<ScrollKeys> {
+        DEFAULT_KEYS.with(|default| Rc::clone(&default.borrow()))
+    }
+}
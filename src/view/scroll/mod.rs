@@ -7,12 +7,15 @@
 //! [`ScrollView`](crate::views::ScrollView) may be an easier way to add scrolling to an existing view.
 
 mod core;
+mod keys;
 mod raw;
 
 pub use self::core::{Core, Scroller};
+pub use self::keys::{ScrollAction, ScrollKeys};
 
 use crate::event::{Event, EventResult};
-use crate::{Printer, Rect, Vec2};
+use crate::theme::ColorStyle;
+use crate::{Printer, Rect, Vec2, XY};
 
 /// Defines the scrolling behaviour on content or size change
 #[derive(Debug)]
@@ -31,6 +34,124 @@ impl Default for ScrollStrategy {
     }
 }
 
+/// Defines when a scrollbar should be visible.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScrollbarPolicy {
+    /// Always show the scrollbar, even if the content fits.
+    Always,
+    /// Only show the scrollbar when the content doesn't fit.
+    Auto,
+    /// Never show the scrollbar.
+    ///
+    /// The view can still be scrolled with the keyboard or the mouse wheel.
+    Never,
+}
+
+/// Defines on which side of the content a scrollbar is drawn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScrollbarPosition {
+    /// Left side for the vertical scrollbar, top for the horizontal one.
+    Start,
+    /// Right side for the vertical scrollbar, bottom for the horizontal one.
+    End,
+}
+
+/// Defines how scrollbars are drawn.
+///
+/// For the glyphs, `x` is used by the horizontal scrollbar and `y` by the
+/// vertical one. Each glyph should be a single cell wide.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::view::ScrollbarStyle;
+/// # use cursive::views::{ScrollView, TextView};
+/// let view = ScrollView::new(TextView::new("Lorem ipsum..."))
+///     .scrollbar_style(ScrollbarStyle::block().width(2));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScrollbarStyle {
+    /// Glyph for the part of the scrollbar outside of the thumb.
+    pub track: XY<String>,
+    /// Glyph for the thumb.
+    pub thumb: XY<String>,
+    /// Glyph for the thumb while it is dragged with the mouse.
+    pub grabbed_thumb: XY<String>,
+    /// Glyph drawn where the two scrollbars meet.
+    pub corner: String,
+    /// Color of the track.
+    ///
+    /// If `None`, the track uses the color of the view.
+    pub track_color: Option<ColorStyle>,
+    /// Color of the thumb.
+    ///
+    /// If `None`, the thumb is highlighted, or inactive-highlighted when
+    /// the view is not focused.
+    pub thumb_color: Option<ColorStyle>,
+    /// Thickness of the scrollbars, in cells.
+    pub width: usize,
+}
+
+impl ScrollbarStyle {
+    /// Thin lines, with a shaded thumb.
+    ///
+    /// This is the default.
+    pub fn line() -> Self {
+        ScrollbarStyle {
+            track: XY::new("-", "|").map(String::from),
+            thumb: XY::both_from("▒").map(String::from),
+            grabbed_thumb: XY::both_from(" ").map(String::from),
+            corner: String::from("╳"),
+            track_color: None,
+            thumb_color: None,
+            width: 1,
+        }
+    }
+
+    /// A light track with a solid thumb.
+    pub fn block() -> Self {
+        ScrollbarStyle {
+            track: XY::both_from("░").map(String::from),
+            thumb: XY::both_from("█").map(String::from),
+            grabbed_thumb: XY::both_from("▓").map(String::from),
+            corner: String::from(" "),
+            track_color: None,
+            thumb_color: None,
+            width: 1,
+        }
+    }
+
+    /// Sets the thickness of the scrollbars.
+    ///
+    /// Chainable variant.
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Sets the color of the track.
+    ///
+    /// Chainable variant.
+    pub fn track_color(mut self, color: ColorStyle) -> Self {
+        self.track_color = Some(color);
+        self
+    }
+
+    /// Sets the color of the thumb.
+    ///
+    /// Chainable variant.
+    pub fn thumb_color(mut self, color: ColorStyle) -> Self {
+        self.thumb_color = Some(color);
+        self
+    }
+}
+
+impl Default for ScrollbarStyle {
+    fn default() -> Self {
+        ScrollbarStyle::line()
+    }
+}
+
 /// Performs `View::on_event` on a `scroll::Scroller`.
 ///
 /// Example:
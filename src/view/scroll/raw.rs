@@ -76,10 +76,11 @@ where
         }
     }
 
-    // Attempt 1: try without scrollbars
+    // Attempt 1: try with only the scrollbars that are always visible.
+    let initial = get_scroller(model).scrollbars_for(XY::new(false, false));
     let (inner_size, size, scrollable) = sizes_when_scrolling(
         constraint,
-        XY::new(false, false),
+        initial,
         strict,
         model,
         get_scroller,
@@ -87,18 +88,20 @@ where
     );
 
     // If we need to add scrollbars, the available size will change.
-    if scrollable.any() && get_scroller(model).get_show_scrollbars() {
+    let needed = get_scroller(model).scrollbars_for(scrollable);
+    if needed != initial {
         // Attempt 2: he wants to scroll? Sure!
         // Try again with some space for the scrollbar.
         let (inner_size, size, new_scrollable) = sizes_when_scrolling(
             constraint,
-            scrollable,
+            needed,
             strict,
             model,
             get_scroller,
             required_size,
         );
-        if scrollable == new_scrollable {
+        let new_needed = get_scroller(model).scrollbars_for(new_scrollable);
+        if needed == new_needed {
             // Yup, scrolling did it. We're good to go now.
             (inner_size, size)
         } else {
@@ -106,7 +109,7 @@ where
             // There is no end to this!
             let (inner_size, size, _) = sizes_when_scrolling(
                 constraint,
-                new_scrollable,
+                new_needed,
                 strict,
                 model,
                 get_scroller,
@@ -118,8 +121,8 @@ where
             (inner_size, size)
         }
     } else {
-        // We're not showing any scrollbar, either because we don't scroll
-        // or because scrollbars are hidden.
+        // We don't need any scrollbar besides the ones we reserved,
+        // either because we don't scroll or because scrollbars are hidden.
         (inner_size, size)
     }
 }
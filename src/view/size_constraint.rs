@@ -17,6 +17,11 @@ pub enum SizeConstraint {
     AtMost(usize),
     /// Returns the maximum of the included value and the child view's size.
     AtLeast(usize),
+    /// Always takes the given percentage of the available space, no matter
+    /// what the child needs.
+    ///
+    /// The value should be between `0.0` and `100.0`.
+    Percent(f32),
 }
 
 impl SizeConstraint {
@@ -32,6 +37,7 @@ impl SizeConstraint {
             SizeConstraint::Fixed(value) | SizeConstraint::AtMost(value) => {
                 min(value, available)
             }
+            SizeConstraint::Percent(percent) => percent_of(percent, available),
         }
     }
 
@@ -43,8 +49,15 @@ impl SizeConstraint {
             SizeConstraint::AtLeast(value) if result < value => value,
             SizeConstraint::AtMost(value) if result > value => value,
             SizeConstraint::Fixed(value) => value,
-            SizeConstraint::Full => available,
+            // The percentage was already applied by `available()`.
+            SizeConstraint::Full | SizeConstraint::Percent(_) => available,
             _ => result,
         }
     }
 }
+
+// Returns `percent`% of `available`, rounded down.
+fn percent_of(percent: f32, available: usize) -> usize {
+    let percent = percent.clamp(0.0, 100.0);
+    (available as f32 * percent / 100.0) as usize
+}
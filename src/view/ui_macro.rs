@@ -0,0 +1,116 @@
+/// Builds a tree of views from a concise description.
+///
+/// Each node is written `kind(arguments) { children } [modifiers]`, where
+/// every part but the kind is optional:
+///
+/// * `vertical { ... }` and `horizontal { ... }` build a `LinearLayout`
+///   from a comma-separated list of children.
+/// * `dialog("Title") { child }` wraps a single child in a `Dialog`, and
+///   `panel { child }` in a `Panel`. The title is optional.
+/// * `view(expression)` uses any expression returning a view, such as
+///   `view(DummyView)`.
+/// * Any other kind is a view type, built with its `new` method and the
+///   given arguments: `TextView("Hello")` is `TextView::new("Hello")`.
+///
+/// Modifiers are method calls applied to the node, in order. This includes
+/// the chainable methods of the view itself, like `button` for a dialog,
+/// as well as the ones from [`traits`], like `with_id`, `fixed_width` or
+/// `scrollable`. Since `with_id` must wrap the view itself for `find_id` to
+/// work, it usually comes first.
+///
+/// [`traits`]: traits/index.html
+///
+/// # Examples
+///
+/// ```rust
+/// # #[macro_use] extern crate cursive;
+/// # use cursive::Cursive;
+/// # use cursive::views::{Checkbox, EditView, TextView};
+/// # fn main() {
+/// let mut siv = Cursive::dummy();
+/// siv.add_layer(cursive_ui! {
+///     dialog("Login") {
+///         vertical {
+///             TextView("Name"),
+///             EditView [with_id("name"), fixed_width(20)],
+///             horizontal {
+///                 Checkbox [with_id("remember")],
+///                 TextView(" Remember me"),
+///             },
+///         }
+///     } [button("Ok", |s| s.quit()), dismiss_button("Cancel")]
+/// });
+///
+/// assert!(siv.find_id::<EditView>("name").is_some());
+/// # }
+/// ```
+#[macro_export]
+macro_rules! cursive_ui {
+    // Parts are normalized so every node has arguments, body and modifiers.
+    (@node vertical () { $($body:tt)* } [ $($mods:tt)* ]) => {
+        $crate::cursive_ui!(@mods
+            $crate::cursive_ui!(@children
+                $crate::views::LinearLayout::vertical(); $($body)*);
+            $($mods)*)
+    };
+    (@node horizontal () { $($body:tt)* } [ $($mods:tt)* ]) => {
+        $crate::cursive_ui!(@mods
+            $crate::cursive_ui!(@children
+                $crate::views::LinearLayout::horizontal(); $($body)*);
+            $($mods)*)
+    };
+    (@node dialog () { $($body:tt)* } [ $($mods:tt)* ]) => {
+        $crate::cursive_ui!(@mods
+            $crate::views::Dialog::around($crate::cursive_ui!($($body)*));
+            $($mods)*)
+    };
+    (@node dialog ($title:expr) { $($body:tt)* } [ $($mods:tt)* ]) => {
+        $crate::cursive_ui!(@mods
+            $crate::views::Dialog::around($crate::cursive_ui!($($body)*))
+                .title($title);
+            $($mods)*)
+    };
+    (@node panel () { $($body:tt)* } [ $($mods:tt)* ]) => {
+        $crate::cursive_ui!(@mods
+            $crate::views::Panel::new($crate::cursive_ui!($($body)*));
+            $($mods)*)
+    };
+    (@node view ($view:expr) {} [ $($mods:tt)* ]) => {
+        $crate::cursive_ui!(@mods $view; $($mods)*)
+    };
+    (@node $kind:ident ($($args:tt)*) {} [ $($mods:tt)* ]) => {
+        $crate::cursive_ui!(@mods $kind::new($($args)*); $($mods)*)
+    };
+
+    (@children $layout:expr; $(
+        $kind:ident
+        $(( $($args:tt)* ))?
+        $({ $($body:tt)* })?
+        $([ $($mods:tt)* ])?
+    ),* $(,)?) => {{
+        let mut layout = $layout;
+        $(
+            layout.add_child($crate::cursive_ui!(@node $kind
+                ($($($args)*)?) {$($($body)*)?} [$($($mods)*)?]));
+        )*
+        layout
+    }};
+
+    (@mods $view:expr; $(
+        $method:ident $(( $($args:tt)* ))?
+    ),* $(,)?) => {{
+        #[allow(unused_imports)]
+        use $crate::traits::*;
+        $view $(.$method($($($args)*)?))*
+    }};
+
+    (
+        $kind:ident
+        $(( $($args:tt)* ))?
+        $({ $($body:tt)* })?
+        $([ $($mods:tt)* ])?
+    ) => {
+        $crate::cursive_ui!(@node $kind
+            ($($($args)*)?) {$($($body)*)?} [$($($mods)*)?])
+    };
+}
@@ -1,8 +1,9 @@
 use crate::direction::Direction;
 use crate::event::{AnyCb, Event, EventResult};
 use crate::rect::Rect;
+use crate::state::ViewState;
 use crate::vec::Vec2;
-use crate::view::{AnyView, Selector};
+use crate::view::{AnyView, FocusPolicy, Selector};
 use crate::Printer;
 use std::any::Any;
 
@@ -41,6 +42,16 @@ pub trait View: Any + AnyView {
         true
     }
 
+    /// Asks the view to be laid out again, even if it saw no change.
+    ///
+    /// Until the next call to `layout()`, `needs_relayout()` should then
+    /// return `true`. Views caching their layout should forget it, and view
+    /// groups should propagate the call to their children.
+    ///
+    /// Default implementation does nothing, which is fine for views always
+    /// returning `true` from `needs_relayout()`.
+    fn mark_dirty(&mut self) {}
+
     /// Returns the minimum size the view requires with the given restrictions.
     ///
     /// This is the main way a view communicate its size to its parent.
@@ -81,10 +92,12 @@ pub trait View: Any + AnyView {
     /// If the selector doesn't find a match, the closure will not be run.
     ///
     /// View groups should implement this to forward the call to each children.
+    /// With `Selector::All`, they should also run the callback on themselves.
     ///
-    /// Default implementation is a no-op.
-    fn call_on_any<'a>(&mut self, _: &Selector<'_>, _: AnyCb<'a>) {
+    /// Default implementation only runs the callback for `Selector::All`.
+    fn call_on_any<'a>(&mut self, selector: &Selector<'_>, mut cb: AnyCb<'a>) {
         // TODO: FnMut -> FnOnce once it works
+        selector.call_on_self(self.as_any_mut(), &mut cb);
     }
 
     /// Moves the focus to the view identified by the given selector.
@@ -96,6 +109,19 @@ pub trait View: Any + AnyView {
         Err(())
     }
 
+    /// Returns the area of the view identified by the given selector.
+    ///
+    /// It is given the view size (same size given to `layout`), and returns
+    /// an area relative to this view, using the sizes from the last layout.
+    ///
+    /// View groups should implement this to look into their children.
+    ///
+    /// Default implementation simply returns `None`.
+    fn locate(&self, _: &Selector<'_>, view_size: Vec2) -> Option<Rect> {
+        let _ = view_size;
+        None
+    }
+
     /// This view is offered focus. Will it take it?
     ///
     /// `source` indicates where the focus comes from.
@@ -107,6 +133,16 @@ pub trait View: Any + AnyView {
         false
     }
 
+    /// What happens when the focus reaches the edge of this view?
+    ///
+    /// Containers return the policy they apply when `Tab` (or an arrow key)
+    /// would move the focus out of them.
+    ///
+    /// Default implementation returns `FocusPolicy::Escape`.
+    fn focus_policy(&self) -> FocusPolicy {
+        FocusPolicy::Escape
+    }
+
     /// What part of the view is important and should be visible?
     ///
     /// When only part of this view can be visible, this helps
@@ -118,4 +154,24 @@ pub trait View: Any + AnyView {
     fn important_area(&self, view_size: Vec2) -> Rect {
         Rect::from_size((0, 0), view_size)
     }
+
+    /// Returns the state of this view to save, if any.
+    ///
+    /// This is used by `Cursive::save_ui_state` for views with an id. See
+    /// the [`state`](../state/index.html) module.
+    ///
+    /// Default implementation returns `None`.
+    fn save_state(&self) -> Option<ViewState> {
+        None
+    }
+
+    /// Restores a state returned by `save_state`.
+    ///
+    /// Callbacks, like the ones run when the selection changes, are not
+    /// called.
+    ///
+    /// Default implementation does nothing.
+    fn restore_state(&mut self, state: &ViewState) {
+        let _ = state;
+    }
 }
@@ -1,8 +1,9 @@
 use crate::direction::Direction;
 use crate::event::{AnyCb, Event, EventResult};
 use crate::rect::Rect;
+use crate::state::ViewState;
 use crate::vec::Vec2;
-use crate::view::{Selector, View};
+use crate::view::{FocusPolicy, Selector, View};
 use crate::Printer;
 use std::any::Any;
 
@@ -90,16 +91,44 @@ pub trait ViewWrapper: 'static {
             .unwrap_or(Err(()))
     }
 
+    /// Wraps the `locate` method.
+    fn wrap_locate(
+        &self, selector: &Selector<'_>, size: Vec2,
+    ) -> Option<Rect> {
+        self.with_view(|v| v.locate(selector, size)).flatten()
+    }
+
     /// Wraps the `needs_relayout` method.
     fn wrap_needs_relayout(&self) -> bool {
         self.with_view(View::needs_relayout).unwrap_or(true)
     }
 
+    /// Wraps the `mark_dirty` method.
+    fn wrap_mark_dirty(&mut self) {
+        self.with_view_mut(View::mark_dirty);
+    }
+
     /// Wraps the `important_area` method.
     fn wrap_important_area(&self, size: Vec2) -> Rect {
         self.with_view(|v| v.important_area(size))
             .unwrap_or_else(|| Rect::from((0, 0)))
     }
+
+    /// Wraps the `focus_policy` method.
+    fn wrap_focus_policy(&self) -> FocusPolicy {
+        self.with_view(View::focus_policy)
+            .unwrap_or(FocusPolicy::Escape)
+    }
+
+    /// Wraps the `save_state` method.
+    fn wrap_save_state(&self) -> Option<ViewState> {
+        self.with_view(View::save_state).flatten()
+    }
+
+    /// Wraps the `restore_state` method.
+    fn wrap_restore_state(&mut self, state: &ViewState) {
+        self.with_view_mut(|v| v.restore_state(state));
+    }
 }
 
 // The main point of implementing ViewWrapper is to have View for free.
@@ -126,8 +155,9 @@ impl<T: ViewWrapper> View for T {
 
     fn call_on_any<'a>(
         &mut self, selector: &Selector<'_>,
-        callback: Box<FnMut(&mut dyn Any) + 'a>,
+        mut callback: Box<dyn FnMut(&mut dyn Any) + 'a>,
     ) {
+        selector.call_on_self(self, &mut callback);
         self.wrap_call_on_any(selector, callback)
     }
 
@@ -135,13 +165,33 @@ impl<T: ViewWrapper> View for T {
         self.wrap_needs_relayout()
     }
 
+    fn mark_dirty(&mut self) {
+        self.wrap_mark_dirty();
+    }
+
     fn focus_view(&mut self, selector: &Selector<'_>) -> Result<(), ()> {
         self.wrap_focus_view(selector)
     }
 
+    fn locate(&self, selector: &Selector<'_>, size: Vec2) -> Option<Rect> {
+        self.wrap_locate(selector, size)
+    }
+
     fn important_area(&self, size: Vec2) -> Rect {
         self.wrap_important_area(size)
     }
+
+    fn focus_policy(&self) -> FocusPolicy {
+        self.wrap_focus_policy()
+    }
+
+    fn save_state(&self) -> Option<ViewState> {
+        self.wrap_save_state()
+    }
+
+    fn restore_state(&mut self, state: &ViewState) {
+        self.wrap_restore_state(state);
+    }
 }
 
 /// Convenient macro to implement the [`ViewWrapper`] trait.
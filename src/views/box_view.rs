@@ -116,6 +116,26 @@ impl<T: View> BoxView<T> {
         BoxView::new(SizeConstraint::Free, SizeConstraint::Fixed(height), view)
     }
 
+    /// Wraps `view` in a new `BoxView` taking a percentage of the available
+    /// width.
+    pub fn with_percent_width(percent: f32, view: T) -> Self {
+        BoxView::new(
+            SizeConstraint::Percent(percent),
+            SizeConstraint::Free,
+            view,
+        )
+    }
+
+    /// Wraps `view` in a new `BoxView` taking a percentage of the available
+    /// height.
+    pub fn with_percent_height(percent: f32, view: T) -> Self {
+        BoxView::new(
+            SizeConstraint::Free,
+            SizeConstraint::Percent(percent),
+            view,
+        )
+    }
+
     /// Wraps `view` in a `BoxView` which will take all available space.
     pub fn with_full_screen(view: T) -> Self {
         BoxView::new(SizeConstraint::Full, SizeConstraint::Full, view)
@@ -243,6 +263,11 @@ impl<T: View> ViewWrapper for BoxView<T> {
     fn wrap_needs_relayout(&self) -> bool {
         self.invalidated || self.view.needs_relayout()
     }
+
+    fn wrap_mark_dirty(&mut self) {
+        self.invalidate();
+        self.view.mark_dirty();
+    }
 }
 
 #[cfg(test)]
@@ -302,6 +327,18 @@ mod tests {
         assert_eq!(Vec2::new(5, 5), max_s.required_size(Vec2::new(10, 10)));
     }
 
+    #[test]
+    fn percent_size() {
+        let mut percent = DummyView.percent_width(60.0).percent_height(50.0);
+
+        assert_eq!(Vec2::new(6, 5), percent.required_size(Vec2::new(10, 10)));
+        assert_eq!(Vec2::new(0, 0), percent.required_size(Vec2::new(1, 1)));
+        assert_eq!(
+            Vec2::new(60, 25),
+            percent.required_size(Vec2::new(100, 50))
+        );
+    }
+
     #[test]
     fn full_screen() {
         let mut full = DummyView.full_screen();
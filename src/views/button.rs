@@ -2,26 +2,54 @@ use crate::align::HAlign;
 use crate::direction::Direction;
 use crate::event::*;
 use crate::rect::Rect;
-use crate::theme::ColorStyle;
+use crate::theme::{ColorStyle, Effect};
+use crate::utils::width::TextWidth;
 use crate::vec::Vec2;
 use crate::view::View;
 use crate::{Cursive, Printer, With};
-use unicode_width::UnicodeWidthStr;
 
 /// Simple text label with a callback when <Enter> is pressed.
 ///
 /// A button shows its content in a single line and has a fixed size.
 ///
+/// Buttons created with `new_with_mnemonic` can have a mnemonic: a `&` in
+/// their label marks the next character, which will be underlined. Pressing
+/// `Alt` with this character will activate the button. Use `&&` for a
+/// literal `&` in these labels; other labels are shown as they are.
+///
 /// # Examples
 ///
 /// ```
-/// # use cursive::views::Button;
+/// # use cursive::views::{Button, Dialog};
 /// let quit_button = Button::new("Quit", |s| s.quit());
+///
+/// let save_button =
+///     Button::new_with_mnemonic("&Save", |s| s.quit()).hint("Ctrl+S");
+/// assert_eq!(save_button.label(), "<Save>");
+/// assert_eq!(save_button.mnemonic(), Some('s'));
+///
+/// // In a dialog.
+/// let dialog = Dialog::text("Unsaved changes").button_view(save_button);
+///
+/// // Without a mnemonic, `&` is just a character.
+/// let button = Button::new("R&D", |s| s.quit());
+/// assert_eq!(button.label(), "<R&D>");
 /// ```
 pub struct Button {
     label: String,
     callback: Callback,
     enabled: bool,
+
+    // Byte offset in `label` of the mnemonic character, and the character
+    // itself, in lowercase.
+    mnemonic: Option<(usize, char)>,
+
+    // Shortcut hint, shown on the right of the label.
+    hint: Option<String>,
+
+    // Style used when the button is enabled but not focused.
+    style: ColorStyle,
+
     last_size: Vec2,
 
     invalidated: bool,
@@ -38,6 +66,19 @@ impl Button {
         Self::new_raw(format!("<{}>", label), cb)
     }
 
+    /// Creates a new button with the given content and callback, and a
+    /// mnemonic.
+    ///
+    /// A `&` in the label marks the next character as the mnemonic.
+    pub fn new_with_mnemonic<F, S>(label: S, cb: F) -> Self
+    where
+        F: 'static + Fn(&mut Cursive),
+        S: Into<String>,
+    {
+        Self::new_raw("", cb)
+            .with(|button| button.set_label_with_mnemonic(label))
+    }
+
     /// Creates a new button without angle brackets.
     pub fn new_raw<F, S: Into<String>>(label: S, cb: F) -> Self
     where
@@ -47,6 +88,9 @@ impl Button {
             label: label.into(),
             callback: Callback::from_fn(cb),
             enabled: true,
+            mnemonic: None,
+            hint: None,
+            style: ColorStyle::primary(),
             last_size: Vec2::zero(),
             invalidated: true,
         }
@@ -91,9 +135,74 @@ impl Button {
         self.enabled
     }
 
+    /// Sets the style used to draw this button when it is not focused.
+    ///
+    /// This can be used to make a destructive action stand out, for example
+    /// with a red "Delete" button.
+    ///
+    /// Defaults to `ColorStyle::primary()`.
+    pub fn set_style<S: Into<ColorStyle>>(&mut self, style: S) {
+        self.style = style.into();
+    }
+
+    /// Sets the style used to draw this button when it is not focused.
+    ///
+    /// Chainable variant.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::views::Button;
+    /// # use cursive::theme::{BaseColor, Color};
+    /// let delete_button = Button::new("Delete", |s| s.quit())
+    ///     .style(Color::Dark(BaseColor::Red));
+    /// ```
+    pub fn style<S: Into<ColorStyle>>(self, style: S) -> Self {
+        self.with(|s| s.set_style(style))
+    }
+
+    /// Returns the style used when this button is not focused.
+    pub fn get_style(&self) -> ColorStyle {
+        self.style
+    }
+
+    /// Returns the mnemonic for this button, if any.
+    ///
+    /// The mnemonic is always given in lowercase.
+    pub fn mnemonic(&self) -> Option<char> {
+        self.mnemonic.map(|(_, c)| c)
+    }
+
+    /// Sets a shortcut hint, shown right-aligned after the label.
+    ///
+    /// This is only a visual hint: the shortcut itself must be registered
+    /// separately, for instance with `Cursive::add_global_callback`.
+    pub fn set_hint<S: Into<String>>(&mut self, hint: S) {
+        self.hint = Some(hint.into());
+        self.invalidate();
+    }
+
+    /// Sets a shortcut hint, shown right-aligned after the label.
+    ///
+    /// Chainable variant.
+    pub fn hint<S: Into<String>>(self, hint: S) -> Self {
+        self.with(|s| s.set_hint(hint))
+    }
+
+    /// Removes the shortcut hint, if any.
+    pub fn clear_hint(&mut self) {
+        self.hint = None;
+        self.invalidate();
+    }
+
+    /// Returns the shortcut hint, if any.
+    pub fn get_hint(&self) -> Option<&str> {
+        self.hint.as_deref()
+    }
+
     /// Returns the label for this button.
     ///
-    /// Includes brackets.
+    /// Includes brackets, but not the `&` mnemonic marker.
     ///
     /// # Examples
     ///
@@ -108,7 +217,7 @@ impl Button {
 
     /// Sets the label to the given value.
     ///
-    /// This will include brackets.
+    /// This will include brackets, and remove the mnemonic, if any.
     pub fn set_label<S>(&mut self, label: S)
     where
         S: Into<String>,
@@ -116,19 +225,47 @@ impl Button {
         self.set_label_raw(format!("<{}>", label.into()));
     }
 
+    /// Sets the label to the given value, with a mnemonic.
+    ///
+    /// This will include brackets. A `&` marks the next character as the
+    /// mnemonic, and `&&` gives a literal `&`.
+    pub fn set_label_with_mnemonic<S>(&mut self, label: S)
+    where
+        S: Into<String>,
+    {
+        let (label, mnemonic) = parse_mnemonic(&label.into());
+        self.set_label_raw(format!("<{}>", label));
+
+        // Account for the opening bracket.
+        self.mnemonic = mnemonic.map(|(i, c)| (i + 1, c));
+    }
+
     /// Sets the label exactly to the given value.
     ///
-    /// This will not include brackets.
+    /// This will not include brackets, and will not look for a mnemonic.
     pub fn set_label_raw<S>(&mut self, label: S)
     where
         S: Into<String>,
     {
         self.label = label.into();
+        self.mnemonic = None;
         self.invalidate();
     }
 
     fn req_size(&self) -> Vec2 {
-        Vec2::new(self.label.width(), 1)
+        let hint_width = self.hint.as_ref().map_or(0, |hint| hint.width() + 1);
+        Vec2::new(self.label.width() + hint_width, 1)
+    }
+
+    // Offset of the label in the given width.
+    //
+    // The label is centered, unless a hint takes the right side.
+    fn label_offset(&self, width: usize) -> usize {
+        if self.hint.is_some() {
+            0
+        } else {
+            HAlign::Center.get_offset(self.label.width(), width)
+        }
     }
 
     fn invalidate(&mut self) {
@@ -147,14 +284,26 @@ impl View for Button {
         } else if printer.focused {
             ColorStyle::highlight()
         } else {
-            ColorStyle::primary()
+            self.style
         };
 
-        let offset =
-            HAlign::Center.get_offset(self.label.width(), printer.size.x);
+        let offset = self.label_offset(printer.size.x);
 
         printer.with_color(style, |printer| {
             printer.print((offset, 0), &self.label);
+
+            if let Some((i, _)) = self.mnemonic {
+                let x = offset + self.label[..i].width();
+                let c = self.label[i..].chars().next().unwrap();
+                printer.with_effect(Effect::Underline, |printer| {
+                    printer.print((x, 0), c.encode_utf8(&mut [0; 4]));
+                });
+            }
+
+            if let Some(ref hint) = self.hint {
+                let x = printer.size.x.saturating_sub(hint.width());
+                printer.print((x, 0), hint);
+            }
         });
     }
 
@@ -171,13 +320,19 @@ impl View for Button {
     fn on_event(&mut self, event: Event) -> EventResult {
         // eprintln!("{:?}", event);
         // eprintln!("{:?}", self.req_size());
-        let width = self.label.width();
-        let self_offset = HAlign::Center.get_offset(width, self.last_size.x);
+        let self_offset = self.label_offset(self.last_size.x);
         match event {
             // 10 is the ascii code for '\n', that is the return key
             Event::Key(Key::Enter) => {
                 EventResult::Consumed(Some(self.callback.clone()))
             }
+            Event::AltChar(c)
+                if self.enabled
+                    && self.mnemonic().is_some()
+                    && self.mnemonic() == c.to_lowercase().next() =>
+            {
+                EventResult::Consumed(Some(self.callback.clone()))
+            }
             Event::Mouse {
                 event: MouseEvent::Release(MouseButton::Left),
                 position,
@@ -197,7 +352,7 @@ impl View for Button {
 
     fn important_area(&self, view_size: Vec2) -> Rect {
         let width = self.label.width();
-        let offset = HAlign::Center.get_offset(width, view_size.x);
+        let offset = self.label_offset(view_size.x);
 
         Rect::from_size((offset, 0), (width, 1))
     }
@@ -205,4 +360,49 @@ impl View for Button {
     fn needs_relayout(&self) -> bool {
         self.invalidated
     }
+
+    fn mark_dirty(&mut self) {
+        self.invalidate();
+    }
+}
+
+// Removes the `&` markers from a label.
+//
+// Returns the cleaned label, and the byte offset and lowercase value of the
+// mnemonic character, if any.
+fn parse_mnemonic(label: &str) -> (String, Option<(usize, char)>) {
+    let mut result = String::with_capacity(label.len());
+    let mut mnemonic = None;
+
+    let mut chars = label.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek().cloned()) {
+            ('&', Some('&')) => {
+                chars.next();
+                result.push('&');
+            }
+            ('&', Some(next))
+                if next.is_alphanumeric() && mnemonic.is_none() =>
+            {
+                let offset = result.len();
+                mnemonic = next.to_lowercase().next().map(|m| (offset, m));
+            }
+            (c, _) => result.push(c),
+        }
+    }
+
+    (result, mnemonic)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_mnemonic;
+
+    #[test]
+    fn mnemonics() {
+        assert_eq!(parse_mnemonic("&Save"), ("Save".into(), Some((0, 's'))));
+        assert_eq!(parse_mnemonic("E&xit"), ("Exit".into(), Some((1, 'x'))));
+        assert_eq!(parse_mnemonic("A && B"), ("A & B".into(), None));
+        assert_eq!(parse_mnemonic("A & B"), ("A & B".into(), None));
+    }
 }
@@ -274,7 +274,10 @@ impl<T: 'static> View for Canvas<T> {
         (self.important_area)(&self.state, view_size)
     }
 
-    fn call_on_any<'a>(&mut self, selector: &Selector<'_>, cb: AnyCb<'a>) {
+    fn call_on_any<'a>(
+        &mut self, selector: &Selector<'_>, mut cb: AnyCb<'a>,
+    ) {
+        selector.call_on_self(self, &mut cb);
         (self.call_on_any)(&mut self.state, selector, cb);
     }
 }
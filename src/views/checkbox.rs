@@ -1,5 +1,6 @@
 use crate::direction::Direction;
 use crate::event::{Event, EventResult, Key, MouseButton, MouseEvent};
+use crate::state::ViewState;
 use crate::theme::ColorStyle;
 use crate::vec::Vec2;
 use crate::view::View;
@@ -8,14 +9,36 @@ use crate::Printer;
 use crate::With;
 use std::rc::Rc;
 
+/// State of a `Checkbox`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CheckState {
+    /// The box is not checked.
+    Unchecked,
+    /// The box is checked.
+    Checked,
+    /// The box is partially checked.
+    ///
+    /// Usually means that only some of the options it controls are checked.
+    /// This state can only be set programmatically: toggling a partial box
+    /// checks it.
+    Partial,
+}
+
 /// Checkable box.
 pub struct Checkbox {
-    checked: bool,
+    state: CheckState,
     enabled: bool,
 
+    // Drawn between the brackets for the checked and partial states.
+    checked_symbol: String,
+    partial_symbol: String,
+
     on_change: Option<Rc<dyn Fn(&mut Cursive, bool)>>,
+    on_state_change: Option<Rc<OnStateChange>>,
 }
 
+type OnStateChange = dyn Fn(&mut Cursive, CheckState);
+
 new_default!(Checkbox);
 
 impl Checkbox {
@@ -24,9 +47,12 @@ impl Checkbox {
     /// Creates a new, unchecked checkbox.
     pub fn new() -> Self {
         Checkbox {
-            checked: false,
+            state: CheckState::Unchecked,
             enabled: true,
+            checked_symbol: String::from("X"),
+            partial_symbol: String::from("-"),
             on_change: None,
+            on_state_change: None,
         }
     }
 
@@ -46,9 +72,68 @@ impl Checkbox {
         self.with(|s| s.set_on_change(on_change))
     }
 
+    /// Sets a callback to be used when the state changes.
+    ///
+    /// Unlike `set_on_change`, the callback can tell the partial state
+    /// apart from the unchecked one.
+    pub fn set_on_state_change<F: 'static + Fn(&mut Cursive, CheckState)>(
+        &mut self, on_state_change: F,
+    ) {
+        self.on_state_change = Some(Rc::new(on_state_change));
+    }
+
+    /// Sets a callback to be used when the state changes.
+    ///
+    /// Chainable variant.
+    pub fn on_state_change<F: 'static + Fn(&mut Cursive, CheckState)>(
+        self, on_state_change: F,
+    ) -> Self {
+        self.with(|s| s.set_on_state_change(on_state_change))
+    }
+
+    /// Sets the symbol drawn in a checked box.
+    ///
+    /// It should be one cell wide. Defaults to `"X"`.
+    pub fn set_checked_symbol<S: Into<String>>(&mut self, symbol: S) {
+        self.checked_symbol = symbol.into();
+    }
+
+    /// Sets the symbol drawn in a checked box.
+    ///
+    /// Chainable variant.
+    pub fn checked_symbol<S: Into<String>>(self, symbol: S) -> Self {
+        self.with(|s| s.set_checked_symbol(symbol))
+    }
+
+    /// Sets the symbol drawn in a partially checked box.
+    ///
+    /// It should be one cell wide. Defaults to `"-"`.
+    pub fn set_partial_symbol<S: Into<String>>(&mut self, symbol: S) {
+        self.partial_symbol = symbol.into();
+    }
+
+    /// Sets the symbol drawn in a partially checked box.
+    ///
+    /// Chainable variant.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::views::{CheckState, Checkbox};
+    /// let checkbox = Checkbox::new()
+    ///     .checked_symbol("✓")
+    ///     .partial_symbol("~")
+    ///     .state(CheckState::Partial);
+    /// ```
+    pub fn partial_symbol<S: Into<String>>(self, symbol: S) -> Self {
+        self.with(|s| s.set_partial_symbol(symbol))
+    }
+
     /// Toggles the checkbox state.
+    ///
+    /// A partially checked box becomes checked.
     pub fn toggle(&mut self) -> EventResult {
-        let checked = !self.checked;
+        let checked = !self.is_checked();
         self.set_checked(checked)
     }
 
@@ -67,8 +152,20 @@ impl Checkbox {
     }
 
     /// Returns `true` if the checkbox is checked.
+    ///
+    /// Returns `false` if it is only partially checked.
     pub fn is_checked(&self) -> bool {
-        self.checked
+        self.state == CheckState::Checked
+    }
+
+    /// Returns `true` if the checkbox is partially checked.
+    pub fn is_partial(&self) -> bool {
+        self.state == CheckState::Partial
+    }
+
+    /// Returns the current state of the checkbox.
+    pub fn get_state(&self) -> CheckState {
+        self.state
     }
 
     /// Uncheck the checkbox.
@@ -87,19 +184,53 @@ impl Checkbox {
 
     /// Sets the checkbox state.
     pub fn set_checked(&mut self, checked: bool) -> EventResult {
-        self.checked = checked;
-        if let Some(ref on_change) = self.on_change {
-            let on_change = Rc::clone(on_change);
-            EventResult::with_cb(move |s| on_change(s, checked))
+        self.set_state(if checked {
+            CheckState::Checked
         } else {
-            EventResult::Consumed(None)
+            CheckState::Unchecked
+        })
+    }
+
+    /// Sets the checkbox state, possibly to partially checked.
+    pub fn set_state(&mut self, state: CheckState) -> EventResult {
+        self.state = state;
+
+        let on_change = self.on_change.clone();
+        let on_state_change = self.on_state_change.clone();
+        if on_change.is_none() && on_state_change.is_none() {
+            return EventResult::Consumed(None);
         }
+
+        let checked = self.is_checked();
+        EventResult::with_cb(move |s| {
+            if let Some(ref on_change) = on_change {
+                on_change(s, checked);
+            }
+            if let Some(ref on_state_change) = on_state_change {
+                on_state_change(s, state);
+            }
+        })
+    }
+
+    /// Sets the checkbox state, possibly to partially checked.
+    ///
+    /// Chainable variant.
+    pub fn state(self, state: CheckState) -> Self {
+        self.with(|s| {
+            s.set_state(state);
+        })
     }
 
     fn draw_internal(&self, printer: &Printer<'_, '_>) {
         printer.print((0, 0), "[ ]");
-        if self.checked {
-            printer.print((1, 0), "X");
+        match self.state {
+            CheckState::Checked => {
+                printer.print((1, 0), &self.checked_symbol)
+            }
+            CheckState::Partial => {
+                printer.print((1, 0), &self.partial_symbol)
+            }
+            CheckState::Unchecked => (),
         }
     }
 }
@@ -136,4 +267,14 @@ impl View for Checkbox {
             _ => EventResult::Ignored,
         }
     }
+
+    fn save_state(&self) -> Option<ViewState> {
+        Some(ViewState::Checked(self.is_checked()))
+    }
+
+    fn restore_state(&mut self, state: &ViewState) {
+        if let ViewState::Checked(checked) = *state {
+            self.set_checked(checked);
+        }
+    }
 }
@@ -1,6 +1,6 @@
 use crate::direction::Direction;
 use crate::event::{Event, EventResult, Key};
-use crate::view::{View, ViewWrapper};
+use crate::view::{FocusPolicy, View, ViewWrapper};
 
 /// Adds circular focus to a wrapped view.
 ///
@@ -60,6 +60,14 @@ impl<T: View> CircularFocus<T> {
 impl<T: View> ViewWrapper for CircularFocus<T> {
     wrap_impl!(self.view: T);
 
+    fn wrap_focus_policy(&self) -> FocusPolicy {
+        if self.wrap_tab {
+            FocusPolicy::Wrap
+        } else {
+            self.view.focus_policy()
+        }
+    }
+
     fn wrap_on_event(&mut self, event: Event) -> EventResult {
         match (self.view.on_event(event.clone()), event) {
             (EventResult::Ignored, Event::Key(Key::Tab)) if self.wrap_tab => {
@@ -1,11 +1,11 @@
 use crate::logger;
 use crate::theme;
+use crate::utils::width::TextWidth;
 use crate::vec::Vec2;
 use crate::view::View;
 use crate::views;
 use crate::Printer;
 
-use unicode_width::UnicodeWidthStr;
 
 #[derive(Clone, Debug, PartialEq)]
 enum ModuleFilter {
@@ -1,17 +1,22 @@
 use crate::align::*;
 use crate::direction::{Absolute, Direction, Relative};
-use crate::event::{AnyCb, Event, EventResult, Key};
+use crate::event::{AnyCb, Event, EventResult, Key, MouseEvent};
 use crate::rect::Rect;
 use crate::theme::ColorStyle;
+use crate::utils::time::Instant;
+use crate::utils::width::TextWidth;
 use crate::vec::Vec2;
-use crate::view::{Margins, Selector, View};
-use crate::views::{Button, DummyView, SizedView, TextView, ViewBox};
+use crate::view::{on_child_edge, Margins, Selector, View};
+use crate::views::{
+    Button, DummyView, LayerPosition, SizedView, StackView, TextView, ViewBox,
+};
 use crate::Cursive;
 use crate::Printer;
 use crate::With;
 use std::cell::Cell;
-use std::cmp::max;
-use unicode_width::UnicodeWidthStr;
+use std::cmp::{max, min};
+use std::rc::Rc;
+use std::time::Duration;
 
 /// Identifies currently focused element in [`Dialog`].
 ///
@@ -24,9 +29,27 @@ pub enum DialogFocus {
     Button(usize),
 }
 
+/// How the buttons of a [`Dialog`] are arranged.
+///
+/// [`Dialog`]: struct.Dialog.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ButtonLayout {
+    /// All buttons on a single row.
+    ///
+    /// This is the default.
+    Horizontal,
+    /// One button per row.
+    Vertical,
+    /// Buttons fill a row, and continue on the next one when the dialog is
+    /// too narrow.
+    Wrap,
+}
+
 struct ChildButton {
     button: SizedView<Button>,
     offset: Cell<Vec2>,
+    // Row this button is on, from the last layout.
+    row: Cell<usize>,
 }
 
 impl ChildButton {
@@ -34,11 +57,20 @@ impl ChildButton {
     where
         F: 'static + Fn(&mut Cursive),
     {
+        Self::from_button(Button::new(label, cb))
+    }
+
+    pub fn from_button(button: Button) -> Self {
         ChildButton {
-            button: SizedView::new(Button::new(label, cb)),
+            button: SizedView::new(button),
             offset: Cell::new(Vec2::zero()),
+            row: Cell::new(0),
         }
     }
+
+    fn is_enabled(&self) -> bool {
+        self.button.view.is_enabled()
+    }
 }
 
 /// Popup-like view with a main content, and optional buttons under it.
@@ -76,8 +108,33 @@ pub struct Dialog {
     // How to align the buttons under the view.
     align: Align,
 
+    // How to arrange the buttons.
+    button_layout: ButtonLayout,
+
+    // Button to focus first when the dialog receives focus, if any.
+    default_button: Option<usize>,
+
     // `true` when we needs to relayout
     invalidated: bool,
+
+    // Delay after which the dialog closes itself.
+    dismiss_after: Option<Duration>,
+
+    // When the dialog was first laid out, to start the countdown.
+    shown_at: Option<Instant>,
+
+    // Button showing the remaining time, with its original label.
+    countdown_button: Option<(usize, String)>,
+
+    // Seconds shown by the countdown button.
+    countdown_shown: Option<u128>,
+
+    // Identifies this dialog for its dismiss timer, until the delay
+    // changes.
+    dismiss_token: Rc<()>,
+
+    // Set once the dismiss timer is scheduled.
+    dismiss_scheduled: bool,
 }
 
 new_default!(Dialog);
@@ -101,7 +158,15 @@ impl Dialog {
             padding: Margins::new(1, 1, 0, 0),
             borders: Margins::new(1, 1, 1, 1),
             align: Align::top_right(),
+            button_layout: ButtonLayout::Horizontal,
+            default_button: None,
             invalidated: true,
+            dismiss_after: None,
+            shown_at: None,
+            countdown_button: None,
+            countdown_shown: None,
+            dismiss_token: Rc::new(()),
+            dismiss_scheduled: false,
         }
     }
 
@@ -173,11 +238,120 @@ impl Dialog {
         self.invalidate();
     }
 
+    /// Adds an already-built button to the dialog.
+    ///
+    /// This lets you customize the button first, for example with
+    /// [`Button::style`](struct.Button.html#method.style).
+    ///
+    /// Chainable variant.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::views::{Button, Dialog};
+    /// # use cursive::theme::BaseColor;
+    /// let dialog = Dialog::text("Remove all files?")
+    ///     .button_view(Button::new("Delete", |s| s.quit())
+    ///         .style(BaseColor::Red))
+    ///     .dismiss_button("Cancel")
+    ///     .default_button(1);
+    /// ```
+    pub fn button_view(self, button: Button) -> Self {
+        self.with(|s| s.add_button_view(button))
+    }
+
+    /// Adds an already-built button to the dialog.
+    pub fn add_button_view(&mut self, button: Button) {
+        self.buttons.push(ChildButton::from_button(button));
+        self.invalidate();
+    }
+
+    /// Returns a mutable reference to the button at the given position.
+    ///
+    /// Returns `None` if `i >= self.buttons_len()`.
+    pub fn get_button_mut(&mut self, i: usize) -> Option<&mut Button> {
+        self.invalidate();
+        self.buttons.get_mut(i).map(|b| &mut b.button.view)
+    }
+
+    /// Enables or disables the button at the given position.
+    ///
+    /// A disabled button cannot be focused. If it currently has the focus,
+    /// the focus moves to the next enabled button, or back to the content.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i >= self.buttons_len()`.
+    pub fn set_button_enabled(&mut self, i: usize, enabled: bool) {
+        self.buttons[i].button.view.set_enabled(enabled);
+
+        if !enabled && self.focus == DialogFocus::Button(i) {
+            self.focus = match self
+                .next_button(i)
+                .or_else(|| self.previous_button(i))
+            {
+                Some(j) => DialogFocus::Button(j),
+                None => {
+                    self.content.take_focus(Direction::none());
+                    DialogFocus::Content
+                }
+            };
+        }
+    }
+
+    /// Sets the button to focus when the dialog receives the focus.
+    ///
+    /// By default (or when given `None`), the content is focused first.
+    pub fn set_default_button(&mut self, i: Option<usize>) {
+        self.default_button = i;
+    }
+
+    /// Sets the button to focus when the dialog receives the focus.
+    ///
+    /// Chainable variant.
+    pub fn default_button(self, i: usize) -> Self {
+        self.with(|s| s.set_default_button(Some(i)))
+    }
+
     /// Returns the number of buttons on this dialog.
     pub fn buttons_len(&self) -> usize {
         self.buttons.len()
     }
 
+    /// Sets how the buttons are arranged.
+    ///
+    /// Defaults to `ButtonLayout::Horizontal`.
+    ///
+    /// When the buttons are on several rows, `Up` and `Down` move the focus
+    /// between rows, and `Tab` goes through the buttons in order.
+    pub fn set_button_layout(&mut self, layout: ButtonLayout) {
+        self.button_layout = layout;
+        self.invalidate();
+    }
+
+    /// Sets how the buttons are arranged.
+    ///
+    /// Chainable variant.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::views::{ButtonLayout, Dialog};
+    /// let dialog = Dialog::text("Save changes?")
+    ///     .button("Save", |s| s.quit())
+    ///     .button("Discard", |s| s.quit())
+    ///     .dismiss_button("Cancel")
+    ///     .button_layout(ButtonLayout::Wrap);
+    /// ```
+    pub fn button_layout(self, layout: ButtonLayout) -> Self {
+        self.with(|s| s.set_button_layout(layout))
+    }
+
+    /// Returns how the buttons are arranged.
+    pub fn get_button_layout(&self) -> ButtonLayout {
+        self.button_layout
+    }
+
     /// Removes any button from `self`.
     pub fn clear_buttons(&mut self) {
         self.buttons.clear();
@@ -203,6 +377,11 @@ impl Dialog {
         self
     }
 
+    /// Sets the horizontal alignment for the buttons, if any.
+    pub fn set_h_align(&mut self, h: HAlign) {
+        self.align.h = h;
+    }
+
     /*
      * Commented out because currently un-implemented.
      *
@@ -219,7 +398,7 @@ impl Dialog {
     /// Shortcut method to add a button that will dismiss the dialog.
     pub fn dismiss_button<S: Into<String>>(self, label: S) -> Self {
         self.button(label, |s| {
-            s.pop_layer();
+            s.close_layer();
         })
     }
 
@@ -290,27 +469,117 @@ impl Dialog {
         self.focus
     }
 
+    /// Moves the focus to the given element.
+    ///
+    /// Returns `false` if the element could not take the focus, for
+    /// example if the button is disabled or doesn't exist.
+    pub fn set_focus(&mut self, focus: DialogFocus) -> bool {
+        let accepted = match focus {
+            DialogFocus::Content => {
+                self.content.take_focus(Direction::none())
+            }
+            DialogFocus::Button(i) => self.is_button_enabled(i),
+        };
+
+        if accepted {
+            self.focus = focus;
+        }
+
+        accepted
+    }
+
     // Private methods
 
+    // Returns `true` if the button `i` exists and is enabled.
+    fn is_button_enabled(&self, i: usize) -> bool {
+        i < self.buttons.len() && self.buttons[i].is_enabled()
+    }
+
+    // Returns the first enabled button after `i`, if any.
+    fn next_button(&self, i: usize) -> Option<usize> {
+        (i + 1..self.buttons.len()).find(|&j| self.buttons[j].is_enabled())
+    }
+
+    // Returns the last enabled button before `i`, if any.
+    fn previous_button(&self, i: usize) -> Option<usize> {
+        (0..i).rev().find(|&j| self.buttons[j].is_enabled())
+    }
+
+    // Returns the first enabled button, if any.
+    fn first_button(&self) -> Option<usize> {
+        self.buttons.iter().position(ChildButton::is_enabled)
+    }
+
+    // Returns the enabled button closest to `i` on the row above or below.
+    fn button_in_row(&self, i: usize, below: bool) -> Option<usize> {
+        let row = self.buttons[i].row.get();
+        let target = if below { row + 1 } else { row.checked_sub(1)? };
+        let x = self.buttons[i].offset.get().x;
+
+        self.buttons
+            .iter()
+            .enumerate()
+            .filter(|&(_, b)| b.row.get() == target && b.is_enabled())
+            .min_by_key(|&(_, b)| {
+                let bx = b.offset.get().x;
+                max(bx, x) - min(bx, x)
+            })
+            .map(|(j, _)| j)
+    }
+
+    // Assigns a row to each button, fitting them in `width`.
+    //
+    // Returns the size taken by the buttons, without the blank row above.
+    fn arrange_buttons(&self, width: usize) -> Vec2 {
+        let mut row = 0;
+        // Size of the current row, and of all the rows before it.
+        let mut current = Vec2::zero();
+        let mut total = Vec2::zero();
+
+        for button in &self.buttons {
+            let size = button.button.size;
+            let starts_row = match self.button_layout {
+                ButtonLayout::Horizontal => false,
+                ButtonLayout::Vertical => true,
+                ButtonLayout::Wrap => current.x + 1 + size.x > width,
+            };
+
+            if current.x > 0 && starts_row {
+                total.x = max(total.x, current.x);
+                total.y += current.y;
+                current = Vec2::zero();
+                row += 1;
+            }
+
+            button.row.set(row);
+            if current.x > 0 {
+                // Keep 1 blank between two buttons
+                current.x += 1;
+            }
+            current = Vec2::new(current.x + size.x, max(current.y, size.y));
+        }
+
+        Vec2::new(max(total.x, current.x), total.y + current.y)
+    }
+
     // An event is received while the content is in focus
     fn on_event_content(&mut self, event: Event) -> EventResult {
-        match self.content.on_event(
-            event.relativized((self.padding + self.borders).top_left()),
-        ) {
-            EventResult::Ignored => {
-                if self.buttons.is_empty() {
-                    EventResult::Ignored
-                } else {
-                    match event {
-                        Event::Key(Key::Down) | Event::Key(Key::Tab) => {
-                            // Default to leftmost button when going down.
-                            self.focus = DialogFocus::Button(0);
-                            EventResult::Consumed(None)
-                        }
-                        _ => EventResult::Ignored,
-                    }
+        let result = self
+            .content
+            .on_event(
+                event.relativized((self.padding + self.borders).top_left()),
+            )
+            .or_else(|| on_child_edge(&mut self.content, &event));
+        match result {
+            EventResult::Ignored => match (event, self.first_button()) {
+                (Event::Key(Key::Down), Some(i))
+                | (Event::Key(Key::Tab), Some(i)) => {
+                    // Default to leftmost button when going down.
+                    self.focus = DialogFocus::Button(i);
+                    EventResult::Consumed(None)
                 }
-            }
+                _ => EventResult::Ignored,
+            },
             res => res,
         }
     }
@@ -328,20 +597,22 @@ impl Dialog {
         match result {
             EventResult::Ignored => {
                 match event {
-                    // Up goes back to the content
-                    Event::Key(Key::Up) => {
-                        if self.content.take_focus(Direction::down()) {
-                            self.focus = DialogFocus::Content;
-                            EventResult::Consumed(None)
-                        } else {
-                            EventResult::Ignored
+                    Event::Key(Key::Down) => {
+                        match self.button_in_row(button_id, true) {
+                            Some(i) => {
+                                self.focus = DialogFocus::Button(i);
+                                EventResult::Consumed(None)
+                            }
+                            None => EventResult::Ignored,
                         }
                     }
-                    Event::Shift(Key::Tab)
-                        if self.focus == DialogFocus::Button(0) =>
-                    {
-                        // If we're at the first button, jump back to the content.
-                        if self.content.take_focus(Direction::back()) {
+                    Event::Key(Key::Up) => {
+                        if let Some(i) = self.button_in_row(button_id, false) {
+                            self.focus = DialogFocus::Button(i);
+                            EventResult::Consumed(None)
+                        } else if self.content.take_focus(Direction::down())
+                        {
+                            // Up goes back to the content
                             self.focus = DialogFocus::Content;
                             EventResult::Consumed(None)
                         } else {
@@ -349,40 +620,43 @@ impl Dialog {
                         }
                     }
                     Event::Shift(Key::Tab) => {
-                        // Otherwise, jump to the previous button.
-                        if let DialogFocus::Button(ref mut i) = self.focus {
-                            // This should always be the case.
-                            *i -= 1;
+                        match self.previous_button(button_id) {
+                            // Jump to the previous button.
+                            Some(i) => {
+                                self.focus = DialogFocus::Button(i);
+                                EventResult::Consumed(None)
+                            }
+                            // At the first button, jump back to the content.
+                            None if self
+                                .content
+                                .take_focus(Direction::back()) =>
+                            {
+                                self.focus = DialogFocus::Content;
+                                EventResult::Consumed(None)
+                            }
+                            None => EventResult::Ignored,
                         }
-                        EventResult::Consumed(None)
                     }
-                    Event::Key(Key::Tab)
-                        if self.focus
-                            == DialogFocus::Button(
-                                self.buttons.len().saturating_sub(1),
-                            ) =>
-                    {
-                        // End of the line
-                        EventResult::Ignored
-                    }
-                    Event::Key(Key::Tab) => {
-                        // Otherwise, jump to the next button.
-                        if let DialogFocus::Button(ref mut i) = self.focus {
-                            // This should always be the case.
-                            *i += 1;
+                    // Tab, Left and Right move to other buttons.
+                    // Disabled buttons are skipped.
+                    Event::Key(Key::Tab) | Event::Key(Key::Right) => {
+                        match self.next_button(button_id) {
+                            Some(i) => {
+                                self.focus = DialogFocus::Button(i);
+                                EventResult::Consumed(None)
+                            }
+                            // End of the line
+                            None => EventResult::Ignored,
                         }
-                        EventResult::Consumed(None)
-                    }
-                    // Left and Right move to other buttons
-                    Event::Key(Key::Right)
-                        if button_id + 1 < self.buttons.len() =>
-                    {
-                        self.focus = DialogFocus::Button(button_id + 1);
-                        EventResult::Consumed(None)
                     }
-                    Event::Key(Key::Left) if button_id > 0 => {
-                        self.focus = DialogFocus::Button(button_id - 1);
-                        EventResult::Consumed(None)
+                    Event::Key(Key::Left) => {
+                        match self.previous_button(button_id) {
+                            Some(i) => {
+                                self.focus = DialogFocus::Button(i);
+                                EventResult::Consumed(None)
+                            }
+                            None => EventResult::Ignored,
+                        }
                     }
                     _ => EventResult::Ignored,
                 }
@@ -392,51 +666,62 @@ impl Dialog {
     }
 
     fn draw_buttons(&self, printer: &Printer<'_, '_>) -> Option<usize> {
-        let mut buttons_height = 0;
-        // Current horizontal position of the next button we'll draw.
-
-        // Sum of the sizes + len-1 for margins
-        let width = self
-            .buttons
-            .iter()
-            .map(|button| button.button.size.x)
-            .sum::<usize>()
-            + self.buttons.len().saturating_sub(1);
         let overhead = self.padding + self.borders;
         if printer.size.x < overhead.horizontal() {
             return None;
         }
-        let mut offset = overhead.left
-            + self
-                .align
-                .h
-                .get_offset(width, printer.size.x - overhead.horizontal());
-
-        let overhead_bottom = self.padding.bottom + self.borders.bottom + 1;
+        let available = printer.size.x - overhead.horizontal();
+        let size = self.arrange_buttons(available);
+
+        let overhead_bottom = self.padding.bottom + self.borders.bottom;
+        let mut y = printer.size.y.checked_sub(overhead_bottom + size.y)?;
+
+        for row in 0..=self.buttons.last().map_or(0, |b| b.row.get()) {
+            let buttons = || {
+                self.buttons
+                    .iter()
+                    .enumerate()
+                    .filter(move |&(_, b)| b.row.get() == row)
+            };
 
-        let y = match printer.size.y.checked_sub(overhead_bottom) {
-            Some(y) => y,
-            None => return None,
-        };
+            // Sum of the sizes + len-1 for margins
+            let width = buttons()
+                .map(|(_, button)| button.button.size.x + 1)
+                .sum::<usize>()
+                .saturating_sub(1);
+            let height = buttons()
+                .map(|(_, button)| button.button.size.y)
+                .max()
+                .unwrap_or(0);
+
+            // Current horizontal position of the next button we'll draw.
+            let mut offset =
+                overhead.left + self.align.h.get_offset(width, available);
+
+            for (i, button) in buttons() {
+                let size = button.button.size;
+                // Add some special effect to the focused button
+                let position = Vec2::new(offset, y);
+                button.offset.set(position);
+                button.button.draw(
+                    &printer
+                        .offset(position)
+                        .cropped(size)
+                        .focused(self.focus == DialogFocus::Button(i)),
+                );
+                // Keep 1 blank between two buttons
+                offset += size.x + 1;
+            }
 
-        for (i, button) in self.buttons.iter().enumerate() {
-            let size = button.button.size;
-            // Add some special effect to the focused button
-            let position = Vec2::new(offset, y);
-            button.offset.set(position);
-            button.button.draw(
-                &printer
-                    .offset(position)
-                    .cropped(size)
-                    .focused(self.focus == DialogFocus::Button(i)),
-            );
-            // Keep 1 blank between two buttons
-            offset += size.x + 1;
-            // Also keep 1 blank above the buttons
-            buttons_height = max(buttons_height, size.y + 1);
+            y += height;
         }
 
-        Some(buttons_height)
+        // Also keep 1 blank above the buttons
+        if self.buttons.is_empty() {
+            Some(0)
+        } else {
+            Some(size.y + 1)
+        }
     }
 
     fn draw_content(&self, printer: &Printer<'_, '_>, buttons_height: usize) {
@@ -504,7 +789,10 @@ impl Dialog {
                 // If position fits there...
                 position.fits_in_rect(btn.offset.get(), btn.button.size)
             }) {
-                self.focus = DialogFocus::Button(i);
+                // Disabled buttons can't take the focus.
+                if self.buttons[i].is_enabled() {
+                    self.focus = DialogFocus::Button(i);
+                }
             } else if position.fits_in_rect(
                 (self.padding + self.borders).top_left(),
                 self.content.size,
@@ -516,11 +804,169 @@ impl Dialog {
         }
     }
 
+    /// Closes the dialog automatically after the given delay.
+    ///
+    /// The countdown starts when the dialog is first shown. `None` disables
+    /// it.
+    ///
+    /// A timer then closes the layer containing the dialog, even if other
+    /// layers were added above it.
+    pub fn set_dismiss_after(&mut self, delay: Option<Duration>) {
+        self.dismiss_after = delay;
+        self.shown_at = None;
+        self.dismiss_token = Rc::new(());
+        self.dismiss_scheduled = false;
+        self.invalidate();
+    }
+
+    /// Closes the dialog automatically after the given delay.
+    ///
+    /// Chainable variant.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cursive::views::Dialog;
+    /// # use std::time::Duration;
+    /// let dialog = Dialog::text("Copied!")
+    ///     .dismiss_button("Ok")
+    ///     .dismiss_after(Duration::from_secs(3))
+    ///     .countdown_button(0);
+    /// ```
+    pub fn dismiss_after(self, delay: Duration) -> Self {
+        self.with(|s| s.set_dismiss_after(Some(delay)))
+    }
+
+    /// Returns the time left before the dialog closes itself, if any.
+    pub fn remaining_time(&self) -> Option<Duration> {
+        let delay = self.dismiss_after?;
+        Some(match self.shown_at {
+            Some(start) => {
+                delay.checked_sub(start.elapsed()).unwrap_or_default()
+            }
+            None => delay,
+        })
+    }
+
+    /// Shows the remaining seconds in the label of the given button.
+    ///
+    /// This only has an effect with `set_dismiss_after`. `None` restores
+    /// the original label.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i >= self.buttons_len()`.
+    pub fn set_countdown_button(&mut self, i: Option<usize>) {
+        if let Some((j, label)) = self.countdown_button.take() {
+            if let Some(button) = self.buttons.get_mut(j) {
+                button.button.view.set_label_raw(label);
+            }
+        }
+
+        if let Some(i) = i {
+            let label = self.buttons[i].button.view.label().to_string();
+            self.countdown_button = Some((i, label));
+        }
+        self.invalidate();
+    }
+
+    /// Shows the remaining seconds in the label of the given button.
+    ///
+    /// Chainable variant.
+    pub fn countdown_button(self, i: usize) -> Self {
+        self.with(|s| s.set_countdown_button(Some(i)))
+    }
+
+    // Returns the seconds the countdown should show, rounded up so it ends
+    // at 1.
+    fn countdown_seconds(&self) -> Option<u128> {
+        self.countdown_button.as_ref()?;
+        let remaining = self.remaining_time()?;
+        Some(remaining.as_millis().div_ceil(1000))
+    }
+
+    // Updates the countdown label, if any.
+    fn update_countdown(&mut self) {
+        let seconds = match self.countdown_seconds() {
+            Some(seconds) => seconds,
+            None => return,
+        };
+        let (i, label) = match self.countdown_button {
+            Some((i, ref label)) => (i, label),
+            None => return,
+        };
+
+        let label = match label
+            .strip_prefix('<')
+            .and_then(|label| label.strip_suffix('>'))
+        {
+            Some(inner) => format!("<{} ({})>", inner, seconds),
+            None => format!("{} ({})", label, seconds),
+        };
+
+        if let Some(button) = self.buttons.get_mut(i) {
+            button.button.view.set_label_raw(label);
+        }
+        if self.countdown_shown == Some(seconds) {
+            return;
+        }
+        self.countdown_shown = Some(seconds);
+
+        // Wakes up the event loop when the label should change, so it is
+        // laid out again.
+        let remaining = self.remaining_time().unwrap_or_default();
+        if seconds > 1 {
+            let next = Duration::from_secs(seconds as u64 - 1);
+            let delay = remaining.checked_sub(next).unwrap_or_default();
+            crate::cursive::set_view_timeout(delay, |_| ());
+        }
+    }
+
+    // Schedules the timer closing the layer of this dialog, once shown.
+    fn schedule_dismiss(&mut self) {
+        let remaining = match self.remaining_time() {
+            Some(remaining) if !self.dismiss_scheduled => remaining,
+            _ => return,
+        };
+
+        let token = Rc::downgrade(&self.dismiss_token);
+        self.dismiss_scheduled =
+            crate::cursive::set_view_timeout(remaining, move |s| {
+                let token = match token.upgrade() {
+                    Some(token) => token,
+                    None => return,
+                };
+                if let Some(layer) = find_layer(s.screen_mut(), &token) {
+                    s.screen_mut().close_layer_at(layer);
+                }
+            });
+    }
+
     fn invalidate(&mut self) {
         self.invalidated = true;
     }
 }
 
+// Finds the layer containing the dialog with the given dismiss token.
+fn find_layer(
+    screen: &mut StackView, token: &Rc<()>,
+) -> Option<LayerPosition> {
+    (0..screen.len()).map(LayerPosition::FromBack).find(|&position| {
+        let mut found = false;
+        if let Some(layer) = screen.get_mut(position) {
+            layer.call_on_any(
+                &Selector::All,
+                Box::new(|any| {
+                    if let Some(dialog) = any.downcast_mut::<Dialog>() {
+                        found |= Rc::ptr_eq(&dialog.dismiss_token, token);
+                    }
+                }),
+            );
+        }
+        found
+    })
+}
+
 impl View for Dialog {
     fn draw(&self, printer: &Printer<'_, '_>) {
         // This will be the buttons_height used by the buttons.
@@ -542,16 +988,16 @@ impl View for Dialog {
         let nomans_land = self.padding.combined() + self.borders.combined();
 
         // Buttons are not flexible, so their size doesn't depend on ours.
-        let mut buttons_size = Vec2::new(0, 0);
-
-        // Start with the inter-button space.
-        buttons_size.x += self.buttons.len().saturating_sub(1);
-
         for button in &mut self.buttons {
-            let s = button.button.view.required_size(req);
-            buttons_size.x += s.x;
-            buttons_size.y = max(buttons_size.y, s.y + 1);
+            button.button.size = button.button.view.required_size(req);
         }
+        let buttons_size = if self.buttons.is_empty() {
+            Vec2::zero()
+        } else {
+            // Also keep 1 blank above the buttons
+            self.arrange_buttons(req.x.saturating_sub(nomans_land.x))
+                + (0, 1)
+        };
 
         // We also remove one row for the buttons.
         let taken = nomans_land + Vec2::new(0, buttons_size.y);
@@ -581,18 +1027,27 @@ impl View for Dialog {
     }
 
     fn layout(&mut self, mut size: Vec2) {
+        if self.dismiss_after.is_some() && self.shown_at.is_none() {
+            self.shown_at = Some(Instant::now());
+        }
+        self.schedule_dismiss();
+        self.update_countdown();
+
         // Padding and borders are taken, sorry.
         // TODO: handle border-less themes?
         let taken = self.borders.combined() + self.padding.combined();
         size = size.saturating_sub(taken);
 
         // Buttons are kings, we give them everything they want.
-        let mut buttons_height = 0;
         for button in self.buttons.iter_mut().rev() {
             let size = button.button.required_size(size);
-            buttons_height = max(buttons_height, size.y + 1);
             button.button.layout(size);
         }
+        let mut buttons_height = if self.buttons.is_empty() {
+            0
+        } else {
+            self.arrange_buttons(size.x).y + 1
+        };
 
         // Poor content will have to make do with what's left.
         if buttons_height > size.y {
@@ -606,15 +1061,43 @@ impl View for Dialog {
     }
 
     fn on_event(&mut self, event: Event) -> EventResult {
+        // Drags pass over the content without taking the focus.
+        if let Event::Mouse {
+            event: MouseEvent::DragOver,
+            ..
+        } = event
+        {
+            return self.content.on_event(
+                event.relativized((self.padding + self.borders).top_left()),
+            );
+        }
+
         // First: some mouse events can instantly change the focus.
         self.check_focus_grab(&event);
 
-        match self.focus {
+        let mnemonic = match event {
+            Event::AltChar(c) => Some(c),
+            _ => None,
+        };
+
+        let result = match self.focus {
             // If we are on the content, we can only go down.
             // TODO: Careful if/when we add buttons elsewhere on the dialog!
             DialogFocus::Content => self.on_event_content(event),
             // If we are on a button, we have more choice
             DialogFocus::Button(i) => self.on_event_button(event, i),
+        };
+
+        match (result, mnemonic) {
+            // Buttons can be activated by their mnemonic, even when they
+            // are not focused.
+            (EventResult::Ignored, Some(c)) => self
+                .buttons
+                .iter_mut()
+                .map(|button| button.button.on_event(Event::AltChar(c)))
+                .find(EventResult::is_consumed)
+                .unwrap_or(EventResult::Ignored),
+            (result, _) => result,
         }
     }
 
@@ -626,23 +1109,32 @@ impl View for Dialog {
             | Direction::Rel(Relative::Front)
             | Direction::Abs(Absolute::Left)
             | Direction::Abs(Absolute::Up) => {
+                // The default button, if any, goes first.
+                if let Some(i) = self.default_button {
+                    if self.is_button_enabled(i) {
+                        self.focus = DialogFocus::Button(i);
+                        return true;
+                    }
+                }
+
                 // Forward focus: content, then buttons
                 if self.content.take_focus(source) {
                     self.focus = DialogFocus::Content;
                     true
-                } else if self.buttons.is_empty() {
-                    false
-                } else {
-                    self.focus = DialogFocus::Button(0);
+                } else if let Some(i) = self.first_button() {
+                    self.focus = DialogFocus::Button(i);
                     true
+                } else {
+                    false
                 }
             }
             Direction::Rel(Relative::Back)
             | Direction::Abs(Absolute::Right)
             | Direction::Abs(Absolute::Down) => {
                 // Back focus: first buttons, then content
-                if !self.buttons.is_empty() {
-                    self.focus = DialogFocus::Button(self.buttons.len() - 1);
+                let last = self.previous_button(self.buttons.len());
+                if let Some(i) = last {
+                    self.focus = DialogFocus::Button(i);
                     true
                 } else if self.content.take_focus(source) {
                     self.focus = DialogFocus::Content;
@@ -655,8 +1147,9 @@ impl View for Dialog {
     }
 
     fn call_on_any<'a>(
-        &mut self, selector: &Selector<'_>, callback: AnyCb<'a>,
+        &mut self, selector: &Selector<'_>, mut callback: AnyCb<'a>,
     ) {
+        selector.call_on_self(self, &mut callback);
         self.content.call_on_any(selector, callback);
     }
 
@@ -664,6 +1157,13 @@ impl View for Dialog {
         self.content.focus_view(selector)
     }
 
+    fn locate(&self, selector: &Selector<'_>, _: Vec2) -> Option<Rect> {
+        let offset = self.borders.top_left() + self.padding.top_left();
+        self.content
+            .locate(selector, self.content.size)
+            .map(|area| area + offset)
+    }
+
     fn important_area(&self, _: Vec2) -> Rect {
         self.content.important_area(self.content.size)
             + self.borders.top_left()
@@ -671,6 +1171,128 @@ impl View for Dialog {
     }
 
     fn needs_relayout(&self) -> bool {
-        self.invalidated || self.content.needs_relayout()
+        self.invalidated
+            || self.content.needs_relayout()
+            || self.countdown_seconds() != self.countdown_shown
+    }
+
+    fn mark_dirty(&mut self) {
+        self.invalidate();
+        self.content.mark_dirty();
+        for button in &mut self.buttons {
+            button.button.mark_dirty();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dismiss_after() {
+        let mut dialog = Dialog::text("Copied!")
+            .dismiss_button("Ok")
+            .dismiss_after(Duration::from_secs(5))
+            .countdown_button(0);
+
+        dialog.layout(Vec2::new(30, 10));
+        assert_eq!(dialog.get_button_mut(0).unwrap().label(), "<Ok (5)>");
+        // The label is laid out again when the seconds change.
+        assert_eq!(dialog.countdown_shown, Some(5));
+        assert_eq!(dialog.countdown_seconds(), Some(5));
+
+        dialog.set_countdown_button(None);
+        assert_eq!(dialog.get_button_mut(0).unwrap().label(), "<Ok>");
+    }
+
+    #[test]
+    fn dismiss_own_layer() {
+        use crate::backend::puppet;
+        use crate::Cursive;
+
+        let (backend, _puppet) = puppet::Backend::init((40, 10));
+        let mut siv = Cursive::new(move || backend);
+        siv.add_layer(TextView::new("Back"));
+        siv.add_layer(
+            Dialog::text("Copied!").dismiss_after(Duration::from_millis(20)),
+        );
+        siv.refresh();
+
+        // A layer opened above the dialog stays.
+        siv.add_layer(TextView::new("Front"));
+        let start = Instant::now();
+        while siv.screen().len() == 3 {
+            assert!(start.elapsed() < Duration::from_secs(5));
+            siv.step_with_timeout(Duration::from_millis(10));
+        }
+
+        let front = siv.screen_mut().get_mut(LayerPosition::FromFront(0));
+        let front = front.unwrap().as_any_mut().downcast_mut::<TextView>();
+        assert_eq!(front.unwrap().get_content().source(), "Front");
+        assert_eq!(siv.screen().len(), 2);
+    }
+
+    #[test]
+    fn wrapping_buttons() {
+        let mut dialog = Dialog::new()
+            .button("One", |_| ())
+            .button("Two", |_| ())
+            .button("Three", |_| ())
+            .button_layout(ButtonLayout::Wrap);
+
+        // `<One> <Two>` fits in 11 cells, `<Three>` goes below.
+        let size = dialog.required_size(Vec2::new(15, 10));
+        assert_eq!(size, Vec2::new(15, 6));
+
+        dialog.layout(size);
+        let rows: Vec<usize> =
+            dialog.buttons.iter().map(|b| b.row.get()).collect();
+        assert_eq!(rows, vec![0, 0, 1]);
+
+        // Down goes to the next row, Up comes back.
+        assert!(dialog.take_focus(Direction::front()));
+        assert_eq!(dialog.focus(), DialogFocus::Button(0));
+        assert!(dialog.on_event(Event::Key(Key::Down)).is_consumed());
+        assert_eq!(dialog.focus(), DialogFocus::Button(2));
+        assert!(dialog.on_event(Event::Key(Key::Up)).is_consumed());
+        assert_eq!(dialog.focus(), DialogFocus::Button(0));
+    }
+    #[test]
+    fn content_focus_policy() {
+        use crate::view::FocusPolicy;
+        use crate::views::{ListView, TextView};
+
+        // Reports its policy, and leaves it to its parent.
+        struct Field(FocusPolicy);
+
+        impl View for Field {
+            fn draw(&self, _: &Printer<'_, '_>) {}
+
+            fn take_focus(&mut self, _: Direction) -> bool {
+                true
+            }
+
+            fn focus_policy(&self) -> FocusPolicy {
+                self.0
+            }
+        }
+
+        let tab = Event::Key(Key::Tab);
+        let mut dialog =
+            Dialog::around(Field(FocusPolicy::Stop)).button("Ok", |_| ());
+        assert!(dialog.on_event(tab.clone()).is_consumed());
+        assert_eq!(dialog.focus(), DialogFocus::Content);
+
+        let mut dialog =
+            Dialog::around(Field(FocusPolicy::Escape)).button("Ok", |_| ());
+        assert!(dialog.on_event(tab.clone()).is_consumed());
+        assert_eq!(dialog.focus(), DialogFocus::Button(0));
+
+        let mut list = ListView::new()
+            .child("a", Field(FocusPolicy::Wrap))
+            .child("b", TextView::new("b"));
+        assert!(list.on_event(tab).is_consumed());
+        assert_eq!(list.focus(), 0);
     }
 }
@@ -1,15 +1,21 @@
+use crate::clipboard;
 use crate::direction::Direction;
 use crate::event::{Callback, Event, EventResult, Key, MouseEvent};
+use crate::menu::MenuTree;
 use crate::rect::Rect;
-use crate::theme::{ColorStyle, Effect};
+use crate::state::ViewState;
+use crate::theme::{BaseColor, ColorStyle, Effect, PaletteColor};
+use crate::utils::compose::Composer;
 use crate::utils::lines::simple::{simple_prefix, simple_suffix};
+use crate::utils::width::{grapheme_width, TextWidth};
 use crate::vec::Vec2;
-use crate::view::View;
+use crate::view::{Position, View};
+use crate::views::{MenuPopup, OnEventView};
 use crate::{Cursive, Printer, With};
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 use unicode_segmentation::UnicodeSegmentation;
-use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+use unicode_width::UnicodeWidthChar;
 
 /// Closure type for callbacks when the content is modified.
 ///
@@ -22,6 +28,36 @@ pub type OnEdit = dyn Fn(&mut Cursive, &str, usize);
 /// Arguments are the `Cursive` and the content of the input.
 pub type OnSubmit = dyn Fn(&mut Cursive, &str);
 
+/// Closure type for content validation.
+///
+/// Returns an error message if the given content is not valid.
+pub type Validator = dyn Fn(&str) -> Result<(), String>;
+
+/// Closure type for completion.
+///
+/// Returns the possible completions for the given content. Each completion
+/// replaces the entire content.
+pub type Completer = dyn Fn(&str) -> Vec<String>;
+
+/// How the length of an `EditView` content is counted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LengthUnit {
+    /// Counts bytes of UTF-8.
+    Bytes,
+    /// Counts user-perceived characters (grapheme clusters).
+    Graphemes,
+}
+
+impl LengthUnit {
+    /// Returns the length of `text` in this unit.
+    pub fn length(self, text: &str) -> usize {
+        match self {
+            LengthUnit::Bytes => text.len(),
+            LengthUnit::Graphemes => text.graphemes(true).count(),
+        }
+    }
+}
+
 /// Input box where the user can enter and edit text.
 ///
 /// # Examples
@@ -90,6 +126,17 @@ pub struct EditView {
     /// Input will be rejected if it would make the content exceed this width.
     max_content_width: Option<usize>,
 
+    /// Optional limit to the content length, counted in `length_unit`.
+    max_content_length: Option<usize>,
+
+    /// How the content length is counted.
+    length_unit: LengthUnit,
+
+    /// When `true`, a `length/max` counter is shown on the right.
+    show_counter: bool,
+
+    counter_style: ColorStyle,
+
     /// Last display length, to know the possible offset range
     last_length: usize,
 
@@ -110,6 +157,47 @@ pub struct EditView {
     enabled: bool,
 
     style: ColorStyle,
+
+    /// Checks the content after every change.
+    validator: Option<Rc<Validator>>,
+
+    /// Error message from the last validation, if the content is invalid.
+    error: Option<String>,
+
+    /// Style used instead of `style` while the content is invalid.
+    error_style: ColorStyle,
+
+    /// Text being composed at the cursor, not yet part of the content.
+    composer: Composer,
+
+    /// Previously submitted entries, oldest first.
+    history: Vec<String>,
+
+    /// Maximum number of entries kept in `history`. 0 disables it.
+    history_size: usize,
+
+    /// Entry currently shown, when browsing the history.
+    history_pos: Option<usize>,
+
+    /// Content before browsing the history, also used as a prefix filter.
+    history_draft: String,
+
+    /// Gives possible completions on `<Tab>`.
+    completer: Option<Rc<Completer>>,
+
+    /// Completion chosen in the popup, not applied yet.
+    pending_completion: Rc<RefCell<Option<String>>>,
+
+    /// Where we were last drawn, to place the completion popup.
+    last_offset: Cell<Vec2>,
+
+    /// Text shown when the content is empty.
+    placeholder: Option<String>,
+
+    /// When `true`, the placeholder is also shown when focused.
+    persistent_placeholder: bool,
+
+    placeholder_style: ColorStyle,
 }
 
 new_default!(EditView);
@@ -125,10 +213,34 @@ impl EditView {
             on_edit: None,
             on_submit: None,
             max_content_width: None,
+            max_content_length: None,
+            length_unit: LengthUnit::Graphemes,
+            show_counter: false,
+            counter_style: ColorStyle::secondary(),
             secret: false,
             filler: "_".to_string(),
             enabled: true,
             style: ColorStyle::secondary(),
+            validator: None,
+            error: None,
+            error_style: ColorStyle::new(
+                BaseColor::Red.dark(),
+                PaletteColor::View,
+            ),
+            composer: Composer::new(),
+            history: Vec::new(),
+            history_size: 0,
+            history_pos: None,
+            history_draft: String::new(),
+            completer: None,
+            pending_completion: Rc::new(RefCell::new(None)),
+            last_offset: Cell::new(Vec2::zero()),
+            placeholder: None,
+            persistent_placeholder: false,
+            placeholder_style: ColorStyle::new(
+                PaletteColor::Secondary,
+                PaletteColor::Primary,
+            ),
         }
     }
 
@@ -150,6 +262,107 @@ impl EditView {
         self.with(|s| s.set_max_content_width(Some(width)))
     }
 
+    /// Sets a maximum length for the content.
+    ///
+    /// Input will be rejected if it would make the content longer than
+    /// this, counted in the length unit (graphemes by default). Content
+    /// given to `set_content` is not truncated.
+    ///
+    /// Giving `None` means no maximum length is applied.
+    pub fn set_max_content_length(&mut self, length: Option<usize>) {
+        self.max_content_length = length;
+    }
+
+    /// Sets a maximum length for the content.
+    ///
+    /// Chainable variant.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::views::EditView;
+    /// let mut edit = EditView::new().max_content_length(3).show_counter();
+    /// edit.insert_str("abcd");
+    /// assert_eq!(&*edit.get_content(), "abc");
+    /// assert_eq!(edit.get_counter(), "3/3");
+    /// ```
+    pub fn max_content_length(self, length: usize) -> Self {
+        self.with(|s| s.set_max_content_length(Some(length)))
+    }
+
+    /// Sets how the content length is counted.
+    ///
+    /// Defaults to `LengthUnit::Graphemes`.
+    pub fn set_length_unit(&mut self, unit: LengthUnit) {
+        self.length_unit = unit;
+    }
+
+    /// Sets how the content length is counted.
+    ///
+    /// Chainable variant.
+    pub fn length_unit(self, unit: LengthUnit) -> Self {
+        self.with(|s| s.set_length_unit(unit))
+    }
+
+    /// Returns the length of the content, counted in the length unit.
+    pub fn content_length(&self) -> usize {
+        self.length_unit.length(&self.content)
+    }
+
+    /// Returns the text of the character counter.
+    ///
+    /// This is `length/max`, or only the length without a maximum. It can
+    /// be used in a separate label, instead of showing the counter in the
+    /// view.
+    pub fn get_counter(&self) -> String {
+        match self.max_content_length {
+            Some(max) => format!("{}/{}", self.content_length(), max),
+            None => self.content_length().to_string(),
+        }
+    }
+
+    /// Shows the character counter at the right edge of the view.
+    pub fn set_show_counter(&mut self, show: bool) {
+        self.show_counter = show;
+    }
+
+    /// Shows the character counter at the right edge of the view.
+    ///
+    /// Chainable variant.
+    pub fn show_counter(self) -> Self {
+        self.with(|s| s.set_show_counter(true))
+    }
+
+    /// Sets the style used for the character counter.
+    ///
+    /// Defaults to `ColorStyle::secondary()`.
+    pub fn set_counter_style(&mut self, style: ColorStyle) {
+        self.counter_style = style;
+    }
+
+    /// Sets the style used for the character counter.
+    ///
+    /// Chainable variant.
+    pub fn counter_style(self, style: ColorStyle) -> Self {
+        self.with(|s| s.set_counter_style(style))
+    }
+
+    // Width kept on the right for the counter, with a space before it.
+    fn counter_width(&self) -> usize {
+        if !self.show_counter {
+            return 0;
+        }
+        // Keep room for the longest counter, so the input doesn't move.
+        let widest = match self.max_content_length {
+            Some(limit) => {
+                let length = self.content_length().max(limit);
+                format!("{}/{}", length, limit).len()
+            }
+            None => self.get_counter().len(),
+        };
+        widest + 1
+    }
+
     /// If `secret` is `true`, the content won't be displayed in clear.
     ///
     /// Only `*` will be shown.
@@ -222,6 +435,71 @@ impl EditView {
         self.with(|s| s.set_style(style))
     }
 
+    /// Sets a text shown when the content is empty.
+    ///
+    /// By default, it is hidden when the view is focused.
+    pub fn set_placeholder<S: Into<String>>(&mut self, placeholder: S) {
+        self.placeholder = Some(placeholder.into());
+    }
+
+    /// Sets a text shown when the content is empty.
+    ///
+    /// Chainable variant.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::views::EditView;
+    /// let edit = EditView::new().placeholder("Search…");
+    /// ```
+    pub fn placeholder<S: Into<String>>(self, placeholder: S) -> Self {
+        self.with(|s| s.set_placeholder(placeholder))
+    }
+
+    /// Removes the placeholder.
+    pub fn clear_placeholder(&mut self) {
+        self.placeholder = None;
+    }
+
+    /// Returns the placeholder, if any.
+    pub fn get_placeholder(&self) -> Option<&str> {
+        self.placeholder.as_deref()
+    }
+
+    /// If `true`, the placeholder is still shown when the view is focused,
+    /// until some text is entered.
+    ///
+    /// Defaults to `false`.
+    pub fn set_persistent_placeholder(&mut self, persistent: bool) {
+        self.persistent_placeholder = persistent;
+    }
+
+    /// Keeps showing the placeholder when the view is focused.
+    ///
+    /// Chainable variant.
+    pub fn persistent_placeholder(self) -> Self {
+        self.with(|s| s.set_persistent_placeholder(true))
+    }
+
+    /// Sets the style used for the placeholder.
+    ///
+    /// Like the main style, it is reversed when the view is enabled.
+    /// Disabled views use the main style instead.
+    ///
+    /// Defaults to `PaletteColor::Primary` on `PaletteColor::Secondary`, so
+    /// it follows the theme: the text is written in the primary color, on
+    /// the background of the field.
+    pub fn set_placeholder_style(&mut self, style: ColorStyle) {
+        self.placeholder_style = style;
+    }
+
+    /// Sets the style used for the placeholder.
+    ///
+    /// Chainable variant.
+    pub fn placeholder_style(self, style: ColorStyle) -> Self {
+        self.with(|s| s.set_placeholder_style(style))
+    }
+
     /// Sets a mutable callback to be called whenever the content is modified.
     ///
     /// `callback` will be called with the view
@@ -336,6 +614,92 @@ impl EditView {
         self.with(|v| v.set_on_submit(callback))
     }
 
+    /// Sets a validator for the content.
+    ///
+    /// It will be called every time the content changes. While it returns
+    /// an error, the view uses its error style, and `<Enter>` will not
+    /// trigger the `on_submit` callback.
+    ///
+    /// The error message is available through [`get_error`], for instance
+    /// to display it in a `TextView` from the `on_edit` callback.
+    ///
+    /// [`get_error`]: #method.get_error
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::views::EditView;
+    /// let mut edit = EditView::new().validator(|text| {
+    ///     if text.chars().all(|c| c.is_ascii_digit()) {
+    ///         Ok(())
+    ///     } else {
+    ///         Err(String::from("Only digits are allowed"))
+    ///     }
+    /// });
+    ///
+    /// edit.set_content("12a");
+    /// assert_eq!(edit.get_error(), Some("Only digits are allowed"));
+    /// ```
+    pub fn set_validator<F>(&mut self, validator: F)
+    where
+        F: Fn(&str) -> Result<(), String> + 'static,
+    {
+        self.validator = Some(Rc::new(validator));
+        self.validate();
+    }
+
+    /// Sets a validator for the content.
+    ///
+    /// Chainable variant. See [`set_validator`](#method.set_validator).
+    pub fn validator<F>(self, validator: F) -> Self
+    where
+        F: Fn(&str) -> Result<(), String> + 'static,
+    {
+        self.with(|v| v.set_validator(validator))
+    }
+
+    /// Removes the validator, if any.
+    pub fn clear_validator(&mut self) {
+        self.validator = None;
+        self.error = None;
+    }
+
+    /// Returns the error message for the current content, if it is invalid.
+    pub fn get_error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Returns `true` if the current content passed validation.
+    ///
+    /// Always `true` when no validator is set.
+    pub fn is_valid(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// Sets the style used while the content is invalid.
+    ///
+    /// When the view is enabled, the style will be reversed.
+    ///
+    /// Defaults to a red text color.
+    pub fn set_error_style(&mut self, style: ColorStyle) {
+        self.error_style = style;
+    }
+
+    /// Sets the style used while the content is invalid.
+    ///
+    /// Chainable variant.
+    pub fn error_style(self, style: ColorStyle) -> Self {
+        self.with(|s| s.set_error_style(style))
+    }
+
+    // Runs the validator on the current content.
+    fn validate(&mut self) {
+        self.error = match self.validator {
+            Some(ref validator) => validator(&self.content).err(),
+            None => None,
+        };
+    }
+
     /// Enable or disable this view.
     pub fn set_enabled(&mut self, enabled: bool) {
         self.enabled = enabled;
@@ -355,9 +719,11 @@ impl EditView {
         let content = content.into();
         let len = content.len();
 
+        self.history_pos = None;
         self.content = Rc::new(content);
         self.offset = 0;
         self.set_cursor(len);
+        self.validate();
 
         self.make_edit_cb().unwrap_or_else(Callback::dummy)
     }
@@ -384,6 +750,288 @@ impl EditView {
         self.keep_cursor_in_view();
     }
 
+    /// Keeps up to `size` submitted entries in a history.
+    ///
+    /// When enabled, `<Up>` and `<Down>` cycle through these entries, like
+    /// in a shell. Only entries starting with the content typed before
+    /// browsing are shown.
+    ///
+    /// Entries are recorded when `<Enter>` is pressed, if a submit callback
+    /// is set. A size of 0 (the default) disables the history.
+    pub fn set_history_size(&mut self, size: usize) {
+        self.history_size = size;
+        self.trim_history();
+    }
+
+    /// Keeps up to `size` submitted entries in a history.
+    ///
+    /// Chainable variant.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::views::EditView;
+    /// let command_bar = EditView::new()
+    ///     .history_size(100)
+    ///     .on_submit(|s, command| {
+    ///         // Run the command...
+    ///     });
+    /// ```
+    pub fn history_size(self, size: usize) -> Self {
+        self.with(|s| s.set_history_size(size))
+    }
+
+    /// Adds an entry at the end of the history.
+    ///
+    /// Empty entries and repeats of the last entry are ignored.
+    pub fn add_history<S: Into<String>>(&mut self, entry: S) {
+        let entry = entry.into();
+        if entry.is_empty() || self.history.last() == Some(&entry) {
+            return;
+        }
+
+        self.history.push(entry);
+        self.trim_history();
+    }
+
+    /// Returns the history entries, oldest first.
+    pub fn get_history(&self) -> &[String] {
+        &self.history
+    }
+
+    /// Removes all entries from the history.
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+        self.history_pos = None;
+    }
+
+    fn trim_history(&mut self) {
+        if self.history.len() > self.history_size {
+            let extra = self.history.len() - self.history_size;
+            self.history.drain(..extra);
+            self.history_pos = None;
+        }
+    }
+
+    // Shows the previous (or next) history entry matching the draft.
+    //
+    // Returns `None` if there is nothing to show.
+    fn browse_history(&mut self, backward: bool) -> Option<Callback> {
+        if self.history_size == 0 {
+            return None;
+        }
+
+        let pos = self.history_pos;
+        if pos.is_none() {
+            if !backward {
+                return None;
+            }
+            self.history_draft = (*self.content).clone();
+        }
+
+        let draft = &self.history_draft;
+        let content = &*self.content;
+        let matches = |&(_, entry): &(usize, &String)| {
+            entry.starts_with(draft.as_str()) && entry != content
+        };
+
+        let entries = self.history.iter().enumerate();
+        let found = if backward {
+            let end = pos.unwrap_or(self.history.len());
+            entries.take(end).rev().find(matches)
+        } else {
+            entries.skip(pos.unwrap() + 1).find(matches)
+        }
+        .map(|(i, entry)| (i, entry.clone()));
+
+        match found {
+            Some((i, entry)) => {
+                let cb = self.set_content(entry);
+                self.history_pos = Some(i);
+                Some(cb)
+            }
+            // Going down past the newest entry brings back the draft.
+            None if !backward => {
+                let draft = std::mem::take(&mut self.history_draft);
+                Some(self.set_content(draft))
+            }
+            None => None,
+        }
+    }
+
+    /// Sets a function giving completions for the current content.
+    ///
+    /// Pressing `<Tab>` (or calling `complete`) asks for completions. A single
+    /// completion is applied right away; otherwise a popup lets the user
+    /// choose one, with the arrow keys or `<Tab>`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::views::EditView;
+    /// let commands = ["open", "quit", "save", "save-as"];
+    /// let edit = EditView::new().completer(move |content| {
+    ///     commands
+    ///         .iter()
+    ///         .filter(|command| command.starts_with(content))
+    ///         .map(|command| command.to_string())
+    ///         .collect()
+    /// });
+    /// ```
+    pub fn set_completer<F>(&mut self, completer: F)
+    where
+        F: Fn(&str) -> Vec<String> + 'static,
+    {
+        self.completer = Some(Rc::new(completer));
+    }
+
+    /// Sets a function giving completions for the current content.
+    ///
+    /// Chainable variant.
+    pub fn completer<F>(self, completer: F) -> Self
+    where
+        F: Fn(&str) -> Vec<String> + 'static,
+    {
+        self.with(|s| s.set_completer(completer))
+    }
+
+    /// Removes the completion function.
+    pub fn clear_completer(&mut self) {
+        self.completer = None;
+    }
+
+    /// Completes the current content.
+    ///
+    /// Returns a callback, either in response to content change, or to open
+    /// a popup if several completions are possible.
+    ///
+    /// You should run this callback with a `&mut Cursive`.
+    pub fn complete(&mut self) -> Callback {
+        self.try_complete().unwrap_or_else(Callback::dummy)
+    }
+
+    // Returns `None` if there is no completion.
+    fn try_complete(&mut self) -> Option<Callback> {
+        let completer = self.completer.clone()?;
+        let mut completions = completer(&self.content);
+
+        match completions.len() {
+            0 => None,
+            1 => Some(self.set_content(completions.remove(0))),
+            _ => Some(self.make_completion_popup(completions)),
+        }
+    }
+
+    fn make_completion_popup(&self, completions: Vec<String>) -> Callback {
+        let mut tree = MenuTree::new();
+        for completion in completions {
+            let pending = Rc::clone(&self.pending_completion);
+            tree.add_leaf(completion.clone(), move |s| {
+                *pending.borrow_mut() = Some(completion.clone());
+                // Let the view (focused again) apply the completion.
+                s.on_event(Event::Refresh);
+            });
+        }
+
+        // Show the popup under the cursor, aligning the text with it.
+        // Skip the border and padding of the popup.
+        let x = self.content[self.offset..self.cursor].width();
+        let offset = self.last_offset.get() + (x, 1);
+        let offset = offset.saturating_sub((2, 0));
+
+        let tree = Rc::new(tree);
+        Callback::from_fn(move |s| {
+            let current_offset = s.screen().offset();
+            let offset = offset.signed() - current_offset;
+            let popup = OnEventView::new(MenuPopup::new(Rc::clone(&tree)))
+                .on_pre_event_map(Key::Tab, |_| Event::Key(Key::Down))
+                .on_pre_event_map(Event::Shift(Key::Tab), |_| {
+                    Event::Key(Key::Up)
+                });
+            s.screen_mut()
+                .add_layer_at(Position::parent(offset), popup);
+        })
+    }
+
+    // Applies a completion chosen in the popup, if any.
+    fn apply_pending_completion(&mut self) -> Option<Callback> {
+        let completion = self.pending_completion.borrow_mut().take()?;
+        Some(self.set_content(completion))
+    }
+
+    /// Enables or disables dead key composition.
+    ///
+    /// When enabled, typing an accent like `^` or `´` starts a composition,
+    /// completed by the next character: `^` then `e` inserts `ê`.
+    ///
+    /// This is only needed when the terminal doesn't compose characters
+    /// itself. Defaults to `false`.
+    pub fn set_dead_keys(&mut self, enabled: bool) {
+        self.composer.set_dead_keys(enabled);
+    }
+
+    /// Enables dead key composition.
+    ///
+    /// Chainable variant.
+    pub fn dead_keys(self) -> Self {
+        self.with(|s| s.set_dead_keys(true))
+    }
+
+    /// Sets the text being composed at the cursor.
+    ///
+    /// It is shown underlined, but is not part of the content until it is
+    /// committed, either with `commit_composition` or by typing another
+    /// character. Backspace or Escape drops it.
+    ///
+    /// This can be used to integrate an input method. The characters it
+    /// then sends to commit the text, even one event at a time, replace
+    /// the composition instead of being inserted after it.
+    pub fn set_composition<S: Into<String>>(&mut self, text: S) {
+        self.composer.set_text(text);
+    }
+
+    /// Returns the text being composed at the cursor.
+    ///
+    /// This is empty when no composition is going on.
+    pub fn get_composition(&self) -> &str {
+        self.composer.text()
+    }
+
+    /// Inserts the text being composed at the cursor position.
+    ///
+    /// Returns a callback in response to content change.
+    ///
+    /// You should run this callback with a `&mut Cursive`.
+    pub fn commit_composition(&mut self) -> Callback {
+        let text = self.composer.take();
+        self.insert_str(&text)
+    }
+
+    /// Drops the text being composed.
+    pub fn clear_composition(&mut self) {
+        self.composer.clear();
+    }
+
+    /// Insert `text` at the current cursor position.
+    ///
+    /// Returns a callback in response to content change.
+    ///
+    /// You should run this callback with a `&mut Cursive`.
+    pub fn insert_str(&mut self, text: &str) -> Callback {
+        text.chars()
+            .map(|ch| self.insert(ch))
+            .last()
+            .unwrap_or_else(Callback::dummy)
+    }
+
+    // Inserts pasted text, on a single line since this view only holds one.
+    fn paste(&mut self, text: &str) -> Callback {
+        let text = text.trim_end_matches(&['\r', '\n'][..]);
+        let text = text.replace("\r\n", " ");
+        let text = text.replace(&['\r', '\n'][..], " ");
+        self.insert_str(&text)
+    }
+
     /// Insert `ch` at the current cursor position.
     ///
     /// Returns a callback in response to content change.
@@ -401,6 +1049,13 @@ impl EditView {
                 return Callback::dummy();
             }
         }
+        if let Some(max) = self.max_content_length {
+            let mut content = (*self.content).clone();
+            content.insert(self.cursor, ch);
+            if self.length_unit.length(&content) > max {
+                return Callback::dummy();
+            }
+        }
 
         // `make_mut` applies copy-on-write
         // It means it'll just return a ref if no one else has a ref,
@@ -408,8 +1063,10 @@ impl EditView {
 
         Rc::make_mut(&mut self.content).insert(self.cursor, ch);
         self.cursor += ch.len_utf8();
+        self.history_pos = None;
 
         self.keep_cursor_in_view();
+        self.validate();
 
         self.make_edit_cb().unwrap_or_else(Callback::dummy)
     }
@@ -423,8 +1080,10 @@ impl EditView {
         let start = self.cursor;
         let end = self.cursor + len;
         for _ in Rc::make_mut(&mut self.content).drain(start..end) {}
+        self.history_pos = None;
 
         self.keep_cursor_in_view();
+        self.validate();
 
         self.make_edit_cb().unwrap_or_else(Callback::dummy)
     }
@@ -454,7 +1113,7 @@ impl EditView {
             // (either a char, or _)
             let c_len = self.content[self.cursor..]
                 .graphemes(true)
-                .map(UnicodeWidthStr::width)
+                .map(grapheme_width)
                 .next()
                 .unwrap_or(1);
 
@@ -490,6 +1149,17 @@ impl EditView {
             self.offset = self.content.len() - suffix_length;
         }
     }
+
+    // Returns the placeholder, if it should be drawn.
+    fn visible_placeholder(&self, printer: &Printer<'_, '_>) -> Option<&str> {
+        if !self.content.is_empty() || self.composer.is_composing() {
+            return None;
+        }
+        if printer.focused && !self.persistent_placeholder {
+            return None;
+        }
+        self.get_placeholder()
+    }
 }
 
 /// Returns a `&str` with `length` characters `*`.
@@ -503,6 +1173,17 @@ fn make_small_stars(length: usize) -> &'static str {
 
 impl View for EditView {
     fn draw(&self, printer: &Printer<'_, '_>) {
+        self.last_offset.set(printer.offset);
+
+        if self.show_counter {
+            let counter = self.get_counter();
+            let x = printer.size.x.saturating_sub(counter.len());
+            printer.with_color(self.counter_style, |printer| {
+                printer.print((x, 0), &counter);
+            });
+        }
+        let printer = &printer.cropped((self.last_length, 1));
+
         assert_eq!(
             printer.size.x, self.last_length,
             "Was promised {}, received {}",
@@ -510,7 +1191,12 @@ impl View for EditView {
         );
 
         let width = self.content.width();
-        printer.with_color(self.style, |printer| {
+        let style = if self.is_valid() {
+            self.style
+        } else {
+            self.error_style
+        };
+        printer.with_color(style, |printer| {
             let effect = if self.enabled && printer.enabled {
                 Effect::Reverse
             } else {
@@ -566,10 +1252,25 @@ impl View for EditView {
                         );
                     }
                 }
+
+                if let Some(placeholder) = self.visible_placeholder(printer) {
+                    if self.enabled && printer.enabled {
+                        printer.with_color(self.placeholder_style, |printer| {
+                            printer.print((0, 0), placeholder);
+                        });
+                    } else {
+                        printer.print((0, 0), placeholder);
+                    }
+                }
             });
 
-            // Now print cursor
-            if printer.focused {
+            // Text being composed replaces the cursor.
+            if printer.focused && self.composer.is_composing() {
+                let offset = self.content[self.offset..self.cursor].width();
+                printer.with_effect(Effect::Underline, |printer| {
+                    printer.print((offset, 0), self.composer.text());
+                });
+            } else if printer.focused {
                 let c: &str = if self.cursor == self.content.len() {
                     &self.filler
                 } else {
@@ -596,7 +1297,7 @@ impl View for EditView {
     }
 
     fn layout(&mut self, size: Vec2) {
-        self.last_length = size.x;
+        self.last_length = size.x.saturating_sub(self.counter_width());
     }
 
     fn take_focus(&mut self, _: Direction) -> bool {
@@ -604,9 +1305,42 @@ impl View for EditView {
     }
 
     fn on_event(&mut self, event: Event) -> EventResult {
+        // The completion is applied with the next event, so `on_edit` runs.
+        if let Some(cb) = self.apply_pending_completion() {
+            let applied = EventResult::Consumed(Some(cb));
+            if event == Event::Refresh {
+                return applied;
+            }
+            return applied.and(self.on_event(event));
+        }
+
         match event {
+            Event::Key(Key::Tab) if self.completer.is_some() => {
+                return match self.try_complete() {
+                    Some(cb) => EventResult::Consumed(Some(cb)),
+                    None => EventResult::Ignored,
+                };
+            }
             Event::Char(ch) => {
-                return EventResult::Consumed(Some(self.insert(ch)));
+                let text = self.composer.feed(ch);
+                return EventResult::Consumed(Some(self.insert_str(&text)));
+            }
+            Event::CtrlChar('v') => {
+                return match clipboard::get_text() {
+                    Some(text) => {
+                        EventResult::Consumed(Some(self.paste(&text)))
+                    }
+                    None => EventResult::Ignored,
+                };
+            }
+            Event::Paste(text) => {
+                return EventResult::Consumed(Some(self.paste(&text)));
+            }
+            Event::Key(Key::Backspace) | Event::Key(Key::Esc)
+                if self.composer.is_composing() =>
+            {
+                self.composer.clear();
+                return EventResult::Consumed(None);
             }
             // TODO: handle ctrl-key?
             Event::Key(Key::Home) => self.set_cursor(0),
@@ -618,7 +1352,7 @@ impl View for EditView {
             Event::Key(Key::Left) if self.cursor > 0 => {
                 let len = self.content[..self.cursor]
                     .graphemes(true)
-                    .last()
+                    .next_back()
                     .unwrap()
                     .len();
                 let cursor = self.cursor - len;
@@ -636,7 +1370,7 @@ impl View for EditView {
             Event::Key(Key::Backspace) if self.cursor > 0 => {
                 let len = self.content[..self.cursor]
                     .graphemes(true)
-                    .last()
+                    .next_back()
                     .unwrap()
                     .len();
                 self.cursor -= len;
@@ -650,9 +1384,25 @@ impl View for EditView {
                     .len();
                 return EventResult::Consumed(Some(self.remove(len)));
             }
+            // Invalid content cannot be submitted.
+            Event::Key(Key::Enter)
+                if self.on_submit.is_some() && !self.is_valid() => {}
+            Event::Key(Key::Up) | Event::Key(Key::Down)
+                if self.history_size > 0 =>
+            {
+                let backward = event == Event::Key(Key::Up);
+                return match self.browse_history(backward) {
+                    Some(cb) => EventResult::Consumed(Some(cb)),
+                    None => EventResult::Ignored,
+                };
+            }
             Event::Key(Key::Enter) if self.on_submit.is_some() => {
                 let cb = self.on_submit.clone().unwrap();
                 let content = Rc::clone(&self.content);
+                if self.history_size > 0 {
+                    self.add_history((*content).clone());
+                    self.history_pos = None;
+                }
                 return EventResult::with_cb(move |s| {
                     cb(s, &content);
                 });
@@ -696,4 +1446,129 @@ impl View for EditView {
 
         Rect::from_size((x, 0), (char_width, 1))
     }
+
+    fn save_state(&self) -> Option<ViewState> {
+        // Passwords are not saved.
+        if self.secret {
+            return None;
+        }
+        Some(ViewState::Content(self.content.to_string()))
+    }
+
+    fn restore_state(&mut self, state: &ViewState) {
+        if let ViewState::Content(ref content) = *state {
+            self.set_content(content.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn history() {
+        let mut view = EditView::new().history_size(2).on_submit(|_, _| ());
+        for entry in &["git status", "ls", "git log"] {
+            view.set_content(*entry);
+            view.on_event(Event::Key(Key::Enter));
+        }
+        assert_eq!(view.get_history(), &["ls", "git log"]);
+
+        let press = |view: &mut EditView, key| {
+            view.on_event(Event::Key(key));
+            view.get_content().to_string()
+        };
+
+        view.set_content("l");
+        assert_eq!(press(&mut view, Key::Up), "ls");
+        assert!(!view.on_event(Event::Key(Key::Up)).is_consumed());
+        assert_eq!(press(&mut view, Key::Down), "l");
+
+        view.set_content("");
+        assert_eq!(press(&mut view, Key::Up), "git log");
+        assert_eq!(press(&mut view, Key::Up), "ls");
+        assert_eq!(press(&mut view, Key::Down), "git log");
+        assert_eq!(press(&mut view, Key::Down), "");
+    }
+
+    #[test]
+    fn completion() {
+        let mut view = EditView::new().completer(|content| {
+            vec!["save", "save-as", "quit"]
+                .into_iter()
+                .filter(|c| c.starts_with(content))
+                .map(String::from)
+                .collect()
+        });
+
+        view.set_content("q");
+        assert!(view.on_event(Event::Key(Key::Tab)).is_consumed());
+        assert_eq!(&*view.get_content(), "quit");
+
+        // Several completions open a popup.
+        view.set_content("s");
+        assert!(view.on_event(Event::Key(Key::Tab)).has_callback());
+        assert_eq!(&*view.get_content(), "s");
+
+        *view.pending_completion.borrow_mut() = Some("save-as".into());
+        assert!(view.on_event(Event::Refresh).has_callback());
+        assert_eq!(&*view.get_content(), "save-as");
+
+        // Without the refresh, the next event applies it and runs `on_edit`.
+        let edited = Rc::new(RefCell::new(Vec::new()));
+        let recorder = Rc::clone(&edited);
+        view.set_on_edit(move |_, content, _| {
+            recorder.borrow_mut().push(content.to_string());
+        });
+        view.set_content("s");
+        *view.pending_completion.borrow_mut() = Some("save".into());
+        view.layout(Vec2::new(10, 1));
+        assert_eq!(&*view.get_content(), "s");
+        let mut siv = crate::Cursive::dummy();
+        view.on_event(Event::Char('!')).process(&mut siv);
+        assert_eq!(&*view.get_content(), "save!");
+        assert_eq!(*edited.borrow(), ["save", "save!"]);
+
+        view.set_content("x");
+        assert!(!view.on_event(Event::Key(Key::Tab)).is_consumed());
+    }
+
+    #[test]
+    fn max_content_length() {
+        let mut view = EditView::new().max_content_length(4).show_counter();
+        view.insert_str("e\u{301}t\u{e9}");
+        assert_eq!(view.content_length(), 3);
+        view.insert_str("s!");
+        assert_eq!(&*view.get_content(), "e\u{301}t\u{e9}s");
+
+        // Bytes are counted differently.
+        view.set_length_unit(LengthUnit::Bytes);
+        assert_eq!(view.get_counter(), "7/4");
+
+        // The counter takes the right of the view.
+        view.layout(Vec2::new(10, 1));
+        assert_eq!(view.last_length, 6);
+    }
+
+    #[test]
+    fn paste() {
+        let mut view = EditView::new().on_submit(|_, _| panic!("submitted"));
+        let result = view.on_event(Event::Paste("two\nlines\n".into()));
+        assert!(result.has_callback());
+        assert_eq!(&*view.get_content(), "two lines");
+    }
+
+    #[test]
+    fn input_method_commit() {
+        let mut view = EditView::new();
+        view.set_composition("漢字");
+
+        // The committed text comes in separate events.
+        view.on_event(Event::Char('漢'));
+        assert_eq!(view.get_composition(), "字");
+        view.on_event(Event::Char('字'));
+        assert_eq!(&*view.get_content(), "漢字");
+        assert_eq!(view.get_composition(), "");
+    }
 }
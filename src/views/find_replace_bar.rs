@@ -0,0 +1,282 @@
+use crate::traits::Boxable;
+use crate::utils::search::{Error, Pattern};
+use crate::view::ViewWrapper;
+use crate::views::{
+    Button, Checkbox, DummyView, EditView, LinearLayout, TextArea,
+    TextContent, TextView,
+};
+use crate::Cursive;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Find and replace bar for a [`TextArea`].
+///
+/// The bar targets a `TextArea` by its id. Typing in the "Find" field
+/// highlights the matches in the text area, and the buttons move to the
+/// next match or replace them. Replacements can be undone in the text area.
+///
+/// Patterns can be plain text or [regular expressions], and be case
+/// sensitive or not.
+///
+/// [`TextArea`]: struct.TextArea.html
+/// [regular expressions]: ../utils/search/index.html
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::views::{FindReplaceBar, LinearLayout, TextArea};
+/// # use cursive::traits::*;
+/// let editor = LinearLayout::vertical()
+///     .child(TextArea::new().with_id("text").full_screen())
+///     .child(FindReplaceBar::new("text"));
+/// ```
+pub struct FindReplaceBar {
+    layout: LinearLayout,
+    state: Rc<RefCell<State>>,
+}
+
+struct State {
+    target: String,
+    find: String,
+    replace: String,
+    regex: bool,
+    case_sensitive: bool,
+    status: TextContent,
+}
+
+impl FindReplaceBar {
+    /// Creates a new bar, working on the `TextArea` with the given id.
+    pub fn new<S: Into<String>>(target: S) -> Self {
+        let status = TextContent::new("");
+        let state = Rc::new(RefCell::new(State {
+            target: target.into(),
+            find: String::new(),
+            replace: String::new(),
+            regex: false,
+            case_sensitive: false,
+            status: status.clone(),
+        }));
+
+        let find = EditView::new()
+            .on_edit({
+                let state = Rc::clone(&state);
+                move |s, text, _| {
+                    state.borrow_mut().find = text.to_string();
+                    state.borrow_mut().update(s);
+                }
+            })
+            .on_submit(on_submit(action(&state, State::find_next)));
+        let replace = EditView::new()
+            .on_edit({
+                let state = Rc::clone(&state);
+                move |_, text, _| state.borrow_mut().replace = text.to_string()
+            })
+            .on_submit(on_submit(action(&state, State::replace_next)));
+
+        let regex = Checkbox::new().on_change({
+            let state = Rc::clone(&state);
+            move |s, checked| {
+                state.borrow_mut().regex = checked;
+                state.borrow_mut().update(s);
+            }
+        });
+        let case_sensitive = Checkbox::new().on_change({
+            let state = Rc::clone(&state);
+            move |s, checked| {
+                state.borrow_mut().case_sensitive = checked;
+                state.borrow_mut().update(s);
+            }
+        });
+
+        let layout = LinearLayout::vertical()
+            .child(
+                LinearLayout::horizontal()
+                    .child(TextView::new("Find:    "))
+                    .child(find.min_width(20))
+                    .child(DummyView)
+                    .child(regex)
+                    .child(TextView::new(" Regex "))
+                    .child(case_sensitive)
+                    .child(TextView::new(" Match case")),
+            )
+            .child(
+                LinearLayout::horizontal()
+                    .child(TextView::new("Replace: "))
+                    .child(replace.min_width(20))
+                    .child(DummyView)
+                    .child(Button::new(
+                        "Next",
+                        action(&state, State::find_next),
+                    ))
+                    .child(Button::new(
+                        "Replace",
+                        action(&state, State::replace_next),
+                    ))
+                    .child(Button::new(
+                        "All",
+                        action(&state, State::replace_all),
+                    ))
+                    .child(DummyView)
+                    .child(TextView::new_with_content(status)),
+            );
+
+        FindReplaceBar { layout, state }
+    }
+
+    /// Returns the id of the `TextArea` this bar works on.
+    pub fn target(&self) -> String {
+        self.state.borrow().target.clone()
+    }
+
+    /// Returns the text currently searched for.
+    pub fn get_find(&self) -> String {
+        self.state.borrow().find.clone()
+    }
+
+    /// Returns the current replacement text.
+    pub fn get_replace(&self) -> String {
+        self.state.borrow().replace.clone()
+    }
+
+    /// Returns `true` if the search text is a regular expression.
+    pub fn is_regex(&self) -> bool {
+        self.state.borrow().regex
+    }
+
+    /// Returns `true` if the search is case sensitive.
+    pub fn is_case_sensitive(&self) -> bool {
+        self.state.borrow().case_sensitive
+    }
+}
+
+// Makes a callback running `f` on the state.
+fn action<F>(state: &Rc<RefCell<State>>, f: F) -> impl Fn(&mut Cursive)
+where
+    F: Fn(&mut State, &mut Cursive),
+{
+    let state = Rc::clone(state);
+    move |s| f(&mut state.borrow_mut(), s)
+}
+
+// Runs `f` when an `EditView` is submitted.
+fn on_submit<F: Fn(&mut Cursive)>(f: F) -> impl Fn(&mut Cursive, &str) {
+    move |s, _| f(s)
+}
+
+impl State {
+    // Builds the pattern from the current options.
+    //
+    // Returns `Ok(None)` if there is nothing to look for.
+    fn pattern(&self) -> Result<Option<Pattern>, Error> {
+        if self.find.is_empty() {
+            return Ok(None);
+        }
+
+        if self.regex {
+            Pattern::regex(&self.find, self.case_sensitive).map(Some)
+        } else {
+            Ok(Some(Pattern::literal(&self.find, self.case_sensitive)))
+        }
+    }
+
+    // Runs `f` on the target text area.
+    fn call<F: FnOnce(&mut TextArea)>(&self, s: &mut Cursive, f: F) {
+        s.call_on_id(&self.target, f);
+    }
+
+    fn find_next(&mut self, s: &mut Cursive) {
+        self.call(s, |area| {
+            area.find_next();
+        });
+    }
+
+    fn replace_next(&mut self, s: &mut Cursive) {
+        self.call(s, |area| {
+            area.replace_next(&self.replace);
+        });
+        self.update(s);
+    }
+
+    fn replace_all(&mut self, s: &mut Cursive) {
+        self.call(s, |area| {
+            area.replace_all(&self.replace);
+        });
+        self.update(s);
+    }
+
+    // Updates the search of the text area, and the status.
+    fn update(&mut self, s: &mut Cursive) {
+        match self.pattern() {
+            Ok(pattern) => {
+                let searching = pattern.is_some();
+                let count =
+                    s.call_on_id(&self.target, |area: &mut TextArea| {
+                        area.set_search(pattern);
+                        area.matches().len()
+                    });
+                let status = match count {
+                    Some(1) => "1 match".to_string(),
+                    Some(count) if searching => format!("{} matches", count),
+                    _ => String::new(),
+                };
+                self.status.set_content(status);
+            }
+            Err(_) => {
+                self.call(s, |area| area.set_search(None));
+                self.status.set_content("Invalid pattern");
+            }
+        }
+    }
+}
+
+impl ViewWrapper for FindReplaceBar {
+    wrap_impl!(self.layout: LinearLayout);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Identifiable;
+
+    #[test]
+    fn find_and_replace() {
+        let mut siv = Cursive::dummy();
+        let bar = FindReplaceBar::new("text");
+        let state = Rc::clone(&bar.state);
+        siv.add_layer(
+            LinearLayout::vertical()
+                .child(TextArea::new().content("one two One").with_id("text"))
+                .child(bar),
+        );
+        let content = |siv: &mut Cursive| {
+            siv.call_on_id("text", |area: &mut TextArea| {
+                area.get_content().to_string()
+            })
+            .unwrap()
+        };
+
+        state.borrow_mut().find = "one".to_string();
+        state.borrow_mut().update(&mut siv);
+        assert_eq!(state.borrow().status.get_content().source(), "2 matches");
+
+        state.borrow_mut().case_sensitive = true;
+        state.borrow_mut().update(&mut siv);
+        assert_eq!(state.borrow().status.get_content().source(), "1 match");
+
+        state.borrow_mut().replace = "1".to_string();
+        state.borrow_mut().replace_all(&mut siv);
+        assert_eq!(content(&mut siv), "1 two One");
+
+        state.borrow_mut().regex = true;
+        state.borrow_mut().find = "(o".to_string();
+        state.borrow_mut().update(&mut siv);
+        let status = state.borrow().status.get_content().source().to_string();
+        assert_eq!(status, "Invalid pattern");
+
+        state.borrow_mut().find = "[a-z]+".to_string();
+        state.borrow_mut().update(&mut siv);
+        state.borrow_mut().replace = "x".to_string();
+        state.borrow_mut().replace_all(&mut siv);
+        assert_eq!(content(&mut siv), "1 x Ox");
+    }
+}
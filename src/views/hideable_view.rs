@@ -1,8 +1,11 @@
+use crate::utils::time::Instant;
 use crate::vec::Vec2;
 use crate::view::{Selector, View, ViewWrapper};
+use crate::Printer;
 use crate::With;
 
 use std::any::Any;
+use std::time::Duration;
 
 /// Wrapper around another view that can be hidden at will.
 ///
@@ -12,10 +15,37 @@ use std::any::Any;
 /// invisible view, will not take focus and will not accept input.
 ///
 /// It can be made visible again with `HideableView::unhide()`.
+///
+/// An animation can be played when the visibility changes, with
+/// `HideableView::set_animation()`. While hiding, the view is still drawn
+/// until the animation is over, but it no longer takes focus or input.
 pub struct HideableView<V> {
     view: V,
     visible: bool,
     invalidated: bool,
+
+    animation: HideAnimation,
+    duration: Duration,
+    // When the current animation started, if any.
+    animation_start: Option<Instant>,
+    on_complete: Option<Box<dyn FnMut(bool)>>,
+
+    // Size of the view when fully visible.
+    full_size: Vec2,
+}
+
+/// Animation played when a `HideableView` is shown or hidden.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HideAnimation {
+    /// The view appears and disappears at once.
+    None,
+    /// The view slides in from the right, and slides out towards it.
+    Slide,
+    /// The view is revealed line by line, from the top.
+    ///
+    /// Unlike `Slide`, this changes the size of the view during the
+    /// animation, pushing neighbouring views around.
+    Reveal,
 }
 
 impl<V> HideableView<V> {
@@ -27,13 +57,97 @@ impl<V> HideableView<V> {
             view,
             visible: true,
             invalidated: true,
+            animation: HideAnimation::None,
+            duration: Duration::from_millis(200),
+            animation_start: None,
+            on_complete: None,
+            full_size: Vec2::zero(),
         }
     }
 
     /// Sets the visibility for this view.
+    ///
+    /// If an animation is set, it will start playing.
     pub fn set_visible(&mut self, visible: bool) {
+        if self.visible == visible {
+            return;
+        }
+
         self.visible = visible;
         self.invalidate();
+
+        if self.animation == HideAnimation::None {
+            self.animation_start = None;
+            self.complete();
+            return;
+        }
+
+        // Reverse a running animation from wherever it was.
+        let elapsed = self.duration.mul_f32(1.0 - self.progress());
+        self.animation_start = Some(Instant::now() - elapsed);
+    }
+
+    /// Sets the animation to play when the visibility changes.
+    ///
+    /// The animation only runs if the screen is refreshed regularly, for
+    /// instance with `Cursive::set_autorefresh`.
+    pub fn set_animation(&mut self, animation: HideAnimation) {
+        self.animation = animation;
+        if animation == HideAnimation::None && self.is_animating() {
+            self.animation_start = None;
+            self.invalidate();
+            self.complete();
+        }
+    }
+
+    /// Sets the animation to play when the visibility changes.
+    ///
+    /// Chainable variant.
+    pub fn animation(self, animation: HideAnimation) -> Self {
+        self.with(|s| s.set_animation(animation))
+    }
+
+    /// Sets how long the animation lasts.
+    ///
+    /// Defaults to 200ms.
+    pub fn set_animation_duration(&mut self, duration: Duration) {
+        self.duration = duration;
+    }
+
+    /// Sets how long the animation lasts.
+    ///
+    /// Chainable variant.
+    pub fn animation_duration(self, duration: Duration) -> Self {
+        self.with(|s| s.set_animation_duration(duration))
+    }
+
+    /// Sets a callback to run when the view is done showing or hiding.
+    ///
+    /// The callback is given the new visibility. It runs once the animation
+    /// is over, or right away if there is no animation.
+    ///
+    /// It cannot access the `Cursive` root; use `Cursive::cb_sink()` to
+    /// send a callback there.
+    pub fn set_on_complete<F>(&mut self, f: F)
+    where
+        F: FnMut(bool) + 'static,
+    {
+        self.on_complete = Some(Box::new(f));
+    }
+
+    /// Sets a callback to run when the view is done showing or hiding.
+    ///
+    /// Chainable variant.
+    pub fn on_complete<F>(self, f: F) -> Self
+    where
+        F: FnMut(bool) + 'static,
+    {
+        self.with(|s| s.set_on_complete(f))
+    }
+
+    /// Returns `true` if a show or hide animation is currently playing.
+    pub fn is_animating(&self) -> bool {
+        self.animation_start.is_some()
     }
 
     /// Sets the visibility for this view to `false`.
@@ -62,6 +176,43 @@ impl<V> HideableView<V> {
         self.invalidated = true;
     }
 
+    fn complete(&mut self) {
+        let visible = self.visible;
+        if let Some(ref mut on_complete) = self.on_complete {
+            on_complete(visible);
+        }
+    }
+
+    // Returns how far along the current animation is, between 0 and 1.
+    fn progress(&self) -> f32 {
+        let start = match self.animation_start {
+            None => return 1.0,
+            Some(start) => start,
+        };
+
+        let total = self.duration.as_millis();
+        let elapsed = start.elapsed().as_millis();
+        if total == 0 || elapsed >= total {
+            1.0
+        } else {
+            elapsed as f32 / total as f32
+        }
+    }
+
+    // Returns the visible fraction of the view, between 0 and 1.
+    fn shown(&self) -> f32 {
+        if self.visible {
+            self.progress()
+        } else {
+            1.0 - self.progress()
+        }
+    }
+
+    // Returns `true` if the view should be drawn.
+    fn is_drawn(&self) -> bool {
+        self.visible || self.is_animating()
+    }
+
     inner_getters!(self.view: V);
 }
 
@@ -92,7 +243,7 @@ impl<V: View> ViewWrapper for HideableView<V> {
 
     fn wrap_call_on_any<'a>(
         &mut self, selector: &Selector<'_>,
-        callback: Box<FnMut(&mut dyn Any) + 'a>,
+        callback: Box<dyn FnMut(&mut dyn Any) + 'a>,
     ) {
         // We always run callbacks, even when invisible.
         self.view.call_on_any(selector, callback)
@@ -106,12 +257,132 @@ impl<V: View> ViewWrapper for HideableView<V> {
         Ok(self.view)
     }
 
+    fn wrap_draw(&self, printer: &Printer<'_, '_>) {
+        if !self.is_drawn() {
+            return;
+        }
+
+        if !self.is_animating() {
+            self.view.draw(printer);
+            return;
+        }
+
+        let shown = self.shown();
+        match self.animation {
+            HideAnimation::Slide => {
+                let hidden = printer.size.x
+                    - (printer.size.x as f32 * shown).round() as usize;
+                // The view keeps its size: the part still outside is
+                // clipped.
+                let printer =
+                    printer.offset((hidden, 0)).inner_size(printer.size);
+                self.view.draw(&printer);
+            }
+            // The printer is already cropped to the revealed rows.
+            _ => self.view.draw(printer),
+        }
+    }
+
+    fn wrap_required_size(&mut self, req: Vec2) -> Vec2 {
+        if !self.is_drawn() {
+            return Vec2::zero();
+        }
+
+        self.full_size = self.view.required_size(req);
+
+        if self.is_animating() && self.animation == HideAnimation::Reveal {
+            let rows = (self.full_size.y as f32 * self.shown()).ceil();
+            Vec2::new(self.full_size.x, rows as usize)
+        } else {
+            self.full_size
+        }
+    }
+
     fn wrap_layout(&mut self, size: Vec2) {
         self.invalidated = false;
-        self.with_view_mut(|v| v.layout(size));
+
+        if self.is_animating() && self.progress() >= 1.0 {
+            self.animation_start = None;
+            self.complete();
+        }
+
+        if !self.is_drawn() {
+            return;
+        }
+
+        if self.is_animating() && self.animation == HideAnimation::Reveal {
+            // Keep the full height, the printer will crop the rest.
+            self.view.layout(Vec2::new(size.x, self.full_size.y.max(size.y)));
+        } else {
+            self.view.layout(size);
+        }
     }
 
     fn wrap_needs_relayout(&self) -> bool {
-        self.invalidated || (self.visible && self.view.needs_relayout())
+        self.invalidated
+            || self.is_animating()
+            || (self.visible && self.view.needs_relayout())
+    }
+
+    fn wrap_mark_dirty(&mut self) {
+        self.invalidate();
+        self.view.mark_dirty();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::puppet;
+    use crate::theme::{PaletteColor, Theme};
+    use crate::views::{DummyView, TextView};
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn hide_animation() {
+        let completed = Rc::new(Cell::new(None));
+        let mut view = HideableView::new(DummyView)
+            .animation(HideAnimation::Reveal)
+            .animation_duration(Duration::from_secs(60))
+            .on_complete({
+                let completed = Rc::clone(&completed);
+                move |visible| completed.set(Some(visible))
+            });
+
+        view.hide();
+        assert!(!view.is_visible());
+        assert!(view.is_animating());
+        view.layout(Vec2::new(10, 10));
+        assert_eq!(completed.get(), None);
+
+        view.set_animation_duration(Duration::from_secs(0));
+        view.layout(Vec2::new(10, 10));
+        assert!(!view.is_animating());
+        assert_eq!(completed.get(), Some(false));
+
+        view.set_animation(HideAnimation::None);
+        view.unhide();
+        assert!(!view.is_animating());
+        assert_eq!(completed.get(), Some(true));
+    }
+
+    #[test]
+    fn slide_frame() {
+        let mut view = HideableView::new(TextView::new("abcdefghij"))
+            .animation(HideAnimation::Slide)
+            .animation_duration(Duration::from_secs(2));
+        view.hide();
+        view.animation_start = Some(Instant::now() - Duration::from_secs(1));
+        view.layout(Vec2::new(10, 1));
+
+        let (mut backend, puppet) = puppet::Backend::init((10, 1));
+        let theme = Theme::default();
+        backend.clear(theme.palette[PaletteColor::Background]);
+        view.draw(&Printer::new((10, 1), &theme, &*backend));
+        backend.refresh();
+
+        // Half way out, the start of the text is still shown.
+        assert_eq!(puppet.screen().row(0).unwrap(), "     abcde");
     }
 }
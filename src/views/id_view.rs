@@ -1,14 +1,105 @@
+use crate::profiler::{self, Phase};
+use crate::rect::Rect;
+use crate::vec::Vec2;
 use crate::view::{Selector, View, ViewWrapper};
+use crate::Printer;
 use owning_ref::{OwningHandle, RcRef};
 use std::any::Any;
-use std::cell::{RefCell, RefMut};
+use std::cell::{Cell, RefCell, RefMut};
 use std::ops::DerefMut;
-use std::rc::Rc;
+use std::rc::{Rc, Weak};
 
 /// Wrapper around a view to provide interior mutability.
 pub struct IdView<V: View> {
     view: Rc<RefCell<V>>,
-    id: String,
+    // Shared with `AnyIdView`, so views can be renamed from there.
+    id: Rc<RefCell<String>>,
+    // `true` if the view had the focus when it was last drawn.
+    focused: Rc<Cell<bool>>,
+}
+
+/// Error returned when managing view ids.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IdError {
+    /// A view with this id already exists.
+    Duplicate(String),
+    /// No view has this id.
+    NotFound(String),
+    /// The view with this id is already borrowed, for example by its own
+    /// callback.
+    Borrowed(String),
+    /// The view with this id is not of the expected type.
+    WrongType(String),
+}
+
+/// An `IdView` of any type, found with `Selector::AnyIdPrefix`.
+///
+/// It gives access to the id, and to the wrapped view as a `dyn View`.
+#[derive(Clone)]
+pub struct AnyIdView {
+    id: Rc<RefCell<String>>,
+    view: Rc<RefCell<dyn View>>,
+    focused: Rc<Cell<bool>>,
+}
+
+impl AnyIdView {
+    /// Returns the id of this view.
+    pub fn get_id(&self) -> String {
+        self.id.borrow().clone()
+    }
+
+    /// Returns `true` if this view had the focus when it was last drawn.
+    ///
+    /// `Cursive::focus_id` also updates this before the next draw.
+    pub fn is_focused(&self) -> bool {
+        self.focused.get()
+    }
+
+    pub(crate) fn set_focused(&self, focused: bool) {
+        self.focused.set(focused);
+    }
+
+    /// Changes the id of this view.
+    pub fn set_id<S: Into<String>>(&mut self, id: S) {
+        *self.id.borrow_mut() = id.into();
+    }
+
+    /// Runs `f` on the wrapped view, and returns its result.
+    ///
+    /// Returns `None` if the view is already borrowed (for example from its
+    /// own callback).
+    pub fn with_view<F, R>(&self, f: F) -> Option<R>
+    where
+        F: FnOnce(&mut dyn View) -> R,
+    {
+        let mut view = self.view.try_borrow_mut().ok()?;
+        Some(f(&mut *view))
+    }
+}
+
+/// Returns the `IdView`s in `view` with an id starting with `prefix`.
+pub(crate) fn find_id_views(
+    view: &mut dyn View, prefix: &str,
+) -> Vec<AnyIdView> {
+    let mut result = Vec::new();
+    view.call_on_any(
+        &Selector::AnyIdPrefix(prefix),
+        Box::new(|view: &mut dyn Any| {
+            if let Some(view) = view.downcast_mut::<AnyIdView>() {
+                result.push(view.clone());
+            }
+        }),
+    );
+    result
+}
+
+/// Returns the first id used by more than one of `views`.
+pub(crate) fn find_duplicate(views: &[AnyIdView]) -> Option<String> {
+    let mut ids: Vec<String> = views.iter().map(AnyIdView::get_id).collect();
+    ids.sort();
+    ids.windows(2)
+        .find(|pair| pair[0] == pair[1])
+        .map(|pair| pair[0].clone())
 }
 
 /// Mutable reference to a view.
@@ -18,15 +109,98 @@ pub struct IdView<V: View> {
 /// [`RefMut`]: https://doc.rust-lang.org/std/cell/struct.RefMut.html
 pub type ViewRef<V> = OwningHandle<RcRef<RefCell<V>>, RefMut<'static, V>>;
 
+/// Weak handle to a view wrapped in an `IdView`.
+///
+/// Unlike a [`ViewRef`], it does not keep the view alive, and does not
+/// borrow it until upgraded: it can be kept for a long time, for example in
+/// a callback, and tells when the view was removed.
+///
+/// It is obtained with `IdView::downgrade` or `Cursive::find_weak_id`. It
+/// cannot be sent to other threads: use a [`ViewSink`] there.
+///
+/// [`ViewRef`]: type.ViewRef.html
+/// [`ViewSink`]: ../struct.ViewSink.html
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::views::{IdView, TextView};
+/// let view = IdView::new("status", TextView::new("Ready"));
+/// let handle = view.downgrade();
+///
+/// handle.with(|text| text.set_content("Busy"));
+/// assert_eq!(handle.upgrade().unwrap().get_content().source(), "Busy");
+///
+/// drop(view);
+/// assert!(!handle.is_alive());
+/// assert!(handle.with(|text| text.set_content("Done")).is_none());
+/// ```
+pub struct WeakViewRef<V> {
+    view: Weak<RefCell<V>>,
+}
+
+impl<V> Clone for WeakViewRef<V> {
+    fn clone(&self) -> Self {
+        WeakViewRef {
+            view: Weak::clone(&self.view),
+        }
+    }
+}
+
+impl<V: View> WeakViewRef<V> {
+    /// Returns `true` if the view still exists.
+    pub fn is_alive(&self) -> bool {
+        self.view.strong_count() > 0
+    }
+
+    /// Gets mutable access to the view.
+    ///
+    /// Returns `None` if the view was removed, or if it is already borrowed
+    /// (for example from its own callback).
+    pub fn upgrade(&self) -> Option<ViewRef<V>> {
+        let view = self.view.upgrade()?;
+        if view.try_borrow_mut().is_err() {
+            return None;
+        }
+        Some(OwningHandle::new_mut(RcRef::new(view)))
+    }
+
+    /// Runs `f` on the view, and returns its result.
+    ///
+    /// Returns `None` if the view was removed, or if it is already borrowed.
+    pub fn with<F, R>(&self, f: F) -> Option<R>
+    where
+        F: FnOnce(&mut V) -> R,
+    {
+        let view = self.view.upgrade()?;
+        let mut view = view.try_borrow_mut().ok()?;
+        Some(f(&mut *view))
+    }
+}
+
 impl<V: View> IdView<V> {
     /// Wraps `view` in a new `IdView`.
+    ///
+    /// Duplicate ids are detected when the view is added to a `Cursive`
+    /// root, see `Cursive::try_add_layer`.
     pub fn new<S: Into<String>>(id: S, view: V) -> Self {
         IdView {
             view: Rc::new(RefCell::new(view)),
-            id: id.into(),
+            id: Rc::new(RefCell::new(id.into())),
+            focused: Rc::new(Cell::new(false)),
         }
     }
 
+    /// Returns the id of this view.
+    pub fn get_id(&self) -> String {
+        self.id.borrow().clone()
+    }
+
+    /// Changes the id of this view.
+    pub fn set_id<S: Into<String>>(&mut self, id: S) {
+        *self.id.borrow_mut() = id.into();
+    }
+
     /// Gets mutable access to the inner view.
     ///
     /// This returns a `ViewRef<V>`, which implement `DerefMut<Target = V>`.
@@ -39,10 +213,31 @@ impl<V: View> IdView<V> {
 
         OwningHandle::new_mut(cell_ref)
     }
+
+    /// Returns a weak handle to the inner view.
+    ///
+    /// It does not keep the view alive when this `IdView` is dropped.
+    pub fn downgrade(&self) -> WeakViewRef<V> {
+        WeakViewRef {
+            view: Rc::downgrade(&self.view),
+        }
+    }
+}
+
+impl<V: View> IdView<V> {
+    // Returns `true` if this view is selected by its id.
+    fn matches(&self, selector: &Selector<'_>) -> bool {
+        let id = self.id.borrow();
+        match *selector {
+            Selector::Id(other) => *id == other,
+            Selector::IdPrefix(prefix) => id.starts_with(prefix),
+            _ => false,
+        }
+    }
 }
 
 // Shortcut for a boxed callback (for the wrap_call_on_any method).
-type BoxedCallback<'a> = Box<for<'b> FnMut(&'b mut dyn Any) + 'a>;
+type BoxedCallback<'a> = Box<dyn for<'b> FnMut(&'b mut dyn Any) + 'a>;
 
 impl<T: View + 'static> ViewWrapper for IdView<T> {
     type V = T;
@@ -75,23 +270,51 @@ impl<T: View + 'static> ViewWrapper for IdView<T> {
         }
     }
 
+    fn wrap_draw(&self, printer: &Printer<'_, '_>) {
+        self.focused.set(printer.focused);
+        profiler::observe(&self.id, Phase::Draw, || {
+            self.with_view(|v| v.draw(printer));
+        });
+    }
+
+    fn wrap_layout(&mut self, size: Vec2) {
+        let id = Rc::clone(&self.id);
+        profiler::observe(&id, Phase::Layout, || {
+            self.with_view_mut(|v| v.layout(size));
+        });
+    }
+
     // Some for<'b> weirdness here to please the borrow checker gods...
     fn wrap_call_on_any<'a>(
         &mut self, selector: &Selector<'_>, mut callback: BoxedCallback<'a>,
     ) {
-        match selector {
-            &Selector::Id(id) if id == self.id => callback(self),
-            s => {
-                if let Ok(mut v) = self.view.try_borrow_mut() {
-                    v.deref_mut().call_on_any(s, callback);
-                }
+        match *selector {
+            Selector::Id(_) if self.matches(selector) => {
+                callback(self);
+                return;
             }
+            Selector::IdPrefix(_) if self.matches(selector) => callback(self),
+            Selector::AnyIdPrefix(prefix)
+                if self.id.borrow().starts_with(prefix) =>
+            {
+                callback(&mut AnyIdView {
+                    id: Rc::clone(&self.id),
+                    view: Rc::clone(&self.view) as _,
+                    focused: Rc::clone(&self.focused),
+                });
+            }
+            _ => (),
+        }
+
+        // Prefixes can also match views nested in this one.
+        if let Ok(mut v) = self.view.try_borrow_mut() {
+            v.deref_mut().call_on_any(selector, callback);
         }
     }
 
     fn wrap_focus_view(&mut self, selector: &Selector<'_>) -> Result<(), ()> {
         match selector {
-            &Selector::Id(id) if id == self.id => Ok(()),
+            _ if self.matches(selector) => Ok(()),
             s => self
                 .view
                 .try_borrow_mut()
@@ -99,4 +322,86 @@ impl<T: View + 'static> ViewWrapper for IdView<T> {
                 .and_then(|mut v| v.deref_mut().focus_view(s)),
         }
     }
+
+    fn wrap_locate(
+        &self, selector: &Selector<'_>, size: Vec2,
+    ) -> Option<Rect> {
+        match selector {
+            _ if self.matches(selector) => {
+                Some(Rect::from_size((0, 0), size))
+            }
+            s => self.view.try_borrow().ok()?.locate(s, size),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::views::{DummyView, LinearLayout};
+    use crate::Cursive;
+
+    #[test]
+    fn find_in_tree() {
+        let mut layout = LinearLayout::vertical()
+            .child(IdView::new("a", DummyView))
+            .child(IdView::new(
+                "b",
+                LinearLayout::vertical().child(IdView::new("b-a", DummyView)),
+            ));
+
+        let ids = |views: &[AnyIdView]| {
+            views.iter().map(AnyIdView::get_id).collect::<Vec<_>>()
+        };
+        let views = find_id_views(&mut layout, "");
+        assert_eq!(ids(&views), vec!["a", "b", "b-a"]);
+        assert_eq!(find_duplicate(&views), None);
+        assert_eq!(ids(&find_id_views(&mut layout, "b")), vec!["b", "b-a"]);
+
+        // Renamed from the tree.
+        let mut view = find_id_views(&mut layout, "b-a").remove(0);
+        view.set_id("a");
+        let views = find_id_views(&mut layout, "");
+        assert_eq!(find_duplicate(&views), Some("a".to_string()));
+    }
+
+    #[test]
+    fn rename() {
+        let mut siv = Cursive::dummy();
+        siv.add_layer(IdView::new("old", DummyView));
+        siv.add_layer(IdView::new("other", DummyView));
+
+        assert_eq!(
+            siv.rename_id("old", "other"),
+            Err(IdError::Duplicate("other".into()))
+        );
+        assert_eq!(siv.rename_id("old", "new"), Ok(()));
+        assert_eq!(siv.find_ids_with_prefix(""), vec!["new", "other"]);
+
+        // Views out of the tree are not seen.
+        let _view = IdView::new("outside", DummyView);
+        let missing = Err(IdError::NotFound("outside".into()));
+        assert_eq!(siv.rename_id("outside", "inside"), missing);
+        let duplicate = Err(IdError::Duplicate("new".into()));
+        let view = IdView::new("new", DummyView);
+        assert_eq!(siv.try_add_layer(view), duplicate);
+    }
+
+    #[test]
+    fn weak_ref() {
+        let mut view = IdView::new("weak", DummyView);
+        let handle = view.downgrade();
+        assert!(handle.upgrade().is_some());
+
+        // Already borrowed: upgrading does not panic.
+        let strong = view.get_mut();
+        assert!(handle.upgrade().is_none());
+        assert_eq!(handle.with(|_| ()), None);
+        drop(strong);
+        assert_eq!(handle.clone().with(|_| ()), Some(()));
+
+        drop(view);
+        assert!(!handle.is_alive());
+        assert!(handle.upgrade().is_none());
+    }
 }
@@ -1,8 +1,9 @@
 use crate::direction;
-use crate::event::{AnyCb, Event, EventResult, Key};
+use crate::event::{AnyCb, Event, EventResult, Key, MouseEvent};
 use crate::rect::Rect;
+use crate::state::ViewState;
 use crate::vec::Vec2;
-use crate::view::{Selector, SizeCache, View};
+use crate::view::{on_child_edge, FocusPolicy, Selector, SizeCache, View};
 use crate::Printer;
 use crate::With;
 use crate::XY;
@@ -15,8 +16,16 @@ pub struct LinearLayout {
     children: Vec<Child>,
     orientation: direction::Orientation,
     focus: usize,
+    focus_policy: FocusPolicy,
+
+    // Child which consumed the last mouse press, receiving the drag.
+    mouse_grab: Option<usize>,
 
     cache: Option<XY<SizeCache>>,
+
+    // Size the leftover space was last distributed for, if the children
+    // sizes include it.
+    distributed: Option<Vec2>,
 }
 
 struct Child {
@@ -25,6 +34,8 @@ struct Child {
     // Doesn't have to be what the child actually gets.
     size: Vec2,
     weight: usize,
+    // The size given to the child by the last layout, if any.
+    layout_size: Option<Vec2>,
 }
 
 impl Child {
@@ -116,17 +127,34 @@ impl LinearLayout {
             children: Vec::new(),
             orientation,
             focus: 0,
+            mouse_grab: None,
+            focus_policy: FocusPolicy::Escape,
             cache: None,
+            distributed: None,
         }
     }
 
     /// Sets the weight of the given child.
     ///
+    /// When the layout is given more space than its children require, the
+    /// leftover space is distributed among children proportionally to their
+    /// weight. Children with a weight of `0` (the default) don't grow.
+    ///
     /// # Panics
     ///
     /// Panics if `i >= self.len()`.
     pub fn set_weight(&mut self, i: usize, weight: usize) {
         self.children[i].weight = weight;
+        self.invalidate();
+    }
+
+    /// Returns the weight of the given child.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i >= self.len()`.
+    pub fn get_weight(&self, i: usize) -> usize {
+        self.children[i].weight
     }
 
     /// Modifies the weight of the last child added.
@@ -138,6 +166,35 @@ impl LinearLayout {
         self
     }
 
+    /// Adds a child to the layout, with the given weight.
+    ///
+    /// Chainable variant.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::views::{LinearLayout, TextView};
+    /// # use cursive::traits::Boxable;
+    /// // The first view will take twice as much extra space as the second.
+    /// let layout = LinearLayout::horizontal()
+    ///     .weighted_child(TextView::new("Left"), 2)
+    ///     .weighted_child(TextView::new("Right"), 1)
+    ///     .full_width();
+    /// ```
+    pub fn weighted_child<V: View + 'static>(
+        self, view: V, weight: usize,
+    ) -> Self {
+        self.with(|s| s.add_weighted_child(view, weight))
+    }
+
+    /// Adds a child to the layout, with the given weight.
+    pub fn add_weighted_child<V: View + 'static>(
+        &mut self, view: V, weight: usize,
+    ) {
+        self.add_child(view);
+        self.children.last_mut().unwrap().weight = weight;
+    }
+
     /// Adds a child to the layout.
     ///
     /// Chainable variant.
@@ -151,6 +208,7 @@ impl LinearLayout {
             view: Box::new(view),
             size: Vec2::zero(),
             weight: 0,
+            layout_size: None,
         });
         self.invalidate();
     }
@@ -167,6 +225,7 @@ impl LinearLayout {
                 view: Box::new(view),
                 size: Vec2::zero(),
                 weight: 0,
+                layout_size: None,
             },
         );
         self.invalidate();
@@ -196,6 +255,7 @@ impl LinearLayout {
     // Invalidate the view, to request a layout next time
     fn invalidate(&mut self) {
         self.cache = None;
+        self.distributed = None;
     }
 
     /// Creates a new vertical layout.
@@ -208,6 +268,35 @@ impl LinearLayout {
         LinearLayout::new(direction::Orientation::Horizontal)
     }
 
+    /// Sets what happens when the focus reaches the first or last child.
+    ///
+    /// This applies to `Tab` and `Shift+Tab`, and to the arrow keys along
+    /// the orientation of this layout.
+    ///
+    /// Defaults to `FocusPolicy::Escape`.
+    pub fn set_focus_policy(&mut self, policy: FocusPolicy) {
+        self.focus_policy = policy;
+    }
+
+    /// Sets what happens when the focus reaches the first or last child.
+    ///
+    /// Chainable variant.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::view::FocusPolicy;
+    /// # use cursive::views::{Button, LinearLayout};
+    /// // Tab goes from "Two" back to "One".
+    /// let buttons = LinearLayout::horizontal()
+    ///     .child(Button::new("One", |_| ()))
+    ///     .child(Button::new("Two", |_| ()))
+    ///     .with_focus_policy(FocusPolicy::Wrap);
+    /// ```
+    pub fn with_focus_policy(self, policy: FocusPolicy) -> Self {
+        self.with(|s| s.set_focus_policy(policy))
+    }
+
     /// Returns a reference to a child.
     pub fn get_child(&self, i: usize) -> Option<&dyn View> {
         self.children.get(i).map(|child| &*child.view)
@@ -262,6 +351,30 @@ impl LinearLayout {
         }
     }
 
+    fn has_weights(&self) -> bool {
+        self.children.iter().any(|child| child.weight > 0)
+    }
+
+    // Gives any space left by the children to the weighted ones.
+    fn distribute_leftover(&mut self, available: usize) {
+        let o = self.orientation;
+
+        let used: usize =
+            self.children.iter().map(|child| *child.size.get(o)).sum();
+        let mut leftover = available.saturating_sub(used);
+        let mut weights: usize =
+            self.children.iter().map(|child| child.weight).sum();
+
+        for child in self.children.iter_mut().filter(|c| c.weight > 0) {
+            // The last weighted child gets whatever rounding left.
+            let share = leftover * child.weight / weights;
+            *child.size.get_mut(o) += share;
+
+            leftover -= share;
+            weights -= child.weight;
+        }
+    }
+
     fn children_are_sleeping(&self) -> bool {
         !self
             .children
@@ -309,23 +422,33 @@ impl LinearLayout {
         EventResult::Consumed(None)
     }
 
+    // Applies the focus policy to an event no child could use.
+    fn on_focus_edge(&mut self, event: &Event) -> EventResult {
+        let source = match focus_source(self.orientation, event) {
+            Some(source) => source,
+            None => return EventResult::Ignored,
+        };
+
+        let policy = self.focus_policy;
+        policy.apply(self, source)
+    }
+
     // If the event is a mouse event,
     // move the focus to the selected view if needed.
-    fn check_focus_grab(&mut self, event: &Event) {
+    //
+    // Returns the child under the mouse, which may not take the focus.
+    fn check_focus_grab(&mut self, event: &Event) -> Option<usize> {
         if let Event::Mouse {
             offset,
             position,
             event,
         } = *event
         {
-            if !event.grabs_focus() {
-                return;
+            if !event.targets_hovered() {
+                return None;
             }
 
-            let position = match position.checked_sub(offset) {
-                None => return,
-                Some(pos) => pos,
-            };
+            let position = position.checked_sub(offset)?;
 
             // Find the selected child
             // Let's only care about the coordinate for our orientation.
@@ -346,14 +469,19 @@ impl LinearLayout {
                 let child_size = item.child.size.get(self.orientation);
 
                 if item.offset + child_size > position {
-                    if item.child.view.take_focus(direction::Direction::none())
+                    if event.grabs_focus()
+                        && item
+                            .child
+                            .view
+                            .take_focus(direction::Direction::none())
                     {
                         self.focus = i;
                     }
-                    return;
+                    return Some(i);
                 }
             }
         }
+        None
     }
 }
 
@@ -367,6 +495,23 @@ fn try_focus(
     }
 }
 
+// Returns where the focus comes from when wrapping around after `event`.
+fn focus_source(
+    orientation: direction::Orientation, event: &Event,
+) -> Option<direction::Direction> {
+    use crate::direction::{Direction, Orientation};
+
+    Some(match (orientation, event) {
+        (_, Event::Key(Key::Tab)) => Direction::front(),
+        (_, Event::Shift(Key::Tab)) => Direction::back(),
+        (Orientation::Horizontal, Event::Key(Key::Left)) => Direction::right(),
+        (Orientation::Horizontal, Event::Key(Key::Right)) => Direction::left(),
+        (Orientation::Vertical, Event::Key(Key::Up)) => Direction::down(),
+        (Orientation::Vertical, Event::Key(Key::Down)) => Direction::up(),
+        _ => return None,
+    })
+}
+
 impl View for LinearLayout {
     fn draw(&self, printer: &Printer<'_, '_>) {
         // Use pre-computed sizes
@@ -397,23 +542,50 @@ impl View for LinearLayout {
         !self.children_are_sleeping()
     }
 
+    fn mark_dirty(&mut self) {
+        self.invalidate();
+        for child in &mut self.children {
+            child.view.mark_dirty();
+        }
+    }
+
     fn layout(&mut self, size: Vec2) {
+        // We'll use this guy a few times, but it's a mouthful...
+        let o = self.orientation;
+
         // If we can get away without breaking a sweat, you can bet we will.
         // debug!("Laying out with {:?}", size);
-        if self.get_cache(size).is_none() {
+        if self.has_weights() {
+            if self.distributed != Some(size) || self.get_cache(size).is_none()
+            {
+                // Sizes from the last layout include the leftover space;
+                // we need fresh ones to distribute it again.
+                if self.distributed.is_some() {
+                    self.invalidate();
+                }
+                self.required_size(size);
+                self.distribute_leftover(*size.get(o));
+                self.distributed = Some(size);
+            }
+        } else if self.get_cache(size).is_none() {
             self.required_size(size);
         }
 
-        // We'll use this guy a few times, but it's a mouthful...
-        let o = self.orientation;
-
         for item in
             ChildIterator::new(self.children.iter_mut(), o, *size.get(o))
         {
             // Every item has the same size orthogonal to the layout
             item.child.size.set_axis_from(o.swap(), &size);
 
-            item.child.view.layout(size.with_axis(o, item.length));
+            // Children which did not change keep their layout.
+            let child_size = size.with_axis(o, item.length);
+            if item.child.layout_size == Some(child_size)
+                && !item.child.view.needs_relayout()
+            {
+                continue;
+            }
+            item.child.view.layout(child_size);
+            item.child.layout_size = Some(child_size);
         }
     }
 
@@ -424,6 +596,9 @@ impl View for LinearLayout {
         }
         debug!("Req: {:?}", req);
 
+        // The children sizes are computed again, without any leftover.
+        self.distributed = None;
+
         // First, make a naive scenario: everything will work fine.
         let ideal_sizes: Vec<Vec2> = self
             .children
@@ -575,7 +750,21 @@ impl View for LinearLayout {
             return EventResult::Ignored;
         }
 
-        self.check_focus_grab(&event);
+        let hovered = self.check_focus_grab(&event);
+
+        // Mouse presses go to the child under the mouse, even if it did not
+        // take the focus. The following drag goes to the same child.
+        let mouse = match event {
+            Event::Mouse { event, .. } => Some(event),
+            _ => None,
+        };
+        let target = match mouse {
+            Some(mouse) if mouse.is_drag() => self.mouse_grab,
+            _ => hovered,
+        };
+        let target = target
+            .filter(|&i| i < self.children.len())
+            .unwrap_or(self.focus);
 
         let result = {
             let mut iterator = ChildIterator::new(
@@ -583,11 +772,23 @@ impl View for LinearLayout {
                 self.orientation,
                 usize::max_value(),
             );
-            let item = iterator.nth(self.focus).unwrap();
+            let item = iterator.nth(target).unwrap();
             let offset = self.orientation.make_vec(item.offset, 0);
-            item.child.view.on_event(event.relativized(offset))
+            item.child
+                .view
+                .on_event(event.relativized(offset))
+                .or_else(|| on_child_edge(&mut *item.child.view, &event))
         };
-        match result {
+        match mouse {
+            Some(MouseEvent::Press(_)) if result.is_consumed() => {
+                self.mouse_grab = Some(target);
+            }
+            Some(MouseEvent::Press(_)) | Some(MouseEvent::Release(_)) => {
+                self.mouse_grab = None;
+            }
+            _ => (),
+        }
+        let result = match result {
             EventResult::Ignored => match event {
                 Event::Shift(Key::Tab) if self.focus > 0 => {
                     self.move_focus(direction::Direction::back())
@@ -625,15 +826,36 @@ impl View for LinearLayout {
                 {
                     self.move_focus(direction::Direction::up())
                 }
+                // Other children may still use it, like a button mnemonic.
+                Event::AltChar(_) => {
+                    let focus = self.focus;
+                    self.children
+                        .iter_mut()
+                        .enumerate()
+                        .filter(|&(i, _)| i != focus)
+                        .map(|(_, child)| child.view.on_event(event.clone()))
+                        .find(EventResult::is_consumed)
+                        .unwrap_or(EventResult::Ignored)
+                }
                 _ => EventResult::Ignored,
             },
             res => res,
+        };
+
+        match result {
+            EventResult::Ignored => self.on_focus_edge(&event),
+            res => res,
         }
     }
 
+    fn focus_policy(&self) -> FocusPolicy {
+        self.focus_policy
+    }
+
     fn call_on_any<'a>(
         &mut self, selector: &Selector<'_>, mut callback: AnyCb<'a>,
     ) {
+        selector.call_on_self(self, &mut callback);
         for child in &mut self.children {
             child
                 .view
@@ -652,6 +874,21 @@ impl View for LinearLayout {
         Err(())
     }
 
+    fn locate(&self, selector: &Selector<'_>, _: Vec2) -> Option<Rect> {
+        ChildIterator::new(
+            self.children.iter(),
+            self.orientation,
+            usize::MAX,
+        )
+        .find_map(|item| {
+            let offset = self.orientation.make_vec(item.offset, 0);
+            item.child
+                .view
+                .locate(selector, item.child.size)
+                .map(|area| area + offset)
+        })
+    }
+
     fn important_area(&self, _: Vec2) -> Rect {
         if self.is_empty() {
             // Return dummy area if we are empty.
@@ -677,4 +914,207 @@ impl View for LinearLayout {
         // Add `offset` to the rect.
         rect + offset
     }
+
+    fn save_state(&self) -> Option<ViewState> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(ViewState::Selected(self.focus))
+        }
+    }
+
+    fn restore_state(&mut self, state: &ViewState) {
+        if let ViewState::Selected(i) = *state {
+            let source = direction::Direction::none();
+            if let Some(child) = self.children.get_mut(i) {
+                if child.view.take_focus(source) {
+                    self.focus = i;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::views::DummyView;
+
+    #[test]
+    fn weighted_children() {
+        let mut layout = LinearLayout::horizontal()
+            .child(DummyView)
+            .weighted_child(DummyView, 2)
+            .weighted_child(DummyView, 1);
+
+        // Each child requires 1 cell; 27 cells are left over.
+        layout.layout(Vec2::new(30, 1));
+
+        let widths: Vec<usize> =
+            layout.children.iter().map(|child| child.size.x).collect();
+        assert_eq!(widths, vec![1, 19, 10]);
+
+        // Weights can be changed at runtime.
+        layout.set_weight(0, 3);
+        layout.layout(Vec2::new(30, 1));
+
+        let widths: Vec<usize> =
+            layout.children.iter().map(|child| child.size.x).collect();
+        assert_eq!(widths, vec![14, 10, 6]);
+    }
+
+    #[test]
+    fn weighted_children_cache() {
+        use crate::views::Canvas;
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let requests = Rc::new(Cell::new(0));
+        let child = Canvas::new(Rc::clone(&requests))
+            .with_required_size(|requests, _| {
+                requests.set(requests.get() + 1);
+                Vec2::new(1, 1)
+            })
+            .with_needs_relayout(|_| false);
+        let mut layout = LinearLayout::horizontal()
+            .weighted_child(child, 1)
+            .child(DummyView);
+        let widths = |layout: &LinearLayout| -> Vec<usize> {
+            layout.children.iter().map(|child| child.size.x).collect()
+        };
+
+        let size = Vec2::new(10, 1);
+        layout.layout(size);
+        assert_eq!(widths(&layout), vec![9, 1]);
+        let count = requests.get();
+
+        // The same constraint keeps the distributed sizes.
+        layout.required_size(size);
+        layout.layout(size);
+        assert_eq!(requests.get(), count);
+        assert_eq!(widths(&layout), vec![9, 1]);
+
+        // A new one distributes the leftover space again.
+        layout.required_size(Vec2::new(6, 1));
+        layout.layout(Vec2::new(6, 1));
+        assert!(requests.get() > count);
+        assert_eq!(widths(&layout), vec![5, 1]);
+    }
+
+    #[test]
+    fn skip_clean_children() {
+        use crate::views::Canvas;
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        // Counts its layouts, and needs one when `dirty` is set.
+        let counter = |layouts: &Rc<Cell<usize>>, dirty: &Rc<Cell<bool>>| {
+            Canvas::new((Rc::clone(layouts), Rc::clone(dirty)))
+                .with_layout(|(layouts, dirty), _| {
+                    layouts.set(layouts.get() + 1);
+                    dirty.set(false);
+                })
+                .with_needs_relayout(|(_, dirty)| dirty.get())
+        };
+        let layouts = [Rc::new(Cell::new(0)), Rc::new(Cell::new(0))];
+        let dirty = [Rc::new(Cell::new(true)), Rc::new(Cell::new(true))];
+        let mut layout = LinearLayout::vertical()
+            .child(counter(&layouts[0], &dirty[0]))
+            .child(counter(&layouts[1], &dirty[1]));
+        let count = || (layouts[0].get(), layouts[1].get());
+
+        let size = Vec2::new(10, 5);
+        layout.layout(size);
+        assert_eq!(count(), (1, 1));
+        assert!(!layout.needs_relayout());
+
+        // Only the child which changed is laid out again.
+        dirty[1].set(true);
+        assert!(layout.needs_relayout());
+        layout.layout(size);
+        assert_eq!(count(), (1, 2));
+
+        // A new size reaches every child.
+        layout.layout(Vec2::new(12, 5));
+        assert_eq!(count(), (2, 3));
+    }
+
+    #[test]
+    fn focus_policy() {
+        use crate::views::Button;
+
+        let mut layout = LinearLayout::horizontal()
+            .child(Button::new("1", |_| ()))
+            .child(Button::new("2", |_| ()));
+        let tab = Event::Key(Key::Tab);
+
+        assert!(layout.on_event(tab.clone()).is_consumed());
+        assert_eq!(layout.get_focus_index(), 1);
+
+        // By default, the focus escapes to the parent.
+        assert!(!layout.on_event(tab.clone()).is_consumed());
+
+        layout.set_focus_policy(FocusPolicy::Stop);
+        assert!(layout.on_event(tab.clone()).is_consumed());
+        assert_eq!(layout.get_focus_index(), 1);
+
+        layout.set_focus_policy(FocusPolicy::Wrap);
+        assert!(layout.on_event(tab.clone()).is_consumed());
+        assert_eq!(layout.get_focus_index(), 0);
+        assert!(layout.on_event(Event::Key(Key::Left)).is_consumed());
+        assert_eq!(layout.get_focus_index(), 1);
+    }
+
+    #[test]
+    fn mouse_grab() {
+        use crate::event::MouseButton;
+        use crate::views::{Button, Canvas};
+
+        // The divider never takes the focus, but still gets the drag.
+        let divider = Canvas::new(Vec::new()).with_on_event(|events, event| {
+            events.push(event);
+            EventResult::Consumed(None)
+        });
+        let mut layout = LinearLayout::horizontal()
+            .child(Button::new("1", |_| ()))
+            .child(divider)
+            .child(Button::new("2", |_| ()));
+        layout.layout(Vec2::new(20, 1));
+
+        let mouse = |x, event| Event::Mouse {
+            offset: Vec2::zero(),
+            position: Vec2::new(x, 0),
+            event,
+        };
+        let left = MouseButton::Left;
+        layout.on_event(mouse(3, MouseEvent::Press(left)));
+        layout.on_event(mouse(15, MouseEvent::Hold(left)));
+        layout.on_event(mouse(15, MouseEvent::Release(left)));
+        assert_eq!(layout.get_focus_index(), 0);
+
+        let divider = layout.get_child_mut(1).unwrap().as_any_mut();
+        let divider = divider.downcast_mut::<Canvas<Vec<Event>>>().unwrap();
+        assert_eq!(divider.state_mut().len(), 3);
+    }
+
+    #[test]
+    fn drag_over_keeps_focus() {
+        use crate::views::Button;
+
+        let mut layout = LinearLayout::horizontal()
+            .child(Button::new("1", |_| ()))
+            .child(Button::new("2", |_| ()));
+        layout.layout(Vec2::new(20, 1));
+
+        let mouse = |event| Event::Mouse {
+            offset: Vec2::zero(),
+            position: Vec2::new(4, 0),
+            event,
+        };
+        layout.on_event(mouse(MouseEvent::DragOver));
+        assert_eq!(layout.get_focus_index(), 0);
+
+        layout.on_event(mouse(MouseEvent::Drop));
+        assert_eq!(layout.get_focus_index(), 1);
+    }
 }
@@ -1,14 +1,17 @@
 use crate::direction;
-use crate::event::{AnyCb, Callback, Event, EventResult, Key};
+use crate::event::{AnyCb, Callback, Event, EventResult, Key, MouseEvent};
 use crate::rect::Rect;
+use crate::theme::ColorStyle;
+use crate::utils::markup::StyledString;
+use crate::utils::width::TextWidth;
 use crate::vec::Vec2;
-use crate::view::{Selector, View};
+use crate::view::{on_child_edge, Selector, View};
 use crate::Cursive;
 use crate::Printer;
 use crate::With;
 use log::debug;
+use std::cmp::max;
 use std::rc::Rc;
-use unicode_width::UnicodeWidthStr;
 
 /// Represents a child from a [`ListView`].
 ///
@@ -18,6 +21,10 @@ pub enum ListChild {
     Row(String, Box<dyn View>),
     /// A delimiter between groups.
     Delimiter,
+    /// A header starting a new section.
+    ///
+    /// It spans the entire width, and cannot be focused.
+    Header(StyledString),
 }
 
 impl ListChild {
@@ -39,6 +46,8 @@ impl ListChild {
 /// Displays a list of elements.
 pub struct ListView {
     children: Vec<ListChild>,
+    // Indentation of each child's label, kept in sync with `children`.
+    indents: Vec<usize>,
     focus: usize,
     // This callback is called when the selection is changed.
     on_select: Option<Rc<dyn Fn(&mut Cursive, &String)>>,
@@ -52,6 +61,7 @@ impl ListView {
     pub fn new() -> Self {
         ListView {
             children: Vec::new(),
+            indents: Vec::new(),
             focus: 0,
             on_select: None,
             last_size: Vec2::zero(),
@@ -89,16 +99,186 @@ impl ListView {
         &mut self.children[id]
     }
 
+    /// Returns an iterator on the rows of this list, with their labels.
+    ///
+    /// Delimiters and headers are skipped.
+    pub fn labeled_children(&self) -> impl Iterator<Item = (&str, &dyn View)> {
+        self.children.iter().filter_map(|child| match *child {
+            ListChild::Row(ref label, ref view) => {
+                Some((label.as_str(), view.as_ref()))
+            }
+            _ => None,
+        })
+    }
+
+    /// Returns a mutable iterator on the rows of this list, with their labels.
+    ///
+    /// Delimiters and headers are skipped.
+    pub fn labeled_children_mut(
+        &mut self,
+    ) -> impl Iterator<Item = (&str, &mut dyn View)> {
+        self.children.iter_mut().filter_map(|child| match *child {
+            ListChild::Row(ref label, ref mut view) => {
+                Some((label.as_str(), view.as_mut()))
+            }
+            _ => None,
+        })
+    }
+
+    /// Returns the position of the first row with the given label.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::views::{EditView, ListView};
+    /// let mut list = ListView::new()
+    ///     .child("Name", EditView::new())
+    ///     .child("Email", EditView::new());
+    ///
+    /// assert_eq!(list.find_child("Email"), Some(1));
+    ///
+    /// list.remove_child_by_label("Name");
+    /// assert_eq!(list.find_child("Email"), Some(0));
+    /// ```
+    pub fn find_child(&self, label: &str) -> Option<usize> {
+        self.children.iter().position(|child| match *child {
+            ListChild::Row(ref l, _) => l == label,
+            _ => false,
+        })
+    }
+
+    /// Returns the view in the first row with the given label.
+    pub fn get_child_by_label(&self, label: &str) -> Option<&dyn View> {
+        self.labeled_children()
+            .find(|&(l, _)| l == label)
+            .map(|(_, view)| view)
+    }
+
+    /// Returns the view in the first row with the given label.
+    pub fn get_child_by_label_mut(
+        &mut self, label: &str,
+    ) -> Option<&mut dyn View> {
+        self.labeled_children_mut()
+            .find(|&(l, _)| l == label)
+            .map(|(_, view)| view)
+    }
+
+    /// Removes the first row with the given label, and returns it.
+    pub fn remove_child_by_label(&mut self, label: &str) -> Option<ListChild> {
+        self.find_child(label).map(|i| self.remove_child(i))
+    }
+
     /// Adds a view to the end of the list.
-    pub fn add_child<V: View + 'static>(&mut self, label: &str, mut view: V) {
+    pub fn add_child<V: View + 'static>(&mut self, label: &str, view: V) {
+        let index = self.children.len();
+        self.insert_child(index, label, view);
+    }
+
+    /// Adds a view to the end of the list, with an indented label.
+    ///
+    /// `indent` is the number of blank cells printed before the label.
+    pub fn add_indented_child<V: View + 'static>(
+        &mut self, label: &str, indent: usize, view: V,
+    ) {
+        self.add_child(label, view);
+        self.indents[self.children.len() - 1] = indent;
+    }
+
+    /// Adds a view to the end of the list, with an indented label.
+    ///
+    /// Chainable variant.
+    pub fn indented_child<V: View + 'static>(
+        self, label: &str, indent: usize, view: V,
+    ) -> Self {
+        self.with(|s| s.add_indented_child(label, indent, view))
+    }
+
+    /// Inserts a view at position `index`, shifting all children after it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > self.len()`.
+    pub fn insert_child<V: View + 'static>(
+        &mut self, index: usize, label: &str, mut view: V,
+    ) {
         view.take_focus(direction::Direction::none());
-        self.children
-            .push(ListChild::Row(label.to_string(), Box::new(view)));
+        self.insert(
+            index,
+            ListChild::Row(label.to_string(), Box::new(view)),
+        );
+    }
+
+    /// Inserts a delimiter at position `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > self.len()`.
+    pub fn insert_delimiter(&mut self, index: usize) {
+        self.insert(index, ListChild::Delimiter);
+    }
+
+    /// Inserts a section header at position `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > self.len()`.
+    pub fn insert_header<S>(&mut self, index: usize, header: S)
+    where
+        S: Into<StyledString>,
+    {
+        self.insert(index, ListChild::Header(header.into()));
+    }
+
+    /// Removes the child at position `index`, and returns it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index >= self.len()`.
+    pub fn remove_child(&mut self, index: usize) -> ListChild {
+        self.indents.remove(index);
+        let child = self.children.remove(index);
+
+        if self.focus > index
+            || (self.focus == self.children.len() && self.focus > 0)
+        {
+            self.focus -= 1;
+        }
+
+        child
+    }
+
+    /// Sets the indentation of the label for the child at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index >= self.len()`.
+    pub fn set_child_indent(&mut self, index: usize, indent: usize) {
+        self.indents[index] = indent;
+    }
+
+    /// Returns the indentation of the label for the child at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index >= self.len()`.
+    pub fn get_child_indent(&self, index: usize) -> usize {
+        self.indents[index]
+    }
+
+    fn insert(&mut self, index: usize, child: ListChild) {
+        self.children.insert(index, child);
+        self.indents.insert(index, 0);
+
+        // Keep the same child focused.
+        if index <= self.focus && self.children.len() > 1 {
+            self.focus += 1;
+        }
     }
 
     /// Removes all children from this view.
     pub fn clear(&mut self) {
         self.children.clear();
+        self.indents.clear();
         self.focus = 0;
     }
 
@@ -111,7 +291,8 @@ impl ListView {
 
     /// Adds a delimiter to the end of the list.
     pub fn add_delimiter(&mut self) {
-        self.children.push(ListChild::Delimiter);
+        let index = self.children.len();
+        self.insert_delimiter(index);
     }
 
     /// Adds a delimiter to the end of the list.
@@ -121,6 +302,34 @@ impl ListView {
         self.with(Self::add_delimiter)
     }
 
+    /// Adds a section header to the end of the list.
+    ///
+    /// Headers are drawn with the title color, unless the given text is
+    /// already styled.
+    pub fn add_header<S: Into<StyledString>>(&mut self, header: S) {
+        let index = self.children.len();
+        self.insert_header(index, header);
+    }
+
+    /// Adds a section header to the end of the list.
+    ///
+    /// Chainable variant.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::views::{Checkbox, EditView, ListView};
+    /// let list = ListView::new()
+    ///     .header("Account")
+    ///     .indented_child("Name", 2, EditView::new())
+    ///     .indented_child("Email", 2, EditView::new())
+    ///     .header("Options")
+    ///     .indented_child("Newsletter", 2, Checkbox::new());
+    /// ```
+    pub fn header<S: Into<StyledString>>(self, header: S) -> Self {
+        self.with(|s| s.add_header(header))
+    }
+
     /// Sets a callback to be used when an item is selected.
     pub fn set_on_select<F>(&mut self, cb: F)
     where
@@ -196,42 +405,47 @@ impl ListView {
     fn labels_width(&self) -> usize {
         self.children
             .iter()
-            .map(ListChild::label)
-            .map(UnicodeWidthStr::width)
+            .zip(&self.indents)
+            .filter_map(|(child, indent)| match *child {
+                ListChild::Row(ref label, _) => Some(indent + label.width()),
+                _ => None,
+            })
             .max()
             .unwrap_or(0)
     }
 
-    fn check_focus_grab(&mut self, event: &Event) {
+    // Returns the row under the mouse for drags passing over it.
+    fn check_focus_grab(&mut self, event: &Event) -> Option<usize> {
         if let Event::Mouse {
             offset,
             position,
             event,
         } = *event
         {
-            if !event.grabs_focus() {
-                return;
+            if !event.targets_hovered() {
+                return None;
             }
 
-            let position = match position.checked_sub(offset) {
-                None => return,
-                Some(pos) => pos,
-            };
+            let position = position.checked_sub(offset)?;
 
             // eprintln!("Rel pos: {:?}", position);
 
             // Now that we have a relative position, checks for buttons?
             let focus = position.y;
             if focus >= self.children.len() {
-                return;
+                return None;
             }
 
             if let ListChild::Row(_, ref mut view) = self.children[focus] {
+                if event == MouseEvent::DragOver {
+                    return Some(focus);
+                }
                 if view.take_focus(direction::Direction::none()) {
                     self.focus = focus;
                 }
             }
         }
+        None
     }
 }
 
@@ -239,7 +453,7 @@ fn try_focus(
     (i, child): (usize, &mut ListChild), source: direction::Direction,
 ) -> Option<usize> {
     match *child {
-        ListChild::Delimiter => None,
+        ListChild::Delimiter | ListChild::Header(_) => None,
         ListChild::Row(_, ref mut view) => {
             if view.take_focus(source) {
                 Some(i)
@@ -262,11 +476,16 @@ impl View for ListView {
         for (i, child) in self.children.iter().enumerate() {
             match child {
                 ListChild::Row(ref label, ref view) => {
-                    printer.print((0, i), label);
+                    printer.print((self.indents[i], i), label);
                     view.draw(
                         &printer.offset((offset, i)).focused(i == self.focus),
                     );
                 }
+                ListChild::Header(ref header) => {
+                    printer.with_color(ColorStyle::title_primary(), |p| {
+                        p.print_styled((0, i), header.into())
+                    });
+                }
                 ListChild::Delimiter => (),
             }
         }
@@ -274,13 +493,7 @@ impl View for ListView {
 
     fn required_size(&mut self, req: Vec2) -> Vec2 {
         // We'll show 2 columns: the labels, and the views.
-        let label_width = self
-            .children
-            .iter()
-            .map(ListChild::label)
-            .map(UnicodeWidthStr::width)
-            .max()
-            .unwrap_or(0);
+        let label_width = self.labels_width();
 
         let view_size = self
             .children
@@ -290,20 +503,28 @@ impl View for ListView {
             .max()
             .unwrap_or(0);
 
-        Vec2::new(label_width + 1 + view_size, self.children.len())
+        // Headers take the entire row.
+        let header_width = self
+            .children
+            .iter()
+            .filter_map(|child| match *child {
+                ListChild::Header(ref header) => Some(header.width()),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0);
+
+        Vec2::new(
+            max(label_width + 1 + view_size, header_width),
+            self.children.len(),
+        )
     }
 
     fn layout(&mut self, size: Vec2) {
         self.last_size = size;
 
         // We'll show 2 columns: the labels, and the views.
-        let label_width = self
-            .children
-            .iter()
-            .map(ListChild::label)
-            .map(UnicodeWidthStr::width)
-            .max()
-            .unwrap_or(0);
+        let label_width = self.labels_width();
 
         let spacing = 1;
 
@@ -316,20 +537,29 @@ impl View for ListView {
         }
     }
 
+    fn mark_dirty(&mut self) {
+        for child in self.children.iter_mut().filter_map(ListChild::view) {
+            child.mark_dirty();
+        }
+    }
+
     fn on_event(&mut self, event: Event) -> EventResult {
         if self.children.is_empty() {
             return EventResult::Ignored;
         }
 
-        self.check_focus_grab(&event);
+        let hovered = self.check_focus_grab(&event);
 
         // Send the event to the focused child.
         let labels_width = self.labels_width();
-        if let ListChild::Row(_, ref mut view) = self.children[self.focus] {
+        let target = hovered.unwrap_or(self.focus);
+        if let ListChild::Row(_, ref mut view) = self.children[target] {
             // If self.focus < self.scrollbase.start_line, it means the focus is not
             // in view. Something's fishy, so don't send the event.
-            let offset = (labels_width + 1, self.focus);
-            let result = view.on_event(event.relativized(offset));
+            let offset = (labels_width + 1, target);
+            let result = view
+                .on_event(event.relativized(offset))
+                .or_else(|| on_child_edge(&mut **view, &event));
             if result.is_consumed() {
                 return result;
             }
@@ -382,6 +612,7 @@ impl View for ListView {
     fn call_on_any<'a>(
         &mut self, selector: &Selector<'_>, mut callback: AnyCb<'a>,
     ) {
+        selector.call_on_self(self, &mut callback);
         for view in self.children.iter_mut().filter_map(ListChild::view) {
             view.call_on_any(selector, Box::new(|any| callback(any)));
         }
@@ -403,6 +634,18 @@ impl View for ListView {
         }
     }
 
+    fn locate(&self, selector: &Selector<'_>, size: Vec2) -> Option<Rect> {
+        let labels_width = self.labels_width();
+        let available = Vec2::new(size.x.saturating_sub(labels_width + 1), 1);
+
+        self.children.iter().enumerate().find_map(|(i, child)| match *child {
+            ListChild::Row(_, ref view) => view
+                .locate(selector, available)
+                .map(|area| area + (labels_width + 1, i)),
+            _ => None,
+        })
+    }
+
     fn important_area(&self, size: Vec2) -> Rect {
         if self.children.is_empty() {
             return Rect::from((0, 0));
@@ -416,7 +659,9 @@ impl View for ListView {
                     Vec2::new(size.x.saturating_sub(labels_width + 1), 1);
                 view.important_area(available) + (labels_width, 0)
             }
-            ListChild::Delimiter => Rect::from_size((0, 0), (size.x, 1)),
+            ListChild::Delimiter | ListChild::Header(_) => {
+                Rect::from_size((0, 0), (size.x, 1))
+            }
         };
 
         area + (0, self.focus)
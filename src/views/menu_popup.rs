@@ -4,6 +4,7 @@ use crate::event::{
 };
 use crate::menu::{MenuItem, MenuTree};
 use crate::rect::Rect;
+use crate::utils::width::TextWidth;
 use crate::vec::Vec2;
 use crate::view::scroll;
 use crate::view::{Position, View};
@@ -13,7 +14,6 @@ use crate::Printer;
 use crate::With;
 use std::cmp::min;
 use std::rc::Rc;
-use unicode_width::UnicodeWidthStr;
 
 /// Popup that shows a list of items.
 pub struct MenuPopup {
@@ -51,6 +51,14 @@ impl MenuPopup {
         }
     }
 
+    /// Replaces the menu tree shown by this popup.
+    ///
+    /// The focus is kept when possible.
+    pub fn set_menu(&mut self, menu: Rc<MenuTree>) {
+        self.menu = menu;
+        self.focus = min(self.focus, self.menu.len().saturating_sub(1));
+    }
+
     /// Sets the currently focused element.
     pub fn set_focus(&mut self, focus: usize) {
         self.focus = min(focus, self.menu.len());
@@ -72,6 +80,9 @@ impl MenuPopup {
         match *item {
             MenuItem::Delimiter => 1,
             MenuItem::Leaf(ref title, _) => title.width(),
+            // Room for the check mark.
+            MenuItem::Toggle(ref title, _, _)
+            | MenuItem::Radio(ref title, _, _, _) => title.width() + 4,
             MenuItem::Subtree(ref title, _) => title.width() + 3,
         }
     }
@@ -159,8 +170,12 @@ impl MenuPopup {
 
     fn submit(&mut self) -> EventResult {
         match self.menu.children[self.focus] {
-            MenuItem::Leaf(_, ref cb) => {
-                let cb = cb.clone();
+            MenuItem::Subtree(_, ref tree) => self.make_subtree_cb(tree),
+            MenuItem::Delimiter => {
+                unreachable!("Delimiters cannot be submitted.")
+            }
+            _ => {
+                let cb = self.menu.activate(self.focus).unwrap();
                 let action_cb = self.on_action.clone();
                 EventResult::with_cb(move |s| {
                     // Remove ourselves from the face of the earth
@@ -173,8 +188,6 @@ impl MenuPopup {
                     cb.clone()(s);
                 })
             }
-            MenuItem::Subtree(_, ref tree) => self.make_subtree_cb(tree),
-            _ => unreachable!("Delimiters cannot be submitted."),
         }
     }
 
@@ -358,6 +371,21 @@ impl View for MenuPopup {
                         printer.print_hline((0, 0), printer.size.x, " ");
                         printer.print((1, 0), label);
                     }
+                    MenuItem::Toggle(ref label, ref state, _)
+                    | MenuItem::Radio(ref label, _, ref state, _) => {
+                        if printer.size.x < 2 {
+                            return;
+                        }
+                        let mark = match (item.radio_group(), state.get()) {
+                            (None, true) => "[x]",
+                            (None, false) => "[ ]",
+                            (Some(_), true) => "(*)",
+                            (Some(_), false) => "( )",
+                        };
+                        printer.print_hline((0, 0), printer.size.x, " ");
+                        printer.print((1, 0), mark);
+                        printer.print((5, 0), label);
+                    }
                 }
             });
         });
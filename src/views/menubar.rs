@@ -3,13 +3,14 @@ use crate::event::*;
 use crate::menu::{MenuItem, MenuTree};
 use crate::rect::Rect;
 use crate::theme::ColorStyle;
+use crate::utils::width::TextWidth;
 use crate::vec::Vec2;
 use crate::view::{Position, View};
 use crate::views::{MenuPopup, OnEventView};
 use crate::Cursive;
 use crate::Printer;
+use std::cmp::min;
 use std::rc::Rc;
-use unicode_width::UnicodeWidthStr;
 
 /// Current state of the menubar
 #[derive(PartialEq, Debug)]
@@ -42,6 +43,16 @@ pub struct Menubar {
 
     // TODO: make Menubar impl View and take out the State management
     state: State,
+
+    // Set when the menu tree may have been modified.
+    // Open menus are then updated on the next refresh.
+    changed: bool,
+
+    // Event selecting the menubar, if not used by the views.
+    reveal_event: Option<Event>,
+
+    // If `true`, a click on the top row selects the menubar.
+    reveal_on_mouse: bool,
 }
 
 new_default!(Menubar);
@@ -54,11 +65,58 @@ impl Menubar {
             autohide: true,
             state: State::Inactive,
             focus: 0,
+            changed: false,
+            reveal_event: None,
+            reveal_on_mouse: false,
+        }
+    }
+
+    /// Sets an event that selects the menubar.
+    ///
+    /// The event is only used if no view handled it.
+    ///
+    /// This is mostly useful with `autohide`, to reveal the hidden menubar,
+    /// for instance with `Key::Esc`. `None` (the default) disables it.
+    pub fn set_reveal_event<E: Into<Event>>(&mut self, event: Option<E>) {
+        self.reveal_event = event.map(Into::into);
+    }
+
+    /// Returns the event that selects the menubar, if any.
+    pub fn reveal_event(&self) -> Option<&Event> {
+        self.reveal_event.as_ref()
+    }
+
+    /// If `true`, a click on the top row of the screen selects the menubar.
+    ///
+    /// When `autohide` is enabled, the click is only used if no view
+    /// handled it. Disabled by default.
+    pub fn set_reveal_on_mouse(&mut self, reveal: bool) {
+        self.reveal_on_mouse = reveal;
+    }
+
+    /// Returns `true` if a click on the top row selects the menubar.
+    pub fn reveal_on_mouse(&self) -> bool {
+        self.reveal_on_mouse
+    }
+
+    /// Returns `true` if the given ignored event should select the menubar.
+    pub(crate) fn reveals_on(&self, event: &Event) -> bool {
+        if self.root.is_empty() || self.state != State::Inactive {
+            return false;
+        }
+
+        match *event {
+            Event::Mouse {
+                event, position, ..
+            } => {
+                self.reveal_on_mouse && event.grabs_focus() && position.y == 0
+            }
+            ref event => self.reveal_event.as_ref() == Some(event),
         }
     }
 
     /// Hides the menubar.
-    fn hide(&mut self) {
+    pub(crate) fn hide(&mut self) {
         self.state = State::Inactive;
     }
 
@@ -113,6 +171,7 @@ impl Menubar {
         S: Into<String>,
     {
         self.root.insert_subtree(i, title, menu);
+        self.changed = true;
         self
     }
 
@@ -121,6 +180,7 @@ impl Menubar {
     /// It will show up as `|`.
     pub fn insert_delimiter(&mut self, i: usize) -> &mut Self {
         self.root.insert_delimiter(i);
+        self.changed = true;
         self
     }
 
@@ -133,6 +193,7 @@ impl Menubar {
         F: 'static + Fn(&mut Cursive),
     {
         self.root.insert_leaf(i, title, cb);
+        self.changed = true;
         self
     }
 
@@ -140,6 +201,7 @@ impl Menubar {
     pub fn clear(&mut self) {
         self.root.clear();
         self.focus = 0;
+        self.changed = true;
     }
 
     /// Returns the number of items in this menubar.
@@ -156,14 +218,56 @@ impl Menubar {
     ///
     /// Returns `None` if `i > self.len()`
     pub fn get_subtree(&mut self, i: usize) -> Option<&mut MenuTree> {
+        self.changed = true;
         self.root.get_subtree(i)
     }
 
     /// Looks for an item with the given label.
+    ///
+    /// Changes made to the returned tree will show up in open menus.
     pub fn find_subtree(&mut self, label: &str) -> Option<&mut MenuTree> {
+        self.changed = true;
         self.root.find_subtree(label)
     }
 
+    /// Looks for an item, following the given path of labels.
+    ///
+    /// For instance, `&["File", "Open"]` looks for the `Open` item in the
+    /// `File` subtree.
+    ///
+    /// Returns `None` if no such item was found.
+    pub fn find_path(&mut self, path: &[&str]) -> Option<&mut MenuItem> {
+        self.changed = true;
+        self.root.find_path(path)
+    }
+
+    /// Removes the subtree with the given label, and returns it.
+    ///
+    /// Returns `None` if no such subtree was found.
+    pub fn remove_subtree(&mut self, label: &str) -> Option<MenuTree> {
+        let i = self.find_position(label)?;
+        if !self.root.children[i].is_subtree() {
+            return None;
+        }
+
+        match self.take(i) {
+            MenuItem::Subtree(_, tree) => Some(
+                Rc::try_unwrap(tree).unwrap_or_else(|tree| (*tree).clone()),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Renames the item with the given label.
+    ///
+    /// Returns `false` if no such label was found.
+    pub fn rename<S: Into<String>>(
+        &mut self, label: &str, new_label: S,
+    ) -> bool {
+        self.changed = true;
+        self.root.rename_item(label, new_label)
+    }
+
     /// Returns the position of the item with the given label.
     ///
     /// Returns `None` if no such label was found.
@@ -173,7 +277,28 @@ impl Menubar {
 
     /// Remove the item at the given position.
     pub fn remove(&mut self, i: usize) {
-        self.root.remove(i);
+        self.take(i);
+    }
+
+    // Removes the item at the given position, and returns it.
+    fn take(&mut self, i: usize) -> MenuItem {
+        let item = self.root.children.remove(i);
+        self.focus = min(self.focus, self.root.len().saturating_sub(1));
+        self.changed = true;
+        item
+    }
+
+    /// Returns `true` if the menu tree may have changed since last time.
+    pub(crate) fn take_changed(&mut self) -> bool {
+        std::mem::replace(&mut self.changed, false)
+    }
+
+    /// Returns the subtree for the focused item, if any.
+    pub(crate) fn focused_subtree(&self) -> Option<Rc<MenuTree>> {
+        match self.root.children.get(self.focus) {
+            Some(MenuItem::Subtree(_, tree)) => Some(Rc::clone(tree)),
+            _ => None,
+        }
     }
 
     fn child_at(&self, x: usize) -> Option<usize> {
@@ -194,10 +319,15 @@ impl Menubar {
 
     fn select_child(&mut self, open_only: bool) -> EventResult {
         match self.root.children[self.focus] {
-            MenuItem::Leaf(_, ref cb) if !open_only => {
+            MenuItem::Leaf(_, _)
+            | MenuItem::Toggle(_, _, _)
+            | MenuItem::Radio(_, _, _, _)
+                if !open_only =>
+            {
                 // Go inactive after an action.
                 self.state = State::Inactive;
-                EventResult::Consumed(Some(cb.clone()))
+                self.changed = true;
+                EventResult::Consumed(self.root.activate(self.focus))
             }
             MenuItem::Subtree(_, ref tree) => {
                 // First, we need a new Rc to send the callback,
@@ -45,6 +45,7 @@ mod dialog;
 mod dummy;
 mod edit_view;
 mod enableable_view;
+mod find_replace_bar;
 mod hideable_view;
 mod id_view;
 mod layer;
@@ -65,21 +66,24 @@ mod slider_view;
 mod stack_view;
 mod text_area;
 mod text_view;
+mod themed_view;
 mod tracked_view;
 mod view_box;
 
 pub use self::box_view::BoxView;
 pub use self::button::Button;
 pub use self::canvas::Canvas;
-pub use self::checkbox::Checkbox;
+pub use self::checkbox::{CheckState, Checkbox};
 pub use self::circular_focus::CircularFocus;
 pub use self::debug_view::{DebugView, DebugViewFilter};
-pub use self::dialog::{Dialog, DialogFocus};
+pub use self::dialog::{ButtonLayout, Dialog, DialogFocus};
 pub use self::dummy::DummyView;
-pub use self::edit_view::EditView;
+pub use self::edit_view::{EditView, LengthUnit};
 pub use self::enableable_view::EnableableView;
-pub use self::hideable_view::HideableView;
-pub use self::id_view::{IdView, ViewRef};
+pub use self::find_replace_bar::FindReplaceBar;
+pub use self::hideable_view::{HideAnimation, HideableView};
+pub use self::id_view::{AnyIdView, IdError, IdView, ViewRef, WeakViewRef};
+pub(crate) use self::id_view::{find_duplicate, find_id_views};
 pub use self::layer::Layer;
 pub use self::linear_layout::LinearLayout;
 pub use self::list_view::{ListChild, ListView};
@@ -95,8 +99,11 @@ pub use self::select_view::SelectView;
 pub use self::shadow_view::ShadowView;
 pub use self::sized_view::SizedView;
 pub use self::slider_view::SliderView;
-pub use self::stack_view::{LayerPosition, StackView};
-pub use self::text_area::TextArea;
-pub use self::text_view::{TextContent, TextContentRef, TextView};
+pub use self::stack_view::{LayerPosition, StackView, Transition};
+pub use self::text_area::{LineNumbers, TextArea};
+pub use self::text_view::{
+    TextContent, TextContentRef, TextView, Truncation,
+};
+pub use self::themed_view::ThemedView;
 pub use self::tracked_view::TrackedView;
 pub use self::view_box::ViewBox;
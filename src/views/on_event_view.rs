@@ -29,6 +29,11 @@ use std::rc::Rc;
 /// [`on_event_inner`]: struct.OnEventView.html#method.on_event_inner
 /// [`on_pre_event_inner`]: struct.OnEventView.html#method.on_pre_event_inner
 ///
+/// Before any of that, events can be transformed with [`on_pre_event_map`],
+/// for instance to remap keys.
+///
+/// [`on_pre_event_map`]: struct.OnEventView.html#method.on_pre_event_map
+///
 /// # Examples
 ///
 /// ```
@@ -41,10 +46,13 @@ use std::rc::Rc;
 pub struct OnEventView<T: View> {
     view: T,
     callbacks: Vec<(EventTrigger, Action<T>)>,
+    maps: Vec<(EventTrigger, EventMap)>,
 }
 
 type InnerCallback<T> = Rc<Box<dyn Fn(&mut T, &Event) -> Option<EventResult>>>;
 
+type EventMap = Box<dyn Fn(&Event) -> Event>;
+
 struct Action<T> {
     phase: TriggerPhase,
     callback: InnerCallback<T>,
@@ -71,6 +79,7 @@ impl<T: View> OnEventView<T> {
         OnEventView {
             view,
             callbacks: Vec::new(),
+            maps: Vec::new(),
         }
     }
 
@@ -123,7 +132,24 @@ impl<T: View> OnEventView<T> {
     /// * Otherwise, it bypasses the child view and directly processes the
     ///   result.
     ///
+    /// Returning `None` can be used to simply observe events.
+    ///
     /// Chainable variant.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::views::{OnEventView, DummyView};
+    /// # use cursive::event::{Event, EventTrigger};
+    /// // Log every event, without changing anything.
+    /// let view = OnEventView::new(DummyView).on_pre_event_inner(
+    ///     EventTrigger::any(),
+    ///     |_, event: &Event| {
+    ///         eprintln!("Event: {:?}", event);
+    ///         None
+    ///     },
+    /// );
+    /// ```
     pub fn on_pre_event_inner<F, E>(self, trigger: E, cb: F) -> Self
     where
         E: Into<EventTrigger>,
@@ -132,6 +158,32 @@ impl<T: View> OnEventView<T> {
         self.with(|s| s.set_on_pre_event_inner(trigger, cb))
     }
 
+    /// Replaces the given event with another one, before anything else.
+    ///
+    /// Every other callback, and the child view, will only see the new
+    /// event. Maps run in the order they were added, each one seeing the
+    /// result of the previous ones.
+    ///
+    /// Chainable variant.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::views::{OnEventView, TextArea};
+    /// # use cursive::event::{Event, Key};
+    /// // Move around with vim keys.
+    /// let view = OnEventView::new(TextArea::new())
+    ///     .on_pre_event_map(Event::CtrlChar('j'), |_| Event::Key(Key::Down))
+    ///     .on_pre_event_map(Event::CtrlChar('k'), |_| Event::Key(Key::Up));
+    /// ```
+    pub fn on_pre_event_map<F, E>(self, trigger: E, map: F) -> Self
+    where
+        E: Into<EventTrigger>,
+        F: Fn(&Event) -> Event + 'static,
+    {
+        self.with(|s| s.set_on_pre_event_map(trigger, map))
+    }
+
     /// Registers a callback when the given event is ignored by the child.
     ///
     /// This is an advanced method to get more control.
@@ -246,9 +298,25 @@ impl<T: View> OnEventView<T> {
         ));
     }
 
+    /// Replaces the given event with another one, before anything else.
+    ///
+    /// Every other callback, and the child view, will only see the new
+    /// event. Maps run in the order they were added, each one seeing the
+    /// result of the previous ones.
+    pub fn set_on_pre_event_map<F, E>(&mut self, trigger: E, map: F)
+    where
+        E: Into<EventTrigger>,
+        F: Fn(&Event) -> Event + 'static,
+    {
+        self.maps.push((trigger.into(), Box::new(map)));
+    }
+
     /// Remove any callbacks defined for this view.
+    ///
+    /// This includes event maps.
     pub fn clear_callbacks(&mut self) {
         self.callbacks.clear();
+        self.maps.clear();
     }
 
     inner_getters!(self.view: T);
@@ -258,6 +326,14 @@ impl<T: View> ViewWrapper for OnEventView<T> {
     wrap_impl!(self.view: T);
 
     fn wrap_on_event(&mut self, event: Event) -> EventResult {
+        let event = self.maps.iter().fold(event, |event, (trigger, map)| {
+            if trigger.apply(&event) {
+                map(&event)
+            } else {
+                event
+            }
+        });
+
         // Until we have better closure capture, define captured members separately.
         let callbacks = &self.callbacks;
         let view = &mut self.view;
@@ -299,3 +375,24 @@ impl<T: View> ViewWrapper for OnEventView<T> {
             })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::Key;
+    use crate::views::DummyView;
+
+    #[test]
+    fn pre_event_map() {
+        let mut view = OnEventView::new(DummyView)
+            .on_pre_event_map('j', |_| Event::Key(Key::Down))
+            .on_pre_event_inner(Key::Down, |_, _| {
+                Some(EventResult::Consumed(None))
+            });
+
+        let mut send = |event| View::on_event(&mut view, event).is_consumed();
+        assert!(send(Event::Char('j')));
+        assert!(send(Event::Key(Key::Down)));
+        assert!(!send(Event::Char('k')));
+    }
+}
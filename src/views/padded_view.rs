@@ -1,6 +1,7 @@
 use crate::event::{Event, EventResult};
+use crate::rect::Rect;
 use crate::vec::Vec2;
-use crate::view::{Margins, View, ViewWrapper};
+use crate::view::{Margins, Selector, View, ViewWrapper};
 use crate::Printer;
 
 /// Adds padding to another view.
@@ -62,4 +63,13 @@ impl<V: View> ViewWrapper for PaddedView<V> {
         let printer = &printer.offset(top_left).shrinked(bot_right);
         self.view.draw(printer);
     }
+
+    fn wrap_locate(
+        &self, selector: &Selector<'_>, size: Vec2,
+    ) -> Option<Rect> {
+        let inner_size = size.saturating_sub(self.margins.combined());
+        self.view
+            .locate(selector, inner_size)
+            .map(|area| area + self.margins.top_left())
+    }
 }
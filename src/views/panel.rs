@@ -2,11 +2,13 @@ use crate::align::*;
 use crate::event::{Event, EventResult};
 use crate::rect::Rect;
 use crate::theme::ColorStyle;
+use crate::utils::markup::StyledString;
+use crate::utils::span::SpannedStr;
 use crate::vec::Vec2;
-use crate::view::{View, ViewWrapper};
+use crate::view::{Selector, View, ViewWrapper};
+use crate::views::TextContent;
 use crate::Printer;
 use crate::With;
-use unicode_width::UnicodeWidthStr;
 
 /// Draws a border around a wrapped view.
 #[derive(Debug)]
@@ -15,7 +17,12 @@ pub struct Panel<V: View> {
     view: V,
 
     // Possibly empty title.
-    title: String,
+    //
+    // It can be shared, to update the title from elsewhere.
+    title: TextContent,
+
+    // Possibly empty subtitle, shown on the bottom border.
+    subtitle: StyledString,
 
     // Where to put the title position
     title_position: HAlign,
@@ -29,7 +36,8 @@ impl<V: View> Panel<V> {
     pub fn new(view: V) -> Self {
         Panel {
             view,
-            title: String::new(),
+            title: TextContent::new(""),
+            subtitle: StyledString::new(),
             title_position: HAlign::Center,
             invalidated: true,
         }
@@ -40,13 +48,55 @@ impl<V: View> Panel<V> {
     /// Sets the title of the dialog.
     ///
     /// If not empty, it will be visible at the top.
-    pub fn title<S: Into<String>>(self, label: S) -> Self {
+    ///
+    /// The title can be styled, for instance using a `StyledString`.
+    /// Unstyled parts use the `TitlePrimary` color.
+    pub fn title<S: Into<StyledString>>(self, label: S) -> Self {
         self.with(|s| s.set_title(label))
     }
 
     /// Sets the title of the dialog.
-    pub fn set_title<S: Into<String>>(&mut self, label: S) {
-        self.title = label.into();
+    pub fn set_title<S: Into<StyledString>>(&mut self, label: S) {
+        self.title.set_content(label);
+        self.invalidate();
+    }
+
+    /// Returns a handle on the title.
+    ///
+    /// It can be used to change the title later, possibly from another
+    /// thread, without having to find this view.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::views::{Panel, TextView};
+    /// let panel = Panel::new(TextView::new("Content")).title("Loading");
+    /// let mut title = panel.get_title_content();
+    ///
+    /// // Later
+    /// title.set_content("Done");
+    /// ```
+    pub fn get_title_content(&self) -> TextContent {
+        self.title.clone()
+    }
+
+    /// Uses the given handle for the title.
+    pub fn set_title_content(&mut self, content: TextContent) {
+        self.title = content;
+        self.invalidate();
+    }
+
+    /// Sets the subtitle of the dialog.
+    ///
+    /// If not empty, it will be visible on the bottom border, using the
+    /// same alignment as the title.
+    pub fn subtitle<S: Into<StyledString>>(self, label: S) -> Self {
+        self.with(|s| s.set_subtitle(label))
+    }
+
+    /// Sets the subtitle of the dialog.
+    pub fn set_subtitle<S: Into<StyledString>>(&mut self, label: S) {
+        self.subtitle = label.into();
         self.invalidate();
     }
 
@@ -63,8 +113,20 @@ impl<V: View> Panel<V> {
     }
 
     fn draw_title(&self, printer: &Printer<'_, '_>) {
-        if !self.title.is_empty() {
-            let len = self.title.width();
+        self.draw_label(printer, 0, &self.title.get_content());
+
+        let y = printer.size.y.saturating_sub(1);
+        if y > 0 {
+            self.draw_label(printer, y, &self.subtitle);
+        }
+    }
+
+    // Draws a label on the border at the given row.
+    fn draw_label(
+        &self, printer: &Printer<'_, '_>, y: usize, label: &StyledString,
+    ) {
+        if !label.is_empty() {
+            let len = label.width();
             let spacing = 3; //minimum distance to borders
             let spacing_both_ends = 2 * spacing;
             if len + spacing_both_ends > printer.size.x {
@@ -75,12 +137,12 @@ impl<V: View> Panel<V> {
                     .title_position
                     .get_offset(len, printer.size.x - spacing_both_ends);
             printer.with_high_border(false, |printer| {
-                printer.print((x - 2, 0), "┤ ");
-                printer.print((x + len, 0), " ├");
+                printer.print((x - 2, y), "┤ ");
+                printer.print((x + len, y), " ├");
             });
 
             printer.with_color(ColorStyle::title_primary(), |p| {
-                p.print((x, 0), &self.title)
+                p.print_styled((x, y), SpannedStr::from(label))
             });
         }
     }
@@ -113,6 +175,7 @@ impl<V: View> ViewWrapper for Panel<V> {
     }
 
     fn wrap_layout(&mut self, size: Vec2) {
+        self.invalidated = false;
         self.view.layout(size.saturating_sub((2, 2)));
     }
 
@@ -121,7 +184,21 @@ impl<V: View> ViewWrapper for Panel<V> {
         self.view.important_area(inner_size) + (1, 1)
     }
 
+    fn wrap_locate(
+        &self, selector: &Selector<'_>, size: Vec2,
+    ) -> Option<Rect> {
+        let inner_size = size.saturating_sub((2, 2));
+        self.view
+            .locate(selector, inner_size)
+            .map(|area| area + (1, 1))
+    }
+
     fn wrap_needs_relayout(&self) -> bool {
         self.invalidated || self.view.needs_relayout()
     }
+
+    fn wrap_mark_dirty(&mut self) {
+        self.invalidate();
+        self.view.mark_dirty();
+    }
 }
@@ -1,10 +1,14 @@
 use crate::align::HAlign;
 use crate::theme::{ColorStyle, ColorType, Effect};
-use crate::utils::Counter;
+use crate::utils::time::Instant;
+use crate::utils::{Counter, Counter64, FloatCounter};
 use crate::view::View;
 use crate::{Printer, With};
+use std::cell::{Cell, RefCell};
 use std::cmp;
+use std::rc::Rc;
 use std::thread;
+use std::time::Duration;
 
 // pub type CbPromise = Option<Box<Fn(&mut Cursive) + Send>>;
 
@@ -20,6 +24,14 @@ use std::thread;
 /// The bar defaults to the current theme's highlight color,
 /// but that can be customized.
 ///
+/// When the total amount of work is unknown, the bar can be made
+/// indeterminate: it then shows a block bouncing from side to side, until
+/// it is switched back to showing the progress.
+///
+/// The bar keeps track of the time since the progress started, and can
+/// estimate the progress rate and the remaining time. See
+/// `with_timed_label`.
+///
 /// # Example
 ///
 /// ```
@@ -35,20 +47,61 @@ use std::thread;
 ///                       });
 /// ```
 pub struct ProgressBar {
-    min: usize,
-    max: usize,
-    value: Counter,
+    min: u64,
+    max: u64,
+    value: Value,
     color: ColorType,
+    // When set, the progress is unknown and we show a bouncing block,
+    // starting from this instant.
+    indeterminate: Option<Instant>,
+    // When the value was first seen past `min`, and the value then: the rate
+    // is measured from there, even if the bar was created long before its
+    // task started. Shared with the label of `with_eta_label`.
+    started: Rc<Cell<Option<(Instant, u64)>>>,
+    // Value and label of the last frame: the bar asks to be drawn again
+    // once they change, as the counter is updated without any event.
+    drawn: RefCell<Option<(u64, String)>>,
     // TODO: use a Promise instead?
-    label_maker: Box<dyn Fn(usize, (usize, usize)) -> String>,
+    label_maker: Box<LabelMaker>,
+}
+
+type LabelMaker = dyn Fn(u64, (u64, u64), Duration) -> String;
+
+// Where the progress value comes from.
+enum Value {
+    Usize(Counter),
+    U64(Counter64),
+    F64(FloatCounter),
 }
 
-fn make_percentage(value: usize, (min, max): (usize, usize)) -> String {
+impl Value {
+    fn get(&self) -> u64 {
+        match *self {
+            Value::Usize(ref counter) => counter.get() as u64,
+            Value::U64(ref counter) => counter.get(),
+            Value::F64(ref counter) => counter.get().max(0.0).round() as u64,
+        }
+    }
+
+    // A `usize` counter saturates on values it cannot hold.
+    fn set(&self, value: u64) {
+        match *self {
+            Value::Usize(ref counter) => {
+                counter.set(cmp::min(value, usize::MAX as u64) as usize)
+            }
+            Value::U64(ref counter) => counter.set(value),
+            Value::F64(ref counter) => counter.set(value as f64),
+        }
+    }
+}
+
+fn make_percentage(value: u64, (min, max): (u64, u64)) -> String {
     if value < min {
         return String::from("0 %");
     }
 
     let (percentage, extra) = ratio(value - min, max - min, 100);
+    let percentage = percentage as u64;
     let percentage = if extra > 4 {
         percentage + 1
     } else {
@@ -65,13 +118,17 @@ fn make_percentage(value: usize, (min, max): (usize, usize)) -> String {
 /// Returns a tuple with:
 /// * The integer part of the division
 /// * A value between 0 and 8 (exclusive) corresponding to the remainder.
-fn ratio(value: usize, max: usize, length: usize) -> (usize, usize) {
+///
+/// Computed on 128 bits, so large 64-bit values don't overflow.
+fn ratio(value: u64, max: u64, length: usize) -> (usize, usize) {
+    let (value, max, length) =
+        (u128::from(value), u128::from(max), length as u128);
     let integer = length * value / max;
     let fraction = length * value - max * integer;
 
     let fraction = fraction * 8 / max;
 
-    (integer, fraction)
+    (integer as usize, fraction as usize)
 }
 
 new_default!(ProgressBar);
@@ -88,9 +145,14 @@ impl ProgressBar {
         ProgressBar {
             min: 0,
             max: 100,
-            value: Counter::new(0),
+            value: Value::Usize(Counter::new(0)),
             color: ColorStyle::highlight().back,
-            label_maker: Box::new(make_percentage),
+            indeterminate: None,
+            started: Rc::default(),
+            drawn: RefCell::new(None),
+            label_maker: Box::new(|value, range, _| {
+                make_percentage(value, range)
+            }),
         }
     }
 
@@ -99,7 +161,36 @@ impl ProgressBar {
     /// Use this to manually control the progress to display
     /// by directly modifying the value pointed to by `value`.
     pub fn with_value(mut self, value: Counter) -> Self {
-        self.value = value;
+        self.value = Value::Usize(value);
+        self
+    }
+
+    /// Sets a 64-bit value to follow.
+    ///
+    /// Use this when the progress may not fit in a `usize`, like a byte
+    /// count on 32-bit platforms.
+    pub fn with_value_u64(mut self, value: Counter64) -> Self {
+        self.value = Value::U64(value);
+        self
+    }
+
+    /// Sets a floating-point value to follow.
+    ///
+    /// The value is rounded to the nearest integer for display.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cursive::utils::FloatCounter;
+    /// # use cursive::views::ProgressBar;
+    /// let counter = FloatCounter::new(0.0);
+    /// let bar = ProgressBar::new().with_value_f64(counter.clone());
+    ///
+    /// // Each item is a quarter of a percent.
+    /// counter.tick(0.25);
+    /// ```
+    pub fn with_value_f64(mut self, value: FloatCounter) -> Self {
+        self.value = Value::F64(value);
         self
     }
 
@@ -109,8 +200,17 @@ impl ProgressBar {
     ///
     /// This does not reset the value, so it can be called several times
     /// to advance the progress in multiple sessions.
+    ///
+    /// If the bar followed a 64-bit or floating-point value, it now follows
+    /// a new `Counter` starting from the current value.
     pub fn start<F: FnOnce(Counter) + Send + 'static>(&mut self, f: F) {
-        let counter: Counter = self.value.clone();
+        let counter = match self.value {
+            Value::Usize(ref counter) => counter.clone(),
+            ref value => {
+                Counter::new(cmp::min(value.get(), usize::MAX as u64) as usize)
+            }
+        };
+        self.value = Value::Usize(counter.clone());
 
         thread::spawn(move || {
             f(counter);
@@ -135,24 +235,135 @@ impl ProgressBar {
     /// The default one shows a percentage progress:
     ///
     /// ```
-    /// fn make_progress(value: usize, (min, max): (usize, usize)) -> String {
+    /// fn make_progress(value: u64, (min, max): (u64, u64)) -> String {
     ///     let percent = 101 * (value - min) / (1 + max - min);
     ///     format!("{} %", percent)
     /// }
     /// ```
-    pub fn with_label<F: Fn(usize, (usize, usize)) -> String + 'static>(
-        mut self, label_maker: F,
+    pub fn with_label<F: Fn(u64, (u64, u64)) -> String + 'static>(
+        self, label_maker: F,
     ) -> Self {
+        self.with_timed_label(move |value, range, _| label_maker(value, range))
+    }
+
+    /// Sets the label generator, with timing information.
+    ///
+    /// The given function will be called with `(value, (min, max), elapsed)`,
+    /// where `elapsed` is the time since the progress started: since the
+    /// value was first seen past `min`. Use `ProgressBar::estimate` to
+    /// compute the remaining time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cursive::views::ProgressBar;
+    /// let bar = ProgressBar::new().max(1_000_000).with_timed_label(
+    ///     |value, (min, max), elapsed| {
+    ///         let secs = elapsed.as_secs_f64();
+    ///         let rate = if secs > 0.0 { value as f64 / secs } else { 0.0 };
+    ///         let left = ProgressBar::estimate(value, (min, max), elapsed)
+    ///             .map(ProgressBar::format_duration)
+    ///             .unwrap_or_else(|| "--:--".to_string());
+    ///         format!("{:.1} kB/s - {} left", rate / 1000.0, left)
+    ///     },
+    /// );
+    /// ```
+    pub fn with_timed_label<F>(mut self, label_maker: F) -> Self
+    where
+        F: Fn(u64, (u64, u64), Duration) -> String + 'static,
+    {
         self.label_maker = Box::new(label_maker);
         self
     }
 
+    /// Shows the percentage and the estimated remaining time.
+    ///
+    /// The label looks like `42 % - 00:12 left`.
+    pub fn with_eta_label(self) -> Self {
+        let started = Rc::clone(&self.started);
+        self.with_timed_label(move |value, range, elapsed| {
+            let percentage = make_percentage(value, range);
+            // The progress made before the start is not part of the rate.
+            let from = started.get().map_or(range.0, |(_, from)| from);
+            match ProgressBar::estimate(value, (from, range.1), elapsed) {
+                Some(left) => format!(
+                    "{} - {} left",
+                    percentage,
+                    ProgressBar::format_duration(left)
+                ),
+                None => percentage,
+            }
+        })
+    }
+
+    /// Returns the time since the progress started.
+    ///
+    /// The progress starts when the value is first seen past `min`: this is
+    /// zero until then.
+    pub fn elapsed(&self) -> Duration {
+        self.progress_start(self.value.get())
+            .map_or(Duration::from_secs(0), |(start, _)| start.elapsed())
+    }
+
+    /// Returns the average progress per second since the progress started.
+    pub fn rate(&self) -> f64 {
+        let value = self.value.get();
+        let from = self
+            .progress_start(value)
+            .map_or(self.min, |(_, from)| from);
+        let done = value.saturating_sub(from) as f64;
+        let secs = self.elapsed().as_secs_f64();
+        if secs > 0.0 {
+            done / secs
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns the estimated time until the bar reaches its maximum.
+    ///
+    /// Returns `None` if there is no progress yet to base the estimation on.
+    pub fn eta(&self) -> Option<Duration> {
+        let value = self.value.get();
+        let (start, from) = self.progress_start(value)?;
+        ProgressBar::estimate(value, (from, self.max), start.elapsed())
+    }
+
+    /// Estimates the time left until `value` reaches `max`.
+    ///
+    /// This assumes the progress from `min` to `value` took `elapsed`, and
+    /// will continue at the same rate.
+    ///
+    /// Returns `None` if `value <= min`.
+    pub fn estimate(
+        value: u64, (min, max): (u64, u64), elapsed: Duration,
+    ) -> Option<Duration> {
+        if value <= min {
+            return None;
+        }
+
+        let done = (value - min) as f64;
+        let left = max.saturating_sub(value) as f64;
+        Some(Duration::from_secs_f64(elapsed.as_secs_f64() * left / done))
+    }
+
+    /// Formats a duration as `mm:ss`, or `h:mm:ss` above one hour.
+    pub fn format_duration(duration: Duration) -> String {
+        let secs = duration.as_secs();
+        let (hours, minutes, secs) = (secs / 3600, secs / 60 % 60, secs % 60);
+        if hours > 0 {
+            format!("{}:{:02}:{:02}", hours, minutes, secs)
+        } else {
+            format!("{:02}:{:02}", minutes, secs)
+        }
+    }
+
     /// Sets the minimum value.
     ///
     /// When `value` equals `min`, the bar is at the minimum level.
     ///
     /// If `self.min > max`, `self.min` is set to `max`.
-    pub fn min(mut self, min: usize) -> Self {
+    pub fn min(mut self, min: u64) -> Self {
         self.min = min;
         self.max = cmp::max(self.max, self.min);
 
@@ -164,7 +375,7 @@ impl ProgressBar {
     /// When `value` equals `max`, the bar is at the maximum level.
     ///
     /// If `min > self.max`, `self.max` is set to `min`.
-    pub fn max(mut self, max: usize) -> Self {
+    pub fn max(mut self, max: u64) -> Self {
         self.max = max;
         self.min = cmp::min(self.min, self.max);
 
@@ -174,7 +385,7 @@ impl ProgressBar {
     /// Sets the `min` and `max` range for the value.
     ///
     /// If `min > max`, swap the two values.
-    pub fn range(self, min: usize, max: usize) -> Self {
+    pub fn range(self, min: u64, max: u64) -> Self {
         if min > max {
             self.min(max).max(min)
         } else {
@@ -182,10 +393,48 @@ impl ProgressBar {
         }
     }
 
+    /// Sets the `min` and `max` range for the value.
+    ///
+    /// If `min > max`, swap the two values.
+    ///
+    /// This can be used when the total becomes known, together with
+    /// `set_indeterminate(false)`.
+    pub fn set_range(&mut self, min: u64, max: u64) {
+        self.min = cmp::min(min, max);
+        self.max = cmp::max(min, max);
+    }
+
+    /// Makes the bar indeterminate, or shows the progress again.
+    ///
+    /// An indeterminate bar shows a block bouncing from side to side instead
+    /// of the progress, for tasks where the total is not known yet.
+    ///
+    /// The animation only runs if the screen is refreshed regularly, for
+    /// instance with `Cursive::set_autorefresh`.
+    pub fn set_indeterminate(&mut self, indeterminate: bool) {
+        self.indeterminate = if indeterminate {
+            Some(Instant::now())
+        } else {
+            None
+        };
+    }
+
+    /// Makes the bar indeterminate.
+    ///
+    /// Chainable variant.
+    pub fn indeterminate(self) -> Self {
+        self.with(|s| s.set_indeterminate(true))
+    }
+
+    /// Returns `true` if the bar is indeterminate.
+    pub fn is_indeterminate(&self) -> bool {
+        self.indeterminate.is_some()
+    }
+
     /// Sets the current value.
     ///
     /// Value is clamped between `min` and `max`.
-    pub fn set_value(&mut self, value: usize) {
+    pub fn set_value(&mut self, value: u64) {
         self.value.set(value);
     }
 
@@ -208,6 +457,48 @@ impl ProgressBar {
     {
         self.with(|s| s.set_color(color))
     }
+
+    fn make_label(&self, value: u64) -> String {
+        let elapsed = self
+            .progress_start(value)
+            .map_or(Duration::from_secs(0), |(start, _)| start.elapsed());
+        (self.label_maker)(value, (self.min, self.max), elapsed)
+    }
+
+    // Returns when the progress started, and the value then.
+    //
+    // The first value seen past `min` starts it.
+    fn progress_start(&self, value: u64) -> Option<(Instant, u64)> {
+        if self.started.get().is_none() && value > self.min {
+            self.started.set(Some((Instant::now(), value)));
+        }
+        self.started.get()
+    }
+
+    fn draw_indeterminate(&self, printer: &Printer<'_, '_>, start: Instant) {
+        let available = printer.size.x;
+        let width = cmp::max(available / 5, 1);
+
+        // The block goes back and forth over this distance.
+        let span = available.saturating_sub(width);
+        let position = if span == 0 {
+            0
+        } else {
+            let step = start.elapsed().as_millis() / BOUNCE_STEP_MS;
+            let step = (step % (2 * span as u128)) as usize;
+            if step < span {
+                step
+            } else {
+                2 * span - step
+            }
+        };
+
+        let color_style =
+            ColorStyle::new(ColorStyle::highlight().front, self.color);
+        printer.with_color(color_style, |printer| {
+            printer.print_hline((position, 0), width, " ");
+        });
+    }
 }
 
 fn sub_block(extra: usize) -> &'static str {
@@ -224,8 +515,16 @@ fn sub_block(extra: usize) -> &'static str {
     }
 }
 
+// Number of milliseconds for the indeterminate block to move by one cell.
+const BOUNCE_STEP_MS: u128 = 50;
+
 impl View for ProgressBar {
     fn draw(&self, printer: &Printer<'_, '_>) {
+        if let Some(start) = self.indeterminate {
+            self.draw_indeterminate(printer, start);
+            return;
+        }
+
         // Now, the bar itself...
         let available = printer.size.x;
 
@@ -240,7 +539,7 @@ impl View for ProgressBar {
             ratio(value - self.min, self.max - self.min, available)
         };
 
-        let label = (self.label_maker)(value, (self.min, self.max));
+        let label = self.make_label(value);
         let offset = HAlign::Center.get_offset(label.len(), printer.size.x);
 
         let color_style =
@@ -258,5 +557,63 @@ impl View for ProgressBar {
             // Draw the left part in color_style (it may be cropped)
             printer.print((offset, 0), &label);
         });
+
+        *self.drawn.borrow_mut() = Some((value, label));
+    }
+
+    fn needs_relayout(&self) -> bool {
+        if self.indeterminate.is_some() {
+            return true;
+        }
+
+        let value = self.value.get();
+        match *self.drawn.borrow() {
+            Some((drawn, ref label)) => {
+                drawn != value || *label != self.make_label(value)
+            }
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn large_values() {
+        let max = 1 << 62;
+        assert_eq!(ratio(max / 4, max, 100), (25, 0));
+        assert_eq!(make_percentage(max, (0, max)), "100 %");
+
+        let counter = Counter64::new(5_000_000_000);
+        let bar = ProgressBar::new()
+            .max(10_000_000_000)
+            .with_value_u64(counter);
+        assert_eq!(bar.make_label(bar.value.get()), "50 %");
+    }
+
+    #[test]
+    fn estimate() {
+        let elapsed = Duration::from_secs(10);
+        assert_eq!(ProgressBar::estimate(0, (0, 100), elapsed), None);
+        assert_eq!(
+            ProgressBar::estimate(25, (0, 100), elapsed),
+            Some(Duration::from_secs(30))
+        );
+
+        // A bar created before its task only counts from the first update.
+        let counter = Counter::new(0);
+        let bar = ProgressBar::new().with_value(counter.clone());
+        assert_eq!(bar.eta(), None);
+        assert_eq!(bar.elapsed(), Duration::from_secs(0));
+        counter.set(25);
+        assert_eq!(bar.eta(), None);
+        counter.set(50);
+        assert!(bar.eta().is_some());
+
+        let format = ProgressBar::format_duration;
+        assert_eq!(format(Duration::from_secs(72)), "01:12");
+        assert_eq!(format(Duration::from_secs(3725)), "1:02:05");
     }
 }
@@ -1,5 +1,7 @@
 use crate::direction::Direction;
-use crate::event::{Event, EventResult, Key, MouseButton, MouseEvent};
+use crate::event::{
+    Callback, Event, EventResult, Key, MouseButton, MouseEvent,
+};
 use crate::theme::ColorStyle;
 use crate::vec::Vec2;
 use crate::view::View;
@@ -15,10 +17,19 @@ struct SharedState<T> {
     on_change: Option<Rc<dyn Fn(&mut Cursive, &T)>>,
 }
 
-impl<T> SharedState<T> {
+impl<T: 'static> SharedState<T> {
     pub fn selection(&self) -> Rc<T> {
         Rc::clone(&self.values[self.selection])
     }
+
+    // Selects the given button, and returns the `on_change` callback.
+    fn select(&mut self, id: usize) -> Option<Callback> {
+        self.selection = id;
+        self.on_change.clone().map(|on_change| {
+            let value = self.selection();
+            Callback::from_fn(move |s| on_change(s, &value))
+        })
+    }
 }
 
 /// Group to coordinate multiple radio buttons.
@@ -75,6 +86,60 @@ impl<T: 'static> RadioGroup<T> {
         self.state.borrow().selection()
     }
 
+    /// Selects the button with the given id.
+    ///
+    /// Buttons are indexed in the order they are created, starting from 0.
+    /// All buttons from this group will reflect the new selection.
+    ///
+    /// Returns a callback in response to the selection change.
+    ///
+    /// You should run this callback with a `&mut Cursive`.
+    ///
+    /// # Panics
+    ///
+    /// If `id` is not the id of a button from this group.
+    pub fn set_selection(&mut self, id: usize) -> Callback {
+        let mut state = self.state.borrow_mut();
+        assert!(
+            id < state.values.len(),
+            "Radio button id out of bounds: {} (group has {} buttons)",
+            id,
+            state.values.len()
+        );
+
+        state.select(id).unwrap_or_else(Callback::dummy)
+    }
+
+    /// Selects the first button with the given value.
+    ///
+    /// Returns `None` if no button has this value. Otherwise, returns a
+    /// callback in response to the selection change.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::views::RadioGroup;
+    /// let mut group = RadioGroup::new();
+    /// let _small = group.button_str("Small");
+    /// let _large = group.button_str("Large");
+    ///
+    /// assert!(group.select_value(&String::from("Large")).is_some());
+    /// assert_eq!(group.selected_id(), 1);
+    /// ```
+    pub fn select_value(&mut self, value: &T) -> Option<Callback>
+    where
+        T: PartialEq,
+    {
+        let id = self
+            .state
+            .borrow()
+            .values
+            .iter()
+            .position(|v| **v == *value)?;
+
+        Some(self.set_selection(id))
+    }
+
     /// Sets a callback to be used when the selection changes.
     pub fn set_on_change<F: 'static + Fn(&mut Cursive, &T)>(
         &mut self, on_change: F,
@@ -141,15 +206,7 @@ impl<T: 'static> RadioButton<T> {
 
     /// Selects this button, un-selecting any other in the same group.
     pub fn select(&mut self) -> EventResult {
-        let mut state = self.state.borrow_mut();
-        state.selection = self.id;
-        if let Some(ref on_change) = state.on_change {
-            let on_change = Rc::clone(on_change);
-            let value = state.selection();
-            EventResult::with_cb(move |s| on_change(s, &value))
-        } else {
-            EventResult::Consumed(None)
-        }
+        EventResult::Consumed(self.state.borrow_mut().select(self.id))
     }
 
     /// Selects this button, un-selecting any other in the same group.
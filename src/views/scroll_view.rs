@@ -1,7 +1,12 @@
-use crate::direction::Direction;
+use crate::direction::{Direction, Orientation};
 use crate::event::{AnyCb, Event, EventResult};
-use crate::view::{scroll, ScrollStrategy, Selector, View};
+use crate::state::ViewState;
+use crate::view::{
+    scroll, ScrollKeys, ScrollStrategy, ScrollbarPolicy, ScrollbarPosition,
+    ScrollbarStyle, Selector, View,
+};
 use crate::{Printer, Rect, Vec2, With};
+use std::time::Duration;
 
 /// Wraps a view in a scrollable area.
 pub struct ScrollView<V> {
@@ -58,6 +63,45 @@ where
         self.with(|s| s.set_scroll_strategy(strategy))
     }
 
+    /// Keeps the viewport at the bottom when the content grows.
+    ///
+    /// This is useful for logs and other streaming output. Unlike
+    /// `ScrollStrategy::StickToBottom`, this is not reset when the user
+    /// scrolls: following stops while the user scrolls up, and resumes when
+    /// they scroll back to the bottom.
+    pub fn set_follow_bottom(&mut self, follow: bool) {
+        self.core.set_follow_bottom(follow);
+    }
+
+    /// Keeps the viewport at the bottom when the content grows.
+    ///
+    /// Chainable variant.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::views::{ScrollView, TextContent, TextView};
+    /// let mut log = TextContent::new("");
+    /// let view = ScrollView::new(TextView::new_with_content(log.clone()))
+    ///     .follow_bottom(true);
+    ///
+    /// // Possibly from another thread
+    /// log.append_lines(vec!["Starting...", "Done."]);
+    /// ```
+    pub fn follow_bottom(self, follow: bool) -> Self {
+        self.with(|s| s.set_follow_bottom(follow))
+    }
+
+    /// Returns `true` if the viewport follows the bottom of the content.
+    pub fn get_follow_bottom(&self) -> bool {
+        self.core.get_follow_bottom()
+    }
+
+    /// Returns `true` if the viewport shows the bottom of the content.
+    pub fn is_at_bottom(&self) -> bool {
+        self.core.is_at_bottom()
+    }
+
     /// Control whether scroll bars are visibile.
     ///
     /// Defaults to `true`.
@@ -72,6 +116,134 @@ where
         self.with(|s| s.set_show_scrollbars(show_scrollbars))
     }
 
+    /// Sets when to show the scrollbar for the given orientation.
+    ///
+    /// `Orientation::Horizontal` refers to the horizontal scrollbar, used to
+    /// scroll along the x axis.
+    ///
+    /// Defaults to `ScrollbarPolicy::Auto`.
+    pub fn set_scrollbar_policy(
+        &mut self, orientation: Orientation, policy: ScrollbarPolicy,
+    ) {
+        self.core.set_scrollbar_policy(orientation, policy);
+    }
+
+    /// Sets when to show the scrollbar for the given orientation.
+    ///
+    /// Chainable variant.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::direction::Orientation;
+    /// # use cursive::view::ScrollbarPolicy;
+    /// # use cursive::views::{ScrollView, TextView};
+    /// let view = ScrollView::new(TextView::new("Lorem ipsum"))
+    ///     .scroll_x(true)
+    ///     .scrollbar_policy(Orientation::Vertical, ScrollbarPolicy::Always)
+    ///     .scrollbar_policy(Orientation::Horizontal, ScrollbarPolicy::Never);
+    /// ```
+    pub fn scrollbar_policy(
+        self, orientation: Orientation, policy: ScrollbarPolicy,
+    ) -> Self {
+        self.with(|s| s.set_scrollbar_policy(orientation, policy))
+    }
+
+    /// Sets on which side to draw the scrollbar for the given orientation.
+    ///
+    /// Defaults to `ScrollbarPosition::End` (right or bottom).
+    pub fn set_scrollbar_position(
+        &mut self, orientation: Orientation, position: ScrollbarPosition,
+    ) {
+        self.core.set_scrollbar_position(orientation, position);
+    }
+
+    /// Sets on which side to draw the scrollbar for the given orientation.
+    ///
+    /// Chainable variant.
+    pub fn scrollbar_position(
+        self, orientation: Orientation, position: ScrollbarPosition,
+    ) -> Self {
+        self.with(|s| s.set_scrollbar_position(orientation, position))
+    }
+
+    /// Sets the glyphs, colors and width of the scrollbars.
+    ///
+    /// Defaults to `ScrollbarStyle::line()`.
+    pub fn set_scrollbar_style(&mut self, style: ScrollbarStyle) {
+        self.core.set_scrollbar_style(style);
+    }
+
+    /// Sets the glyphs, colors and width of the scrollbars.
+    ///
+    /// Chainable variant.
+    pub fn scrollbar_style(self, style: ScrollbarStyle) -> Self {
+        self.with(|s| s.set_scrollbar_style(style))
+    }
+
+    /// Returns the glyphs, colors and width of the scrollbars.
+    pub fn get_scrollbar_style(&self) -> &ScrollbarStyle {
+        self.core.get_scrollbar_style()
+    }
+
+    /// Sets the keys used to scroll this view.
+    ///
+    /// Defaults to the bindings set with `ScrollKeys::set_default`, which
+    /// are the arrow keys unless changed.
+    pub fn set_scroll_keys(&mut self, keys: ScrollKeys) {
+        self.core.set_scroll_keys(keys);
+    }
+
+    /// Sets the keys used to scroll this view.
+    ///
+    /// Chainable variant.
+    pub fn scroll_keys(self, keys: ScrollKeys) -> Self {
+        self.with(|s| s.set_scroll_keys(keys))
+    }
+
+    /// Returns the keys used to scroll this view.
+    pub fn get_scroll_keys(&self) -> &ScrollKeys {
+        self.core.get_scroll_keys()
+    }
+
+    /// Hides the scrollbars after the given time without scrolling.
+    ///
+    /// Their space is still reserved, so the content doesn't move when they
+    /// appear or disappear. Since nothing else triggers a redraw, you may
+    /// want to use `Cursive::set_autorefresh` alongside this.
+    ///
+    /// Give `None` to always show them (this is the default).
+    pub fn set_scrollbar_auto_hide(&mut self, timeout: Option<Duration>) {
+        self.core.set_scrollbar_auto_hide(timeout);
+    }
+
+    /// Hides the scrollbars after the given time without scrolling.
+    ///
+    /// Chainable variant.
+    pub fn scrollbar_auto_hide(self, timeout: Duration) -> Self {
+        self.with(|s| s.set_scrollbar_auto_hide(Some(timeout)))
+    }
+
+    /// Controls whether large jumps are animated.
+    ///
+    /// When enabled, paging, `Home`/`End` and programmatic scrolling move the
+    /// viewport over a few frames instead of all at once.
+    ///
+    /// The animation progresses with each refresh, so you will want to set a
+    /// non-zero refresh rate with `Cursive::set_fps`.
+    ///
+    /// Defaults to `false`.
+    pub fn set_smooth_scrolling(&mut self, smooth_scrolling: bool) {
+        self.core.set_smooth_scrolling(smooth_scrolling);
+    }
+
+    /// Controls whether large jumps are animated.
+    ///
+    /// Chainable variant.
+    pub fn smooth_scrolling(self, smooth_scrolling: bool) -> Self {
+        self.with(|s| s.set_smooth_scrolling(smooth_scrolling))
+    }
+
     /// Sets the scroll offset to the given value
     pub fn set_offset<S>(&mut self, offset: S)
     where
@@ -112,6 +284,53 @@ where
         self.with(|s| s.set_scroll_x(enabled))
     }
 
+    /// Scroll as little as possible to show the given area of the content.
+    ///
+    /// If the area is larger than the viewport, its top-left corner is
+    /// shown.
+    pub fn scroll_to_rect(&mut self, rect: Rect) {
+        self.core.scroll_to_rect(rect);
+    }
+
+    /// Scroll as little as possible to show the view matching `selector`.
+    ///
+    /// The position of the view comes from the last layout.
+    ///
+    /// Returns `false` if no such view was found.
+    pub fn scroll_to_view(&mut self, selector: &Selector<'_>) -> bool {
+        match self.inner.locate(selector, self.core.inner_size()) {
+            Some(area) => {
+                self.scroll_to_rect(area);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Scroll as little as possible to show the view with the given id.
+    ///
+    /// Returns `false` if no such view was found.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::views::{LinearLayout, ScrollView, TextView};
+    /// # use cursive::traits::*;
+    /// let mut view = ScrollView::new(
+    ///     LinearLayout::vertical()
+    ///         .child(TextView::new("Lots of text\n".repeat(50)))
+    ///         .child(TextView::new("Result").with_id("result")),
+    /// );
+    ///
+    /// // Once laid out, jump to the result.
+    /// view.layout((20, 10).into());
+    /// assert!(view.scroll_to_id("result"));
+    /// assert_eq!(view.content_viewport().bottom(), 50);
+    /// ```
+    pub fn scroll_to_id(&mut self, id: &str) -> bool {
+        self.scroll_to_view(&Selector::Id(id))
+    }
+
     /// Programmatically scroll to the top of the view.
     pub fn scroll_to_top(&mut self) {
         self.core.scroll_to_top();
@@ -171,6 +390,11 @@ where
         self.core.needs_relayout() || self.inner.needs_relayout()
     }
 
+    fn mark_dirty(&mut self) {
+        self.core.invalidate_cache();
+        self.inner.mark_dirty();
+    }
+
     fn required_size(&mut self, constraint: Vec2) -> Vec2 {
         scroll::required_size(
             self,
@@ -180,7 +404,10 @@ where
         )
     }
 
-    fn call_on_any<'a>(&mut self, selector: &Selector<'_>, cb: AnyCb<'a>) {
+    fn call_on_any<'a>(
+        &mut self, selector: &Selector<'_>, mut cb: AnyCb<'a>,
+    ) {
+        selector.call_on_self(self, &mut cb);
         self.inner.call_on_any(selector, cb)
     }
 
@@ -192,7 +419,62 @@ where
         self.inner.take_focus(source) || self.core.is_scrolling().any()
     }
 
+    fn locate(&self, selector: &Selector<'_>, _: Vec2) -> Option<Rect> {
+        // Views scrolled out of the viewport are not on screen.
+        let viewport = self.core.content_viewport();
+        let area = self.inner.locate(selector, self.core.inner_size())?;
+        if !viewport.contains(area.top_left()) {
+            return None;
+        }
+
+        // Convert the position in the content to the visible viewport.
+        let top_left =
+            area.top_left() - viewport.top_left() + self.core.content_origin();
+        Some(Rect::from_size(top_left, area.size()))
+    }
+
     fn important_area(&self, size: Vec2) -> Rect {
         scroll::important_area(self, size, |s, si| s.inner.important_area(si))
     }
+
+    fn save_state(&self) -> Option<ViewState> {
+        Some(ViewState::Offset(self.content_viewport().top_left()))
+    }
+
+    fn restore_state(&mut self, state: &ViewState) {
+        if let ViewState::Offset(offset) = *state {
+            self.set_offset(offset);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Identifiable;
+    use crate::views::{ListView, TextView};
+
+    #[test]
+    fn locate() {
+        let mut list = ListView::new()
+            .indented_child("a", 2, TextView::new("x").with_id("first"))
+            .child("long", TextView::new("y"));
+        for _ in 0..10 {
+            list.add_child("row", TextView::new("z"));
+        }
+        list.add_child("end", TextView::new("w").with_id("last"));
+
+        // Children start after the widest label, with its indent, and the
+        // space after it.
+        let mut view = ScrollView::new(list);
+        view.layout((20, 3).into());
+        let area = view.locate(&Selector::Id("first"), (20, 3).into());
+        assert_eq!(area, Some(Rect::from_size((5, 0), (1, 1))));
+
+        // Views outside of the viewport cannot be located.
+        assert!(view.scroll_to_id("last"));
+        assert_eq!(view.locate(&Selector::Id("first"), (20, 3).into()), None);
+        let area = view.locate(&Selector::Id("last"), (20, 3).into());
+        assert_eq!(area, Some(Rect::from_size((5, 2), (1, 1))));
+    }
 }
@@ -1,12 +1,15 @@
 use crate::align::{Align, HAlign, VAlign};
 use crate::direction::Direction;
+use crate::div::div_up;
 use crate::event::{
     Callback, Event, EventResult, Key, MouseButton, MouseEvent,
 };
 use crate::menu::MenuTree;
 use crate::rect::Rect;
+use crate::state::ViewState;
 use crate::theme::ColorStyle;
 use crate::utils::markup::StyledString;
+use crate::utils::time::Instant;
 use crate::vec::Vec2;
 use crate::view::{Position, View};
 use crate::views::MenuPopup;
@@ -15,8 +18,12 @@ use crate::Printer;
 use crate::With;
 use std::borrow::Borrow;
 use std::cell::Cell;
-use std::cmp::{min, Ordering};
+use std::cmp::{max, min, Ordering};
+use std::ops::Range;
 use std::rc::Rc;
+use std::time::Duration;
+
+type OnReachEnd = dyn Fn(&mut Cursive);
 
 /// View to select an item among a list.
 ///
@@ -68,14 +75,46 @@ pub struct SelectView<T = String> {
     on_select: Option<Rc<dyn Fn(&mut Cursive, &T)>>,
 
     // If `true`, when a character is pressed, jump to the next item starting
-    // with this character.
+    // with the characters typed so far.
     autojump: bool,
 
+    // Characters typed so far for auto-jump, and when the last one was.
+    autojump_query: String,
+    autojump_last: Option<Instant>,
+
+    // After this delay without typing, the auto-jump query starts over.
+    autojump_timeout: Duration,
+
+    // If `true`, a single click only selects, and a double click submits.
+    submit_on_double_click: bool,
+
+    // Called when the selection gets close to the last item.
+    on_reach_end: Option<Rc<OnReachEnd>>,
+
+    // How many items before the last one trigger `on_reach_end`.
+    reach_end_distance: usize,
+
+    // `true` while more items are being loaded.
+    loading: bool,
+
+    // Shown below the items while loading.
+    loading_label: StyledString,
+
     align: Align,
 
     // `true` if we show a one-line view, with popup on selection.
     popup: bool,
 
+    // Number of columns to lay the items in.
+    // `None` means we fit as many columns as the width allows.
+    columns: Option<usize>,
+
+    // Number of columns actually used, as computed during layout.
+    effective_columns: usize,
+
+    // Number of items per page, if the list is paged.
+    page_size: Option<usize>,
+
     // We need the last offset to place the popup window
     // We "cache" it during the draw, so we need interior mutability.
     last_offset: Cell<Vec2>,
@@ -99,7 +138,18 @@ impl<T: 'static> SelectView<T> {
             on_submit: None,
             align: Align::top_left(),
             popup: false,
+            columns: Some(1),
+            effective_columns: 1,
+            page_size: None,
             autojump: false,
+            autojump_query: String::new(),
+            autojump_last: None,
+            autojump_timeout: Duration::from_secs(1),
+            submit_on_double_click: false,
+            on_reach_end: None,
+            reach_end_distance: 0,
+            loading: false,
+            loading_label: StyledString::plain("Loading..."),
             last_offset: Cell::new(Vec2::zero()),
             last_size: Vec2::zero(),
         }
@@ -109,6 +159,12 @@ impl<T: 'static> SelectView<T> {
     ///
     /// If enabled, when a key is pressed, the selection will jump to the next
     /// item beginning with the pressed letter.
+    ///
+    /// Letters typed in quick succession are combined, so typing `"ban"`
+    /// selects the first item starting with `"ban"`. If no item starts with
+    /// the typed text, the first item containing it is selected instead.
+    /// Typing the same letter repeatedly cycles through the items beginning
+    /// with it.
     pub fn set_autojump(&mut self, autojump: bool) {
         self.autojump = autojump;
     }
@@ -123,6 +179,35 @@ impl<T: 'static> SelectView<T> {
         self.with(|s| s.set_autojump(true))
     }
 
+    /// Sets the delay after which typed letters start a new auto-jump search.
+    ///
+    /// Defaults to 1 second.
+    pub fn set_autojump_timeout(&mut self, timeout: Duration) {
+        self.autojump_timeout = timeout;
+    }
+
+    /// Sets the delay after which typed letters start a new auto-jump search.
+    ///
+    /// Chainable variant.
+    pub fn autojump_timeout(self, timeout: Duration) -> Self {
+        self.with(|s| s.set_autojump_timeout(timeout))
+    }
+
+    /// Sets whether items are submitted by a double click.
+    ///
+    /// By default, clicking an item submits it. If enabled, a click only
+    /// selects the item, and a double click submits it.
+    pub fn set_submit_on_double_click(&mut self, double_click: bool) {
+        self.submit_on_double_click = double_click;
+    }
+
+    /// Submits items on a double click instead of a single click.
+    ///
+    /// Chainable variant.
+    pub fn submit_on_double_click(self) -> Self {
+        self.with(|s| s.set_submit_on_double_click(true))
+    }
+
     /// Turns `self` into a popup select view.
     ///
     /// Chainable variant.
@@ -135,6 +220,95 @@ impl<T: 'static> SelectView<T> {
         self.popup = popup;
     }
 
+    /// Lays the items in the given number of columns.
+    ///
+    /// Items are placed row by row: the first `columns` items fill the
+    /// first row, and so on. Left and right arrows then move the selection
+    /// across columns.
+    ///
+    /// This has no effect in popup mode.
+    pub fn set_columns(&mut self, columns: usize) {
+        self.columns = Some(max(columns, 1));
+    }
+
+    /// Lays the items in the given number of columns.
+    ///
+    /// Chainable variant.
+    pub fn columns(self, columns: usize) -> Self {
+        self.with(|s| s.set_columns(columns))
+    }
+
+    /// Lays the items in as many columns as the available width allows.
+    pub fn set_auto_columns(&mut self) {
+        self.columns = None;
+    }
+
+    /// Lays the items in as many columns as the available width allows.
+    ///
+    /// Chainable variant.
+    pub fn auto_columns(self) -> Self {
+        self.with(Self::set_auto_columns)
+    }
+
+    /// Shows the items one page at a time, with `size` items per page.
+    ///
+    /// The page of the selected item is shown, with a page indicator below
+    /// it. `PageUp` and `PageDown` then move to the previous and next page.
+    /// `None` shows all items, to be scrolled through.
+    ///
+    /// This has no effect in popup mode.
+    pub fn set_page_size(&mut self, size: Option<usize>) {
+        self.page_size = size.map(|size| max(size, 1));
+    }
+
+    /// Shows the items one page at a time, with `size` items per page.
+    ///
+    /// Chainable variant.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::views::SelectView;
+    /// let select = SelectView::new()
+    ///     .with_all_str((1..1000).map(|i| i.to_string()))
+    ///     .paged(20);
+    /// assert_eq!(select.page_count(), 50);
+    /// ```
+    pub fn paged(self, size: usize) -> Self {
+        self.with(|s| s.set_page_size(Some(size)))
+    }
+
+    /// Returns the number of items per page, if the list is paged.
+    pub fn get_page_size(&self) -> Option<usize> {
+        self.page_size
+    }
+
+    /// Returns the number of pages.
+    ///
+    /// This is `1` when the list is not paged.
+    pub fn page_count(&self) -> usize {
+        match self.page_size {
+            Some(size) => max(div_up(self.items.len(), size), 1),
+            None => 1,
+        }
+    }
+
+    /// Returns the page of the selected item, starting at `0`.
+    pub fn get_page(&self) -> usize {
+        self.page_size.map_or(0, |size| self.focus() / size)
+    }
+
+    /// Selects the first item of the given page.
+    ///
+    /// The page is capped to the last one.
+    ///
+    /// Returns a callback in response to the selection change.
+    pub fn set_page(&mut self, page: usize) -> Callback {
+        let page = min(page, self.page_count() - 1);
+        let size = self.page_size.unwrap_or(0);
+        self.set_selection(page * size)
+    }
+
     /// Disables this view.
     ///
     /// A disabled view cannot be selected.
@@ -214,6 +388,113 @@ impl<T: 'static> SelectView<T> {
         self.with(|s| s.set_on_submit(cb))
     }
 
+    /// Sets a callback to run when the selection reaches the end of the list.
+    ///
+    /// This can be used to load more items on demand. When the callback
+    /// runs, the view starts loading (see `set_loading`) and shows an
+    /// indicator below the last item. It won't run again until loading is
+    /// over, which happens when a new item is added, or with
+    /// `set_loading(false)` if there is nothing more to load.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::views::SelectView;
+    /// # use cursive::traits::*;
+    /// let select = SelectView::<usize>::new()
+    ///     .with_all((0..20).map(|i| (format!("Item {}", i), i)))
+    ///     .on_reach_end(|s| {
+    ///         s.call_on_id("list", |view: &mut SelectView<usize>| {
+    ///             let start = view.len();
+    ///             for i in start..start + 20 {
+    ///                 view.add_item(format!("Item {}", i), i);
+    ///             }
+    ///         });
+    ///     })
+    ///     .with_id("list");
+    /// ```
+    pub fn set_on_reach_end<F>(&mut self, cb: F)
+    where
+        F: Fn(&mut Cursive) + 'static,
+    {
+        self.on_reach_end = Some(Rc::new(cb));
+    }
+
+    /// Sets a callback to run when the selection reaches the end of the list.
+    ///
+    /// Chainable variant.
+    pub fn on_reach_end<F>(self, cb: F) -> Self
+    where
+        F: Fn(&mut Cursive) + 'static,
+    {
+        self.with(|s| s.set_on_reach_end(cb))
+    }
+
+    /// Removes the callback set with `set_on_reach_end`.
+    pub fn clear_on_reach_end(&mut self) {
+        self.on_reach_end = None;
+        self.loading = false;
+    }
+
+    /// Sets how close to the last item the selection must be to trigger
+    /// `on_reach_end`.
+    ///
+    /// Defaults to 0, meaning the last item must be selected.
+    pub fn set_reach_end_distance(&mut self, distance: usize) {
+        self.reach_end_distance = distance;
+    }
+
+    /// Sets how close to the last item the selection must be to trigger
+    /// `on_reach_end`.
+    ///
+    /// Chainable variant.
+    pub fn reach_end_distance(self, distance: usize) -> Self {
+        self.with(|s| s.set_reach_end_distance(distance))
+    }
+
+    /// Sets whether more items are being loaded.
+    ///
+    /// While loading, an indicator is shown below the last item.
+    pub fn set_loading(&mut self, loading: bool) {
+        self.loading = loading;
+    }
+
+    /// Returns `true` if more items are being loaded.
+    pub fn is_loading(&self) -> bool {
+        self.loading
+    }
+
+    /// Sets the label shown below the items while loading.
+    ///
+    /// Defaults to `"Loading..."`.
+    pub fn set_loading_label<S: Into<StyledString>>(&mut self, label: S) {
+        self.loading_label = label.into();
+    }
+
+    /// Sets the label shown below the items while loading.
+    ///
+    /// Chainable variant.
+    pub fn loading_label<S: Into<StyledString>>(self, label: S) -> Self {
+        self.with(|s| s.set_loading_label(label))
+    }
+
+    // Runs `on_reach_end` if the selection is close enough to the end.
+    fn check_reach_end(&mut self) -> EventResult {
+        let distance = self.len().saturating_sub(self.focus() + 1);
+        let far = distance > self.reach_end_distance;
+        if self.loading || self.is_empty() || far {
+            return EventResult::Ignored;
+        }
+
+        match self.on_reach_end.clone() {
+            None => EventResult::Ignored,
+            Some(cb) => {
+                self.loading = true;
+                EventResult::with_cb(move |s| cb(s))
+            }
+        }
+    }
+
     /// Sets the alignment for this view.
     pub fn align(mut self, align: Align) -> Self {
         self.align = align;
@@ -255,8 +536,11 @@ impl<T: 'static> SelectView<T> {
     }
 
     /// Adds a item to the list, with given label and value.
+    ///
+    /// This ends loading, if `on_reach_end` was triggered.
     pub fn add_item<S: Into<StyledString>>(&mut self, label: S, value: T) {
         self.items.push(Item::new(label.into(), value));
+        self.loading = false;
     }
 
     /// Gets an item at given idx or None.
@@ -315,11 +599,14 @@ impl<T: 'static> SelectView<T> {
 
     /// Inserts an item at position `index`, shifting all elements after it to
     /// the right.
+    ///
+    /// This ends loading, if `on_reach_end` was triggered.
     pub fn insert_item<S>(&mut self, index: usize, label: S, value: T)
     where
         S: Into<StyledString>,
     {
         self.items.insert(index, Item::new(label.into(), value));
+        self.loading = false;
     }
 
     /// Chainable variant of add_item
@@ -349,14 +636,208 @@ impl<T: 'static> SelectView<T> {
         self.with(|s| s.add_all(iter))
     }
 
+    /// Sets a glyph or short text shown before the label of item `i`.
+    ///
+    /// Icons are always on the left, whatever the alignment. Like secondary
+    /// texts, they are not shown by popups.
+    ///
+    /// # Panics
+    ///
+    /// If `i` is out of bounds.
+    pub fn set_item_icon<S: Into<StyledString>>(&mut self, i: usize, icon: S) {
+        self.items[i].icon = Some(icon.into());
+    }
+
+    /// Sets a glyph or short text shown before the label of the last item.
+    ///
+    /// Chainable variant.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::views::SelectView;
+    /// let select = SelectView::new()
+    ///     .item("Open", 1)
+    ///     .icon("> ")
+    ///     .secondary("Ctrl-O")
+    ///     .item("Quit", 2)
+    ///     .secondary("Ctrl-Q");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the view has no item.
+    pub fn icon<S: Into<StyledString>>(self, icon: S) -> Self {
+        self.with(|s| s.set_item_icon(s.len() - 1, icon))
+    }
+
+    /// Sets a text shown on the right of item `i`, like a shortcut or a
+    /// count.
+    ///
+    /// When space is short, the label is truncated first.
+    ///
+    /// # Panics
+    ///
+    /// If `i` is out of bounds.
+    pub fn set_item_secondary<S: Into<StyledString>>(
+        &mut self, i: usize, text: S,
+    ) {
+        self.items[i].secondary = Some(text.into());
+    }
+
+    /// Sets a text shown on the right of the last item.
+    ///
+    /// Chainable variant.
+    ///
+    /// # Panics
+    ///
+    /// If the view has no item.
+    pub fn secondary<S: Into<StyledString>>(self, text: S) -> Self {
+        self.with(|s| s.set_item_secondary(s.len() - 1, text))
+    }
+
+    /// Returns the icon of item `i`, if any.
+    pub fn get_item_icon(&self, i: usize) -> Option<&StyledString> {
+        self.items.get(i).and_then(|item| item.icon.as_ref())
+    }
+
+    /// Returns the secondary text of item `i`, if any.
+    pub fn get_item_secondary(&self, i: usize) -> Option<&StyledString> {
+        self.items.get(i).and_then(|item| item.secondary.as_ref())
+    }
+
+    /// Removes the icon and secondary text of item `i`.
+    ///
+    /// # Panics
+    ///
+    /// If `i` is out of bounds.
+    pub fn clear_item_decorations(&mut self, i: usize) {
+        let item = &mut self.items[i];
+        item.icon = None;
+        item.secondary = None;
+    }
+
     fn draw_item(&self, printer: &Printer<'_, '_>, i: usize) {
-        let l = self.items[i].label.width();
-        let x = self.align.h.get_offset(l, printer.size.x);
-        printer.print_hline((0, 0), x, " ");
-        printer.print_styled((x, 0), (&self.items[i].label).into());
-        if l < printer.size.x {
-            assert!((l + x) <= printer.size.x);
-            printer.print_hline((x + l, 0), printer.size.x - (l + x), " ");
+        let item = &self.items[i];
+        let width = printer.size.x;
+        printer.print_hline((0, 0), width, " ");
+
+        let icon_width = item.icon.as_ref().map_or(0, StyledString::width);
+        if let Some(ref icon) = item.icon {
+            printer.print_styled((0, 0), icon.into());
+        }
+
+        // Keep a space between the label and the secondary text.
+        let secondary_width =
+            item.secondary.as_ref().map_or(0, |text| text.width() + 1);
+        let available = width.saturating_sub(icon_width + secondary_width);
+
+        let l = item.label.width();
+        if l <= available {
+            let x = icon_width + self.align.h.get_offset(l, available);
+            printer.print_styled((x, 0), (&item.label).into());
+        } else if available > 0 {
+            // Truncate the label, and show it with an ellipsis.
+            printer
+                .offset((icon_width, 0))
+                .cropped((available - 1, 1))
+                .print_styled((0, 0), (&item.label).into());
+            printer.print((icon_width + available - 1, 0), "…");
+        }
+
+        if let Some(ref text) = item.secondary {
+            let x = width.saturating_sub(text.width());
+            printer.print_styled((x, 0), text.into());
+        }
+    }
+
+    // Width of a single column: the longest item.
+    fn column_width(&self) -> usize {
+        self.items
+            .iter()
+            .map(|item| {
+                // Popups only show the label.
+                if self.popup {
+                    return item.label.width();
+                }
+                let icon = item.icon.as_ref().map_or(0, StyledString::width);
+                let secondary =
+                    item.secondary.as_ref().map_or(0, |text| text.width() + 1);
+                icon + item.label.width() + secondary
+            })
+            .max()
+            .unwrap_or(1)
+    }
+
+    // Number of columns to use when given the available width.
+    fn columns_for_width(&self, width: usize) -> usize {
+        let columns = match self.columns {
+            Some(columns) => columns,
+            None => {
+                (width + COLUMN_SPACING)
+                    / (self.column_width() + COLUMN_SPACING)
+            }
+        };
+
+        // No need for more columns than we have items.
+        max(1, min(columns, self.items.len()))
+    }
+
+    // Number of rows needed to fit a page in the given columns.
+    fn rows_for_columns(&self, columns: usize) -> usize {
+        let len = self.items.len();
+        div_up(self.page_size.map_or(len, |size| min(size, len)), columns)
+    }
+
+    // Indices of the items on the current page.
+    fn page_items(&self) -> Range<usize> {
+        match self.page_size {
+            Some(size) => {
+                let start = self.get_page() * size;
+                start..min(start + size, self.items.len())
+            }
+            None => 0..self.items.len(),
+        }
+    }
+
+    // `true` if the page indicator is shown.
+    fn shows_pages(&self) -> bool {
+        !self.popup && self.page_count() > 1
+    }
+
+    // Top-left corner of the given item, which must be on the current page.
+    fn item_position(&self, i: usize) -> Vec2 {
+        let i = i - self.page_items().start;
+        let columns = self.effective_columns;
+        let x = (i % columns) * (self.column_width() + COLUMN_SPACING);
+        Vec2::new(x, i / columns)
+    }
+
+    // Returns the index of the item at the given (relative) position.
+    fn item_at(&self, position: Vec2) -> Option<usize> {
+        let columns = self.effective_columns;
+        let column = if columns == 1 {
+            // A single column spans the entire width.
+            0
+        } else {
+            let width = self.column_width();
+            if position.x % (width + COLUMN_SPACING) >= width {
+                // We're right between two columns.
+                return None;
+            }
+            position.x / (width + COLUMN_SPACING)
+        };
+
+        if column >= columns {
+            return None;
+        }
+
+        let items = self.page_items();
+        let i = items.start + position.y * columns + column;
+        if i < items.end {
+            Some(i)
+        } else {
+            None
         }
     }
 
@@ -385,6 +866,11 @@ impl<T: 'static> SelectView<T> {
         self.focus.get()
     }
 
+    // Column of the currently selected item.
+    fn focus_column(&self) -> usize {
+        self.focus() % self.effective_columns
+    }
+
     /// Sort the current items lexicographically by their label.
     /// Note that this does not change the current focus index, which means that the current
     /// selection will likely be changed by the sorting.
@@ -500,40 +986,93 @@ impl<T: 'static> SelectView<T> {
     }
 
     fn on_char_event(&mut self, c: char) -> EventResult {
+        let now = Instant::now();
+        let timeout = self.autojump_timeout;
+        if self
+            .autojump_last
+            .filter(|&last| now.duration_since(last) < timeout)
+            .is_none()
+        {
+            self.autojump_query.clear();
+        }
+        self.autojump_last = Some(now);
+
+        // We'll do a lowercase check.
+        self.autojump_query.extend(c.to_lowercase());
+
         let i = {
+            // Repeating the same letter cycles through the matching items,
+            // so only look for that letter.
+            let first = self.autojump_query.chars().next().unwrap();
+            let repeated = self.autojump_query.chars().all(|c| c == first);
+            let (query, skip) = if repeated {
+                (&self.autojump_query[..first.len_utf8()], 1)
+            } else {
+                // The current item may still match a longer query.
+                (&self.autojump_query[..], 0)
+            };
+
             // * Starting from the current focus, find the first item that
-            //   match the char.
+            //   match the query.
             // * Cycle back to the beginning of the list when we reach the end.
             // * This is achieved by chaining twice the iterator.
-            let iter = self.iter().chain(self.iter());
-
-            // We'll do a lowercase check.
-            let lower_c: Vec<char> = c.to_lowercase().collect();
-            let lower_c: &[char] = &lower_c;
+            let find = |predicate: &dyn Fn(&str) -> bool| {
+                self.iter()
+                    .chain(self.iter())
+                    .enumerate()
+                    .skip(self.focus() + skip)
+                    .find(|&(_, (label, _))| {
+                        predicate(&label.to_lowercase())
+                    })
+                    .map(|(i, _)| i)
+            };
 
-            if let Some((i, _)) = iter.enumerate().skip(self.focus() + 1).find(
-                |&(_, (label, _))| label.to_lowercase().starts_with(lower_c),
-            ) {
-                i % self.len()
-            } else {
-                return EventResult::Ignored;
+            match find(&|label| label.starts_with(query))
+                .or_else(|| find(&|label| label.contains(query)))
+            {
+                // Apply modulo in case we have a hit from the chained iterator
+                Some(i) => i % self.len(),
+                None => return EventResult::Ignored,
             }
         };
 
         self.focus.set(i);
-        // Apply modulo in case we have a hit from the chained iterator
         let cb = self.set_selection(i);
         EventResult::Consumed(Some(cb))
     }
 
     fn on_event_regular(&mut self, event: Event) -> EventResult {
+        let columns = self.effective_columns;
         match event {
-            Event::Key(Key::Up) if self.focus() > 0 => self.focus_up(1),
-            Event::Key(Key::Down) if self.focus() + 1 < self.items.len() => {
+            Event::Key(Key::Up) if self.focus() >= columns => {
+                self.focus_up(columns)
+            }
+            Event::Key(Key::Down)
+                if self.focus() + columns < self.items.len() =>
+            {
+                self.focus_down(columns)
+            }
+            // At the bottom, pressing down may still load more items.
+            Event::Key(Key::Down)
+                if self.on_reach_end.is_some() && !self.loading =>
+            {
+                return self.check_reach_end();
+            }
+            Event::Key(Key::Left) if self.focus_column() > 0 => {
+                self.focus_up(1)
+            }
+            Event::Key(Key::Right)
+                if self.focus_column() + 1 < columns
+                    && self.focus() + 1 < self.items.len() =>
+            {
                 self.focus_down(1)
             }
-            Event::Key(Key::PageUp) => self.focus_up(10),
-            Event::Key(Key::PageDown) => self.focus_down(10),
+            Event::Key(Key::PageUp) => {
+                self.focus_up(self.page_size.unwrap_or(10 * columns))
+            }
+            Event::Key(Key::PageDown) => {
+                self.focus_down(self.page_size.unwrap_or(10 * columns))
+            }
             Event::Key(Key::Home) => self.focus.set(0),
             Event::Key(Key::End) => {
                 self.focus.set(self.items.len().saturating_sub(1))
@@ -544,24 +1083,38 @@ impl<T: 'static> SelectView<T> {
                 offset,
             } if position
                 .checked_sub(offset)
-                .map(|position| {
-                    position < self.last_size && position.y < self.len()
-                })
-                .unwrap_or(false) =>
+                .filter(|&position| position < self.last_size)
+                .and_then(|position| self.item_at(position))
+                .is_some() =>
             {
-                self.focus.set(position.y - offset.y)
+                let i = self.item_at(position - offset).unwrap();
+                self.focus.set(i)
             }
             Event::Mouse {
                 event: MouseEvent::Release(MouseButton::Left),
                 position,
                 offset,
             } if self.on_submit.is_some()
+                && !self.submit_on_double_click
                 && position
                     .checked_sub(offset)
-                    .map(|position| {
-                        position < self.last_size && position.y == self.focus()
-                    })
-                    .unwrap_or(false) =>
+                    .filter(|&position| position < self.last_size)
+                    .and_then(|position| self.item_at(position))
+                    == Some(self.focus()) =>
+            {
+                return self.submit();
+            }
+            Event::Mouse {
+                event: MouseEvent::DoubleClick(MouseButton::Left),
+                position,
+                offset,
+            } if self.on_submit.is_some()
+                && self.submit_on_double_click
+                && position
+                    .checked_sub(offset)
+                    .filter(|&position| position < self.last_size)
+                    .and_then(|position| self.item_at(position))
+                    == Some(self.focus()) =>
             {
                 return self.submit();
             }
@@ -572,7 +1125,8 @@ impl<T: 'static> SelectView<T> {
             _ => return EventResult::Ignored,
         }
 
-        EventResult::Consumed(self.make_select_cb())
+        let result = EventResult::Consumed(self.make_select_cb());
+        result.and(self.check_reach_end())
     }
 
     /// Returns a callback from selection change.
@@ -754,12 +1308,20 @@ impl<T: 'static> View for SelectView<T> {
             });
         } else {
             // Non-popup mode: we always print the entire list.
-            let h = self.items.len();
+            let columns = self.effective_columns;
+            let h = self.rows_for_columns(columns);
             let offset = self.align.v.get_offset(h, printer.size.y);
             let printer = &printer.offset((0, offset));
-
-            for i in 0..self.len() {
-                printer.offset((0, i)).with_selection(
+            let width = self.column_width();
+
+            let items = self.page_items();
+            for i in items {
+                let printer = if columns == 1 {
+                    printer.offset(self.item_position(i))
+                } else {
+                    printer.offset(self.item_position(i)).cropped((width, 1))
+                };
+                printer.with_selection(
                     i == self.focus(),
                     |printer| {
                         if i != self.focus()
@@ -775,24 +1337,47 @@ impl<T: 'static> View for SelectView<T> {
                     },
                 );
             }
+
+            let mut y = self.rows_for_columns(columns);
+            if self.loading {
+                printer.with_color(ColorStyle::secondary(), |printer| {
+                    printer.print_styled((0, y), (&self.loading_label).into());
+                });
+                y += 1;
+            }
+
+            if self.shows_pages() {
+                let label = page_label(self.get_page(), self.page_count());
+                let x = HAlign::Center.get_offset(label.len(), printer.size.x);
+                printer.with_color(ColorStyle::secondary(), |printer| {
+                    printer.print((x, y), &label);
+                });
+            }
         }
     }
 
-    fn required_size(&mut self, _: Vec2) -> Vec2 {
+    fn required_size(&mut self, constraint: Vec2) -> Vec2 {
         // Items here are not compressible.
         // So no matter what the horizontal requirements are,
         // we'll still return our longest item.
-        let w = self
-            .items
-            .iter()
-            .map(|item| item.label.width())
-            .max()
-            .unwrap_or(1);
+        let w = self.column_width();
         if self.popup {
             Vec2::new(w + 2, 1)
         } else {
-            let h = self.items.len();
-
+            let columns = self.columns_for_width(constraint.x);
+            let h = self.rows_for_columns(columns);
+            let mut w = columns * w + (columns - 1) * COLUMN_SPACING;
+            let mut h = h;
+
+            if self.loading {
+                w = max(w, self.loading_label.width());
+                h += 1;
+            }
+            if self.shows_pages() {
+                let label = page_label(self.get_page(), self.page_count());
+                w = max(w, label.len());
+                h += 1;
+            }
             Vec2::new(w, h)
         }
     }
@@ -811,25 +1396,60 @@ impl<T: 'static> View for SelectView<T> {
 
     fn layout(&mut self, size: Vec2) {
         self.last_size = size;
+        self.effective_columns = self.columns_for_width(size.x);
     }
 
     fn important_area(&self, size: Vec2) -> Rect {
         self.selected_id()
-            .map(|i| Rect::from_size((0, i), (size.x, 1)))
+            .map(|i| {
+                if self.effective_columns == 1 {
+                    Rect::from_size(self.item_position(i), (size.x, 1))
+                } else {
+                    let width = self.column_width();
+                    Rect::from_size(self.item_position(i), (width, 1))
+                }
+            })
             .unwrap_or_else(|| Rect::from((0, 0)))
     }
+
+    fn save_state(&self) -> Option<ViewState> {
+        self.selected_id().map(ViewState::Selected)
+    }
+
+    fn restore_state(&mut self, state: &ViewState) {
+        if let ViewState::Selected(i) = *state {
+            self.set_selection(i);
+        }
+    }
+}
+
+// Blank space between two columns.
+const COLUMN_SPACING: usize = 1;
+
+// Indicator shown below a paged list.
+fn page_label(page: usize, count: usize) -> String {
+    format!("< {}/{} >", page + 1, count)
 }
 
 // We wrap each value in a `Rc` and add a label
 struct Item<T> {
     label: StyledString,
     value: Rc<T>,
+    // Shown before the label.
+    icon: Option<StyledString>,
+    // Shown on the right.
+    secondary: Option<StyledString>,
 }
 
 impl<T> Item<T> {
     fn new(label: StyledString, value: T) -> Self {
         let value = Rc::new(value);
-        Item { label, value }
+        Item {
+            label,
+            value,
+            icon: None,
+            secondary: None,
+        }
     }
 }
 
@@ -908,6 +1528,103 @@ mod tests {
         assert_eq!(view.selection(), Some(Rc::new(MyStruct { key: 3 })));
     }
 
+    #[test]
+    fn select_view_autojump() {
+        let mut view = SelectView::new().autojump();
+        view.add_all_str(vec!["apple", "banana", "blueberry", "cherry"]);
+
+        let type_char = |view: &mut SelectView, c| {
+            view.on_event(Event::Char(c));
+            view.selected_id()
+        };
+
+        assert_eq!(type_char(&mut view, 'b'), Some(1));
+        assert_eq!(type_char(&mut view, 'l'), Some(2));
+        assert_eq!(type_char(&mut view, 'x'), Some(2));
+
+        // A new search, matching in the middle of an item
+        view.set_autojump_timeout(Duration::from_secs(0));
+        assert_eq!(type_char(&mut view, 'h'), Some(3));
+        assert_eq!(type_char(&mut view, 'b'), Some(1));
+        assert_eq!(type_char(&mut view, 'b'), Some(2));
+    }
+
+    #[test]
+    fn select_view_reach_end() {
+        let mut view = SelectView::new()
+            .with_all_str(vec!["a", "b", "c"])
+            .reach_end_distance(1)
+            .on_reach_end(|_| ());
+
+        assert!(view.on_event(Event::Key(Key::Down)).has_callback());
+        assert!(view.is_loading());
+        assert_eq!(view.required_size(Vec2::new(20, 20)), Vec2::new(10, 4));
+
+        // No new callback while loading.
+        assert!(!view.on_event(Event::Key(Key::Down)).has_callback());
+        assert!(!view.on_event(Event::Key(Key::Down)).is_consumed());
+
+        view.add_item_str("d");
+        assert!(!view.is_loading());
+    }
+
+    #[test]
+    fn select_view_columns() {
+        let mut view = SelectView::new().columns(3);
+        view.add_all_str(vec!["a", "b", "c", "d", "e"]);
+
+        // Two rows of up to 3 columns.
+        let size = view.required_size(Vec2::new(80, 24));
+        assert_eq!(size, Vec2::new(5, 2));
+        view.layout(size);
+
+        view.on_event(Event::Key(Key::Right));
+        assert_eq!(view.selected_id(), Some(1));
+        view.on_event(Event::Key(Key::Down));
+        assert_eq!(view.selected_id(), Some(4));
+        // Last column of the second row is empty.
+        view.on_event(Event::Key(Key::Right));
+        assert_eq!(view.selected_id(), Some(4));
+        view.on_event(Event::Key(Key::Left));
+        assert_eq!(view.selected_id(), Some(3));
+        view.on_event(Event::Key(Key::Up));
+        assert_eq!(view.selected_id(), Some(0));
+    }
+
+    #[test]
+    fn select_view_pages() {
+        let mut view = SelectView::new().paged(3);
+        view.add_all_str(vec!["a", "b", "c", "d", "e", "f", "g"]);
+        assert_eq!(view.page_count(), 3);
+
+        // Three items, and the page indicator.
+        let size = view.required_size(Vec2::new(80, 24));
+        assert_eq!(size, Vec2::new(7, 4));
+        view.layout(size);
+
+        view.on_event(Event::Key(Key::Down));
+        view.on_event(Event::Key(Key::PageDown));
+        assert_eq!(view.selected_id(), Some(4));
+        assert_eq!(view.get_page(), 1);
+
+        // Items are placed relative to their page.
+        assert_eq!(view.item_at(Vec2::new(0, 0)), Some(3));
+
+        view.set_page(5);
+        assert_eq!(view.selected_id(), Some(6));
+        assert_eq!(view.page_items(), 6..7);
+    }
+
+    #[test]
+    fn select_view_auto_columns() {
+        let mut view = SelectView::new().auto_columns();
+        view.add_all_str(vec!["aa", "bb", "cc", "dd", "ee"]);
+
+        // Each column takes 2 cells plus 1 cell of spacing.
+        assert_eq!(view.required_size(Vec2::new(8, 24)), Vec2::new(8, 2));
+        assert_eq!(view.required_size(Vec2::new(4, 24)), Vec2::new(2, 5));
+    }
+
     #[test]
     fn select_view_sorting_orderable_items() {
         // We add items in no particular order, from going by their value.
@@ -929,4 +1646,54 @@ mod tests {
         view.on_event(Event::Key(Key::Down));
         assert_eq!(view.selection(), Some(Rc::new(3)));
     }
+
+    #[test]
+    fn item_decorations() {
+        let mut view = SelectView::new()
+            .item("Open", 1)
+            .icon("> ")
+            .secondary("Ctrl-O")
+            .item("Quit", 2);
+
+        // Icon, label, space and secondary text.
+        assert_eq!(view.required_size(Vec2::new(80, 10)), Vec2::new(13, 2));
+        assert_eq!(view.get_item_icon(0).map(|i| i.source()), Some("> "));
+        assert_eq!(view.get_item_secondary(1), None);
+
+        view.clear_item_decorations(0);
+        assert_eq!(view.required_size(Vec2::new(80, 10)), Vec2::new(4, 2));
+    }
+
+    #[test]
+    fn submit_on_double_click() {
+        use crate::backend::puppet;
+        use crate::traits::Boxable;
+
+        let (backend, puppet) = puppet::Backend::init((10, 4));
+        let mut siv = Cursive::new(move || backend);
+        // However slow the steps below, the clicks make a double click.
+        siv.set_click_interval(Duration::from_secs(3600));
+        let view = SelectView::new()
+            .item_str("a")
+            .item_str("b")
+            .submit_on_double_click()
+            .on_submit(|s, item: &str| s.set_user_data(item.to_string()));
+        siv.add_fullscreen_layer(view.full_screen());
+        siv.refresh();
+
+        let click = |event| Event::Mouse {
+            offset: Vec2::zero(),
+            position: Vec2::new(0, 1),
+            event,
+        };
+        puppet.send(click(MouseEvent::Press(MouseButton::Left)));
+        puppet.send(click(MouseEvent::Release(MouseButton::Left)));
+        while siv.step() {}
+        assert_eq!(siv.user_data::<String>(), None);
+
+        // The second press completes a double click.
+        puppet.send(click(MouseEvent::Press(MouseButton::Left)));
+        while siv.step() {}
+        assert_eq!(siv.user_data::<String>(), Some(&mut "b".to_string()));
+    }
 }
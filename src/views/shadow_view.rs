@@ -1,17 +1,38 @@
 use crate::event::{Event, EventResult};
+use crate::rect::Rect;
 use crate::theme::ColorStyle;
 use crate::vec::Vec2;
-use crate::view::{View, ViewWrapper};
-use crate::Printer;
+use crate::view::{Selector, View, ViewWrapper};
+use crate::{Printer, With};
 
 /// Wrapper view that adds a shadow.
 ///
 /// It reserves a 1 pixel border on each side.
+///
+/// The shadow is drawn below and to the right of the view. Its size, the
+/// character it is made of and its color can be customized.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::theme::ColorStyle;
+/// # use cursive::views::{ShadowView, TextView};
+/// let view = ShadowView::new(TextView::new("Deep"))
+///     .offset((2, 1))
+///     .glyph("░")
+///     .style(ColorStyle::secondary())
+///     .no_padding();
+/// ```
 pub struct ShadowView<T: View> {
     view: T,
     top_padding: bool,
     left_padding: bool,
     // TODO: invalidate if we change the padding?
+
+    // Size of the shadow, right and below the view.
+    offset: Vec2,
+    glyph: String,
+    style: ColorStyle,
 }
 
 impl<T: View> ShadowView<T> {
@@ -21,11 +42,14 @@ impl<T: View> ShadowView<T> {
             view,
             top_padding: true,
             left_padding: true,
+            offset: Vec2::new(1, 1),
+            glyph: String::from(" "),
+            style: ColorStyle::shadow(),
         }
     }
 
     fn padding(&self) -> Vec2 {
-        self.top_left_padding() + (1, 1)
+        self.top_left_padding() + self.offset
     }
 
     fn top_left_padding(&self) -> Vec2 {
@@ -48,6 +72,91 @@ impl<T: View> ShadowView<T> {
         self
     }
 
+    /// Removes both the top and left padding.
+    ///
+    /// Chainable variant.
+    pub fn no_padding(self) -> Self {
+        self.left_padding(false).top_padding(false)
+    }
+
+    /// Sets whether an empty column is added to the left of the view.
+    pub fn set_left_padding(&mut self, value: bool) {
+        self.left_padding = value;
+    }
+
+    /// Sets whether an empty row is added at the top of the view.
+    pub fn set_top_padding(&mut self, value: bool) {
+        self.top_padding = value;
+    }
+
+    /// Returns `true` if an empty column is added to the left of the view.
+    pub fn has_left_padding(&self) -> bool {
+        self.left_padding
+    }
+
+    /// Returns `true` if an empty row is added at the top of the view.
+    pub fn has_top_padding(&self) -> bool {
+        self.top_padding
+    }
+
+    /// Sets the size of the shadow.
+    ///
+    /// `x` is the width of the shadow on the right of the view, and `y` its
+    /// height below the view. Defaults to `(1, 1)`.
+    pub fn set_offset<V: Into<Vec2>>(&mut self, offset: V) {
+        self.offset = offset.into();
+    }
+
+    /// Sets the size of the shadow.
+    ///
+    /// Chainable variant.
+    pub fn offset<V: Into<Vec2>>(self, offset: V) -> Self {
+        self.with(|s| s.set_offset(offset))
+    }
+
+    /// Returns the size of the shadow.
+    pub fn get_offset(&self) -> Vec2 {
+        self.offset
+    }
+
+    /// Sets the character used to draw the shadow.
+    ///
+    /// It should be a single cell wide. Defaults to a space.
+    pub fn set_glyph<S: Into<String>>(&mut self, glyph: S) {
+        self.glyph = glyph.into();
+    }
+
+    /// Sets the character used to draw the shadow.
+    ///
+    /// Chainable variant.
+    pub fn glyph<S: Into<String>>(self, glyph: S) -> Self {
+        self.with(|s| s.set_glyph(glyph))
+    }
+
+    /// Returns the character used to draw the shadow.
+    pub fn get_glyph(&self) -> &str {
+        &self.glyph
+    }
+
+    /// Sets the color used to draw the shadow.
+    ///
+    /// Defaults to `ColorStyle::shadow()`.
+    pub fn set_style<S: Into<ColorStyle>>(&mut self, style: S) {
+        self.style = style.into();
+    }
+
+    /// Sets the color used to draw the shadow.
+    ///
+    /// Chainable variant.
+    pub fn style<S: Into<ColorStyle>>(self, style: S) -> Self {
+        self.with(|s| s.set_style(style))
+    }
+
+    /// Returns the color used to draw the shadow.
+    pub fn get_style(&self) -> ColorStyle {
+        self.style
+    }
+
     inner_getters!(self.view: T);
 }
 
@@ -70,6 +179,15 @@ impl<T: View> ViewWrapper for ShadowView<T> {
         self.view.on_event(event.relativized(padding))
     }
 
+    fn wrap_locate(
+        &self, selector: &Selector<'_>, size: Vec2,
+    ) -> Option<Rect> {
+        let inner_size = size.saturating_sub(self.padding());
+        self.view
+            .locate(selector, inner_size)
+            .map(|area| area + self.top_left_padding())
+    }
+
     fn wrap_draw(&self, printer: &Printer<'_, '_>) {
         if printer.size.y <= self.top_padding as usize
             || printer.size.x <= self.left_padding as usize
@@ -90,14 +208,20 @@ impl<T: View> ViewWrapper for ShadowView<T> {
                 return;
             }
 
-            printer.with_color(ColorStyle::shadow(), |printer| {
-                printer.print_hline((1, h - 1), w - 1, " ");
-                printer.print_vline((w - 1, 1), h - 1, " ");
+            // The shadow is shifted by its own size.
+            let Vec2 { x: dx, y: dy } = self.offset.or_min((w, h));
+            printer.with_color(self.style, |printer| {
+                for y in h - dy..h {
+                    printer.print_hline((dx, y), w - dx, &self.glyph);
+                }
+                for x in w - dx..w {
+                    printer.print_vline((x, dy), h - dy, &self.glyph);
+                }
             });
         }
 
         // Draw the view background
-        let printer = printer.shrinked((1, 1));
+        let printer = printer.shrinked(self.offset);
         self.view.draw(&printer);
     }
 }
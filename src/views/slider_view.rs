@@ -2,21 +2,62 @@ use crate::direction::{Direction, Orientation};
 use crate::event::{
     Callback, Event, EventResult, Key, MouseButton, MouseEvent,
 };
+use crate::state::ViewState;
 use crate::theme::ColorStyle;
+use crate::utils::width::TextWidth;
 use crate::vec::Vec2;
 use crate::view::View;
 use crate::With;
 use crate::{Cursive, Printer};
+use std::cell::Cell;
 use std::rc::Rc;
 
+type OnFloatChange = dyn Fn(&mut Cursive, f64);
+
+type LabelFormat = dyn Fn(f64) -> String;
+
 /// A horizontal or vertical slider.
+///
+/// The slider has a number of positions, from `0` to `max_value - 1`.
+/// Positions can be mapped to a numeric range with `SliderView::range`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::views::SliderView;
+/// // From 0.0 to 1.0, by steps of 0.1: 11 positions.
+/// let slider = SliderView::horizontal(0)
+///     .range(0.0, 1.0, 0.1)
+///     .ticks(5)
+///     .labels()
+///     .float_value(0.5)
+///     .on_float_change(|s, value| {
+///         // Use `value`...
+///     });
+/// ```
 pub struct SliderView {
     orientation: Orientation,
     on_change: Option<Rc<dyn Fn(&mut Cursive, usize)>>,
+    on_float_change: Option<Rc<OnFloatChange>>,
     on_enter: Option<Rc<dyn Fn(&mut Cursive, usize)>>,
     value: usize,
     max_value: usize,
     dragging: bool,
+
+    // Value of the first position, and difference between two positions.
+    min: f64,
+    step: f64,
+
+    // Draw a tick every `ticks` positions. 0 means no tick.
+    ticks: usize,
+    tick_symbol: String,
+
+    // If `true`, show min, max and current values next to the slider.
+    labels: bool,
+    label_format: Option<Rc<LabelFormat>>,
+
+    // Width of the widest label, until the range or the format changes.
+    label_width: Cell<Option<usize>>,
 }
 
 impl SliderView {
@@ -30,8 +71,16 @@ impl SliderView {
             value: 0,
             max_value,
             on_change: None,
+            on_float_change: None,
             on_enter: None,
             dragging: false,
+            min: 0.0,
+            step: 1.0,
+            ticks: 0,
+            tick_symbol: String::from("+"),
+            labels: false,
+            label_format: None,
+            label_width: Cell::new(None),
         }
     }
 
@@ -54,6 +103,166 @@ impl SliderView {
         self.get_change_result()
     }
 
+    /// Returns the current position.
+    pub fn get_value(&self) -> usize {
+        self.value
+    }
+
+    /// Returns the number of positions in this slider.
+    pub fn get_max_value(&self) -> usize {
+        self.max_value
+    }
+
+    /// Maps the positions of this slider to the given range.
+    ///
+    /// The slider will have one position for each `step` from `min` to `max`.
+    /// This replaces the `max_value` given in the constructor.
+    ///
+    /// # Panics
+    ///
+    /// If `step` is not positive.
+    pub fn set_range(&mut self, min: f64, max: f64, step: f64) {
+        assert!(step > 0.0, "SliderView: the step must be positive.");
+        self.min = min;
+        self.step = step;
+        self.max_value = ((max - min) / step).round().max(0.0) as usize + 1;
+        self.value = self.value.min(self.max_value.saturating_sub(1));
+        self.label_width.set(None);
+    }
+
+    /// Maps the positions of this slider to the given range.
+    ///
+    /// Chainable variant.
+    pub fn range(self, min: f64, max: f64, step: f64) -> Self {
+        self.with(|s| s.set_range(min, max, step))
+    }
+
+    /// Returns the current value, in the range set with `set_range`.
+    ///
+    /// Without a range, this is the current position.
+    pub fn get_float_value(&self) -> f64 {
+        self.float_at(self.value)
+    }
+
+    /// Sets the current value to the position closest to `value`.
+    ///
+    /// Returns an event result with a possible callback,
+    /// if `on_change` was set.
+    pub fn set_float_value(&mut self, value: f64) -> EventResult {
+        let position = ((value - self.min) / self.step).round().max(0.0);
+        let last = self.max_value.saturating_sub(1);
+        self.set_value((position as usize).min(last))
+    }
+
+    /// Sets the current value to the position closest to `value`.
+    ///
+    /// Chainable variant.
+    pub fn float_value(self, value: f64) -> Self {
+        self.with(|s| {
+            s.set_float_value(value);
+        })
+    }
+
+    /// Draws a tick mark every `interval` positions.
+    ///
+    /// 0 (the default) disables ticks.
+    pub fn set_ticks(&mut self, interval: usize) {
+        self.ticks = interval;
+    }
+
+    /// Draws a tick mark every `interval` positions.
+    ///
+    /// Chainable variant.
+    pub fn ticks(self, interval: usize) -> Self {
+        self.with(|s| s.set_ticks(interval))
+    }
+
+    /// Sets the symbol drawn for tick marks.
+    ///
+    /// It should be one cell wide. Defaults to `"+"`.
+    pub fn set_tick_symbol<S: Into<String>>(&mut self, symbol: S) {
+        self.tick_symbol = symbol.into();
+    }
+
+    /// Sets the symbol drawn for tick marks.
+    ///
+    /// Chainable variant.
+    pub fn tick_symbol<S: Into<String>>(self, symbol: S) -> Self {
+        self.with(|s| s.set_tick_symbol(symbol))
+    }
+
+    /// Shows the min, max and current values next to the slider.
+    pub fn set_labels(&mut self, labels: bool) {
+        self.labels = labels;
+    }
+
+    /// Shows the min, max and current values next to the slider.
+    ///
+    /// Chainable variant.
+    pub fn labels(self) -> Self {
+        self.with(|s| s.set_labels(true))
+    }
+
+    /// Sets the function used to format labels.
+    ///
+    /// By default, values are shown with as many decimals as the step.
+    pub fn set_label_format<F>(&mut self, format: F)
+    where
+        F: Fn(f64) -> String + 'static,
+    {
+        self.label_format = Some(Rc::new(format));
+        self.label_width.set(None);
+    }
+
+    /// Sets the function used to format labels.
+    ///
+    /// Chainable variant.
+    pub fn label_format<F>(self, format: F) -> Self
+    where
+        F: Fn(f64) -> String + 'static,
+    {
+        self.with(|s| s.set_label_format(format))
+    }
+
+    // Returns the value for the given position.
+    fn float_at(&self, position: usize) -> f64 {
+        self.min + position as f64 * self.step
+    }
+
+    fn format_label(&self, position: usize) -> String {
+        let value = self.float_at(position);
+        match self.label_format {
+            Some(ref format) => format(value),
+            None => {
+                // Show as many decimals as the step needs.
+                let mut decimals = 0;
+                let mut step = self.step;
+                while decimals < 6 && (step - step.round()).abs() > 1e-9 {
+                    step *= 10.0;
+                    decimals += 1;
+                }
+                format!("{:.*}", decimals, value)
+            }
+        }
+    }
+
+    // Returns the width needed for labels.
+    //
+    // Every label is formatted the first time, as a custom format may make
+    // any of them the widest.
+    fn label_width(&self) -> usize {
+        if let Some(width) = self.label_width.get() {
+            return width;
+        }
+
+        let width = (0..self.max_value)
+            .map(|i| self.format_label(i).width())
+            .max()
+            .unwrap_or(0);
+        self.label_width.set(Some(width));
+        width
+    }
+
     /// Sets the current value.
     ///
     /// Chainable variant.
@@ -72,6 +281,17 @@ impl SliderView {
         self
     }
 
+    /// Sets a callback to be called when the slider is moved.
+    ///
+    /// The callback is given the value in the range set with `set_range`.
+    pub fn on_float_change<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&mut Cursive, f64) + 'static,
+    {
+        self.on_float_change = Some(Rc::new(callback));
+        self
+    }
+
     /// Sets a callback to be called when the <Enter> key is pressed.
     pub fn on_enter<F>(mut self, callback: F) -> Self
     where
@@ -82,12 +302,17 @@ impl SliderView {
     }
 
     fn get_change_result(&self) -> EventResult {
-        EventResult::Consumed(self.on_change.clone().map(|cb| {
+        let result = EventResult::Consumed(self.on_change.clone().map(|cb| {
             let value = self.value;
             Callback::from_fn(move |s| {
                 cb(s, value);
             })
-        }))
+        }));
+
+        let value = self.get_float_value();
+        result.and(EventResult::Consumed(self.on_float_change.clone().map(
+            |cb| Callback::from_fn(move |s| cb(s, value)),
+        )))
     }
 
     fn slide_plus(&mut self) -> EventResult {
@@ -108,9 +333,53 @@ impl SliderView {
         }
     }
 
-    fn req_size(&self) -> Vec2 {
+    // Size of the slider itself, without labels.
+    fn track_size(&self) -> Vec2 {
         self.orientation.make_vec(self.max_value, 1)
     }
+
+    fn req_size(&self) -> Vec2 {
+        if !self.labels {
+            return self.track_size();
+        }
+
+        match self.orientation {
+            Orientation::Horizontal => Vec2::new(self.max_value, 2),
+            Orientation::Vertical => {
+                Vec2::new(2 + self.label_width(), self.max_value)
+            }
+        }
+    }
+
+    fn draw_labels(&self, printer: &Printer<'_, '_>) {
+        let last = self.max_value.saturating_sub(1);
+        let min_label = self.format_label(0);
+        let max_label = self.format_label(last);
+        let current = self.format_label(self.value);
+
+        match self.orientation {
+            Orientation::Horizontal => {
+                let end =
+                    |label: &str| self.max_value.saturating_sub(label.width());
+                printer.print((0, 1), &min_label);
+                printer.print((end(&max_label), 1), &max_label);
+
+                // Center the current value under the knob.
+                let x = self.value.saturating_sub(current.width() / 2);
+                let x = x.min(end(&current));
+                printer.with_color(ColorStyle::title_primary(), |printer| {
+                    printer.print((x, 1), &current);
+                });
+            }
+            Orientation::Vertical => {
+                printer.print((2, 0), &min_label);
+                printer.print((2, last), &max_label);
+                printer.with_color(ColorStyle::title_primary(), |printer| {
+                    printer.print((2, self.value), &current);
+                });
+            }
+        }
+    }
 }
 
 impl View for SliderView {
@@ -124,6 +393,17 @@ impl View for SliderView {
             }
         }
 
+        if self.ticks > 0 {
+            for i in (0..self.max_value).step_by(self.ticks) {
+                let position = self.orientation.make_vec(i, 0);
+                printer.print(position, &self.tick_symbol);
+            }
+        }
+
+        if self.labels {
+            self.draw_labels(printer);
+        }
+
         let color = if printer.focused {
             ColorStyle::highlight()
         } else {
@@ -185,7 +465,7 @@ impl View for SliderView {
                 event: MouseEvent::Press(MouseButton::Left),
                 position,
                 offset,
-            } if position.fits_in_rect(offset, self.req_size()) => {
+            } if position.fits_in_rect(offset, self.track_size()) => {
                 if let Some(position) = position.checked_sub(offset) {
                     self.dragging = true;
                     self.value = self.orientation.get(&position);
@@ -206,4 +486,47 @@ impl View for SliderView {
     fn take_focus(&mut self, _: Direction) -> bool {
         true
     }
+
+    fn save_state(&self) -> Option<ViewState> {
+        Some(ViewState::Selected(self.value))
+    }
+
+    fn restore_state(&mut self, state: &ViewState) {
+        if let ViewState::Selected(value) = *state {
+            if value < self.max_value {
+                self.value = value;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn float_range() {
+        let mut slider = SliderView::horizontal(5).range(-1.0, 1.0, 0.25);
+        assert_eq!(slider.get_max_value(), 9);
+        assert_eq!(slider.get_float_value(), -1.0);
+
+        slider.set_float_value(0.3);
+        assert_eq!(slider.get_value(), 5);
+        assert_eq!(slider.get_float_value(), 0.25);
+
+        slider.set_float_value(12.0);
+        assert_eq!(slider.get_value(), 8);
+        assert_eq!(slider.format_label(8), "1.00");
+
+        slider.set_labels(true);
+        assert_eq!(slider.req_size(), Vec2::new(9, 2));
+
+        // The label width is kept until the range or the format changes.
+        slider.orientation = Orientation::Vertical;
+        assert_eq!(slider.req_size(), Vec2::new(7, 9));
+        slider.set_range(0.0, 100.0, 10.0);
+        assert_eq!(slider.req_size(), Vec2::new(5, 11));
+        slider.set_label_format(|value| format!("{}%", value));
+        assert_eq!(slider.req_size(), Vec2::new(6, 11));
+    }
 }
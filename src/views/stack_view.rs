@@ -1,18 +1,25 @@
-use crate::direction::Direction;
-use crate::event::{AnyCb, Event, EventResult};
-use crate::theme::ColorStyle;
+use crate::direction::{Absolute, Direction};
+use crate::event::{AnyCb, Event, EventResult, MouseEvent};
+use crate::theme::{ColorStyle, PaletteColor};
+use crate::utils::time::Instant;
 use crate::vec::Vec2;
 use crate::view::{
     IntoBoxedView, Offset, Position, Selector, View, ViewWrapper,
 };
 use crate::views::{CircularFocus, Layer, ShadowView, ViewBox};
 use crate::Printer;
+use crate::Rect;
 use crate::With;
 use std::cell;
 use std::ops::Deref;
+use std::time::Duration;
 
 /// Simple stack of views.
 /// Only the top-most view is active and can receive input.
+///
+/// Non-modal layers are the exception: they float above other layers, but
+/// let through mouse events outside of them, and key events they ignore.
+/// See `StackView::add_non_modal_layer`.
 pub struct StackView {
     // Store layers from back to front.
     layers: Vec<Child>,
@@ -20,6 +27,38 @@ pub struct StackView {
     // Flag indicates if undrawn areas of the background are exposed
     // and therefore need redrawing.
     bg_dirty: cell::Cell<bool>,
+
+    // Layers being removed, still drawn until their transition is over.
+    closing: Vec<ClosingLayer>,
+
+    // Transition given to new layers.
+    default_transition: Transition,
+    transition_duration: Duration,
+    transitions_enabled: bool,
+}
+
+/// Animation played when a layer is added to or removed from a `StackView`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Transition {
+    /// The layer appears and disappears at once.
+    None,
+    /// The layer slides in from the given side of the screen, and slides
+    /// out towards it.
+    ///
+    /// `Slide(Absolute::None)` is the same as `Transition::None`.
+    Slide(Absolute),
+    /// The layer fades in and out.
+    ///
+    /// Terminals have no transparency, so this is approximated by drawing
+    /// the layer with muted colors for part of the transition.
+    Fade,
+}
+
+// A layer that was removed, but is still playing its closing transition.
+struct ClosingLayer {
+    child: Child,
+    offset: Vec2,
+    start: Instant,
 }
 
 /// Where should the view be on the screen (per dimension).
@@ -152,6 +191,22 @@ impl<T: View> View for ChildWrapper<T> {
         }
     }
 
+    fn needs_relayout(&self) -> bool {
+        match *self {
+            ChildWrapper::Shadow(ref v) => v.needs_relayout(),
+            ChildWrapper::Backfilled(ref v) => v.needs_relayout(),
+            ChildWrapper::Plain(ref v) => v.needs_relayout(),
+        }
+    }
+
+    fn mark_dirty(&mut self) {
+        match *self {
+            ChildWrapper::Shadow(ref mut v) => v.mark_dirty(),
+            ChildWrapper::Backfilled(ref mut v) => v.mark_dirty(),
+            ChildWrapper::Plain(ref mut v) => v.mark_dirty(),
+        }
+    }
+
     fn required_size(&mut self, size: Vec2) -> Vec2 {
         match *self {
             ChildWrapper::Shadow(ref mut v) => v.required_size(size),
@@ -191,6 +246,14 @@ impl<T: View> View for ChildWrapper<T> {
             ChildWrapper::Plain(ref mut v) => v.focus_view(selector),
         }
     }
+
+    fn locate(&self, selector: &Selector<'_>, size: Vec2) -> Option<Rect> {
+        match *self {
+            ChildWrapper::Shadow(ref v) => v.locate(selector, size),
+            ChildWrapper::Backfilled(ref v) => v.locate(selector, size),
+            ChildWrapper::Plain(ref v) => v.locate(selector, size),
+        }
+    }
 }
 
 struct Child {
@@ -203,6 +266,19 @@ struct Child {
     // So we want to call `take_focus` right after the first call to `layout`.
     // This flag remembers when we've done that.
     virgin: bool,
+
+    // Transition played when this layer appears or disappears.
+    transition: Transition,
+
+    // When the opening transition started, if it is still running.
+    opening: Option<Instant>,
+
+    // If `false`, events can reach the layers below this one.
+    modal: bool,
+
+    // If `true`, this layer consumed the last mouse press, and receives
+    // mouse events until it is released.
+    mouse_grab: bool,
 }
 
 new_default!(StackView);
@@ -214,6 +290,254 @@ impl StackView {
             layers: Vec::new(),
             last_size: Vec2::zero(),
             bg_dirty: cell::Cell::new(true),
+            closing: Vec::new(),
+            default_transition: Transition::None,
+            transition_duration: Duration::from_millis(200),
+            transitions_enabled: true,
+        }
+    }
+
+    /// Sets the transition given to layers added from now on.
+    ///
+    /// Defaults to `Transition::None`.
+    ///
+    /// Transitions are animated during the refresh phase: while one is
+    /// running, `Cursive` will refresh the screen as fast as it can.
+    pub fn set_default_transition(&mut self, transition: Transition) {
+        self.default_transition = transition;
+    }
+
+    /// Sets the transition given to layers added from now on.
+    ///
+    /// Chainable variant.
+    pub fn default_transition(self, transition: Transition) -> Self {
+        self.with(|s| s.set_default_transition(transition))
+    }
+
+    /// Sets the transition for the given layer.
+    ///
+    /// It will be used when this layer is closed with `close_layer`.
+    ///
+    /// # Panics
+    ///
+    /// If `layer` is out of bounds.
+    pub fn set_layer_transition(
+        &mut self, layer: LayerPosition, transition: Transition,
+    ) {
+        let i = self.get_index(layer).unwrap();
+        self.layers[i].transition = transition;
+    }
+
+    /// Sets how long transitions last.
+    ///
+    /// Defaults to 200ms.
+    pub fn set_transition_duration(&mut self, duration: Duration) {
+        self.transition_duration = duration;
+    }
+
+    /// Enables or disables all transitions.
+    ///
+    /// When disabled, layers appear and disappear at once, whatever their
+    /// configured transition.
+    ///
+    /// Defaults to `true`.
+    pub fn set_transitions_enabled(&mut self, enabled: bool) {
+        self.transitions_enabled = enabled;
+    }
+
+    /// Returns `true` if transitions are enabled.
+    pub fn get_transitions_enabled(&self) -> bool {
+        self.transitions_enabled
+    }
+
+    /// Returns `true` if a transition is currently running.
+    pub fn is_animating(&self) -> bool {
+        !self.closing.is_empty()
+            || self
+                .layers
+                .iter()
+                .any(|layer| self.opening_progress(layer) < 1.0)
+    }
+
+    /// Returns `true` if the layers should be laid out again for `size`.
+    pub(crate) fn needs_layout(&self, size: Vec2) -> bool {
+        size != self.last_size || self.needs_relayout()
+    }
+
+    /// Removes the top-most layer, playing its transition.
+    ///
+    /// Unlike `pop_layer`, the view is not returned: it keeps being drawn
+    /// until the transition is over, and is then dropped.
+    ///
+    /// Returns `false` if there was no layer to close.
+    pub fn close_layer(&mut self) -> bool {
+        self.close_layer_at(LayerPosition::FromFront(0))
+    }
+
+    /// Removes the layer at the given position, playing its transition.
+    ///
+    /// Returns `false` if there is no layer at this position.
+    pub fn close_layer_at(&mut self, position: LayerPosition) -> bool {
+        let i = match self.get_index(position) {
+            Some(i) if i < self.layers.len() => i,
+            _ => return false,
+        };
+        let (_, offset) =
+            StackPositionIterator::new(self.layers.iter(), self.last_size)
+                .nth(i)
+                .unwrap();
+
+        let child = self.layers.remove(i);
+        self.bg_dirty.set(true);
+
+        if self.transitions_enabled && child.transition != Transition::None
+        {
+            // Start closing from wherever the opening transition was.
+            let elapsed = self.transition_duration.mul_f32(
+                1.0 - self.opening_progress(&child),
+            );
+            self.closing.push(ClosingLayer {
+                child,
+                offset,
+                start: Instant::now() - elapsed,
+            });
+        }
+
+        true
+    }
+
+    // Returns how far along the given transition is, between 0 and 1.
+    fn progress(&self, start: Instant) -> f32 {
+        let total = self.transition_duration.as_millis();
+        if total == 0 {
+            return 1.0;
+        }
+
+        let elapsed = start.elapsed().as_millis();
+        if elapsed >= total {
+            1.0
+        } else {
+            elapsed as f32 / total as f32
+        }
+    }
+
+    // Returns how much of the given layer is shown, between 0 and 1.
+    fn opening_progress(&self, child: &Child) -> f32 {
+        match child.opening {
+            Some(start)
+                if self.transitions_enabled
+                    && child.transition != Transition::None =>
+            {
+                self.progress(start)
+            }
+            _ => 1.0,
+        }
+    }
+
+    fn new_child(
+        &self, view: ChildWrapper<ViewBox>, placement: Placement,
+    ) -> Child {
+        Child {
+            view,
+            size: Vec2::zero(),
+            placement,
+            virgin: true,
+            transition: self.default_transition,
+            opening: Some(Instant::now()),
+            modal: true,
+            mouse_grab: false,
+        }
+    }
+
+    // Draws a layer, possibly in the middle of a transition.
+    //
+    // `shown` is how much of the layer should be visible, between 0 and 1.
+    fn draw_layer(
+        &self, printer: &Printer<'_, '_>, child: &Child, offset: Vec2,
+        shown: f32, focused: bool,
+    ) {
+        let transition = if shown < 1.0 {
+            child.transition
+        } else {
+            Transition::None
+        };
+
+        match transition {
+            Transition::None | Transition::Slide(Absolute::None) => {
+                child.view.draw(
+                    &printer
+                        .offset(offset)
+                        .cropped(child.size)
+                        .focused(focused),
+                );
+            }
+            Transition::Slide(side) => {
+                let size = child.size;
+                let hidden = 1.0 - shown;
+                let offset = offset.signed();
+                let shift =
+                    |distance: usize| (distance as f32 * hidden) as isize;
+
+                let offset = match side {
+                    Absolute::Left => offset
+                        .map_x(|x| x - shift(x as usize + size.x)),
+                    Absolute::Up => offset
+                        .map_y(|y| y - shift(y as usize + size.y)),
+                    Absolute::Right => offset.map_x(|x| {
+                        x + shift(printer.size.x.saturating_sub(x as usize))
+                    }),
+                    Absolute::Down => offset.map_y(|y| {
+                        y + shift(printer.size.y.saturating_sub(y as usize))
+                    }),
+                    Absolute::None => offset,
+                };
+
+                // Part of the layer may be off-screen to the top or left.
+                let visible =
+                    offset.map(|x| if x > 0 { x as usize } else { 0 });
+                let cut = offset.map(|x| if x < 0 { -x as usize } else { 0 });
+
+                child.view.draw(
+                    &printer
+                        .offset(visible)
+                        .cropped(size.saturating_sub(cut))
+                        .content_offset(cut)
+                        .inner_size(size)
+                        .focused(focused),
+                );
+            }
+            Transition::Fade if shown < 1.0 / 3.0 => (),
+            Transition::Fade if shown < 2.0 / 3.0 => {
+                let mut theme = printer.theme.clone();
+                let muted = theme.palette[PaletteColor::Secondary];
+                for &color in &[
+                    PaletteColor::Primary,
+                    PaletteColor::Tertiary,
+                    PaletteColor::TitlePrimary,
+                    PaletteColor::TitleSecondary,
+                ] {
+                    theme.palette[color] = muted;
+                }
+                theme.palette[PaletteColor::Highlight] =
+                    theme.palette[PaletteColor::HighlightInactive];
+                theme.shadow = false;
+
+                child.view.draw(
+                    &printer
+                        .theme(&theme)
+                        .offset(offset)
+                        .cropped(child.size)
+                        .focused(focused),
+                );
+            }
+            Transition::Fade => {
+                child.view.draw(
+                    &printer
+                        .offset(offset)
+                        .cropped(child.size)
+                        .focused(focused),
+                );
+            }
         }
     }
 
@@ -246,14 +570,13 @@ impl StackView {
         T: IntoBoxedView,
     {
         let boxed = ViewBox::boxed(view);
-        self.layers.push(Child {
-            view: ChildWrapper::Backfilled(Layer::new(
-                CircularFocus::wrap_tab(boxed),
-            )),
-            size: Vec2::zero(),
-            placement: Placement::Fullscreen,
-            virgin: true,
-        });
+        let child = self.new_child(
+            ChildWrapper::Backfilled(Layer::new(CircularFocus::wrap_tab(
+                boxed,
+            ))),
+            Placement::Fullscreen,
+        );
+        self.layers.push(child);
     }
 
     /// Adds new view on top of the stack in the center of the screen.
@@ -356,17 +679,16 @@ impl StackView {
         T: IntoBoxedView,
     {
         let boxed = ViewBox::boxed(view);
-        self.layers.push(Child {
+        let child = self.new_child(
             // Skip padding for absolute/parent-placed views
-            view: ChildWrapper::Shadow(
+            ChildWrapper::Shadow(
                 ShadowView::new(Layer::new(CircularFocus::wrap_tab(boxed)))
                     .top_padding(position.y == Offset::Center)
                     .left_padding(position.x == Offset::Center),
             ),
-            size: Vec2::new(0, 0),
-            placement: Placement::Floating(position),
-            virgin: true,
-        });
+            Placement::Floating(position),
+        );
+        self.layers.push(child);
     }
 
     /// Adds a transparent view on top of the stack in the center of the screen.
@@ -383,12 +705,74 @@ impl StackView {
         T: IntoBoxedView,
     {
         let boxed = ViewBox::boxed(view);
-        self.layers.push(Child {
-            view: ChildWrapper::Plain(CircularFocus::wrap_tab(boxed)),
-            size: Vec2::new(0, 0),
-            placement: Placement::Floating(position),
-            virgin: true,
-        });
+        let child = self.new_child(
+            ChildWrapper::Plain(CircularFocus::wrap_tab(boxed)),
+            Placement::Floating(position),
+        );
+        self.layers.push(child);
+    }
+
+    /// Adds a non-modal view on top of the stack in the center of the
+    /// screen.
+    ///
+    /// The view is drawn above other layers, but does not block them:
+    ///
+    /// * Mouse events outside of it are given to the layers below.
+    /// * Key events it ignores are given to the layers below.
+    ///
+    /// This is useful for palettes or inspector windows.
+    pub fn add_non_modal_layer<T>(&mut self, view: T)
+    where
+        T: IntoBoxedView,
+    {
+        self.add_non_modal_layer_at(Position::center(), view);
+    }
+
+    /// Adds a non-modal view on top of the stack at the given position.
+    ///
+    /// See `StackView::add_non_modal_layer`.
+    pub fn add_non_modal_layer_at<T>(&mut self, position: Position, view: T)
+    where
+        T: IntoBoxedView,
+    {
+        self.add_layer_at(position, view);
+        self.layers.last_mut().unwrap().modal = false;
+    }
+
+    /// Adds a non-modal view on top of the stack in the center of the
+    /// screen.
+    ///
+    /// Chainable variant.
+    pub fn non_modal_layer<T>(self, view: T) -> Self
+    where
+        T: IntoBoxedView,
+    {
+        self.with(|s| s.add_non_modal_layer(view))
+    }
+
+    /// Sets whether the given layer blocks events for the layers below.
+    ///
+    /// # Panics
+    ///
+    /// If `layer` is out of bounds.
+    pub fn set_layer_modal(&mut self, layer: LayerPosition, modal: bool) {
+        let i = self.get_index(layer).unwrap();
+        self.layers[i].modal = modal;
+    }
+
+    /// Returns `true` if the given layer blocks events for the layers below.
+    ///
+    /// # Panics
+    ///
+    /// If `layer` is out of bounds.
+    pub fn is_layer_modal(&self, layer: LayerPosition) -> bool {
+        let i = self.get_index(layer).unwrap();
+        self.layers[i].modal
+    }
+
+    // Returns the index of the lowest layer that can receive events.
+    fn active_floor(&self) -> usize {
+        self.layers.iter().rposition(|layer| layer.modal).unwrap_or(0)
     }
 
     /// Adds a view on top of the stack at the given position.
@@ -484,6 +868,67 @@ impl StackView {
         self.move_layer(layer, LayerPosition::FromBack(0));
     }
 
+    /// Moves a layer one step towards the front of the stack.
+    ///
+    /// Does nothing if the layer is already at the front.
+    ///
+    /// # Panics
+    ///
+    /// If `layer` is out of bounds.
+    pub fn raise_layer(&mut self, layer: LayerPosition) {
+        let i = self.get_index(layer).unwrap();
+        if i + 1 < self.layers.len() {
+            self.layers.swap(i, i + 1);
+        }
+    }
+
+    /// Moves a layer one step towards the back of the stack.
+    ///
+    /// Does nothing if the layer is already at the back.
+    ///
+    /// # Panics
+    ///
+    /// If `layer` is out of bounds.
+    pub fn lower_layer(&mut self, layer: LayerPosition) {
+        let i = self.get_index(layer).unwrap();
+        if i > 0 {
+            self.layers.swap(i, i - 1);
+        }
+    }
+
+    /// Swaps the elevation of two layers.
+    ///
+    /// # Panics
+    ///
+    /// If either `a` or `b` is out of bounds.
+    pub fn swap_layers(&mut self, a: LayerPosition, b: LayerPosition) {
+        let a = self.get_index(a).unwrap();
+        let b = self.get_index(b).unwrap();
+
+        self.layers.swap(a, b);
+    }
+
+    /// Adds a new layer directly below the given one.
+    ///
+    /// Use `LayerPosition::FromFront(0)` to insert a layer below the top one,
+    /// without stealing its focus.
+    ///
+    /// The new layer is centered, like with `add_layer`.
+    ///
+    /// # Panics
+    ///
+    /// If `layer` is out of bounds.
+    pub fn insert_layer_below<T>(&mut self, layer: LayerPosition, view: T)
+    where
+        T: IntoBoxedView,
+    {
+        let i = self.get_index(layer).unwrap();
+        self.add_layer(view);
+
+        let new_layer = self.layers.pop().unwrap();
+        self.layers.insert(i, new_layer);
+    }
+
     /// Moves a layer to a new position on the screen.
     ///
     /// # Panics
@@ -503,6 +948,11 @@ impl StackView {
         }
     }
 
+    /// Redraws the background on the next draw.
+    pub(crate) fn invalidate_background(&self) {
+        self.bg_dirty.set(true);
+    }
+
     /// Background drawing
     ///
     /// Drawing functions are split into forground and background to
@@ -530,17 +980,27 @@ impl StackView {
     ///
     /// You probably just want to call draw()
     pub fn draw_fg(&self, printer: &Printer<'_, '_>) {
-        let last = self.layers.len();
+        // Layers under the top one may receive mouse events, but only the
+        // top one has the focus.
+        let top = self.layers.len().saturating_sub(1);
         printer.with_color(ColorStyle::primary(), |printer| {
             for (i, (v, offset)) in
                 StackPositionIterator::new(self.layers.iter(), printer.size)
                     .enumerate()
             {
-                v.view.draw(
-                    &printer
-                        .offset(offset)
-                        .cropped(v.size)
-                        .focused(i + 1 == last),
+                let shown = self.opening_progress(v);
+                self.draw_layer(printer, v, offset, shown, i == top);
+            }
+
+            // Closing layers are still on top of everything else.
+            for closing in &self.closing {
+                let shown = 1.0 - self.progress(closing.start);
+                self.draw_layer(
+                    printer,
+                    &closing.child,
+                    closing.offset,
+                    shown,
+                    false,
                 );
             }
         });
@@ -603,29 +1063,100 @@ impl View for StackView {
     }
 
     fn on_event(&mut self, event: Event) -> EventResult {
-        if event == Event::WindowResize {
+        if let Event::WindowResize(_) = event {
             self.bg_dirty.set(true);
         }
-        // Use the stack position iterator to get the offset of the top layer.
+
+        // A new press may start a drag in another layer.
+        let mouse = match event {
+            Event::Mouse { event, .. } => Some(event),
+            _ => None,
+        };
+        if let Some(MouseEvent::Press(_)) = mouse {
+            for layer in &mut self.layers {
+                layer.mouse_grab = false;
+            }
+        }
+        // Use the stack position iterator to get the offset of each layer.
         // TODO: save it instead when drawing?
-        match StackPositionIterator::new(
+        let floor = self.active_floor();
+        let mut layers: Vec<_> = StackPositionIterator::new(
             self.layers.iter_mut(),
             self.last_size,
         )
-        .last()
-        {
-            None => EventResult::Ignored,
-            Some((v, offset)) => v.view.on_event(event.relativized(offset)),
+        .skip(floor)
+        .collect();
+
+        // Drags go to the layer where they started.
+        let grabbed = mouse.map(MouseEvent::is_drag).unwrap_or(false)
+            && layers.iter().any(|(v, _)| v.mouse_grab);
+
+        // Start from the top, and go down through non-modal layers.
+        for (v, offset) in layers.iter_mut().rev() {
+            if grabbed {
+                if !v.mouse_grab {
+                    continue;
+                }
+            } else if !v.modal {
+                if let Event::Mouse {
+                    offset: event_offset,
+                    position,
+                    ..
+                } = event
+                {
+                    let area = Rect::from_size(event_offset + *offset, v.size);
+                    if !area.contains(position) {
+                        continue;
+                    }
+                }
+            }
+
+            let result = v.view.on_event(event.relativized(*offset));
+            match mouse {
+                Some(MouseEvent::Press(_)) => {
+                    v.mouse_grab = result.is_consumed();
+                }
+                Some(MouseEvent::Release(_)) => v.mouse_grab = false,
+                _ => (),
+            }
+            let is_mouse = event.mouse_position().is_some();
+            if v.modal || result.is_consumed() || is_mouse {
+                return result;
+            }
         }
+
+        EventResult::Ignored
     }
 
     fn layout(&mut self, size: Vec2) {
+        let resized = size != self.last_size;
         self.last_size = size;
 
+        // Moving layers leave a trail behind them.
+        if self.is_animating() {
+            self.bg_dirty.set(true);
+        }
+
+        // Forget about finished transitions.
+        let duration = self.transition_duration;
+        self.closing.retain(|layer| layer.start.elapsed() < duration);
+        for layer in &mut self.layers {
+            if let Some(start) = layer.opening {
+                if start.elapsed() >= duration {
+                    layer.opening = None;
+                }
+            }
+        }
+
         // The call has been made, we can't ask for more space anymore.
         // Let's make do with what we have.
 
         for layer in &mut self.layers {
+            // Layers which did not change keep their layout.
+            if !resized && !layer.virgin && !layer.view.needs_relayout() {
+                continue;
+            }
+
             // Give each guy what he asks for, within the budget constraints.
             let size = Vec2::min(size, layer.view.required_size(size));
             layer.size = size;
@@ -642,6 +1173,21 @@ impl View for StackView {
         }
     }
 
+    fn needs_relayout(&self) -> bool {
+        // Transitions progress during the layout phase.
+        self.is_animating()
+            || self
+                .layers
+                .iter()
+                .any(|layer| layer.virgin || layer.view.needs_relayout())
+    }
+
+    fn mark_dirty(&mut self) {
+        for layer in &mut self.layers {
+            layer.view.mark_dirty();
+        }
+    }
+
     fn required_size(&mut self, size: Vec2) -> Vec2 {
         // The min size is the max of all children's
 
@@ -661,6 +1207,7 @@ impl View for StackView {
     fn call_on_any<'a>(
         &mut self, selector: &Selector<'_>, mut callback: AnyCb<'a>,
     ) {
+        selector.call_on_self(self, &mut callback);
         for layer in &mut self.layers {
             layer
                 .view
@@ -677,12 +1224,25 @@ impl View for StackView {
 
         Err(())
     }
+
+    fn locate(&self, selector: &Selector<'_>, _: Vec2) -> Option<Rect> {
+        // Top layers hide the ones below.
+        let layers: Vec<_> =
+            StackPositionIterator::new(self.layers.iter(), self.last_size)
+                .collect();
+        layers.into_iter().rev().find_map(|(layer, offset)| {
+            layer
+                .view
+                .locate(selector, layer.size)
+                .map(|area| area + offset)
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::views::TextView;
+    use crate::views::{Panel, TextView};
 
     #[test]
     fn pop_add() {
@@ -702,6 +1262,20 @@ mod tests {
         assert_eq!(text.get_content().source(), "1");
     }
 
+    #[test]
+    fn relayout_when_dirty() {
+        let mut stack = StackView::new().layer(Panel::new(TextView::new("1")));
+        let size = Vec2::new(10, 5);
+        assert!(stack.needs_layout(size));
+
+        stack.layout(size);
+        assert!(!stack.needs_layout(size));
+        assert!(stack.needs_layout(Vec2::new(12, 5)));
+
+        stack.mark_dirty();
+        assert!(stack.needs_layout(size));
+    }
+
     #[test]
     fn move_layer_works() {
         let mut stack = StackView::new()
@@ -749,6 +1323,154 @@ mod tests {
         assert!(stack.pop_layer().is_none());
     }
 
+    #[test]
+    fn non_modal_layers() {
+        use crate::views::{DummyView, OnEventView};
+
+        let mut stack = StackView::new()
+            .layer(OnEventView::new(DummyView).on_pre_event_inner('a', |_, _| {
+                Some(EventResult::Consumed(None))
+            }))
+            .non_modal_layer(DummyView);
+        assert!(!stack.is_layer_modal(LayerPosition::FromFront(0)));
+
+        // The key goes through the non-modal layer.
+        assert!(stack.on_event(Event::Char('a')).is_consumed());
+
+        stack.add_layer(DummyView);
+        assert!(!stack.on_event(Event::Char('a')).is_consumed());
+    }
+
+    #[test]
+    fn only_top_layer_focused() {
+        use crate::views::Canvas;
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        // Each layer records whether it was drawn with the focus.
+        let layer = |focused: &Rc<Cell<bool>>| {
+            Canvas::new(Rc::clone(focused))
+                .with_draw(|focused, printer| focused.set(printer.focused))
+                .with_required_size(|_, _| Vec2::new(2, 1))
+        };
+        let back = Rc::new(Cell::new(false));
+        let front = Rc::new(Cell::new(false));
+        let mut stack = StackView::new()
+            .layer(layer(&back))
+            .non_modal_layer(layer(&front));
+
+        let size = Vec2::new(10, 5);
+        stack.layout(size);
+        let backend = crate::backend::dummy::Backend::init();
+        let theme = crate::theme::load_default();
+        stack.draw_fg(&Printer::new(size, &theme, &*backend));
+        assert!(!back.get());
+        assert!(front.get());
+    }
+
+    #[test]
+    fn mouse_drag_follows_press() {
+        use crate::event::{EventTrigger, MouseButton};
+        use crate::views::OnEventView;
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let drags = Rc::new(Cell::new(0));
+        let view = OnEventView::new(TextView::new("x")).on_pre_event_inner(
+            EventTrigger::mouse(),
+            {
+                let drags = Rc::clone(&drags);
+                move |_, event| {
+                    if let Event::Mouse { event, .. } = *event {
+                        if event.is_drag() {
+                            drags.set(drags.get() + 1);
+                        }
+                    }
+                    Some(EventResult::Consumed(None))
+                }
+            },
+        );
+        let mut stack = StackView::new();
+        stack.add_non_modal_layer_at(Position::absolute((0, 0)), view);
+        stack.layout(Vec2::new(10, 10));
+
+        let mouse = |event, position| Event::Mouse {
+            offset: Vec2::zero(),
+            position,
+            event,
+        };
+
+        // Pressing outside the layer doesn't start a drag.
+        let outside = Vec2::new(5, 5);
+        stack.on_event(mouse(MouseEvent::Press(MouseButton::Left), outside));
+        stack.on_event(mouse(MouseEvent::Hold(MouseButton::Left), outside));
+        assert_eq!(drags.get(), 0);
+
+        // Once pressed, the layer gets the drag even outside of its area.
+        let inside = Vec2::zero();
+        stack.on_event(mouse(MouseEvent::Press(MouseButton::Left), inside));
+        stack.on_event(mouse(MouseEvent::Hold(MouseButton::Left), outside));
+        stack.on_event(mouse(MouseEvent::Release(MouseButton::Left), outside));
+        stack.on_event(mouse(MouseEvent::Hold(MouseButton::Left), outside));
+        assert_eq!(drags.get(), 2);
+    }
+
+    #[test]
+    fn raise_lower_swap() {
+        let mut stack = StackView::new()
+            .layer(TextView::new("1"))
+            .layer(TextView::new("2"))
+            .layer(TextView::new("3"));
+
+        // 1,2,3
+        stack.raise_layer(LayerPosition::FromBack(0));
+        // 2,1,3
+        stack.lower_layer(LayerPosition::FromFront(0));
+        // 2,3,1
+        stack.lower_layer(LayerPosition::FromBack(0));
+        stack.raise_layer(LayerPosition::FromFront(0));
+        // 2,3,1
+        stack.swap_layers(
+            LayerPosition::FromBack(0),
+            LayerPosition::FromBack(1),
+        );
+        // 3,2,1
+        stack.insert_layer_below(
+            LayerPosition::FromFront(0),
+            TextView::new("4"),
+        );
+        // 3,2,4,1
+
+        for &expected in &["1", "4", "2", "3"] {
+            let layer = stack.pop_layer().unwrap();
+            let text: Box<TextView> =
+                layer.as_boxed_any().downcast().unwrap();
+            assert_eq!(text.get_content().source(), expected);
+        }
+
+        assert!(stack.pop_layer().is_none());
+    }
+
+    #[test]
+    fn close_layer_with_transition() {
+        let mut stack = StackView::new()
+            .default_transition(Transition::Fade)
+            .layer(TextView::new("1"))
+            .layer(TextView::new("2"));
+
+        assert!(stack.is_animating());
+
+        // The layer is gone right away, but still animating.
+        assert!(stack.close_layer());
+        assert_eq!(stack.len(), 1);
+        assert!(stack.is_animating());
+
+        stack.set_transitions_enabled(false);
+        assert!(stack.close_layer());
+        assert!(stack.is_empty());
+        assert!(!stack.close_layer());
+    }
+
     #[test]
     fn get() {
         let mut stack = StackView::new()
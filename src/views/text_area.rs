@@ -1,21 +1,37 @@
+use crate::clipboard;
 use crate::direction::Direction;
 use crate::event::{Event, EventResult, Key, MouseButton, MouseEvent};
 use crate::rect::Rect;
-use crate::theme::{ColorStyle, Effect};
+use crate::state::ViewState;
+use crate::theme::{ColorStyle, Effect, Style};
+use crate::utils::compose::Composer;
 use crate::utils::lines::simple::{prefix, simple_prefix, LinesIterator, Row};
+use crate::utils::search::Pattern;
+use crate::utils::width::TextWidth;
 use crate::vec::Vec2;
 use crate::view::{ScrollBase, SizeCache, View};
-use crate::{Printer, With, XY};
+use crate::{Cursive, Printer, With, XY};
 use log::debug;
 use std::cmp::min;
+use std::mem;
+use std::ops::Range;
+use std::rc::Rc;
 use unicode_segmentation::UnicodeSegmentation;
-use unicode_width::UnicodeWidthStr;
 
 /// Multi-lines text editor.
 ///
 /// A `TextArea` will attempt to grow vertically and horizontally
 /// dependent on the content.  Wrap it in a `BoxView` to
 /// constrain its size.
+///
+/// Line numbers can be shown in a gutter on the left, with
+/// `TextArea::line_numbers`.
+///
+/// Edits can be undone with `Ctrl-Z`, and redone with `Ctrl-Y`.
+///
+/// Text can be selected with `Shift` and the arrow keys, with `Ctrl-A`, or
+/// by dragging the mouse. A read-only `TextArea` still allows this, so it
+/// can be used as a viewer.
 pub struct TextArea {
     // TODO: use a smarter data structure (rope?)
     content: String,
@@ -28,6 +44,20 @@ pub struct TextArea {
     /// When `false`, we don't take any input.
     enabled: bool,
 
+    /// When `true`, the cursor can move but the content cannot be edited.
+    read_only: bool,
+
+    /// Other end of the selection, the cursor being one end.
+    selection_anchor: Option<usize>,
+
+    selection_style: ColorStyle,
+
+    /// Called with the selected text on copy.
+    on_copy: Option<Rc<clipboard::OnCopy>>,
+
+    /// Copies the selection.
+    copy_event: Event,
+
     /// Base for scrolling features
     scrollbase: ScrollBase,
 
@@ -37,6 +67,74 @@ pub struct TextArea {
 
     /// Byte offset of the currently selected grapheme.
     cursor: usize,
+
+    /// When `false`, long lines are not wrapped but scroll horizontally.
+    wrap: bool,
+
+    /// First visible column, when lines are not wrapped.
+    h_offset: usize,
+
+    /// Text being composed at the cursor, not yet part of the content.
+    composer: Composer,
+
+    /// How to number lines in the gutter, if at all.
+    line_numbers: Option<LineNumbers>,
+
+    /// Width of the gutter, including a separating space.
+    gutter: usize,
+
+    gutter_style: ColorStyle,
+
+    /// Style for the number of the line containing the cursor.
+    current_line_style: ColorStyle,
+
+    /// Edits that can be undone, the most recent last.
+    undo_stack: Vec<Edit>,
+
+    /// Undone edits that can be redone, the most recent last.
+    redo_stack: Vec<Edit>,
+
+    /// Pattern to highlight in the content.
+    search: Option<Pattern>,
+
+    /// Byte ranges of the content matching `search`.
+    matches: Vec<Range<usize>>,
+
+    search_style: ColorStyle,
+
+    /// Returns `false` for misspelled words.
+    spell_checker: Option<Rc<SpellChecker>>,
+
+    /// Byte ranges of the words rejected by `spell_checker`.
+    misspellings: Vec<Range<usize>>,
+
+    spelling_style: Style,
+}
+
+type SpellChecker = dyn Fn(&str) -> bool;
+
+// A change to the content, which can be undone.
+#[derive(Clone, Debug)]
+struct Edit {
+    // Byte offset of the change.
+    position: usize,
+    // Text removed at `position`.
+    removed: String,
+    // Text inserted at `position`, in place of `removed`.
+    inserted: String,
+    // Cursor position before the change.
+    cursor: usize,
+}
+
+/// How lines are numbered in the gutter of a `TextArea`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineNumbers {
+    /// Each line shows its own number, starting at 1.
+    Absolute,
+    /// Each line shows its distance to the line containing the cursor.
+    ///
+    /// The current line still shows its own number.
+    Relative,
 }
 
 fn make_rows(text: &str, width: usize) -> Vec<Row> {
@@ -45,6 +143,17 @@ fn make_rows(text: &str, width: usize) -> Vec<Row> {
     LinesIterator::new(text, width).show_spaces().collect()
 }
 
+// Returns the byte ranges of the words of `text` rejected by `checker`,
+// shifted by `offset`.
+fn misspellings(
+    checker: &SpellChecker, text: &str, offset: usize,
+) -> Vec<Range<usize>> {
+    text.unicode_word_indices()
+        .filter(|&(_, word)| !checker(word))
+        .map(|(start, word)| offset + start..offset + start + word.len())
+        .collect()
+}
+
 new_default!(TextArea);
 
 impl TextArea {
@@ -54,10 +163,163 @@ impl TextArea {
             content: String::new(),
             rows: Vec::new(),
             enabled: true,
+            read_only: false,
+            selection_anchor: None,
+            selection_style: ColorStyle::highlight(),
+            on_copy: None,
+            copy_event: clipboard::DEFAULT_COPY_EVENT,
             scrollbase: ScrollBase::new().right_padding(0),
             size_cache: None,
             last_size: Vec2::zero(),
             cursor: 0,
+            wrap: true,
+            h_offset: 0,
+            composer: Composer::new(),
+            line_numbers: None,
+            gutter: 0,
+            gutter_style: ColorStyle::secondary(),
+            current_line_style: ColorStyle::highlight(),
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            search: None,
+            matches: Vec::new(),
+            search_style: ColorStyle::highlight_inactive(),
+            spell_checker: None,
+            misspellings: Vec::new(),
+            spelling_style: Style::from(Effect::Underline),
+        }
+    }
+
+    /// Disables content wrap for this view.
+    ///
+    /// Long lines will then scroll horizontally to follow the cursor.
+    ///
+    /// Chainable variant.
+    pub fn no_wrap(self) -> Self {
+        self.with(|s| s.set_content_wrap(false))
+    }
+
+    /// Controls content wrap for this view.
+    ///
+    /// If `true` (the default), long lines will wrap when needed.
+    /// Otherwise, they will scroll horizontally to follow the cursor.
+    pub fn set_content_wrap(&mut self, wrap: bool) {
+        self.wrap = wrap;
+        self.h_offset = 0;
+
+        if let Some(size) = self.size_cache.map(|s| s.map(|s| s.value)) {
+            self.invalidate();
+            self.compute_rows(size);
+        }
+    }
+
+    /// Returns `true` if long lines are wrapped.
+    pub fn get_content_wrap(&self) -> bool {
+        self.wrap
+    }
+
+    /// Shows line numbers in a gutter on the left.
+    ///
+    /// Give `None` to hide the gutter (the default).
+    ///
+    /// Wrapped lines only show their number on their first row.
+    pub fn set_line_numbers(&mut self, line_numbers: Option<LineNumbers>) {
+        self.line_numbers = line_numbers;
+
+        if let Some(size) = self.size_cache.map(|s| s.map(|s| s.value)) {
+            self.invalidate();
+            self.compute_rows(size);
+        }
+    }
+
+    /// Shows line numbers in a gutter on the left.
+    ///
+    /// Chainable variant.
+    pub fn line_numbers(self, line_numbers: LineNumbers) -> Self {
+        self.with(|s| s.set_line_numbers(Some(line_numbers)))
+    }
+
+    /// Returns how lines are numbered, if at all.
+    pub fn get_line_numbers(&self) -> Option<LineNumbers> {
+        self.line_numbers
+    }
+
+    /// Sets the style used for line numbers.
+    ///
+    /// Defaults to `ColorStyle::secondary()`.
+    pub fn set_gutter_style(&mut self, style: ColorStyle) {
+        self.gutter_style = style;
+    }
+
+    /// Sets the style used for line numbers.
+    ///
+    /// Chainable variant.
+    pub fn gutter_style(self, style: ColorStyle) -> Self {
+        self.with(|s| s.set_gutter_style(style))
+    }
+
+    /// Sets the style used for the number of the line with the cursor.
+    ///
+    /// Defaults to `ColorStyle::highlight()`.
+    pub fn set_current_line_style(&mut self, style: ColorStyle) {
+        self.current_line_style = style;
+    }
+
+    /// Sets the style used for the number of the line with the cursor.
+    ///
+    /// Chainable variant.
+    pub fn current_line_style(self, style: ColorStyle) -> Self {
+        self.with(|s| s.set_current_line_style(style))
+    }
+
+    // Returns the gutter width needed for the current content.
+    fn compute_gutter(&self) -> usize {
+        if self.line_numbers.is_none() {
+            return 0;
+        }
+
+        let lines = 1 + self.content.matches('\n').count();
+        lines.to_string().len() + 1
+    }
+
+    // Returns the width to use when splitting rows.
+    fn wrap_width(&self, available: usize) -> usize {
+        if self.wrap {
+            available.saturating_sub(self.gutter)
+        } else {
+            usize::MAX
+        }
+    }
+
+    // Returns the width available for the text, excluding the scrollbar.
+    fn text_width(&self) -> usize {
+        let width = if self.scrollbase.scrollable() {
+            self.last_size.x.saturating_sub(1)
+        } else {
+            self.last_size.x
+        };
+        width.saturating_sub(self.gutter)
+    }
+
+    // Scrolls horizontally so the cursor stays visible.
+    fn fix_h_offset(&mut self) {
+        if self.wrap {
+            self.h_offset = 0;
+            return;
+        }
+
+        // We don't know our size yet; `layout` will call us again.
+        let width = self.text_width();
+        if width == 0 {
+            return;
+        }
+
+        let col = self.selected_col();
+
+        if col < self.h_offset {
+            self.h_offset = col;
+        } else if col >= self.h_offset + width {
+            self.h_offset = col + 1 - width;
         }
     }
 
@@ -86,11 +348,17 @@ impl TextArea {
 
         let focus = self.selected_row();
         self.scrollbase.scroll_to(focus);
+        self.fix_h_offset();
     }
 
     /// Sets the content of the view.
+    ///
+    /// This clears the undo history.
     pub fn set_content<S: Into<String>>(&mut self, content: S) {
         self.content = content.into();
+        self.clear_history();
+        self.clear_selection();
+        self.update_matches();
 
         // First, make sure we are within the bounds.
         self.cursor = min(self.cursor, self.content.len());
@@ -145,6 +413,614 @@ impl TextArea {
         self.enabled
     }
 
+    /// Makes this view read-only, or editable again.
+    ///
+    /// A read-only view can still be focused: the cursor can move, and text
+    /// can be selected and copied. Only the user input is rejected; the
+    /// content can still be changed with `set_content`.
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+        self.composer.clear();
+    }
+
+    /// Makes this view read-only.
+    ///
+    /// Chainable variant.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::views::TextArea;
+    /// let viewer = TextArea::new()
+    ///     .content("Some text to look at.")
+    ///     .read_only()
+    ///     .on_copy(|s, text| s.set_user_data(text.to_string()));
+    /// ```
+    pub fn read_only(self) -> Self {
+        self.with(|s| s.set_read_only(true))
+    }
+
+    /// Returns `true` if this view is read-only.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Returns the byte range of the selected text, if any.
+    pub fn get_selection(&self) -> Option<Range<usize>> {
+        let anchor = self.selection_anchor?;
+        let range = if anchor < self.cursor {
+            anchor..self.cursor
+        } else {
+            self.cursor..anchor
+        };
+
+        if range.start == range.end {
+            None
+        } else {
+            Some(range)
+        }
+    }
+
+    /// Returns the selected text, if any.
+    pub fn get_selected_text(&self) -> Option<&str> {
+        self.get_selection().map(|range| &self.content[range])
+    }
+
+    /// Selects the text in `range`.
+    ///
+    /// The cursor is moved to the end of the range.
+    ///
+    /// # Panics
+    ///
+    /// If `range` is out of bounds, or does not fall on char boundaries.
+    pub fn set_selection(&mut self, range: Range<usize>) {
+        assert!(self.content.is_char_boundary(range.start));
+        assert!(self.content.is_char_boundary(range.end));
+
+        self.selection_anchor = Some(range.start);
+        self.move_to(range.end);
+    }
+
+    /// Selects the entire content.
+    pub fn select_all(&mut self) {
+        self.set_selection(0..self.content.len());
+    }
+
+    /// Unselects the text.
+    pub fn clear_selection(&mut self) {
+        self.selection_anchor = None;
+    }
+
+    /// Sets the style used for the selected text.
+    ///
+    /// Defaults to `ColorStyle::highlight()`.
+    pub fn set_selection_style(&mut self, style: ColorStyle) {
+        self.selection_style = style;
+    }
+
+    /// Sets the style used for the selected text.
+    ///
+    /// Chainable variant.
+    pub fn selection_style(self, style: ColorStyle) -> Self {
+        self.with(|s| s.set_selection_style(style))
+    }
+
+    /// Sets the event copying the selected text.
+    ///
+    /// Defaults to [`clipboard::DEFAULT_COPY_EVENT`], `Alt-W`, like
+    /// `TextView`. `Ctrl-C` only reaches the views if an event filter turns
+    /// `Event::Exit` back into it (see `Cursive::add_event_filter`).
+    ///
+    /// [`clipboard::DEFAULT_COPY_EVENT`]:
+    /// ../clipboard/constant.DEFAULT_COPY_EVENT.html
+    pub fn set_copy_event<E: Into<Event>>(&mut self, event: E) {
+        self.copy_event = event.into();
+    }
+
+    /// Sets the event copying the selected text.
+    ///
+    /// Chainable variant.
+    pub fn copy_event<E: Into<Event>>(self, event: E) -> Self {
+        self.with(|s| s.set_copy_event(event))
+    }
+
+    /// Returns the event copying the selected text.
+    pub fn get_copy_event(&self) -> &Event {
+        &self.copy_event
+    }
+
+    /// Sets a callback to be called with the selected text on copy.
+    ///
+    /// By default, the selection is copied to the clipboard (see
+    /// `Cursive::clipboard`).
+    pub fn set_on_copy<F>(&mut self, callback: F)
+    where
+        F: Fn(&mut Cursive, &str) + 'static,
+    {
+        self.on_copy = Some(Rc::new(callback));
+    }
+
+    /// Sets a callback to be called with the selected text on copy.
+    ///
+    /// Chainable variant.
+    pub fn on_copy<F>(self, callback: F) -> Self
+    where
+        F: Fn(&mut Cursive, &str) + 'static,
+    {
+        self.with(|s| s.set_on_copy(callback))
+    }
+
+    /// Enables or disables dead key composition.
+    ///
+    /// When enabled, typing an accent like `^` or `´` starts a composition,
+    /// completed by the next character: `^` then `e` inserts `ê`.
+    ///
+    /// This is only needed when the terminal doesn't compose characters
+    /// itself. Defaults to `false`.
+    pub fn set_dead_keys(&mut self, enabled: bool) {
+        self.composer.set_dead_keys(enabled);
+    }
+
+    /// Enables dead key composition.
+    ///
+    /// Chainable variant.
+    pub fn dead_keys(self) -> Self {
+        self.with(|s| s.set_dead_keys(true))
+    }
+
+    /// Sets the text being composed at the cursor.
+    ///
+    /// It is shown underlined, but is not part of the content until it is
+    /// committed, either with `commit_composition` or by typing another
+    /// character. Backspace or Escape drops it.
+    ///
+    /// This can be used to integrate an input method. The characters it
+    /// then sends to commit the text, even one event at a time, replace
+    /// the composition instead of being inserted after it.
+    pub fn set_composition<S: Into<String>>(&mut self, text: S) {
+        self.composer.set_text(text);
+    }
+
+    /// Returns the text being composed at the cursor.
+    ///
+    /// This is empty when no composition is going on.
+    pub fn get_composition(&self) -> &str {
+        self.composer.text()
+    }
+
+    /// Inserts the text being composed at the cursor position.
+    pub fn commit_composition(&mut self) {
+        let text = self.composer.take();
+        self.insert_str(&text);
+    }
+
+    /// Drops the text being composed.
+    pub fn clear_composition(&mut self) {
+        self.composer.clear();
+    }
+
+    /// Inserts `text` at the cursor position.
+    pub fn insert_str(&mut self, text: &str) {
+        for ch in text.chars() {
+            self.insert(ch);
+        }
+        self.fix_h_offset();
+    }
+
+    /// Undoes the last edit.
+    ///
+    /// Consecutive typed characters are undone together.
+    ///
+    /// Returns `false` if there was nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        let edit = match self.undo_stack.pop() {
+            Some(edit) => edit,
+            None => return false,
+        };
+
+        let end = edit.position + edit.inserted.len();
+        self.splice(edit.position..end, &edit.removed, edit.cursor);
+        self.redo_stack.push(edit);
+        true
+    }
+
+    /// Redoes the last undone edit.
+    ///
+    /// Returns `false` if there was nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        let edit = match self.redo_stack.pop() {
+            Some(edit) => edit,
+            None => return false,
+        };
+
+        let end = edit.position + edit.removed.len();
+        let cursor = edit.position + edit.inserted.len();
+        self.splice(edit.position..end, &edit.inserted, cursor);
+        self.undo_stack.push(edit);
+        true
+    }
+
+    /// Returns `true` if some edit can be undone.
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    /// Returns `true` if some undone edit can be redone.
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    /// Forgets all edits, so they cannot be undone anymore.
+    pub fn clear_history(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+
+    /// Replaces the text in `range` with `text`, as a single edit.
+    ///
+    /// The cursor is moved to the end of the inserted text.
+    ///
+    /// # Panics
+    ///
+    /// If `range` is out of bounds, or does not fall on char boundaries.
+    pub fn replace_range(&mut self, range: Range<usize>, text: &str) {
+        let edit = Edit {
+            position: range.start,
+            removed: self.content[range.clone()].to_string(),
+            inserted: text.to_string(),
+            cursor: self.cursor,
+        };
+        self.splice(range, text, edit.position + text.len());
+        self.push_edit(edit);
+    }
+
+    /// Highlights the parts of the content matching `pattern`.
+    ///
+    /// Use `None` to stop highlighting.
+    pub fn set_search(&mut self, pattern: Option<Pattern>) {
+        self.search = pattern;
+        self.update_matches();
+    }
+
+    /// Highlights the parts of the content matching `pattern`.
+    ///
+    /// Chainable variant.
+    pub fn search(self, pattern: Pattern) -> Self {
+        self.with(|s| s.set_search(Some(pattern)))
+    }
+
+    /// Returns the pattern currently highlighted, if any.
+    pub fn get_search(&self) -> Option<&Pattern> {
+        self.search.as_ref()
+    }
+
+    /// Returns the byte ranges of the content matching the search pattern.
+    pub fn matches(&self) -> &[Range<usize>] {
+        &self.matches
+    }
+
+    /// Sets the style used to highlight matches.
+    ///
+    /// The match at the cursor is drawn with the focused highlight style.
+    ///
+    /// Defaults to `ColorStyle::highlight_inactive()`.
+    pub fn set_search_style(&mut self, style: ColorStyle) {
+        self.search_style = style;
+    }
+
+    /// Sets the style used to highlight matches.
+    ///
+    /// Chainable variant.
+    pub fn search_style(self, style: ColorStyle) -> Self {
+        self.with(|s| s.set_search_style(style))
+    }
+
+    /// Sets a spell checker, called for each word of the content.
+    ///
+    /// It should return `true` if the word is spelled correctly. Words it
+    /// rejects are drawn with the spelling style.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::views::TextArea;
+    /// let dictionary = ["hello", "world"];
+    /// let mut area = TextArea::new().content("hello wrld");
+    /// area.set_spell_checker(move |word| {
+    ///     dictionary.contains(&word.to_lowercase().as_str())
+    /// });
+    /// assert_eq!(area.misspellings(), &[6..10][..]);
+    /// ```
+    pub fn set_spell_checker<F>(&mut self, checker: F)
+    where
+        F: Fn(&str) -> bool + 'static,
+    {
+        self.spell_checker = Some(Rc::new(checker));
+        self.update_matches();
+    }
+
+    /// Sets a spell checker, called for each word of the content.
+    ///
+    /// Chainable variant.
+    pub fn spell_checker<F>(self, checker: F) -> Self
+    where
+        F: Fn(&str) -> bool + 'static,
+    {
+        self.with(|s| s.set_spell_checker(checker))
+    }
+
+    /// Removes the spell checker.
+    pub fn clear_spell_checker(&mut self) {
+        self.spell_checker = None;
+        self.update_matches();
+    }
+
+    /// Returns the byte ranges of the misspelled words.
+    pub fn misspellings(&self) -> &[Range<usize>] {
+        &self.misspellings
+    }
+
+    /// Sets the style added to misspelled words.
+    ///
+    /// Defaults to underlined text.
+    pub fn set_spelling_style<S: Into<Style>>(&mut self, style: S) {
+        self.spelling_style = style.into();
+    }
+
+    /// Sets the style added to misspelled words.
+    ///
+    /// Chainable variant.
+    pub fn spelling_style<S: Into<Style>>(self, style: S) -> Self {
+        self.with(|s| s.set_spelling_style(style))
+    }
+
+    /// Moves the cursor to the next match, wrapping around the end.
+    ///
+    /// Returns `false` if nothing matches.
+    pub fn find_next(&mut self) -> bool {
+        let cursor = self.cursor;
+        let next = self
+            .matches
+            .iter()
+            .find(|m| m.start > cursor)
+            .or_else(|| self.matches.first())
+            .map(|m| m.start);
+
+        match next {
+            Some(start) => {
+                self.move_to(start);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Moves the cursor to the previous match, wrapping around the start.
+    ///
+    /// Returns `false` if nothing matches.
+    pub fn find_previous(&mut self) -> bool {
+        let cursor = self.cursor;
+        let previous = self
+            .matches
+            .iter()
+            .rev()
+            .find(|m| m.start < cursor)
+            .or_else(|| self.matches.last())
+            .map(|m| m.start);
+
+        match previous {
+            Some(start) => {
+                self.move_to(start);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Replaces the match at the cursor, and moves to the next one.
+    ///
+    /// If the cursor is not at the start of a match, it only moves to the
+    /// next one, so it can be checked before being replaced.
+    ///
+    /// Returns `true` if a match was replaced.
+    pub fn replace_next(&mut self, replacement: &str) -> bool {
+        let cursor = self.cursor;
+        let current = self.matches.iter().find(|m| m.start == cursor).cloned();
+
+        let replaced = match current {
+            Some(range) => {
+                self.replace_range(range, replacement);
+                true
+            }
+            None => false,
+        };
+
+        self.find_next();
+        replaced
+    }
+
+    /// Replaces every match, as a single edit.
+    ///
+    /// Returns the number of replaced matches.
+    pub fn replace_all(&mut self, replacement: &str) -> usize {
+        let (start, end) = match (self.matches.first(), self.matches.last()) {
+            (Some(first), Some(last)) => (first.start, last.end),
+            _ => return 0,
+        };
+
+        let mut text = String::new();
+        let mut last = start;
+        for range in &self.matches {
+            text.push_str(&self.content[last..range.start]);
+            text.push_str(replacement);
+            last = range.end;
+        }
+
+        let count = self.matches.len();
+        self.replace_range(start..end, &text);
+        count
+    }
+
+    // Records an edit, merging it with the previous one when possible.
+    fn push_edit(&mut self, edit: Edit) {
+        self.redo_stack.clear();
+
+        if let Some(last) = self.undo_stack.last_mut() {
+            if last.merge(&edit) {
+                return;
+            }
+        }
+
+        self.undo_stack.push(edit);
+    }
+
+    // Inserts pasted text, replacing the selection.
+    //
+    // The whole paste, selection included, is undone at once.
+    fn paste(&mut self, text: &str) {
+        let range = self
+            .get_selection()
+            .unwrap_or(self.cursor..self.cursor);
+        self.clear_selection();
+
+        // Terminals usually send new lines as carriage returns.
+        let text = text.replace("\r\n", "\n").replace('\r', "\n");
+        let edit = Edit {
+            position: range.start,
+            removed: self.content[range.clone()].to_string(),
+            inserted: text.clone(),
+            cursor: self.cursor,
+        };
+        self.splice(range, &text, edit.position + text.len());
+
+        // Never merged with the surrounding typing.
+        self.redo_stack.clear();
+        self.undo_stack.push(edit);
+    }
+
+    // Replaces text without recording an edit, and moves the cursor.
+    //
+    // Rows are computed again, since the change can be anywhere.
+    fn splice(&mut self, range: Range<usize>, text: &str, cursor: usize) {
+        let (start, removed) = (range.start, range.len());
+        self.content.replace_range(range, text);
+        self.clear_selection();
+        self.update_edited_matches(start, removed, text.len());
+
+        if let Some(size) = self.size_cache.map(|s| s.map(|s| s.value)) {
+            self.invalidate();
+            self.compute_rows(size);
+        }
+        self.move_to(cursor);
+    }
+
+    // Moves the cursor, scrolling to it if the rows are known.
+    fn move_to(&mut self, cursor: usize) {
+        if self.rows.is_empty() {
+            self.cursor = cursor;
+        } else {
+            self.set_cursor(cursor);
+        }
+    }
+
+    // Removes the selected text, if any.
+    fn delete_selection(&mut self) {
+        if let Some(range) = self.get_selection() {
+            self.replace_range(range, "");
+        }
+        self.clear_selection();
+    }
+
+    // Returns the content offset at the given position (relative to the
+    // view).
+    fn offset_at(&self, position: Vec2) -> usize {
+        let y = position.y + self.scrollbase.start_line;
+        let y = min(y, self.rows.len() - 1);
+        let x = position.x.saturating_sub(self.gutter) + self.h_offset;
+        let row = &self.rows[y];
+        let content = &self.content[row.start..row.end];
+
+        row.start + simple_prefix(content, x).length
+    }
+
+    // Finds the search matches and the misspelled words again.
+    fn update_matches(&mut self) {
+        self.update_search();
+        self.misspellings = match self.spell_checker {
+            Some(ref checker) => misspellings(&**checker, &self.content, 0),
+            None => Vec::new(),
+        };
+    }
+
+    fn update_search(&mut self) {
+        self.matches = match self.search {
+            Some(ref pattern) => pattern.find_all(&self.content),
+            None => Vec::new(),
+        };
+    }
+
+    // Updates the matches after `removed` bytes at `start` were replaced
+    // by `inserted` bytes.
+    //
+    // Search patterns can match anywhere, so the content is searched again.
+    // Words never span lines: only the edited lines are checked again.
+    fn update_edited_matches(
+        &mut self, start: usize, removed: usize, inserted: usize,
+    ) {
+        self.update_search();
+        let checker = match self.spell_checker {
+            Some(ref checker) => Rc::clone(checker),
+            None => return,
+        };
+
+        let end = start + inserted;
+        let line_start =
+            self.content[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = self.content[end..]
+            .find('\n')
+            .map_or(self.content.len(), |i| end + i);
+        // Where the edited lines ended before the edit.
+        let old_line_end = line_end - inserted + removed;
+
+        let old = mem::take(&mut self.misspellings);
+        let (before, rest): (Vec<_>, Vec<_>) =
+            old.into_iter().partition(|range| range.start < line_start);
+        let shift = |i: usize| i - removed + inserted;
+        let after = rest
+            .into_iter()
+            .filter(|range| range.start >= old_line_end)
+            .map(|range| shift(range.start)..shift(range.end));
+
+        self.misspellings = before;
+        self.misspellings.extend(misspellings(
+            &*checker,
+            &self.content[line_start..line_end],
+            line_start,
+        ));
+        self.misspellings.extend(after);
+    }
+
+    // For each row, returns the index of the line it starts, if any.
+    //
+    // Rows continuing a wrapped line get `None`.
+    fn row_line_numbers(&self) -> Vec<Option<usize>> {
+        let mut line = 0;
+        self.rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                if i == 0 {
+                    Some(0)
+                } else if self.content[..row.start].ends_with('\n') {
+                    line += 1;
+                    Some(line)
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
     /// Finds the row containing the grapheme at the given offset
     fn row_at(&self, offset: usize) -> usize {
         debug!("Offset: {}", offset);
@@ -231,7 +1107,7 @@ impl TextArea {
             }
 
             let text = &self.content[self.rows[row].start..self.cursor];
-            text.graphemes(true).last().unwrap().len()
+            text.graphemes(true).next_back().unwrap().len()
         };
         self.cursor -= len;
     }
@@ -280,15 +1156,16 @@ impl TextArea {
         }
         debug!("Computing! Oh yeah!");
 
+        self.gutter = self.compute_gutter();
         let mut available = size.x;
 
-        self.rows = make_rows(&self.content, available);
+        self.rows = make_rows(&self.content, self.wrap_width(available));
         self.fix_ghost_row();
 
-        if self.rows.len() > size.y {
+        if self.wrap && self.rows.len() > size.y {
             available = available.saturating_sub(1);
             // Apparently we'll need a scrollbar. Doh :(
-            self.rows = make_rows(&self.content, available);
+            self.rows = make_rows(&self.content, self.wrap_width(available));
             self.fix_ghost_row();
         }
 
@@ -323,8 +1200,14 @@ impl TextArea {
         let end = self.cursor + len;
         debug!("Start/end: {}/{}", start, end);
         debug!("Content: `{}`", self.content);
-        for _ in self.content.drain(start..end) {}
+        let removed: String = self.content.drain(start..end).collect();
         debug!("Content: `{}`", self.content);
+        self.push_edit(Edit {
+            position: start,
+            removed,
+            inserted: String::new(),
+            cursor: self.cursor,
+        });
 
         let selected_row = self.selected_row();
         debug!("Selected row: {}", selected_row);
@@ -343,6 +1226,7 @@ impl TextArea {
         }
         debug!("Rows: {:?}", self.rows);
 
+        self.update_edited_matches(start, len, 0);
         self.fix_damages();
         debug!("Rows: {:?}", self.rows);
     }
@@ -351,6 +1235,12 @@ impl TextArea {
         // First, we inject the data, but keep the cursor unmoved
         // (So the cursor is to the left of the injected char)
         self.content.insert(self.cursor, ch);
+        self.push_edit(Edit {
+            position: self.cursor,
+            removed: String::new(),
+            inserted: ch.to_string(),
+            cursor: self.cursor,
+        });
 
         // Then, we shift the indexes of every row after this one.
         let shift = ch.len_utf8();
@@ -363,6 +1253,7 @@ impl TextArea {
             row.shift(shift);
         }
         self.cursor += shift;
+        self.update_edited_matches(self.cursor - shift, 0, shift);
 
         // Finally, rows may not have the correct width anymore, so fix them.
         self.fix_damages();
@@ -380,6 +1271,13 @@ impl TextArea {
 
         let size = self.size_cache.unwrap().map(|s| s.value);
 
+        // A wider gutter changes every row.
+        if self.compute_gutter() != self.gutter {
+            self.invalidate();
+            self.compute_rows(size);
+            return;
+        }
+
         // Find affected text.
         // We know the damage started at this row, so it'll need to go.
         //
@@ -398,7 +1296,7 @@ impl TextArea {
             .map(|i| 1 + i + self.cursor);
         let last_row = last_byte
             .map_or(self.rows.len(), |last_byte| self.row_at(last_byte));
-        let last_byte = last_byte.unwrap_or_else(|| self.content.len());
+        let last_byte = last_byte.unwrap_or(self.content.len());
 
         debug!("Content: `{}` (len={})", self.content, self.content.len());
         debug!("start/end: {}/{}", first_byte, last_byte);
@@ -415,6 +1313,7 @@ impl TextArea {
 
         // First attempt, if scrollbase status didn't change.
         debug!("Rows: {:?}", self.rows);
+        let available = self.wrap_width(available);
         let new_rows =
             make_rows(&self.content[first_byte..last_byte], available);
         // How much did this add?
@@ -442,6 +1341,40 @@ impl TextArea {
     }
 }
 
+impl Edit {
+    // Tries to extend this edit with the next one.
+    //
+    // Typed characters are merged until a new line starts, as are
+    // consecutive deletions.
+    fn merge(&mut self, next: &Edit) -> bool {
+        let typing = self.removed.is_empty()
+            && next.removed.is_empty()
+            && !self.inserted.ends_with('\n')
+            && next.position == self.position + self.inserted.len();
+        if typing {
+            self.inserted.push_str(&next.inserted);
+            return true;
+        }
+
+        if !self.inserted.is_empty() || !next.inserted.is_empty() {
+            return false;
+        }
+
+        if next.position == self.position {
+            // Delete
+            self.removed.push_str(&next.removed);
+            true
+        } else if next.position + next.removed.len() == self.position {
+            // Backspace
+            self.removed.insert_str(0, &next.removed);
+            self.position = next.position;
+            true
+        } else {
+            false
+        }
+    }
+}
+
 impl View for TextArea {
     fn required_size(&mut self, constraint: Vec2) -> Vec2 {
         // Make sure our structure is up to date
@@ -454,6 +1387,7 @@ impl View for TextArea {
         let scroll_width = if self.rows.len() > constraint.y { 1 } else { 0 };
         Vec2::new(
             scroll_width
+                + self.gutter
                 + 1
                 + self.rows.iter().map(|r| r.width).max().unwrap_or(1),
             self.rows.len(),
@@ -473,25 +1407,110 @@ impl View for TextArea {
             } else {
                 printer.size.x
             };
+            let gutter = self.gutter;
             printer.with_effect(effect, |printer| {
                 for y in 0..printer.size.y {
-                    printer.print_hline((0, y), w, " ");
+                    printer.print_hline(
+                        (gutter, y),
+                        w.saturating_sub(gutter),
+                        " ",
+                    );
                 }
             });
 
+            let line_numbers = self.row_line_numbers();
+            let current_line = line_numbers[..=self.selected_row()]
+                .iter()
+                .rev()
+                .find_map(|&line| line)
+                .unwrap_or(0);
+
             debug!("Content: `{}`", &self.content);
             self.scrollbase.draw(printer, |printer, i| {
                 debug!("Drawing row {}", i);
+                if let (Some(mode), Some(line)) =
+                    (self.line_numbers, line_numbers[i])
+                {
+                    let (number, style) = if line == current_line {
+                        (line + 1, self.current_line_style)
+                    } else if mode == LineNumbers::Relative {
+                        (line.abs_diff(current_line), self.gutter_style)
+                    } else {
+                        (line + 1, self.gutter_style)
+                    };
+                    let number = format!("{:>1$}", number, gutter - 1);
+                    printer.with_color(style, |printer| {
+                        printer.print((0, 0), &number);
+                    });
+                }
+
+                let printer = &printer
+                    .offset((gutter, 0))
+                    .content_offset((self.h_offset, 0));
                 let row = &self.rows[i];
                 debug!("row: {:?}", row);
                 let text = &self.content[row.start..row.end];
                 debug!("row text: `{}`", text);
                 printer.with_effect(effect, |printer| {
                     printer.print((0, 0), text);
+
+                    for range in &self.misspellings {
+                        if range.end <= row.start || range.start >= row.end {
+                            continue;
+                        }
+                        let start = range.start.max(row.start) - row.start;
+                        let end = range.end.min(row.end) - row.start;
+                        let style = self.spelling_style;
+                        printer.with_style(style, |printer| {
+                            let x = text[..start].width();
+                            printer.print((x, 0), &text[start..end]);
+                        });
+                    }
                 });
 
+                for range in &self.matches {
+                    if range.end <= row.start || range.start >= row.end {
+                        continue;
+                    }
+                    let start = range.start.max(row.start) - row.start;
+                    let end = range.end.min(row.end) - row.start;
+                    let style = if range.start == self.cursor {
+                        ColorStyle::highlight()
+                    } else {
+                        self.search_style
+                    };
+                    printer.with_color(style, |printer| {
+                        let x = text[..start].width();
+                        printer.print((x, 0), &text[start..end]);
+                    });
+                }
+
+                if let Some(range) = self.get_selection() {
+                    let start = range.start.max(row.start);
+                    let end = range.end.min(row.end);
+                    if start < end {
+                        let start = start - row.start;
+                        let end = end - row.start;
+                        let x = text[..start].width();
+                        let selected = &text[start..end];
+                        printer.with_color(self.selection_style, |printer| {
+                            printer.print((x, 0), selected);
+                        });
+                    }
+                }
+
                 if printer.focused && i == self.selected_row() {
                     let cursor_offset = self.cursor - row.start;
+                    let offset = text[..cursor_offset].width();
+
+                    // Text being composed replaces the cursor.
+                    if self.composer.is_composing() {
+                        printer.with_effect(Effect::Underline, |printer| {
+                            printer.print((offset, 0), self.composer.text());
+                        });
+                        return;
+                    }
+
                     let c = if cursor_offset == text.len() {
                         "_"
                     } else {
@@ -500,7 +1519,6 @@ impl View for TextArea {
                             .next()
                             .expect("Found no char!")
                     };
-                    let offset = text[..cursor_offset].width();
                     printer.print((offset, 0), c);
                 }
             });
@@ -508,10 +1526,75 @@ impl View for TextArea {
     }
 
     fn on_event(&mut self, event: Event) -> EventResult {
+        // Moving with Shift extends the selection.
+        if let Event::Shift(key) = event {
+            if key.is_movement() {
+                let anchor = self.selection_anchor.unwrap_or(self.cursor);
+                let result = self.on_event(Event::Key(key));
+                self.selection_anchor = Some(anchor);
+                return result;
+            }
+        }
+
+        if event == self.copy_event {
+            let text = self.get_selected_text().map(str::to_string);
+            return clipboard::copy(self.on_copy.clone(), text);
+        }
+
         let mut fix_scroll = true;
+        let mut keep_selection = false;
         match event {
-            Event::Char(ch) => self.insert(ch),
-            Event::Key(Key::Enter) => self.insert('\n'),
+            Event::CtrlChar('a') => {
+                self.select_all();
+                keep_selection = true;
+            }
+            Event::Char(_)
+            | Event::Key(Key::Enter)
+            | Event::Key(Key::Backspace)
+            | Event::Key(Key::Del)
+            | Event::CtrlChar('z')
+            | Event::CtrlChar('y')
+            | Event::CtrlChar('v')
+            | Event::Paste(_)
+                if self.read_only =>
+            {
+                return EventResult::Ignored;
+            }
+            Event::Char(ch) => {
+                let text = self.composer.feed(ch);
+                if text.is_empty() {
+                    // Still composing: nothing was typed yet.
+                    keep_selection = true;
+                } else {
+                    self.delete_selection();
+                }
+                self.insert_str(&text);
+            }
+            Event::Key(Key::Backspace) | Event::Key(Key::Esc)
+                if self.composer.is_composing() =>
+            {
+                self.composer.clear();
+            }
+            Event::Key(Key::Backspace) | Event::Key(Key::Del)
+                if self.get_selection().is_some() =>
+            {
+                self.delete_selection();
+            }
+            Event::Key(Key::Enter) => {
+                self.delete_selection();
+                self.insert('\n');
+            }
+            Event::CtrlChar('v') => match clipboard::get_text() {
+                Some(text) => self.paste(&text),
+                None => return EventResult::Ignored,
+            },
+            Event::Paste(ref text) => self.paste(text),
+            Event::CtrlChar('z') => {
+                self.undo();
+            }
+            Event::CtrlChar('y') => {
+                self.redo();
+            }
             Event::Key(Key::Backspace) if self.cursor > 0 => self.backspace(),
             Event::Key(Key::Del) if self.cursor < self.content.len() => {
                 self.delete()
@@ -574,35 +1657,64 @@ impl View for TextArea {
                 event: MouseEvent::Hold(MouseButton::Left),
                 position,
                 offset,
-            } => {
+            } if self.scrollbase.is_dragging() => {
                 fix_scroll = false;
                 let position = position.saturating_sub(offset);
                 self.scrollbase.drag(position);
             }
             Event::Mouse {
-                event: MouseEvent::Press(_),
+                event: MouseEvent::Hold(MouseButton::Left),
+                position,
+                offset,
+            } if self.selection_anchor.is_some() && !self.rows.is_empty() => {
+                // Dragging from a press extends the selection.
+                keep_selection = true;
+                self.cursor = self.offset_at(position.saturating_sub(offset));
+            }
+            Event::Mouse {
+                event: MouseEvent::Release(MouseButton::Left),
+                ..
+            } if self.scrollbase.is_dragging() => {
+                fix_scroll = false;
+                self.scrollbase.release_grab();
+            }
+            Event::Mouse {
+                event: MouseEvent::Release(MouseButton::Left),
+                ..
+            } if self.selection_anchor.is_some() => {
+                // A click without a drag selects nothing.
+                keep_selection = self.get_selection().is_some();
+            }
+            Event::Mouse {
+                event: MouseEvent::Press(button),
                 position,
                 offset,
             } if !self.rows.is_empty()
                 && position.fits_in_rect(offset, self.last_size) =>
             {
                 if let Some(position) = position.checked_sub(offset) {
-                    let y = position.y + self.scrollbase.start_line;
-                    let y = min(y, self.rows.len() - 1);
-                    let x = position.x;
-                    let row = &self.rows[y];
-                    let content = &self.content[row.start..row.end];
+                    self.cursor = self.offset_at(position);
+                }
 
-                    self.cursor = row.start + simple_prefix(content, x).length;
+                // A drag may start here.
+                if button == MouseButton::Left {
+                    self.selection_anchor = Some(self.cursor);
+                    keep_selection = true;
                 }
             }
             _ => return EventResult::Ignored,
         }
 
+        // Edits and moves end the selection, but scrolling does not.
+        if !keep_selection && fix_scroll {
+            self.clear_selection();
+        }
+
         debug!("Rows: {:?}", self.rows);
         if fix_scroll {
             let focus = self.selected_row();
             self.scrollbase.scroll_to(focus);
+            self.fix_h_offset();
         }
 
         EventResult::Consumed(None)
@@ -615,6 +1727,7 @@ impl View for TextArea {
     fn layout(&mut self, size: Vec2) {
         self.last_size = size;
         self.compute_rows(size);
+        self.fix_h_offset();
     }
 
     fn important_area(&self, _: Vec2) -> Rect {
@@ -631,9 +1744,207 @@ impl View for TextArea {
                 .width()
         };
 
+        let x = self.selected_col().saturating_sub(self.h_offset);
         Rect::from_size(
-            (self.selected_col(), self.selected_row()),
+            (self.gutter + x, self.selected_row()),
             (char_width, 1),
         )
     }
+
+    fn save_state(&self) -> Option<ViewState> {
+        Some(ViewState::Content(self.content.clone()))
+    }
+
+    fn restore_state(&mut self, state: &ViewState) {
+        if let ViewState::Content(ref content) = *state {
+            self.set_content(content.clone());
+        }
+    }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_wrap_scrolls() {
+        let mut area = TextArea::new().no_wrap().content("0123456789\nab");
+        area.layout(Vec2::new(5, 3));
+        assert_eq!(area.rows.len(), 2);
+        area.set_cursor(8);
+        assert_eq!(area.h_offset, 4);
+        area.set_cursor(1);
+        assert_eq!(area.h_offset, 1);
+        area.set_content_wrap(true);
+        area.layout(Vec2::new(5, 3));
+        assert_eq!(area.h_offset, 0);
+        assert!(area.rows.len() > 2);
+    }
+
+    #[test]
+    fn line_numbers() {
+        let content = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj";
+        let mut area = TextArea::new()
+            .line_numbers(LineNumbers::Absolute)
+            .content(content);
+        area.layout(Vec2::new(20, 20));
+        assert_eq!(area.gutter, 3);
+        assert_eq!(area.required_size(Vec2::new(20, 20)).x, 5);
+
+        area.set_cursor(content.len());
+        area.on_event(Event::Key(Key::Backspace));
+        area.on_event(Event::Key(Key::Backspace));
+        assert_eq!(area.gutter, 2);
+
+        area.set_line_numbers(None);
+        assert_eq!(area.gutter, 0);
+    }
+
+    #[test]
+    fn click_with_gutter_and_offset() {
+        let mut area = TextArea::new()
+            .no_wrap()
+            .line_numbers(LineNumbers::Absolute)
+            .content("0123456789\nab");
+        area.layout(Vec2::new(7, 3));
+        area.set_cursor(8);
+        assert_eq!((area.gutter, area.h_offset), (2, 4));
+
+        // The gutter takes 2 cells, then the text starts at the offset.
+        assert_eq!(area.offset_at(Vec2::new(3, 0)), 5);
+        assert_eq!(area.offset_at(Vec2::new(0, 0)), 4);
+    }
+
+    #[test]
+    fn spell_checker() {
+        let mut area = TextArea::new()
+            .content("a cat, a dgo")
+            .spell_checker(|word| word != "dgo");
+        area.layout(Vec2::new(20, 5));
+        let dgo = 9..12;
+        assert_eq!(area.misspellings(), &[dgo][..]);
+
+        // Edits check the words again.
+        area.set_cursor(12);
+        area.on_event(Event::Key(Key::Backspace));
+        area.on_event(Event::Key(Key::Backspace));
+        area.on_event(Event::Char('o'));
+        area.on_event(Event::Char('g'));
+        assert_eq!(area.get_content(), "a cat, a dog");
+        assert!(area.misspellings().is_empty());
+
+        // Only the edited line is checked again; the others are shifted.
+        use std::cell::Cell;
+        let checked = Rc::new(Cell::new(0));
+        let counter = Rc::clone(&checked);
+        area.set_content("dgo\nok\ndgo");
+        area.set_spell_checker(move |word| {
+            counter.set(counter.get() + 1);
+            word != "dgo"
+        });
+        area.set_cursor(4);
+        checked.set(0);
+        area.on_event(Event::Char('x'));
+        assert_eq!(checked.get(), 1);
+        assert_eq!(area.misspellings(), &[0..3, 8..11][..]);
+    }
+
+    #[test]
+    fn undo_and_replace() {
+        let mut area = TextArea::new();
+        area.layout(Vec2::new(20, 5));
+        area.insert_str("one two");
+        area.on_event(Event::Key(Key::Backspace));
+        area.on_event(Event::Key(Key::Backspace));
+        assert_eq!(area.get_content(), "one t");
+
+        assert!(area.undo());
+        assert_eq!(area.get_content(), "one two");
+        assert!(area.undo());
+        assert_eq!(area.get_content(), "");
+        assert!(!area.undo());
+        assert!(area.redo());
+        assert_eq!(area.get_content(), "one two");
+
+        area.set_search(Some(Pattern::literal("O", false)));
+        assert_eq!(area.matches(), &[0..1, 6..7][..]);
+        assert_eq!(area.replace_all("0"), 2);
+        assert_eq!(area.get_content(), "0ne tw0");
+        assert!(area.matches().is_empty());
+        assert!(area.undo());
+        assert_eq!(area.get_content(), "one two");
+    }
+
+    #[test]
+    fn read_only_selection() {
+        let mut area = TextArea::new().content("hello world").read_only();
+        area.layout(Vec2::new(20, 5));
+
+        assert!(!area.on_event(Event::Char('x')).is_consumed());
+        area.on_event(Event::Key(Key::Home));
+        for _ in 0..5 {
+            area.on_event(Event::Shift(Key::Right));
+        }
+        assert_eq!(area.get_selected_text(), Some("hello"));
+        assert!(area.on_event(Event::AltChar('w')).has_callback());
+        assert!(!area.on_event(Event::CtrlChar('c')).is_consumed());
+
+        area.on_event(Event::Key(Key::Right));
+        assert_eq!(area.get_selection(), None);
+
+        area.set_read_only(false);
+        area.select_all();
+        area.on_event(Event::Char('x'));
+        assert_eq!(area.get_content(), "x");
+    }
+
+    #[test]
+    fn click_then_edit() {
+        let mut area = TextArea::new();
+        area.layout(Vec2::new(20, 5));
+        for c in "abcdef".chars() {
+            area.on_event(Event::Char(c));
+        }
+
+        let click = |event| Event::Mouse {
+            event,
+            position: Vec2::new(3, 0),
+            offset: Vec2::zero(),
+        };
+        area.on_event(click(MouseEvent::Press(MouseButton::Left)));
+        area.on_event(click(MouseEvent::Release(MouseButton::Left)));
+        assert_eq!(area.selection_anchor, None);
+
+        // Each backspace removes a single character.
+        area.on_event(Event::Key(Key::Backspace));
+        area.on_event(Event::Key(Key::Backspace));
+        assert_eq!(area.get_content(), "adef");
+        assert_eq!(area.selection_anchor, None);
+    }
+
+    #[test]
+    fn paste() {
+        let mut area = TextArea::new();
+        area.layout(Vec2::new(20, 5));
+        area.insert_str("ab");
+        area.on_event(Event::Key(Key::Left));
+        area.on_event(Event::Paste("one\r\ntwo\rthree".into()));
+        assert_eq!(area.get_content(), "aone\ntwo\nthreeb");
+        assert_eq!(area.cursor, 14);
+
+        // The whole paste is undone at once.
+        assert!(area.undo());
+        assert_eq!(area.get_content(), "ab");
+
+        // Pasting over a selection is a single edit too.
+        area.on_event(Event::Key(Key::Home));
+        area.on_event(Event::Shift(Key::End));
+        area.on_event(Event::Paste("cd".into()));
+        assert_eq!(area.get_content(), "cd");
+        assert!(area.undo());
+        assert_eq!(area.get_content(), "ab");
+        assert!(area.redo());
+        assert_eq!(area.get_content(), "cd");
+    }
+}
+
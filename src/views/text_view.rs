@@ -1,16 +1,24 @@
-use std::ops::Deref;
+use std::ops::{Deref, Index};
+use std::rc::Rc;
 use std::sync::Arc;
 use std::sync::{Mutex, MutexGuard};
 
 use owning_ref::{ArcRef, OwningHandle};
-use unicode_width::UnicodeWidthStr;
+use unicode_segmentation::UnicodeSegmentation;
 
 use crate::align::*;
-use crate::theme::Effect;
+use crate::clipboard;
+use crate::direction::Direction;
+use crate::event::{Event, EventResult, Key, MouseButton, MouseEvent};
+use crate::rect::Rect;
+use crate::theme::{ColorStyle, Effect, Style};
+use crate::utils::lines::simple::{simple_prefix, simple_suffix};
 use crate::utils::lines::spans::{LinesIterator, Row};
-use crate::utils::markup::StyledString;
+use crate::utils::markup::{self, StyledString};
+use crate::utils::span::Span;
+use crate::utils::width::TextWidth;
 use crate::view::{SizeCache, View};
-use crate::{Printer, Vec2, With, XY};
+use crate::{Cursive, Printer, Vec2, With, XY};
 
 /// Provides access to the content of a [`TextView`].
 ///
@@ -29,7 +37,7 @@ use crate::{Printer, Vec2, With, XY};
 /// content.set_content("new content");
 /// assert!(view.get_content().source().contains("new"));
 /// ```
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct TextContent {
     content: Arc<Mutex<TextContentInner>>,
 }
@@ -42,13 +50,10 @@ impl TextContent {
     where
         S: Into<StyledString>,
     {
-        let content = content.into();
-
         TextContent {
-            content: Arc::new(Mutex::new(TextContentInner {
-                content,
-                size_cache: None,
-            })),
+            content: Arc::new(Mutex::new(TextContentInner::new(
+                content.into(),
+            ))),
         }
     }
 }
@@ -71,7 +76,10 @@ impl Deref for TextContentRef {
     type Target = StyledString;
 
     fn deref(&self) -> &StyledString {
-        &self.handle.content
+        self.handle
+            .joined
+            .as_ref()
+            .expect("TextContentRef: content not joined")
     }
 }
 
@@ -81,15 +89,90 @@ impl TextContent {
     where
         S: Into<StyledString>,
     {
-        self.with_content(|c| *c = content.into());
+        self.with_content(|c| c.set_content(content.into()));
+    }
+
+    /// Marks parts of the current content as links.
+    ///
+    /// The range of each link is a byte range of the content as displayed,
+    /// with a newline between lines. Links are forgotten when the content is
+    /// replaced, and a line loses its links when it is replaced.
+    pub fn set_links(&mut self, links: Vec<markup::Link>) {
+        self.with_content(|c| c.set_links(links));
     }
 
     /// Append `content` to the end of a `TextView`.
+    ///
+    /// Only the last line of the existing content is modified.
     pub fn append<S>(&mut self, content: S)
     where
         S: Into<StyledString>,
     {
-        self.with_content(|c| c.append(content))
+        self.with_content(|c| c.append(content.into()))
+    }
+
+    /// Appends `line` and a newline to the end of a `TextView`.
+    pub fn append_line<S>(&mut self, line: S)
+    where
+        S: Into<StyledString>,
+    {
+        self.append_lines(Some(line));
+    }
+
+    /// Appends each line, followed by a newline, to the end of a `TextView`.
+    ///
+    /// The content is only locked once for the entire batch.
+    pub fn append_lines<I, S>(&mut self, lines: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<StyledString>,
+    {
+        self.with_content(|c| {
+            for line in lines {
+                c.append(line.into());
+                c.append(StyledString::plain("\n"));
+            }
+        })
+    }
+
+    /// Returns the number of lines in the content.
+    ///
+    /// This counts the (possibly empty) text after the last newline.
+    pub fn lines_len(&self) -> usize {
+        self.content.lock().unwrap().lines.len()
+    }
+
+    /// Replaces the line at the given position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i >= self.lines_len()`, or if `line` contains a newline.
+    pub fn set_line<S>(&mut self, i: usize, line: S)
+    where
+        S: Into<StyledString>,
+    {
+        self.with_content(|c| c.set_line(i, line.into()));
+    }
+
+    /// Inserts a line at the given position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i > self.lines_len()`, or if `line` contains a newline.
+    pub fn insert_line<S>(&mut self, i: usize, line: S)
+    where
+        S: Into<StyledString>,
+    {
+        self.with_content(|c| c.insert_line(i, line.into()));
+    }
+
+    /// Removes the line at the given position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i >= self.lines_len()`.
+    pub fn remove_line(&mut self, i: usize) {
+        self.with_content(|c| c.remove_line(i));
     }
 
     /// Returns a reference to the content.
@@ -102,11 +185,11 @@ impl TextContent {
 
     fn with_content<F, O>(&mut self, f: F) -> O
     where
-        F: FnOnce(&mut StyledString) -> O,
+        F: FnOnce(&mut TextContentInner) -> O,
     {
         let mut lock = self.content.lock().unwrap();
 
-        let out = f(&mut lock.content);
+        let out = f(&mut lock);
 
         lock.size_cache = None;
 
@@ -114,20 +197,372 @@ impl TextContent {
     }
 }
 
+/// A single line of content, with its rows.
+#[derive(Debug)]
+struct Line {
+    text: StyledString,
+
+    // For each span of `text`, the link it belongs to, if any.
+    links: Vec<Option<usize>>,
+
+    // Rows for this line, and the width they were computed for.
+    rows: Vec<Row>,
+    rows_width: Option<usize>,
+}
+
+impl Line {
+    fn new(text: StyledString) -> Self {
+        Line {
+            text,
+            links: Vec::new(),
+            rows: Vec::new(),
+            rows_width: None,
+        }
+    }
+
+    // Returns the link the given span belongs to, if any.
+    fn link(&self, span_id: usize) -> Option<usize> {
+        self.links.get(span_id).cloned().and_then(|link| link)
+    }
+
+    // Splits the spans at the bounds of the links, and marks them.
+    //
+    // `start` is the offset of this line in the whole content.
+    fn set_links(&mut self, start: usize, links: &[markup::Link]) {
+        let mut text = StyledString::new();
+        let mut span_links = Vec::new();
+
+        let mut offset = start;
+        for span in self.text.spans() {
+            let end = offset + span.content.len();
+
+            // Where the span may be cut.
+            let mut bounds: Vec<usize> = links
+                .iter()
+                .flat_map(|link| vec![link.range.start, link.range.end])
+                .filter(|&b| offset < b && b < end)
+                .filter(|&b| span.content.is_char_boundary(b - offset))
+                .collect();
+            bounds.sort();
+            bounds.dedup();
+            bounds.push(end);
+
+            let mut from = offset;
+            for to in bounds {
+                text.append_styled(
+                    &span.content[from - offset..to - offset],
+                    *span.attr,
+                );
+                span_links.push(links.iter().position(|link| {
+                    link.range.start <= from && from < link.range.end
+                }));
+                from = to;
+            }
+            offset = end;
+        }
+
+        self.text = text;
+        self.links = span_links;
+        self.rows_width = None;
+    }
+
+    fn compute_rows(&mut self, width: usize) {
+        if self.rows_width == Some(width) {
+            return;
+        }
+
+        self.rows = LinesIterator::new(&self.text, width).collect();
+        if self.rows.is_empty() {
+            // Empty lines still take a row.
+            self.rows.push(Row {
+                segments: Vec::new(),
+                width: 0,
+            });
+        }
+        self.rows_width = Some(width);
+    }
+}
+
+// Number of lines in a new chunk. Chunks are split when they get twice
+// larger.
+const CHUNK_LEN: usize = 64;
+
+/// Lines of the content, stored in chunks.
+///
+/// Each chunk caches the number of rows of its lines, and their widest
+/// row. Editing a line only moves the lines of its chunk, and only this
+/// chunk has its rows counted again. Finding a row skips whole chunks, and
+/// finding a line is a binary search on the index of their first lines.
+#[derive(Debug)]
+struct Lines {
+    chunks: Vec<Chunk>,
+
+    // Index of the first line of each chunk.
+    starts: Vec<usize>,
+    len: usize,
+}
+
+#[derive(Debug, Default)]
+struct Chunk {
+    lines: Vec<Line>,
+
+    // Number of rows and widest row, for the width given to `compute_rows`.
+    rows: Option<ChunkRows>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ChunkRows {
+    width: usize,
+    count: usize,
+    widest: usize,
+}
+
+impl Chunk {
+    // Number of rows of the lines, even if they were not all computed.
+    fn row_count(&self) -> usize {
+        match self.rows {
+            Some(rows) => rows.count,
+            None => self.lines.iter().map(|line| line.rows.len()).sum(),
+        }
+    }
+}
+
+impl Lines {
+    fn new(lines: Vec<StyledString>) -> Self {
+        let len = lines.len();
+        let mut lines = lines.into_iter().map(Line::new).peekable();
+        let mut chunks = Vec::new();
+        let mut starts = Vec::new();
+        while lines.peek().is_some() {
+            starts.push(chunks.len() * CHUNK_LEN);
+            chunks.push(Chunk {
+                lines: lines.by_ref().take(CHUNK_LEN).collect(),
+                rows: None,
+            });
+        }
+        Lines {
+            chunks,
+            starts,
+            len,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // Returns the chunk of the line `i`, and the position of the line in it.
+    //
+    // For `i == len`, returns the end of the last chunk.
+    fn find(&self, i: usize) -> (usize, usize) {
+        // Chunks are never empty, so their first lines are all different.
+        let c = match self.starts.binary_search(&i) {
+            Ok(c) => c,
+            Err(0) => return (0, 0),
+            Err(c) => c - 1,
+        };
+        (c, i - self.starts[c])
+    }
+
+    // Moves the first lines of the chunks after `c` by one line.
+    fn shift_starts(&mut self, c: usize, inserted: bool) {
+        for start in &mut self.starts[c + 1..] {
+            if inserted {
+                *start += 1;
+            } else {
+                *start -= 1;
+            }
+        }
+    }
+
+    fn get_mut(&mut self, i: usize) -> &mut Line {
+        assert!(i < self.len, "line {} out of {}", i, self.len);
+        let (c, i) = self.find(i);
+        let chunk = &mut self.chunks[c];
+        chunk.rows = None;
+        &mut chunk.lines[i]
+    }
+
+    fn last_mut(&mut self) -> Option<&mut Line> {
+        match self.len {
+            0 => None,
+            len => Some(self.get_mut(len - 1)),
+        }
+    }
+
+    fn last(&self) -> Option<&Line> {
+        self.chunks.last().and_then(|chunk| chunk.lines.last())
+    }
+
+    fn insert(&mut self, i: usize, line: Line) {
+        assert!(i <= self.len, "line {} out of {}", i, self.len);
+        if self.chunks.is_empty() {
+            self.chunks.push(Chunk::default());
+            self.starts.push(0);
+        }
+
+        let (c, i) = self.find(i);
+        let chunk = &mut self.chunks[c];
+        chunk.lines.insert(i, line);
+        chunk.rows = None;
+        self.len += 1;
+
+        if chunk.lines.len() >= 2 * CHUNK_LEN {
+            let lines = chunk.lines.split_off(CHUNK_LEN);
+            self.chunks.insert(c + 1, Chunk { lines, rows: None });
+            self.starts.insert(c + 1, self.starts[c] + CHUNK_LEN);
+            self.shift_starts(c + 1, true);
+        } else {
+            self.shift_starts(c, true);
+        }
+    }
+
+    fn push(&mut self, line: Line) {
+        self.insert(self.len, line);
+    }
+
+    fn remove(&mut self, i: usize) -> Line {
+        assert!(i < self.len, "line {} out of {}", i, self.len);
+        let (c, i) = self.find(i);
+        let chunk = &mut self.chunks[c];
+        let line = chunk.lines.remove(i);
+        chunk.rows = None;
+        let emptied = chunk.lines.is_empty();
+        self.shift_starts(c, false);
+        if emptied {
+            self.chunks.remove(c);
+            self.starts.remove(c);
+        }
+        self.len -= 1;
+        line
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &Line> {
+        self.chunks.iter().flat_map(|chunk| chunk.lines.iter())
+    }
+
+    fn iter_mut(&mut self) -> impl Iterator<Item = &mut Line> {
+        self.chunks.iter_mut().flat_map(|chunk| {
+            chunk.rows = None;
+            chunk.lines.iter_mut()
+        })
+    }
+
+    // Makes sure every line has rows for the given width.
+    fn compute_rows(&mut self, width: usize) {
+        for chunk in &mut self.chunks {
+            match chunk.rows {
+                Some(rows) if rows.width == width => continue,
+                _ => (),
+            }
+
+            let mut rows = ChunkRows {
+                width,
+                count: 0,
+                widest: 0,
+            };
+            for line in &mut chunk.lines {
+                line.compute_rows(width);
+                rows.count += line.rows.len();
+                rows.widest = line
+                    .rows
+                    .iter()
+                    .map(|row| row.width)
+                    .fold(rows.widest, usize::max);
+            }
+            chunk.rows = Some(rows);
+        }
+    }
+
+    // Returns the number of rows, and the widest one.
+    fn row_stats(&self) -> (usize, usize) {
+        self.chunks.iter().fold((0, 0), |(count, widest), chunk| {
+            match chunk.rows {
+                Some(rows) => (count + rows.count, widest.max(rows.widest)),
+                None => (count + chunk.row_count(), widest),
+            }
+        })
+    }
+
+    // Iterates on the rows starting with the row `start`, with the index of
+    // their line.
+    fn rows_from(
+        &self, mut start: usize,
+    ) -> impl Iterator<Item = (usize, &Line, &Row)> {
+        // Whole chunks before `start` are skipped.
+        let mut first_line = 0;
+        let mut skipped = 0;
+        for chunk in &self.chunks {
+            let rows = chunk.row_count();
+            if start < rows {
+                break;
+            }
+            start -= rows;
+            skipped += 1;
+            first_line += chunk.lines.len();
+        }
+
+        self.chunks[skipped..]
+            .iter()
+            .flat_map(|chunk| chunk.lines.iter())
+            .enumerate()
+            .flat_map(move |(i, line)| {
+                line.rows.iter().map(move |row| (first_line + i, line, row))
+            })
+            .skip(start)
+    }
+}
+
+impl Index<usize> for Lines {
+    type Output = Line;
+
+    fn index(&self, i: usize) -> &Line {
+        assert!(i < self.len, "line {} out of {}", i, self.len);
+        let (c, i) = self.find(i);
+        &self.chunks[c].lines[i]
+    }
+}
+
 /// Internel representation of the content for a `TextView`.
 ///
-/// This is mostly just a `StyledString`.
+/// The content is stored as chunks of lines, each with its cached rows.
+/// Modifying a line only requires computing rows for this line again.
 ///
 /// Can be shared (through a `Arc<Mutex>`).
+#[derive(Debug)]
 struct TextContentInner {
-    // content: String,
-    content: StyledString,
+    // Lines of the content, without the newlines.
+    //
+    // There is always at least one (possibly empty) line.
+    lines: Lines,
+
+    // Links in the content, referred to by the lines.
+    links: Vec<markup::Link>,
+
+    // The complete content, re-built when requested with `get_content`
+    // after an edit other than an append.
+    joined: Option<StyledString>,
 
     // We keep the cache here so it can be busted when we change the content.
     size_cache: Option<XY<SizeCache>>,
 }
 
 impl TextContentInner {
+    fn new(content: StyledString) -> Self {
+        let mut inner = TextContentInner {
+            lines: Lines::new(Vec::new()),
+            links: Vec::new(),
+            joined: None,
+            size_cache: None,
+        };
+        inner.set_content(content);
+        inner
+    }
+
     /// From a shareable content (Arc + Mutex), return a
     fn get_content(content: &Arc<Mutex<TextContentInner>>) -> TextContentRef {
         let arc_ref: ArcRef<Mutex<TextContentInner>> =
@@ -135,11 +570,134 @@ impl TextContentInner {
 
         TextContentRef {
             handle: OwningHandle::new_with_fn(arc_ref, |mutex| unsafe {
-                (*mutex).lock().unwrap()
+                let mut lock = (*mutex).lock().unwrap();
+                lock.join();
+                lock
             }),
         }
     }
 
+    fn set_content(&mut self, content: StyledString) {
+        self.lines = Lines::new(split_lines(&content));
+        self.links.clear();
+        self.joined = Some(content);
+    }
+
+    fn set_links(&mut self, links: Vec<markup::Link>) {
+        let mut start = 0;
+        for line in self.lines.iter_mut() {
+            let len: usize =
+                line.text.spans().map(|span| span.content.len()).sum();
+            line.set_links(start, &links);
+            start += len + 1;
+        }
+        self.links = links;
+        self.joined = None;
+    }
+
+    fn append(&mut self, content: StyledString) {
+        let mut new_lines = split_lines(&content).into_iter();
+
+        // The first part continues the current last line.
+        if let Some(first) = new_lines.next() {
+            let last = self.lines.last_mut().unwrap();
+            last.text.append(first);
+            last.rows_width = None;
+        }
+        for line in new_lines {
+            self.lines.push(Line::new(line));
+        }
+
+        // The complete content can be kept.
+        if let Some(ref mut joined) = self.joined {
+            joined.append(content);
+        }
+    }
+
+    fn set_line(&mut self, i: usize, line: StyledString) {
+        assert!(
+            !line.source().contains('\n'),
+            "TextContent::set_line: the line contains a newline"
+        );
+        *self.lines.get_mut(i) = Line::new(line);
+        self.joined = None;
+    }
+
+    fn insert_line(&mut self, i: usize, line: StyledString) {
+        assert!(
+            !line.source().contains('\n'),
+            "TextContent::insert_line: the line contains a newline"
+        );
+        self.lines.insert(i, Line::new(line));
+        self.joined = None;
+    }
+
+    fn remove_line(&mut self, i: usize) {
+        self.lines.remove(i);
+        if self.lines.is_empty() {
+            self.lines.push(Line::new(StyledString::new()));
+        }
+        self.joined = None;
+    }
+
+    // Builds the complete content, if needed.
+    fn join(&mut self) {
+        if self.joined.is_some() {
+            return;
+        }
+
+        let mut joined = StyledString::new();
+        for (i, line) in self.lines.iter().enumerate() {
+            if i > 0 {
+                joined.append_plain("\n");
+            }
+            joined.append(line.text.clone());
+        }
+        self.joined = Some(joined);
+    }
+
+    // Makes sure every line has rows for the given width.
+    fn compute_rows(&mut self, width: usize) {
+        self.lines.compute_rows(width);
+    }
+
+    // Number of lines to show.
+    fn shown_lines(&self) -> usize {
+        // An empty last line (after a final newline) is not shown.
+        match self.lines.last() {
+            Some(line) if line.text.is_empty() => self.lines.len() - 1,
+            _ => self.lines.len(),
+        }
+    }
+
+    // Returns the number of rows to show, and the widest one.
+    fn row_stats(&self) -> (usize, usize) {
+        let (count, widest) = self.lines.row_stats();
+        if self.shown_lines() < self.lines.len() {
+            // The hidden empty line has a single, empty row.
+            (count - 1, widest)
+        } else {
+            (count, widest)
+        }
+    }
+
+    // Returns the `y`-th row to show, with the line it comes from.
+    fn row(&self, y: usize) -> Option<(&StyledString, &Row)> {
+        self.rows_from(y).next().map(|(_, (_, line, row))| (&line.text, row))
+    }
+
+    // Returns the rows to show, numbered, starting with the row `start`.
+    fn rows_from(
+        &self, start: usize,
+    ) -> impl Iterator<Item = (usize, (usize, &Line, &Row))> {
+        let n = self.shown_lines();
+        self.lines
+            .rows_from(start)
+            .take_while(move |&(i, _, _)| i < n)
+            .enumerate()
+            .map(move |(y, row)| (start + y, row))
+    }
+
     fn is_cache_valid(&self, size: Vec2) -> bool {
         match self.size_cache {
             None => false,
@@ -148,6 +706,26 @@ impl TextContentInner {
     }
 }
 
+// Splits the given text on newlines.
+//
+// Always returns at least one (possibly empty) line.
+fn split_lines(content: &StyledString) -> Vec<StyledString> {
+    let mut lines = vec![StyledString::new()];
+
+    for span in content.spans() {
+        for (i, part) in span.content.split('\n').enumerate() {
+            if i > 0 {
+                lines.push(StyledString::new());
+            }
+            if !part.is_empty() {
+                lines.last_mut().unwrap().append_styled(part, *span.attr);
+            }
+        }
+    }
+
+    lines
+}
+
 /// A simple view showing a fixed text.
 ///
 /// # Examples
@@ -162,7 +740,6 @@ impl TextContentInner {
 pub struct TextView {
     // content: String,
     content: Arc<Mutex<TextContentInner>>,
-    rows: Vec<Row>,
 
     align: Align,
     effect: Effect,
@@ -170,9 +747,64 @@ pub struct TextView {
     // True if we can wrap long lines.
     wrap: bool,
 
+    // How to show lines too long for the view, when not wrapping.
+    truncation: Truncation,
+
     // ScrollBase make many scrolling-related things easier
     last_size: Vec2,
     width: Option<usize>,
+
+    // Number of rows for the current content.
+    height: usize,
+
+    // When `true`, text can be selected.
+    selectable: bool,
+
+    // Position (column and row) of the selection cursor.
+    cursor: Vec2,
+
+    // Other end of the selection, the cursor being one end.
+    selection_anchor: Option<Vec2>,
+
+    selection_style: ColorStyle,
+
+    on_copy: Option<Rc<clipboard::OnCopy>>,
+
+    // Copies the selection.
+    copy_event: Event,
+
+    // Links in the content, in reading order.
+    links: Vec<Link>,
+
+    // Index of the selected link, if any.
+    focused_link: Option<usize>,
+
+    link_style: Style,
+
+    on_link: Option<Rc<OnLink>>,
+}
+
+type OnLink = dyn Fn(&mut Cursive, &str);
+
+/// A link shown in a `TextView`.
+///
+/// A link can span several rows when it is wrapped.
+#[derive(Debug)]
+struct Link {
+    target: String,
+
+    // Start (column and row) and width of each part of the link.
+    parts: Vec<(Vec2, usize)>,
+}
+
+impl Link {
+    fn contains(&self, position: Vec2) -> bool {
+        self.parts.iter().any(|&(start, width)| {
+            position.y == start.y
+                && start.x <= position.x
+                && position.x < start.x + width
+        })
+    }
 }
 
 impl TextView {
@@ -204,11 +836,22 @@ impl TextView {
         TextView {
             content: content.content,
             effect: Effect::Simple,
-            rows: Vec::new(),
             wrap: true,
+            truncation: Truncation::Clip,
             align: Align::top_left(),
             last_size: Vec2::zero(),
             width: None,
+            height: 0,
+            selectable: false,
+            cursor: Vec2::zero(),
+            selection_anchor: None,
+            selection_style: ColorStyle::highlight(),
+            on_copy: None,
+            copy_event: clipboard::DEFAULT_COPY_EVENT,
+            links: Vec::new(),
+            focused_link: None,
+            link_style: Style::from(Effect::Underline),
+            on_link: None,
         }
     }
 
@@ -241,61 +884,318 @@ impl TextView {
     /// If `true` (the default), text will wrap long lines when needed.
     pub fn set_content_wrap(&mut self, wrap: bool) {
         self.wrap = wrap;
+        self.invalidate();
     }
 
-    /// Sets the horizontal alignment for this view.
-    pub fn h_align(mut self, h: HAlign) -> Self {
-        self.align.h = h;
-
-        self
+    /// Sets how lines too long for the view are shown.
+    ///
+    /// This only applies when content wrap is disabled.
+    ///
+    /// Defaults to `Truncation::Clip`.
+    pub fn set_truncation(&mut self, truncation: Truncation) {
+        self.truncation = truncation;
     }
 
-    /// Sets the vertical alignment for this view.
-    pub fn v_align(mut self, v: VAlign) -> Self {
-        self.align.v = v;
-
-        self
+    /// Sets how lines too long for the view are shown.
+    ///
+    /// Chainable variant.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::views::{TextView, Truncation};
+    /// let label = TextView::new("/a/very/long/path/to/some/file.txt")
+    ///     .no_wrap()
+    ///     .truncation(Truncation::Leading);
+    /// ```
+    pub fn truncation(self, truncation: Truncation) -> Self {
+        self.with(|s| s.set_truncation(truncation))
     }
 
-    /// Sets the alignment for this view.
-    pub fn align(mut self, a: Align) -> Self {
-        self.align = a;
-
-        self
+    /// Returns how lines too long for the view are shown.
+    pub fn get_truncation(&self) -> Truncation {
+        self.truncation
     }
 
-    /// Center the text horizontally and vertically inside the view.
-    pub fn center(mut self) -> Self {
-        self.align = Align::center();
-        self
+    /// Allows selecting text in this view.
+    ///
+    /// A selectable view can take the focus. Text is then selected with
+    /// `Shift` and the arrow keys, or by dragging the mouse, and copied with
+    /// `Alt-W` (see `set_copy_event` and `set_on_copy`).
+    ///
+    /// Defaults to `false`.
+    pub fn set_selectable(&mut self, selectable: bool) {
+        self.selectable = selectable;
+        if !selectable {
+            self.clear_selection();
+        }
     }
 
-    /// Replace the text in this view.
+    /// Allows selecting text in this view.
     ///
     /// Chainable variant.
-    pub fn content<S>(self, content: S) -> Self
-    where
-        S: Into<StyledString>,
-    {
-        self.with(|s| s.set_content(content))
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::views::TextView;
+    /// let text = TextView::new("Select me!")
+    ///     .selectable()
+    ///     .on_copy(|s, text| s.set_user_data(text.to_string()));
+    /// ```
+    pub fn selectable(self) -> Self {
+        self.with(|s| s.set_selectable(true))
     }
 
-    /// Replace the text in this view.
-    pub fn set_content<S>(&mut self, content: S)
-    where
-        S: Into<StyledString>,
-    {
-        self.content.lock().unwrap().content = content.into();
-        self.invalidate();
+    /// Returns `true` if text can be selected in this view.
+    pub fn is_selectable(&self) -> bool {
+        self.selectable
     }
 
-    /// Append `content` to the end of a `TextView`.
+    /// Returns the selected text, if any.
+    ///
+    /// Lines wrapped by the view are joined without a separator.
+    pub fn get_selection(&self) -> Option<String> {
+        let (start, end) = self.selection_bounds()?;
+        let content = self.content.lock().unwrap();
+
+        let mut result = String::new();
+        let mut last_line = None;
+        let rows = content.rows_from(start.y);
+        for (y, (i, line, row)) in rows.take(end.y + 1 - start.y) {
+            let text = &line.text;
+            if last_line.is_some() && last_line != Some(i) {
+                result.push('\n');
+            }
+            last_line = Some(i);
+
+            let from = if y == start.y { start.x } else { 0 };
+            let to = if y == end.y { end.x } else { row.width };
+            for span in cells(&row.resolve(text), from, to) {
+                result.push_str(span.content);
+            }
+        }
+
+        Some(result)
+    }
+
+    /// Unselects the text.
+    pub fn clear_selection(&mut self) {
+        self.selection_anchor = None;
+    }
+
+    /// Sets the style used for the selected text.
+    ///
+    /// Defaults to `ColorStyle::highlight()`.
+    pub fn set_selection_style(&mut self, style: ColorStyle) {
+        self.selection_style = style;
+    }
+
+    /// Sets the style used for the selected text.
+    ///
+    /// Chainable variant.
+    pub fn selection_style(self, style: ColorStyle) -> Self {
+        self.with(|s| s.set_selection_style(style))
+    }
+
+    /// Sets the event copying the selected text.
+    ///
+    /// Defaults to [`clipboard::DEFAULT_COPY_EVENT`], `Alt-W`. `Ctrl-C` only
+    /// reaches the views if an event filter turns `Event::Exit` back into it
+    /// (see `Cursive::add_event_filter`).
+    ///
+    /// [`clipboard::DEFAULT_COPY_EVENT`]:
+    /// ../clipboard/constant.DEFAULT_COPY_EVENT.html
+    pub fn set_copy_event<E: Into<Event>>(&mut self, event: E) {
+        self.copy_event = event.into();
+    }
+
+    /// Sets the event copying the selected text.
+    ///
+    /// Chainable variant.
+    pub fn copy_event<E: Into<Event>>(self, event: E) -> Self {
+        self.with(|s| s.set_copy_event(event))
+    }
+
+    /// Returns the event copying the selected text.
+    pub fn get_copy_event(&self) -> &Event {
+        &self.copy_event
+    }
+
+    /// Sets a callback to be called with the selected text on copy.
+    ///
+    /// By default, the selection is copied to the clipboard (see
+    /// `Cursive::clipboard`).
+    pub fn set_on_copy<F>(&mut self, callback: F)
+    where
+        F: Fn(&mut Cursive, &str) + 'static,
+    {
+        self.on_copy = Some(Rc::new(callback));
+    }
+
+    /// Sets a callback to be called with the selected text on copy.
+    ///
+    /// Chainable variant.
+    pub fn on_copy<F>(self, callback: F) -> Self
+    where
+        F: Fn(&mut Cursive, &str) + 'static,
+    {
+        self.with(|s| s.set_on_copy(callback))
+    }
+
+    /// Sets a callback to be called when a link is activated.
+    ///
+    /// Links are set with [`set_links`]. They are activated by clicking
+    /// them, or with `Enter` once selected with `Tab` and `Shift-Tab`. The
+    /// callback is given the target of the link.
+    ///
+    /// [`set_links`]: #method.set_links
+    pub fn set_on_link<F>(&mut self, callback: F)
+    where
+        F: Fn(&mut Cursive, &str) + 'static,
+    {
+        self.on_link = Some(Rc::new(callback));
+    }
+
+    /// Sets a callback to be called when a link is activated.
+    ///
+    /// Chainable variant.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::utils::markup::Link;
+    /// # use cursive::views::{Dialog, TextView};
+    /// let links = vec![Link::new(9..16, "LICENSE")];
+    ///
+    /// let view = TextView::new("Read the license")
+    ///     .links(links)
+    ///     .on_link(|s, target| {
+    ///         s.add_layer(Dialog::info(format!("Opening {}", target)));
+    ///     });
+    /// ```
+    pub fn on_link<F>(self, callback: F) -> Self
+    where
+        F: Fn(&mut Cursive, &str) + 'static,
+    {
+        self.with(|s| s.set_on_link(callback))
+    }
+
+    /// Sets the style added to links.
+    ///
+    /// Defaults to underlined text. The selected link is also highlighted.
+    pub fn set_link_style(&mut self, style: Style) {
+        self.link_style = style;
+    }
+
+    /// Sets the style added to links.
+    ///
+    /// Chainable variant.
+    pub fn link_style(self, style: Style) -> Self {
+        self.with(|s| s.set_link_style(style))
+    }
+
+    /// Returns the target of the selected link, if any.
+    pub fn get_focused_link(&self) -> Option<&str> {
+        let link = self.links.get(self.focused_link?)?;
+        Some(&link.target)
+    }
+
+    /// Sets the horizontal alignment for this view.
+    pub fn h_align(mut self, h: HAlign) -> Self {
+        self.align.h = h;
+
+        self
+    }
+
+    /// Sets the vertical alignment for this view.
+    pub fn v_align(mut self, v: VAlign) -> Self {
+        self.align.v = v;
+
+        self
+    }
+
+    /// Sets the alignment for this view.
+    pub fn align(mut self, a: Align) -> Self {
+        self.align = a;
+
+        self
+    }
+
+    /// Center the text horizontally and vertically inside the view.
+    pub fn center(mut self) -> Self {
+        self.align = Align::center();
+        self
+    }
+
+    /// Replace the text in this view.
+    ///
+    /// Chainable variant.
+    pub fn content<S>(self, content: S) -> Self
+    where
+        S: Into<StyledString>,
+    {
+        self.with(|s| s.set_content(content))
+    }
+
+    /// Replace the text in this view.
+    pub fn set_content<S>(&mut self, content: S)
+    where
+        S: Into<StyledString>,
+    {
+        self.get_shared_content().set_content(content);
+    }
+
+    /// Append `content` to the end of a `TextView`.
     pub fn append<S>(&mut self, content: S)
     where
         S: Into<StyledString>,
     {
-        self.content.lock().unwrap().content.append(content.into());
-        self.invalidate();
+        self.get_shared_content().append(content);
+    }
+
+    /// Appends `line` and a newline to the end of a `TextView`.
+    ///
+    /// See `TextContent::append_line`.
+    pub fn append_line<S>(&mut self, line: S)
+    where
+        S: Into<StyledString>,
+    {
+        self.get_shared_content().append_line(line);
+    }
+
+    /// Appends each line, followed by a newline, to the end of a `TextView`.
+    ///
+    /// See `TextContent::append_lines`.
+    pub fn append_lines<I, S>(&mut self, lines: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<StyledString>,
+    {
+        self.get_shared_content().append_lines(lines);
+    }
+
+    /// Marks parts of the current content as links.
+    ///
+    /// See `TextContent::set_links`.
+    pub fn set_links(&mut self, links: Vec<markup::Link>) {
+        self.get_shared_content().set_links(links);
+    }
+
+    /// Marks parts of the current content as links.
+    ///
+    /// Chainable variant.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use cursive::utils::markup::Link;
+    /// # use cursive::views::TextView;
+    /// let view = TextView::new("Read the license")
+    ///     .links(vec![Link::new(9..16, "LICENSE")]);
+    /// ```
+    pub fn links(self, links: Vec<markup::Link>) -> Self {
+        self.with(|s| s.set_links(links))
     }
 
     /// Returns the current text in this view.
@@ -332,10 +1232,12 @@ impl TextView {
             return;
         }
 
-        self.rows = LinesIterator::new(&content.content, size.x).collect();
+        content.compute_rows(size.x);
 
         // Desired width
-        self.width = self.rows.iter().map(|row| row.width).max();
+        let (height, width) = content.row_stats();
+        self.width = if height == 0 { None } else { Some(width) };
+        self.height = height;
     }
 
     // Invalidates the cache, so next call will recompute everything.
@@ -343,11 +1245,494 @@ impl TextView {
         let mut content = self.content.lock().unwrap();
         content.size_cache = None;
     }
+
+    // Returns the ordered ends of the selection, if it is not empty.
+    fn selection_bounds(&self) -> Option<(Vec2, Vec2)> {
+        let anchor = self.selection_anchor?;
+        let cursor = self.cursor;
+
+        if (anchor.y, anchor.x) < (cursor.y, cursor.x) {
+            Some((anchor, cursor))
+        } else if anchor != cursor {
+            Some((cursor, anchor))
+        } else {
+            None
+        }
+    }
+
+    // Returns the width of the given row.
+    fn row_width(&self, y: usize) -> usize {
+        let content = self.content.lock().unwrap();
+        let width = content.row(y).map_or(0, |(_, row)| row.width);
+        width
+    }
+
+    // Returns the start, end and kind (in a word or not) of each grapheme
+    // on the given row.
+    fn graphemes(&self, y: usize) -> Vec<(usize, usize, bool)> {
+        let content = self.content.lock().unwrap();
+        let (text, row) = match content.row(y) {
+            Some(row) => row,
+            None => return Vec::new(),
+        };
+
+        let mut cells = Vec::new();
+        let mut start = 0;
+        for span in row.resolve(text) {
+            for grapheme in span.content.graphemes(true) {
+                let end = start + grapheme.width();
+                let is_word = grapheme.chars().any(char::is_alphanumeric);
+                cells.push((start, end, is_word));
+                start = end;
+            }
+        }
+        cells
+    }
+
+    // Returns the start of the grapheme covering the cell `x`, so the
+    // cursor never lands in the middle of a wide character.
+    fn snap_cursor(&self, y: usize, x: usize) -> usize {
+        self.graphemes(y)
+            .into_iter()
+            .find(|&(_, end, _)| end > x)
+            .map_or_else(|| self.row_width(y), |(start, _, _)| start)
+    }
+
+    // Returns the cells of the word around `x` on the given row.
+    //
+    // Outside of a word, only selects the grapheme at `x`.
+    fn word_bounds(&self, y: usize, x: usize) -> (usize, usize) {
+        let cells = self.graphemes(y);
+
+        let i = match cells.iter().position(|&(_, end, _)| end > x) {
+            Some(i) => i,
+            None => return (x, x),
+        };
+        let (mut start, mut end, is_word) = cells[i];
+        if is_word {
+            for &(s, _, w) in cells[..i].iter().rev() {
+                if !w {
+                    break;
+                }
+                start = s;
+            }
+            for &(_, e, w) in &cells[i + 1..] {
+                if !w {
+                    break;
+                }
+                end = e;
+            }
+        }
+        (start, end)
+    }
+
+    // Returns the offset of the content for the given row, from alignment.
+    fn row_offset(&self, y: usize) -> Vec2 {
+        let x = self.align.h.get_offset(self.row_width(y), self.last_size.x);
+        let y = self.align.v.get_offset(self.height, self.last_size.y);
+        Vec2::new(x, y)
+    }
+
+    // Moves the selection cursor, staying within the text.
+    fn move_cursor(&mut self, key: Key) {
+        let last_row = self.height.saturating_sub(1);
+        let Vec2 { mut x, mut y } = self.cursor;
+
+        let page = self.last_size.y.max(1);
+
+        match key {
+            Key::Left if x > 0 => x = self.snap_cursor(y, x - 1),
+            Key::Left if y > 0 => {
+                y -= 1;
+                x = self.row_width(y);
+            }
+            Key::Right if x < self.row_width(y) => {
+                x = self
+                    .graphemes(y)
+                    .into_iter()
+                    .find(|&(_, end, _)| end > x)
+                    .map_or(x + 1, |(_, end, _)| end);
+            }
+            Key::Right if y < last_row => {
+                y += 1;
+                x = 0;
+            }
+            Key::Up => y = y.saturating_sub(1),
+            Key::Down => y = (y + 1).min(last_row),
+            Key::PageUp => y = y.saturating_sub(page),
+            Key::PageDown => y = (y + page).min(last_row),
+            Key::Home => x = 0,
+            Key::End => x = self.row_width(y),
+            _ => (),
+        }
+
+        self.cursor = Vec2::new(self.snap_cursor(y, x), y);
+    }
+
+    // Moves the cursor to a position relative to the view.
+    fn move_cursor_to(&mut self, position: Vec2) {
+        let y = position
+            .y
+            .saturating_sub(self.row_offset(0).y)
+            .min(self.height.saturating_sub(1));
+        let x = position.x.saturating_sub(self.row_offset(y).x);
+        self.cursor = Vec2::new(self.snap_cursor(y, x), y);
+    }
+
+    // Finds the links in the current rows.
+    fn compute_links(&mut self) {
+        let content = self.content.lock().unwrap();
+
+        let mut links: Vec<Link> = Vec::new();
+        // Link of the last segment, if any.
+        let mut previous: Option<usize> = None;
+        for (y, (_, line, row)) in content.rows_from(0) {
+            let mut x = 0;
+            for segment in &row.segments {
+                if segment.start == segment.end {
+                    continue;
+                }
+                let width = segment.width;
+                let link = line.link(segment.span_id);
+                match (link, links.last_mut()) {
+                    (None, _) => (),
+                    (Some(_), Some(last)) if previous == link => {
+                        match last.parts.last_mut() {
+                            Some(&mut (start, ref mut w))
+                                if start.y == y && start.x + *w == x =>
+                            {
+                                *w += width
+                            }
+                            _ => last.parts.push(((x, y).into(), width)),
+                        }
+                    }
+                    (Some(link), _) => links.push(Link {
+                        target: content.links[link].target.clone(),
+                        parts: vec![((x, y).into(), width)],
+                    }),
+                }
+                previous = link;
+                x += width;
+            }
+        }
+        drop(content);
+
+        self.focused_link = self.focused_link.filter(|&i| i < links.len());
+        self.links = links;
+    }
+
+    // Returns the index of the link at a position relative to the view.
+    fn link_at(&self, position: Vec2) -> Option<usize> {
+        let y = position.y.checked_sub(self.row_offset(0).y)?;
+        let x = position.x.checked_sub(self.row_offset(y).x)?;
+        self.links
+            .iter()
+            .position(|link| link.contains(Vec2::new(x, y)))
+    }
+
+    fn activate_link(&self) -> EventResult {
+        let target = match self.get_focused_link() {
+            Some(target) => target.to_string(),
+            None => return EventResult::Ignored,
+        };
+        match self.on_link.clone() {
+            Some(cb) => EventResult::with_cb(move |s| cb(s, &target)),
+            None => EventResult::Consumed(None),
+        }
+    }
+
+    fn on_link_event(&mut self, event: &Event) -> EventResult {
+        let last = self.links.len().checked_sub(1);
+        match *event {
+            Event::Key(Key::Tab) if last.is_some() => {
+                self.focused_link = match self.focused_link {
+                    None => Some(0),
+                    Some(i) if Some(i) < last => Some(i + 1),
+                    Some(_) => return EventResult::Ignored,
+                };
+            }
+            Event::Shift(Key::Tab) if last.is_some() => {
+                self.focused_link = match self.focused_link {
+                    None => last,
+                    Some(i) if i > 0 => Some(i - 1),
+                    Some(_) => return EventResult::Ignored,
+                };
+            }
+            Event::Key(Key::Enter) => return self.activate_link(),
+            Event::Mouse {
+                event: MouseEvent::Press(MouseButton::Left),
+                position,
+                offset,
+            } => match self.link_at(position.saturating_sub(offset)) {
+                Some(i) if position.fits_in_rect(offset, self.last_size) => {
+                    self.focused_link = Some(i);
+                }
+                _ => return EventResult::Ignored,
+            },
+            Event::Mouse {
+                event: MouseEvent::Release(MouseButton::Left),
+                position,
+                offset,
+            } if position.fits_in_rect(offset, self.last_size) => {
+                let link = self.link_at(position.saturating_sub(offset));
+                if link.is_none() || link != self.focused_link {
+                    return EventResult::Ignored;
+                }
+                return self.activate_link();
+            }
+            _ => return EventResult::Ignored,
+        }
+
+        EventResult::Consumed(None)
+    }
+
+    fn on_selection_event(&mut self, event: Event) -> EventResult {
+        if event == self.copy_event {
+            let text = self.get_selection();
+            return clipboard::copy(self.on_copy.clone(), text);
+        }
+
+        match event {
+            Event::Shift(key) if key.is_movement() => {
+                let anchor = self.selection_anchor.unwrap_or(self.cursor);
+                self.move_cursor(key);
+                self.selection_anchor = Some(anchor);
+            }
+            Event::Key(key) if key.is_movement() => {
+                self.move_cursor(key);
+                self.clear_selection();
+            }
+            Event::Key(Key::Esc) if self.selection_anchor.is_some() => {
+                self.clear_selection();
+            }
+            Event::Mouse {
+                event: MouseEvent::Press(MouseButton::Left),
+                position,
+                offset,
+            } if position.fits_in_rect(offset, self.last_size) => {
+                self.move_cursor_to(position.saturating_sub(offset));
+                self.selection_anchor = Some(self.cursor);
+            }
+            Event::Mouse {
+                event: MouseEvent::Hold(MouseButton::Left),
+                position,
+                offset,
+            } if self.selection_anchor.is_some() => {
+                self.move_cursor_to(position.saturating_sub(offset));
+            }
+            Event::Mouse {
+                event: MouseEvent::DoubleClick(MouseButton::Left),
+                position,
+                offset,
+            } if position.fits_in_rect(offset, self.last_size) => {
+                // The press already moved the cursor.
+                let y = self.cursor.y;
+                let (start, end) = self.word_bounds(y, self.cursor.x);
+                self.selection_anchor = Some(Vec2::new(start, y));
+                self.cursor = Vec2::new(end, y);
+            }
+            Event::Mouse {
+                event: MouseEvent::TripleClick(MouseButton::Left),
+                position,
+                offset,
+            } if position.fits_in_rect(offset, self.last_size) => {
+                let y = self.cursor.y;
+                self.selection_anchor = Some(Vec2::new(0, y));
+                self.cursor = Vec2::new(self.row_width(y), y);
+            }
+            _ => return EventResult::Ignored,
+        }
+
+        EventResult::Consumed(None)
+    }
+}
+
+/// How a `TextView` shows lines too long for it.
+///
+/// The ellipsis takes one cell, and counts in the available width.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Truncation {
+    /// Cuts the end of the line, without any marker.
+    Clip,
+    /// Keeps the end of the line, and shows an ellipsis at the start.
+    Leading,
+    /// Keeps the start of the line, and shows an ellipsis at the end.
+    Trailing,
+    /// Keeps both ends of the line, and shows an ellipsis in the middle.
+    Middle,
+}
+
+// Shortens a row to fit in `width` cells.
+fn truncate<'a>(
+    spans: Vec<Span<'a, Style>>, width: usize, truncation: Truncation,
+) -> Vec<Span<'a, Style>> {
+    if width == 0 {
+        return Vec::new();
+    }
+
+    let (first, last) = match (spans.first(), spans.last()) {
+        (Some(first), Some(last)) => (first.attr, last.attr),
+        _ => return spans,
+    };
+
+    // Style the ellipsis like the text next to it.
+    let ellipsis = |attr| Span {
+        content: "…",
+        attr,
+        width: 1,
+    };
+    let available = width - 1;
+
+    match truncation {
+        Truncation::Clip => spans,
+        Truncation::Trailing => {
+            let mut result = keep_first(&spans, available);
+            let attr = result.last().map_or(first, |span| span.attr);
+            result.push(ellipsis(attr));
+            result
+        }
+        Truncation::Leading => {
+            let kept = keep_last(&spans, available);
+            let attr = kept.first().map_or(last, |span| span.attr);
+            let mut result = vec![ellipsis(attr)];
+            result.extend(kept);
+            result
+        }
+        Truncation::Middle => {
+            let mut result = keep_first(&spans, available - available / 2);
+            let attr = result.last().map_or(first, |span| span.attr);
+            result.push(ellipsis(attr));
+            result.extend(keep_last(&spans, available / 2));
+            result
+        }
+    }
+}
+
+// Keeps the cells between `from` and `to`.
+fn cells<'a>(
+    spans: &[Span<'a, Style>], from: usize, to: usize,
+) -> Vec<Span<'a, Style>> {
+    let head = keep_first(spans, to);
+    let width: usize = head.iter().map(|span| span.width).sum();
+    keep_last(&head, width.saturating_sub(from))
+}
+
+// Keeps the start of the spans, up to `width` cells.
+//
+// The last span may be cut.
+fn keep_first<'a>(
+    spans: &[Span<'a, Style>], mut width: usize,
+) -> Vec<Span<'a, Style>> {
+    let mut result = Vec::new();
+    for span in spans {
+        if span.width > width {
+            let prefix = simple_prefix(span.content, width);
+            if prefix.length > 0 {
+                result.push(Span {
+                    content: &span.content[..prefix.length],
+                    attr: span.attr,
+                    width: prefix.width,
+                });
+            }
+            break;
+        }
+        width -= span.width;
+        result.push(span.clone());
+    }
+    result
+}
+
+// Keeps the end of the spans, up to `width` cells.
+fn keep_last<'a>(
+    spans: &[Span<'a, Style>], mut width: usize,
+) -> Vec<Span<'a, Style>> {
+    let mut result = Vec::new();
+    for span in spans.iter().rev() {
+        if span.width > width {
+            let suffix = simple_suffix(span.content, width);
+            if suffix.length > 0 {
+                let start = span.content.len() - suffix.length;
+                result.push(Span {
+                    content: &span.content[start..],
+                    attr: span.attr,
+                    width: suffix.width,
+                });
+            }
+            break;
+        }
+        width -= span.width;
+        result.push(span.clone());
+    }
+    result.reverse();
+    result
+}
+
+impl TextView {
+    // Highlights the part of the selected link on the given row.
+    fn draw_focused_link(
+        &self, printer: &Printer<'_, '_>, line: &StyledString, row: &Row,
+        (x, y): (usize, usize),
+    ) {
+        let link = match self.focused_link.and_then(|i| self.links.get(i)) {
+            Some(link) => link,
+            None => return,
+        };
+        let spans = row.resolve(line);
+
+        for &(start, width) in &link.parts {
+            if start.y != y {
+                continue;
+            }
+            let mut x = x + start.x;
+            printer.with_color(ColorStyle::highlight(), |printer| {
+                for span in cells(&spans, start.x, start.x + width) {
+                    printer.print((x, y), span.content);
+                    x += span.width;
+                }
+            });
+        }
+    }
+
+    // Draws the selection and the cursor over the given row.
+    fn draw_selection(
+        &self, printer: &Printer<'_, '_>, line: &StyledString, row: &Row,
+        (x, y): (usize, usize),
+    ) {
+        let spans = row.resolve(line);
+
+        if let Some((start, end)) = self.selection_bounds() {
+            if start.y <= y && y <= end.y {
+                let from = if y == start.y { start.x } else { 0 };
+                let to = if y == end.y { end.x } else { row.width };
+                let mut x = x + from;
+                printer.with_color(self.selection_style, |printer| {
+                    for span in cells(&spans, from, to) {
+                        printer.print((x, y), span.content);
+                        x += span.width;
+                    }
+                });
+            }
+        }
+
+        if printer.focused && self.cursor.y == y {
+            // The cursor covers a whole grapheme, even a wide one.
+            let end = self
+                .graphemes(y)
+                .into_iter()
+                .find(|&(start, _, _)| start == self.cursor.x)
+                .map_or(self.cursor.x + 1, |(_, end, _)| end);
+            let cell = cells(&spans, self.cursor.x, end);
+            let text = cell.first().map_or(" ", |span| span.content);
+            printer.with_effect(Effect::Reverse, |printer| {
+                printer.print((x + self.cursor.x, y), text);
+            });
+        }
+    }
 }
 
 impl View for TextView {
     fn draw(&self, printer: &Printer<'_, '_>) {
-        let h = self.rows.len();
+        let h = self.height;
         // If the content is smaller than the view, align it somewhere.
         let offset = self.align.v.get_offset(h, printer.size.y);
         let printer = &printer.offset((0, offset));
@@ -355,29 +1740,98 @@ impl View for TextView {
         let content = self.content.lock().unwrap();
 
         printer.with_effect(self.effect, |printer| {
-            for (y, row) in self.rows.iter().enumerate() {
-                let l = row.width;
-                let mut x = self.align.h.get_offset(l, printer.size.x);
+            // Only look at visible rows.
+            let rows = content
+                .rows_from(printer.content_offset.y)
+                .take(printer.output_size.y);
+            for (y, (_, source, row)) in rows {
+                let line = &source.text;
+                // Links get the link style on top of their own.
+                let styles: Vec<Style> = row
+                    .segments
+                    .iter()
+                    .filter(|segment| segment.start < segment.end)
+                    .map(|segment| {
+                        let style = line.spans_raw()[segment.span_id].attr;
+                        match source.link(segment.span_id) {
+                            Some(_) => style.combine(self.link_style),
+                            None => style,
+                        }
+                    })
+                    .collect();
+                let mut spans: Vec<_> = row
+                    .resolve(line)
+                    .into_iter()
+                    .zip(&styles)
+                    .map(|(span, attr)| Span { attr, ..span })
+                    .collect();
+                let mut l = row.width;
+                if !self.wrap && l > printer.size.x {
+                    spans = truncate(spans, printer.size.x, self.truncation);
+                    l = spans.iter().map(|span| span.width).sum();
+                }
+                let x_offset = self.align.h.get_offset(l, printer.size.x);
+                let mut x = x_offset;
 
-                for span in row.resolve(&content.content) {
+                for span in spans {
                     printer.with_style(*span.attr, |printer| {
                         printer.print((x, y), span.content);
                         x += span.content.width();
                     });
                 }
+
+                if printer.focused {
+                    self.draw_focused_link(printer, line, row, (x_offset, y));
+                }
+                if self.selectable {
+                    self.draw_selection(printer, line, row, (x_offset, y));
+                }
             }
         });
     }
 
+    fn take_focus(&mut self, _: Direction) -> bool {
+        if self.focused_link.is_none() && !self.links.is_empty() {
+            self.focused_link = Some(0);
+        }
+        self.selectable || !self.links.is_empty()
+    }
+
+    fn on_event(&mut self, event: Event) -> EventResult {
+        match self.on_link_event(&event) {
+            EventResult::Ignored if self.selectable => {
+                self.on_selection_event(event)
+            }
+            result => result,
+        }
+    }
+
+    fn important_area(&self, size: Vec2) -> Rect {
+        let link = self.focused_link.and_then(|i| self.links.get(i));
+        if let Some(&(start, width)) = link.and_then(|l| l.parts.first()) {
+            let offset = self.row_offset(start.y);
+            Rect::from_size(offset + start, (width, 1))
+        } else if self.selectable {
+            let offset = self.row_offset(self.cursor.y);
+            Rect::from_size(offset + self.cursor, (1, 1))
+        } else {
+            Rect::from_size((0, 0), size)
+        }
+    }
+
     fn needs_relayout(&self) -> bool {
         let content = self.content.lock().unwrap();
         content.size_cache.is_none()
     }
 
+    fn mark_dirty(&mut self) {
+        self.content.lock().unwrap().size_cache = None;
+    }
+
     fn required_size(&mut self, size: Vec2) -> Vec2 {
         self.compute_rows(size);
 
-        Vec2::new(self.width.unwrap_or(0), self.rows.len())
+        Vec2::new(self.width.unwrap_or(0), self.height)
     }
 
     fn layout(&mut self, size: Vec2) {
@@ -386,10 +1840,277 @@ impl View for TextView {
         self.compute_rows(size);
 
         // The entire "virtual" size (includes all rows)
-        let my_size = Vec2::new(self.width.unwrap_or(0), self.rows.len());
+        let my_size = Vec2::new(self.width.unwrap_or(0), self.height);
 
         // Build a fresh cache.
         let mut content = self.content.lock().unwrap();
         content.size_cache = Some(SizeCache::build(my_size, size));
+        drop(content);
+
+        self.compute_links();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::markup::Link;
+    use crate::views::ScrollView;
+
+    #[test]
+    fn append_lines() {
+        let mut view = TextView::new("first\n");
+        view.layout(Vec2::new(5, 10));
+
+        view.append_lines(vec!["second line", "third"]);
+        view.append("fourth ");
+        view.append_line("and more");
+        view.layout(Vec2::new(5, 10));
+
+        // Rows for the appended text match a complete computation.
+        let text = |view: &TextView| -> Vec<String> {
+            let content = view.content.lock().unwrap();
+            content
+                .rows_from(0)
+                .map(|(_, (_, line, row))| {
+                    let spans = row.resolve(&line.text);
+                    spans.iter().map(|s| s.content).collect()
+                })
+                .collect()
+        };
+        let source = view.get_content().source().to_string();
+        let mut fresh = TextView::new(source);
+        fresh.layout(Vec2::new(5, 10));
+        assert_eq!(text(&view), text(&fresh));
+        assert_eq!(view.width, fresh.width);
+    }
+
+    #[test]
+    fn follow_bottom() {
+        let mut content = TextContent::new("");
+        let mut view =
+            ScrollView::new(TextView::new_with_content(content.clone()))
+                .follow_bottom(true);
+
+        content.append_lines((0..10).map(|i| i.to_string()));
+        view.layout(Vec2::new(5, 3));
+        assert!(view.is_at_bottom());
+        assert_eq!(view.content_viewport().top(), 7);
+
+        // Once the user scrolled up, stay there.
+        view.set_offset((0, 2));
+        content.append_line("10");
+        view.layout(Vec2::new(5, 3));
+        assert_eq!(view.content_viewport().top(), 2);
+    }
+
+    #[test]
+    fn lines() {
+        let mut content = TextContent::new("a\n\nb");
+        assert_eq!(content.lines_len(), 3);
+
+        content.append(StyledString::styled("c\nd", Effect::Bold));
+        content.set_line(0, "first");
+        content.insert_line(1, "second");
+        content.remove_line(2);
+        assert_eq!(content.get_content().source(), "first\nsecond\nbc\nd");
+
+        // Rows are computed like for the whole text.
+        for text in &["", "\n", "a\n", "a\nb\n\n", "abc def ghi\n"] {
+            let mut view = TextView::new(*text);
+            view.layout(Vec2::new(5, 10));
+
+            let joined = StyledString::plain(*text);
+            assert_eq!(view.height, LinesIterator::new(&joined, 5).count());
+        }
+    }
+
+    #[test]
+    fn many_lines() {
+        let lines: Vec<String> = (0..300).map(|i| i.to_string()).collect();
+        let mut content = TextContent::new(lines.join("\n"));
+        let mut view = TextView::new_with_content(content.clone());
+
+        // Appending keeps the complete content.
+        assert_eq!(content.get_content().source().len(), 1089);
+        content.append_line("");
+        assert!(content.content.lock().unwrap().joined.is_some());
+
+        // Lines are split in chunks, which grow and shrink.
+        for i in 0..200 {
+            content.insert_line(100, format!("new {}", i));
+        }
+        for _ in 0..150 {
+            content.remove_line(10);
+        }
+        assert_eq!(content.lines_len(), 351);
+
+        view.layout(Vec2::new(10, 20));
+        assert_eq!(view.height, 350);
+        assert_eq!(view.row_width(0), 1);
+        assert_eq!(view.row_width(10), 7);
+        assert_eq!(view.row_width(149), 5);
+        assert_eq!(view.row_width(150), 3);
+        assert_eq!(view.row_width(350), 0);
+
+        let source = content.get_content().source().to_string();
+        let expected: Vec<String> = (0..10)
+            .map(|i| i.to_string())
+            .chain((0..140).rev().map(|i| format!("new {}", i)))
+            .chain((100..300).map(|i| i.to_string()))
+            .collect();
+        assert_eq!(source, expected.join("\n") + "\n");
+    }
+
+    #[test]
+    #[should_panic(expected = "newline")]
+    fn set_line_with_newline() {
+        let mut content = TextContent::new("a\nb");
+        content.set_line(0, "c\nd");
+    }
+
+    #[test]
+    fn truncation() {
+        let text = StyledString::plain("abcdef日本");
+        let spans: Vec<_> = text.spans().collect();
+        let truncated = |width, truncation| -> String {
+            truncate(spans.clone(), width, truncation)
+                .iter()
+                .map(|span| span.content)
+                .collect()
+        };
+
+        assert_eq!(truncated(6, Truncation::Clip), "abcdef日本");
+        assert_eq!(truncated(6, Truncation::Trailing), "abcde…");
+        assert_eq!(truncated(6, Truncation::Leading), "…f日本");
+        assert_eq!(truncated(6, Truncation::Middle), "abc…本");
+        assert_eq!(truncated(1, Truncation::Middle), "…");
+    }
+
+    #[test]
+    fn chunk_boundaries() {
+        let line = |text: String| Line::new(StyledString::plain(text));
+        let text = |lines: &Lines, i| lines[i].text.source().to_string();
+        let mut lines = Lines::new(
+            (0..130).map(|i| StyledString::plain(i.to_string())).collect(),
+        );
+        assert_eq!(lines.find(0), (0, 0));
+        assert_eq!(lines.find(63), (0, 63));
+        assert_eq!(lines.find(64), (1, 0));
+        assert_eq!(lines.find(128), (2, 0));
+        assert_eq!(lines.find(130), (2, 2));
+
+        // Filling a chunk splits it.
+        for i in 0..64 {
+            lines.insert(64, line(format!("x{}", i)));
+        }
+        assert_eq!(lines.find(127), (1, 63));
+        assert_eq!(lines.find(128), (2, 0));
+        assert_eq!(text(&lines, 64), "x63");
+        assert_eq!(text(&lines, 128), "64");
+
+        lines.insert(0, line("first".to_string()));
+        assert_eq!(lines.find(64), (0, 64));
+        assert_eq!(lines.find(65), (1, 0));
+        assert_eq!(text(&lines, 129), "64");
+        assert_eq!(text(&lines, 193), "128");
+
+        // Emptying a chunk removes it.
+        lines.remove(193);
+        lines.remove(193);
+        assert_eq!(lines.find(lines.len()), (2, 64));
+        assert_eq!(text(&lines, 192), "127");
+
+        lines.remove(0);
+        assert_eq!(lines.find(63), (0, 63));
+        assert_eq!(lines.find(64), (1, 0));
+        assert_eq!(text(&lines, 0), "0");
+    }
+
+    #[test]
+    fn selection() {
+        let mut view = TextView::new("first line\nsecond").selectable();
+        view.layout(Vec2::new(20, 5));
+
+        view.on_event(Event::Key(Key::Right));
+        for _ in 0..12 {
+            view.on_event(Event::Shift(Key::Right));
+        }
+        assert_eq!(view.get_selection().unwrap(), "irst line\nse");
+
+        view.on_event(Event::Key(Key::Down));
+        assert_eq!(view.get_selection(), None);
+
+        // Double clicks select a word, triple clicks a row.
+        let click = |event| Event::Mouse {
+            event,
+            position: Vec2::new(7, 0),
+            offset: Vec2::zero(),
+        };
+        view.on_event(click(MouseEvent::Press(MouseButton::Left)));
+        view.on_event(click(MouseEvent::DoubleClick(MouseButton::Left)));
+        assert_eq!(view.get_selection().unwrap(), "line");
+        view.on_event(click(MouseEvent::TripleClick(MouseButton::Left)));
+        assert_eq!(view.get_selection().unwrap(), "first line");
+
+        // Ctrl-C never reaches the views unless asked for.
+        assert!(view.on_event(Event::AltChar('w')).has_callback());
+        assert!(!view.on_event(Event::CtrlChar('c')).is_consumed());
+        view.set_copy_event(Event::CtrlChar('c'));
+        assert!(view.on_event(Event::CtrlChar('c')).has_callback());
+    }
+
+    #[test]
+    fn wide_cursor() {
+        let mut view = TextView::new("日本語\na").selectable();
+        view.layout(Vec2::new(20, 5));
+
+        // The cursor moves over whole graphemes, even wide ones.
+        view.on_event(Event::Key(Key::Right));
+        assert_eq!(view.cursor, Vec2::new(2, 0));
+        view.on_event(Event::Shift(Key::Right));
+        assert_eq!(view.get_selection().unwrap(), "本");
+
+        // It never lands in the middle of a wide character.
+        view.on_event(Event::Key(Key::Down));
+        view.on_event(Event::Key(Key::End));
+        view.on_event(Event::Key(Key::Up));
+        assert_eq!(view.cursor, Vec2::new(0, 0));
+        view.on_event(Event::Key(Key::PageDown));
+        assert_eq!(view.cursor, Vec2::new(0, 1));
+    }
+
+    #[test]
+    fn links() {
+        let mut text = StyledString::plain("See first ");
+        text.append_styled("link", Effect::Bold);
+        text.append_plain(" and b");
+        let links = vec![Link::new(4..14, "a"), Link::new(19..20, "b")];
+        let mut view = TextView::new(text).links(links).on_link(|_, _| ());
+        view.layout(Vec2::new(8, 5));
+
+        // The first link is wrapped over two rows.
+        assert_eq!(view.links.len(), 2);
+        assert_eq!(view.links[0].parts.len(), 2);
+        assert_eq!(view.get_content().source(), "See first link and b");
+
+        assert!(view.take_focus(Direction::none()));
+        assert_eq!(view.get_focused_link(), Some("a"));
+        view.on_event(Event::Key(Key::Tab));
+        assert_eq!(view.get_focused_link(), Some("b"));
+        assert!(!view.on_event(Event::Key(Key::Tab)).is_consumed());
+
+        let click = |event| Event::Mouse {
+            event,
+            position: Vec2::new(1, 1),
+            offset: Vec2::zero(),
+        };
+        view.on_event(click(MouseEvent::Press(MouseButton::Left)));
+        assert_eq!(view.get_focused_link(), Some("a"));
+        match view.on_event(click(MouseEvent::Release(MouseButton::Left))) {
+            EventResult::Consumed(Some(_)) => (),
+            _ => panic!("the link was not activated"),
+        }
     }
 }
+
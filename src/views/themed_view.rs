@@ -0,0 +1,59 @@
+use crate::theme::{ColorStyle, Theme};
+use crate::view::{View, ViewWrapper};
+use crate::Printer;
+
+/// Wrapper view that draws its subtree with a different theme.
+///
+/// The wrapped view, and all its children, will use the palette and borders
+/// from this theme instead of the application one. The background is filled
+/// with the theme's `View` color first.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cursive::theme::{Color, PaletteColor, Theme};
+/// # use cursive::views::{TextView, ThemedView};
+/// let mut theme = Theme::default();
+/// theme.palette[PaletteColor::View] = Color::TerminalDefault;
+/// theme.palette[PaletteColor::Primary] = Color::Rgb(200, 200, 200);
+///
+/// let logs = ThemedView::new(theme, TextView::new("Starting..."));
+/// ```
+pub struct ThemedView<T> {
+    theme: Theme,
+    view: T,
+}
+
+impl<T> ThemedView<T> {
+    /// Wraps `view` so it is drawn with `theme`.
+    pub fn new(theme: Theme, view: T) -> Self {
+        ThemedView { theme, view }
+    }
+
+    /// Returns the theme used by this view.
+    pub fn get_theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    /// Sets the theme used by this view.
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    inner_getters!(self.view: T);
+}
+
+impl<T: View> ViewWrapper for ThemedView<T> {
+    wrap_impl!(self.view: T);
+
+    fn wrap_draw(&self, printer: &Printer<'_, '_>) {
+        printer.with_theme(&self.theme, |printer| {
+            printer.with_color(ColorStyle::primary(), |printer| {
+                for y in 0..printer.size.y {
+                    printer.print_hline((0, y), printer.size.x, " ");
+                }
+            });
+            self.view.draw(printer);
+        });
+    }
+}
@@ -0,0 +1,242 @@
+//! Run heavy work on background threads.
+//!
+//! A [`WorkerPool`] runs jobs on a fixed number of threads. While it runs,
+//! a job can report progress or partial results through its [`Reporter`]:
+//! each report is sent to the event loop, which gives it to a callback on
+//! the UI thread. Once the job is done, its result is given to another
+//! callback.
+//!
+//! Reports go through the `Cursive::cb_sink` channel, so the screen is
+//! refreshed after each of them.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use cursive::Cursive;
+//! # use cursive::traits::Identifiable;
+//! # use cursive::views::{Dialog, ProgressBar};
+//! use cursive::workers::WorkerPool;
+//!
+//! let mut siv = Cursive::dummy();
+//! siv.add_layer(ProgressBar::new().max(100).with_id("progress"));
+//!
+//! let pool = WorkerPool::new(&siv, 2);
+//! pool.spawn(
+//!     |reporter| {
+//!         for i in 0..=100 {
+//!             // Some heavy work...
+//!             reporter.report(i);
+//!         }
+//!         "Done!"
+//!     },
+//!     |s, i| {
+//!         s.call_on_id("progress", |bar: &mut ProgressBar| bar.set_value(i));
+//!     },
+//!     |s, message| {
+//!         s.pop_layer();
+//!         s.add_layer(Dialog::info(message));
+//!     },
+//! );
+//! ```
+//!
+//! [`WorkerPool`]: struct.WorkerPool.html
+//! [`Reporter`]: struct.Reporter.html
+
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crossbeam_channel::{unbounded, Receiver, Sender};
+
+use crate::{CbSink, Cursive};
+
+type Job = Box<dyn FnOnce() + Send>;
+
+type ReportCallback<T> = dyn FnMut(&mut Cursive, T) + Send;
+
+/// Runs jobs on background threads.
+///
+/// Dropping the pool does not stop running jobs: the threads exit once the
+/// jobs already spawned are done.
+pub struct WorkerPool {
+    jobs: Sender<Job>,
+    sink: CbSink,
+}
+
+impl WorkerPool {
+    /// Creates a pool of `threads` threads, reporting to `siv`.
+    ///
+    /// Like the `cb_sink`, the pool is invalidated by
+    /// `Cursive::set_cb_sink_capacity`.
+    ///
+    /// # Panics
+    ///
+    /// If `threads` is zero.
+    pub fn new(siv: &Cursive, threads: usize) -> Self {
+        assert!(threads > 0, "a worker pool needs at least one thread");
+
+        let (jobs, receiver) = unbounded();
+        for i in 0..threads {
+            let receiver: Receiver<Job> = receiver.clone();
+            thread::Builder::new()
+                .name(format!("cursive-worker-{}", i))
+                .spawn(move || {
+                    for job in receiver {
+                        // A panicking job should not take the thread down.
+                        let _ = panic::catch_unwind(AssertUnwindSafe(job));
+                    }
+                })
+                .expect("could not spawn worker thread");
+        }
+
+        WorkerPool {
+            jobs,
+            sink: siv.cb_sink().clone(),
+        }
+    }
+
+    /// Runs `work` on one of the threads.
+    ///
+    /// `on_report` is called on the UI thread with each value given to
+    /// `Reporter::report`, in order. Once `work` returns, `on_done` is
+    /// called with its result, after every report.
+    ///
+    /// If the job panics or is cancelled, `on_done` is not called.
+    pub fn spawn<T, R, W, P, D>(
+        &self, work: W, on_report: P, on_done: D,
+    ) -> JobHandle
+    where
+        T: Send + 'static,
+        R: Send + 'static,
+        W: FnOnce(&Reporter<T>) -> R + Send + 'static,
+        P: FnMut(&mut Cursive, T) + Send + 'static,
+        D: FnOnce(&mut Cursive, R) + Send + 'static,
+    {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let reporter = Reporter {
+            sink: self.sink.clone(),
+            on_report: Arc::new(Mutex::new(on_report)),
+            cancelled: Arc::clone(&cancelled),
+        };
+
+        let sink = self.sink.clone();
+        let job = Box::new(move || {
+            let result = work(&reporter);
+            let cancelled = reporter.cancelled;
+            let _ = sink.send(Box::new(move |s: &mut Cursive| {
+                if !cancelled.load(Ordering::SeqCst) {
+                    on_done(s, result);
+                }
+            }));
+        });
+        // The threads only stop once `jobs` is dropped.
+        let _ = self.jobs.send(job);
+
+        JobHandle { cancelled }
+    }
+}
+
+/// Given to a job, to report progress to the UI thread.
+pub struct Reporter<T> {
+    sink: CbSink,
+    on_report: Arc<Mutex<ReportCallback<T>>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl<T: Send + 'static> Reporter<T> {
+    /// Sends `value` to the report callback.
+    ///
+    /// Does nothing if the job was cancelled.
+    pub fn report(&self, value: T) {
+        if self.is_cancelled() {
+            return;
+        }
+
+        let on_report = Arc::clone(&self.on_report);
+        let cancelled = Arc::clone(&self.cancelled);
+        let _ = self.sink.send(Box::new(move |s: &mut Cursive| {
+            if !cancelled.load(Ordering::SeqCst) {
+                let mut on_report = on_report.lock().unwrap();
+                (*on_report)(s, value);
+            }
+        }));
+    }
+
+    /// Returns `true` if the job was cancelled.
+    ///
+    /// Long jobs should check this regularly, and return early.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// Handle to a job spawned on a `WorkerPool`.
+#[derive(Clone)]
+pub struct JobHandle {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl JobHandle {
+    /// Cancels the job.
+    ///
+    /// Reports not yet given to the UI thread are dropped, and the result
+    /// callback is not called. The job itself keeps running until it checks
+    /// `Reporter::is_cancelled`.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns `true` if the job was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::puppet;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn reports_and_result() {
+        let (backend, _) = puppet::Backend::init((10, 2));
+        let mut siv = Cursive::new(move || backend);
+        siv.set_user_data(Vec::<u32>::new());
+
+        fn push(s: &mut Cursive, value: u32) {
+            s.with_user_data(|values: &mut Vec<u32>| values.push(value));
+        }
+
+        let pool = WorkerPool::new(&siv, 2);
+        pool.spawn(
+            |reporter| {
+                (0..5).inspect(|&i| reporter.report(i)).sum::<u32>()
+            },
+            push,
+            push,
+        );
+        let cancelled = pool.spawn(
+            |reporter| {
+                while !reporter.is_cancelled() {
+                    thread::yield_now();
+                }
+            },
+            |_, ()| (),
+            |s, ()| push(s, 100),
+        );
+        cancelled.cancel();
+
+        let start = Instant::now();
+        while siv.user_data::<Vec<u32>>().unwrap().len() < 6 {
+            assert!(start.elapsed() < Duration::from_secs(5));
+            siv.step_with_timeout(Duration::from_millis(10));
+        }
+        // Leaves time for the cancelled job to report back.
+        thread::sleep(Duration::from_millis(20));
+        siv.step_with_timeout(Duration::from_millis(10));
+        let values = siv.take_user_data::<Vec<u32>>().unwrap();
+        assert_eq!(values, vec![0, 1, 2, 3, 4, 10]);
+    }
+}